@@ -0,0 +1,151 @@
+//! The app's one low-level keyboard and mouse hook, shared by activity tracking and typing
+//! practice. The hooks only count: keys, clicks, and the keys (and which of them were
+//! backspaces) pressed while the typing practice window was in the foreground. Which key was
+//! pressed is never kept.
+
+use serde::{Deserialize, Serialize};
+use std::sync::mpsc;
+use std::sync::Mutex;
+use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, WPARAM};
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::System::Threading::GetCurrentThreadId;
+use windows::Win32::UI::WindowsAndMessaging::{
+    CallNextHookEx, DispatchMessageW, GetForegroundWindow, GetMessageW, GetWindowThreadProcessId, PostThreadMessageW,
+    SetWindowsHookExW, TranslateMessage, UnhookWindowsHookEx, HHOOK, KBDLLHOOKSTRUCT, MSG, WH_KEYBOARD_LL, WH_MOUSE_LL,
+    WM_KEYDOWN, WM_LBUTTONDOWN, WM_MBUTTONDOWN, WM_QUIT, WM_RBUTTONDOWN, WM_SYSKEYDOWN,
+};
+
+/// Virtual-key code of backspace
+const VK_BACK: u32 = 0x08;
+
+/// Counts since the previous `input_counts` call
+#[derive(Default, Serialize)]
+pub struct InputCounts {
+    keystrokes: u64,
+    clicks: u64,
+    /// Keys pressed in the typing practice window, backspaces included
+    typing_keys: u64,
+    typing_backspaces: u64,
+}
+
+#[derive(Deserialize)]
+pub struct CountsParams {
+    /// Process (without `.exe`) whose keys count towards `typing_keys`
+    #[serde(default)]
+    typing_process: Option<String>,
+}
+
+struct Typing {
+    process: Option<String>,
+    /// The last foreground process seen and whether it was the practice one, so the process
+    /// isn't looked up on every key
+    foreground: Option<(u32, bool)>,
+}
+
+static COUNTS: Mutex<InputCounts> =
+    Mutex::new(InputCounts { keystrokes: 0, clicks: 0, typing_keys: 0, typing_backspaces: 0 });
+static TYPING: Mutex<Typing> = Mutex::new(Typing { process: None, foreground: None });
+/// Thread running the hooks' message loop, while they are installed
+static HOOK_THREAD: Mutex<Option<u32>> = Mutex::new(None);
+
+fn in_typing_window() -> bool {
+    let mut typing = TYPING.lock().unwrap();
+    let Some(target) = typing.process.clone() else {
+        return false;
+    };
+    let hwnd = unsafe { GetForegroundWindow() };
+    let mut pid = 0u32;
+    unsafe { GetWindowThreadProcessId(hwnd, Some(&mut pid)) };
+    if let Some((seen, matches)) = typing.foreground {
+        if seen == pid {
+            return matches;
+        }
+    }
+    let matches = crate::win::process_name(hwnd).is_some_and(|name| name.eq_ignore_ascii_case(&target));
+    typing.foreground = Some((pid, matches));
+    matches
+}
+
+unsafe extern "system" fn keyboard_proc(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    let message = wparam.0 as u32;
+    if code >= 0 && (message == WM_KEYDOWN || message == WM_SYSKEYDOWN) {
+        let backspace = (*(lparam.0 as *const KBDLLHOOKSTRUCT)).vkCode == VK_BACK;
+        let typing = in_typing_window();
+        let mut counts = COUNTS.lock().unwrap();
+        counts.keystrokes += 1;
+        if typing {
+            counts.typing_keys += 1;
+            counts.typing_backspaces += backspace as u64;
+        }
+    }
+    CallNextHookEx(HHOOK::default(), code, wparam, lparam)
+}
+
+unsafe extern "system" fn mouse_proc(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    let message = wparam.0 as u32;
+    if code >= 0 && matches!(message, WM_LBUTTONDOWN | WM_RBUTTONDOWN | WM_MBUTTONDOWN) {
+        COUNTS.lock().unwrap().clicks += 1;
+    }
+    CallNextHookEx(HHOOK::default(), code, wparam, lparam)
+}
+
+/// Install the hooks on a thread of their own (low-level hooks are called on the installing
+/// thread's message loop) unless they already are
+fn start() -> Result<(), String> {
+    let mut thread = HOOK_THREAD.lock().unwrap();
+    if thread.is_some() {
+        return Ok(());
+    }
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || unsafe {
+        let installed = GetModuleHandleW(None).and_then(|module| {
+            let keyboard = SetWindowsHookExW(WH_KEYBOARD_LL, Some(keyboard_proc), module, 0)?;
+            match SetWindowsHookExW(WH_MOUSE_LL, Some(mouse_proc), module, 0) {
+                Ok(mouse) => Ok((keyboard, mouse)),
+                Err(e) => {
+                    let _ = UnhookWindowsHookEx(keyboard);
+                    Err(e)
+                }
+            }
+        });
+        let (keyboard, mouse) = match installed {
+            Ok(hooks) => hooks,
+            Err(e) => {
+                let _ = sender.send(Err(format!("Failed to install the input hook: {}", e)));
+                return;
+            }
+        };
+        let _ = sender.send(Ok(GetCurrentThreadId()));
+        let mut message = MSG::default();
+        while GetMessageW(&mut message, HWND::default(), 0, 0).as_bool() {
+            let _ = TranslateMessage(&message);
+            DispatchMessageW(&message);
+        }
+        let _ = UnhookWindowsHookEx(keyboard);
+        let _ = UnhookWindowsHookEx(mouse);
+    });
+    let id = receiver.recv().map_err(|_| "The input hook thread exited".to_string())??;
+    *thread = Some(id);
+    *COUNTS.lock().unwrap() = InputCounts::default();
+    Ok(())
+}
+
+/// Input counted since the last call, installing the hooks on first use
+pub fn counts(params: CountsParams) -> Result<InputCounts, String> {
+    start()?;
+    let mut typing = TYPING.lock().unwrap();
+    let process = params.typing_process.map(|name| name.trim_end_matches(".exe").to_string());
+    if typing.process != process {
+        *typing = Typing { process, foreground: None };
+    }
+    drop(typing);
+    Ok(std::mem::take(&mut *COUNTS.lock().unwrap()))
+}
+
+/// Remove the hooks
+pub fn stop() {
+    if let Some(id) = HOOK_THREAD.lock().unwrap().take() {
+        let _ = unsafe { PostThreadMessageW(id, WM_QUIT, WPARAM(0), LPARAM(0)) };
+    }
+    *TYPING.lock().unwrap() = Typing { process: None, foreground: None };
+}
@@ -0,0 +1,58 @@
+//! Native helper for AI Teacher. Does the window enumeration, PrintWindow and
+//! Windows.Graphics.Capture captures and OCR that the app otherwise does through PowerShell and
+//! inline `Add-Type` C#, without compiling anything at runtime, and runs the input hook behind
+//! activity tracking and typing practice.
+//!
+//! Runs as a Tauri sidecar and speaks JSON lines over stdio: each request is
+//! `{ "id": <u64>, "method": "...", "params": {...} }` and gets exactly one
+//! `{ "id": <same>, "result": ... }` or `{ "id": <same>, "error": "..." }` line back.
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::io::{BufRead, Write};
+
+#[cfg(windows)]
+mod input;
+#[cfg(windows)]
+mod win;
+
+#[derive(Deserialize)]
+struct Request {
+    id: u64,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[cfg(windows)]
+fn dispatch(method: &str, params: Value) -> Result<Value, String> {
+    win::dispatch(method, params)
+}
+
+#[cfg(not(windows))]
+fn dispatch(_method: &str, _params: Value) -> Result<Value, String> {
+    Err("ai-teacher-helper only runs on Windows".to_string())
+}
+
+fn main() {
+    #[cfg(windows)]
+    win::init();
+
+    let stdin = std::io::stdin();
+    let mut stdout = std::io::stdout().lock();
+    for line in stdin.lock().lines().map_while(Result::ok) {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let reply = match serde_json::from_str::<Request>(&line) {
+            Ok(request) => match dispatch(&request.method, request.params) {
+                Ok(result) => json!({ "id": request.id, "result": result }),
+                Err(error) => json!({ "id": request.id, "error": error }),
+            },
+            Err(e) => json!({ "id": Value::Null, "error": format!("Malformed request: {}", e) }),
+        };
+        if writeln!(stdout, "{}", reply).and_then(|_| stdout.flush()).is_err() {
+            break;
+        }
+    }
+}
@@ -0,0 +1,560 @@
+//! Win32 and WinRT implementations of the helper methods. Results have the same shape as the
+//! output of the app's PowerShell scripts, so the app handles both the same way.
+
+use base64::{engine::general_purpose, Engine as _};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::time::{Duration, Instant};
+use windows::core::{Interface, PWSTR};
+use windows::Foundation::IAsyncOperation;
+use windows::Graphics::Capture::{Direct3D11CaptureFramePool, GraphicsCaptureItem, GraphicsCaptureSession};
+use windows::Graphics::DirectX::Direct3D11::IDirect3DDevice;
+use windows::Graphics::DirectX::DirectXPixelFormat;
+use windows::Graphics::Imaging::{BitmapAlphaMode, BitmapDecoder, BitmapEncoder, SoftwareBitmap};
+use windows::Media::Ocr::OcrEngine;
+use windows::Storage::Streams::{DataReader, DataWriter, InMemoryRandomAccessStream};
+use windows::Win32::Foundation::{CloseHandle, BOOL, COLORREF, HMODULE, HWND, LPARAM, RECT};
+use windows::Win32::Graphics::Direct3D::D3D_DRIVER_TYPE_HARDWARE;
+use windows::Win32::Graphics::Direct3D11::{D3D11CreateDevice, D3D11_CREATE_DEVICE_BGRA_SUPPORT, D3D11_SDK_VERSION};
+use windows::Win32::Graphics::Dxgi::IDXGIDevice;
+use windows::Win32::Graphics::Gdi::{
+    BitBlt, CreateCompatibleBitmap, CreateCompatibleDC, DeleteDC, DeleteObject, GetDC, GetDIBits, ReleaseDC,
+    SelectObject, BITMAPINFO, BITMAPINFOHEADER, BI_RGB, CAPTUREBLT, DIB_RGB_COLORS, HDC, SRCCOPY,
+};
+use windows::Win32::Storage::Xps::{PrintWindow, PRINT_WINDOW_FLAGS};
+use windows::Win32::System::Com::{CoCreateInstance, CLSCTX_INPROC_SERVER};
+use windows::Win32::System::Threading::{
+    OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32, PROCESS_QUERY_LIMITED_INFORMATION,
+};
+use windows::Win32::System::WinRT::Direct3D11::CreateDirect3D11DeviceFromDXGIDevice;
+use windows::Win32::System::WinRT::Graphics::Capture::IGraphicsCaptureItemInterop;
+use windows::Win32::System::WinRT::{RoInitialize, RO_INIT_MULTITHREADED};
+use windows::Win32::UI::Accessibility::{
+    CUIAutomation, IUIAutomation, TreeScope_Descendants, UIA_BoundingRectanglePropertyId, UIA_ClassNamePropertyId,
+    UIA_ControlTypePropertyId, UIA_NamePropertyId,
+};
+use windows::Win32::UI::WindowsAndMessaging::{
+    EnumWindows, GetForegroundWindow, GetLayeredWindowAttributes, GetWindowLongW, GetWindowRect, GetWindowTextLengthW,
+    GetWindowTextW, GetWindowThreadProcessId, IsIconic, IsWindow, IsWindowVisible, SetLayeredWindowAttributes, SetWindowLongW, ShowWindow,
+    GWL_EXSTYLE, LAYERED_WINDOW_ATTRIBUTES_FLAGS, LWA_ALPHA, SW_SHOWMINNOACTIVE, SW_SHOWNOACTIVATE, WS_EX_LAYERED,
+};
+
+/// Time a restored minimized window gets to paint before it is printed
+const PAINT_DELAY: Duration = Duration::from_millis(200);
+/// How long Windows.Graphics.Capture gets to deliver a frame
+const FRAME_TIMEOUT: Duration = Duration::from_secs(2);
+/// Descendants returned by `list_elements`; a browser page can have thousands
+const MAX_ELEMENTS: i32 = 2000;
+/// UI Automation control type names, indexed from `UIA_ButtonControlTypeId` (50000)
+const CONTROL_TYPES: [&str; 41] = [
+    "Button", "Calendar", "CheckBox", "ComboBox", "Edit", "Hyperlink", "Image", "ListItem", "List", "Menu",
+    "MenuBar", "MenuItem", "ProgressBar", "RadioButton", "ScrollBar", "Slider", "Spinner", "StatusBar", "Tab",
+    "TabItem", "Text", "ToolBar", "ToolTip", "Tree", "TreeItem", "Custom", "Group", "Thumb", "DataGrid",
+    "DataItem", "Document", "SplitButton", "Window", "Pane", "Header", "HeaderItem", "Table", "TitleBar",
+    "Separator", "SemanticZoom", "AppBar",
+];
+/// PrintWindow flag that includes hardware-accelerated (composited) content
+const PW_RENDERFULLCONTENT: PRINT_WINDOW_FLAGS = PRINT_WINDOW_FLAGS(2);
+
+pub fn init() {
+    // OCR goes through WinRT
+    let _ = unsafe { RoInitialize(RO_INIT_MULTITHREADED) };
+}
+
+pub fn dispatch(method: &str, params: Value) -> Result<Value, String> {
+    match method {
+        "list_windows" => to_value(Ok(list_windows())),
+        "capture_window" => {
+            let params: CaptureParams = parse(params)?;
+            to_value(capture_window(HWND(params.hwnd as _)))
+        }
+        "capture_rect" => to_value(capture_rect(parse(params)?)),
+        "graphics_capture" => {
+            let params: CaptureParams = parse(params)?;
+            to_value(graphics_capture(HWND(params.hwnd as _)))
+        }
+        "list_elements" => {
+            let params: CaptureParams = parse(params)?;
+            to_value(list_elements(HWND(params.hwnd as _)).map_err(|e| format!("UI Automation failed: {}", e)))
+        }
+        "ocr" => {
+            let params: OcrParams = parse(params)?;
+            to_value(ocr(&params.image_base64, params.word_boxes))
+        }
+        "input_counts" => to_value(crate::input::counts(parse(params)?)),
+        "input_stop" => {
+            crate::input::stop();
+            Ok(Value::Null)
+        }
+        other => Err(format!("Unknown method '{}'", other)),
+    }
+}
+
+fn parse<T: DeserializeOwned>(params: Value) -> Result<T, String> {
+    let params = if params.is_null() { Value::Object(Default::default()) } else { params };
+    serde_json::from_value(params).map_err(|e| format!("Invalid params: {}", e))
+}
+
+fn to_value<T: Serialize>(result: Result<T, String>) -> Result<Value, String> {
+    result.and_then(|value| serde_json::to_value(value).map_err(|e| e.to_string()))
+}
+
+struct TopWindow {
+    hwnd: HWND,
+    title: String,
+    process_name: String,
+}
+
+unsafe extern "system" fn collect_window(hwnd: HWND, lparam: LPARAM) -> BOOL {
+    let handles = &mut *(lparam.0 as *mut Vec<HWND>);
+    if IsWindowVisible(hwnd).as_bool() {
+        handles.push(hwnd);
+    }
+    BOOL(1)
+}
+
+/// Visible top-level windows in z-order, skipping those whose process can't be opened
+fn visible_windows() -> Vec<TopWindow> {
+    let mut handles: Vec<HWND> = Vec::new();
+    unsafe {
+        let _ = EnumWindows(Some(collect_window), LPARAM(&mut handles as *mut Vec<HWND> as isize));
+    }
+    handles
+        .into_iter()
+        .filter_map(|hwnd| Some(TopWindow { hwnd, title: window_title(hwnd), process_name: process_name(hwnd)? }))
+        .collect()
+}
+
+/// Full title, read as UTF-16 so non-ASCII titles survive
+fn window_title(hwnd: HWND) -> String {
+    let capacity = unsafe { GetWindowTextLengthW(hwnd) }.max(0) as usize + 1;
+    let mut buffer = vec![0u16; capacity];
+    let length = unsafe { GetWindowTextW(hwnd, &mut buffer) };
+    String::from_utf16_lossy(&buffer[..length.max(0) as usize])
+}
+
+/// Executable name without `.exe`, like PowerShell's `ProcessName`
+pub fn process_name(hwnd: HWND) -> Option<String> {
+    let mut pid = 0u32;
+    unsafe { GetWindowThreadProcessId(hwnd, Some(&mut pid)) };
+    let process = unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid) }.ok()?;
+    let mut buffer = [0u16; 1024];
+    let mut length = buffer.len() as u32;
+    let queried =
+        unsafe { QueryFullProcessImageNameW(process, PROCESS_NAME_WIN32, PWSTR(buffer.as_mut_ptr()), &mut length) };
+    unsafe {
+        let _ = CloseHandle(process);
+    }
+    queried.ok()?;
+    let path = String::from_utf16_lossy(&buffer[..length as usize]);
+    std::path::Path::new(&path)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+}
+
+/// Same fields as the app's window list script; the app does the matching
+#[derive(Serialize)]
+struct WindowInfo {
+    hwnd: i64,
+    title: String,
+    process_name: String,
+    is_active: bool,
+}
+
+fn list_windows() -> Vec<WindowInfo> {
+    let foreground = unsafe { GetForegroundWindow() };
+    visible_windows()
+        .into_iter()
+        .map(|window| WindowInfo {
+            hwnd: window.hwnd.0 as i64,
+            is_active: window.hwnd == foreground,
+            title: window.title,
+            process_name: window.process_name,
+        })
+        .collect()
+}
+
+/// A minimized window restored fully transparent so PrintWindow has something to print.
+/// Minimized again, with its old style and transparency, when dropped.
+struct RestoredWindow {
+    hwnd: HWND,
+    style: i32,
+    alpha: Option<(COLORREF, u8, LAYERED_WINDOW_ATTRIBUTES_FLAGS)>,
+}
+
+impl RestoredWindow {
+    /// None (and nothing done) when the window isn't minimized
+    fn restore(hwnd: HWND) -> Option<Self> {
+        unsafe {
+            if !IsIconic(hwnd).as_bool() {
+                return None;
+            }
+            let style = GetWindowLongW(hwnd, GWL_EXSTYLE);
+            let layered = style & WS_EX_LAYERED.0 as i32 != 0;
+            let mut key = COLORREF(0);
+            let mut alpha = 0u8;
+            let mut flags = LAYERED_WINDOW_ATTRIBUTES_FLAGS(0);
+            let had_alpha = layered
+                && GetLayeredWindowAttributes(hwnd, Some(&mut key), Some(&mut alpha), Some(&mut flags)).is_ok();
+            SetWindowLongW(hwnd, GWL_EXSTYLE, style | WS_EX_LAYERED.0 as i32);
+            let _ = SetLayeredWindowAttributes(hwnd, COLORREF(0), 0, LWA_ALPHA);
+            let _ = ShowWindow(hwnd, SW_SHOWNOACTIVATE);
+            std::thread::sleep(PAINT_DELAY);
+            Some(Self { hwnd, style, alpha: had_alpha.then_some((key, alpha, flags)) })
+        }
+    }
+}
+
+impl Drop for RestoredWindow {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = ShowWindow(self.hwnd, SW_SHOWMINNOACTIVE);
+            if let Some((key, alpha, flags)) = self.alpha {
+                let _ = SetLayeredWindowAttributes(self.hwnd, key, alpha, flags);
+            }
+            SetWindowLongW(self.hwnd, GWL_EXSTYLE, self.style);
+        }
+    }
+}
+
+/// Top-down RGBA pixels of a `width` x `height` bitmap that `draw` paints into
+fn read_pixels(width: i32, height: i32, draw: impl FnOnce(HDC, HDC)) -> Result<Vec<u8>, String> {
+    let mut pixels = vec![0u8; width as usize * height as usize * 4];
+    let lines = unsafe {
+        let screen = GetDC(HWND::default());
+        let memory = CreateCompatibleDC(screen);
+        let bitmap = CreateCompatibleBitmap(screen, width, height);
+        let previous = SelectObject(memory, bitmap);
+        draw(memory, screen);
+        SelectObject(memory, previous);
+
+        let mut info = BITMAPINFO {
+            bmiHeader: BITMAPINFOHEADER {
+                biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+                biWidth: width,
+                // Negative height: rows top to bottom
+                biHeight: -height,
+                biPlanes: 1,
+                biBitCount: 32,
+                biCompression: BI_RGB.0,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let lines = GetDIBits(
+            memory,
+            bitmap,
+            0,
+            height as u32,
+            Some(pixels.as_mut_ptr().cast()),
+            &mut info,
+            DIB_RGB_COLORS,
+        );
+        let _ = DeleteObject(bitmap);
+        let _ = DeleteDC(memory);
+        ReleaseDC(HWND::default(), screen);
+        lines
+    };
+    if lines == 0 {
+        return Err("Failed to read the bitmap".to_string());
+    }
+    // GDI gives BGRA with undefined alpha
+    for pixel in pixels.chunks_exact_mut(4) {
+        pixel.swap(0, 2);
+        pixel[3] = 255;
+    }
+    Ok(pixels)
+}
+
+/// A window printed with PW_RENDERFULLCONTENT, and plain PrintWindow as the fallback
+fn print_window(hwnd: HWND, width: i32, height: i32) -> Result<Vec<u8>, String> {
+    read_pixels(width, height, |memory, _| unsafe {
+        if !PrintWindow(hwnd, memory, PW_RENDERFULLCONTENT).as_bool() {
+            let _ = PrintWindow(hwnd, memory, PRINT_WINDOW_FLAGS(0));
+        }
+    })
+}
+
+fn encode_png(rgba: &[u8], width: u32, height: u32) -> Result<Vec<u8>, String> {
+    let mut png = Vec::new();
+    let mut encoder = png::Encoder::new(&mut png, width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder
+        .write_header()
+        .and_then(|mut writer| writer.write_image_data(rgba))
+        .map_err(|e| format!("Failed to encode PNG: {}", e))?;
+    Ok(png)
+}
+
+/// Same fields as the capture script's JSON
+#[derive(Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct WindowCapture {
+    image_base64: String,
+    window_title: String,
+    process_name: String,
+    left: i32,
+    top: i32,
+    width: i32,
+    height: i32,
+    was_minimized: bool,
+    hwnd: i64,
+}
+
+#[derive(Deserialize)]
+struct CaptureParams {
+    /// A window from `list_windows`
+    hwnd: i64,
+}
+
+fn capture_window(hwnd: HWND) -> Result<WindowCapture, String> {
+    if !unsafe { IsWindow(hwnd) }.as_bool() {
+        return Err("Window no longer exists".to_string());
+    }
+    let restored = RestoredWindow::restore(hwnd);
+    let mut rect = RECT::default();
+    unsafe { GetWindowRect(hwnd, &mut rect) }.map_err(|e| format!("Failed to get window rectangle: {}", e))?;
+    let (width, height) = (rect.right - rect.left, rect.bottom - rect.top);
+    if width <= 0 || height <= 0 {
+        return Err("Window has no visible rectangle".to_string());
+    }
+    let pixels = print_window(hwnd, width, height)?;
+    let was_minimized = restored.is_some();
+    drop(restored);
+
+    Ok(WindowCapture {
+        image_base64: general_purpose::STANDARD.encode(encode_png(&pixels, width as u32, height as u32)?),
+        window_title: window_title(hwnd),
+        process_name: process_name(hwnd).unwrap_or_default(),
+        left: rect.left,
+        top: rect.top,
+        width,
+        height,
+        was_minimized,
+        hwnd: hwnd.0 as i64,
+    })
+}
+
+#[derive(Deserialize)]
+struct RectParams {
+    left: i32,
+    top: i32,
+    width: i32,
+    height: i32,
+}
+
+/// A rectangle of the screen, as base64 PNG like the app's screen crop script
+fn capture_rect(rect: RectParams) -> Result<String, String> {
+    if rect.width <= 0 || rect.height <= 0 {
+        return Err("Rectangle is empty".to_string());
+    }
+    let pixels = read_pixels(rect.width, rect.height, |memory, screen| unsafe {
+        let _ = BitBlt(memory, 0, 0, rect.width, rect.height, screen, rect.left, rect.top, SRCCOPY | CAPTUREBLT);
+    })?;
+    Ok(general_purpose::STANDARD.encode(encode_png(&pixels, rect.width as u32, rect.height as u32)?))
+}
+
+fn direct3d_device() -> windows::core::Result<IDirect3DDevice> {
+    let mut device = None;
+    unsafe {
+        D3D11CreateDevice(
+            None,
+            D3D_DRIVER_TYPE_HARDWARE,
+            HMODULE::default(),
+            D3D11_CREATE_DEVICE_BGRA_SUPPORT,
+            None,
+            D3D11_SDK_VERSION,
+            Some(&mut device),
+            None,
+            None,
+        )?;
+    }
+    let dxgi: IDXGIDevice = device.ok_or_else(windows::core::Error::empty)?.cast()?;
+    unsafe { CreateDirect3D11DeviceFromDXGIDevice(&dxgi) }?.cast()
+}
+
+/// One frame of a window read straight from DWM, as PNG bytes
+fn capture_frame(hwnd: HWND) -> Result<Vec<u8>, String> {
+    let failed = |e: windows::core::Error| format!("Windows.Graphics.Capture failed: {}", e);
+    if !GraphicsCaptureSession::IsSupported().map_err(failed)? {
+        return Err("Windows.Graphics.Capture is not supported on this system".to_string());
+    }
+    let interop = windows::core::factory::<GraphicsCaptureItem, IGraphicsCaptureItemInterop>().map_err(failed)?;
+    let item: GraphicsCaptureItem = unsafe { interop.CreateForWindow(hwnd) }.map_err(failed)?;
+    let device = direct3d_device().map_err(failed)?;
+    let pool = Direct3D11CaptureFramePool::CreateFreeThreaded(
+        &device,
+        DirectXPixelFormat::B8G8R8A8UIntNormalized,
+        1,
+        item.Size().map_err(failed)?,
+    )
+    .map_err(failed)?;
+    let session = pool.CreateCaptureSession(&item).map_err(failed)?;
+    session.StartCapture().map_err(failed)?;
+
+    let deadline = Instant::now() + FRAME_TIMEOUT;
+    let frame = loop {
+        // No frame yet comes back as an error
+        if let Ok(frame) = pool.TryGetNextFrame() {
+            break frame;
+        }
+        if Instant::now() > deadline {
+            return Err("No frame arrived from Windows.Graphics.Capture".to_string());
+        }
+        std::thread::sleep(Duration::from_millis(15));
+    };
+    let encode = || -> windows::core::Result<Vec<u8>> {
+        let surface = frame.Surface()?;
+        let bitmap = SoftwareBitmap::CreateCopyWithAlphaFromSurfaceAsync(&surface, BitmapAlphaMode::Premultiplied)?.get()?;
+        let stream = InMemoryRandomAccessStream::new()?;
+        let encoder = BitmapEncoder::CreateAsync(BitmapEncoder::PngEncoderId()?, &stream)?.get()?;
+        encoder.SetSoftwareBitmap(&bitmap)?;
+        encoder.FlushAsync()?.get()?;
+        let size = stream.Size()? as u32;
+        let reader = DataReader::CreateDataReader(&stream.GetInputStreamAt(0)?)?;
+        reader.LoadAsync(size)?.get()?;
+        let mut png = vec![0u8; size as usize];
+        reader.ReadBytes(&mut png)?;
+        Ok(png)
+    };
+    let png = encode().map_err(failed);
+    let _ = frame.Close();
+    let _ = session.Close();
+    let _ = pool.Close();
+    png
+}
+
+/// Same output as the app's Windows.Graphics.Capture script: the PNG as base64. Minimized
+/// windows produce no frames and time out.
+fn graphics_capture(hwnd: HWND) -> Result<String, String> {
+    if !unsafe { IsWindow(hwnd) }.as_bool() {
+        return Err("Window no longer exists".to_string());
+    }
+    Ok(general_purpose::STANDARD.encode(capture_frame(hwnd)?))
+}
+
+/// Same fields as the app's element list script
+#[derive(Serialize)]
+struct ElementInfo {
+    name: String,
+    control_type: String,
+    class_name: String,
+    left: i32,
+    top: i32,
+    width: i32,
+    height: i32,
+}
+
+/// Visible UI Automation descendants of a window (tabs, panes, documents, child windows), in
+/// tree order. Properties come from one cached request instead of a round trip per element.
+fn list_elements(hwnd: HWND) -> windows::core::Result<Vec<ElementInfo>> {
+    unsafe {
+        let automation: IUIAutomation = CoCreateInstance(&CUIAutomation, None, CLSCTX_INPROC_SERVER)?;
+        let root = automation.ElementFromHandle(hwnd)?;
+        let cache = automation.CreateCacheRequest()?;
+        for property in [UIA_NamePropertyId, UIA_ControlTypePropertyId, UIA_ClassNamePropertyId, UIA_BoundingRectanglePropertyId] {
+            cache.AddProperty(property)?;
+        }
+        let found = root.FindAllBuildCache(TreeScope_Descendants, &automation.CreateTrueCondition()?, &cache)?;
+
+        let mut elements = Vec::new();
+        for index in 0..found.Length()?.min(MAX_ELEMENTS) {
+            let element = found.GetElement(index)?;
+            let rect = element.CachedBoundingRectangle()?;
+            if rect.right <= rect.left || rect.bottom <= rect.top {
+                continue;
+            }
+            let control_type = element.CachedControlType()?.0;
+            elements.push(ElementInfo {
+                name: element.CachedName()?.to_string(),
+                control_type: usize::try_from(control_type - 50000)
+                    .ok()
+                    .and_then(|i| CONTROL_TYPES.get(i))
+                    .unwrap_or(&"Unknown")
+                    .to_string(),
+                class_name: element.CachedClassName()?.to_string(),
+                left: rect.left,
+                top: rect.top,
+                width: rect.right - rect.left,
+                height: rect.bottom - rect.top,
+            });
+        }
+        Ok(elements)
+    }
+}
+
+#[derive(Deserialize)]
+struct OcrParams {
+    image_base64: String,
+    #[serde(default)]
+    word_boxes: bool,
+}
+
+/// Same fields as the OCR script's word boxes
+#[derive(Serialize)]
+struct WordBox {
+    text: String,
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
+    line: u32,
+}
+
+fn load_bitmap(bytes: &[u8]) -> windows::core::Result<SoftwareBitmap> {
+    let stream = InMemoryRandomAccessStream::new()?;
+    let writer = DataWriter::CreateDataWriter(&stream)?;
+    writer.WriteBytes(bytes)?;
+    writer.StoreAsync()?.cast::<IAsyncOperation<u32>>()?.get()?;
+    writer.DetachStream()?;
+    stream.Seek(0)?;
+    let decoder = BitmapDecoder::CreateAsync(&stream)?.get()?;
+    decoder.GetSoftwareBitmapAsync()?.get()
+}
+
+/// Recognized text joined with spaces, or the word boxes as JSON. Like the script, a missing
+/// OCR language gives empty text rather than an error.
+fn ocr(image_base64: &str, word_boxes: bool) -> Result<String, String> {
+    let bytes = general_purpose::STANDARD
+        .decode(image_base64)
+        .map_err(|e| format!("Failed to decode image: {}", e))?;
+    let bitmap = load_bitmap(&bytes).map_err(|e| format!("Failed to load image: {}", e))?;
+    let Ok(engine) = OcrEngine::TryCreateFromUserProfileLanguages() else {
+        return Ok(String::new());
+    };
+    let result = engine
+        .RecognizeAsync(&bitmap)
+        .and_then(|operation| operation.get())
+        .map_err(|e| format!("OCR failed: {}", e))?;
+
+    let mut words = Vec::new();
+    let mut boxes = Vec::new();
+    let lines = result.Lines().map_err(|e| format!("OCR failed: {}", e))?;
+    for (index, line) in lines.into_iter().enumerate() {
+        for word in line.Words().map_err(|e| format!("OCR failed: {}", e))? {
+            let text = word.Text().map_err(|e| format!("OCR failed: {}", e))?.to_string();
+            if text.trim().is_empty() {
+                continue;
+            }
+            if word_boxes {
+                let rect = word.BoundingRect().map_err(|e| format!("OCR failed: {}", e))?;
+                boxes.push(WordBox {
+                    text: text.clone(),
+                    x: rect.X,
+                    y: rect.Y,
+                    width: rect.Width,
+                    height: rect.Height,
+                    line: index as u32,
+                });
+            }
+            words.push(text);
+        }
+    }
+    if word_boxes {
+        serde_json::to_string(&boxes).map_err(|e| e.to_string())
+    } else {
+        Ok(words.join(" "))
+    }
+}
@@ -0,0 +1,17 @@
+# Build the native helper (helper/) and place it where Tauri looks for the sidecar:
+# src-tauri/binaries/ai-teacher-helper-<target triple>.exe
+# Release bundles sign it along with the app (bundle.windows.certificateThumbprint / signCommand).
+# Usage: .\scripts\build-helper.ps1
+
+$ErrorActionPreference = 'Stop'
+
+$root = Split-Path -Parent $PSScriptRoot
+$triple = (rustc -vV | Select-String '^host: ').ToString().Substring(6).Trim()
+
+cargo build --release --manifest-path (Join-Path $root 'helper\Cargo.toml')
+if ($LASTEXITCODE -ne 0) { exit $LASTEXITCODE }
+
+$binaries = Join-Path $root 'src-tauri\binaries'
+New-Item -ItemType Directory -Force -Path $binaries | Out-Null
+Copy-Item (Join-Path $root 'helper\target\release\ai-teacher-helper.exe') (Join-Path $binaries "ai-teacher-helper-$triple.exe") -Force
+Write-Host "Built ai-teacher-helper-$triple.exe"
@@ -0,0 +1,308 @@
+//! Accessibility checks on captures: WCAG contrast of text against its background, and
+//! previews of how the image looks with common color-vision deficiencies. Used by the UI
+//! design lessons and to review the student's own web projects.
+
+use base64::{engine::general_purpose, Engine as _};
+use image::imageops::FilterType;
+use image::{ImageFormat, Rgb, RgbImage};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Cursor;
+
+/// Images are checked in square tiles of this many pixels, about one line of UI text
+const TILE_SIZE: u32 = 32;
+/// WCAG AA minimum for normal text
+const DEFAULT_MIN_CONTRAST: f32 = 4.5;
+/// A tile needs a dominant background covering at least this share to count as text on a background
+const MIN_BACKGROUND_SHARE: f32 = 0.5;
+/// Share of foreground pixels expected for text; outside this range the tile is blank or busy
+const FOREGROUND_SHARE: (f32, f32) = (0.03, 0.5);
+/// Sum of channel differences above which a pixel is not background
+const FOREGROUND_DISTANCE: i32 = 48;
+const MAX_REGIONS: usize = 50;
+/// Simulated images are scaled down to at most this width
+const MAX_SIMULATION_WIDTH: u32 = 1280;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ColorVision {
+    /// No red cones
+    Protanopia,
+    /// No green cones, the most common deficiency
+    Deuteranopia,
+    /// No blue cones
+    Tritanopia,
+    /// No color vision at all
+    Achromatopsia,
+}
+
+const ALL_COLOR_VISIONS: [ColorVision; 4] = [
+    ColorVision::Protanopia,
+    ColorVision::Deuteranopia,
+    ColorVision::Tritanopia,
+    ColorVision::Achromatopsia,
+];
+
+impl ColorVision {
+    /// Simulation matrix in linear RGB (Machado et al. 2009, full severity)
+    fn matrix(self) -> [[f32; 3]; 3] {
+        match self {
+            ColorVision::Protanopia => [
+                [0.152286, 1.052583, -0.204868],
+                [0.114503, 0.786281, 0.099216],
+                [-0.003882, -0.048116, 1.051998],
+            ],
+            ColorVision::Deuteranopia => [
+                [0.367322, 0.860646, -0.227968],
+                [0.280085, 0.672501, 0.047413],
+                [-0.011820, 0.042940, 0.968881],
+            ],
+            ColorVision::Tritanopia => [
+                [1.255528, -0.076749, -0.178779],
+                [-0.078411, 0.930809, 0.147602],
+                [0.004733, 0.691367, 0.303900],
+            ],
+            ColorVision::Achromatopsia => [[0.2126, 0.7152, 0.0722]; 3],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ContrastRegion {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+    /// Lowest contrast ratio in the region (1 to 21)
+    pub contrast_ratio: f32,
+    /// Text color as #rrggbb
+    pub foreground: String,
+    /// Background color as #rrggbb
+    pub background: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ColorVisionSimulation {
+    pub deficiency: ColorVision,
+    pub image_base64: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AccessibilityReport {
+    pub width: u32,
+    pub height: u32,
+    pub min_contrast: f32,
+    /// Tiles that looked like text or icons on a plain background
+    pub checked_tiles: usize,
+    pub low_contrast_tiles: usize,
+    /// Low-contrast areas, worst first
+    pub low_contrast_regions: Vec<ContrastRegion>,
+    pub simulations: Vec<ColorVisionSimulation>,
+}
+
+fn srgb_to_linear_table() -> [f32; 256] {
+    let mut table = [0.0; 256];
+    for (value, entry) in table.iter_mut().enumerate() {
+        let c = value as f32 / 255.0;
+        *entry = if c <= 0.04045 { c / 12.92 } else { ((c + 0.055) / 1.055).powf(2.4) };
+    }
+    table
+}
+
+fn linear_to_srgb(c: f32) -> u8 {
+    let c = c.clamp(0.0, 1.0);
+    let s = if c <= 0.0031308 { c * 12.92 } else { 1.055 * c.powf(1.0 / 2.4) - 0.055 };
+    (s * 255.0).round() as u8
+}
+
+fn relative_luminance(linear: &[f32; 256], pixel: Rgb<u8>) -> f32 {
+    0.2126 * linear[pixel[0] as usize] + 0.7152 * linear[pixel[1] as usize] + 0.0722 * linear[pixel[2] as usize]
+}
+
+fn contrast_ratio(a: f32, b: f32) -> f32 {
+    (a.max(b) + 0.05) / (a.min(b) + 0.05)
+}
+
+fn hex(pixel: Rgb<u8>) -> String {
+    format!("#{:02x}{:02x}{:02x}", pixel[0], pixel[1], pixel[2])
+}
+
+fn distance(a: Rgb<u8>, b: Rgb<u8>) -> i32 {
+    (0..3).map(|i| (a[i] as i32 - b[i] as i32).abs()).sum()
+}
+
+fn average(pixels: &[Rgb<u8>]) -> Rgb<u8> {
+    let mut sum = [0u32; 3];
+    for pixel in pixels {
+        for (total, channel) in sum.iter_mut().zip(pixel.0) {
+            *total += channel as u32;
+        }
+    }
+    let n = pixels.len().max(1) as u32;
+    Rgb([(sum[0] / n) as u8, (sum[1] / n) as u8, (sum[2] / n) as u8])
+}
+
+/// Contrast of the foreground against the dominant background of a tile, or None when the tile
+/// isn't text-like
+fn tile_contrast(image: &RgbImage, linear: &[f32; 256], x: u32, y: u32, width: u32, height: u32) -> Option<(f32, Rgb<u8>, Rgb<u8>)> {
+    let pixels: Vec<Rgb<u8>> = (y..y + height)
+        .flat_map(|py| (x..x + width).map(move |px| (px, py)))
+        .map(|(px, py)| *image.get_pixel(px, py))
+        .collect();
+
+    // Dominant color, quantized to 4 bits per channel so anti-aliasing noise doesn't split it
+    let mut bins: HashMap<[u8; 3], Vec<Rgb<u8>>> = HashMap::new();
+    for pixel in &pixels {
+        bins.entry([pixel[0] >> 4, pixel[1] >> 4, pixel[2] >> 4]).or_default().push(*pixel);
+    }
+    let background_pixels = bins.values().max_by_key(|bin| bin.len())?;
+    if (background_pixels.len() as f32) < pixels.len() as f32 * MIN_BACKGROUND_SHARE {
+        return None;
+    }
+    let background = average(background_pixels);
+
+    let mut foreground: Vec<Rgb<u8>> =
+        pixels.iter().copied().filter(|p| distance(*p, background) > FOREGROUND_DISTANCE).collect();
+    let share = foreground.len() as f32 / pixels.len() as f32;
+    if share < FOREGROUND_SHARE.0 || share > FOREGROUND_SHARE.1 {
+        return None;
+    }
+    // Glyph edges blend into the background; the quarter of pixels furthest from it is the text color
+    let background_luminance = relative_luminance(linear, background);
+    foreground.sort_by(|a, b| {
+        let da = (relative_luminance(linear, *a) - background_luminance).abs();
+        let db = (relative_luminance(linear, *b) - background_luminance).abs();
+        db.total_cmp(&da)
+    });
+    foreground.truncate((foreground.len() / 4).max(1));
+    let text = average(&foreground);
+    Some((contrast_ratio(relative_luminance(linear, text), background_luminance), text, background))
+}
+
+fn contrast_regions(image: &RgbImage, linear: &[f32; 256], min_contrast: f32) -> (Vec<ContrastRegion>, usize, usize) {
+    let (width, height) = image.dimensions();
+    let mut regions: Vec<ContrastRegion> = Vec::new();
+    let (mut checked, mut low) = (0, 0);
+    for y in (0..height).step_by(TILE_SIZE as usize) {
+        let tile_h = TILE_SIZE.min(height - y);
+        let mut run: Option<ContrastRegion> = None;
+        for x in (0..width).step_by(TILE_SIZE as usize) {
+            let tile_w = TILE_SIZE.min(width - x);
+            let low_tile = match tile_contrast(image, linear, x, y, tile_w, tile_h) {
+                Some((ratio, text, background)) => {
+                    checked += 1;
+                    (ratio < min_contrast).then_some((ratio, text, background))
+                }
+                None => None,
+            };
+            match (low_tile, run.as_mut()) {
+                (Some((ratio, text, background)), Some(region)) => {
+                    region.width += tile_w;
+                    if ratio < region.contrast_ratio {
+                        region.contrast_ratio = ratio;
+                        region.foreground = hex(text);
+                        region.background = hex(background);
+                    }
+                    low += 1;
+                }
+                (Some((ratio, text, background)), None) => {
+                    run = Some(ContrastRegion {
+                        x,
+                        y,
+                        width: tile_w,
+                        height: tile_h,
+                        contrast_ratio: ratio,
+                        foreground: hex(text),
+                        background: hex(background),
+                    });
+                    low += 1;
+                }
+                (None, _) => regions.extend(run.take()),
+            }
+        }
+        regions.extend(run);
+    }
+    regions.sort_by(|a, b| a.contrast_ratio.total_cmp(&b.contrast_ratio));
+    regions.truncate(MAX_REGIONS);
+    for region in &mut regions {
+        region.contrast_ratio = (region.contrast_ratio * 100.0).round() / 100.0;
+    }
+    (regions, checked, low)
+}
+
+fn simulate(image: &RgbImage, linear: &[f32; 256], deficiency: ColorVision) -> Result<String, String> {
+    let matrix = deficiency.matrix();
+    let mut simulated = image.clone();
+    for pixel in simulated.pixels_mut() {
+        let rgb = [linear[pixel[0] as usize], linear[pixel[1] as usize], linear[pixel[2] as usize]];
+        for (channel, row) in matrix.iter().enumerate() {
+            pixel[channel] = linear_to_srgb(row[0] * rgb[0] + row[1] * rgb[1] + row[2] * rgb[2]);
+        }
+    }
+    let mut png = Vec::new();
+    simulated
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .map_err(|e| format!("Failed to encode simulation: {}", e))?;
+    Ok(general_purpose::STANDARD.encode(png))
+}
+
+fn analyze(image_bytes: &[u8], min_contrast: f32, deficiencies: &[ColorVision]) -> Result<AccessibilityReport, String> {
+    let image = image::load_from_memory(image_bytes)
+        .map_err(|e| format!("Failed to load image: {}", e))?
+        .to_rgb8();
+    let linear = srgb_to_linear_table();
+    let (low_contrast_regions, checked_tiles, low_contrast_tiles) = contrast_regions(&image, &linear, min_contrast);
+
+    let preview = if image.width() > MAX_SIMULATION_WIDTH {
+        let height = image.height() * MAX_SIMULATION_WIDTH / image.width();
+        image::imageops::resize(&image, MAX_SIMULATION_WIDTH, height.max(1), FilterType::Triangle)
+    } else {
+        image.clone()
+    };
+    let simulations = deficiencies
+        .iter()
+        .map(|&deficiency| {
+            Ok(ColorVisionSimulation { deficiency, image_base64: simulate(&preview, &linear, deficiency)? })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    Ok(AccessibilityReport {
+        width: image.width(),
+        height: image.height(),
+        min_contrast,
+        checked_tiles,
+        low_contrast_tiles,
+        low_contrast_regions,
+        simulations,
+    })
+}
+
+/// Find low-contrast text in an image and render it as seen with color-vision deficiencies
+/// (all of them unless `simulate` lists some; an empty list skips simulation)
+#[tauri::command]
+pub async fn analyze_accessibility(
+    image_base64: String,
+    min_contrast: Option<f32>,
+    simulate: Option<Vec<ColorVision>>,
+) -> Result<AccessibilityReport, String> {
+    let min_contrast = min_contrast.unwrap_or(DEFAULT_MIN_CONTRAST);
+    if !(1.0..=21.0).contains(&min_contrast) {
+        return Err("min_contrast must be between 1 and 21".to_string());
+    }
+    let image_bytes = general_purpose::STANDARD
+        .decode(image_base64.trim())
+        .map_err(|e| format!("Invalid base64 image: {}", e))?;
+    let deficiencies = simulate.unwrap_or_else(|| ALL_COLOR_VISIONS.to_vec());
+
+    let report = tauri::async_runtime::spawn_blocking(move || analyze(&image_bytes, min_contrast, &deficiencies))
+        .await
+        .map_err(|e| format!("Accessibility analysis failed: {}", e))??;
+    tracing::info!(
+        "♿ Accessibility check: {} of {} text tiles below {:.1}:1",
+        report.low_contrast_tiles,
+        report.checked_tiles,
+        min_contrast
+    );
+    Ok(report)
+}
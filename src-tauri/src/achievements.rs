@@ -0,0 +1,240 @@
+//! Streaks, time on task and badges, computed from what the history database already records
+//! (exercise attempts, flashcard reviews, finished lessons) plus the minutes of activity and
+//! focus logged here per day. Badges are stored once unlocked and announced with
+//! `achievement-unlocked`.
+
+use chrono::{Duration as DateDuration, Local, NaiveDate};
+use rusqlite::{params, Connection};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+
+use crate::history::HistoryState;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Achievement tables, applied by the history database's migrations
+pub(crate) const SCHEMA: &str = r#"
+CREATE TABLE study_days (
+    day TEXT PRIMARY KEY,
+    active_minutes INTEGER NOT NULL DEFAULT 0,
+    focus_minutes INTEGER NOT NULL DEFAULT 0
+);
+CREATE TABLE achievements (
+    id TEXT PRIMARY KEY,
+    unlocked_at INTEGER NOT NULL
+);
+"#;
+
+/// Every local day with any study: logged minutes, attempts, reviews or finished lessons
+const STUDY_DAYS_QUERY: &str = "
+SELECT day FROM study_days WHERE active_minutes > 0 OR focus_minutes > 0
+UNION SELECT date(attempted_at, 'unixepoch', 'localtime') FROM exercise_attempts
+UNION SELECT date(reviewed_at, 'unixepoch', 'localtime') FROM flashcard_reviews
+UNION SELECT date(completed_at, 'unixepoch', 'localtime') FROM lesson_progress";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Metric {
+    StreakDays,
+    StudyMinutes,
+    Attempts,
+    CorrectAttempts,
+    Reviews,
+    Lessons,
+    Curricula,
+}
+
+struct BadgeDefinition {
+    id: &'static str,
+    title: &'static str,
+    description: &'static str,
+    metric: Metric,
+    goal: u32,
+}
+
+const BADGES: &[BadgeDefinition] = &[
+    BadgeDefinition { id: "first_exercise", title: "First Steps", description: "Attempt your first exercise", metric: Metric::Attempts, goal: 1 },
+    BadgeDefinition { id: "exercises_100", title: "Practice Makes Perfect", description: "Attempt 100 exercises", metric: Metric::Attempts, goal: 100 },
+    BadgeDefinition { id: "correct_50", title: "Sharp Shooter", description: "Answer 50 exercises correctly", metric: Metric::CorrectAttempts, goal: 50 },
+    BadgeDefinition { id: "reviews_50", title: "Memory Builder", description: "Review 50 flashcards", metric: Metric::Reviews, goal: 50 },
+    BadgeDefinition { id: "reviews_500", title: "Elephant Memory", description: "Review 500 flashcards", metric: Metric::Reviews, goal: 500 },
+    BadgeDefinition { id: "first_lesson", title: "Lesson Learned", description: "Finish a lesson", metric: Metric::Lessons, goal: 1 },
+    BadgeDefinition { id: "curriculum_complete", title: "Graduate", description: "Finish every lesson of a curriculum", metric: Metric::Curricula, goal: 1 },
+    BadgeDefinition { id: "streak_3", title: "On a Roll", description: "Study 3 days in a row", metric: Metric::StreakDays, goal: 3 },
+    BadgeDefinition { id: "streak_7", title: "Week Warrior", description: "Study 7 days in a row", metric: Metric::StreakDays, goal: 7 },
+    BadgeDefinition { id: "streak_30", title: "Unstoppable", description: "Study 30 days in a row", metric: Metric::StreakDays, goal: 30 },
+    BadgeDefinition { id: "time_1h", title: "Warming Up", description: "Spend an hour on task", metric: Metric::StudyMinutes, goal: 60 },
+    BadgeDefinition { id: "time_10h", title: "Dedicated", description: "Spend 10 hours on task", metric: Metric::StudyMinutes, goal: 600 },
+    BadgeDefinition { id: "time_100h", title: "Scholar", description: "Spend 100 hours on task", metric: Metric::StudyMinutes, goal: 6000 },
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Badge {
+    pub id: String,
+    pub title: String,
+    pub description: String,
+    pub unlocked_at: Option<i64>,
+    /// Toward the goal, 0 to 1
+    pub progress: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Achievements {
+    /// Days in a row with study up to today (or yesterday, while today has none yet)
+    pub current_streak_days: u32,
+    pub longest_streak_days: u32,
+    pub studied_today: bool,
+    /// Minutes on task: per day, the larger of active input minutes and focus-session minutes
+    pub total_study_minutes: u32,
+    pub badges: Vec<Badge>,
+}
+
+/// What kind of minutes `log_minutes` adds
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum MinuteKind {
+    /// A minute with keyboard or mouse input (activity tracking)
+    Active,
+    /// A finished focus phase
+    Focus,
+}
+
+/// Add minutes to today's study log
+pub(crate) fn log_minutes(app: &AppHandle, kind: MinuteKind, minutes: u32) {
+    let column = match kind {
+        MinuteKind::Active => "active_minutes",
+        MinuteKind::Focus => "focus_minutes",
+    };
+    let day = Local::now().format("%Y-%m-%d").to_string();
+    let result = app.state::<HistoryState>().with_db(|db| {
+        db.execute(
+            &format!(
+                "INSERT INTO study_days (day, {column}) VALUES (?1, ?2)
+                 ON CONFLICT(day) DO UPDATE SET {column} = {column} + excluded.{column}"
+            ),
+            params![day, minutes],
+        )
+    });
+    if let Err(e) = result {
+        tracing::warn!("⚠️ Failed to log study minutes: {}", e);
+    }
+}
+
+/// Titles of the given badge ids, in badge order
+pub(crate) fn badge_titles(ids: &[String]) -> Vec<String> {
+    BADGES.iter().filter(|b| ids.iter().any(|id| id == b.id)).map(|b| b.title.to_string()).collect()
+}
+
+/// Current streak (ending today or yesterday) and longest streak, in days
+fn streaks(days: &BTreeSet<NaiveDate>, today: NaiveDate) -> (u32, u32) {
+    let (mut longest, mut run, mut previous) = (0, 0, None::<NaiveDate>);
+    for day in days {
+        run = match previous {
+            Some(previous) if *day - previous == DateDuration::days(1) => run + 1,
+            _ => 1,
+        };
+        longest = longest.max(run);
+        previous = Some(*day);
+    }
+    let mut current = 0;
+    let mut day = if days.contains(&today) { today } else { today - DateDuration::days(1) };
+    while days.contains(&day) {
+        current += 1;
+        day -= DateDuration::days(1);
+    }
+    (current, longest)
+}
+
+fn count(db: &Connection, sql: &str) -> rusqlite::Result<u32> {
+    db.query_row(sql, [], |row| row.get(0))
+}
+
+/// Compute streaks and badge progress, storing badges that were just reached; those are also
+/// returned separately
+fn evaluate(db: &Connection) -> rusqlite::Result<(Achievements, Vec<Badge>)> {
+    let today = Local::now().date_naive();
+    let days: BTreeSet<NaiveDate> = db
+        .prepare(STUDY_DAYS_QUERY)?
+        .query_map([], |row| row.get::<_, String>(0))?
+        .filter_map(|day| day.ok()?.parse().ok())
+        .collect();
+    let (current_streak_days, longest_streak_days) = streaks(&days, today);
+    let total_study_minutes = count(db, "SELECT COALESCE(SUM(MAX(active_minutes, focus_minutes)), 0) FROM study_days")?;
+
+    let metrics: HashMap<Metric, u32> = HashMap::from([
+        (Metric::StreakDays, longest_streak_days),
+        (Metric::StudyMinutes, total_study_minutes),
+        (Metric::Attempts, count(db, "SELECT COUNT(*) FROM exercise_attempts")?),
+        (Metric::CorrectAttempts, count(db, "SELECT COUNT(*) FROM exercise_attempts WHERE correct")?),
+        (Metric::Reviews, count(db, "SELECT COUNT(*) FROM flashcard_reviews")?),
+        (Metric::Lessons, count(db, "SELECT COUNT(*) FROM lesson_progress")?),
+        (Metric::Curricula, crate::curriculum::completed_count(db)?),
+    ]);
+
+    let unlocked: HashMap<String, i64> = db
+        .prepare("SELECT id, unlocked_at FROM achievements")?
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<rusqlite::Result<_>>()?;
+    let now = chrono::Utc::now().timestamp();
+    let mut badges = Vec::with_capacity(BADGES.len());
+    let mut newly_unlocked = Vec::new();
+    for definition in BADGES {
+        let value = metrics[&definition.metric];
+        let mut unlocked_at = unlocked.get(definition.id).copied();
+        let just_unlocked = unlocked_at.is_none() && value >= definition.goal;
+        if just_unlocked {
+            db.execute("INSERT OR IGNORE INTO achievements (id, unlocked_at) VALUES (?1, ?2)", params![definition.id, now])?;
+            unlocked_at = Some(now);
+        }
+        let badge = Badge {
+            id: definition.id.to_string(),
+            title: definition.title.to_string(),
+            description: definition.description.to_string(),
+            unlocked_at,
+            progress: (value as f64 / definition.goal as f64).min(1.0),
+        };
+        if just_unlocked {
+            newly_unlocked.push(badge.clone());
+        }
+        badges.push(badge);
+    }
+
+    let achievements = Achievements {
+        current_streak_days,
+        longest_streak_days,
+        studied_today: days.contains(&today),
+        total_study_minutes,
+        badges,
+    };
+    Ok((achievements, newly_unlocked))
+}
+
+fn announce(app: &AppHandle, unlocked: Vec<Badge>) {
+    for badge in unlocked {
+        tracing::info!("🏆 Achievement unlocked: {}", badge.title);
+        let _ = crate::event_bus::emit(app, crate::events::ACHIEVEMENT_UNLOCKED, badge);
+    }
+}
+
+/// Check for newly reached badges every minute
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let history = app.state::<HistoryState>().inner().clone();
+        loop {
+            match history.with_db(|db| evaluate(db)) {
+                Ok((_, unlocked)) => announce(&app, unlocked),
+                Err(e) => tracing::debug!("Achievement check skipped: {}", e),
+            }
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
+
+/// Streaks, time on task and every badge with its progress
+#[tauri::command]
+pub async fn get_achievements(app: AppHandle, state: State<'_, HistoryState>) -> Result<Achievements, String> {
+    let (achievements, unlocked) = state.with_db(|db| evaluate(db))?;
+    announce(&app, unlocked);
+    Ok(achievements)
+}
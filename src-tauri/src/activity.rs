@@ -0,0 +1,188 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager, State};
+
+use crate::consent::{ConsentScope, ConsentState};
+use crate::persistence;
+
+const METRICS_FILE: &str = "activity_metrics.json";
+
+/// Per-minute samples older than this are dropped
+const RETENTION_SECS: i64 = 7 * 24 * 60 * 60;
+
+/// Input counts for one minute. Never contains what was typed or clicked.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ActivityMinute {
+    pub minute_start: i64,
+    pub keystrokes: u32,
+    pub clicks: u32,
+}
+
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct ActivityRange {
+    /// Unix timestamp (seconds), defaults to one hour ago
+    #[serde(default)]
+    pub from: Option<i64>,
+    /// Unix timestamp (seconds), defaults to now
+    #[serde(default)]
+    pub to: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ActivityMetrics {
+    pub from: i64,
+    pub to: i64,
+    pub tracking_enabled: bool,
+    pub minutes: Vec<ActivityMinute>,
+    pub total_keystrokes: u64,
+    pub total_clicks: u64,
+    /// Minutes with at least one keystroke or click
+    pub active_minutes: usize,
+    /// Average keystrokes per active minute
+    pub keystrokes_per_minute: f64,
+    pub clicks_per_minute: f64,
+}
+
+#[derive(Clone, Default)]
+pub struct ActivityState {
+    pub enabled: Arc<AtomicBool>,
+    /// The minute being counted, recorded once the next one starts
+    current: Arc<Mutex<Option<ActivityMinute>>>,
+    minutes: Arc<Mutex<Vec<ActivityMinute>>>,
+}
+
+fn persist(app: &AppHandle, minutes: &[ActivityMinute]) -> Result<(), String> {
+    let path = persistence::data_file(app, METRICS_FILE)?;
+    persistence::save_json(&path, &minutes)
+}
+
+/// Load samples recorded by previous runs
+pub fn init(app: &AppHandle) {
+    let loaded: Vec<ActivityMinute> = match persistence::data_file(app, METRICS_FILE) {
+        Ok(path) => persistence::load_json(&path),
+        Err(e) => {
+            tracing::warn!("⚠️ {}", e);
+            Vec::new()
+        }
+    };
+    *app.state::<ActivityState>().minutes.lock().unwrap() = loaded;
+}
+
+fn record_minute(app: &AppHandle, minute: ActivityMinute) {
+    let active = minute.keystrokes + minute.clicks > 0;
+    let state = app.state::<ActivityState>();
+    let mut minutes = state.minutes.lock().unwrap();
+    minutes.push(minute);
+    minutes.retain(|m| m.minute_start >= chrono::Utc::now().timestamp() - RETENTION_SECS);
+    if let Err(e) = persist(app, &minutes) {
+        tracing::warn!("⚠️ Failed to persist metrics: {}", e);
+    }
+    drop(minutes);
+    if active {
+        crate::achievements::log_minutes(app, crate::achievements::MinuteKind::Active, 1);
+    }
+}
+
+/// Add input counted by the shared input hook to the current minute
+pub(crate) fn record_input(app: &AppHandle, keystrokes: u64, clicks: u64) {
+    let state = app.state::<ActivityState>();
+    if !state.enabled.load(Ordering::Relaxed) {
+        return;
+    }
+    let minute_start = chrono::Utc::now().timestamp() / 60 * 60;
+    let mut current = state.current.lock().unwrap();
+    let finished = current.take_if(|minute| minute.minute_start != minute_start);
+    let minute = current.get_or_insert(ActivityMinute { minute_start, keystrokes: 0, clicks: 0 });
+    minute.keystrokes = minute.keystrokes.saturating_add(keystrokes as u32);
+    minute.clicks = minute.clicks.saturating_add(clicks as u32);
+    drop(current);
+    if let Some(finished) = finished {
+        record_minute(app, finished);
+    }
+}
+
+/// Stop counting input, recording the minute in progress. Called when tracking is disabled,
+/// when monitoring consent ends and when the app exits.
+pub fn stop(app: &AppHandle) {
+    let state = app.state::<ActivityState>();
+    if state.enabled.swap(false, Ordering::Relaxed) {
+        tracing::info!("⌨️ Activity tracking stopped");
+    }
+    if let Err(e) = crate::input_hook::set_activity(app, false) {
+        tracing::warn!("⚠️ {}", e);
+    }
+    let current = state.current.lock().unwrap().take();
+    if let Some(minute) = current {
+        record_minute(app, minute);
+    }
+}
+
+/// Summarize the samples between `from` and `to`
+pub fn metrics(state: &ActivityState, from: i64, to: i64) -> ActivityMetrics {
+    let minutes: Vec<ActivityMinute> = state
+        .minutes
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|m| m.minute_start >= from && m.minute_start <= to)
+        .cloned()
+        .collect();
+
+    let total_keystrokes: u64 = minutes.iter().map(|m| m.keystrokes as u64).sum();
+    let total_clicks: u64 = minutes.iter().map(|m| m.clicks as u64).sum();
+    let active_minutes = minutes.iter().filter(|m| m.keystrokes > 0 || m.clicks > 0).count();
+    let per_minute = |total: u64| {
+        if active_minutes == 0 {
+            0.0
+        } else {
+            total as f64 / active_minutes as f64
+        }
+    };
+
+    ActivityMetrics {
+        from,
+        to,
+        tracking_enabled: state.enabled.load(Ordering::Relaxed),
+        keystrokes_per_minute: per_minute(total_keystrokes),
+        clicks_per_minute: per_minute(total_clicks),
+        minutes,
+        total_keystrokes,
+        total_clicks,
+        active_minutes,
+    }
+}
+
+/// Opt in to (or out of) counting keystrokes and clicks per minute. Counting needs monitoring
+/// consent and stops when it ends.
+#[tauri::command]
+pub async fn set_activity_tracking(
+    app: AppHandle,
+    state: State<'_, ActivityState>,
+    enabled: bool,
+) -> Result<(), String> {
+    if enabled {
+        app.state::<ConsentState>().require(ConsentScope::Screen)?;
+        crate::input_hook::set_activity(&app, true)?;
+        if !state.enabled.swap(true, Ordering::Relaxed) {
+            tracing::info!("⌨️ Activity tracking started");
+        }
+    } else {
+        stop(&app);
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_activity_metrics(
+    state: State<'_, ActivityState>,
+    range: ActivityRange,
+) -> Result<ActivityMetrics, String> {
+    let to = range.to.unwrap_or_else(|| chrono::Utc::now().timestamp());
+    let from = range.from.unwrap_or(to - 60 * 60);
+    if from > to {
+        return Err("Range start must be before its end".to_string());
+    }
+    Ok(metrics(&state, from, to))
+}
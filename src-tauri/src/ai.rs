@@ -0,0 +1,836 @@
+//! AI providers the backend can talk to: OpenAI, Anthropic, Gemini, a local Ollama and a
+//! GGUF model loaded in-process with llama.cpp. Each
+//! configured provider has its own model and optional base URL, with its API key kept in the
+//! OS keychain. Requests go to the enabled providers in priority order and fail over to the
+//! next one on an error, so a classroom can switch or add providers without code changes.
+
+use futures_util::future::Either;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, State};
+use tokio::sync::mpsc;
+
+use crate::cancellation::{CancellationState, OperationGuard};
+use crate::persistence;
+use crate::secret_filter::Redaction;
+
+const SETTINGS_FILE: &str = "ai_providers.json";
+/// Keychain service for provider API keys; the account is `ai-provider:{id}`
+const KEYRING_SERVICE: &str = "ai-teacher";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
+/// Longest silence allowed inside a streamed reply
+const STREAM_IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+/// `ai-token` events carry the text received during this interval, so a fast model doesn't
+/// emit one IPC message per token
+const TOKEN_COALESCE_INTERVAL: Duration = Duration::from_millis(50);
+/// Text deltas buffered between the HTTP reader and the emitter; a full buffer pauses reading
+const DELTA_BUFFER: usize = 256;
+const TEST_TIMEOUT: Duration = Duration::from_secs(20);
+pub(crate) const DEFAULT_MAX_TOKENS: u32 = 2048;
+const MAX_PROVIDERS: usize = 16;
+/// Characters of an error response body quoted in the error
+const ERROR_BODY_CHARS: usize = 300;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ProviderKind {
+    OpenAi,
+    Anthropic,
+    Gemini,
+    Ollama,
+    /// The model loaded in-process by `start_local_model`; it has no base URL
+    LlamaCpp,
+}
+
+impl ProviderKind {
+    fn default_base_url(self) -> &'static str {
+        match self {
+            ProviderKind::OpenAi => "https://api.openai.com",
+            ProviderKind::Anthropic => "https://api.anthropic.com",
+            ProviderKind::Gemini => "https://generativelanguage.googleapis.com",
+            ProviderKind::Ollama => "http://localhost:11434",
+            // Answered in-process, never over HTTP
+            ProviderKind::LlamaCpp => "",
+        }
+    }
+
+    fn needs_api_key(self) -> bool {
+        !self.is_local()
+    }
+
+    /// Runs on this machine: needs no key and costs nothing
+    pub(crate) fn is_local(self) -> bool {
+        matches!(self, ProviderKind::Ollama | ProviderKind::LlamaCpp)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ProviderConfig {
+    /// Unique name, e.g. "openai" or "school-proxy"
+    pub id: String,
+    pub kind: ProviderKind,
+    pub model: String,
+    /// Overrides the provider's public API, e.g. for a proxy or a remote Ollama
+    #[serde(default)]
+    pub base_url: Option<String>,
+    #[serde(default = "enabled_by_default")]
+    pub enabled: bool,
+}
+
+fn enabled_by_default() -> bool {
+    true
+}
+
+/// Providers in priority order: requests try the first enabled one, then fail over down the list
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct AiSettings {
+    pub providers: Vec<ProviderConfig>,
+}
+
+impl Default for AiSettings {
+    fn default() -> Self {
+        let provider = |id: &str, kind, model: &str, enabled| ProviderConfig {
+            id: id.to_string(),
+            kind,
+            model: model.to_string(),
+            base_url: None,
+            enabled,
+        };
+        Self {
+            providers: vec![
+                provider("gemini", ProviderKind::Gemini, "gemini-flash-latest", true),
+                provider("openai", ProviderKind::OpenAi, "gpt-4o-mini", false),
+                provider("anthropic", ProviderKind::Anthropic, "claude-3-5-haiku-latest", false),
+                provider("ollama", ProviderKind::Ollama, "llama3.2", false),
+                provider("llamacpp", ProviderKind::LlamaCpp, "local", false),
+            ],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ProviderInfo {
+    #[serde(flatten)]
+    pub config: ProviderConfig,
+    /// An API key is stored in the keychain
+    pub has_api_key: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ChatRole {
+    User,
+    Assistant,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ChatMessage {
+    pub role: ChatRole,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ChatRequest {
+    #[serde(default)]
+    pub system: Option<String>,
+    pub messages: Vec<ChatMessage>,
+    #[serde(default)]
+    pub max_tokens: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ChatResponse {
+    pub text: String,
+    /// The provider that answered
+    pub provider_id: String,
+    pub model: String,
+    /// Providers tried before it, with why they failed
+    pub failed_over: Vec<ProviderFailure>,
+    /// Secrets masked in the request before it was sent
+    pub redactions: Vec<Redaction>,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ProviderFailure {
+    pub provider_id: String,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ProviderTest {
+    pub provider_id: String,
+    pub ok: bool,
+    pub model: String,
+    pub latency_ms: u64,
+    pub error: Option<String>,
+}
+
+/// Payload of `ai-token`: text received since the previous event for the request
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AiToken {
+    pub request_id: String,
+    pub text: String,
+}
+
+/// Payload of `ai-complete`, the last event of a successful streamed request
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AiComplete {
+    pub request_id: String,
+    pub response: ChatResponse,
+}
+
+/// Payload of `ai-error`, the last event of a failed or cancelled streamed request
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AiError {
+    pub request_id: String,
+    pub error: String,
+}
+
+/// Tokens a request used, as reported by the provider
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct TokenUsage {
+    pub input_tokens: u32,
+    pub output_tokens: u32,
+}
+
+#[derive(Clone, Default)]
+pub struct AiState {
+    settings: Arc<Mutex<AiSettings>>,
+}
+
+fn keyring_entry(id: &str) -> Result<keyring::Entry, String> {
+    keyring::Entry::new(KEYRING_SERVICE, &format!("ai-provider:{}", id))
+        .map_err(|e| format!("Failed to open keychain: {}", e))
+}
+
+pub(crate) fn read_api_key(id: &str) -> Result<Option<String>, String> {
+    match keyring_entry(id)?.get_password() {
+        Ok(key) => Ok(Some(key)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(format!("Failed to read the {} API key from keychain: {}", id, e)),
+    }
+}
+
+fn validate(settings: &AiSettings) -> Result<(), String> {
+    if settings.providers.len() > MAX_PROVIDERS {
+        return Err(format!("At most {} providers can be configured", MAX_PROVIDERS));
+    }
+    for (index, provider) in settings.providers.iter().enumerate() {
+        let valid_id = provider.id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if provider.id.is_empty() || provider.id.len() > 64 || !valid_id {
+            return Err(format!("Provider id '{}' must be 1 to 64 letters, digits, '-' or '_'", provider.id));
+        }
+        if settings.providers[..index].iter().any(|p| p.id == provider.id) {
+            return Err(format!("Provider id '{}' is used twice", provider.id));
+        }
+        if provider.model.trim().is_empty() {
+            return Err(format!("Provider '{}' needs a model", provider.id));
+        }
+        if let Some(base_url) = &provider.base_url {
+            let url = url::Url::parse(base_url).map_err(|e| format!("Invalid base URL for '{}': {}", provider.id, e))?;
+            if !matches!(url.scheme(), "http" | "https") {
+                return Err(format!("The base URL for '{}' must be http or https", provider.id));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn http_client(timeout: Duration) -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .timeout(timeout)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))
+}
+
+/// Streams may run long, so only silence is limited
+fn stream_client() -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .connect_timeout(TEST_TIMEOUT)
+        .read_timeout(STREAM_IDLE_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))
+}
+
+fn role_name(role: ChatRole, kind: ProviderKind) -> &'static str {
+    match (role, kind) {
+        (ChatRole::User, _) => "user",
+        (ChatRole::Assistant, ProviderKind::Gemini) => "model",
+        (ChatRole::Assistant, _) => "assistant",
+    }
+}
+
+/// The request for `provider`: URL, headers and JSON body. Streamed replies arrive as
+/// server-sent events, or as JSON lines from Ollama.
+fn build_request(
+    client: &reqwest::Client,
+    provider: &ProviderConfig,
+    api_key: Option<&str>,
+    request: &ChatRequest,
+    stream: bool,
+) -> reqwest::RequestBuilder {
+    let kind = provider.kind;
+    let base = provider.base_url.as_deref().unwrap_or(kind.default_base_url()).trim_end_matches('/');
+    let max_tokens = request.max_tokens.unwrap_or(DEFAULT_MAX_TOKENS);
+    let messages: Vec<Value> = request
+        .messages
+        .iter()
+        .map(|m| json!({ "role": role_name(m.role, kind), "content": m.content }))
+        .collect();
+    let with_system = |mut messages: Vec<Value>| {
+        if let Some(system) = &request.system {
+            messages.insert(0, json!({ "role": "system", "content": system }));
+        }
+        messages
+    };
+
+    match kind {
+        ProviderKind::OpenAi | ProviderKind::LlamaCpp => {
+            let mut body = json!({ "model": provider.model, "messages": with_system(messages), "max_tokens": max_tokens });
+            if stream {
+                body["stream"] = json!(true);
+                body["stream_options"] = json!({ "include_usage": true });
+            }
+            client.post(format!("{}/v1/chat/completions", base)).bearer_auth(api_key.unwrap_or_default()).json(&body)
+        }
+        ProviderKind::Anthropic => {
+            let mut body =
+                json!({ "model": provider.model, "messages": messages, "max_tokens": max_tokens, "stream": stream });
+            if let Some(system) = &request.system {
+                body["system"] = json!(system);
+            }
+            client
+                .post(format!("{}/v1/messages", base))
+                .header("x-api-key", api_key.unwrap_or_default())
+                .header("anthropic-version", "2023-06-01")
+                .json(&body)
+        }
+        ProviderKind::Gemini => {
+            let contents: Vec<Value> = request
+                .messages
+                .iter()
+                .map(|m| json!({ "role": role_name(m.role, kind), "parts": [{ "text": m.content }] }))
+                .collect();
+            let mut body = json!({ "contents": contents, "generationConfig": { "maxOutputTokens": max_tokens } });
+            if let Some(system) = &request.system {
+                body["systemInstruction"] = json!({ "parts": [{ "text": system }] });
+            }
+            let url = if stream {
+                format!("{}/v1beta/models/{}:streamGenerateContent?alt=sse", base, provider.model)
+            } else {
+                format!("{}/v1beta/models/{}:generateContent", base, provider.model)
+            };
+            client
+                .post(url)
+                .header("x-goog-api-key", api_key.unwrap_or_default())
+                .json(&body)
+        }
+        ProviderKind::Ollama => {
+            let body = json!({
+                "model": provider.model,
+                "messages": with_system(messages),
+                "stream": stream,
+                "options": { "num_predict": max_tokens },
+            });
+            client.post(format!("{}/api/chat", base)).json(&body)
+        }
+    }
+}
+
+/// The reply text in a provider's response
+fn response_text(kind: ProviderKind, body: &Value) -> Option<String> {
+    let joined = |parts: &Value, key: &str| {
+        parts.as_array().map(|parts| parts.iter().filter_map(|p| p[key].as_str()).collect::<Vec<_>>().join(""))
+    };
+    let text = match kind {
+        ProviderKind::OpenAi | ProviderKind::LlamaCpp => body["choices"][0]["message"]["content"].as_str().map(str::to_string),
+        ProviderKind::Anthropic => joined(&body["content"], "text"),
+        ProviderKind::Gemini => joined(&body["candidates"][0]["content"]["parts"], "text"),
+        ProviderKind::Ollama => body["message"]["content"].as_str().map(str::to_string),
+    };
+    text.filter(|t| !t.trim().is_empty())
+}
+
+/// Token counts in a provider's response, when it reports them
+fn response_usage(kind: ProviderKind, body: &Value) -> Option<TokenUsage> {
+    let (input, output) = match kind {
+        ProviderKind::OpenAi | ProviderKind::LlamaCpp => (&body["usage"]["prompt_tokens"], &body["usage"]["completion_tokens"]),
+        ProviderKind::Anthropic => (&body["usage"]["input_tokens"], &body["usage"]["output_tokens"]),
+        ProviderKind::Gemini => (&body["usageMetadata"]["promptTokenCount"], &body["usageMetadata"]["candidatesTokenCount"]),
+        ProviderKind::Ollama => (&body["prompt_eval_count"], &body["eval_count"]),
+    };
+    Some(TokenUsage { input_tokens: input.as_u64()? as u32, output_tokens: output.as_u64().unwrap_or(0) as u32 })
+}
+
+/// Send `request` to one provider and return the response once its status is a success
+async fn send(
+    client: &reqwest::Client,
+    provider: &ProviderConfig,
+    request: &ChatRequest,
+    stream: bool,
+) -> Result<reqwest::Response, String> {
+    let api_key = read_api_key(&provider.id)?;
+    if provider.kind.needs_api_key() && api_key.is_none() {
+        return Err("No API key stored".to_string());
+    }
+    let response = build_request(client, provider, api_key.as_deref(), request, stream)
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {}", e))?;
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        let excerpt: String = body.chars().take(ERROR_BODY_CHARS).collect();
+        return Err(format!("HTTP {}: {}", status, excerpt.trim()));
+    }
+    Ok(response)
+}
+
+/// Answer `request` with the local llama.cpp model, collecting its text
+async fn call_local(
+    app: &AppHandle,
+    request: &ChatRequest,
+    timeout: Duration,
+) -> Result<(String, Option<TokenUsage>), String> {
+    let max_tokens = request.max_tokens.unwrap_or(DEFAULT_MAX_TOKENS);
+    let (sender, mut receiver) = mpsc::channel::<String>(DELTA_BUFFER);
+    let collect = async {
+        let mut text = String::new();
+        while let Some(delta) = receiver.recv().await {
+            text.push_str(&delta);
+        }
+        text
+    };
+    // Dropping the receiver on timeout stops the generation
+    let (usage, text) = tokio::time::timeout(timeout, async {
+        tokio::join!(crate::models::generate(app, request, max_tokens, sender), collect)
+    })
+    .await
+    .map_err(|_| format!("The local model didn't answer within {} s", timeout.as_secs()))?;
+    let usage = usage?;
+    if text.trim().is_empty() {
+        return Err("The response held no text".to_string());
+    }
+    Ok((text, usage))
+}
+
+/// Send `request` to one provider and return its reply
+async fn call(
+    app: &AppHandle,
+    provider: &ProviderConfig,
+    request: &ChatRequest,
+    timeout: Duration,
+) -> Result<(String, Option<TokenUsage>), String> {
+    if provider.kind == ProviderKind::LlamaCpp {
+        return call_local(app, request, timeout).await;
+    }
+    let response = send(&http_client(timeout)?, provider, request, false).await?;
+    let body = response.text().await.map_err(|e| format!("Failed to read the response: {}", e))?;
+    let body: Value = serde_json::from_str(&body).map_err(|e| format!("Unexpected response: {}", e))?;
+    let text = response_text(provider.kind, &body).ok_or("The response held no text")?;
+    Ok((text, response_usage(provider.kind, &body)))
+}
+
+pub fn init(app: &AppHandle) {
+    let settings: AiSettings = persistence::data_file(app, SETTINGS_FILE)
+        .map(|path| persistence::load_json(&path))
+        .unwrap_or_default();
+    *app.state::<AiState>().settings.lock().unwrap() = settings;
+}
+
+/// `request` with secrets in its system prompt and messages masked, and what was masked
+fn mask_secrets(request: &ChatRequest) -> (ChatRequest, Vec<Redaction>) {
+    let mut redactions = Vec::new();
+    let mut mask = |text: &str| {
+        let filtered = crate::secret_filter::filter(text);
+        redactions.extend(filtered.redactions);
+        filtered.text
+    };
+    let system = request.system.as_deref().map(&mut mask);
+    let messages = request
+        .messages
+        .iter()
+        .map(|m| ChatMessage { role: m.role, content: mask(&m.content) })
+        .collect();
+    (ChatRequest { system, messages, max_tokens: request.max_tokens }, redactions)
+}
+
+/// The request with secrets masked, what was masked, and the enabled providers in order
+fn prepare(app: &AppHandle, request: &ChatRequest) -> Result<(ChatRequest, Vec<Redaction>, Vec<ProviderConfig>), String> {
+    if request.messages.is_empty() {
+        return Err("A chat request needs at least one message".to_string());
+    }
+    let (request, redactions) = mask_secrets(request);
+    if !redactions.is_empty() {
+        tracing::info!("🔒 Masked {} secret(s) before an AI request", redactions.len());
+    }
+    let providers: Vec<ProviderConfig> =
+        app.state::<AiState>().settings.lock().unwrap().providers.iter().filter(|p| p.enabled).cloned().collect();
+    if providers.is_empty() {
+        return Err("No AI provider is enabled".to_string());
+    }
+    Ok((request, redactions, providers))
+}
+
+fn all_failed(failed_over: &[ProviderFailure]) -> String {
+    let reasons: Vec<String> = failed_over.iter().map(|f| format!("{}: {}", f.provider_id, f.error)).collect();
+    format!("Every AI provider failed ({})", reasons.join("; "))
+}
+
+/// Send `request`, with secrets masked, to the enabled providers in priority order until one
+/// answers
+pub(crate) async fn complete(app: &AppHandle, request: &ChatRequest) -> Result<ChatResponse, String> {
+    crate::metrics::timed(app, crate::metrics::AI_CALL, complete_with(app, request, false)).await
+}
+
+/// `complete` limited to the enabled Ollama and llama.cpp providers, for text that must not
+/// leave the machine
+pub(crate) async fn complete_locally(app: &AppHandle, request: &ChatRequest) -> Result<ChatResponse, String> {
+    crate::metrics::timed(app, crate::metrics::AI_CALL, complete_with(app, request, true)).await
+}
+
+async fn complete_with(app: &AppHandle, request: &ChatRequest, local_only: bool) -> Result<ChatResponse, String> {
+    let (request, redactions, mut providers) = prepare(app, request)?;
+    if local_only {
+        providers.retain(|p| p.kind.is_local());
+        if providers.is_empty() {
+            return Err("No local AI provider (Ollama or llama.cpp) is enabled".to_string());
+        }
+    }
+    let request = &request;
+
+    let started = Instant::now();
+    let mut failed_over = Vec::new();
+    for provider in providers {
+        let result = match crate::ai_usage::check_budget(app, &provider) {
+            Ok(()) => call(app, &provider, request, REQUEST_TIMEOUT).await,
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok((text, usage)) => {
+                crate::ai_usage::record(app, &provider, "chat", request, &text, usage);
+                if !failed_over.is_empty() {
+                    tracing::warn!("⚠️ AI request failed over to '{}'", provider.id);
+                }
+                return Ok(ChatResponse {
+                    text,
+                    provider_id: provider.id,
+                    model: provider.model,
+                    failed_over,
+                    redactions,
+                    duration_ms: started.elapsed().as_millis() as u64,
+                });
+            }
+            Err(error) => {
+                tracing::warn!("⚠️ AI provider '{}' failed: {}", provider.id, error);
+                failed_over.push(ProviderFailure { provider_id: provider.id, error });
+            }
+        }
+    }
+    Err(all_failed(&failed_over))
+}
+
+/// Input and output token counts gathered across a stream's events
+#[derive(Default)]
+struct StreamUsage {
+    input: Option<u32>,
+    output: Option<u32>,
+}
+
+impl StreamUsage {
+    fn finish(&self) -> Option<TokenUsage> {
+        Some(TokenUsage { input_tokens: self.input?, output_tokens: self.output.unwrap_or(0) })
+    }
+}
+
+fn as_u32(value: &Value) -> Option<u32> {
+    value.as_u64().map(|v| v as u32)
+}
+
+/// The text in one streamed event, noting any token counts it carries
+fn stream_event(kind: ProviderKind, event: &Value, usage: &mut StreamUsage) -> Result<Option<String>, String> {
+    if let Some(error) = event.get("error").filter(|e| !e.is_null()) {
+        let message = error["message"].as_str().or(error.as_str()).unwrap_or("The provider reported an error");
+        return Err(message.to_string());
+    }
+    let text = match kind {
+        ProviderKind::OpenAi | ProviderKind::LlamaCpp => {
+            if let Some(counts) = event.get("usage").filter(|u| !u.is_null()) {
+                usage.input = as_u32(&counts["prompt_tokens"]);
+                usage.output = as_u32(&counts["completion_tokens"]);
+            }
+            event["choices"][0]["delta"]["content"].as_str().map(str::to_string)
+        }
+        ProviderKind::Anthropic => match event["type"].as_str() {
+            Some("message_start") => {
+                usage.input = as_u32(&event["message"]["usage"]["input_tokens"]);
+                None
+            }
+            Some("message_delta") => {
+                usage.output = as_u32(&event["usage"]["output_tokens"]);
+                None
+            }
+            Some("content_block_delta") => event["delta"]["text"].as_str().map(str::to_string),
+            _ => None,
+        },
+        ProviderKind::Gemini => {
+            if let Some(counts) = event.get("usageMetadata") {
+                usage.input = as_u32(&counts["promptTokenCount"]).or(usage.input);
+                usage.output = as_u32(&counts["candidatesTokenCount"]).or(usage.output);
+            }
+            event["candidates"][0]["content"]["parts"]
+                .as_array()
+                .map(|parts| parts.iter().filter_map(|p| p["text"].as_str()).collect::<String>())
+        }
+        ProviderKind::Ollama => {
+            if event["done"].as_bool() == Some(true) {
+                usage.input = as_u32(&event["prompt_eval_count"]);
+                usage.output = as_u32(&event["eval_count"]);
+            }
+            event["message"]["content"].as_str().map(str::to_string)
+        }
+    };
+    Ok(text.filter(|t| !t.is_empty()))
+}
+
+/// Read a streamed reply, passing each text delta to `deltas`, and return its token counts
+async fn read_stream(
+    mut response: reqwest::Response,
+    kind: ProviderKind,
+    deltas: mpsc::Sender<String>,
+) -> Result<Option<TokenUsage>, String> {
+    let mut usage = StreamUsage::default();
+    let mut buffer: Vec<u8> = Vec::new();
+    let mut done = false;
+    while !done {
+        match response.chunk().await.map_err(|e| format!("The stream broke off: {}", e))? {
+            Some(bytes) => buffer.extend_from_slice(&bytes),
+            None => {
+                done = true;
+                buffer.push(b'\n');
+            }
+        }
+        while let Some(end) = buffer.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = buffer.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim();
+            // Ollama sends bare JSON lines, the others server-sent events
+            let payload = match (kind, line.strip_prefix("data:")) {
+                (ProviderKind::Ollama, _) => line,
+                (_, Some(payload)) => payload.trim(),
+                (_, None) => continue,
+            };
+            if payload.is_empty() || payload == "[DONE]" {
+                continue;
+            }
+            let event: Value = serde_json::from_str(payload).map_err(|e| format!("Unexpected stream event: {}", e))?;
+            if let Some(text) = stream_event(kind, &event, &mut usage)? {
+                deltas.send(text).await.map_err(|_| crate::cancellation::CANCELLED.to_string())?;
+            }
+        }
+    }
+    Ok(usage.finish())
+}
+
+/// Stream one provider's reply as `ai-token` events, each carrying the text received during
+/// the coalescing interval. On failure, also says whether text was already emitted, after which
+/// failing over would repeat the start of the answer.
+async fn stream_provider(
+    app: &AppHandle,
+    provider: &ProviderConfig,
+    request: &ChatRequest,
+    request_id: &str,
+    operation: &OperationGuard,
+) -> Result<(String, Option<TokenUsage>), (String, bool)> {
+    let (sender, mut receiver) = mpsc::channel(DELTA_BUFFER);
+    let reader = if provider.kind == ProviderKind::LlamaCpp {
+        let max_tokens = request.max_tokens.unwrap_or(DEFAULT_MAX_TOKENS);
+        Either::Left(crate::models::generate(app, request, max_tokens, sender))
+    } else {
+        let client = stream_client().map_err(|e| (e, false))?;
+        let response = send(&client, provider, request, true).await.map_err(|e| (e, false))?;
+        Either::Right(read_stream(response, provider.kind, sender))
+    };
+    tokio::pin!(reader);
+    let mut ticker = tokio::time::interval(TOKEN_COALESCE_INTERVAL);
+    let mut text = String::new();
+    let mut pending = String::new();
+    let flush = |pending: &mut String| {
+        if !pending.is_empty() {
+            let token = AiToken { request_id: request_id.to_string(), text: std::mem::take(pending) };
+            let _ = crate::event_bus::emit(app, crate::events::AI_TOKEN, token);
+        }
+    };
+
+    let result = loop {
+        tokio::select! {
+            result = &mut reader => break result,
+            Some(delta) = receiver.recv() => {
+                text.push_str(&delta);
+                pending.push_str(&delta);
+            }
+            _ = ticker.tick() => {
+                if let Err(e) = operation.check() {
+                    break Err(e);
+                }
+                flush(&mut pending);
+            }
+        }
+    };
+    // Deltas queued just before the reader finished
+    while let Ok(delta) = receiver.try_recv() {
+        text.push_str(&delta);
+        pending.push_str(&delta);
+    }
+    flush(&mut pending);
+
+    let emitted = !text.is_empty();
+    match result {
+        Ok(usage) if emitted => Ok((text, usage)),
+        Ok(_) => Err(("The response held no text".to_string(), false)),
+        Err(e) => Err((e, emitted)),
+    }
+}
+
+/// Stream `request` from the enabled providers in priority order. A provider that fails before
+/// sending any text is skipped for the next one; a failure mid-answer ends the request.
+async fn stream_completion(
+    app: &AppHandle,
+    request: &ChatRequest,
+    request_id: &str,
+    operation: &OperationGuard,
+) -> Result<ChatResponse, String> {
+    let (request, redactions, providers) = prepare(app, request)?;
+    let started = Instant::now();
+    let mut failed_over = Vec::new();
+    for provider in providers {
+        let result = match crate::ai_usage::check_budget(app, &provider) {
+            Ok(()) => stream_provider(app, &provider, &request, request_id, operation).await,
+            Err(e) => Err((e, false)),
+        };
+        match result {
+            Ok((text, usage)) => {
+                crate::ai_usage::record(app, &provider, "chat", &request, &text, usage);
+                return Ok(ChatResponse {
+                    text,
+                    provider_id: provider.id,
+                    model: provider.model,
+                    failed_over,
+                    redactions,
+                    duration_ms: started.elapsed().as_millis() as u64,
+                });
+            }
+            Err((error, true)) => return Err(format!("{} stopped mid-answer: {}", provider.id, error)),
+            Err((error, false)) => {
+                operation.check()?;
+                tracing::warn!("⚠️ AI provider '{}' failed: {}", provider.id, error);
+                failed_over.push(ProviderFailure { provider_id: provider.id, error });
+            }
+        }
+    }
+    Err(all_failed(&failed_over))
+}
+
+#[tauri::command]
+pub async fn get_ai_providers(state: State<'_, AiState>) -> Result<Vec<ProviderInfo>, String> {
+    let providers = state.settings.lock().unwrap().providers.clone();
+    providers
+        .into_iter()
+        .map(|config| Ok(ProviderInfo { has_api_key: read_api_key(&config.id)?.is_some(), config }))
+        .collect()
+}
+
+/// Replace the provider list; its order is the failover order (teacher mode only)
+#[tauri::command]
+pub async fn set_ai_providers(
+    app: AppHandle,
+    state: State<'_, AiState>,
+    providers: Vec<ProviderConfig>,
+) -> Result<(), String> {
+    let settings = AiSettings { providers };
+    validate(&settings)?;
+    let path = persistence::data_file(&app, SETTINGS_FILE)?;
+    persistence::save_json(&path, &settings)?;
+    let order: Vec<&str> = settings.providers.iter().filter(|p| p.enabled).map(|p| p.id.as_str()).collect();
+    tracing::info!("🤖 AI provider order: {}", order.join(", "));
+    *state.settings.lock().unwrap() = settings;
+    Ok(())
+}
+
+/// Store a provider's API key in the OS keychain, or remove it with `null` (teacher mode only)
+#[tauri::command]
+pub async fn set_provider_api_key(id: String, api_key: Option<String>) -> Result<(), String> {
+    let entry = keyring_entry(&id)?;
+    match api_key.filter(|k| !k.trim().is_empty()) {
+        Some(key) => entry.set_password(key.trim()).map_err(|e| format!("Failed to save the API key: {}", e)),
+        None => match entry.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(format!("Failed to remove the API key: {}", e)),
+        },
+    }
+}
+
+/// Send a tiny prompt to one provider, enabled or not, to check its key, model and reachability
+#[tauri::command]
+pub async fn test_provider(app: AppHandle, state: State<'_, AiState>, id: String) -> Result<ProviderTest, String> {
+    let provider = state
+        .settings
+        .lock()
+        .unwrap()
+        .providers
+        .iter()
+        .find(|p| p.id == id)
+        .cloned()
+        .ok_or_else(|| format!("No AI provider '{}'", id))?;
+    let request = ChatRequest {
+        system: None,
+        messages: vec![ChatMessage { role: ChatRole::User, content: "Reply with the single word OK.".to_string() }],
+        max_tokens: Some(16),
+    };
+    let started = Instant::now();
+    let result = call(&app, &provider, &request, TEST_TIMEOUT).await;
+    let latency_ms = started.elapsed().as_millis() as u64;
+    if let Ok((text, usage)) = &result {
+        crate::ai_usage::record(&app, &provider, "test", &request, text, *usage);
+    }
+    tracing::info!("🤖 Tested AI provider '{}': {}", provider.id, if result.is_ok() { "ok" } else { "failed" });
+    Ok(ProviderTest { provider_id: provider.id, ok: result.is_ok(), model: provider.model, latency_ms, error: result.err() })
+}
+
+/// Answer a chat with the first enabled provider that responds
+#[tauri::command]
+pub async fn ai_chat(app: AppHandle, request: ChatRequest) -> Result<ChatResponse, String> {
+    complete(&app, &request).await
+}
+
+/// Stream a chat answer as `ai-token` events, ending with `ai-complete` or `ai-error`; every
+/// event carries the request id. Returns the id at once (pass one to subscribe beforehand);
+/// `cancel_operation` with it stops the stream.
+#[tauri::command]
+pub async fn ai_chat_stream(app: AppHandle, request: ChatRequest, request_id: Option<String>) -> Result<String, String> {
+    if request.messages.is_empty() {
+        return Err("A chat request needs at least one message".to_string());
+    }
+    let request_id = request_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let operation = app.state::<CancellationState>().register(Some(&request_id));
+    let id = request_id.clone();
+    tauri::async_runtime::spawn(async move {
+        let completion = stream_completion(&app, &request, &id, &operation);
+        match crate::metrics::timed(&app, crate::metrics::AI_CALL, completion).await {
+            Ok(response) => {
+                tracing::info!("🤖 Streamed AI answer {} from '{}'", id, response.provider_id);
+                let _ = crate::event_bus::emit(&app, crate::events::AI_COMPLETE, AiComplete { request_id: id, response });
+            }
+            Err(error) => {
+                tracing::warn!("⚠️ Streamed AI request {} failed: {}", id, error);
+                let _ = crate::event_bus::emit(&app, crate::events::AI_ERROR, AiError { request_id: id, error });
+            }
+        }
+    });
+    Ok(request_id)
+}
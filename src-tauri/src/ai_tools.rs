@@ -0,0 +1,305 @@
+//! Tools the AI may call while answering: capture a window and read its text, read a project
+//! file, run an allowlisted command. The model asks for a tool in a fenced `tool_call` block,
+//! the call is checked here against the same policy the frontend is held to (role, rate limit,
+//! approved project folders, command allowlist), run, and its result handed back to the model
+//! until it answers without asking for more. Everything happens in the backend, so the checks
+//! can't be skipped by a modified frontend.
+
+use regex::Regex;
+use schemars::{schema_for, JsonSchema};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::sync::OnceLock;
+use tauri::{AppHandle, Manager};
+
+use crate::ai::{ChatMessage, ChatRequest, ChatResponse, ChatRole};
+use crate::consent::{ConsentScope, ConsentState};
+use crate::project_files::ProjectRootsState;
+
+const DEFAULT_MAX_ROUNDS: u32 = 5;
+const MAX_ROUNDS: u32 = 10;
+/// Tool calls carried out per model reply; further ones are skipped
+const MAX_CALLS_PER_ROUND: usize = 4;
+/// Characters of a tool's output handed back to the model
+const MAX_OUTPUT_CHARS: usize = 12_000;
+/// Allowlisted for the frontend, but a shell in the model's hands runs whatever injected text
+/// in a capture or file tells it to
+const SHELLS: [&str; 3] = ["powershell", "pwsh", "cmd"];
+
+/// A tool the model may call
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AiTool {
+    pub name: String,
+    pub description: String,
+    /// JSON schema of the tool's arguments
+    pub parameters: Value,
+    /// The backend command whose role and rate-limit policy applies to it
+    pub command: String,
+}
+
+/// A tool call as the model wrote it
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ToolCall {
+    pub name: String,
+    #[serde(default)]
+    pub arguments: Value,
+}
+
+/// A tool call and what came of it
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ToolCallRecord {
+    pub name: String,
+    pub arguments: Value,
+    /// False when the call was refused by policy or failed
+    pub ok: bool,
+    /// The result handed back to the model: the tool's output, or the refusal
+    pub output: Value,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ToolChatResponse {
+    /// The model's final answer
+    pub response: ChatResponse,
+    /// Every tool call made on the way, in order
+    pub tool_calls: Vec<ToolCallRecord>,
+    /// Model replies it took, including the final one
+    pub rounds: u32,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+struct CaptureWindowArgs {
+    /// Part of the window title, e.g. "Visual Studio Code"
+    #[serde(default)]
+    window_title: Option<String>,
+    /// Process name, e.g. "chrome.exe"
+    #[serde(default)]
+    process_name: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+struct ReadFileArgs {
+    /// Path inside one of the student's approved project folders
+    path: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+struct RunCommandArgs {
+    /// Program to run, e.g. "git" or "npm"
+    command: String,
+    #[serde(default)]
+    args: Vec<String>,
+}
+
+fn tool<T: JsonSchema>(name: &str, command: &str, description: &str) -> AiTool {
+    let mut parameters = serde_json::to_value(schema_for!(T)).unwrap_or_default();
+    if let Some(schema) = parameters.as_object_mut() {
+        schema.remove("$schema");
+        schema.remove("title");
+    }
+    AiTool { name: name.to_string(), description: description.to_string(), parameters, command: command.to_string() }
+}
+
+/// Every tool the model can be offered
+pub(crate) fn tools() -> Vec<AiTool> {
+    vec![
+        tool::<CaptureWindowArgs>(
+            "capture_window",
+            "capture_window_with_ocr",
+            "Capture a window on the student's screen and return the text recognized in it",
+        ),
+        tool::<ReadFileArgs>("read_file", "read_project_file", "Read a text file from the student's project"),
+        tool::<RunCommandArgs>(
+            "run_command",
+            "execute_command",
+            "Run an allowlisted developer command (git, npm or docker, read-only subcommands) and return its output",
+        ),
+    ]
+}
+
+fn tool_call_block() -> &'static Regex {
+    static BLOCK: OnceLock<Regex> = OnceLock::new();
+    BLOCK.get_or_init(|| Regex::new(r"(?s)```tool_call\s*\n(.*?)```").unwrap())
+}
+
+/// Tool calls in a model reply, or why one couldn't be read
+fn parse_tool_calls(reply: &str) -> Vec<Result<ToolCall, String>> {
+    tool_call_block()
+        .captures_iter(reply)
+        .map(|captures| {
+            serde_json::from_str::<ToolCall>(captures[1].trim()).map_err(|e| format!("Malformed tool call: {}", e))
+        })
+        .collect()
+}
+
+/// How to call the offered tools, appended to the system prompt
+fn tool_instructions(tools: &[AiTool]) -> String {
+    let mut text = String::from(
+        "You can use tools. To call one, reply with a fenced block and nothing after it:\n\
+         ```tool_call\n{\"name\": \"<tool>\", \"arguments\": { ... }}\n```\n\
+         The results come back in a `tool_result` block. Answer normally once you have what you need.\n\nTools:\n",
+    );
+    for tool in tools {
+        text.push_str(&format!("- {}: {}. Arguments: {}\n", tool.name, tool.description, tool.parameters));
+    }
+    text
+}
+
+fn truncate_output(text: &str) -> String {
+    if text.chars().count() <= MAX_OUTPUT_CHARS {
+        return text.to_string();
+    }
+    let kept: String = text.chars().take(MAX_OUTPUT_CHARS).collect();
+    format!("{}\n[… output truncated]", kept)
+}
+
+fn arguments<T: DeserializeOwned>(call: &ToolCall) -> Result<T, String> {
+    let arguments = if call.arguments.is_null() { json!({}) } else { call.arguments.clone() };
+    serde_json::from_value(arguments).map_err(|e| format!("Invalid arguments for {}: {}", call.name, e))
+}
+
+async fn capture_window(app: &AppHandle, args: CaptureWindowArgs) -> Result<Value, String> {
+    if args.window_title.is_none() && args.process_name.is_none() {
+        return Err("Give a window_title or a process_name".to_string());
+    }
+    let options = crate::commands::CaptureWindowParams {
+        window_title: args.window_title,
+        process_name: args.process_name,
+        ..Default::default()
+    };
+    app.state::<ConsentState>().require(ConsentScope::ScreenAndText)?;
+    let capture = crate::commands::capture_window_with_ocr(app.clone(), options).await?;
+    Ok(json!({
+        "window_title": capture.window_title,
+        "process_name": capture.process_name,
+        "text": truncate_output(capture.ocr_text.as_deref().unwrap_or_default()),
+    }))
+}
+
+async fn read_file(app: &AppHandle, args: ReadFileArgs) -> Result<Value, String> {
+    let file = crate::project_files::read_project_file(app.state::<ProjectRootsState>(), args.path).await?;
+    Ok(json!({
+        "path": file.path,
+        "content": truncate_output(&file.content),
+        "truncated": file.truncated || file.content.chars().count() > MAX_OUTPUT_CHARS,
+    }))
+}
+
+async fn run_command(app: &AppHandle, args: RunCommandArgs) -> Result<Value, String> {
+    if SHELLS.contains(&args.command.to_lowercase().as_str()) {
+        return Err(format!("'{}' can't be run as a tool; use git, npm or docker", args.command));
+    }
+    let result = crate::commands::execute_command(app.clone(), args.command, args.args).await?;
+    if !result.success && result.exit_code.is_none() {
+        // Refused by the command policy before it ran
+        return Err(result.error.unwrap_or_else(|| "The command could not run".to_string()));
+    }
+    Ok(json!({
+        "exit_code": result.exit_code,
+        "stdout": truncate_output(&result.stdout),
+        "stderr": truncate_output(&result.stderr),
+    }))
+}
+
+/// Check `call` against the role and rate-limit policy of its backend command, then run it
+pub(crate) async fn dispatch(app: &AppHandle, call: &ToolCall, offered: &[AiTool]) -> Result<Value, String> {
+    let tool = offered
+        .iter()
+        .find(|t| t.name == call.name)
+        .ok_or_else(|| format!("Unknown tool '{}'", call.name))?;
+    app.state::<crate::roles::RolesState>().check_invoke(&tool.command)?;
+    app.state::<crate::rate_limit::RateLimiterState>().check(&tool.command).map_err(|limited| limited.to_string())?;
+    match tool.name.as_str() {
+        "capture_window" => capture_window(app, arguments(call)?).await,
+        "read_file" => read_file(app, arguments(call)?).await,
+        "run_command" => run_command(app, arguments(call)?).await,
+        other => Err(format!("Unknown tool '{}'", other)),
+    }
+}
+
+/// Carry out the tool calls in one model reply and describe their results for the model
+async fn run_round(app: &AppHandle, calls: Vec<Result<ToolCall, String>>, offered: &[AiTool]) -> (String, Vec<ToolCallRecord>) {
+    let skipped = calls.len().saturating_sub(MAX_CALLS_PER_ROUND);
+    let mut results = Vec::new();
+    let mut records = Vec::new();
+    for call in calls.into_iter().take(MAX_CALLS_PER_ROUND) {
+        let started = std::time::Instant::now();
+        let (name, arguments, outcome) = match call {
+            Ok(call) => {
+                let outcome = dispatch(app, &call, offered).await;
+                (call.name, call.arguments, outcome)
+            }
+            Err(error) => (String::new(), Value::Null, Err(error)),
+        };
+        if let Err(reason) = &outcome {
+            tracing::warn!("🛠️ AI tool call '{}' refused or failed: {}", name, reason);
+        }
+        let ok = outcome.is_ok();
+        let output = outcome.unwrap_or_else(|error| json!({ "error": error }));
+        results.push(json!({ "name": name, "result": output }));
+        records.push(ToolCallRecord { name, arguments, ok, output, duration_ms: started.elapsed().as_millis() as u64 });
+    }
+    let mut text = format!("```tool_result\n{}\n```", serde_json::to_string_pretty(&results).unwrap_or_default());
+    if skipped > 0 {
+        text.push_str(&format!("\n{} more tool call(s) were not run: at most {} run per reply.", skipped, MAX_CALLS_PER_ROUND));
+    }
+    (text, records)
+}
+
+/// The tools that may be offered, optionally narrowed to `names`
+fn offered_tools(names: Option<Vec<String>>) -> Result<Vec<AiTool>, String> {
+    let all = tools();
+    let Some(names) = names else {
+        return Ok(all);
+    };
+    if let Some(unknown) = names.iter().find(|n| !all.iter().any(|t| &t.name == *n)) {
+        return Err(format!("Unknown tool '{}'", unknown));
+    }
+    Ok(all.into_iter().filter(|t| names.contains(&t.name)).collect())
+}
+
+/// Tools the AI can be given in `ai_chat_with_tools`
+#[tauri::command]
+pub async fn list_ai_tools() -> Result<Vec<AiTool>, String> {
+    Ok(tools())
+}
+
+/// Chat with tools: each tool the model calls is checked against policy and run here, and its
+/// result returned to the model, until it answers or `max_rounds` replies have been used
+#[tauri::command]
+pub async fn ai_chat_with_tools(
+    app: AppHandle,
+    request: ChatRequest,
+    tools: Option<Vec<String>>,
+    max_rounds: Option<u32>,
+) -> Result<ToolChatResponse, String> {
+    let offered = offered_tools(tools)?;
+    let max_rounds = max_rounds.unwrap_or(DEFAULT_MAX_ROUNDS).clamp(1, MAX_ROUNDS);
+    let instructions = tool_instructions(&offered);
+    let mut request = ChatRequest {
+        system: Some(match request.system {
+            Some(system) => format!("{}\n\n{}", system, instructions),
+            None => instructions,
+        }),
+        ..request
+    };
+
+    let mut tool_calls = Vec::new();
+    let mut round = 1;
+    loop {
+        let response = crate::ai::complete(&app, &request).await?;
+        let calls = parse_tool_calls(&response.text);
+        if calls.is_empty() || offered.is_empty() {
+            return Ok(ToolChatResponse { response, tool_calls, rounds: round });
+        }
+        if round == max_rounds {
+            return Err(format!("The AI was still calling tools after {} replies", max_rounds));
+        }
+        let (results, records) = run_round(&app, calls, &offered).await;
+        tool_calls.extend(records);
+        request.messages.push(ChatMessage { role: ChatRole::Assistant, content: response.text });
+        request.messages.push(ChatMessage { role: ChatRole::User, content: results });
+        round += 1;
+    }
+}
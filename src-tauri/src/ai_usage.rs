@@ -0,0 +1,306 @@
+//! Token use and estimated cost of every AI request, kept in the history database. Costs come
+//! from a configurable price list per model; providers that don't report token counts are
+//! estimated. An optional monthly budget blocks further paid requests once it is used up and
+//! emits `budget-exceeded` when a request crosses it. Local Ollama and llama.cpp models are free.
+
+use chrono::{Datelike, Local, TimeZone};
+use rusqlite::params;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager, State};
+
+use crate::ai::{ChatRequest, ProviderConfig, TokenUsage};
+use crate::history::HistoryState;
+use crate::persistence;
+
+const SETTINGS_FILE: &str = "ai_budget.json";
+
+/// AI usage table, applied by the history database's migrations
+pub(crate) const SCHEMA: &str = r#"
+CREATE TABLE ai_usage (
+    id INTEGER PRIMARY KEY,
+    provider_id TEXT NOT NULL,
+    model TEXT NOT NULL,
+    purpose TEXT NOT NULL,
+    input_tokens INTEGER NOT NULL,
+    output_tokens INTEGER NOT NULL,
+    estimated INTEGER NOT NULL,
+    cost_usd REAL NOT NULL,
+    created_at INTEGER NOT NULL
+);
+CREATE INDEX ai_usage_created ON ai_usage(created_at);
+"#;
+
+/// Price of a model family in US dollars per million tokens
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ModelPrice {
+    /// Matches models starting with this; the longest matching prefix wins
+    pub model_prefix: String,
+    pub input_per_million: f64,
+    pub output_per_million: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct AiBudgetSettings {
+    /// Spending allowed per calendar month; None means no limit
+    pub monthly_limit_usd: Option<f64>,
+    pub prices: Vec<ModelPrice>,
+}
+
+impl Default for AiBudgetSettings {
+    fn default() -> Self {
+        let price = |prefix: &str, input, output| ModelPrice {
+            model_prefix: prefix.to_string(),
+            input_per_million: input,
+            output_per_million: output,
+        };
+        Self {
+            monthly_limit_usd: None,
+            prices: vec![
+                price("gemini", 0.30, 2.50),
+                price("gpt-4o-mini", 0.15, 0.60),
+                price("gpt-4o", 2.50, 10.00),
+                price("claude-3-5-haiku", 0.80, 4.00),
+                price("claude", 3.00, 15.00),
+            ],
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct AiUsageState {
+    settings: Arc<Mutex<AiBudgetSettings>>,
+}
+
+/// Payload of the `budget-exceeded` event
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct BudgetExceeded {
+    pub month_to_date_usd: f64,
+    pub monthly_limit_usd: f64,
+    /// The provider whose request crossed the limit
+    pub provider_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct UsageRange {
+    /// Unix timestamp (seconds), defaults to the start of the current month
+    #[serde(default)]
+    pub from: Option<i64>,
+    /// Unix timestamp (seconds), defaults to now
+    #[serde(default)]
+    pub to: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ModelUsage {
+    pub provider_id: String,
+    pub model: String,
+    pub requests: u32,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cost_usd: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AiUsageReport {
+    pub from: i64,
+    pub to: i64,
+    pub requests: u32,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cost_usd: f64,
+    /// Requests whose token counts were estimated rather than reported
+    pub estimated_requests: u32,
+    /// Most expensive first
+    pub by_model: Vec<ModelUsage>,
+    pub month_to_date_usd: f64,
+    pub monthly_limit_usd: Option<f64>,
+}
+
+fn month_start() -> i64 {
+    let now = Local::now();
+    Local
+        .with_ymd_and_hms(now.year(), now.month(), 1, 0, 0, 0)
+        .earliest()
+        .map(|start| start.timestamp())
+        .unwrap_or_else(|| now.timestamp())
+}
+
+fn price_for<'a>(settings: &'a AiBudgetSettings, model: &str) -> Option<&'a ModelPrice> {
+    settings
+        .prices
+        .iter()
+        .filter(|p| model.starts_with(&p.model_prefix))
+        .max_by_key(|p| p.model_prefix.len())
+}
+
+fn month_to_date(history: &HistoryState) -> Result<f64, String> {
+    history.with_db(|db| {
+        db.query_row("SELECT COALESCE(SUM(cost_usd), 0) FROM ai_usage WHERE created_at >= ?1", [month_start()], |row| {
+            row.get(0)
+        })
+    })
+}
+
+pub fn init(app: &AppHandle) {
+    let settings: AiBudgetSettings = persistence::data_file(app, SETTINGS_FILE)
+        .map(|path| persistence::load_json(&path))
+        .unwrap_or_default();
+    *app.state::<AiUsageState>().settings.lock().unwrap() = settings;
+}
+
+/// Refuse a paid request once this month's budget is used up
+pub(crate) fn check_budget(app: &AppHandle, provider: &ProviderConfig) -> Result<(), String> {
+    if provider.kind.is_local() {
+        return Ok(());
+    }
+    let Some(limit) = app.state::<AiUsageState>().settings.lock().unwrap().monthly_limit_usd else {
+        return Ok(());
+    };
+    let spent = month_to_date(&app.state::<HistoryState>())?;
+    if spent >= limit {
+        return Err(format!("The monthly AI budget of ${:.2} is used up (${:.2} spent)", limit, spent));
+    }
+    Ok(())
+}
+
+/// Store a finished request's tokens and cost, estimating tokens the provider didn't report
+pub(crate) fn record(
+    app: &AppHandle,
+    provider: &ProviderConfig,
+    purpose: &str,
+    request: &ChatRequest,
+    reply: &str,
+    usage: Option<TokenUsage>,
+) {
+    let estimated = usage.is_none();
+    let usage = usage.unwrap_or_else(|| {
+        let prompt: String = request.system.iter().chain(request.messages.iter().map(|m| &m.content)).cloned().collect();
+        TokenUsage {
+            input_tokens: crate::context_budget::estimate_tokens(&prompt, &provider.model),
+            output_tokens: crate::context_budget::estimate_tokens(reply, &provider.model),
+        }
+    });
+    let settings = app.state::<AiUsageState>().settings.lock().unwrap().clone();
+    let cost = match price_for(&settings, &provider.model) {
+        Some(price) if !provider.kind.is_local() => {
+            (usage.input_tokens as f64 * price.input_per_million + usage.output_tokens as f64 * price.output_per_million)
+                / 1_000_000.0
+        }
+        _ => 0.0,
+    };
+
+    let history = app.state::<HistoryState>();
+    let before = month_to_date(&history).unwrap_or_default();
+    let stored = history.with_db(|db| {
+        db.execute(
+            "INSERT INTO ai_usage (provider_id, model, purpose, input_tokens, output_tokens, estimated, cost_usd, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                provider.id,
+                provider.model,
+                purpose,
+                usage.input_tokens,
+                usage.output_tokens,
+                estimated,
+                cost,
+                chrono::Utc::now().timestamp()
+            ],
+        )
+    });
+    if let Err(e) = stored {
+        tracing::warn!("⚠️ Failed to record AI usage: {}", e);
+        return;
+    }
+    if let Some(limit) = settings.monthly_limit_usd {
+        if before < limit && before + cost >= limit {
+            tracing::warn!("💸 Monthly AI budget of ${:.2} reached", limit);
+            let _ = crate::event_bus::emit(
+                app,
+                crate::events::BUDGET_EXCEEDED,
+                BudgetExceeded { month_to_date_usd: before + cost, monthly_limit_usd: limit, provider_id: provider.id.clone() },
+            );
+        }
+    }
+}
+
+/// Requests, tokens and estimated cost in `range`, per model, with this month's spending
+#[tauri::command]
+pub async fn get_ai_usage(
+    history: State<'_, HistoryState>,
+    state: State<'_, AiUsageState>,
+    range: Option<UsageRange>,
+) -> Result<AiUsageReport, String> {
+    let to = range.as_ref().and_then(|r| r.to).unwrap_or_else(|| chrono::Utc::now().timestamp());
+    let from = range.as_ref().and_then(|r| r.from).unwrap_or_else(month_start);
+    if from > to {
+        return Err("Range start must be before its end".to_string());
+    }
+    let (by_model, estimated_requests) = history.with_db(|db| {
+        let by_model: Vec<ModelUsage> = db
+            .prepare(
+                "SELECT provider_id, model, COUNT(*), SUM(input_tokens), SUM(output_tokens), SUM(cost_usd) FROM ai_usage
+                 WHERE created_at BETWEEN ?1 AND ?2 GROUP BY provider_id, model ORDER BY SUM(cost_usd) DESC, COUNT(*) DESC",
+            )?
+            .query_map([from, to], |row| {
+                Ok(ModelUsage {
+                    provider_id: row.get(0)?,
+                    model: row.get(1)?,
+                    requests: row.get(2)?,
+                    input_tokens: row.get(3)?,
+                    output_tokens: row.get(4)?,
+                    cost_usd: row.get(5)?,
+                })
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+        let estimated: u32 = db.query_row(
+            "SELECT COUNT(*) FROM ai_usage WHERE created_at BETWEEN ?1 AND ?2 AND estimated",
+            [from, to],
+            |row| row.get(0),
+        )?;
+        Ok((by_model, estimated))
+    })?;
+    Ok(AiUsageReport {
+        from,
+        to,
+        requests: by_model.iter().map(|m| m.requests).sum(),
+        input_tokens: by_model.iter().map(|m| m.input_tokens).sum(),
+        output_tokens: by_model.iter().map(|m| m.output_tokens).sum(),
+        cost_usd: by_model.iter().map(|m| m.cost_usd).sum(),
+        estimated_requests,
+        by_model,
+        month_to_date_usd: month_to_date(&history)?,
+        monthly_limit_usd: state.settings.lock().unwrap().monthly_limit_usd,
+    })
+}
+
+#[tauri::command]
+pub async fn get_ai_budget(state: State<'_, AiUsageState>) -> Result<AiBudgetSettings, String> {
+    Ok(state.settings.lock().unwrap().clone())
+}
+
+/// Change the monthly limit and model prices (teacher mode only)
+#[tauri::command]
+pub async fn set_ai_budget(
+    app: AppHandle,
+    state: State<'_, AiUsageState>,
+    settings: AiBudgetSettings,
+) -> Result<(), String> {
+    if settings.monthly_limit_usd.is_some_and(|limit| !limit.is_finite() || limit < 0.0) {
+        return Err("The monthly limit must be zero or more".to_string());
+    }
+    let valid_price = |p: &ModelPrice| {
+        !p.model_prefix.trim().is_empty()
+            && [p.input_per_million, p.output_per_million].iter().all(|v| v.is_finite() && *v >= 0.0)
+    };
+    if !settings.prices.iter().all(valid_price) {
+        return Err("Prices need a model prefix and non-negative amounts".to_string());
+    }
+    let path = persistence::data_file(&app, SETTINGS_FILE)?;
+    persistence::save_json(&path, &settings)?;
+    tracing::info!("💸 AI budget set to {:?} per month", settings.monthly_limit_usd);
+    *state.settings.lock().unwrap() = settings;
+    Ok(())
+}
@@ -0,0 +1,206 @@
+//! Timings of the capture and OCR pipeline on this machine, so a change to it (the native
+//! helper, a new capture backend) can be judged by numbers rather than feel. Each stage runs
+//! `iterations` times and reports percentiles; a stage that fails is reported with its error
+//! and the others still run.
+
+use base64::{engine::general_purpose, Engine as _};
+use image::ImageEncoder;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::time::Instant;
+use tauri::{AppHandle, Manager};
+
+use crate::cancellation::{CancellationState, OperationGuard};
+use crate::capture_backend::{CaptureBackend, CaptureBackendState};
+use crate::commands::CaptureWindowParams;
+use crate::ocr_cache::OcrCacheState;
+use crate::screen_capture::ScreenCapture;
+
+const DEFAULT_ITERATIONS: u32 = 5;
+const MAX_ITERATIONS: u32 = 50;
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct StageTimings {
+    /// "capture", "encode", "ocr" or "capture_window_with_ocr"
+    pub stage: String,
+    /// Runs that succeeded
+    pub samples: u32,
+    pub min_ms: f64,
+    pub p50_ms: f64,
+    pub p90_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+    pub mean_ms: f64,
+    /// Why the stage stopped early, if it did
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct BenchmarkReport {
+    pub iterations: u32,
+    pub platform: String,
+    pub capture_backend: CaptureBackend,
+    /// Whether OCR and window capture went through the native helper rather than PowerShell
+    pub native_helper: bool,
+    /// Size of the full-screen capture the encode and OCR stages work on
+    pub image_width: Option<u32>,
+    pub image_height: Option<u32>,
+    pub stages: Vec<StageTimings>,
+    pub started_at: i64,
+    pub duration_ms: u64,
+}
+
+/// Nearest-rank percentile of sorted samples
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+fn summarize(stage: &str, mut samples: Vec<f64>, error: Option<String>) -> StageTimings {
+    samples.sort_by(f64::total_cmp);
+    let round = |ms: f64| (ms * 100.0).round() / 100.0;
+    let mean = if samples.is_empty() { 0.0 } else { samples.iter().sum::<f64>() / samples.len() as f64 };
+    StageTimings {
+        stage: stage.to_string(),
+        samples: samples.len() as u32,
+        min_ms: round(samples.first().copied().unwrap_or_default()),
+        p50_ms: round(percentile(&samples, 50.0)),
+        p90_ms: round(percentile(&samples, 90.0)),
+        p99_ms: round(percentile(&samples, 99.0)),
+        max_ms: round(samples.last().copied().unwrap_or_default()),
+        mean_ms: round(mean),
+        error,
+    }
+}
+
+/// Time `run` `iterations` times, stopping at its first error. Also returns the last result.
+async fn time_stage<F, Fut, T>(
+    stage: &str,
+    iterations: u32,
+    operation: &OperationGuard,
+    mut run: F,
+) -> Result<(StageTimings, Option<T>), String>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, String>>,
+{
+    let mut samples = Vec::with_capacity(iterations as usize);
+    let mut error = None;
+    let mut last = None;
+    for _ in 0..iterations {
+        operation.check()?;
+        let started = Instant::now();
+        match run().await {
+            Ok(result) => {
+                samples.push(started.elapsed().as_secs_f64() * 1000.0);
+                last = Some(result);
+            }
+            Err(e) => {
+                error = Some(e);
+                break;
+            }
+        }
+    }
+    operation.check()?;
+    Ok((summarize(stage, samples, error), last))
+}
+
+fn encode_png(image: &image::RgbaImage) -> Result<Vec<u8>, String> {
+    let mut png = Vec::new();
+    image::codecs::png::PngEncoder::new(&mut png)
+        .write_image(image, image.width(), image.height(), image::ExtendedColorType::Rgba8)
+        .map_err(|e| format!("Failed to encode PNG: {}", e))?;
+    Ok(png)
+}
+
+/// The app's own window: always there to capture, whatever else is open
+fn own_window() -> CaptureWindowParams {
+    let process_name = std::env::current_exe()
+        .ok()
+        .and_then(|path| path.file_stem().map(|stem| stem.to_string_lossy().into_owned()));
+    CaptureWindowParams { process_name, ..Default::default() }
+}
+
+/// Time full-screen capture, PNG encoding, OCR and `capture_window_with_ocr` (on the app's
+/// own window), `iterations` times each. Takes a while; cancel it with `request_id`.
+#[tauri::command]
+pub async fn run_benchmark(
+    app: AppHandle,
+    iterations: Option<u32>,
+    request_id: Option<String>,
+) -> Result<BenchmarkReport, String> {
+    let iterations = iterations.unwrap_or(DEFAULT_ITERATIONS).clamp(1, MAX_ITERATIONS);
+    let operation = app.state::<CancellationState>().register(request_id.as_deref());
+    let started_at = chrono::Utc::now().timestamp_millis();
+    let started = Instant::now();
+    tracing::info!("⏱️ Benchmarking the capture pipeline ({} iterations)", iterations);
+
+    let mut stages = Vec::new();
+    let (timings, capture) = time_stage("capture", iterations, &operation, || async {
+        ScreenCapture::new().capture_full_screen(&app).await
+    })
+    .await?;
+    stages.push(timings);
+    let last_capture = capture.map(|capture| capture.image_base64);
+
+    // Encoding and OCR work on the last capture, so capture time doesn't count towards them
+    let decoded = last_capture.as_deref().map(|image_base64| {
+        general_purpose::STANDARD
+            .decode(image_base64)
+            .map_err(|e| format!("Failed to decode the capture: {}", e))
+            .and_then(|png| image::load_from_memory(&png).map_err(|e| format!("Failed to decode the capture: {}", e)))
+            .map(|image| image.to_rgba8())
+    });
+    let (image_width, image_height) = match &decoded {
+        Some(Ok(image)) => (Some(image.width()), Some(image.height())),
+        _ => (None, None),
+    };
+    // Every run has to do the OCR, not find it in the cache
+    let ocr_cache = app.state::<OcrCacheState>();
+    let no_capture = || "No capture to work on".to_string();
+    match &decoded {
+        Some(Ok(image)) => stages.push(time_stage("encode", iterations, &operation, || async { encode_png(image) }).await?.0),
+        Some(Err(e)) => stages.push(summarize("encode", Vec::new(), Some(e.clone()))),
+        None => stages.push(summarize("encode", Vec::new(), Some(no_capture()))),
+    }
+    match &last_capture {
+        Some(image_base64) => stages.push(
+            time_stage("ocr", iterations, &operation, || {
+                ocr_cache.clear();
+                crate::commands::ocr_image(&app, image_base64.clone(), &operation)
+            })
+            .await?
+            .0,
+        ),
+        None => stages.push(summarize("ocr", Vec::new(), Some(no_capture()))),
+    }
+
+    stages.push(
+        time_stage("capture_window_with_ocr", iterations, &operation, || {
+            ocr_cache.clear();
+            crate::commands::capture_window_with_ocr(app.clone(), own_window())
+        })
+        .await?
+        .0,
+    );
+
+    let report = BenchmarkReport {
+        iterations,
+        platform: std::env::consts::OS.to_string(),
+        capture_backend: app.state::<CaptureBackendState>().get(),
+        native_helper: crate::native_helper::in_use(&app),
+        image_width,
+        image_height,
+        stages,
+        started_at,
+        duration_ms: started.elapsed().as_millis() as u64,
+    };
+    for stage in &report.stages {
+        tracing::info!("⏱️ {}: p50 {} ms, p90 {} ms over {} runs", stage.stage, stage.p50_ms, stage.p90_ms, stage.samples);
+    }
+    Ok(report)
+}
@@ -0,0 +1,275 @@
+//! TypeScript bindings for every command and event, generated from the same
+//! schemas that back `list_available_commands` and from the event list in
+//! `events.rs`. Debug builds regenerate `src/types/bindings.ts` on startup and
+//! `npm run bindings` writes it without starting the app, so the frontend types
+//! always match the Rust structs (including their exact snake_case field names).
+
+use schemars::schema::{InstanceType, ObjectValidation, Schema, SchemaObject, SingleOrVec};
+use std::fs;
+use std::path::Path;
+
+use crate::command_catalog::CommandMetadata;
+
+fn union(types: impl IntoIterator<Item = String>) -> String {
+    let mut members: Vec<String> = Vec::new();
+    for t in types {
+        if !members.contains(&t) {
+            members.push(t);
+        }
+    }
+    match members.len() {
+        0 => "never".to_string(),
+        _ => members.join(" | "),
+    }
+}
+
+fn array_of(element: String) -> String {
+    if element.contains(' ') {
+        format!("({})[]", element)
+    } else {
+        format!("{}[]", element)
+    }
+}
+
+fn property_key(name: &str) -> String {
+    let is_identifier = name.chars().enumerate().all(|(i, c)| {
+        c == '_' || c == '$' || c.is_ascii_alphabetic() || (i > 0 && c.is_ascii_digit())
+    });
+    if is_identifier && !name.is_empty() {
+        name.to_string()
+    } else {
+        format!("{:?}", name)
+    }
+}
+
+fn object_literal(object: &ObjectValidation) -> String {
+    if object.properties.is_empty() {
+        return match &object.additional_properties {
+            Some(schema) => format!("Record<string, {}>", ts_type(schema)),
+            None => "Record<string, unknown>".to_string(),
+        };
+    }
+    let fields: Vec<String> = object
+        .properties
+        .iter()
+        .map(|(name, schema)| {
+            let optional = if object.required.contains(name) { "" } else { "?" };
+            format!("{}{}: {}", property_key(name), optional, ts_type(schema))
+        })
+        .collect();
+    format!("{{ {} }}", fields.join("; "))
+}
+
+fn instance_type(obj: &SchemaObject, instance: InstanceType) -> String {
+    match instance {
+        InstanceType::Null => "null".to_string(),
+        InstanceType::Boolean => "boolean".to_string(),
+        InstanceType::Integer | InstanceType::Number => "number".to_string(),
+        InstanceType::String => "string".to_string(),
+        InstanceType::Array => match obj.array.as_ref().and_then(|a| a.items.as_ref()) {
+            Some(SingleOrVec::Single(item)) => array_of(ts_type(item)),
+            Some(SingleOrVec::Vec(items)) => {
+                format!("[{}]", items.iter().map(ts_type).collect::<Vec<_>>().join(", "))
+            }
+            None => "unknown[]".to_string(),
+        },
+        InstanceType::Object => match &obj.object {
+            Some(object) => object_literal(object),
+            None => "Record<string, unknown>".to_string(),
+        },
+    }
+}
+
+/// TypeScript type expression for a JSON schema
+pub fn ts_type(schema: &Schema) -> String {
+    let obj = match schema {
+        Schema::Bool(true) => return "unknown".to_string(),
+        Schema::Bool(false) => return "never".to_string(),
+        Schema::Object(obj) => obj,
+    };
+
+    if let Some(reference) = &obj.reference {
+        return reference.rsplit('/').next().unwrap_or(reference).to_string();
+    }
+    if let Some(values) = &obj.enum_values {
+        return union(values.iter().map(|v| v.to_string()));
+    }
+    if let Some(value) = &obj.const_value {
+        return value.to_string();
+    }
+    if let Some(subschemas) = &obj.subschemas {
+        if let Some(all_of) = &subschemas.all_of {
+            return all_of.iter().map(ts_type).collect::<Vec<_>>().join(" & ");
+        }
+        if let Some(variants) = subschemas.any_of.as_ref().or(subschemas.one_of.as_ref()) {
+            return union(variants.iter().map(ts_type));
+        }
+    }
+
+    match &obj.instance_type {
+        Some(SingleOrVec::Single(instance)) => instance_type(obj, **instance),
+        Some(SingleOrVec::Vec(instances)) => union(instances.iter().map(|i| instance_type(obj, *i))),
+        None if obj.object.is_some() => instance_type(obj, InstanceType::Object),
+        None => "unknown".to_string(),
+    }
+}
+
+/// Whether a schema accepts `null`, i.e. the value is an `Option`
+pub fn allows_null(schema: &Schema) -> bool {
+    match schema {
+        Schema::Bool(accepts_all) => *accepts_all,
+        Schema::Object(obj) => {
+            let null_type = match &obj.instance_type {
+                Some(SingleOrVec::Single(instance)) => **instance == InstanceType::Null,
+                Some(SingleOrVec::Vec(instances)) => instances.contains(&InstanceType::Null),
+                None => false,
+            };
+            let null_variant = obj
+                .subschemas
+                .as_ref()
+                .and_then(|s| s.any_of.as_ref())
+                .map(|variants| variants.iter().any(allows_null))
+                .unwrap_or(false);
+            null_type || null_variant
+        }
+    }
+}
+
+fn doc_comment(indent: &str, text: Option<&str>) -> String {
+    match text {
+        Some(text) if !text.trim().is_empty() => {
+            format!("{}/** {} */\n", indent, text.trim().replace('\n', " "))
+        }
+        _ => String::new(),
+    }
+}
+
+fn render_definition(name: &str, schema: &Schema) -> String {
+    let description = match schema {
+        Schema::Object(obj) => obj.metadata.as_ref().and_then(|m| m.description.as_deref()),
+        Schema::Bool(_) => None,
+    };
+    let mut out = doc_comment("", description);
+
+    let interface_fields = match schema {
+        Schema::Object(obj) if obj.subschemas.is_none() && obj.reference.is_none() => {
+            obj.object.as_ref().filter(|o| !o.properties.is_empty())
+        }
+        _ => None,
+    };
+
+    match interface_fields {
+        Some(object) => {
+            out.push_str(&format!("export interface {} {{\n", name));
+            for (field, field_schema) in &object.properties {
+                let field_description = match field_schema {
+                    Schema::Object(obj) => obj.metadata.as_ref().and_then(|m| m.description.as_deref()),
+                    Schema::Bool(_) => None,
+                };
+                out.push_str(&doc_comment("  ", field_description));
+                let optional = if object.required.contains(field) { "" } else { "?" };
+                out.push_str(&format!("  {}{}: {};\n", property_key(field), optional, ts_type(field_schema)));
+            }
+            out.push_str("}\n");
+        }
+        None => out.push_str(&format!("export type {} = {};\n", name, ts_type(schema))),
+    }
+    out
+}
+
+fn render_command(command: &CommandMetadata) -> String {
+    let params: Vec<String> = command
+        .params
+        .iter()
+        .map(|p| format!("{}: {}", p.name, p.type_name))
+        .collect();
+    let args = if command.params.is_empty() {
+        String::new()
+    } else {
+        let keys: Vec<&str> = command.params.iter().map(|p| p.name.as_str()).collect();
+        format!(", {{ {} }}", keys.join(", "))
+    };
+    format!(
+        "{}  {}: ({}) => invoke<{}>(\"{}\"{}),\n",
+        doc_comment("  ", Some(command.description)),
+        crate::command_catalog::to_camel_case(command.name),
+        params.join(", "),
+        command.returns,
+        command.name,
+        args
+    )
+}
+
+/// Property key for an event: its quoted name, or for scoped channels like `screen-changed:{id}`
+/// a template literal index signature (`` [channel: `screen-changed:${string}`] ``) so each
+/// instance is typed
+fn event_key(event: &str) -> String {
+    if !event.contains('{') {
+        return format!("{:?}", event);
+    }
+    let mut pattern = String::new();
+    let mut in_placeholder = false;
+    for c in event.chars() {
+        match c {
+            '{' => {
+                in_placeholder = true;
+                pattern.push_str("${string}");
+            }
+            '}' => in_placeholder = false,
+            _ if !in_placeholder => pattern.push(c),
+            _ => {}
+        }
+    }
+    format!("[channel: `{}`]", pattern)
+}
+
+/// Render the complete bindings file
+pub fn render() -> String {
+    let mut gen = crate::command_catalog::schema_generator();
+    let commands = crate::command_catalog::catalog_with(&mut gen);
+    let events = crate::events::catalog(&mut gen);
+
+    let mut out = String::new();
+    out.push_str("// This file is generated by src-tauri/src/bindings.rs when the app starts in debug mode.\n");
+    out.push_str("// Do not edit it by hand; change the Rust types instead.\n\n");
+    out.push_str("import { invoke } from \"@tauri-apps/api/core\";\n");
+    out.push_str("import { listen, type UnlistenFn } from \"@tauri-apps/api/event\";\n\n");
+
+    for (name, schema) in gen.definitions() {
+        out.push_str(&render_definition(name, schema));
+        out.push('\n');
+    }
+
+    out.push_str("export const commands = {\n");
+    for command in &commands {
+        out.push_str(&render_command(command));
+    }
+    out.push_str("};\n\n");
+
+    out.push_str("export interface EventPayloads {\n");
+    for (event, payload) in &events {
+        out.push_str(&format!("  {}: {};\n", event_key(event), payload));
+    }
+    out.push_str("}\n\n");
+    // Object payloads carry the `seq` added by `event_bus::emit`; the last one seen survives a
+    // webview reload in session storage, for `get_missed_events`
+    out.push_str(
+        "/** Sequence number of the latest event seen, to pass to `getMissedEvents` after a reload */\n\
+         export let lastEventSeq = Number(sessionStorage.getItem(\"lastEventSeq\") ?? 0);\n\n",
+    );
+    out.push_str(
+        "export function listenEvent<K extends keyof EventPayloads>(\n  event: K,\n  handler: (payload: EventPayloads[K]) => void,\n): Promise<UnlistenFn> {\n  return listen<EventPayloads[K]>(event, (e) => {\n    const seq = (e.payload as { seq?: number } | null)?.seq;\n    if (typeof seq === \"number\" && seq > lastEventSeq) {\n      lastEventSeq = seq;\n      sessionStorage.setItem(\"lastEventSeq\", String(seq));\n    }\n    handler(e.payload);\n  });\n}\n",
+    );
+    out
+}
+
+/// Write the bindings file, leaving it untouched when nothing changed so Vite doesn't reload.
+/// Returns whether it was written.
+pub fn export(path: &Path) -> Result<bool, String> {
+    let contents = render();
+    if fs::read_to_string(path).map(|existing| existing == contents).unwrap_or(false) {
+        return Ok(false);
+    }
+    fs::write(path, contents).map_err(|e| format!("Failed to write bindings to {}: {}", path.display(), e))?;
+    Ok(true)
+}
@@ -0,0 +1,142 @@
+//! Registry of in-flight capture/OCR operations keyed by the frontend's request id,
+//! so `cancel_operation` can stop the work (and kill its PowerShell process).
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager, State};
+
+use crate::request_queue::RequestQueueState;
+
+pub const CANCELLED: &str = "Operation cancelled";
+
+#[derive(Default)]
+struct Operation {
+    cancelled: AtomicBool,
+    /// External process doing the work right now
+    pid: Mutex<Option<u32>>,
+}
+
+#[derive(Clone, Default)]
+pub struct CancellationState {
+    operations: Arc<Mutex<HashMap<String, Arc<Operation>>>>,
+}
+
+/// Handle to a registered operation; unregisters itself when dropped
+pub struct OperationGuard {
+    state: CancellationState,
+    request_id: Option<String>,
+    operation: Arc<Operation>,
+}
+
+impl OperationGuard {
+    pub fn check(&self) -> Result<(), String> {
+        if self.operation.cancelled.load(Ordering::SeqCst) {
+            Err(CANCELLED.to_string())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Record the process doing the work so cancelling kills it. Kills it straight
+    /// away if the operation was cancelled while the process was starting.
+    pub fn attach(&self, pid: u32) {
+        *self.operation.pid.lock().unwrap() = Some(pid);
+        if self.operation.cancelled.load(Ordering::SeqCst) {
+            kill_and_log(pid);
+        }
+    }
+
+    pub fn detach(&self) {
+        *self.operation.pid.lock().unwrap() = None;
+    }
+
+    /// Whether the operation was cancelled, for blocking work that can't borrow the guard
+    /// (a library's abort callback, say)
+    pub fn cancelled_flag(&self) -> impl Fn() -> bool + Send + Sync + 'static {
+        let operation = self.operation.clone();
+        move || operation.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+impl Drop for OperationGuard {
+    fn drop(&mut self) {
+        if let Some(id) = &self.request_id {
+            let mut operations = self.state.operations.lock().unwrap();
+            if operations.get(id).is_some_and(|op| Arc::ptr_eq(op, &self.operation)) {
+                operations.remove(id);
+            }
+        }
+    }
+}
+
+impl CancellationState {
+    /// Track an operation. Without a request id it can't be cancelled, but callers
+    /// still get a guard so the code path is the same.
+    pub fn register(&self, request_id: Option<&str>) -> OperationGuard {
+        let operation = Arc::new(Operation::default());
+        if let Some(id) = request_id {
+            self.operations.lock().unwrap().insert(id.to_string(), operation.clone());
+        }
+        OperationGuard {
+            state: self.clone(),
+            request_id: request_id.map(String::from),
+            operation,
+        }
+    }
+
+    fn cancel(&self, request_id: &str) -> bool {
+        let Some(operation) = self.operations.lock().unwrap().get(request_id).cloned() else {
+            return false;
+        };
+        operation.cancelled.store(true, Ordering::SeqCst);
+        if let Some(pid) = operation.pid.lock().unwrap().take() {
+            kill_and_log(pid);
+        }
+        true
+    }
+}
+
+/// Kill `pid` and, on Windows, every process it started
+pub(crate) fn kill_process_tree(pid: u32) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    let result = std::process::Command::new("taskkill")
+        .args(["/PID", &pid.to_string(), "/T", "/F"])
+        .output();
+    #[cfg(not(target_os = "windows"))]
+    let result = std::process::Command::new("kill")
+        .args(["-9", &pid.to_string()])
+        .output();
+
+    match result {
+        Ok(output) if output.status.success() => Ok(()),
+        Ok(output) => Err(format!(
+            "Failed to kill process {}: {}",
+            pid,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )),
+        Err(e) => Err(format!("Failed to kill process {}: {}", pid, e)),
+    }
+}
+
+fn kill_and_log(pid: u32) {
+    match kill_process_tree(pid) {
+        Ok(()) => tracing::info!("🛑 Killed process {}", pid),
+        Err(e) => tracing::warn!("⚠️ {}", e),
+    }
+}
+
+/// Abort an in-flight capture/OCR operation, or drop a queued AI request, by request id
+#[tauri::command]
+pub async fn cancel_operation(
+    app: AppHandle,
+    state: State<'_, CancellationState>,
+    request_id: String,
+) -> Result<bool, String> {
+    let cancelled = state.cancel(&request_id);
+    let dequeued = crate::request_queue::remove(&app, &app.state::<RequestQueueState>(), &request_id)?;
+    if cancelled || dequeued {
+        tracing::info!("🛑 Cancelled {}", request_id);
+    }
+    Ok(cancelled || dequeued)
+}
@@ -0,0 +1,219 @@
+//! Window capture backend selection. PrintWindow can't render many GPU-composited windows
+//! (Chrome, Electron apps, games); Windows.Graphics.Capture reads the window straight from DWM.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager, State};
+
+use crate::persistence;
+#[cfg(target_os = "windows")]
+use crate::powershell_host::PowerShellHostState;
+
+const SETTINGS_FILE: &str = "capture_settings.json";
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CaptureBackend {
+    /// PrintWindow, switching to Windows.Graphics.Capture when it returns a blank frame
+    #[default]
+    Auto,
+    PrintWindow,
+    /// Windows.Graphics.Capture first, PrintWindow when it's unavailable or fails
+    GraphicsCapture,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct CaptureSettings {
+    backend: CaptureBackend,
+}
+
+#[derive(Clone, Default)]
+pub struct CaptureBackendState {
+    backend: Arc<Mutex<CaptureBackend>>,
+}
+
+impl CaptureBackendState {
+    pub fn get(&self) -> CaptureBackend {
+        *self.backend.lock().unwrap()
+    }
+}
+
+/// Load the saved backend choice
+pub fn init(app: &AppHandle) {
+    let settings: CaptureSettings = persistence::data_file(app, SETTINGS_FILE)
+        .map(|path| persistence::load_json(&path))
+        .unwrap_or_default();
+    *app.state::<CaptureBackendState>().backend.lock().unwrap() = settings.backend;
+}
+
+/// Defines the `GraphicsCapture` class. Its source never changes, so a warm host compiles it
+/// once and later captures reuse it; the call itself is appended per capture.
+#[cfg(target_os = "windows")]
+const GRAPHICS_CAPTURE_SCRIPT: &str = r#"
+    $ErrorActionPreference = 'Stop'
+    $winmd = Join-Path $env:windir 'System32\WinMetadata'
+    $references = @(
+        [System.Reflection.Assembly]::Load('System.Runtime, Version=4.0.0.0, Culture=neutral, PublicKeyToken=b03f5f7f11d50a3a').Location,
+        [System.Reflection.Assembly]::Load('System.Runtime.WindowsRuntime, Version=4.0.0.0, Culture=neutral, PublicKeyToken=b77a5c561934e089').Location,
+        (Join-Path $winmd 'Windows.Foundation.winmd'),
+        (Join-Path $winmd 'Windows.Graphics.winmd'),
+        (Join-Path $winmd 'Windows.Storage.winmd')
+    )
+    Add-Type -ReferencedAssemblies $references @"
+        using System;
+        using System.IO;
+        using System.Runtime.InteropServices;
+        using System.Threading;
+        using Windows.Graphics.Capture;
+        using Windows.Graphics.DirectX;
+        using Windows.Graphics.DirectX.Direct3D11;
+        using Windows.Graphics.Imaging;
+        using Windows.Storage.Streams;
+
+        public static class GraphicsCapture {
+            [ComImport, Guid("3628E81B-3CAC-4C60-B7F4-23CE0E0C3356"), InterfaceType(ComInterfaceType.InterfaceIsIUnknown)]
+            interface IGraphicsCaptureItemInterop {
+                IntPtr CreateForWindow(IntPtr window, [In] ref Guid iid);
+                IntPtr CreateForMonitor(IntPtr monitor, [In] ref Guid iid);
+            }
+
+            [DllImport("d3d11.dll")]
+            static extern int D3D11CreateDevice(IntPtr adapter, int driverType, IntPtr software, uint flags, IntPtr featureLevels, uint featureLevelCount, uint sdkVersion, out IntPtr device, out int featureLevel, out IntPtr context);
+            [DllImport("d3d11.dll")]
+            static extern int CreateDirect3D11DeviceFromDXGIDevice(IntPtr dxgiDevice, out IntPtr graphicsDevice);
+            [DllImport("combase.dll", CharSet = CharSet.Unicode)]
+            static extern int WindowsCreateString(string source, int length, out IntPtr hstring);
+            [DllImport("combase.dll")]
+            static extern int WindowsDeleteString(IntPtr hstring);
+            [DllImport("combase.dll")]
+            static extern int RoGetActivationFactory(IntPtr classId, [In] ref Guid iid, out IntPtr factory);
+
+            const int D3D_DRIVER_TYPE_HARDWARE = 1;
+            const uint D3D11_CREATE_DEVICE_BGRA_SUPPORT = 0x20;
+            const uint D3D11_SDK_VERSION = 7;
+            static readonly Guid IID_IDXGIDevice = new Guid("54EC77FA-1377-44E6-8C32-88FD5F44C84C");
+            static readonly Guid IID_IGraphicsCaptureItem = new Guid("79C3F95B-31F7-4EC2-A464-632EF5D30760");
+
+            static GraphicsCaptureItem CreateItem(IntPtr hwnd) {
+                const string className = "Windows.Graphics.Capture.GraphicsCaptureItem";
+                IntPtr hstring;
+                Marshal.ThrowExceptionForHR(WindowsCreateString(className, className.Length, out hstring));
+                IntPtr factory;
+                try {
+                    Guid interopIid = typeof(IGraphicsCaptureItemInterop).GUID;
+                    Marshal.ThrowExceptionForHR(RoGetActivationFactory(hstring, ref interopIid, out factory));
+                } finally {
+                    WindowsDeleteString(hstring);
+                }
+                var interop = (IGraphicsCaptureItemInterop)Marshal.GetObjectForIUnknown(factory);
+                Marshal.Release(factory);
+                Guid itemIid = IID_IGraphicsCaptureItem;
+                IntPtr item = interop.CreateForWindow(hwnd, ref itemIid);
+                try {
+                    return (GraphicsCaptureItem)Marshal.GetObjectForIUnknown(item);
+                } finally {
+                    Marshal.Release(item);
+                }
+            }
+
+            static IDirect3DDevice CreateDevice() {
+                IntPtr d3dDevice, context, dxgiDevice, inspectable;
+                int featureLevel;
+                Marshal.ThrowExceptionForHR(D3D11CreateDevice(IntPtr.Zero, D3D_DRIVER_TYPE_HARDWARE, IntPtr.Zero, D3D11_CREATE_DEVICE_BGRA_SUPPORT, IntPtr.Zero, 0, D3D11_SDK_VERSION, out d3dDevice, out featureLevel, out context));
+                try {
+                    Guid dxgiIid = IID_IDXGIDevice;
+                    Marshal.ThrowExceptionForHR(Marshal.QueryInterface(d3dDevice, ref dxgiIid, out dxgiDevice));
+                    try {
+                        Marshal.ThrowExceptionForHR(CreateDirect3D11DeviceFromDXGIDevice(dxgiDevice, out inspectable));
+                    } finally {
+                        Marshal.Release(dxgiDevice);
+                    }
+                } finally {
+                    Marshal.Release(context);
+                    Marshal.Release(d3dDevice);
+                }
+                try {
+                    return (IDirect3DDevice)Marshal.GetObjectForIUnknown(inspectable);
+                } finally {
+                    Marshal.Release(inspectable);
+                }
+            }
+
+            public static byte[] CapturePng(IntPtr hwnd, int timeoutMs) {
+                if (!GraphicsCaptureSession.IsSupported()) throw new NotSupportedException("Windows.Graphics.Capture is not supported on this system");
+                var item = CreateItem(hwnd);
+                var device = CreateDevice();
+                using (var pool = Direct3D11CaptureFramePool.CreateFreeThreaded(device, DirectXPixelFormat.B8G8R8A8UIntNormalized, 1, item.Size))
+                using (var session = pool.CreateCaptureSession(item)) {
+                    session.StartCapture();
+                    var deadline = DateTime.UtcNow.AddMilliseconds(timeoutMs);
+                    Direct3D11CaptureFrame frame = null;
+                    while ((frame = pool.TryGetNextFrame()) == null) {
+                        if (DateTime.UtcNow > deadline) throw new TimeoutException("No frame arrived from Windows.Graphics.Capture");
+                        Thread.Sleep(15);
+                    }
+                    using (frame) {
+                        var bitmap = SoftwareBitmap.CreateCopyFromSurfaceAsync(frame.Surface, BitmapAlphaMode.Premultiplied).AsTask().Result;
+                        using (var stream = new InMemoryRandomAccessStream()) {
+                            var encoder = BitmapEncoder.CreateAsync(BitmapEncoder.PngEncoderId, stream).AsTask().Result;
+                            encoder.SetSoftwareBitmap(bitmap);
+                            encoder.FlushAsync().AsTask().Wait();
+                            using (var output = new MemoryStream()) {
+                                stream.Seek(0);
+                                stream.AsStreamForRead().CopyTo(output);
+                                return output.ToArray();
+                            }
+                        }
+                    }
+                }
+            }
+        }
+"@
+"#;
+
+/// Grab one frame of a window with Windows.Graphics.Capture, as PNG bytes: in the native helper,
+/// or in a warm PowerShell host when the helper isn't available. Needs Windows 10 1903 or
+/// later; minimized windows produce no frames and time out.
+#[cfg(target_os = "windows")]
+pub async fn capture_hwnd(app: &AppHandle, hwnd: i64) -> Result<Vec<u8>, String> {
+    use base64::{engine::general_purpose, Engine as _};
+
+    let params = serde_json::json!({ "hwnd": hwnd });
+    let base64 = match crate::native_helper::call::<String>(app, "graphics_capture", params, None).await {
+        Some(result) => result?,
+        None => {
+            let script = format!(
+                "{}\n[Convert]::ToBase64String([GraphicsCapture]::CapturePng([IntPtr]{}, 2000))",
+                GRAPHICS_CAPTURE_SCRIPT, hwnd
+            );
+            let output = app.state::<PowerShellHostState>().run(&script, None, |_| {}).await?;
+            if !output.success() {
+                return Err(format!("Windows.Graphics.Capture failed: {}", output.stderr.trim()));
+            }
+            output.stdout
+        }
+    };
+    general_purpose::STANDARD
+        .decode(base64.trim())
+        .map_err(|e| format!("Failed to decode Windows.Graphics.Capture image: {}", e))
+}
+
+#[tauri::command]
+pub async fn get_capture_backend(state: State<'_, CaptureBackendState>) -> Result<CaptureBackend, String> {
+    Ok(state.get())
+}
+
+/// Choose how window captures are taken; saved across restarts
+#[tauri::command]
+pub async fn set_capture_backend(
+    app: AppHandle,
+    state: State<'_, CaptureBackendState>,
+    backend: CaptureBackend,
+) -> Result<(), String> {
+    *state.backend.lock().unwrap() = backend;
+    let path = persistence::data_file(&app, SETTINGS_FILE)?;
+    persistence::save_json(&path, &CaptureSettings { backend })?;
+    tracing::info!("🖼️ Window capture backend set to {:?}", backend);
+    Ok(())
+}
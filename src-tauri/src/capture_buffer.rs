@@ -0,0 +1,280 @@
+//! Memory cap for buffered capture frames: monitoring frames waiting to be handled, the
+//! recent-activity GIF frames, the remote view's latest frame and large payloads kept for
+//! event replay are put here rather than held directly. Once the frames in memory pass the cap,
+//! the oldest are written to temp files on a blocking thread and read back when asked for, so a
+//! long monitoring session can't grow the app without bound. Spilled files are deleted when
+//! their frame is removed, and by the temp file cleanup on exit.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager, State};
+
+use crate::persistence;
+use crate::temp_files::{TempFile, TempFilesState};
+
+const SETTINGS_FILE: &str = "capture_buffer_settings.json";
+const DEFAULT_MEMORY_CAP_MB: u32 = 16;
+const MAX_MEMORY_CAP_MB: u32 = 1024;
+const SPILL_PREFIX: &str = "capture_spill_";
+
+/// Handle to a frame put in the buffer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameId(u64);
+
+/// A frame that is removed from the buffer when this drops
+pub struct Buffered {
+    id: FrameId,
+    buffer: CaptureBufferState,
+}
+
+impl Buffered {
+    /// A copy of the frame; may read it back from disk, so call it from a blocking thread
+    pub fn get(&self) -> Result<Vec<u8>, String> {
+        self.buffer.get(self.id)
+    }
+}
+
+impl Drop for Buffered {
+    fn drop(&mut self) {
+        self.buffer.remove(self.id);
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CaptureMemoryUsage {
+    pub memory_cap_bytes: usize,
+    pub memory_bytes: usize,
+    pub frames_in_memory: usize,
+    /// Frames moved to temp files to stay under the cap
+    pub disk_bytes: usize,
+    pub frames_on_disk: usize,
+    /// Frames spilled since the app started
+    pub spilled_total: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CaptureBufferSettings {
+    memory_cap_mb: u32,
+}
+
+impl Default for CaptureBufferSettings {
+    fn default() -> Self {
+        Self { memory_cap_mb: DEFAULT_MEMORY_CAP_MB }
+    }
+}
+
+enum Stored {
+    Memory(Arc<Vec<u8>>),
+    Disk { file: TempFile, len: usize },
+}
+
+struct Buffer {
+    memory_cap_bytes: usize,
+    next_id: u64,
+    /// Oldest first, so spilling takes from the front
+    frames: VecDeque<(FrameId, Stored)>,
+    memory_bytes: usize,
+    disk_bytes: usize,
+    spilled_total: u64,
+}
+
+impl Buffer {
+    fn over_cap(&self) -> bool {
+        self.memory_bytes > self.memory_cap_bytes
+    }
+
+    /// The oldest frame still in memory, if the frames in memory are over the cap
+    fn next_to_spill(&self) -> Option<(FrameId, Arc<Vec<u8>>)> {
+        if !self.over_cap() {
+            return None;
+        }
+        self.frames.iter().find_map(|(id, stored)| match stored {
+            Stored::Memory(bytes) => Some((*id, bytes.clone())),
+            Stored::Disk { .. } => None,
+        })
+    }
+
+    fn usage(&self) -> CaptureMemoryUsage {
+        let on_disk = self.frames.iter().filter(|(_, stored)| matches!(stored, Stored::Disk { .. })).count();
+        CaptureMemoryUsage {
+            memory_cap_bytes: self.memory_cap_bytes,
+            memory_bytes: self.memory_bytes,
+            frames_in_memory: self.frames.len() - on_disk,
+            disk_bytes: self.disk_bytes,
+            frames_on_disk: on_disk,
+            spilled_total: self.spilled_total,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct CaptureBufferState {
+    buffer: Arc<Mutex<Buffer>>,
+    temp_files: TempFilesState,
+    /// Set while a blocking thread is spilling, so puts don't start another
+    spilling: Arc<AtomicBool>,
+}
+
+impl CaptureBufferState {
+    /// Spilled frames go to temp files registered with `temp_files`, so they are cleaned up on exit
+    pub fn new(temp_files: TempFilesState) -> Self {
+        Self {
+            buffer: Arc::new(Mutex::new(Buffer {
+                memory_cap_bytes: DEFAULT_MEMORY_CAP_MB as usize * 1024 * 1024,
+                next_id: 0,
+                frames: VecDeque::new(),
+                memory_bytes: 0,
+                disk_bytes: 0,
+                spilled_total: 0,
+            })),
+            temp_files,
+            spilling: Arc::default(),
+        }
+    }
+
+    /// Keep a frame. Doesn't block: going over the cap spills older frames on a blocking thread.
+    pub fn put(&self, bytes: Vec<u8>) -> FrameId {
+        let (id, over_cap) = {
+            let mut buffer = self.buffer.lock().unwrap();
+            buffer.next_id += 1;
+            let id = FrameId(buffer.next_id);
+            buffer.memory_bytes += bytes.len();
+            buffer.frames.push_back((id, Stored::Memory(Arc::new(bytes))));
+            (id, buffer.over_cap())
+        };
+        if over_cap && !self.spilling.swap(true, Ordering::SeqCst) {
+            let state = self.clone();
+            tauri::async_runtime::spawn_blocking(move || loop {
+                let spilled = state.spill();
+                state.spilling.store(false, Ordering::SeqCst);
+                // A put that came in as this spill finished didn't start its own. When nothing
+                // could be written, the next put over the cap tries again.
+                if !spilled || !state.buffer.lock().unwrap().over_cap() || state.spilling.swap(true, Ordering::SeqCst) {
+                    break;
+                }
+            });
+        }
+        id
+    }
+
+    /// Like `put`, removing the frame again when the returned handle drops
+    pub fn hold(&self, bytes: Vec<u8>) -> Buffered {
+        Buffered { id: self.put(bytes), buffer: self.clone() }
+    }
+
+    /// Move the oldest in-memory frames to disk until the rest fit under the cap, returning
+    /// whether any was moved. Files are written without holding the lock, so puts and gets
+    /// carry on meanwhile.
+    fn spill(&self) -> bool {
+        let mut spilled = false;
+        loop {
+            let Some((id, bytes)) = self.buffer.lock().unwrap().next_to_spill() else { return spilled };
+            let file = self.temp_files.create(SPILL_PREFIX, "bin");
+            if let Err(e) = std::fs::write(file.path(), bytes.as_slice()) {
+                // Keep the frames in memory rather than lose them
+                tracing::warn!("⚠️ Failed to spill a capture frame to disk: {}", e);
+                return spilled;
+            }
+            let unused = {
+                let mut guard = self.buffer.lock().unwrap();
+                let buffer = &mut *guard;
+                let in_memory = buffer
+                    .frames
+                    .iter_mut()
+                    .find(|(frame, stored)| *frame == id && matches!(stored, Stored::Memory(_)));
+                match in_memory {
+                    Some((_, stored)) => {
+                        let len = bytes.len();
+                        *stored = Stored::Disk { file, len };
+                        buffer.memory_bytes -= len;
+                        buffer.disk_bytes += len;
+                        buffer.spilled_total += 1;
+                        spilled = true;
+                        None
+                    }
+                    // Removed, or spilled by another thread, while it was written
+                    None => Some(file),
+                }
+            };
+            // Deletes the file if it wasn't needed, outside the lock
+            drop(unused);
+        }
+    }
+
+    /// A copy of the frame, read back from disk if it was spilled
+    pub fn get(&self, id: FrameId) -> Result<Vec<u8>, String> {
+        let path = {
+            let buffer = self.buffer.lock().unwrap();
+            match buffer.frames.iter().find(|(frame, _)| *frame == id).map(|(_, stored)| stored) {
+                Some(Stored::Memory(bytes)) => return Ok(bytes.as_ref().clone()),
+                Some(Stored::Disk { file, .. }) => file.path().to_path_buf(),
+                None => return Err("The capture frame is no longer buffered".to_string()),
+            }
+        };
+        std::fs::read(path).map_err(|e| format!("Failed to read a spilled capture frame: {}", e))
+    }
+
+    /// Forget a frame, deleting its file if it was spilled
+    pub fn remove(&self, id: FrameId) {
+        let removed = {
+            let mut buffer = self.buffer.lock().unwrap();
+            let Some(index) = buffer.frames.iter().position(|(frame, _)| *frame == id) else { return };
+            let removed = buffer.frames.remove(index).map(|(_, stored)| stored);
+            match &removed {
+                Some(Stored::Memory(bytes)) => buffer.memory_bytes -= bytes.len(),
+                Some(Stored::Disk { len, .. }) => buffer.disk_bytes -= len,
+                None => {}
+            }
+            removed
+        };
+        // Dropping a spilled frame's file deletes it, outside the lock
+        drop(removed);
+    }
+
+    pub fn usage(&self) -> CaptureMemoryUsage {
+        self.buffer.lock().unwrap().usage()
+    }
+
+    fn set_cap(&self, memory_cap_mb: u32) {
+        self.buffer.lock().unwrap().memory_cap_bytes = memory_cap_mb as usize * 1024 * 1024;
+        self.spill();
+    }
+}
+
+/// Load the saved memory cap
+pub fn init(app: &AppHandle) {
+    let settings: CaptureBufferSettings = persistence::data_file(app, SETTINGS_FILE)
+        .map(|path| persistence::load_json(&path))
+        .unwrap_or_default();
+    app.state::<CaptureBufferState>().set_cap(settings.memory_cap_mb.clamp(1, MAX_MEMORY_CAP_MB));
+}
+
+/// Memory and disk used by buffered capture frames
+#[tauri::command]
+pub async fn get_capture_memory_usage(state: State<'_, CaptureBufferState>) -> Result<CaptureMemoryUsage, String> {
+    Ok(state.usage())
+}
+
+/// Cap the memory buffered capture frames may use (1 to 1024 MB); older frames past it are
+/// moved to disk. Saved across restarts.
+#[tauri::command]
+pub async fn set_capture_memory_cap(
+    app: AppHandle,
+    state: State<'_, CaptureBufferState>,
+    memory_cap_mb: u32,
+) -> Result<CaptureMemoryUsage, String> {
+    if !(1..=MAX_MEMORY_CAP_MB).contains(&memory_cap_mb) {
+        return Err(format!("The memory cap must be between 1 and {} MB", MAX_MEMORY_CAP_MB));
+    }
+    let path = persistence::data_file(&app, SETTINGS_FILE)?;
+    persistence::save_json(&path, &CaptureBufferSettings { memory_cap_mb })?;
+    let buffer = state.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || buffer.set_cap(memory_cap_mb))
+        .await
+        .map_err(|e| format!("Failed to apply the memory cap: {}", e))?;
+    tracing::info!("🧠 Capture memory cap set to {} MB", memory_cap_mb);
+    Ok(state.usage())
+}
@@ -0,0 +1,120 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use similar::{ChangeTag, TextDiff};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::State;
+
+/// Cap on lines reported per side so a full-page scroll doesn't flood the event
+const MAX_DIFF_LINES: usize = 200;
+
+/// Lines that appeared or disappeared in a window's OCR text since the previous capture
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CaptureDiff {
+    pub window: String,
+    pub added_lines: Vec<String>,
+    pub removed_lines: Vec<String>,
+    /// Whether either list was cut off at the line limit
+    pub truncated: bool,
+}
+
+#[derive(Clone)]
+pub struct CaptureDiffState {
+    /// Off by default: while monitoring, every changed frame is OCR'd to compute the diff
+    pub enabled: Arc<AtomicBool>,
+    /// Window identity and OCR text of the previous capture
+    last: Arc<Mutex<Option<(String, String)>>>,
+}
+
+impl Default for CaptureDiffState {
+    fn default() -> Self {
+        Self {
+            enabled: Arc::new(AtomicBool::new(false)),
+            last: Arc::new(Mutex::new(None)),
+        }
+    }
+}
+
+impl CaptureDiffState {
+    /// Record `text` as the latest OCR result for `window` and diff it against the previous
+    /// capture. Returns `None` when disabled, when the window changed, or when nothing changed.
+    pub fn observe(&self, window: &str, text: &str) -> Option<CaptureDiff> {
+        if !self.enabled.load(Ordering::Relaxed) {
+            return None;
+        }
+
+        let previous = self
+            .last
+            .lock()
+            .unwrap()
+            .replace((window.to_string(), text.to_string()));
+        diff_since(previous.as_ref(), window, text)
+    }
+}
+
+/// Diff `text` against the previous (window, text) capture. `None` when there was no previous
+/// capture, the window changed, or nothing changed.
+pub fn diff_since(previous: Option<&(String, String)>, window: &str, text: &str) -> Option<CaptureDiff> {
+    let (previous_window, previous_text) = previous?;
+    if previous_window != window {
+        return None;
+    }
+
+    let diff = diff_lines(window, previous_text, text);
+    if diff.added_lines.is_empty() && diff.removed_lines.is_empty() {
+        None
+    } else {
+        Some(diff)
+    }
+}
+
+/// Line-level diff, ignoring blank lines and surrounding whitespace that OCR tends to jitter on
+pub fn diff_lines(window: &str, old: &str, new: &str) -> CaptureDiff {
+    let normalize = |text: &str| {
+        text.lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    let (old, new) = (normalize(old), normalize(new));
+
+    let mut diff = CaptureDiff {
+        window: window.to_string(),
+        added_lines: Vec::new(),
+        removed_lines: Vec::new(),
+        truncated: false,
+    };
+    for change in TextDiff::from_lines(&old, &new).iter_all_changes() {
+        let lines = match change.tag() {
+            ChangeTag::Insert => &mut diff.added_lines,
+            ChangeTag::Delete => &mut diff.removed_lines,
+            ChangeTag::Equal => continue,
+        };
+        if lines.len() < MAX_DIFF_LINES {
+            lines.push(change.value().trim_end_matches('\n').to_string());
+        } else {
+            diff.truncated = true;
+        }
+    }
+    diff
+}
+
+#[tauri::command]
+pub async fn set_capture_diff_enabled(
+    state: State<'_, CaptureDiffState>,
+    enabled: bool,
+) -> Result<(), String> {
+    state.enabled.store(enabled, Ordering::Relaxed);
+    if !enabled {
+        *state.last.lock().unwrap() = None;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_capture_diff_enabled(
+    state: State<'_, CaptureDiffState>,
+) -> Result<bool, String> {
+    Ok(state.enabled.load(Ordering::Relaxed))
+}
@@ -0,0 +1,63 @@
+//! `capture-progress` events for long capture/OCR operations. The frontend passes a
+//! `request_id` with the call; operations without one stay silent.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CaptureStage {
+    Enumerating,
+    Capturing,
+    Encoding,
+    Ocr,
+    Done,
+    Failed,
+}
+
+impl CaptureStage {
+    /// Parse a `[STAGE] <name>` marker written to stderr by the capture scripts
+    #[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+    pub fn from_marker(line: &str) -> Option<Self> {
+        match line.trim().strip_prefix("[STAGE]")?.trim() {
+            "enumerating" => Some(Self::Enumerating),
+            "capturing" => Some(Self::Capturing),
+            "encoding" => Some(Self::Encoding),
+            "ocr" => Some(Self::Ocr),
+            _ => None,
+        }
+    }
+}
+
+/// Payload of the `capture-progress` event
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CaptureProgress {
+    pub request_id: String,
+    pub stage: CaptureStage,
+    /// Error message for `failed`, otherwise unset
+    pub detail: Option<String>,
+    pub timestamp: i64,
+}
+
+pub fn emit(app: &AppHandle, request_id: Option<&str>, stage: CaptureStage, detail: Option<String>) {
+    let Some(request_id) = request_id else { return };
+    let _ = crate::event_bus::emit(
+        app,
+        crate::events::CAPTURE_PROGRESS,
+        CaptureProgress {
+            request_id: request_id.to_string(),
+            stage,
+            detail,
+            timestamp: chrono::Utc::now().timestamp_millis(),
+        },
+    );
+}
+
+/// Emit the final `done` or `failed` stage for an operation's result
+pub fn finish<T>(app: &AppHandle, request_id: Option<&str>, result: &Result<T, String>) {
+    match result {
+        Ok(_) => emit(app, request_id, CaptureStage::Done, None),
+        Err(e) => emit(app, request_id, CaptureStage::Failed, Some(e.clone())),
+    }
+}
@@ -0,0 +1,197 @@
+//! Headless capture for scripts, tests and teachers preparing materials, built with the `cli`
+//! feature: `ai-teacher capture --window notepad --ocr --out shot.png` runs the same capture
+//! and OCR pipeline as the app, without opening a window. The image goes to `--out`, the OCR
+//! text (or with `--json`, the whole result) to stdout, and errors to stderr with exit code 1.
+
+use base64::{engine::general_purpose, Engine as _};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+use crate::cancellation::CancellationState;
+use crate::commands::CaptureWindowParams;
+use crate::screen_capture::ScreenCapture;
+
+const USAGE: &str = "Usage: ai-teacher capture [options]
+
+Captures a window, or the whole screen when no window is named.
+
+Options:
+  --window <title>    Window whose title contains <title>
+  --process <name>    Window of the process <name>, e.g. notepad
+  --ocr               Read the text of the capture and print it
+  --out <file>        Save the image; the extension picks the format (.png, .jpg, .bmp)
+  --json              Print the result as JSON instead of the OCR text
+  -h, --help          Show this help";
+
+#[derive(Debug, Default)]
+struct CaptureArgs {
+    window_title: Option<String>,
+    process_name: Option<String>,
+    ocr: bool,
+    out: Option<PathBuf>,
+    json: bool,
+    help: bool,
+}
+
+/// Printed with `--json`; the image itself only goes to `--out`
+#[derive(Serialize)]
+struct CaptureOutput {
+    window_title: Option<String>,
+    process_name: Option<String>,
+    hash: String,
+    timestamp: i64,
+    ocr_text: Option<String>,
+    out: Option<String>,
+}
+
+fn parse(args: &[String]) -> Result<CaptureArgs, String> {
+    let mut parsed = CaptureArgs::default();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().cloned().ok_or_else(|| format!("{} needs a value", arg));
+        match arg.as_str() {
+            "--window" => parsed.window_title = Some(value()?),
+            "--process" => parsed.process_name = Some(value()?),
+            "--out" => parsed.out = Some(PathBuf::from(value()?)),
+            "--ocr" => parsed.ocr = true,
+            "--json" => parsed.json = true,
+            "-h" | "--help" => parsed.help = true,
+            other => return Err(format!("Unknown option '{}'", other)),
+        }
+    }
+    Ok(parsed)
+}
+
+/// Release builds use the Windows GUI subsystem, which starts without a console; join the one
+/// of the terminal that ran us so stdout and stderr reach it
+#[cfg(target_os = "windows")]
+fn attach_console() {
+    use windows_sys::Win32::System::Console::{AttachConsole, ATTACH_PARENT_PROCESS};
+    // Fails when there is no parent console (e.g. started from Explorer) or one is already
+    // attached (debug builds); output is then left as it is
+    unsafe { AttachConsole(ATTACH_PARENT_PROCESS) };
+}
+
+/// Whether the app was started as `ai-teacher capture ...`
+pub fn requested() -> bool {
+    std::env::args().nth(1).as_deref() == Some("capture")
+}
+
+/// Write the PNG capture to `path`, converting it when the extension asks for another format
+fn save_image(image_base64: &str, path: &Path) -> Result<(), String> {
+    let png = general_purpose::STANDARD.decode(image_base64).map_err(|e| format!("Failed to decode the capture: {}", e))?;
+    let is_png = path.extension().is_none_or(|extension| extension.eq_ignore_ascii_case("png"));
+    if is_png {
+        std::fs::write(path, png).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    } else {
+        image::load_from_memory(&png)
+            .and_then(|image| image.save(path))
+            .map_err(|e| format!("Failed to save {}: {}", path.display(), e))
+    }
+}
+
+async fn capture(app: &AppHandle, args: &CaptureArgs) -> Result<CaptureOutput, String> {
+    let (image_base64, mut output) = if args.window_title.is_some() || args.process_name.is_some() {
+        let options = CaptureWindowParams {
+            window_title: args.window_title.clone(),
+            process_name: args.process_name.clone(),
+            ..Default::default()
+        };
+        let result = if args.ocr {
+            crate::commands::capture_window_with_ocr(app.clone(), options).await?
+        } else {
+            crate::commands::capture_window(app.clone(), options).await?
+        };
+        let output = CaptureOutput {
+            window_title: Some(result.window_title),
+            process_name: Some(result.process_name),
+            hash: result.hash,
+            timestamp: result.timestamp,
+            ocr_text: result.ocr_text,
+            out: None,
+        };
+        (result.image_base64, output)
+    } else {
+        let result = ScreenCapture::new().capture_full_screen(app).await?;
+        let ocr_text = if args.ocr {
+            let operation = app.state::<CancellationState>().register(None);
+            Some(crate::commands::ocr_image(app, result.image_base64.clone(), &operation).await?)
+        } else {
+            None
+        };
+        let output = CaptureOutput {
+            window_title: None,
+            process_name: None,
+            hash: result.hash,
+            timestamp: result.timestamp,
+            ocr_text,
+            out: None,
+        };
+        (result.image_base64, output)
+    };
+
+    if let Some(path) = &args.out {
+        save_image(&image_base64, path)?;
+        output.out = Some(path.display().to_string());
+    }
+    Ok(output)
+}
+
+/// Run `ai-teacher capture` and return the process exit code
+pub fn run(mut context: tauri::Context<tauri::Wry>) -> i32 {
+    #[cfg(target_os = "windows")]
+    attach_console();
+    let args: Vec<String> = std::env::args().skip(2).collect();
+    let args = match parse(&args) {
+        Ok(args) if args.help => {
+            println!("{}", USAGE);
+            return 0;
+        }
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            return 2;
+        }
+    };
+
+    // Same state and settings as the app, without its window
+    context.config_mut().app.windows.clear();
+    let app = match crate::with_state(tauri::Builder::default()).build(context) {
+        Ok(app) => app,
+        Err(e) => {
+            eprintln!("Failed to start: {}", e);
+            return 1;
+        }
+    };
+    let handle = app.handle().clone();
+    crate::profiles::init(&handle);
+    crate::capture_backend::init(&handle);
+    crate::image_resize::init(&handle);
+    crate::capture_buffer::init(&handle);
+    crate::translation::init(&handle);
+
+    let result = tauri::async_runtime::block_on(capture(&handle, &args));
+    crate::shutdown::run(&handle);
+    let output = match result {
+        Ok(output) => output,
+        Err(e) => {
+            eprintln!("{}", e);
+            return 1;
+        }
+    };
+    if args.json {
+        match serde_json::to_string_pretty(&output) {
+            Ok(json) => println!("{}", json),
+            Err(e) => {
+                eprintln!("Failed to write the result: {}", e);
+                return 1;
+            }
+        }
+    } else if let Some(text) = &output.ocr_text {
+        println!("{}", text);
+    } else if output.out.is_none() {
+        eprintln!("Captured, but nothing was kept: add --out <file> or --ocr");
+    }
+    0
+}
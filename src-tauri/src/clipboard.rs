@@ -0,0 +1,87 @@
+//! Images on the clipboard, such as a Snipping Tool screenshot the student just took, so the
+//! tutor can look at them without capturing the screen again. Windows reads the clipboard
+//! through the PowerShell host; Linux asks `wl-paste` or `xclip`.
+
+use base64::{engine::general_purpose, Engine as _};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::cancellation::CancellationState;
+
+/// Writes the clipboard image as base64 PNG, or nothing when the clipboard holds no image
+const READ_IMAGE_SCRIPT: &str = r#"
+Add-Type -AssemblyName System.Windows.Forms, System.Drawing
+$image = [System.Windows.Forms.Clipboard]::GetImage()
+if ($image -ne $null) {
+    $stream = New-Object System.IO.MemoryStream
+    $image.Save($stream, [System.Drawing.Imaging.ImageFormat]::Png)
+    [Convert]::ToBase64String($stream.ToArray())
+    $stream.Dispose()
+    $image.Dispose()
+}
+"#;
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ClipboardImage {
+    /// PNG, base64-encoded
+    pub image_base64: String,
+    pub width: u32,
+    pub height: u32,
+    /// Text read by OCR, when requested
+    pub ocr_text: Option<String>,
+}
+
+/// The clipboard image as PNG bytes, None when it holds no image
+async fn read_png(app: &AppHandle) -> Result<Option<Vec<u8>>, String> {
+    if cfg!(target_os = "windows") {
+        let output = app.state::<crate::powershell_host::PowerShellHostState>().run(READ_IMAGE_SCRIPT, None, |_| {}).await?;
+        if !output.success() {
+            return Err(format!("Failed to read the clipboard: {}", output.stderr.trim()));
+        }
+        let encoded = output.stdout.trim();
+        if encoded.is_empty() {
+            return Ok(None);
+        }
+        return general_purpose::STANDARD
+            .decode(encoded)
+            .map(Some)
+            .map_err(|e| format!("Failed to decode the clipboard image: {}", e));
+    }
+
+    let (program, args): (&str, &[&str]) = if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        ("wl-paste", &["--no-newline", "--type", "image/png"])
+    } else {
+        ("xclip", &["-selection", "clipboard", "-target", "image/png", "-out"])
+    };
+    let output = tokio::process::Command::new(program)
+        .args(args)
+        .output()
+        .await
+        .map_err(|e| format!("Reading clipboard images needs {}: {}", program, e))?;
+    // Both fail when the clipboard holds no PNG
+    Ok((output.status.success() && !output.stdout.is_empty()).then_some(output.stdout))
+}
+
+/// The image on the clipboard as base64 PNG, optionally with its OCR text. None when the
+/// clipboard holds no image.
+#[tauri::command]
+pub async fn get_clipboard_image(
+    app: AppHandle,
+    ocr: Option<bool>,
+    request_id: Option<String>,
+) -> Result<Option<ClipboardImage>, String> {
+    let operation = app.state::<CancellationState>().register(request_id.as_deref());
+    let Some(png) = read_png(&app).await? else {
+        return Ok(None);
+    };
+    let image = image::load_from_memory(&png).map_err(|e| format!("The clipboard image is unreadable: {}", e))?;
+    let image_base64 = general_purpose::STANDARD.encode(&png);
+    let ocr_text = if ocr.unwrap_or(false) {
+        Some(crate::commands::ocr_image(&app, image_base64.clone(), &operation).await?)
+    } else {
+        None
+    };
+    tracing::info!("📋 Read a {}x{} image from the clipboard", image.width(), image.height());
+    Ok(Some(ClipboardImage { image_base64, width: image.width(), height: image.height(), ocr_text }))
+}
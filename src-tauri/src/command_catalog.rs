@@ -0,0 +1,1660 @@
+use schemars::gen::{SchemaGenerator, SchemaSettings};
+use schemars::schema::Schema;
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::collections::HashMap;
+use tauri::Manager;
+
+use crate::accessibility::{AccessibilityReport, ColorVision};
+use crate::achievements::Achievements;
+use crate::activity::{ActivityMetrics, ActivityRange};
+use crate::ai::{ChatRequest, ChatResponse, ProviderConfig, ProviderInfo, ProviderTest};
+use crate::ai_tools::{AiTool, ToolChatResponse};
+use crate::ai_usage::{AiBudgetSettings, AiUsageReport, UsageRange};
+use crate::benchmark::BenchmarkReport;
+use crate::bindings::{allows_null, ts_type};
+use crate::capture_backend::CaptureBackend;
+use crate::capture_buffer::CaptureMemoryUsage;
+use crate::clipboard::ClipboardImage;
+use crate::commands::{
+    BatchWindowCapture, CaptureResult, CaptureWindowParams, CommandResult, SystemContext, WindowCaptureResult, WindowInfo,
+};
+use crate::connectivity::ConnectivityStatus;
+use crate::consent::{ConsentLogEntry, ConsentScope, ConsentStatus};
+use crate::content_class::{ContentClassification, ContentTime};
+use crate::context_budget::{AssembledContext, ContextBudgetSettings, ContextSection, TokenCount};
+use crate::conversations::{Conversation, ConversationMessage, ConversationSummary, NewMessage};
+use crate::crash_report::CrashReport;
+use crate::curriculum::{CurriculumProgress, CurriculumSummary, LessonStatus};
+use crate::daily_summary::{DailySummary, DailySummarySettings};
+use crate::data_export::DataExport;
+use crate::debug_bundle::{DebugBundle, ScreenshotConsent};
+use crate::dev_environment::DevEnvironment;
+use crate::diagnostics::DiagnosticsReport;
+use crate::diagnostics_parser::ParsedDiagnostic;
+use crate::dictionary::{Definition, DictionaryImport};
+use crate::distraction::{Classification, DistractionRules, StreakStatus};
+use crate::docker::{ContainerLogLine, ContainerStats, ContainerSummary};
+use crate::event_bus::MissedEvents;
+use crate::exercises::{Exercise, ExerciseAttempt, NewExercise, TopicMastery};
+use crate::file_watcher::FileWatcherStatus;
+use crate::flashcards::Flashcard;
+use crate::focus_timer::FocusSession;
+use crate::frame_delta::FrameDeltaSettings;
+use crate::git_insight::{GitCommit, GitDiff, GitStatus};
+use crate::handwriting::{HandwritingRecognition, InkStroke};
+use crate::history::{HistoryItem, HistoryMatch};
+use crate::homework::{HomeworkContext, HomeworkDetectionParams, HomeworkDetectionSettings};
+use crate::image_resize::ResizeBackend;
+use crate::logging::LogEntry;
+use crate::math_ocr::{MathOcrSettings, MathRecognition};
+use crate::metrics::Metrics;
+use crate::models::{LoadedModelStatus, LocalModel, ModelDownloadRequest, ModelVerification};
+use crate::monitoring::{MonitorInfo, MonitorOptions, MonitorTarget};
+use crate::narrator::{NarratorInfo, NarratorOptions};
+use crate::notes::{NewNote, Note, NoteLink, NoteUpdate};
+use crate::observation::ObservationStatus;
+use crate::ocr_languages::OcrLanguageInstall;
+use crate::ocr_layout::DocumentLayout;
+use crate::ports::ListeningPort;
+use crate::process_manager::JobInfo;
+use crate::process_monitor::ProcessTree;
+use crate::profiles::{Profile, ProfileList};
+use crate::project_analysis::ProjectAnalysis;
+use crate::project_files::{DirectoryEntry, ProjectFile, SearchMatch};
+use crate::prompts::{NewPromptTemplate, PromptTemplate};
+use crate::recent_activity::RecentActivityGif;
+use crate::remote_view::RemoteViewStatus;
+use crate::request_queue::PendingRequest;
+use crate::retention::{PurgeConfirmation, RetentionReport, RetentionSettings};
+use crate::roles::{RolePolicy, RoleStatus};
+use crate::sandbox::SandboxSettings;
+use crate::screen_recording::{RecordingFinished, RecordingInfo, RecordingTarget};
+use crate::script_runner::{ScriptPlan, ScriptRun};
+use crate::secret_filter::FilteredText;
+use crate::sync::{SyncSettings, SyncStatus};
+use crate::terminal::{CreateTerminalParams, TerminalInfo};
+use crate::text_analysis::TextAnalysis;
+use crate::transcription::TranscribeOptions;
+use crate::translation::{Translation, TranslationSettings};
+use crate::typing_practice::TypingSessionStats;
+use crate::updater::{UpdateInfo, UpdateSettings};
+use crate::watchdog::SubsystemHealth;
+use crate::webcam::WebcamDevice;
+use crate::worksheet::{AnswerKey, AnswerKeySummary, WorksheetGrade};
+use crate::ws_server::IntegrationServerStatus;
+
+/// Machine-readable description of an invokable command, served to the
+/// frontend command palette and the API layers.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct CommandMetadata {
+    pub name: &'static str,
+    pub module: &'static str,
+    pub description: &'static str,
+    pub params: Vec<ParamMetadata>,
+    /// TypeScript type of the command's result
+    pub returns: String,
+    pub capabilities: Vec<&'static str>,
+    pub rate_limit: Option<RateLimit>,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ParamMetadata {
+    /// Argument key expected by `invoke` (camelCase, as Tauri converts it)
+    pub name: String,
+    /// TypeScript type of the argument
+    pub type_name: String,
+    pub required: bool,
+    /// JSON schema of the argument; `$ref`s point into `CommandCatalog::definitions`
+    pub schema: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, JsonSchema)]
+pub struct RateLimit {
+    pub max_calls: u32,
+    pub per_seconds: u32,
+}
+
+/// All command metadata plus the JSON schemas of every named type they reference
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct CommandCatalog {
+    pub commands: Vec<CommandMetadata>,
+    pub definitions: serde_json::Map<String, serde_json::Value>,
+}
+
+pub(crate) fn to_camel_case(name: &str) -> String {
+    let mut result = String::with_capacity(name.len());
+    let mut upper_next = false;
+    for c in name.chars() {
+        if c == '_' {
+            upper_next = true;
+        } else if upper_next {
+            result.extend(c.to_uppercase());
+            upper_next = false;
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+/// Schema generator shared by the catalog and the TypeScript bindings, so `$ref`s line up
+pub fn schema_generator() -> SchemaGenerator {
+    SchemaSettings::draft07().into_generator()
+}
+
+fn param<T: JsonSchema>(gen: &mut SchemaGenerator, name: &str) -> ParamMetadata {
+    let schema = gen.subschema_for::<T>();
+    ParamMetadata {
+        name: to_camel_case(name),
+        type_name: ts_type(&schema),
+        required: !allows_null(&schema),
+        schema: serde_json::to_value(&schema).unwrap_or_default(),
+    }
+}
+
+fn returns<T: JsonSchema>(gen: &mut SchemaGenerator) -> String {
+    ts_type(&gen.subschema_for::<T>())
+}
+
+fn definitions_json(gen: &SchemaGenerator) -> serde_json::Map<String, serde_json::Value> {
+    gen.definitions()
+        .iter()
+        .map(|(name, schema): (&String, &Schema)| {
+            (name.clone(), serde_json::to_value(schema).unwrap_or_default())
+        })
+        .collect()
+}
+
+/// Declares every command exactly once. Expands to the Tauri invoke handler and
+/// to the metadata table, so the two can never drift apart. Parameter and result
+/// types are real Rust types; their schemas feed the generated TypeScript bindings.
+macro_rules! command_catalog {
+    ($(
+        $module:ident :: $name:ident {
+            description: $description:literal,
+            params: { $($param:ident : $ty:ty),* $(,)? },
+            returns: $returns:ty,
+            capabilities: [$($capability:literal),* $(,)?]
+            $(, rate_limit: $rate_limit:expr)?
+            $(,)?
+        }
+    ),* $(,)?) => {
+        /// The Tauri invoke handler, refusing teacher-only commands in student mode and calls
+        /// over a command's rate limit
+        pub fn invoke_handler() -> impl Fn(tauri::ipc::Invoke<tauri::Wry>) -> bool + Send + Sync + 'static {
+            let handler: fn(tauri::ipc::Invoke<tauri::Wry>) -> bool = tauri::generate_handler![$(crate::$module::$name),*];
+            move |invoke| {
+                let app = invoke.message.webview_ref().app_handle().clone();
+                if let Err(reason) = app.state::<crate::roles::RolesState>().check_invoke(invoke.message.command()) {
+                    tracing::warn!("🔒 Blocked '{}': {}", invoke.message.command(), reason);
+                    invoke.resolver.reject(reason);
+                    return true;
+                }
+                if let Err(limited) = app.state::<crate::rate_limit::RateLimiterState>().check(invoke.message.command()) {
+                    tracing::warn!("⏳ {}", limited);
+                    invoke.resolver.reject(limited);
+                    return true;
+                }
+                handler(invoke)
+            }
+        }
+
+        /// Rate limit declared for a command, if any
+        pub fn rate_limit(command: &str) -> Option<RateLimit> {
+            match command {
+                $(stringify!($name) => command_catalog!(@rate_limit $($rate_limit)?),)*
+                _ => None,
+            }
+        }
+
+        /// Build the metadata table, registering every referenced type with `gen`
+        pub fn catalog_with(gen: &mut SchemaGenerator) -> Vec<CommandMetadata> {
+            vec![$(
+                CommandMetadata {
+                    name: stringify!($name),
+                    module: stringify!($module),
+                    description: $description,
+                    params: vec![$(param::<$ty>(gen, stringify!($param))),*],
+                    returns: returns::<$returns>(gen),
+                    capabilities: vec![$($capability),*],
+                    rate_limit: command_catalog!(@rate_limit $($rate_limit)?),
+                }
+            ),*]
+        }
+    };
+    (@rate_limit) => { None };
+    (@rate_limit $rate_limit:expr) => { Some($rate_limit) };
+}
+
+command_catalog! {
+    commands::capture_screen {
+        description: "Capture the full virtual screen",
+        params: {},
+        returns: CaptureResult,
+        capabilities: ["screen-capture"],
+    },
+    commands::get_active_window {
+        description: "Get the process name of the foreground window",
+        params: {},
+        returns: String,
+        capabilities: ["window-info"],
+    },
+    process_monitor::get_process_tree {
+        description: "A process with its descendants, their command lines, and its ancestors",
+        params: { pid: u32 },
+        returns: ProcessTree,
+        capabilities: ["process-info"],
+    },
+    docker::list_containers {
+        description: "Docker containers with image, state and published ports (stopped ones too with all)",
+        params: { all: Option<bool> },
+        returns: Vec<ContainerSummary>,
+        capabilities: ["command-execution"],
+    },
+    docker::container_logs {
+        description: "The last lines (default 100) of a Docker container's stdout and stderr",
+        params: { id: String, tail: Option<u32> },
+        returns: Vec<ContainerLogLine>,
+        capabilities: ["command-execution"],
+    },
+    docker::container_stats {
+        description: "CPU, memory, network and process counts of a Docker container",
+        params: { id: String },
+        returns: ContainerStats,
+        capabilities: ["command-execution"],
+    },
+    dev_environment::detect_dev_environment {
+        description: "Installed toolchains (node, npm, python, rustc, cargo, java, docker, git) with versions and PATH locations",
+        params: {},
+        returns: DevEnvironment,
+        capabilities: ["process-info"],
+    },
+    ports::get_listening_ports {
+        description: "Listening TCP and bound UDP ports with the process behind each, optionally only one port",
+        params: { port: Option<u16> },
+        returns: Vec<ListeningPort>,
+        capabilities: ["process-info", "network"],
+    },
+    commands::get_system_context {
+        description: "Get the active window, open windows and running applications",
+        params: {},
+        returns: SystemContext,
+        capabilities: ["window-info", "process-info"],
+        rate_limit: RateLimit { max_calls: 30, per_seconds: 60 },
+    },
+    commands::list_windows_by_process {
+        description: "List all windows matching a process name or window title",
+        params: { options: CaptureWindowParams },
+        returns: Vec<WindowInfo>,
+        capabilities: ["window-info"],
+    },
+    commands::capture_window {
+        description: "Capture a specific window by process name or window title",
+        params: { options: CaptureWindowParams },
+        returns: WindowCaptureResult,
+        capabilities: ["screen-capture"],
+    },
+    commands::capture_windows {
+        description: "Capture several windows in one call, sharing a single window enumeration",
+        params: { filters: Vec<CaptureWindowParams> },
+        returns: Vec<BatchWindowCapture>,
+        capabilities: ["screen-capture"],
+    },
+    screen_recording::start_screen_recording {
+        description: "Record the screen, a region or a window to an MP4 clip with ffmpeg",
+        params: { target: RecordingTarget, fps: Option<u32>, max_seconds: Option<u32> },
+        returns: RecordingInfo,
+        capabilities: ["screen-capture"],
+    },
+    screen_recording::stop_screen_recording {
+        description: "Stop the running screen recording and finalize the clip",
+        params: {},
+        returns: RecordingFinished,
+        capabilities: ["screen-capture"],
+    },
+    frame_delta::get_frame_delta_settings {
+        description: "Get whether screen-changed events carry changed tiles instead of full frames",
+        params: {},
+        returns: FrameDeltaSettings,
+        capabilities: ["settings"],
+    },
+    frame_delta::set_frame_delta_settings {
+        description: "Send only changed tiles in screen-changed events, with a keyframe every N frames",
+        params: { settings: FrameDeltaSettings },
+        returns: (),
+        capabilities: ["settings"],
+    },
+    frame_delta::get_full_frame {
+        description: "Get the latest full frame of a monitor to resync after a missed delta",
+        params: { monitor_id: Option<String> },
+        returns: CaptureResult,
+        capabilities: ["screen-capture"],
+    },
+    recent_activity::export_recent_activity_gif {
+        description: "Replay the last seconds (up to 60) of monitoring captures as an animated GIF",
+        params: { seconds: u32 },
+        returns: RecentActivityGif,
+        capabilities: ["screen-capture"],
+    },
+    capture_backend::get_capture_backend {
+        description: "Get which backend window captures use",
+        params: {},
+        returns: CaptureBackend,
+        capabilities: ["settings"],
+    },
+    capture_backend::set_capture_backend {
+        description: "Choose the window capture backend (auto, print_window, graphics_capture)",
+        params: { backend: CaptureBackend },
+        returns: (),
+        capabilities: ["settings"],
+    },
+    capture_buffer::get_capture_memory_usage {
+        description: "Get the memory and disk used by buffered capture frames",
+        params: {},
+        returns: CaptureMemoryUsage,
+        capabilities: ["settings"],
+    },
+    capture_buffer::set_capture_memory_cap {
+        description: "Cap the memory buffered capture frames may use; older frames past it move to disk",
+        params: { memory_cap_mb: u32 },
+        returns: CaptureMemoryUsage,
+        capabilities: ["settings"],
+    },
+    image_resize::get_resize_backend {
+        description: "Get how captures and monitoring frames are downscaled",
+        params: {},
+        returns: ResizeBackend,
+        capabilities: ["settings"],
+    },
+    image_resize::set_resize_backend {
+        description: "Choose how captures and monitoring frames are downscaled (image, simd)",
+        params: { backend: ResizeBackend },
+        returns: (),
+        capabilities: ["settings"],
+    },
+    commands::capture_window_with_ocr {
+        description: "Capture a window and extract text using OCR",
+        params: { options: CaptureWindowParams },
+        returns: WindowCaptureResult,
+        capabilities: ["screen-capture", "ocr"],
+        rate_limit: RateLimit { max_calls: 12, per_seconds: 60 },
+    },
+    commands::extract_text_from_image {
+        description: "Extract text from a base64-encoded image using Windows OCR",
+        params: { image_base64: String, request_id: Option<String> },
+        returns: String,
+        capabilities: ["ocr"],
+    },
+    diagnostics_parser::parse_diagnostics {
+        description: "Structured rustc, tsc, Python and gcc errors (file, line, code, message) found in OCR or terminal text",
+        params: { text: String },
+        returns: Vec<ParsedDiagnostic>,
+        capabilities: [],
+    },
+    clipboard::get_clipboard_image {
+        description: "The image on the clipboard (e.g. a fresh Snipping Tool screenshot) as base64 PNG, optionally with OCR text",
+        params: { ocr: Option<bool>, request_id: Option<String> },
+        returns: Option<ClipboardImage>,
+        capabilities: ["clipboard", "ocr"],
+    },
+    accessibility::analyze_accessibility {
+        description: "Find low-contrast text regions (WCAG ratio, default 4.5) and simulate color-vision deficiencies on an image",
+        params: { image_base64: String, min_contrast: Option<f32>, simulate: Option<Vec<ColorVision>> },
+        returns: AccessibilityReport,
+        capabilities: [],
+    },
+    ocr_languages::list_ocr_languages {
+        description: "List the language tags Windows OCR can currently recognize",
+        params: {},
+        returns: Vec<String>,
+        capabilities: ["ocr"],
+    },
+    ocr_languages::install_ocr_language {
+        description: "Install the Windows OCR language pack for a tag such as de-DE (asks for elevation, falls back to Language settings), emitting ocr-language-progress",
+        params: { tag: String },
+        returns: OcrLanguageInstall,
+        capabilities: ["ocr", "command-execution"],
+    },
+    ocr_layout::extract_layout {
+        description: "OCR an image and rebuild its layout (headings, paragraphs, tables, text columns) as structured blocks and Markdown",
+        params: { image_base64: String, request_id: Option<String> },
+        returns: DocumentLayout,
+        capabilities: ["ocr"],
+    },
+    cancellation::cancel_operation {
+        description: "Abort an in-flight capture or OCR operation, or drop a queued AI request, by request id",
+        params: { request_id: String },
+        returns: bool,
+        capabilities: ["screen-capture", "ocr"],
+    },
+    observation::pause_monitoring {
+        description: "Pause every monitor for some minutes, or until resume_monitoring",
+        params: { duration_minutes: Option<u32> },
+        returns: ObservationStatus,
+        capabilities: ["monitoring"],
+    },
+    observation::resume_monitoring {
+        description: "Resume monitors paused with pause_monitoring",
+        params: {},
+        returns: ObservationStatus,
+        capabilities: ["monitoring"],
+    },
+    observation::get_observation_status {
+        description: "Whether the screen is being observed, and whether monitoring is paused",
+        params: {},
+        returns: ObservationStatus,
+        capabilities: [],
+    },
+    observation::set_recording_indicator {
+        description: "Show or hide the always-on-top dot shown while the screen is being captured",
+        params: { enabled: bool },
+        returns: (),
+        capabilities: ["settings"],
+    },
+    consent::grant_monitoring_consent {
+        description: "Record the student's consent to monitoring (screen, or screen and text) for some minutes, or until the app exits",
+        params: { scope: ConsentScope, duration_minutes: Option<u32> },
+        returns: ConsentStatus,
+        capabilities: ["monitoring"],
+    },
+    consent::revoke_monitoring_consent {
+        description: "Withdraw monitoring consent and stop every monitor and input counting",
+        params: {},
+        returns: ConsentStatus,
+        capabilities: ["monitoring"],
+    },
+    consent::get_consent_state {
+        description: "The monitoring consent in effect and when it expires",
+        params: {},
+        returns: ConsentStatus,
+        capabilities: [],
+    },
+    consent::get_consent_log {
+        description: "Audit log of monitoring consent grants, revocations and expiries",
+        params: {},
+        returns: Vec<ConsentLogEntry>,
+        capabilities: [],
+    },
+    monitoring::start_monitoring {
+        description: "Start a monitor (full screen, active window or a matching window) emitting screen-changed:{id} events",
+        params: { options: Option<MonitorOptions> },
+        returns: MonitorInfo,
+        capabilities: ["screen-capture", "monitoring"],
+    },
+    monitoring::watch_for_text {
+        description: "Watch a window with OCR and emit text-detected when new text matches a regex (e.g. a build error)",
+        params: { target_window: MonitorTarget, pattern: String, interval: Option<u64> },
+        returns: MonitorInfo,
+        capabilities: ["screen-capture", "monitoring", "ocr"],
+    },
+    monitoring::stop_monitoring {
+        description: "Stop a monitor by id, or every monitor when no id is given",
+        params: { id: Option<String> },
+        returns: (),
+        capabilities: ["monitoring"],
+    },
+    math_ocr::recognize_math {
+        description: "Read a math formula from an image and return it as LaTeX",
+        params: { image_base64: String },
+        returns: MathRecognition,
+        capabilities: ["ocr", "network"],
+    },
+    math_ocr::get_math_ocr_settings {
+        description: "Get the LaTeX-OCR server endpoint",
+        params: {},
+        returns: MathOcrSettings,
+        capabilities: ["settings"],
+    },
+    math_ocr::set_math_ocr_settings {
+        description: "Set the LaTeX-OCR server endpoint",
+        params: { settings: MathOcrSettings },
+        returns: (),
+        capabilities: ["settings"],
+    },
+    handwriting::recognize_handwriting {
+        description: "Read handwriting from pen strokes (Windows Ink) or from an image of handwritten work (preprocessed OCR)",
+        params: { image_base64: Option<String>, strokes: Option<Vec<InkStroke>> },
+        returns: HandwritingRecognition,
+        capabilities: ["ocr"],
+    },
+    webcam::list_webcams {
+        description: "List the cameras a webcam frame can be taken from",
+        params: {},
+        returns: Vec<WebcamDevice>,
+        capabilities: ["camera"],
+    },
+    webcam::capture_webcam_frame {
+        description: "Take a photo with a webcam, e.g. of paper homework, optionally with handwriting OCR",
+        params: { device_id: Option<String>, ocr: Option<bool>, request_id: Option<String> },
+        returns: CaptureResult,
+        capabilities: ["camera", "ocr"],
+    },
+    worksheet::grade_worksheet {
+        description: "Grade a photo or scan of a paper worksheet (or a webcam frame) against a saved answer key, question by question",
+        params: { image_base64: Option<String>, answer_key_id: String, device_id: Option<String>, request_id: Option<String> },
+        returns: WorksheetGrade,
+        capabilities: ["screen-capture", "ocr", "network"],
+    },
+    worksheet::list_answer_keys {
+        description: "List the saved worksheet answer keys, without their answers",
+        params: {},
+        returns: Vec<AnswerKeySummary>,
+        capabilities: [],
+    },
+    worksheet::get_answer_key {
+        description: "Get a worksheet answer key with its answers",
+        params: { id: String },
+        returns: AnswerKey,
+        capabilities: [],
+    },
+    worksheet::save_answer_key {
+        description: "Save a worksheet answer key, replacing the one with the same id",
+        params: { key: AnswerKey },
+        returns: AnswerKey,
+        capabilities: ["settings"],
+    },
+    worksheet::delete_answer_key {
+        description: "Delete a worksheet answer key",
+        params: { id: String },
+        returns: bool,
+        capabilities: ["settings"],
+    },
+    documents::import_document {
+        description: "Import a PDF or DOCX file's text (and PDF page images) into the searchable history",
+        params: { path: String, request_id: Option<String> },
+        returns: HistoryItem,
+        capabilities: ["file-system", "ocr"],
+    },
+    transcription::transcribe_audio_file {
+        description: "Transcribe a voice memo or lecture recording offline with whisper.cpp into the history, one part per minute",
+        params: { path: String, options: Option<TranscribeOptions> },
+        returns: HistoryItem,
+        capabilities: ["file-system", "command-execution"],
+    },
+    translation::translate_text {
+        description: "Translate text, e.g. OCR of a worksheet, into a language with the configured backend (local model, LibreTranslate or cloud AI)",
+        params: { text: String, target_lang: String },
+        returns: Translation,
+        capabilities: ["network", "keychain"],
+    },
+    translation::get_translation_settings {
+        description: "Get the translation backend and LibreTranslate URL",
+        params: {},
+        returns: TranslationSettings,
+        capabilities: [],
+    },
+    translation::set_translation_settings {
+        description: "Choose the translation backend: a local model, a LibreTranslate server or the cloud AI providers",
+        params: { settings: TranslationSettings },
+        returns: (),
+        capabilities: ["settings"],
+    },
+    text_analysis::analyze_text {
+        description: "Reading level (Flesch-Kincaid), key terms and an extractive summary of a text, computed locally",
+        params: { text: String, summary_sentences: Option<usize> },
+        returns: TextAnalysis,
+        capabilities: [],
+    },
+    dictionary::define_term {
+        description: "Define a word from the offline dictionary, falling back to Wiktionary when online",
+        params: { word: String, lang: String, online_fallback: Option<bool> },
+        returns: Definition,
+        capabilities: ["network"],
+    },
+    dictionary::import_dictionary {
+        description: "Fill the offline dictionary for a language from a Wiktionary JSON-lines extract",
+        params: { path: String, lang: String },
+        returns: DictionaryImport,
+        capabilities: ["file-system"],
+    },
+    history::search_history {
+        description: "Full-text search of the history, optionally limited to one kind of item",
+        params: { query: String, kind: Option<String>, limit: Option<u32> },
+        returns: Vec<HistoryMatch>,
+        capabilities: [],
+    },
+    history::get_history_item {
+        description: "Get a history item with all of its text",
+        params: { id: i64 },
+        returns: HistoryItem,
+        capabilities: [],
+    },
+    history::delete_history_item {
+        description: "Delete a history item and any page images stored with it",
+        params: { id: i64 },
+        returns: (),
+        capabilities: [],
+    },
+    notes::create_note {
+        description: "Save a knowledge-base note, e.g. a lesson summary, optionally linked to captures, sessions or history items",
+        params: { note: NewNote },
+        returns: Note,
+        capabilities: [],
+    },
+    notes::get_note {
+        description: "Get a note by id",
+        params: { id: i64 },
+        returns: Note,
+        capabilities: [],
+    },
+    notes::update_note {
+        description: "Change a note's title, body, tags or links",
+        params: { id: i64, update: NoteUpdate },
+        returns: Note,
+        capabilities: [],
+    },
+    notes::delete_note {
+        description: "Delete a note",
+        params: { id: i64 },
+        returns: (),
+        capabilities: [],
+    },
+    notes::search_notes {
+        description: "Full-text search of notes, or the most recent ones without a query, optionally only those with a given link",
+        params: { query: Option<String>, link: Option<NoteLink>, limit: Option<u32> },
+        returns: Vec<Note>,
+        capabilities: [],
+    },
+    ai::get_ai_providers {
+        description: "List the configured AI providers in failover order and whether each has an API key",
+        params: {},
+        returns: Vec<ProviderInfo>,
+        capabilities: ["keychain"],
+    },
+    ai::set_ai_providers {
+        description: "Replace the AI providers; the list order is the failover order",
+        params: { providers: Vec<ProviderConfig> },
+        returns: (),
+        capabilities: ["settings"],
+    },
+    ai::set_provider_api_key {
+        description: "Store (or remove) an AI provider's API key in the OS keychain",
+        params: { id: String, api_key: Option<String> },
+        returns: (),
+        capabilities: ["keychain"],
+    },
+    ai::test_provider {
+        description: "Send a tiny prompt to one AI provider to check its key, model and reachability",
+        params: { id: String },
+        returns: ProviderTest,
+        capabilities: ["network", "keychain"],
+    },
+    ai::ai_chat {
+        description: "Answer a chat with the first enabled AI provider that responds, failing over down the list",
+        params: { request: ChatRequest },
+        returns: ChatResponse,
+        capabilities: ["network", "keychain"],
+    },
+    ai::ai_chat_stream {
+        description: "Stream a chat answer as ai-token events ending with ai-complete or ai-error; returns the request id",
+        params: { request: ChatRequest, request_id: Option<String> },
+        returns: String,
+        capabilities: ["network", "keychain"],
+    },
+    ai_tools::list_ai_tools {
+        description: "List the tools the AI can call in ai_chat_with_tools, with their argument schemas",
+        params: {},
+        returns: Vec<AiTool>,
+        capabilities: [],
+    },
+    ai_tools::ai_chat_with_tools {
+        description: "Chat letting the AI capture windows, read project files and run allowlisted commands, each checked against policy in the backend",
+        params: { request: ChatRequest, tools: Option<Vec<String>>, max_rounds: Option<u32> },
+        returns: ToolChatResponse,
+        capabilities: ["network", "keychain", "screen-capture", "ocr", "file-system", "command-execution"],
+    },
+    ai_usage::get_ai_usage {
+        description: "Requests, tokens and estimated cost of AI calls in a time range (default this month), per model",
+        params: { range: Option<UsageRange> },
+        returns: AiUsageReport,
+        capabilities: [],
+    },
+    ai_usage::get_ai_budget {
+        description: "Get the monthly AI budget and the model prices used to estimate cost",
+        params: {},
+        returns: AiBudgetSettings,
+        capabilities: [],
+    },
+    ai_usage::set_ai_budget {
+        description: "Change the monthly AI budget that blocks further paid requests, and the model prices",
+        params: { settings: AiBudgetSettings },
+        returns: (),
+        capabilities: ["settings"],
+    },
+    models::list_local_models {
+        description: "List the GGUF models downloaded for offline tutoring",
+        params: {},
+        returns: Vec<LocalModel>,
+        capabilities: ["file-system"],
+    },
+    models::download_model {
+        description: "Download a GGUF model, resuming an interrupted download, and keep it once its SHA-256 matches; emits model-download-progress",
+        params: { request: ModelDownloadRequest, request_id: Option<String> },
+        returns: LocalModel,
+        capabilities: ["network", "file-system"],
+    },
+    models::verify_model {
+        description: "Re-hash a downloaded model and compare it with its expected SHA-256",
+        params: { id: String },
+        returns: ModelVerification,
+        capabilities: ["file-system"],
+    },
+    models::delete_model {
+        description: "Delete a downloaded model or the partial file of an interrupted download",
+        params: { id: String },
+        returns: (),
+        capabilities: ["file-system"],
+    },
+    models::start_local_model {
+        description: "Load a downloaded model in-process with llama.cpp for the llamacpp AI provider",
+        params: { id: String },
+        returns: LoadedModelStatus,
+        capabilities: ["file-system"],
+    },
+    models::stop_local_model {
+        description: "Unload the local model",
+        params: {},
+        returns: (),
+        capabilities: [],
+    },
+    models::get_loaded_local_model {
+        description: "The local model loaded for the llamacpp AI provider, if any",
+        params: {},
+        returns: Option<LoadedModelStatus>,
+        capabilities: [],
+    },
+    secret_filter::filter_secrets {
+        description: "Mask API keys, passwords and other secrets in text before it is sent to an AI provider, reporting what was masked",
+        params: { text: String },
+        returns: FilteredText,
+        capabilities: [],
+    },
+    context_budget::count_tokens {
+        description: "Estimate how many tokens a text uses for a model (the configured model when omitted)",
+        params: { text: String, model: Option<String> },
+        returns: TokenCount,
+        capabilities: [],
+    },
+    context_budget::assemble_context {
+        description: "Join OCR and context sections into one text within a token budget, active window and newest first",
+        params: { sections: Vec<ContextSection>, max_tokens: Option<u32>, model: Option<String> },
+        returns: AssembledContext,
+        capabilities: [],
+    },
+    context_budget::get_context_budget {
+        description: "Get the default token budget and model for assembled context",
+        params: {},
+        returns: ContextBudgetSettings,
+        capabilities: [],
+    },
+    context_budget::set_context_budget {
+        description: "Change the default token budget and model for assembled context",
+        params: { settings: ContextBudgetSettings },
+        returns: (),
+        capabilities: ["settings"],
+    },
+    conversations::create_conversation {
+        description: "Start an AI chat conversation, optionally as part of a focus session",
+        params: { title: Option<String>, session_id: Option<String> },
+        returns: Conversation,
+        capabilities: [],
+    },
+    conversations::append_message {
+        description: "Add a message to the end of a conversation, optionally linked to the capture it was about",
+        params: { conversation_id: i64, message: NewMessage },
+        returns: ConversationMessage,
+        capabilities: [],
+    },
+    conversations::update_message {
+        description: "Replace the text of a stored chat message",
+        params: { id: i64, content: String },
+        returns: (),
+        capabilities: [],
+    },
+    conversations::get_conversation {
+        description: "Get a conversation with all its messages",
+        params: { id: i64 },
+        returns: Conversation,
+        capabilities: [],
+    },
+    conversations::list_conversations {
+        description: "List conversations, most recently active first, optionally only those of one focus session",
+        params: { session_id: Option<String>, limit: Option<u32> },
+        returns: Vec<ConversationSummary>,
+        capabilities: [],
+    },
+    conversations::search_conversations {
+        description: "Full-text search of chat messages, returning the conversations that contain them",
+        params: { query: String, limit: Option<u32> },
+        returns: Vec<ConversationSummary>,
+        capabilities: [],
+    },
+    conversations::branch_conversation {
+        description: "Copy a conversation up to a message into a new conversation that can continue differently",
+        params: { id: i64, message_id: i64, title: Option<String> },
+        returns: Conversation,
+        capabilities: [],
+    },
+    conversations::delete_conversation {
+        description: "Delete a conversation and its messages",
+        params: { id: i64 },
+        returns: (),
+        capabilities: [],
+    },
+    prompts::save_prompt {
+        description: "Save a prompt template with {{variable}} placeholders as a new version of its name",
+        params: { template: NewPromptTemplate },
+        returns: PromptTemplate,
+        capabilities: [],
+    },
+    prompts::get_prompt {
+        description: "Get the latest or a given version of a prompt template",
+        params: { name: String, version: Option<u32> },
+        returns: PromptTemplate,
+        capabilities: [],
+    },
+    prompts::list_prompts {
+        description: "List the latest version of every prompt template",
+        params: {},
+        returns: Vec<PromptTemplate>,
+        capabilities: [],
+    },
+    prompts::list_prompt_versions {
+        description: "List every saved version of a prompt template, newest first",
+        params: { name: String },
+        returns: Vec<PromptTemplate>,
+        capabilities: [],
+    },
+    prompts::delete_prompt {
+        description: "Delete a prompt template with all its versions",
+        params: { name: String },
+        returns: (),
+        capabilities: [],
+    },
+    prompts::render_prompt {
+        description: "Fill in a prompt template's variables, using their defaults where no value is given",
+        params: { name: String, vars: Option<HashMap<String, String>>, version: Option<u32> },
+        returns: String,
+        capabilities: [],
+    },
+    flashcards::add_card {
+        description: "Add a spaced-repetition flashcard, due right away",
+        params: { front: String, back: String, deck: Option<String>, note_id: Option<i64> },
+        returns: Flashcard,
+        capabilities: [],
+    },
+    flashcards::get_due_cards {
+        description: "Flashcards due for review, most overdue first",
+        params: { deck: Option<String>, limit: Option<u32> },
+        returns: Vec<Flashcard>,
+        capabilities: [],
+    },
+    flashcards::grade_card {
+        description: "Grade a review from 0 (blackout) to 5 (perfect) and schedule the card's next review (SM-2)",
+        params: { id: i64, grade: u8 },
+        returns: Flashcard,
+        capabilities: [],
+    },
+    flashcards::delete_card {
+        description: "Delete a flashcard and its review history",
+        params: { id: i64 },
+        returns: (),
+        capabilities: [],
+    },
+    exercises::register_exercise {
+        description: "Register an exercise (or update the one with the same key) so attempts at it can be tracked",
+        params: { exercise: NewExercise },
+        returns: Exercise,
+        capabilities: [],
+    },
+    exercises::list_exercises {
+        description: "List registered exercises, optionally of one topic",
+        params: { topic: Option<String> },
+        returns: Vec<Exercise>,
+        capabilities: [],
+    },
+    exercises::record_attempt {
+        description: "Record an attempt at an exercise and whether it was correct",
+        params: { exercise_id: i64, correct: bool, answer: Option<String>, duration_secs: Option<u32> },
+        returns: ExerciseAttempt,
+        capabilities: [],
+    },
+    exercises::get_attempts {
+        description: "An exercise's attempts, newest first",
+        params: { exercise_id: i64 },
+        returns: Vec<ExerciseAttempt>,
+        capabilities: [],
+    },
+    exercises::get_mastery {
+        description: "Mastery statistics per topic: exercises mastered, attempts, recent accuracy",
+        params: { topic: Option<String> },
+        returns: Vec<TopicMastery>,
+        capabilities: [],
+    },
+    curriculum::import_curriculum {
+        description: "Import a lesson plan (units, lessons, prerequisites) from a JSON or YAML file",
+        params: { path: String },
+        returns: CurriculumProgress,
+        capabilities: ["file-system"],
+    },
+    curriculum::list_curricula {
+        description: "List imported curricula with how many lessons are done",
+        params: {},
+        returns: Vec<CurriculumSummary>,
+        capabilities: [],
+    },
+    curriculum::get_curriculum {
+        description: "Get a curriculum with each lesson's completion state and the next recommended lesson",
+        params: { id: String },
+        returns: CurriculumProgress,
+        capabilities: [],
+    },
+    curriculum::set_lesson_completed {
+        description: "Mark a lesson done or not done",
+        params: { curriculum_id: String, lesson_id: String, completed: bool },
+        returns: CurriculumProgress,
+        capabilities: [],
+    },
+    curriculum::get_next_lesson {
+        description: "The next recommended lesson: the first one not done whose prerequisites are",
+        params: { curriculum_id: String },
+        returns: Option<LessonStatus>,
+        capabilities: [],
+    },
+    curriculum::delete_curriculum {
+        description: "Delete a curriculum and its progress",
+        params: { id: String },
+        returns: (),
+        capabilities: [],
+    },
+    achievements::get_achievements {
+        description: "Daily study streaks, time on task and badges with their progress",
+        params: {},
+        returns: Achievements,
+        capabilities: [],
+    },
+    daily_summary::get_daily_summary_settings {
+        description: "Whether and when the end-of-day summary is made",
+        params: {},
+        returns: DailySummarySettings,
+        capabilities: [],
+    },
+    daily_summary::set_daily_summary_settings {
+        description: "Enable or disable the end-of-day summary, set its time (HH:MM) and whether to ask for an AI narrative",
+        params: { settings: DailySummarySettings },
+        returns: (),
+        capabilities: [],
+    },
+    daily_summary::generate_daily_summary {
+        description: "Make the summary of a day (YYYY-MM-DD, default today) now and emit daily-summary-ready",
+        params: { day: Option<String> },
+        returns: DailySummary,
+        capabilities: [],
+    },
+    daily_summary::get_daily_summary {
+        description: "The stored summary of a day (YYYY-MM-DD, default today), if one was made",
+        params: { day: Option<String> },
+        returns: Option<DailySummary>,
+        capabilities: [],
+    },
+    daily_summary::set_daily_summary_narrative {
+        description: "Store the AI-written narrative for a day's summary",
+        params: { day: String, narrative: String },
+        returns: DailySummary,
+        capabilities: [],
+    },
+    data_export::export_all_data {
+        description: "Zip all of the active profile's data (settings, history database, documents, captures) to a file",
+        params: { path: String },
+        returns: DataExport,
+        capabilities: ["file-system"],
+    },
+    data_export::import_all_data {
+        description: "Replace the active profile's data with an export from export_all_data and restart the app",
+        params: { path: String },
+        returns: (),
+        capabilities: ["file-system"],
+    },
+    retention::get_retention_settings {
+        description: "How long captures and stored history text are kept",
+        params: {},
+        returns: RetentionSettings,
+        capabilities: [],
+    },
+    retention::set_retention_settings {
+        description: "Set how many days captures and stored history text are kept (null keeps them) and apply it now",
+        params: { settings: RetentionSettings },
+        returns: RetentionReport,
+        capabilities: ["file-system"],
+    },
+    retention::get_retention_report {
+        description: "What the last retention run deleted or anonymized",
+        params: {},
+        returns: Option<RetentionReport>,
+        capabilities: [],
+    },
+    retention::prepare_capture_purge {
+        description: "Count the saved captures and issue a one-minute token that confirms purge_all_captures",
+        params: {},
+        returns: PurgeConfirmation,
+        capabilities: ["file-system"],
+    },
+    retention::purge_all_captures {
+        description: "Delete every saved capture and recording of the active profile, given a token from prepare_capture_purge",
+        params: { token: String },
+        returns: RetentionReport,
+        capabilities: ["file-system"],
+    },
+    content_class::classify_screen_content {
+        description: "Label content (code, terminal, article, video, chat, ...) from window metadata and optional OCR text",
+        params: { window_title: String, process_name: String, ocr_text: Option<String> },
+        returns: ContentClassification,
+        capabilities: [],
+    },
+    content_class::get_content_time {
+        description: "Time spent on each kind of content over the last hours (default 24)",
+        params: { hours: Option<u32> },
+        returns: Vec<ContentTime>,
+        capabilities: ["monitoring"],
+    },
+    monitoring::list_monitors {
+        description: "List the running monitors",
+        params: {},
+        returns: Vec<MonitorInfo>,
+        capabilities: ["monitoring"],
+    },
+    narrator::start_narrator {
+        description: "Read new text appearing in a window aloud (or an AI summary of it), spaced at least min_gap_seconds apart; emits narration",
+        params: { target: MonitorTarget, options: Option<NarratorOptions> },
+        returns: NarratorInfo,
+        capabilities: ["screen-capture", "ocr"],
+        rate_limit: RateLimit { max_calls: 10, per_seconds: 60 },
+    },
+    narrator::stop_narrator {
+        description: "Stop the narrator and anything it is saying; returns whether it was running",
+        params: {},
+        returns: bool,
+        capabilities: [],
+    },
+    narrator::get_narrator {
+        description: "The running narrator's target and options, if any",
+        params: {},
+        returns: Option<NarratorInfo>,
+        capabilities: [],
+    },
+    commands::get_capture_interval {
+        description: "Get the monitoring capture interval in seconds",
+        params: {},
+        returns: u64,
+        capabilities: ["settings"],
+    },
+    commands::set_capture_interval {
+        description: "Set the monitoring capture interval (1-10 seconds)",
+        params: { interval: u64 },
+        returns: (),
+        capabilities: ["settings"],
+    },
+    commands::minimize_window {
+        description: "Minimize the main window",
+        params: {},
+        returns: (),
+        capabilities: ["window-control"],
+    },
+    commands::maximize_window {
+        description: "Toggle maximize on the main window",
+        params: {},
+        returns: (),
+        capabilities: ["window-control"],
+    },
+    commands::close_window {
+        description: "Close the main window",
+        params: {},
+        returns: (),
+        capabilities: ["window-control"],
+    },
+    commands::execute_command {
+        description: "Run an allowlisted command subject to the command policy",
+        params: { command: String, args: Vec<String> },
+        returns: CommandResult,
+        capabilities: ["command-execution"],
+    },
+    sandbox::get_sandbox_settings {
+        description: "CPU, memory, time and network limits applied to executed commands",
+        params: {},
+        returns: SandboxSettings,
+        capabilities: ["command-execution"],
+    },
+    sandbox::set_sandbox_settings {
+        description: "Change the limits applied to executed commands (teacher mode only)",
+        params: { settings: SandboxSettings },
+        returns: (),
+        capabilities: ["command-execution", "settings"],
+    },
+    script_runner::execute_script {
+        description: "Dry-run a multi-line script: each command with its policy verdict, plus a confirmation token when all are permitted",
+        params: { script: String },
+        returns: ScriptPlan,
+        capabilities: ["command-execution"],
+    },
+    script_runner::confirm_execution {
+        description: "Run a script previously analyzed by execute_script, using its one-time token",
+        params: { token: String },
+        returns: ScriptRun,
+        capabilities: ["command-execution"],
+    },
+    capture_diff::set_capture_diff_enabled {
+        description: "Diff consecutive OCR texts of the same window and attach added/removed lines to captures",
+        params: { enabled: bool },
+        returns: (),
+        capabilities: ["screen-capture", "ocr"],
+    },
+    capture_diff::get_capture_diff_enabled {
+        description: "Whether capture diffs are enabled",
+        params: {},
+        returns: bool,
+        capabilities: [],
+    },
+    homework::get_homework_detection_settings {
+        description: "Get the homework-detection mode settings",
+        params: {},
+        returns: HomeworkDetectionSettings,
+        capabilities: ["settings"],
+    },
+    homework::set_homework_detection {
+        description: "Enable or disable homework detection and session auto-start",
+        params: { settings: HomeworkDetectionSettings },
+        returns: (),
+        capabilities: ["settings"],
+    },
+    homework::detect_homework_context {
+        description: "Check window metadata and OCR text for an assignment context",
+        params: { options: HomeworkDetectionParams },
+        returns: Option<HomeworkContext>,
+        capabilities: ["window-info"],
+    },
+    connectivity::get_connectivity_status {
+        description: "Get the last known connectivity status of the AI endpoint",
+        params: {},
+        returns: ConnectivityStatus,
+        capabilities: ["network"],
+    },
+    connectivity::check_connectivity {
+        description: "Probe the AI endpoint now",
+        params: {},
+        returns: ConnectivityStatus,
+        capabilities: ["network"],
+    },
+    connectivity::set_connectivity_endpoint {
+        description: "Change the AI endpoint used for connectivity checks",
+        params: { endpoint: String },
+        returns: ConnectivityStatus,
+        capabilities: ["network", "settings"],
+    },
+    request_queue::enqueue_ai_request {
+        description: "Queue an AI request payload to be sent when the endpoint is reachable",
+        params: { kind: String, payload: serde_json::Value },
+        returns: PendingRequest,
+        capabilities: ["network"],
+    },
+    request_queue::get_pending_requests {
+        description: "List AI requests waiting for connectivity",
+        params: {},
+        returns: Vec<PendingRequest>,
+        capabilities: ["network"],
+    },
+    request_queue::complete_pending_request {
+        description: "Remove a queued request after it was sent successfully",
+        params: { id: String },
+        returns: (),
+        capabilities: ["network"],
+    },
+    request_queue::fail_pending_request {
+        description: "Put back a queued request whose send failed so it is retried",
+        params: { id: String },
+        returns: (),
+        capabilities: ["network"],
+    },
+    request_queue::cancel_pending_request {
+        description: "Drop a queued request without sending it",
+        params: { id: String },
+        returns: bool,
+        capabilities: ["network"],
+    },
+    process_manager::start_long_running_command {
+        description: "Start an allowlisted long-running command under the sandbox limits (no time limit) and stream its output",
+        params: { command: String, args: Vec<String> },
+        returns: JobInfo,
+        capabilities: ["command-execution"],
+    },
+    process_manager::list_running_jobs {
+        description: "List long-running commands that are still running",
+        params: {},
+        returns: Vec<JobInfo>,
+        capabilities: ["command-execution"],
+    },
+    process_manager::kill_job {
+        description: "Stop a long-running command",
+        params: { id: String },
+        returns: bool,
+        capabilities: ["command-execution"],
+    },
+    terminal::create_terminal {
+        description: "Open an interactive shell in a pseudo-terminal",
+        params: { options: CreateTerminalParams },
+        returns: TerminalInfo,
+        capabilities: ["command-execution"],
+    },
+    terminal::write_to_terminal {
+        description: "Send keystrokes to a terminal; lines that violate the command policy are cancelled",
+        params: { id: String, data: String },
+        returns: (),
+        capabilities: ["command-execution"],
+    },
+    terminal::resize_terminal {
+        description: "Resize a terminal to the given number of columns and rows",
+        params: { id: String, cols: u16, rows: u16 },
+        returns: (),
+        capabilities: ["command-execution"],
+    },
+    terminal::close_terminal {
+        description: "Close a terminal and kill its shell",
+        params: { id: String },
+        returns: bool,
+        capabilities: ["command-execution"],
+    },
+    terminal::list_terminals {
+        description: "List open terminals",
+        params: {},
+        returns: Vec<TerminalInfo>,
+        capabilities: ["command-execution"],
+    },
+    project_files::get_project_roots {
+        description: "List the project folders the user approved for reading",
+        params: {},
+        returns: Vec<String>,
+        capabilities: ["file-system"],
+    },
+    project_files::add_project_root {
+        description: "Approve a project folder for reading",
+        params: { path: String },
+        returns: Vec<String>,
+        capabilities: ["file-system"],
+    },
+    project_files::remove_project_root {
+        description: "Revoke access to an approved project folder",
+        params: { path: String },
+        returns: bool,
+        capabilities: ["file-system"],
+    },
+    project_files::read_project_file {
+        description: "Read a text file inside an approved project folder",
+        params: { path: String },
+        returns: ProjectFile,
+        capabilities: ["file-system"],
+    },
+    project_files::list_directory {
+        description: "List a directory inside an approved project folder",
+        params: { path: String },
+        returns: Vec<DirectoryEntry>,
+        capabilities: ["file-system"],
+    },
+    project_files::search_in_files {
+        description: "Search text files under an approved project folder",
+        params: { root: String, query: String },
+        returns: Vec<SearchMatch>,
+        capabilities: ["file-system"],
+    },
+    project_analysis::analyze_project {
+        description: "Detect the project type and framework in an approved folder and suggest its install, run and test commands",
+        params: { root: String },
+        returns: ProjectAnalysis,
+        capabilities: ["file-system"],
+    },
+    file_watcher::start_file_watcher {
+        description: "Watch the approved project folders and emit file-changed events",
+        params: {},
+        returns: FileWatcherStatus,
+        capabilities: ["file-system"],
+    },
+    file_watcher::stop_file_watcher {
+        description: "Stop watching project folders",
+        params: {},
+        returns: (),
+        capabilities: ["file-system"],
+    },
+    file_watcher::get_file_watcher_status {
+        description: "Whether the file watcher is running and which folders it watches",
+        params: {},
+        returns: FileWatcherStatus,
+        capabilities: ["file-system"],
+    },
+    git_insight::get_git_status {
+        description: "Branch, upstream and changed files of a git repository in an approved project folder",
+        params: { repo: String },
+        returns: GitStatus,
+        capabilities: ["file-system"],
+    },
+    git_insight::get_git_diff {
+        description: "Staged or unstaged changes of a git repository, per file with patches",
+        params: { repo: String, staged: bool },
+        returns: GitDiff,
+        capabilities: ["file-system"],
+    },
+    git_insight::get_recent_commits {
+        description: "The last n commits of a git repository, newest first",
+        params: { repo: String, n: usize },
+        returns: Vec<GitCommit>,
+        capabilities: ["file-system"],
+    },
+    activity::set_activity_tracking {
+        description: "Opt in to counting keystrokes and clicks per minute (never their content); needs monitoring consent",
+        params: { enabled: bool },
+        returns: (),
+        capabilities: ["activity-tracking"],
+    },
+    activity::get_activity_metrics {
+        description: "Keystroke and click counts per minute for a time range",
+        params: { range: ActivityRange },
+        returns: ActivityMetrics,
+        capabilities: ["activity-tracking"],
+    },
+    typing_practice::start_typing_session {
+        description: "Start a typing lesson: read the passage in a practice window with OCR, then count and time keystrokes typed into it (never which keys)",
+        params: { window_title: Option<String>, process_name: Option<String> },
+        returns: TypingSessionStats,
+        capabilities: ["screen-capture", "ocr", "activity-tracking"],
+    },
+    typing_practice::get_typing_session_stats {
+        description: "Typing speed, accuracy and progress of the current or last typing session",
+        params: {},
+        returns: Option<TypingSessionStats>,
+        capabilities: ["activity-tracking"],
+    },
+    typing_practice::stop_typing_session {
+        description: "End the typing session and return its final stats",
+        params: {},
+        returns: Option<TypingSessionStats>,
+        capabilities: ["activity-tracking"],
+    },
+    focus_timer::start_focus_session {
+        description: "Start a pomodoro-style focus session alternating focus and break phases",
+        params: { minutes: u32, break_minutes: u32, pause_monitoring_during_breaks: Option<bool> },
+        returns: FocusSession,
+        capabilities: ["notifications"],
+    },
+    focus_timer::pause_focus_session {
+        description: "Pause the running focus session",
+        params: {},
+        returns: FocusSession,
+        capabilities: [],
+    },
+    focus_timer::resume_focus_session {
+        description: "Resume a paused focus session",
+        params: {},
+        returns: FocusSession,
+        capabilities: [],
+    },
+    focus_timer::stop_focus_session {
+        description: "End the focus session",
+        params: {},
+        returns: bool,
+        capabilities: [],
+    },
+    focus_timer::get_focus_session {
+        description: "The current focus session, if any",
+        params: {},
+        returns: Option<FocusSession>,
+        capabilities: [],
+    },
+    distraction::get_distraction_rules {
+        description: "The study/distraction classification rules",
+        params: {},
+        returns: DistractionRules,
+        capabilities: [],
+    },
+    distraction::set_distraction_rules {
+        description: "Replace the study/distraction classification rules",
+        params: { rules: DistractionRules },
+        returns: (),
+        capabilities: [],
+    },
+    distraction::classify_activity {
+        description: "Classify a window or URL as study, neutral or distraction and update the streak",
+        params: { window_title: String, process_name: String, url: Option<String> },
+        returns: Classification,
+        capabilities: [],
+    },
+    distraction::get_activity_streak {
+        description: "How long the current study/neutral/distraction streak has lasted",
+        params: {},
+        returns: Option<StreakStatus>,
+        capabilities: [],
+    },
+    sync::get_sync_settings {
+        description: "Dashboard sync settings",
+        params: {},
+        returns: SyncSettings,
+        capabilities: [],
+    },
+    sync::set_sync_settings {
+        description: "Configure pushing anonymized usage summaries to a teacher/parent dashboard",
+        params: { enabled: bool, endpoint: Option<String>, interval_minutes: u32 },
+        returns: SyncSettings,
+        capabilities: ["network"],
+    },
+    sync::set_sync_token {
+        description: "Store (or remove) the dashboard auth token in the OS keychain",
+        params: { token: Option<String> },
+        returns: (),
+        capabilities: ["keychain"],
+    },
+    sync::queue_session_report {
+        description: "Queue a session report for the next dashboard sync",
+        params: { report: serde_json::Value },
+        returns: String,
+        capabilities: [],
+    },
+    sync::sync_now {
+        description: "Push the usage summary and queued session reports immediately",
+        params: {},
+        returns: SyncStatus,
+        capabilities: ["network"],
+        rate_limit: RateLimit { max_calls: 6, per_seconds: 60 },
+    },
+    sync::get_sync_status {
+        description: "Result of the last dashboard sync and number of queued reports",
+        params: {},
+        returns: SyncStatus,
+        capabilities: [],
+    },
+    ws_server::get_integration_server_status {
+        description: "Whether the local integration server is running, with its URL and token",
+        params: {},
+        returns: IntegrationServerStatus,
+        capabilities: [],
+    },
+    ws_server::set_integration_server {
+        description: "Start or stop the token-protected local WebSocket server for external tools; prometheus also serves /metrics on it",
+        params: { enabled: bool, port: Option<u16>, prometheus: Option<bool> },
+        returns: IntegrationServerStatus,
+        capabilities: ["network", "screen-capture"],
+    },
+    ws_server::regenerate_integration_token {
+        description: "Issue a new integration server token, disconnecting existing clients",
+        params: {},
+        returns: IntegrationServerStatus,
+        capabilities: [],
+    },
+    remote_view::get_remote_view_status {
+        description: "Whether a remote view session is running, with its one-time code and URLs",
+        params: {},
+        returns: RemoteViewStatus,
+        capabilities: [],
+    },
+    remote_view::start_remote_view {
+        description: "Let a tutor watch the monitored frames from a browser, using a one-time code; localhost only unless local_network is set",
+        params: { port: Option<u16>, local_network: Option<bool> },
+        returns: RemoteViewStatus,
+        capabilities: ["network", "screen-capture"],
+    },
+    remote_view::stop_remote_view {
+        description: "End the remote view session and disconnect the viewer",
+        params: {},
+        returns: RemoteViewStatus,
+        capabilities: [],
+    },
+    diagnostics::run_diagnostics {
+        description: "Self-test screen capture, OCR, PowerShell, disk space and AI endpoint access",
+        params: {},
+        returns: DiagnosticsReport,
+        capabilities: ["screen-capture", "ocr", "network"],
+    },
+    metrics::get_metrics {
+        description: "Capture, OCR and AI call counts and latency histograms since start, the OCR cache hit rate and the event queue depth",
+        params: {},
+        returns: Metrics,
+        capabilities: [],
+    },
+    benchmark::run_benchmark {
+        description: "Time full-screen capture, PNG encoding, OCR and capture_window_with_ocr on this machine, returning percentiles per stage; cancel with request_id",
+        params: { iterations: Option<u32>, request_id: Option<String> },
+        returns: BenchmarkReport,
+        capabilities: ["screen-capture", "ocr"],
+        rate_limit: RateLimit { max_calls: 2, per_seconds: 60 },
+    },
+    debug_bundle::create_debug_bundle {
+        description: "Zip recent logs, crash reports, scrubbed settings, the command policy and diagnostics for a bug report; the last capture is added only with consent (redacted or full)",
+        params: { screenshot: Option<ScreenshotConsent> },
+        returns: DebugBundle,
+        capabilities: ["file-system", "screen-capture", "ocr", "network"],
+    },
+    crash_report::get_last_crash_report {
+        description: "Get the most recent backend panic report, including ones from earlier runs",
+        params: {},
+        returns: Option<CrashReport>,
+        capabilities: [],
+    },
+    watchdog::get_subsystem_health {
+        description: "Health of the background subsystems (screen monitors, narrator, distraction watcher, OCR workers) with their restart counts",
+        params: {},
+        returns: Vec<SubsystemHealth>,
+        capabilities: [],
+    },
+    logging::set_log_level {
+        description: "Change the backend log level (trace, debug, info, warn, error, off)",
+        params: { level: String },
+        returns: (),
+        capabilities: [],
+    },
+    profiles::list_profiles {
+        description: "List student profiles and the active one",
+        params: {},
+        returns: ProfileList,
+        capabilities: [],
+    },
+    profiles::create_profile {
+        description: "Create a student profile with its own settings, history, captures and usage stats",
+        params: { name: String },
+        returns: Profile,
+        capabilities: ["settings"],
+    },
+    profiles::switch_profile {
+        description: "Switch to another profile; the app restarts to load its data",
+        params: { id: String },
+        returns: (),
+        capabilities: ["settings"],
+    },
+    roles::get_role_status {
+        description: "Get whether the app is in student or teacher mode and whether a teacher PIN is set",
+        params: {},
+        returns: RoleStatus,
+        capabilities: [],
+    },
+    roles::set_teacher_pin {
+        description: "Set or change the 4-8 digit teacher PIN; once set, the app starts in student mode",
+        params: { current_pin: Option<String>, new_pin: String },
+        returns: RoleStatus,
+        capabilities: ["settings"],
+    },
+    roles::unlock_teacher_mode {
+        description: "Enter teacher mode for 30 minutes with the teacher PIN",
+        params: { pin: String },
+        returns: RoleStatus,
+        capabilities: [],
+    },
+    roles::lock_teacher_mode {
+        description: "Leave teacher mode and return to student mode",
+        params: {},
+        returns: RoleStatus,
+        capabilities: [],
+    },
+    roles::get_role_policy {
+        description: "Get the programs students may run and the commands reserved for teacher mode",
+        params: {},
+        returns: RolePolicy,
+        capabilities: ["settings"],
+    },
+    roles::set_role_policy {
+        description: "Change the active profile's student permissions (teacher mode only)",
+        params: { policy: RolePolicy },
+        returns: (),
+        capabilities: ["settings"],
+    },
+    updater::check_for_updates {
+        description: "Check the configured update channel for a newer build; null when up to date",
+        params: {},
+        returns: Option<UpdateInfo>,
+        capabilities: ["network"],
+    },
+    updater::install_update {
+        description: "Download and install the available update (emitting update-progress), then restart",
+        params: {},
+        returns: (),
+        capabilities: ["network"],
+    },
+    updater::get_update_settings {
+        description: "Get the update channel (stable/beta) and whether updates are checked at startup",
+        params: {},
+        returns: UpdateSettings,
+        capabilities: ["settings"],
+    },
+    updater::set_update_settings {
+        description: "Set the update channel (stable/beta) and whether updates are checked at startup",
+        params: { settings: UpdateSettings },
+        returns: (),
+        capabilities: ["settings"],
+    },
+    logging::get_recent_logs {
+        description: "Get the most recent backend log entries, oldest first",
+        params: { n: usize },
+        returns: Vec<LogEntry>,
+        capabilities: [],
+    },
+    event_bus::get_missed_events {
+        description: "Events emitted after a sequence number, to catch up after the webview reloads",
+        params: { since_seq: u64 },
+        returns: MissedEvents,
+        capabilities: [],
+    },
+    command_catalog::list_available_commands {
+        description: "List every backend command with its parameters and requirements",
+        params: {},
+        returns: CommandCatalog,
+        capabilities: [],
+    },
+}
+
+pub fn catalog() -> CommandCatalog {
+    let mut gen = schema_generator();
+    let commands = catalog_with(&mut gen);
+    CommandCatalog {
+        commands,
+        definitions: definitions_json(&gen),
+    }
+}
+
+#[tauri::command]
+pub async fn list_available_commands() -> Result<CommandCatalog, String> {
+    Ok(catalog())
+}
@@ -1,10 +1,27 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use tauri::{AppHandle, Emitter, Manager, State};
+use tauri::{AppHandle, Manager, State};
 use std::fs;
 use std::path::PathBuf;
 use base64::{engine::general_purpose, Engine as _};
 
+use crate::cancellation::{CancellationState, OperationGuard, CANCELLED};
+#[cfg(target_os = "windows")]
+use crate::capture_backend::{CaptureBackend, CaptureBackendState};
+use crate::capture_diff::{CaptureDiff, CaptureDiffState};
+use crate::capture_progress::{self, CaptureStage};
+use crate::content_class::{ContentClassState, ContentClassification};
+use crate::frame_delta::FrameDelta;
+use crate::frame_validation::CaptureMethod;
+use crate::metrics::MetricsState;
+use crate::ocr_cache::OcrCacheState;
+use crate::ocr_layout::OcrWord;
 use crate::screen_capture::{ScreenCapture, ScreenCaptureState};
+use crate::translation::Translation;
+use crate::window_elements::{ChildTarget, UiElement};
+use crate::window_match::MatchMode;
+#[cfg(target_os = "windows")]
+use crate::window_match::WindowMatcher;
 
 /// Helper function to extract JSON from PowerShell output which may contain extra text
 fn extract_json_from_output(output: &str) -> String {
@@ -62,14 +79,26 @@ fn extract_json_from_output(output: &str) -> String {
     output.trim().to_string()
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct CaptureResult {
     pub image_base64: String,
     pub hash: String,
     pub timestamp: i64,
+    /// Text changes in the active window since the previous capture (when capture diff is enabled)
+    #[serde(default)]
+    pub diff: Option<CaptureDiff>,
+    /// Set in delta mode; `image_base64` is then empty unless this is a keyframe
+    #[serde(default)]
+    pub delta: Option<FrameDelta>,
+    /// Text of the frame, from monitors started with `ocr: true` and webcam frames taken with `ocr`
+    #[serde(default)]
+    pub ocr_text: Option<String>,
+    /// What kind of content the frame shows (set by monitors)
+    #[serde(default)]
+    pub classification: Option<ContentClassification>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct WindowCaptureResult {
     pub image_base64: String,
     pub hash: String,
@@ -77,14 +106,29 @@ pub struct WindowCaptureResult {
     pub ocr_text: Option<String>,
     pub window_title: String,
     pub process_name: String,
+    #[serde(default)]
+    pub diff: Option<CaptureDiff>,
+    /// What kind of content the window shows (set when OCR runs)
+    #[serde(default)]
+    pub classification: Option<ContentClassification>,
+    /// How the image was produced (PrintWindow, or a fallback when that came back blank)
+    #[serde(default)]
+    pub capture_method: Option<CaptureMethod>,
+    /// Every capture method returned a blank, single-color image
+    #[serde(default)]
+    pub blank_frame: bool,
+    /// The child element (tab, pane) the image was cropped to
+    #[serde(default)]
+    pub element: Option<UiElement>,
+    /// The OCR text translated, when `translate_to` was given
+    #[serde(default)]
+    pub translation: Option<Translation>,
 }
 
 #[tauri::command]
-pub async fn capture_screen(
-    state: State<'_, ScreenCaptureState>,
-) -> Result<CaptureResult, String> {
+pub async fn capture_screen(app: AppHandle) -> Result<CaptureResult, String> {
     let capture = ScreenCapture::new();
-    capture.capture_full_screen(state.inner()).await
+    capture.capture_full_screen(&app).await
 }
 
 #[tauri::command]
@@ -108,14 +152,14 @@ pub async fn get_active_window() -> Result<String, String> {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct WindowInfo {
     pub title: String,
     pub process_name: String,
     pub is_active: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct SystemContext {
     pub active_window: String,
     pub active_window_title: String,
@@ -322,23 +366,87 @@ pub async fn get_system_context() -> Result<SystemContext, String> {
 
 /// Extract text from an image using Windows OCR
 #[tauri::command]
-pub async fn extract_text_from_image(image_base64: String) -> Result<String, String> {
+pub async fn extract_text_from_image(
+    app: AppHandle,
+    image_base64: String,
+    request_id: Option<String>,
+) -> Result<String, String> {
+    let operation = app.state::<CancellationState>().register(request_id.as_deref());
+    ocr_image(&app, image_base64, &operation).await
+}
+
+/// Run OCR as part of an operation that `cancel_operation` can abort
+pub(crate) async fn ocr_image(
+    app: &AppHandle,
+    image_base64: String,
+    operation: &OperationGuard,
+) -> Result<String, String> {
+    run_ocr(app, image_base64, operation, false).await
+}
+
+/// OCR words with their bounding boxes, in reading order of the OCR lines
+pub(crate) async fn ocr_words(
+    app: &AppHandle,
+    image_base64: String,
+    operation: &OperationGuard,
+) -> Result<Vec<OcrWord>, String> {
+    let json = run_ocr(app, image_base64, operation, true).await?;
+    if json.is_empty() {
+        return Ok(Vec::new());
+    }
+    serde_json::from_str(&json).map_err(|e| format!("Failed to parse OCR word boxes: {}", e))
+}
+
+/// OCR an image, answering from the cache when the same image was read recently. Returns the
+/// recognized text, or with `word_boxes` a JSON array of words and their bounding rectangles.
+async fn run_ocr(
+    app: &AppHandle,
+    image_base64: String,
+    operation: &OperationGuard,
+    word_boxes: bool,
+) -> Result<String, String> {
+    let cache = app.state::<OcrCacheState>();
+    let metrics = app.state::<MetricsState>();
+    let key = OcrCacheState::key(&image_base64, word_boxes);
+    if let Some(text) = cache.get(&key) {
+        metrics.increment(crate::metrics::OCR_CACHE_HITS);
+        return Ok(text);
+    }
+    metrics.increment(crate::metrics::OCR_CACHE_MISSES);
+    let text = crate::metrics::timed(app, crate::metrics::OCR, run_ocr_once(app, image_base64, operation, word_boxes)).await?;
+    cache.insert(key, text.clone());
+    Ok(text)
+}
+
+/// Run the Windows OCR script, through the native helper when it is available
+async fn run_ocr_once(
+    app: &AppHandle,
+    image_base64: String,
+    operation: &OperationGuard,
+    word_boxes: bool,
+) -> Result<String, String> {
+    operation.check()?;
     #[cfg(target_os = "windows")]
     {
-        use std::process::Command;
         use base64::{engine::general_purpose, Engine as _};
+        use crate::powershell_host::PowerShellHostState;
+        use crate::temp_files::TempFilesState;
+        
+        // The native helper runs OCR in-process, without a temp file or PowerShell
+        let params = serde_json::json!({ "image_base64": image_base64, "word_boxes": word_boxes });
+        if let Some(result) = crate::native_helper::call::<String>(app, "ocr", params, Some(operation)).await {
+            return result.map(|text| text.trim().to_string());
+        }
         
         // Decode base64 image
         let image_bytes = general_purpose::STANDARD
             .decode(&image_base64)
             .map_err(|e| format!("Failed to decode base64 image: {}", e))?;
         
-        // Save to temp file for OCR
-        let temp_path = std::env::temp_dir().join(format!("ocr_temp_{}.png", 
-            std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap()
-                .as_secs()));
+        // Save to temp file for OCR (deleted when `temp_image` drops)
+        let temp_files = app.state::<TempFilesState>();
+        let temp_image = temp_files.create("ocr_temp_", "png");
+        let temp_path = temp_image.path();
         
         std::fs::write(&temp_path, &image_bytes)
             .map_err(|e| format!("Failed to write temp image: {}", e))?;
@@ -422,6 +530,7 @@ pub async fn extract_text_from_image(image_base64: String) -> Result<String, Str
                 [Windows.Storage.StorageFile, Windows.Storage, ContentType=WindowsRuntime] | Out-Null
                 
                 $imagePath = '{}'
+                $emitBoxes = ${}
                 [Console]::Error.WriteLine("[OCR] Loading image from: $imagePath")
                 
                 # Verify file exists before proceeding
@@ -497,15 +606,22 @@ pub async fn extract_text_from_image(image_base64: String) -> Result<String, Str
                 
                 # Extract text from all lines and words with proper null checking
                 $words = @()
+                $boxes = @()
+                $lineIndex = 0
                 if ($null -ne $ocrResult -and $lineCount -gt 0) {{
                     foreach ($line in $ocrResult.Lines) {{
                         if ($null -ne $line -and $null -ne $line.Words) {{
                             foreach ($word in $line.Words) {{
                                 if ($null -ne $word -and $null -ne $word.Text -and $word.Text.Trim() -ne "") {{
                                     $words += $word.Text
+                                    if ($emitBoxes) {{
+                                        $rect = $word.BoundingRect
+                                        $boxes += [pscustomobject]@{{ text = $word.Text; x = $rect.X; y = $rect.Y; width = $rect.Width; height = $rect.Height; line = $lineIndex }}
+                                    }}
                                 }}
                             }}
                         }}
+                        $lineIndex++
                     }}
                 }}
                 $text = $words -join " "
@@ -525,7 +641,11 @@ pub async fn extract_text_from_image(image_base64: String) -> Result<String, Str
                 Remove-Item $imagePath -ErrorAction SilentlyContinue
                 
                 # Use Write-Output to ensure text goes to stdout
-                Write-Output $text
+                if ($emitBoxes) {{
+                    Write-Output (ConvertTo-Json -InputObject @($boxes) -Compress)
+                }} else {{
+                    Write-Output $text
+                }}
                 # Also write to stderr for debugging (will be filtered out)
                 [Console]::Error.WriteLine("[OCR] Text written to stdout: $($text.Length) chars")
             }} catch {{
@@ -537,95 +657,67 @@ pub async fn extract_text_from_image(image_base64: String) -> Result<String, Str
                 }}
                 Write-Output ""
             }}
-        "#, escaped_path);
-        
-        eprintln!("[extract_text_from_image] 🔍 Running OCR on image: {} bytes", image_bytes.len());
-        eprintln!("[extract_text_from_image] 📁 Temp file: {:?}", temp_path);
+        "#, escaped_path, word_boxes);
         
-        // Write script to temp file to avoid command-line length limits and permission issues
-        let script_path = std::env::temp_dir().join(format!("ocr_script_{}.ps1", 
-            std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap()
-                .as_secs()));
+        tracing::info!("[extract_text_from_image] 🔍 Running OCR on image: {} bytes", image_bytes.len());
+        tracing::debug!("[extract_text_from_image] 📁 Temp file: {:?}", temp_path);
         
-        let script_path_abs = script_path.canonicalize()
-            .unwrap_or_else(|_| script_path.clone());
+        tracing::debug!("[extract_text_from_image] 📜 Script size: {} bytes", ocr_script.len());
         
-        std::fs::write(&script_path, &ocr_script)
-            .map_err(|e| format!("Failed to write OCR script to temp file: {}", e))?;
-        
-        eprintln!("[extract_text_from_image] 📜 Script written to: {:?}", script_path_abs);
-        eprintln!("[extract_text_from_image] 📜 Script size: {} bytes", ocr_script.len());
-        
-        // Execute PowerShell script with UTF-8 output encoding
-        let output = Command::new("powershell")
-            .arg("-NoProfile")
-            .arg("-ExecutionPolicy")
-            .arg("Bypass")
-            .arg("-Command")
-            .arg(format!(
-                r#"$OutputEncoding = [System.Text.Encoding]::UTF8; [Console]::OutputEncoding = [System.Text.Encoding]::UTF8; & '{}'"#,
-                script_path_abs.to_string_lossy().replace('\'', "''")
-            ))
-            .stderr(std::process::Stdio::piped()) // Capture stderr to see errors
-            .stdout(std::process::Stdio::piped()) // Capture stdout
-            .output()
-            .map_err(|e| format!("Failed to execute OCR PowerShell: {}", e))?;
-        
-        // Don't clean up script file immediately - keep for debugging
-        // let _ = std::fs::remove_file(&script_path);
+        // Run in a warm PowerShell host; the WinRT types and AsyncHelper stay loaded between calls
+        let host = app.state::<PowerShellHostState>();
+        let output = host.run(&ocr_script, Some(operation), |_| {}).await?;
         
         // Log stderr for debugging - always show it
-        let stderr_str = String::from_utf8_lossy(&output.stderr);
-        eprintln!("[extract_text_from_image] 📋 PowerShell exit code: {:?}", output.status.code());
-        eprintln!("[extract_text_from_image] 📋 PowerShell stdout length: {} bytes", output.stdout.len());
-        eprintln!("[extract_text_from_image] 📋 PowerShell stderr length: {} bytes", output.stderr.len());
+        let stderr_str = output.stderr.as_str();
+        tracing::debug!("[extract_text_from_image] 📋 PowerShell exit code: {}", output.exit_code);
+        tracing::debug!("[extract_text_from_image] 📋 PowerShell stdout length: {} bytes", output.stdout.len());
+        tracing::debug!("[extract_text_from_image] 📋 PowerShell stderr length: {} bytes", output.stderr.len());
         
         if !stderr_str.trim().is_empty() {
-            eprintln!("[extract_text_from_image] ⚠️ PowerShell stderr output:");
-            eprintln!("{}", stderr_str);
+            tracing::warn!("[extract_text_from_image] ⚠️ PowerShell stderr output:");
+            tracing::debug!("{}", stderr_str);
         } else {
-            eprintln!("[extract_text_from_image] ℹ️ No stderr output from PowerShell");
+            tracing::debug!("[extract_text_from_image] ℹ️ No stderr output from PowerShell");
         }
         
-        // Clean up temp file
-        let _ = std::fs::remove_file(&temp_path);
+        // Clean up temp files
+        drop(temp_image);
+        operation.check()?;
         
-        if !output.status.success() {
-            let error_msg = format!("OCR command failed with status: {:?}. Stderr: {}", 
-                output.status.code(), 
+        if !output.success() {
+            let error_msg = format!("OCR command failed with status: {}. Stderr: {}", 
+                output.exit_code, 
                 stderr_str
             );
-            eprintln!("[extract_text_from_image] ❌ {}", error_msg);
+            tracing::error!("[extract_text_from_image] ❌ {}", error_msg);
             return Err(error_msg);
         }
         
-        // Read stdout as UTF-8 (PowerShell with UTF-8 encoding should output UTF-8)
-        let ocr_text = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        let ocr_text = output.stdout.trim().to_string();
         
-        eprintln!("[extract_text_from_image] 📊 OCR stdout length: {} bytes", output.stdout.len());
-        eprintln!("[extract_text_from_image] 📝 OCR text length: {} characters", ocr_text.len());
+        tracing::info!("[extract_text_from_image] 📊 OCR stdout length: {} bytes", output.stdout.len());
+        tracing::info!("[extract_text_from_image] 📝 OCR text length: {} characters", ocr_text.len());
         
         // Debug: Show raw stdout bytes (first 200 bytes) if empty
-        if ocr_text.is_empty() && output.stdout.len() > 0 {
-            let preview_bytes: String = output.stdout.iter().take(200).enumerate().map(|(i, b)| {
+        if ocr_text.is_empty() && !output.stdout.is_empty() {
+            let preview_bytes: String = output.stdout.bytes().take(200).enumerate().map(|(i, b)| {
                 if i > 0 && i % 16 == 0 { format!("\n  {:04x}: {:02x} ", i, b) }
                 else { format!("{:02x} ", b) }
             }).collect();
-            eprintln!("[extract_text_from_image] 🔍 Raw stdout bytes:\n  {:04x}: {}", 0, preview_bytes);
+            tracing::debug!("[extract_text_from_image] 🔍 Raw stdout bytes:\n  {:04x}: {}", 0, preview_bytes);
         }
         
         if ocr_text.is_empty() {
-            eprintln!("[extract_text_from_image] ⚠️ OCR returned empty text.");
+            tracing::warn!("[extract_text_from_image] ⚠️ OCR returned empty text.");
             if !stderr_str.trim().is_empty() {
-                eprintln!("[extract_text_from_image] Check stderr output above for errors.");
+                tracing::debug!("[extract_text_from_image] Check stderr output above for errors.");
             } else {
-                eprintln!("[extract_text_from_image] Possible reasons:");
-                eprintln!("  - Image contains no readable text");
-                eprintln!("  - OCR engine couldn't detect text");
-                eprintln!("  - Language pack not installed");
-                eprintln!("  - Image quality too low");
+                tracing::debug!("[extract_text_from_image] Possible reasons:");
+                tracing::debug!("  - Image contains no readable text");
+                tracing::debug!("  - OCR engine couldn't detect text");
+                tracing::debug!("  - Language pack not installed");
+                tracing::debug!("  - Image quality too low");
             }
         } else {
             let preview = if ocr_text.len() > 100 {
@@ -633,7 +725,7 @@ pub async fn extract_text_from_image(image_base64: String) -> Result<String, Str
             } else {
                 ocr_text.clone()
             };
-            eprintln!("[extract_text_from_image] ✅ OCR preview: {}", preview);
+            tracing::info!("[extract_text_from_image] ✅ OCR preview: {}", preview);
         }
         
         Ok(ocr_text)
@@ -641,356 +733,438 @@ pub async fn extract_text_from_image(image_base64: String) -> Result<String, Str
     
     #[cfg(not(target_os = "windows"))]
     {
+        let _ = (app, word_boxes);
         Err("OCR not implemented for this platform".to_string())
     }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize, JsonSchema)]
 pub struct CaptureWindowParams {
     #[serde(default)]
     pub process_name: Option<String>,
     #[serde(default)]
     pub window_title: Option<String>,
+    /// Key for `capture-progress` events; no events are emitted without one
+    #[serde(default)]
+    pub request_id: Option<String>,
+    /// Also match compatibility forms, e.g. full-width "Ｗｏｒｄ" for "Word"
+    #[serde(default)]
+    pub normalize: bool,
+    /// Ignore accents and other diacritics, e.g. "resume" for "Résumé"
+    #[serde(default)]
+    pub ignore_diacritics: bool,
+    /// How both `process_name` and `window_title` match, e.g. `regex` for
+    /// "python.*manage.py runserver"
+    #[serde(default)]
+    pub match_mode: Option<MatchMode>,
+    #[serde(default)]
+    pub case_sensitive: bool,
+    /// Capture only this element of the window, e.g. one browser tab's page or one terminal pane
+    #[serde(default)]
+    #[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+    pub child: Option<ChildTarget>,
+    /// Translate the OCR text into this language, e.g. "en" (`capture_window_with_ocr` only)
+    #[serde(default)]
+    pub translate_to: Option<String>,
 }
 
 /// List all windows matching a process name or window title
 #[tauri::command]
 pub async fn list_windows_by_process(
+    app: AppHandle,
     options: CaptureWindowParams,
 ) -> Result<Vec<WindowInfo>, String> {
-    let process_name = options.process_name;
-    let window_title = options.window_title;
-    
     #[cfg(target_os = "windows")]
     {
-        use std::process::Command;
-        
-        // Build match condition
-        let match_condition = if let Some(ref proc) = process_name {
-            if let Some(ref title) = window_title {
-                format!(
-                    r#"$match = ($process.ProcessName -ieq '{}') -and ($title -ilike '*{}*')"#,
-                    proc.replace("'", "''"), title.replace("'", "''")
-                )
-            } else {
-                format!(
-                    r#"$procName = $process.ProcessName
-                    $searchName = '{}'
-                    $procNameLower = $procName.ToLower()
-                    $searchNameLower = $searchName.ToLower()
-                    $match = ($procNameLower -eq $searchNameLower) -or ($procNameLower -eq ($searchNameLower + '.exe')) -or ($procNameLower -like ('*' + $searchNameLower + '*'))"#,
-                    proc.replace("'", "''")
-                )
-            }
-        } else if let Some(ref title) = window_title {
-            format!(r#"$match = $title -ilike '*{}*'"#, title.replace("'", "''"))
-        } else {
-            "$match = $true".to_string()
-        };
-        
-        // Build PowerShell script to list all matching windows
-        let list_script = format!(r#"
-            $ErrorActionPreference = 'Continue'
-            Add-Type @"
-                using System;
-                using System.Runtime.InteropServices;
-                using System.Text;
-                public class Win32 {{
-                    [DllImport("user32.dll")]
-                    public static extern bool EnumWindows(EnumWindowsProc enumProc, IntPtr lParam);
-                    [DllImport("user32.dll")]
-                    public static extern IntPtr GetForegroundWindow();
-                    [DllImport("user32.dll")]
-                    public static extern int GetWindowText(IntPtr hWnd, StringBuilder text, int count);
-                    [DllImport("user32.dll")]
-                    public static extern uint GetWindowThreadProcessId(IntPtr hWnd, out uint processId);
-                    [DllImport("user32.dll")]
-                    public static extern bool IsWindowVisible(IntPtr hWnd);
-                    public delegate bool EnumWindowsProc(IntPtr hWnd, IntPtr lParam);
-                }}
-"@
-            $windows = New-Object System.Collections.ArrayList
-            $foreground = [Win32]::GetForegroundWindow()
-            $enumCount = 0
-            $checkedCount = 0
-            
-            [Win32]::EnumWindows({{
-                param($hWnd, $lParam)
-                $script:enumCount++
-                if ([Win32]::IsWindowVisible($hWnd)) {{
-                    $processId = 0
-                    [Win32]::GetWindowThreadProcessId($hWnd, [ref]$processId)
-                    try {{
-                        $process = Get-Process -Id $processId -ErrorAction SilentlyContinue
-                        if ($process) {{
-                            $sb = New-Object System.Text.StringBuilder 256
-                            [Win32]::GetWindowText($hWnd, $sb, $sb.Capacity) | Out-Null
-                            $title = $sb.ToString()
-                            
-                            if ($title.Length -gt 0) {{
-                                $script:checkedCount++
-                                $match = $false
-                                {}
-                                
-                                if ($match) {{
-                                    [Console]::Error.WriteLine('[LIST] MATCH: Process=' + $process.ProcessName + ', Title=' + $title)
-                                    $windows.Add(@{{
-                                        Title = $title
-                                        ProcessName = $process.ProcessName
-                                        IsActive = ($hWnd -eq $foreground)
-                                    }}) | Out-Null
-                                }}
-                            }}
-                        }}
-                    }} catch {{
-                        # Silently continue on errors
-                    }}
-                }}
-                return $true
-            }}, [IntPtr]::Zero) | Out-Null
-            
-            [Console]::Error.WriteLine('[LIST] EnumWindows checked ' + $script:enumCount + ' windows, checked ' + $script:checkedCount + ' with titles, found ' + $windows.Count + ' matches')
-            $windows | ConvertTo-Json -Depth 3
-        "#, match_condition);
-        
-        eprintln!("[list_windows_by_process] Searching for process_name: {:?}, window_title: {:?}", process_name, window_title);
-        
-        let output = Command::new("powershell")
-            .arg("-NoProfile")
-            .arg("-Command")
-            .arg(&list_script)
-            .stderr(std::process::Stdio::piped()) // Capture stderr for debugging
-            .output()
-            .map_err(|e| format!("Failed to list windows: {}", e))?;
-        
-        // Log stderr for debugging
-        let stderr_str = String::from_utf8_lossy(&output.stderr);
-        if !stderr_str.trim().is_empty() {
-            eprintln!("[list_windows_by_process] PowerShell stderr: {}", stderr_str);
-        }
-        
-        if !output.status.success() {
-            let error_msg = format!("PowerShell command failed. Stderr: {}", stderr_str);
-            eprintln!("[list_windows_by_process] ❌ {}", error_msg);
-            return Err(error_msg);
-        }
-        
-        let output_str = String::from_utf8_lossy(&output.stdout);
-        eprintln!("[list_windows_by_process] PowerShell stdout length: {} bytes", output_str.len());
-        eprintln!("[list_windows_by_process] PowerShell stdout preview: {}", 
-            if output_str.len() > 200 { 
-                format!("{}...", &output_str[..200]) 
-            } else { 
-                output_str.to_string() 
-            }
-        );
-        
-        let json_str = extract_json_from_output(&output_str);
-        eprintln!("[list_windows_by_process] Extracted JSON: {}", 
-            if json_str.len() > 200 { 
-                format!("{}...", &json_str[..200]) 
-            } else { 
-                json_str.clone() 
-            }
-        );
-        
-        // Parse as Value first, then handle both array and single object cases
-        let json_value: serde_json::Value = serde_json::from_str(&json_str)
-            .map_err(|e| {
-                eprintln!("[list_windows_by_process] JSON parse error: {}", e);
-                eprintln!("[list_windows_by_process] JSON string: {}", json_str);
-                format!("Failed to parse windows JSON: {}", e)
-            })?;
+        tracing::info!("[list_windows_by_process] Searching for process_name: {:?}, window_title: {:?}", options.process_name, options.window_title);
         
-        // PowerShell ConvertTo-Json returns a single object when there's 1 item, array when multiple
-        let windows_json: Vec<serde_json::Value> = match json_value {
-            serde_json::Value::Array(arr) => arr,
-            serde_json::Value::Object(_) => vec![json_value], // Single object, wrap in array
-            _ => {
-                eprintln!("[list_windows_by_process] Unexpected JSON type: {:?}", json_value);
-                vec![]
-            }
-        };
-        
-        eprintln!("[list_windows_by_process] Parsed {} window(s) from JSON", windows_json.len());
-        
-        let windows: Vec<WindowInfo> = windows_json
+        let matcher = WindowMatcher::new(&options)?;
+        let windows: Vec<WindowInfo> = crate::window_match::list_windows(&app, None)
+            .await?
             .into_iter()
-            .filter_map(|w| {
-                let title = w["Title"].as_str()?.to_string();
-                let process_name = w["ProcessName"].as_str()?.to_string();
-                let is_active = w["IsActive"].as_bool().unwrap_or(false);
-                eprintln!("[list_windows_by_process] Found window: \"{}\" (process: {}, active: {})", 
-                    title, process_name, is_active);
-                Some(WindowInfo {
-                    title,
-                    process_name,
-                    is_active,
-                })
+            .filter(|window| !window.title.is_empty() && matcher.matches(window))
+            .map(|window| WindowInfo {
+                title: window.title,
+                process_name: window.process_name,
+                is_active: window.is_active,
             })
             .collect();
         
-        eprintln!("[list_windows_by_process] ✅ Returning {} window(s)", windows.len());
+        tracing::info!("[list_windows_by_process] ✅ Returning {} window(s)", windows.len());
         Ok(windows)
     }
     
     #[cfg(not(target_os = "windows"))]
     {
+        let _ = (app, options);
         Ok(vec![])
     }
 }
 
+/// C# helper that lets PrintWindow see minimized windows: the window is restored fully
+/// transparent and without activation, so DWM renders its content while nothing appears on
+/// screen, then minimized again. Handles one window at a time.
+#[cfg(target_os = "windows")]
+const MINIMIZED_WINDOW_HELPER: &str = r#"
+Add-Type @"
+    using System;
+    using System.Runtime.InteropServices;
+    using System.Threading;
+    public static class MinimizedWindow {
+        [DllImport("user32.dll")] static extern bool IsIconic(IntPtr hWnd);
+        [DllImport("user32.dll")] static extern bool ShowWindow(IntPtr hWnd, int nCmdShow);
+        [DllImport("user32.dll")] static extern int GetWindowLong(IntPtr hWnd, int nIndex);
+        [DllImport("user32.dll")] static extern int SetWindowLong(IntPtr hWnd, int nIndex, int dwNewLong);
+        [DllImport("user32.dll")] static extern bool GetLayeredWindowAttributes(IntPtr hWnd, out uint crKey, out byte bAlpha, out uint dwFlags);
+        [DllImport("user32.dll")] static extern bool SetLayeredWindowAttributes(IntPtr hWnd, uint crKey, byte bAlpha, uint dwFlags);
+
+        const int GWL_EXSTYLE = -20;
+        const int WS_EX_LAYERED = 0x80000;
+        const uint LWA_ALPHA = 0x2;
+        const int SW_SHOWNOACTIVATE = 4;
+        const int SW_SHOWMINNOACTIVE = 7;
+
+        static int savedStyle;
+        static bool hadAlpha;
+        static uint savedKey;
+        static byte savedAlpha;
+        static uint savedFlags;
+
+        // Returns false (and does nothing) if the window isn't minimized
+        public static bool Show(IntPtr hWnd) {
+            if (!IsIconic(hWnd)) return false;
+            savedStyle = GetWindowLong(hWnd, GWL_EXSTYLE);
+            hadAlpha = (savedStyle & WS_EX_LAYERED) != 0 && GetLayeredWindowAttributes(hWnd, out savedKey, out savedAlpha, out savedFlags);
+            SetWindowLong(hWnd, GWL_EXSTYLE, savedStyle | WS_EX_LAYERED);
+            SetLayeredWindowAttributes(hWnd, 0, 0, LWA_ALPHA);
+            ShowWindow(hWnd, SW_SHOWNOACTIVATE);
+            // Give the app a moment to paint
+            Thread.Sleep(200);
+            return true;
+        }
+
+        public static void Hide(IntPtr hWnd) {
+            ShowWindow(hWnd, SW_SHOWMINNOACTIVE);
+            if (hadAlpha) SetLayeredWindowAttributes(hWnd, savedKey, savedAlpha, savedFlags);
+            SetWindowLong(hWnd, GWL_EXSTYLE, savedStyle);
+        }
+    }
+"@
+"#;
+
+/// A window image after blank-frame validation
+#[cfg(target_os = "windows")]
+struct ValidatedImage {
+    base64: String,
+    bytes: Vec<u8>,
+    method: CaptureMethod,
+    blank: bool,
+}
+
+/// Copy a rectangle of the screen as PNG bytes, with the native helper or a warm PowerShell host
+#[cfg(target_os = "windows")]
+async fn capture_screen_rect(app: &AppHandle, left: i64, top: i64, width: i64, height: i64) -> Result<Vec<u8>, String> {
+    use crate::powershell_host::PowerShellHostState;
+
+    let params = serde_json::json!({ "left": left, "top": top, "width": width, "height": height });
+    let base64 = match crate::native_helper::call::<String>(app, "capture_rect", params, None).await {
+        Some(result) => result?,
+        None => {
+            let script = format!(r#"
+                Add-Type -AssemblyName System.Drawing
+                $bmp = New-Object System.Drawing.Bitmap({width}, {height})
+                $graphics = [System.Drawing.Graphics]::FromImage($bmp)
+                $graphics.CopyFromScreen({left}, {top}, 0, 0, $bmp.Size)
+                $graphics.Dispose()
+                $ms = New-Object System.IO.MemoryStream
+                $bmp.Save($ms, [System.Drawing.Imaging.ImageFormat]::Png)
+                $bmp.Dispose()
+                [Convert]::ToBase64String($ms.ToArray())
+            "#);
+            let output = app.state::<PowerShellHostState>().run(&script, None, |_| {}).await?;
+            if !output.success() {
+                return Err(format!("Screen region capture failed: {}", output.stderr.trim()));
+            }
+            output.stdout
+        }
+    };
+    general_purpose::STANDARD
+        .decode(base64.trim())
+        .map_err(|e| format!("Failed to decode screen region: {}", e))
+}
+
+/// Capture with Windows.Graphics.Capture, keeping the image only if it isn't blank
+#[cfg(target_os = "windows")]
+async fn try_graphics_capture(app: &AppHandle, hwnd: i64) -> Option<ValidatedImage> {
+    match crate::capture_backend::capture_hwnd(app, hwnd).await {
+        Ok(bytes) if !crate::frame_validation::is_near_uniform(&bytes) => Some(ValidatedImage {
+            base64: general_purpose::STANDARD.encode(&bytes),
+            bytes,
+            method: CaptureMethod::GraphicsCapture,
+            blank: false,
+        }),
+        Ok(_) => {
+            tracing::warn!("⚠️ Windows.Graphics.Capture returned a blank frame");
+            None
+        }
+        Err(e) => {
+            tracing::warn!("⚠️ {}", e);
+            None
+        }
+    }
+}
+
+/// Pick the window image according to the capture backend: Windows.Graphics.Capture when
+/// selected (or when PrintWindow came back blank in auto mode), and as a last resort the
+/// window's rectangle cropped out of the screen. `capture` is the script's JSON with the
+/// window's Hwnd and Left/Top/Width/Height.
+#[cfg(target_os = "windows")]
+async fn validate_window_image(
+    app: &AppHandle,
+    base64: String,
+    bytes: Vec<u8>,
+    capture: &serde_json::Value,
+    backend: CaptureBackend,
+) -> ValidatedImage {
+    use crate::frame_validation::is_near_uniform;
+
+    let was_minimized = capture["WasMinimized"].as_bool().unwrap_or(false);
+    let method = if was_minimized { CaptureMethod::MinimizedRestore } else { CaptureMethod::PrintWindow };
+    // Windows.Graphics.Capture gets no frames from a minimized window
+    let hwnd = capture["Hwnd"].as_i64().filter(|_| !was_minimized);
+
+    if let (CaptureBackend::GraphicsCapture, Some(hwnd)) = (backend, hwnd) {
+        if let Some(image) = try_graphics_capture(app, hwnd).await {
+            return image;
+        }
+    }
+    if !is_near_uniform(&bytes) {
+        return ValidatedImage { base64, bytes, method, blank: false };
+    }
+    if let (CaptureBackend::Auto, Some(hwnd)) = (backend, hwnd) {
+        if let Some(image) = try_graphics_capture(app, hwnd).await {
+            tracing::info!("🖼️ PrintWindow returned a blank frame, used Windows.Graphics.Capture instead");
+            return image;
+        }
+    }
+
+    let rect = ["Left", "Top", "Width", "Height"].map(|key| capture[key].as_i64());
+    let fallback = match rect {
+        // A minimized window was only restored transparently, so the screen shows what's behind it
+        _ if was_minimized => Err("Window is minimized".to_string()),
+        [Some(left), Some(top), Some(width), Some(height)] if width > 0 && height > 0 => {
+            capture_screen_rect(app, left, top, width, height).await
+        }
+        _ => Err("Window has no visible rectangle".to_string()),
+    };
+    match fallback {
+        Ok(cropped) if !is_near_uniform(&cropped) => {
+            tracing::info!("🖼️ PrintWindow returned a blank frame, used a screen crop instead");
+            ValidatedImage {
+                base64: general_purpose::STANDARD.encode(&cropped),
+                bytes: cropped,
+                method: CaptureMethod::ScreenCrop,
+                blank: false,
+            }
+        }
+        Ok(_) => {
+            tracing::warn!("⚠️ Window capture is blank with both PrintWindow and a screen crop");
+            ValidatedImage { base64, bytes, method, blank: true }
+        }
+        Err(e) => {
+            tracing::warn!("⚠️ PrintWindow returned a blank frame and the screen crop fallback failed: {}", e);
+            ValidatedImage { base64, bytes, method, blank: true }
+        }
+    }
+}
+
 /// Capture a specific window by process name or window title
 #[tauri::command]
 pub async fn capture_window(
+    app: AppHandle,
+    options: CaptureWindowParams,
+) -> Result<WindowCaptureResult, String> {
+    let request_id = options.request_id.clone();
+    let operation = app.state::<CancellationState>().register(request_id.as_deref());
+    let result = capture_window_stages(&app, options, &operation).await;
+    capture_progress::finish(&app, request_id.as_deref(), &result);
+    result
+}
+
+/// Window capture without the final `done`/`failed` progress event, so callers can add stages
+pub(crate) async fn capture_window_stages(
+    app: &AppHandle,
     options: CaptureWindowParams,
+    operation: &OperationGuard,
 ) -> Result<WindowCaptureResult, String> {
-    let process_name = options.process_name;
-    let window_title = options.window_title;
+    crate::metrics::timed(app, crate::metrics::CAPTURE, capture_window_once(app, options, operation)).await
+}
+
+async fn capture_window_once(
+    app: &AppHandle,
+    options: CaptureWindowParams,
+    operation: &OperationGuard,
+) -> Result<WindowCaptureResult, String> {
+    operation.check()?;
+    let request_id = options.request_id.clone();
+    capture_progress::emit(app, request_id.as_deref(), CaptureStage::Enumerating, None);
     #[cfg(target_os = "windows")]
     {
-        use std::process::Command;
         use std::time::{SystemTime, UNIX_EPOCH};
         use sha2::{Sha256, Digest};
         use hex;
         use base64::{engine::general_purpose, Engine as _};
         
         // Debug: Log received parameters
-        eprintln!("[capture_window] Received process_name: {:?}, window_title: {:?}", process_name, window_title);
+        tracing::debug!("[capture_window] Received process_name: {:?}, window_title: {:?}", options.process_name, options.window_title);
         
-        // Build match condition first - use case-insensitive matching
-        let match_condition = if let Some(ref proc) = process_name {
-            if let Some(ref title) = window_title {
-                let cond = format!(
-                    r#"$match = ($process.ProcessName -ieq '{}') -and ($title -ilike '*{}*')"#,
-                    proc.replace("'", "''"), title.replace("'", "''")
-                );
-                eprintln!("[capture_window] Match condition (process + title): {}", cond);
-                cond
-            } else {
-                // Case-insensitive process name matching
-                // Try multiple variations: exact match, with .exe, and contains match
-                // Also log ProcessName for debugging (write to stderr so it's captured)
-                let cond = format!(
-                    r#"$procName = $process.ProcessName
-                    $searchName = '{}'
-                    $procNameLower = $procName.ToLower()
-                    $searchNameLower = $searchName.ToLower()
-                    $debugMsg1 = '[DEBUG] Comparing: ProcessName=' + $procName + ' (lower: ' + $procNameLower + ') with searchName=' + $searchName + ' (lower: ' + $searchNameLower + ')'
-                    [Console]::Error.WriteLine($debugMsg1)
-                    $match = ($procNameLower -eq $searchNameLower) -or ($procNameLower -eq ($searchNameLower + '.exe')) -or ($procNameLower -like ('*' + $searchNameLower + '*'))
-                    if ($match) {{
-                        $debugMsg2 = '[DEBUG] MATCH FOUND: ProcessName=' + $procName + ' matches ' + $searchName
-                        [Console]::Error.WriteLine($debugMsg2)
-                    }} else {{
-                        $debugMsg3 = '[DEBUG] NO MATCH: ProcessName=' + $procName + ' does not match ' + $searchName
-                        [Console]::Error.WriteLine($debugMsg3)
-                    }}"#,
-                    proc.replace("'", "''")
-                );
-                eprintln!("[capture_window] Match condition (process only): {}", cond);
-                cond
-            }
-        } else if let Some(ref title) = window_title {
-            let cond = format!(r#"$match = $title -ilike '*{}*'"#, title.replace("'", "''"));
-            eprintln!("[capture_window] Match condition (title only): {}", cond);
-            cond
-        } else {
-            eprintln!("[capture_window] Match condition: $match = $true (no filters)");
-            "$match = $true".to_string()
-        };
+        let matcher = WindowMatcher::new(&options)?;
+        let windows = crate::window_match::list_windows(app, Some(operation)).await?;
+        let target = windows
+            .iter()
+            .find(|window| matcher.matches(window))
+            .ok_or_else(|| crate::window_match::not_found(&windows))?;
+        tracing::debug!("[capture_window] Capturing hwnd={}, process={}, title={}", target.hwnd, target.process_name, target.title);
         
-        // Build PowerShell script to capture specific window
-        let enum_windows_close = "}, [IntPtr]::Zero) | Out-Null";
-        let capture_script = format!(r#"
-            # Load System.Drawing assembly for PowerShell use
-            Add-Type -AssemblyName System.Drawing
-            
-            # Compile C# code (System.Drawing is only used in PowerShell, not in C#)
-            Add-Type @" 
-                using System;
-                using System.Runtime.InteropServices;
-                using System.Text;
-                public class Win32 {{
-                    [DllImport("user32.dll")]
-                    public static extern bool EnumWindows(EnumWindowsProc enumProc, IntPtr lParam);
-                    [DllImport("user32.dll")]
-                    public static extern int GetWindowText(IntPtr hWnd, StringBuilder text, int count);
-                    [DllImport("user32.dll")]
-                    public static extern uint GetWindowThreadProcessId(IntPtr hWnd, out uint processId);
-                    [DllImport("user32.dll")]
-                    public static extern bool IsWindowVisible(IntPtr hWnd);
-                    [DllImport("user32.dll")]
-                    public static extern bool GetWindowRect(IntPtr hWnd, out RECT lpRect);
-                    [DllImport("user32.dll")]
-                    public static extern bool PrintWindow(IntPtr hWnd, IntPtr hdcBlt, int nFlags);
-                    public delegate bool EnumWindowsProc(IntPtr hWnd, IntPtr lParam);
-                    [StructLayout(LayoutKind.Sequential)]
-                    public struct RECT {{
-                        public int Left;
-                        public int Top;
-                        public int Right;
-                        public int Bottom;
-                    }}
-                }}
-"@
-            $script:targetHwnd = [IntPtr]::Zero
-            $script:targetProcess = $null
-            $script:targetTitle = $null
-            $script:enumCount = 0
-            $script:checkedProcesses = @()
-            
-            [Win32]::EnumWindows({{
-                param($hWnd, $lParam)
-                $script:enumCount++
-                if ([Win32]::IsWindowVisible($hWnd)) {{
-                    $processId = 0
-                    [Win32]::GetWindowThreadProcessId($hWnd, [ref]$processId)
-                    try {{
-                        $process = Get-Process -Id $processId -ErrorAction SilentlyContinue
-                        if ($process) {{
-                            if ($script:checkedProcesses -notcontains $process.ProcessName) {{
-                                $script:checkedProcesses += $process.ProcessName
-                            }}
-                            $sb = New-Object System.Text.StringBuilder 256
-                            [Win32]::GetWindowText($hWnd, $sb, $sb.Capacity) | Out-Null
-                            $title = $sb.ToString()
-                            
-                            $match = $false
-                            {}
-                            
-                            # The match condition above sets $match, now use it
-                            if ($match) {{
-                                $script:targetHwnd = $hWnd
-                                $script:targetProcess = $process
-                                $script:targetTitle = $title
-                                $debugMsg = \"[DEBUG] CAPTURING: hWnd=$hWnd, Process=\" + $process.ProcessName + \", Title=$title\"
-                                [Console]::Error.WriteLine($debugMsg)
-                                return $false
-                            }}
-                        }}
-                    }} catch {{
-                        # Silently continue on errors
-                    }}
+        let json = capture_hwnd(app, target.hwnd, request_id.as_deref(), Some(operation)).await?;
+        let image_base64 = json["ImageBase64"].as_str()
+            .ok_or("Missing ImageBase64 in result")?.to_string();
+        let window_title = target.title.clone();
+        let process_name = target.process_name.clone();
+        
+        // Decode image to check it isn't blank and to calculate hash
+        let image_bytes = general_purpose::STANDARD
+            .decode(&image_base64)
+            .map_err(|e| format!("Failed to decode image: {}", e))?;
+        let backend = app.state::<CaptureBackendState>().get();
+        let image = validate_window_image(app, image_base64, image_bytes, &json, backend).await;
+        let (image, element) = crop_to_child(app, &options, &json, image, Some(operation)).await?;
+        
+        let mut hasher = Sha256::new();
+        hasher.update(&image.bytes);
+        let hash = hex::encode(hasher.finalize());
+        
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+        
+        Ok(WindowCaptureResult {
+            image_base64: image.base64,
+            hash,
+            timestamp,
+            ocr_text: None,
+            window_title,
+            process_name,
+            diff: None,
+            classification: None,
+            capture_method: Some(image.method),
+            blank_frame: image.blank,
+            element,
+            translation: None,
+        })
+    }
+    
+    #[cfg(not(target_os = "windows"))]
+    {
+        Err("Window capture not implemented for this platform".to_string())
+    }
+}
+
+/// Crop a window capture to `filter.child`, when the filter has one
+#[cfg(target_os = "windows")]
+async fn crop_to_child(
+    app: &AppHandle,
+    filter: &CaptureWindowParams,
+    capture: &serde_json::Value,
+    image: ValidatedImage,
+    operation: Option<&OperationGuard>,
+) -> Result<(ValidatedImage, Option<UiElement>), String> {
+    let Some(child) = &filter.child else {
+        return Ok((image, None));
+    };
+    // A minimized window's elements have no on-screen rectangles to crop to
+    if capture["WasMinimized"].as_bool().unwrap_or(false) {
+        return Err("Child targets can't be captured while the window is minimized".to_string());
+    }
+    let hwnd = capture["Hwnd"].as_i64().ok_or("Missing Hwnd in capture result")?;
+    let elements = crate::window_elements::list_elements(app, hwnd, operation).await?;
+    let element = crate::window_elements::find_element(&elements, child, filter)?.clone();
+    let rect = ["Left", "Top", "Width", "Height"].map(|key| capture[key].as_i64().unwrap_or(0));
+    let bytes = crate::window_elements::crop_to_element(&image.bytes, rect, &element)?;
+    tracing::debug!("[capture_window] Cropped to {} '{}'", element.control_type, element.name);
+    let cropped = ValidatedImage { base64: general_purpose::STANDARD.encode(&bytes), bytes, ..image };
+    Ok((cropped, Some(element)))
+}
+
+/// Capture one window by handle with the native helper, or the capture script when the helper
+/// isn't available. Returns `{ImageBase64, Left, Top, Width, Height, WasMinimized, Hwnd}`.
+#[cfg(target_os = "windows")]
+async fn capture_hwnd(
+    app: &AppHandle,
+    hwnd: i64,
+    request_id: Option<&str>,
+    operation: Option<&OperationGuard>,
+) -> Result<serde_json::Value, String> {
+    capture_progress::emit(app, request_id, CaptureStage::Capturing, None);
+    let params = serde_json::json!({ "hwnd": hwnd });
+    match crate::native_helper::call(app, "capture_window", params, operation).await {
+        Some(result) => result,
+        None => capture_window_script(app, hwnd, request_id, operation).await,
+    }
+}
+
+#[cfg(target_os = "windows")]
+async fn capture_window_script(
+    app: &AppHandle,
+    hwnd: i64,
+    request_id: Option<&str>,
+    operation: Option<&OperationGuard>,
+) -> Result<serde_json::Value, String> {
+    use crate::powershell_host::PowerShellHostState;
+
+    let capture_script = format!(r#"
+        # Load System.Drawing assembly for PowerShell use
+        Add-Type -AssemblyName System.Drawing
+        
+        # Compile C# code (System.Drawing is only used in PowerShell, not in C#)
+        Add-Type @"
+            using System;
+            using System.Runtime.InteropServices;
+            public class WindowPrint {{
+                [DllImport("user32.dll")]
+                public static extern bool IsWindow(IntPtr hWnd);
+                [DllImport("user32.dll")]
+                public static extern bool GetWindowRect(IntPtr hWnd, out RECT lpRect);
+                [DllImport("user32.dll")]
+                public static extern bool PrintWindow(IntPtr hWnd, IntPtr hdcBlt, int nFlags);
+                [StructLayout(LayoutKind.Sequential)]
+                public struct RECT {{
+                    public int Left;
+                    public int Top;
+                    public int Right;
+                    public int Bottom;
                 }}
-                return $true
-            {}
-            $processList = $script:checkedProcesses -join ', '
-            $debugMsg = '[DEBUG] EnumWindows checked ' + $script:enumCount + ' windows, found processes: ' + $processList
-            [Console]::Error.WriteLine($debugMsg)
-            
-            if ($script:targetHwnd -eq [IntPtr]::Zero) {{
-                # Debug: List available processes for troubleshooting
-                $availableProcesses = Get-Process | Where-Object {{ $_.MainWindowTitle -ne "" }} | Select-Object ProcessName -Unique | ForEach-Object {{ $_.ProcessName }}
-                $errorMsg = "Window not found. Available processes with windows: " + ($availableProcesses -join ", ")
-                Write-Error $errorMsg
-                exit 1
             }}
-            
-            $rect = New-Object Win32+RECT
-            [Win32]::GetWindowRect($script:targetHwnd, [ref]$rect)
+"@
+        $targetHwnd = [IntPtr]::new({})
+        if (-not [WindowPrint]::IsWindow($targetHwnd)) {{
+            Write-Error 'Window no longer exists'
+            exit 1
+        }}
+        
+        {}
+        # Minimized windows have nothing to print until they're (invisibly) restored
+        $wasMinimized = [MinimizedWindow]::Show($targetHwnd)
+        try {{
+            $rect = New-Object WindowPrint+RECT
+            [WindowPrint]::GetWindowRect($targetHwnd, [ref]$rect) | Out-Null
             $width = $rect.Right - $rect.Left
             $height = $rect.Bottom - $rect.Top
+            if ($width -le 0 -or $height -le 0) {{
+                Write-Error 'Window has no visible rectangle'
+                exit 1
+            }}
             
             # Note: We don't bring window to foreground as it can cause focus issues
             # PW_RENDERFULLCONTENT should work even when window is not in foreground
@@ -1002,107 +1176,181 @@ pub async fn capture_window(
             # Use PW_RENDERFULLCONTENT (0x2) flag to capture hardware-accelerated content
             # Flag 0 = PW_CLIENTONLY (old method, doesn't work with modern apps)
             # Flag 2 = PW_RENDERFULLCONTENT (captures composited window content)
-            $captured = [Win32]::PrintWindow($script:targetHwnd, $hdc, 2)
+            $captured = [WindowPrint]::PrintWindow($targetHwnd, $hdc, 2)
             
             # If PW_RENDERFULLCONTENT fails, try with flag 0 as fallback
             if (-not $captured) {{
-                [Win32]::PrintWindow($script:targetHwnd, $hdc, 0) | Out-Null
+                [WindowPrint]::PrintWindow($targetHwnd, $hdc, 0) | Out-Null
             }}
             
             $graphics.ReleaseHdc($hdc)
             $graphics.Dispose()
-            
-            $ms = New-Object System.IO.MemoryStream
-            $bmp.Save($ms, [System.Drawing.Imaging.ImageFormat]::Png)
-            $bytes = $ms.ToArray()
-            $ms.Dispose()
-            $bmp.Dispose()
-            
-            $base64 = [Convert]::ToBase64String($bytes)
-            $json = @{{
-                ImageBase64 = $base64
-                WindowTitle = $script:targetTitle
-                ProcessName = $script:targetProcess.ProcessName
-            }} | ConvertTo-Json
-            
-            $json
-        "#, match_condition, enum_windows_close);
+        }} finally {{
+            if ($wasMinimized) {{ [MinimizedWindow]::Hide($targetHwnd) }}
+        }}
         
-        let output = Command::new("powershell")
-            .arg("-NoProfile")
-            .arg("-Command")
-            .arg(&capture_script)
-            .stderr(std::process::Stdio::piped()) // Capture stderr to see debug output
-            .output()
-            .map_err(|e| format!("Failed to capture window: {}", e))?;
-        
-        // Log stderr for debugging
-        let stderr_str = String::from_utf8_lossy(&output.stderr);
-        if !stderr_str.trim().is_empty() {
-            eprintln!("[capture_window] PowerShell stderr: {}", stderr_str);
-        }
-        
-        if !output.status.success() {
-            // Get error message from stderr for better debugging
-            let error_msg = String::from_utf8_lossy(&output.stderr);
-            let stdout_msg = String::from_utf8_lossy(&output.stdout);
-            return Err(format!(
-                "Window not found or capture failed. Process: {:?}, Title: {:?}. Error: {}. Output: {}",
-                process_name, window_title, error_msg, stdout_msg
-            ));
-        }
-        
-        let output_str = String::from_utf8_lossy(&output.stdout);
-        let json_str = extract_json_from_output(&output_str);
-        let json: serde_json::Value = serde_json::from_str(&json_str)
-            .map_err(|e| format!("Failed to parse capture result: {}", e))?;
-        
-        let image_base64 = json["ImageBase64"].as_str()
-            .ok_or("Missing ImageBase64 in result")?.to_string();
-        let window_title = json["WindowTitle"].as_str()
-            .unwrap_or("").to_string();
-        let process_name = json["ProcessName"].as_str()
-            .unwrap_or("").to_string();
-        
-        // Decode image to calculate hash
-        let image_bytes = general_purpose::STANDARD
-            .decode(&image_base64)
-            .map_err(|e| format!("Failed to decode image: {}", e))?;
-        
-        let mut hasher = Sha256::new();
-        hasher.update(&image_bytes);
-        let hash = hex::encode(hasher.finalize());
+        [Console]::Error.WriteLine('[STAGE] encoding')
+        $ms = New-Object System.IO.MemoryStream
+        $bmp.Save($ms, [System.Drawing.Imaging.ImageFormat]::Png)
+        $bytes = $ms.ToArray()
+        $ms.Dispose()
+        $bmp.Dispose()
         
+        @{{
+            ImageBase64 = [Convert]::ToBase64String($bytes)
+            Left = $rect.Left
+            Top = $rect.Top
+            Width = $width
+            Height = $height
+            WasMinimized = $wasMinimized
+            Hwnd = $targetHwnd.ToInt64()
+        }} | ConvertTo-Json
+    "#, hwnd, MINIMIZED_WINDOW_HELPER);
+    
+    // Run in a warm PowerShell host, forwarding stage markers (stderr) as they arrive
+    let output = app
+        .state::<PowerShellHostState>()
+        .run(&capture_script, operation, |line| {
+            if let Some(stage) = CaptureStage::from_marker(line) {
+                capture_progress::emit(app, request_id, stage, None);
+            }
+        })
+        .await?;
+    if let Some(operation) = operation {
+        operation.check()?;
+    }
+    
+    // Log stderr for debugging
+    if !output.stderr.trim().is_empty() {
+        tracing::debug!("[capture_window] PowerShell stderr: {}", output.stderr);
+    }
+    
+    if !output.success() {
+        return Err(format!("Window capture failed: {}", output.stderr.trim()));
+    }
+    
+    let json_str = extract_json_from_output(&output.stdout);
+    serde_json::from_str(&json_str).map_err(|e| format!("Failed to parse capture result: {}", e))
+}
+
+/// Most windows a single `capture_windows` call may ask for
+const MAX_BATCH_WINDOWS: usize = 8;
+
+/// One entry of a `capture_windows` result, in the same order as the filters
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct BatchWindowCapture {
+    pub index: usize,
+    pub capture: Option<WindowCaptureResult>,
+    /// Why this filter produced no capture (window not found, capture failed)
+    pub error: Option<String>,
+}
+
+/// Capture several windows in one call (e.g. editor + terminal + browser).
+/// Windows are enumerated once and each filter takes its first match, using
+/// the same matching rules as `capture_window`.
+#[tauri::command]
+pub async fn capture_windows(
+    app: AppHandle,
+    filters: Vec<CaptureWindowParams>,
+) -> Result<Vec<BatchWindowCapture>, String> {
+    if filters.is_empty() {
+        return Err("At least one window filter is required".to_string());
+    }
+    if filters.len() > MAX_BATCH_WINDOWS {
+        return Err(format!("At most {} windows can be captured at once", MAX_BATCH_WINDOWS));
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        use std::time::{SystemTime, UNIX_EPOCH};
+        use sha2::{Sha256, Digest};
+
+        let matchers = filters.iter().map(WindowMatcher::new).collect::<Result<Vec<_>, _>>()?;
+        let windows = crate::window_match::list_windows(&app, None).await?;
         let timestamp = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .unwrap()
             .as_secs() as i64;
-        
-        Ok(WindowCaptureResult {
-            image_base64,
-            hash,
-            timestamp,
-            ocr_text: None,
-            window_title,
-            process_name,
-        })
+        let backend = app.state::<CaptureBackendState>().get();
+
+        let mut results = Vec::with_capacity(filters.len());
+        for (index, matcher) in matchers.iter().enumerate() {
+            let Some(window) = windows.iter().find(|window| matcher.matches(window)) else {
+                results.push(BatchWindowCapture { index, capture: None, error: Some("Window not found".to_string()) });
+                continue;
+            };
+            let entry = match capture_hwnd(&app, window.hwnd, None, None).await {
+                Ok(entry) => entry,
+                Err(e) => {
+                    results.push(BatchWindowCapture { index, capture: None, error: Some(e) });
+                    continue;
+                }
+            };
+            let Some(image_base64) = entry["ImageBase64"].as_str() else {
+                results.push(BatchWindowCapture { index, capture: None, error: Some("Missing ImageBase64 in result".to_string()) });
+                continue;
+            };
+            let image_bytes = match general_purpose::STANDARD.decode(image_base64) {
+                Ok(bytes) => bytes,
+                Err(e) => {
+                    results.push(BatchWindowCapture { index, capture: None, error: Some(format!("Failed to decode image: {}", e)) });
+                    continue;
+                }
+            };
+            let image = validate_window_image(&app, image_base64.to_string(), image_bytes, &entry, backend).await;
+            let (image, element) = match crop_to_child(&app, &filters[index], &entry, image, None).await {
+                Ok(cropped) => cropped,
+                Err(e) => {
+                    results.push(BatchWindowCapture { index, capture: None, error: Some(e) });
+                    continue;
+                }
+            };
+            let mut hasher = Sha256::new();
+            hasher.update(&image.bytes);
+
+            results.push(BatchWindowCapture {
+                index,
+                capture: Some(WindowCaptureResult {
+                    image_base64: image.base64,
+                    hash: hex::encode(hasher.finalize()),
+                    timestamp,
+                    ocr_text: None,
+                    window_title: window.title.clone(),
+                    process_name: window.process_name.clone(),
+                    diff: None,
+                    classification: None,
+                    capture_method: Some(image.method),
+                    blank_frame: image.blank,
+                    element,
+                    translation: None,
+                }),
+                error: None,
+            });
+        }
+
+        tracing::info!("[capture_windows] 📸 Captured {} window filter(s) in one pass", filters.len());
+        Ok(results)
     }
-    
+
     #[cfg(not(target_os = "windows"))]
     {
+        let _ = app;
         Err("Window capture not implemented for this platform".to_string())
     }
 }
 
-/// Helper function to save captured image to disk for debugging
-fn save_captured_image(base64_data: &str, window_title: &str, process_name: &str) -> Result<PathBuf, String> {
-    // Decode base64 to bytes
-    let image_bytes = general_purpose::STANDARD
-        .decode(base64_data)
-        .map_err(|e| format!("Failed to decode base64: {}", e))?;
-    
-    // Create captures directory in the project root or temp directory
-    let captures_dir = if let Ok(exe_path) = std::env::current_exe() {
+/// Where captured images are saved: `captures/` in the project root during development,
+/// otherwise a folder in the temp directory, with a subfolder per non-default profile
+pub(crate) fn captures_dir() -> PathBuf {
+    let base = base_captures_dir();
+    match crate::profiles::active_subdir() {
+        Some(profile_dir) => base.join(profile_dir),
+        None => base,
+    }
+}
+
+fn base_captures_dir() -> PathBuf {
+    if let Ok(exe_path) = std::env::current_exe() {
         // Try to use project directory (parent of target/debug or target/release)
         if let Some(exe_dir) = exe_path.parent() {
             if let Some(target_dir) = exe_dir.parent() {
@@ -1119,7 +1367,17 @@ fn save_captured_image(base64_data: &str, window_title: &str, process_name: &str
         }
     } else {
         std::env::temp_dir().join("ai-teacher-captures")
-    };
+    }
+}
+
+/// Helper function to save captured image to disk for debugging
+fn save_captured_image(base64_data: &str, window_title: &str, process_name: &str) -> Result<PathBuf, String> {
+    // Decode base64 to bytes
+    let image_bytes = general_purpose::STANDARD
+        .decode(base64_data)
+        .map_err(|e| format!("Failed to decode base64: {}", e))?;
+    
+    let captures_dir = captures_dir();
     
     // Create directory if it doesn't exist
     fs::create_dir_all(&captures_dir)
@@ -1148,7 +1406,7 @@ fn save_captured_image(base64_data: &str, window_title: &str, process_name: &str
     fs::write(&file_path, image_bytes)
         .map_err(|e| format!("Failed to write image file: {}", e))?;
     
-    eprintln!("[save_captured_image] 💾 Saved captured image to: {}", file_path.display());
+    tracing::info!("[save_captured_image] 💾 Saved captured image to: {}", file_path.display());
     
     Ok(file_path)
 }
@@ -1156,12 +1414,27 @@ fn save_captured_image(base64_data: &str, window_title: &str, process_name: &str
 /// Capture a window and extract text using OCR
 #[tauri::command]
 pub async fn capture_window_with_ocr(
+    app: AppHandle,
     options: CaptureWindowParams,
 ) -> Result<WindowCaptureResult, String> {
-    eprintln!("[capture_window_with_ocr] 📸 Step 1: Capturing window...");
+    let request_id = options.request_id.clone();
+    let operation = app.state::<CancellationState>().register(request_id.as_deref());
+    let result = capture_window_with_ocr_stages(&app, options, &operation).await;
+    capture_progress::finish(&app, request_id.as_deref(), &result);
+    result
+}
+
+async fn capture_window_with_ocr_stages(
+    app: &AppHandle,
+    options: CaptureWindowParams,
+    operation: &OperationGuard,
+) -> Result<WindowCaptureResult, String> {
+    let request_id = options.request_id.clone();
+    let translate_to = options.translate_to.clone();
+    tracing::info!("[capture_window_with_ocr] 📸 Step 1: Capturing window...");
     // First capture the window
-    let mut result = capture_window(options).await?;
-    eprintln!("[capture_window_with_ocr] ✅ Window captured: {} ({} KB image)", 
+    let mut result = capture_window_stages(app, options, operation).await?;
+    tracing::info!("[capture_window_with_ocr] ✅ Window captured: {} ({} KB image)", 
         result.window_title, 
         result.image_base64.len() / 1024
     );
@@ -1169,81 +1442,57 @@ pub async fn capture_window_with_ocr(
     // Save image to disk for debugging
     match save_captured_image(&result.image_base64, &result.window_title, &result.process_name) {
         Ok(path) => {
-            eprintln!("[capture_window_with_ocr] 💾 Image saved to: {}", path.display());
+            tracing::info!("[capture_window_with_ocr] 💾 Image saved to: {}", path.display());
         }
         Err(e) => {
-            eprintln!("[capture_window_with_ocr] ⚠️ Failed to save image: {}", e);
+            tracing::warn!("[capture_window_with_ocr] ⚠️ Failed to save image: {}", e);
             // Don't fail the capture if saving fails
         }
     }
     
-    eprintln!("[capture_window_with_ocr] 🔍 Step 2: Running OCR on captured image...");
+    tracing::info!("[capture_window_with_ocr] 🔍 Step 2: Running OCR on captured image...");
+    capture_progress::emit(app, request_id.as_deref(), CaptureStage::Ocr, None);
     // Then extract text using OCR
-    match extract_text_from_image(result.image_base64.clone()).await {
+    match ocr_image(app, result.image_base64.clone(), operation).await {
         Ok(text) => {
-            eprintln!("[capture_window_with_ocr] ✅ OCR completed: extracted {} characters", text.len());
+            tracing::info!("[capture_window_with_ocr] ✅ OCR completed: extracted {} characters", text.len());
             if !text.is_empty() {
                 let preview = if text.len() > 100 {
                     format!("{}...", &text[..100])
                 } else {
                     text.clone()
                 };
-                eprintln!("[capture_window_with_ocr] 📝 OCR preview: {}", preview);
+                tracing::info!("[capture_window_with_ocr] 📝 OCR preview: {}", preview);
             } else {
-                eprintln!("[capture_window_with_ocr] ⚠️ OCR returned empty text");
+                tracing::warn!("[capture_window_with_ocr] ⚠️ OCR returned empty text");
+            }
+            crate::homework::observe(app, &result.window_title, &result.process_name, Some(&text));
+            result.classification = Some(app.state::<ContentClassState>().observe(&result.window_title, &result.process_name, Some(&text)));
+            let window = format!("{}|{}", result.process_name, result.window_title);
+            result.diff = app.state::<CaptureDiffState>().observe(&window, &text);
+            if let Some(target_lang) = translate_to.as_deref().filter(|_| !text.trim().is_empty()) {
+                // A failed translation still returns the capture and its text
+                match crate::translation::translate(app, &text, target_lang).await {
+                    Ok(translation) => result.translation = Some(translation),
+                    Err(e) => tracing::warn!("[capture_window_with_ocr] ⚠️ Translation failed: {}", e),
+                }
             }
             result.ocr_text = Some(text);
-            eprintln!("[capture_window_with_ocr] 📤 Step 3: Returning result with image and OCR text");
+            tracing::info!("[capture_window_with_ocr] 📤 Step 3: Returning result with image and OCR text");
             Ok(result)
         }
+        Err(e) if e == CANCELLED => Err(e),
         Err(e) => {
             // Return result even if OCR fails
-            eprintln!("[capture_window_with_ocr] ❌ OCR failed: {}", e);
-            eprintln!("[capture_window_with_ocr] 📤 Returning result without OCR text");
+            tracing::error!("[capture_window_with_ocr] ❌ OCR failed: {}", e);
+            crate::homework::observe(app, &result.window_title, &result.process_name, None);
+            result.classification = Some(app.state::<ContentClassState>().observe(&result.window_title, &result.process_name, None));
+            tracing::info!("[capture_window_with_ocr] 📤 Returning result without OCR text");
             Ok(result)
         }
     }
 }
 
-#[tauri::command]
-pub async fn start_monitoring(
-    app: AppHandle,
-    state: State<'_, ScreenCaptureState>,
-) -> Result<(), String> {
-    let state_clone = state.inner().clone();
-    let app_clone = app.clone();
-    
-    tokio::spawn(async move {
-        let mut last_hash = String::new();
-        
-        loop {
-            let interval_secs = state_clone.interval_seconds.load(std::sync::atomic::Ordering::Relaxed);
-            tokio::time::sleep(tokio::time::Duration::from_secs(interval_secs)).await;
-            
-            let capture = ScreenCapture::new();
-            match capture.capture_full_screen(&state_clone).await {
-                Ok(result) => {
-                    if result.hash != last_hash {
-                        last_hash = result.hash.clone();
-                        let _ = app_clone.emit("screen-changed", result);
-                    }
-                }
-                Err(e) => {
-                    eprintln!("Screen capture error: {}", e);
-                }
-            }
-        }
-    });
-    
-    Ok(())
-}
-
-#[tauri::command]
-pub async fn stop_monitoring() -> Result<(), String> {
-    // Monitoring is handled by the spawned task, this is a placeholder
-    Ok(())
-}
-
 #[tauri::command]
 pub async fn get_capture_interval(
     state: State<'_, ScreenCaptureState>,
@@ -1291,7 +1540,7 @@ pub async fn close_window(app: AppHandle) -> Result<(), String> {
     Ok(())
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct CommandResult {
     pub success: bool,
     pub stdout: String,
@@ -1379,36 +1628,17 @@ fn validate_npm_command(args: &[String]) -> Result<(), String> {
     }
 }
 
-fn validate_node_command(args: &[String]) -> Result<(), String> {
-    if args.is_empty() {
-        return Err("node requires arguments.".to_string());
-    }
-    match args[0].as_str() {
-        "-v" | "--version" => Ok(()),
-        other => Err(format!(
-            "node argument '{}' is not permitted. Only version checks are allowed.",
-            other
-        )),
-    }
-}
-
-fn validate_python_command(args: &[String]) -> Result<(), String> {
-    if args.is_empty() {
-        return Err("python requires arguments.".to_string());
-    }
-    match args[0].as_str() {
-        "-v" | "--version" | "-version" => Ok(()),
-        other => Err(format!(
-            "python argument '{}' is not permitted. Only version checks are allowed.",
-            other
-        )),
-    }
-}
-
 fn validate_powershell_command(args: &[String]) -> Result<(), String> {
-    if args.len() >= 2 && (args[0] == "-command" || args[0] == "-c") {
-        let command_text = args[1].as_str();
-        if command_text.starts_with("get-process") {
+    // `Get-Process` alone or with one process name; anything more could chain another command
+    let is_name = |word: &str| !word.is_empty() && word.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.');
+    if args.len() == 2 && (args[0] == "-command" || args[0] == "-c") {
+        let words: Vec<&str> = args[1].split(' ').collect();
+        let permitted = match words.as_slice() {
+            ["get-process"] => true,
+            ["get-process", name] => is_name(name),
+            _ => false,
+        };
+        if permitted {
             return Ok(());
         }
     }
@@ -1428,39 +1658,51 @@ fn validate_command_policy(command: &str, args: &[String]) -> Result<(), String>
         "docker" => validate_docker_command(&lowered_args),
         "git" => validate_git_command(&lowered_args),
         "npm" => validate_npm_command(&lowered_args),
-        "node" => validate_node_command(&lowered_args),
-        "python" => validate_python_command(&lowered_args),
         "powershell" | "pwsh" => validate_powershell_command(&lowered_args),
         "cmd" => validate_cmd_command(&lowered_args),
-        _ => Ok(()),
+        other => Err(format!("No policy permits '{}'.", other)),
     }
 }
 
-#[tauri::command]
-pub async fn execute_command(command: String, args: Vec<String>) -> Result<CommandResult, String> {
-    use std::process::Command;
-    
-    // Security: Only allow safe commands
-    // For now, allow common commands like docker, git, etc.
-    // In production, you might want to whitelist specific commands
-    let allowed_commands = ["docker", "git", "npm", "node", "python", "pwsh", "powershell", "cmd"];
+/// Commands the agent may run at all; each is further restricted by its policy validator.
+/// Toolchain versions come from `detect_dev_environment` instead.
+pub(crate) const ALLOWED_COMMANDS: [&str; 6] = ["docker", "git", "npm", "pwsh", "powershell", "cmd"];
+
+/// Check a command against the allowlist, the current role and the per-tool policy
+pub(crate) fn check_command_permitted(app: &AppHandle, command: &str, args: &[String]) -> Result<(), String> {
     let command_lower = command.to_lowercase();
-    
-    if !allowed_commands.iter().any(|&cmd| command_lower.starts_with(cmd)) {
-        let allowed_list = allowed_commands.iter().map(|s| *s).collect::<Vec<_>>().join(", ");
-        return Ok(CommandResult {
-            success: false,
-            stdout: String::new(),
-            stderr: String::new(),
-            exit_code: None,
-            error: Some(format!("Command '{}' is not allowed. Allowed commands: {}", command, allowed_list)),
-        });
+    if !ALLOWED_COMMANDS.contains(&command_lower.as_str()) {
+        let allowed_list = ALLOWED_COMMANDS.join(", ");
+        return Err(format!("Command '{}' is not allowed. Allowed commands: {}", command, allowed_list));
     }
-    if let Err(reason) = validate_command_policy(&command_lower, &args) {
-        println!(
+    app.state::<crate::roles::RolesState>().check_program(&command_lower)?;
+    if let Err(reason) = validate_command_policy(&command_lower, args) {
+        tracing::warn!(
             "[Security] Blocked command '{} {:?}' - {}",
             command, args, reason
         );
+        return Err(reason);
+    }
+    Ok(())
+}
+
+/// Build the process for an allowlisted command. The arguments are passed as they are, never
+/// through a shell, so the one the policy checked is the one that runs. npm is a batch shim on
+/// Windows, which `npm.cmd` names explicitly.
+pub(crate) fn build_process_command(command: &str, args: &[String]) -> std::process::Command {
+    use std::process::Command;
+
+    let command_lower = command.to_lowercase();
+    let program = if cfg!(target_os = "windows") && command_lower == "npm" { "npm.cmd" } else { command_lower.as_str() };
+    let mut cmd = Command::new(program);
+    cmd.args(args);
+    cmd
+}
+
+#[tauri::command]
+pub async fn execute_command(app: AppHandle, command: String, args: Vec<String>) -> Result<CommandResult, String> {
+    // Security: Only allow safe commands
+    if let Err(reason) = check_command_permitted(&app, &command, &args) {
         return Ok(CommandResult {
             success: false,
             stdout: String::new(),
@@ -1470,31 +1712,8 @@ pub async fn execute_command(command: String, args: Vec<String>) -> Result<Comma
         });
     }
 
-    // Execute the command
-    let output = if cfg!(target_os = "windows") {
-        // On Windows, use cmd.exe /c or PowerShell
-        if command_lower == "docker" || command_lower == "git" {
-            // These commands are usually in PATH
-            Command::new(&command)
-                .args(&args)
-                .output()
-                .map_err(|e| format!("Failed to execute command: {}", e))?
-        } else {
-            // For other commands, try PowerShell
-            let mut cmd = Command::new("powershell");
-            cmd.arg("-Command");
-            let full_cmd = format!("{} {}", command, args.join(" "));
-            cmd.arg(&full_cmd);
-            cmd.output()
-                .map_err(|e| format!("Failed to execute command: {}", e))?
-        }
-    } else {
-        // On Unix-like systems
-        Command::new(&command)
-            .args(&args)
-            .output()
-            .map_err(|e| format!("Failed to execute command: {}", e))?
-    };
+    // Execute the command within the sandbox limits
+    let output = crate::sandbox::run(&app, build_process_command(&command, &args)).await?;
     
     let stdout = String::from_utf8_lossy(&output.stdout).to_string();
     let stderr = String::from_utf8_lossy(&output.stderr).to_string();
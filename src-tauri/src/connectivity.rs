@@ -0,0 +1,162 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, State};
+
+/// Endpoint probed when none has been configured (the Gemini API used by the frontend)
+const DEFAULT_ENDPOINT: &str = "https://generativelanguage.googleapis.com";
+const PROBE_INTERVAL: Duration = Duration::from_secs(15);
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Payload of the `connectivity-changed` event
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ConnectivityStatus {
+    pub online: bool,
+    pub latency_ms: Option<u64>,
+    pub endpoint: String,
+    pub error: Option<String>,
+    pub checked_at: i64,
+}
+
+#[derive(Clone)]
+pub struct ConnectivityState {
+    pub endpoint: Arc<Mutex<String>>,
+    pub status: Arc<Mutex<Option<ConnectivityStatus>>>,
+}
+
+impl Default for ConnectivityState {
+    fn default() -> Self {
+        Self {
+            endpoint: Arc::new(Mutex::new(DEFAULT_ENDPOINT.to_string())),
+            status: Arc::new(Mutex::new(None)),
+        }
+    }
+}
+
+impl ConnectivityState {
+    /// Whether the last probe reached the AI endpoint. Assumes online until the first probe completes.
+    pub fn is_online(&self) -> bool {
+        self.status
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|s| s.online)
+            .unwrap_or(true)
+    }
+}
+
+fn endpoint_address(endpoint: &str) -> Result<(String, u16), String> {
+    let url = url::Url::parse(endpoint).map_err(|e| format!("Invalid endpoint URL: {}", e))?;
+    let host = url
+        .host_str()
+        .ok_or_else(|| format!("Endpoint URL has no host: {}", endpoint))?
+        .to_string();
+    let port = url
+        .port_or_known_default()
+        .ok_or_else(|| format!("Endpoint URL has no known port: {}", endpoint))?;
+    Ok((host, port))
+}
+
+/// Open a TCP connection to the endpoint and measure how long it takes
+pub(crate) async fn probe(endpoint: &str) -> ConnectivityStatus {
+    let checked_at = chrono::Utc::now().timestamp();
+    let (host, port) = match endpoint_address(endpoint) {
+        Ok(address) => address,
+        Err(e) => {
+            return ConnectivityStatus {
+                online: false,
+                latency_ms: None,
+                endpoint: endpoint.to_string(),
+                error: Some(e),
+                checked_at,
+            }
+        }
+    };
+
+    let started = Instant::now();
+    let result = tokio::time::timeout(PROBE_TIMEOUT, tokio::net::TcpStream::connect((host.as_str(), port))).await;
+    let (online, error) = match result {
+        Ok(Ok(_stream)) => (true, None),
+        Ok(Err(e)) => (false, Some(format!("Failed to connect to {}:{}: {}", host, port, e))),
+        Err(_) => (false, Some(format!("Timed out connecting to {}:{}", host, port))),
+    };
+
+    ConnectivityStatus {
+        online,
+        latency_ms: online.then(|| started.elapsed().as_millis() as u64),
+        endpoint: endpoint.to_string(),
+        error,
+        checked_at,
+    }
+}
+
+/// Probe the configured endpoint, store the result and emit `connectivity-changed`
+/// when the online/offline state flips (or on the very first probe).
+async fn check_and_publish(app: &AppHandle, state: &ConnectivityState) -> ConnectivityStatus {
+    let endpoint = state.endpoint.lock().unwrap().clone();
+    let status = probe(&endpoint).await;
+
+    let changed = {
+        let mut current = state.status.lock().unwrap();
+        let changed = current.as_ref().map(|s| s.online) != Some(status.online);
+        *current = Some(status.clone());
+        changed
+    };
+
+    if changed {
+        tracing::info!(
+            "🌐 {} ({})",
+            if status.online { "Online" } else { "Offline" },
+            status.error.as_deref().unwrap_or(&endpoint)
+        );
+        let _ = crate::event_bus::emit(app, crate::events::CONNECTIVITY_CHANGED, status.clone());
+        if status.online {
+            crate::request_queue::flush(app);
+        }
+    }
+
+    status
+}
+
+/// Start the background probe loop
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<ConnectivityState>().inner().clone();
+        loop {
+            check_and_publish(&app, &state).await;
+            tokio::time::sleep(PROBE_INTERVAL).await;
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn get_connectivity_status(
+    app: AppHandle,
+    state: State<'_, ConnectivityState>,
+) -> Result<ConnectivityStatus, String> {
+    let cached = state.status.lock().unwrap().clone();
+    match cached {
+        Some(status) => Ok(status),
+        None => Ok(check_and_publish(&app, state.inner()).await),
+    }
+}
+
+#[tauri::command]
+pub async fn check_connectivity(
+    app: AppHandle,
+    state: State<'_, ConnectivityState>,
+) -> Result<ConnectivityStatus, String> {
+    Ok(check_and_publish(&app, state.inner()).await)
+}
+
+#[tauri::command]
+pub async fn set_connectivity_endpoint(
+    app: AppHandle,
+    state: State<'_, ConnectivityState>,
+    endpoint: String,
+) -> Result<ConnectivityStatus, String> {
+    endpoint_address(&endpoint)?;
+    *state.endpoint.lock().unwrap() = endpoint;
+    Ok(check_and_publish(&app, state.inner()).await)
+}
@@ -0,0 +1,183 @@
+//! Monitoring consent. Screen monitors only start while the student has granted consent for a
+//! scope and a period; when it expires or is revoked every monitor stops, and so does input
+//! counting (activity tracking and typing practice). Consent lasts at most until the app exits,
+//! each change emits `consent-changed`, and grants, revocations and expiries are kept in a
+//! per-profile log so the watching is auditable.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+
+use crate::persistence;
+
+const LOG_FILE: &str = "consent_log.json";
+const MAX_LOG_ENTRIES: usize = 500;
+const MAX_DURATION_MINUTES: u32 = 24 * 60;
+const CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// What monitoring may do
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ConsentScope {
+    /// Capture the screen
+    Screen,
+    /// Capture the screen and read its text with OCR
+    ScreenAndText,
+}
+
+/// Payload of the `consent-changed` event
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ConsentStatus {
+    /// Granted scope; None while monitoring isn't allowed
+    pub scope: Option<ConsentScope>,
+    pub granted_at: Option<i64>,
+    /// None when consent lasts until the app exits
+    pub expires_at: Option<i64>,
+    /// What changed: "granted", "revoked" or "expired"
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ConsentLogEntry {
+    /// "granted", "revoked" or "expired"
+    pub action: String,
+    pub scope: Option<ConsentScope>,
+    pub expires_at: Option<i64>,
+    pub at: i64,
+}
+
+#[derive(Debug, Clone)]
+struct Grant {
+    scope: ConsentScope,
+    granted_at: i64,
+    expires_at: Option<i64>,
+}
+
+#[derive(Clone, Default)]
+pub struct ConsentState {
+    grant: Arc<Mutex<Option<Grant>>>,
+}
+
+impl ConsentState {
+    /// Fail unless consent currently covers `scope`
+    pub(crate) fn require(&self, scope: ConsentScope) -> Result<(), String> {
+        let grant = self.grant.lock().unwrap();
+        let now = chrono::Utc::now().timestamp();
+        match grant.as_ref() {
+            Some(grant) if grant.expires_at.is_some_and(|at| at <= now) => {
+                Err("Monitoring consent has expired; ask the student again".to_string())
+            }
+            Some(grant) if grant.scope >= scope => Ok(()),
+            Some(_) => Err("Monitoring consent doesn't cover reading screen text".to_string()),
+            None => Err("Monitoring needs the student's consent first".to_string()),
+        }
+    }
+
+    fn status(&self, reason: Option<&str>) -> ConsentStatus {
+        let grant = self.grant.lock().unwrap();
+        ConsentStatus {
+            scope: grant.as_ref().map(|g| g.scope),
+            granted_at: grant.as_ref().map(|g| g.granted_at),
+            expires_at: grant.as_ref().and_then(|g| g.expires_at),
+            reason: reason.map(str::to_string),
+        }
+    }
+}
+
+fn record(app: &AppHandle, action: &str, scope: Option<ConsentScope>, expires_at: Option<i64>) {
+    let result = persistence::data_file(app, LOG_FILE).and_then(|path| {
+        let mut log: Vec<ConsentLogEntry> = persistence::load_json(&path);
+        log.push(ConsentLogEntry { action: action.to_string(), scope, expires_at, at: chrono::Utc::now().timestamp() });
+        let excess = log.len().saturating_sub(MAX_LOG_ENTRIES);
+        log.drain(..excess);
+        persistence::save_json(&path, &log)
+    });
+    if let Err(e) = result {
+        tracing::warn!("⚠️ Failed to record consent change: {}", e);
+    }
+}
+
+/// Clear the grant, stop every monitor and input counting, and announce why
+fn end(app: &AppHandle, reason: &str) {
+    let state = app.state::<ConsentState>();
+    let Some(grant) = state.grant.lock().unwrap().take() else {
+        return;
+    };
+    let stopped = crate::monitoring::stop_all(app);
+    // A recording is a capture too; finishing the file waits on ffmpeg
+    let handle = app.clone();
+    tauri::async_runtime::spawn_blocking(move || crate::screen_recording::stop(&handle));
+    crate::remote_view::stop(app);
+    crate::activity::stop(app);
+    crate::typing_practice::stop(app);
+    tracing::info!("🔒 Monitoring consent {}, stopped {} monitors", reason, stopped);
+    record(app, reason, Some(grant.scope), grant.expires_at);
+    let _ = crate::event_bus::emit(app, crate::events::CONSENT_CHANGED, state.status(Some(reason)));
+}
+
+/// End consent when it expires
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(CHECK_INTERVAL).await;
+            let expired = app
+                .state::<ConsentState>()
+                .grant
+                .lock()
+                .unwrap()
+                .as_ref()
+                .and_then(|g| g.expires_at)
+                .is_some_and(|at| at <= chrono::Utc::now().timestamp());
+            if expired {
+                end(&app, "expired");
+            }
+        }
+    });
+}
+
+/// Record the student's consent to monitoring for `duration_minutes` (until the app exits when
+/// omitted). Granting again replaces the previous scope and period.
+#[tauri::command]
+pub async fn grant_monitoring_consent(
+    app: AppHandle,
+    state: State<'_, ConsentState>,
+    scope: ConsentScope,
+    duration_minutes: Option<u32>,
+) -> Result<ConsentStatus, String> {
+    if duration_minutes.is_some_and(|m| !(1..=MAX_DURATION_MINUTES).contains(&m)) {
+        return Err(format!("Consent lasts between 1 and {} minutes", MAX_DURATION_MINUTES));
+    }
+    let now = chrono::Utc::now().timestamp();
+    let expires_at = duration_minutes.map(|m| now + m as i64 * 60);
+    *state.grant.lock().unwrap() = Some(Grant { scope, granted_at: now, expires_at });
+    // Narrowing to screen only ends monitors that read text
+    if scope == ConsentScope::Screen {
+        crate::monitoring::stop_ocr_monitors(&app);
+    }
+    tracing::info!("🔓 Monitoring consent granted ({:?}, {:?} minutes)", scope, duration_minutes);
+    record(&app, "granted", Some(scope), expires_at);
+    let status = state.status(Some("granted"));
+    let _ = crate::event_bus::emit(&app, crate::events::CONSENT_CHANGED, status.clone());
+    Ok(status)
+}
+
+/// Withdraw consent and stop every monitor
+#[tauri::command]
+pub async fn revoke_monitoring_consent(app: AppHandle, state: State<'_, ConsentState>) -> Result<ConsentStatus, String> {
+    end(&app, "revoked");
+    Ok(state.status(None))
+}
+
+#[tauri::command]
+pub async fn get_consent_state(state: State<'_, ConsentState>) -> Result<ConsentStatus, String> {
+    Ok(state.status(None))
+}
+
+/// Consent grants, revocations and expiries, oldest first
+#[tauri::command]
+pub async fn get_consent_log(app: AppHandle) -> Result<Vec<ConsentLogEntry>, String> {
+    let path = persistence::data_file(&app, LOG_FILE)?;
+    Ok(persistence::load_json(&path))
+}
@@ -0,0 +1,257 @@
+//! Labels what's on screen (code, terminal, article, video, ...) from window metadata and OCR
+//! text, so lessons can adapt to what the student is doing. Time spent per kind feeds the
+//! dashboard usage summary.
+
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex, OnceLock};
+use tauri::State;
+
+const TERMINAL_PROCESSES: &[&str] = &[
+    "windowsterminal", "cmd", "powershell", "pwsh", "conhost", "wt", "alacritty", "wezterm-gui",
+    "mintty", "bash", "wsl",
+];
+
+const IDE_PROCESSES: &[&str] = &[
+    "code", "devenv", "idea64", "pycharm64", "clion64", "webstorm64", "rider64", "studio64",
+    "sublime_text", "notepad++", "eclipse", "atom", "cursor", "zed",
+];
+
+const BROWSERS: &[&str] = &["chrome", "msedge", "firefox", "brave", "opera", "vivaldi"];
+
+const VIDEO_PROCESSES: &[&str] = &["vlc", "mpc-hc64", "mpc-be64", "wmplayer", "video.ui", "potplayer64"];
+const VIDEO_SITES: &[&str] = &["youtube", "netflix", "twitch", "vimeo", "disney+", "prime video"];
+
+const CHAT_PROCESSES: &[&str] = &["discord", "slack", "teams", "ms-teams", "whatsapp", "telegram", "signal", "zoom"];
+const CHAT_SITES: &[&str] = &["discord", "slack", "whatsapp", "messenger", "chatgpt", "claude", "gemini"];
+
+const DOCUMENT_PROCESSES: &[&str] = &[
+    "winword", "soffice", "soffice.bin", "wordpad", "notepad", "onenote", "acrord32", "acrobat",
+    "sumatrapdf", "foxitpdfreader",
+];
+const SPREADSHEET_PROCESSES: &[&str] = &["excel", "scalc"];
+
+/// Samples older than this are dropped from the time totals
+const RETENTION_SECS: i64 = 7 * 24 * 60 * 60;
+/// Longest gap between classifications that is still counted as time on the previous kind
+const MAX_SAMPLE_SECS: i64 = 60;
+/// Below this score a capture is labeled `Other`
+const CLASSIFICATION_THRESHOLD: f32 = 0.3;
+
+fn code_file_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        Regex::new(r"(?i)\.(rs|py|js|jsx|ts|tsx|java|c|cpp|h|hpp|cs|go|rb|php|kt|swift|html|css|sql|ipynb)\b")
+            .expect("valid code file regex")
+    })
+}
+
+fn code_line_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        Regex::new(r"(?m)^\s*(fn |def |class |function |import |from \S+ import|#include|public |private |const |let |var |return\b|if \(|for \(|\}\s*$)")
+            .expect("valid code line regex")
+    })
+}
+
+fn prompt_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        Regex::new(r"(?m)^(PS [A-Za-z]:\\|[A-Za-z]:\\[^>]*>|\S+@\S+:.*[$#] |\$ )").expect("valid prompt regex")
+    })
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ContentKind {
+    Code,
+    Terminal,
+    BrowserArticle,
+    Video,
+    Chat,
+    Document,
+    Spreadsheet,
+    Other,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ContentClassification {
+    pub kind: ContentKind,
+    pub confidence: f32,
+    /// Why this kind was chosen
+    pub signals: Vec<String>,
+}
+
+/// Time spent on one kind of content
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ContentTime {
+    pub kind: ContentKind,
+    pub seconds: i64,
+}
+
+struct Sample {
+    timestamp: i64,
+    kind: ContentKind,
+    seconds: i64,
+}
+
+#[derive(Clone, Default)]
+pub struct ContentClassState {
+    /// Kind and time of the latest classification, still accumulating time
+    current: Arc<Mutex<Option<(ContentKind, i64)>>>,
+    samples: Arc<Mutex<Vec<Sample>>>,
+}
+
+struct Candidate {
+    kind: ContentKind,
+    score: f32,
+    signals: Vec<String>,
+}
+
+impl Candidate {
+    fn new(kind: ContentKind) -> Self {
+        Self { kind, score: 0.0, signals: Vec::new() }
+    }
+
+    fn add(&mut self, weight: f32, signal: impl Into<String>) {
+        self.score += weight;
+        self.signals.push(signal.into());
+    }
+}
+
+fn normalize_process(process_name: &str) -> String {
+    process_name.trim().to_lowercase().trim_end_matches(".exe").to_string()
+}
+
+/// Score the window metadata and OCR text against each kind of content and return the best match
+pub fn classify(window_title: &str, process_name: &str, ocr_text: Option<&str>) -> ContentClassification {
+    let process = normalize_process(process_name);
+    let title = window_title.to_lowercase();
+    let ocr = ocr_text.unwrap_or_default();
+    let is_browser = BROWSERS.contains(&process.as_str());
+    let ocr_lines = ocr.lines().filter(|l| !l.trim().is_empty()).count();
+
+    let mut terminal = Candidate::new(ContentKind::Terminal);
+    if TERMINAL_PROCESSES.contains(&process.as_str()) {
+        terminal.add(0.6, format!("terminal ({})", process_name));
+    }
+    let prompts = prompt_pattern().find_iter(ocr).count();
+    if prompts > 0 {
+        terminal.add(if prompts > 1 { 0.4 } else { 0.25 }, format!("{} shell prompt(s) on screen", prompts));
+    }
+
+    let mut code = Candidate::new(ContentKind::Code);
+    if IDE_PROCESSES.contains(&process.as_str()) {
+        code.add(0.6, format!("code editor ({})", process_name));
+    }
+    if code_file_pattern().is_match(&title) {
+        code.add(0.3, "title names a source file");
+    }
+    let code_lines = code_line_pattern().find_iter(ocr).count();
+    if ocr_lines > 0 && code_lines * 4 >= ocr_lines {
+        code.add(0.3, format!("{} of {} lines look like code", code_lines, ocr_lines));
+    }
+
+    let mut video = Candidate::new(ContentKind::Video);
+    if VIDEO_PROCESSES.contains(&process.as_str()) {
+        video.add(0.7, format!("video player ({})", process_name));
+    }
+    if let Some(site) = VIDEO_SITES.iter().find(|s| title.contains(*s)) {
+        video.add(if is_browser { 0.7 } else { 0.4 }, format!("title mentions {}", site));
+    }
+
+    let mut chat = Candidate::new(ContentKind::Chat);
+    if CHAT_PROCESSES.contains(&process.as_str()) {
+        chat.add(0.7, format!("chat app ({})", process_name));
+    }
+    if is_browser {
+        if let Some(site) = CHAT_SITES.iter().find(|s| title.contains(*s)) {
+            chat.add(0.6, format!("chat site ({})", site));
+        }
+    }
+
+    let mut spreadsheet = Candidate::new(ContentKind::Spreadsheet);
+    if SPREADSHEET_PROCESSES.contains(&process.as_str()) || title.contains(".xlsx") || title.contains(".csv") {
+        spreadsheet.add(0.7, "spreadsheet");
+    } else if is_browser && title.contains("google sheets") {
+        spreadsheet.add(0.7, "Google Sheets in browser");
+    }
+
+    let mut document = Candidate::new(ContentKind::Document);
+    if DOCUMENT_PROCESSES.contains(&process.as_str()) {
+        document.add(0.6, format!("document app ({})", process_name));
+    }
+    if title.contains(".pdf") || title.contains(".docx") || (is_browser && title.contains("google docs")) {
+        document.add(0.4, "title names a document");
+    }
+
+    let mut article = Candidate::new(ContentKind::BrowserArticle);
+    if is_browser {
+        article.add(0.35, format!("browser ({})", process_name));
+        if ocr_lines >= 15 {
+            article.add(0.2, "page is mostly text");
+        }
+    }
+
+    let best = [terminal, code, video, chat, spreadsheet, document, article]
+        .into_iter()
+        .max_by(|a, b| a.score.total_cmp(&b.score))
+        .expect("candidates");
+    if best.score < CLASSIFICATION_THRESHOLD {
+        return ContentClassification { kind: ContentKind::Other, confidence: 0.0, signals: best.signals };
+    }
+    ContentClassification {
+        kind: best.kind,
+        confidence: best.score.min(1.0),
+        signals: best.signals,
+    }
+}
+
+impl ContentClassState {
+    /// Classify a capture and count the time since the previous one toward the previous kind
+    pub fn observe(&self, window_title: &str, process_name: &str, ocr_text: Option<&str>) -> ContentClassification {
+        let classification = classify(window_title, process_name, ocr_text);
+        let now = chrono::Utc::now().timestamp();
+        let previous = self.current.lock().unwrap().replace((classification.kind, now));
+        if let Some((kind, since)) = previous {
+            let mut samples = self.samples.lock().unwrap();
+            samples.push(Sample { timestamp: now, kind, seconds: (now - since).clamp(0, MAX_SAMPLE_SECS) });
+            samples.retain(|s| now - s.timestamp <= RETENTION_SECS);
+        }
+        classification
+    }
+
+    /// Seconds spent on each kind of content between `from` and `to`, most first
+    pub fn totals(&self, from: i64, to: i64) -> Vec<ContentTime> {
+        let mut totals: Vec<ContentTime> = Vec::new();
+        for sample in self.samples.lock().unwrap().iter().filter(|s| s.timestamp >= from && s.timestamp <= to) {
+            match totals.iter_mut().find(|t| t.kind == sample.kind) {
+                Some(total) => total.seconds += sample.seconds,
+                None => totals.push(ContentTime { kind: sample.kind, seconds: sample.seconds }),
+            }
+        }
+        totals.sort_by_key(|t| std::cmp::Reverse(t.seconds));
+        totals
+    }
+}
+
+/// Label content from a window's title, process name and (optionally) its OCR text
+#[tauri::command]
+pub async fn classify_screen_content(
+    window_title: String,
+    process_name: String,
+    ocr_text: Option<String>,
+) -> Result<ContentClassification, String> {
+    Ok(classify(&window_title, &process_name, ocr_text.as_deref()))
+}
+
+/// Time spent on each kind of content over the last `hours` (default 24)
+#[tauri::command]
+pub async fn get_content_time(
+    state: State<'_, ContentClassState>,
+    hours: Option<u32>,
+) -> Result<Vec<ContentTime>, String> {
+    let now = chrono::Utc::now().timestamp();
+    Ok(state.totals(now - hours.unwrap_or(24) as i64 * 60 * 60, now))
+}
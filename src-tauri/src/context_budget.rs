@@ -0,0 +1,264 @@
+//! Token counts and a context assembler that fits OCR text, window lists and recent changes
+//! into the model's token budget. Counts are estimates from a tiktoken-style split of the text
+//! (no vocabulary ships with the app), tuned per tokenizer family and erring slightly high.
+//! The assembler keeps the active window first, then the most recent sections, and cuts the
+//! section that crosses the budget rather than sending a request the model would reject.
+
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex, OnceLock};
+use tauri::{AppHandle, Manager, State};
+
+use crate::persistence;
+
+const SETTINGS_FILE: &str = "context_budget.json";
+const MIN_BUDGET: u32 = 256;
+const MAX_BUDGET: u32 = 2_000_000;
+/// Sections that would be cut to fewer tokens than this are left out instead
+const MIN_SECTION_TOKENS: u32 = 32;
+const TRUNCATED_MARKER: &str = "\n[… truncated]";
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct ContextBudgetSettings {
+    /// Tokens the assembled context may use
+    pub max_tokens: u32,
+    /// Model whose tokenizer the counts follow
+    pub model: String,
+}
+
+impl Default for ContextBudgetSettings {
+    fn default() -> Self {
+        Self { max_tokens: 8000, model: "gemini-flash-latest".to_string() }
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct ContextBudgetState {
+    settings: Arc<Mutex<ContextBudgetSettings>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TokenCount {
+    pub tokens: u32,
+    /// Tokenizer family the estimate follows: "cl100k", "o200k" or "gemini"
+    pub encoding: String,
+    /// Always false: counts are estimates
+    pub exact: bool,
+}
+
+/// One piece of context offered to the assembler
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ContextSection {
+    /// Heading for the section, e.g. "Active window: Visual Studio Code"
+    pub label: String,
+    pub text: String,
+    /// The window the student is working in; always placed first
+    #[serde(default)]
+    pub active_window: bool,
+    /// When the content was captured or changed; newer sections win the remaining budget
+    #[serde(default)]
+    pub timestamp: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct IncludedSection {
+    pub label: String,
+    pub tokens: u32,
+    pub truncated: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AssembledContext {
+    pub text: String,
+    pub tokens: u32,
+    pub max_tokens: u32,
+    /// Included sections in the order they appear in `text`
+    pub sections: Vec<IncludedSection>,
+    /// Labels of sections left out for lack of budget
+    pub dropped: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Encoding {
+    Cl100k,
+    O200k,
+    Gemini,
+}
+
+impl Encoding {
+    fn for_model(model: &str) -> Self {
+        let model = model.to_lowercase();
+        if model.starts_with("gemini") || model.starts_with("gemma") {
+            Encoding::Gemini
+        } else if ["gpt-4o", "gpt-4.1", "gpt-5", "o1", "o3", "o4"].iter().any(|prefix| model.starts_with(prefix)) {
+            Encoding::O200k
+        } else {
+            Encoding::Cl100k
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Encoding::Cl100k => "cl100k",
+            Encoding::O200k => "o200k",
+            Encoding::Gemini => "gemini",
+        }
+    }
+
+    /// Characters of an ASCII word per token; larger vocabularies split long words less
+    fn word_chunk(self) -> usize {
+        match self {
+            Encoding::Cl100k => 5,
+            Encoding::O200k | Encoding::Gemini => 6,
+        }
+    }
+}
+
+/// The pre-tokenizer split tiktoken uses, minus its lookahead
+fn pieces() -> &'static Regex {
+    static PIECES: OnceLock<Regex> = OnceLock::new();
+    PIECES.get_or_init(|| {
+        Regex::new(r"(?i:'s|'t|'re|'ve|'m|'ll|'d)| ?\p{L}+| ?\p{N}{1,3}| ?[^\s\p{L}\p{N}]+|\s+").unwrap()
+    })
+}
+
+fn piece_tokens(piece: &str, encoding: Encoding) -> u32 {
+    let body = piece.strip_prefix(' ').unwrap_or(piece);
+    let first = body.chars().next().unwrap_or(' ');
+    let tokens = if first.is_whitespace() {
+        1
+    } else if first.is_alphabetic() {
+        let ascii = body.chars().filter(char::is_ascii).count();
+        // Scripts outside ASCII are mostly a token per character or two
+        ascii.div_ceil(encoding.word_chunk()) + (body.chars().count() - ascii)
+    } else if first.is_numeric() {
+        1
+    } else {
+        body.chars().count().div_ceil(2)
+    };
+    tokens.max(1) as u32
+}
+
+/// Estimated tokens in `text`
+fn count(text: &str, encoding: Encoding) -> u32 {
+    pieces().find_iter(text).map(|m| piece_tokens(m.as_str(), encoding)).sum()
+}
+
+/// Estimated tokens `model` would count in `text`
+pub(crate) fn estimate_tokens(text: &str, model: &str) -> u32 {
+    count(text, Encoding::for_model(model))
+}
+
+/// The longest start of `text` within `budget` tokens, cut between pieces
+fn truncate(text: &str, budget: u32, encoding: Encoding) -> (String, u32) {
+    let mut used = 0;
+    let mut end = 0;
+    for piece in pieces().find_iter(text) {
+        let tokens = piece_tokens(piece.as_str(), encoding);
+        if used + tokens > budget {
+            break;
+        }
+        used += tokens;
+        end = piece.end();
+    }
+    (text[..end].trim_end().to_string(), used)
+}
+
+fn section_header(label: &str) -> String {
+    format!("## {}\n", label)
+}
+
+/// Fit `sections` into `max_tokens`: the active window first, then newest first
+fn assemble(mut sections: Vec<ContextSection>, max_tokens: u32, encoding: Encoding) -> AssembledContext {
+    sections.sort_by_key(|s| (!s.active_window, std::cmp::Reverse(s.timestamp.unwrap_or(i64::MIN))));
+    let separator_tokens = count("\n\n", encoding);
+    let marker_tokens = count(TRUNCATED_MARKER, encoding);
+    let mut parts: Vec<String> = Vec::new();
+    let mut included = Vec::new();
+    let mut dropped = Vec::new();
+    let mut used = 0;
+
+    for section in sections {
+        let header = section_header(&section.label);
+        let overhead = count(&header, encoding) + if parts.is_empty() { 0 } else { separator_tokens };
+        let tokens = count(&section.text, encoding);
+        let remaining = max_tokens.saturating_sub(used + overhead);
+        if tokens <= remaining {
+            parts.push(format!("{}{}", header, section.text));
+            used += overhead + tokens;
+            included.push(IncludedSection { label: section.label, tokens, truncated: false });
+            continue;
+        }
+        let room = remaining.saturating_sub(marker_tokens);
+        if room < MIN_SECTION_TOKENS {
+            dropped.push(section.label);
+            continue;
+        }
+        let (kept, kept_tokens) = truncate(&section.text, room, encoding);
+        parts.push(format!("{}{}{}", header, kept, TRUNCATED_MARKER));
+        used += overhead + kept_tokens + marker_tokens;
+        included.push(IncludedSection { label: section.label, tokens: kept_tokens + marker_tokens, truncated: true });
+    }
+
+    AssembledContext { text: parts.join("\n\n"), tokens: used, max_tokens, sections: included, dropped }
+}
+
+pub fn init(app: &AppHandle) {
+    let settings: ContextBudgetSettings = persistence::data_file(app, SETTINGS_FILE)
+        .map(|path| persistence::load_json(&path))
+        .unwrap_or_default();
+    *app.state::<ContextBudgetState>().settings.lock().unwrap() = settings;
+}
+
+/// Estimated token count of `text` for `model` (the configured model when omitted)
+#[tauri::command]
+pub async fn count_tokens(
+    state: State<'_, ContextBudgetState>,
+    text: String,
+    model: Option<String>,
+) -> Result<TokenCount, String> {
+    let model = model.unwrap_or_else(|| state.settings.lock().unwrap().model.clone());
+    let encoding = Encoding::for_model(&model);
+    Ok(TokenCount { tokens: count(&text, encoding), encoding: encoding.name().to_string(), exact: false })
+}
+
+/// Join context sections under headings, trimmed to the token budget. The active window comes
+/// first, then the newest sections; the section crossing the budget is cut and later ones dropped.
+#[tauri::command]
+pub async fn assemble_context(
+    state: State<'_, ContextBudgetState>,
+    sections: Vec<ContextSection>,
+    max_tokens: Option<u32>,
+    model: Option<String>,
+) -> Result<AssembledContext, String> {
+    let settings = state.settings.lock().unwrap().clone();
+    let max_tokens = max_tokens.unwrap_or(settings.max_tokens).clamp(MIN_BUDGET, MAX_BUDGET);
+    let encoding = Encoding::for_model(model.as_deref().unwrap_or(&settings.model));
+    Ok(assemble(sections, max_tokens, encoding))
+}
+
+#[tauri::command]
+pub async fn get_context_budget(state: State<'_, ContextBudgetState>) -> Result<ContextBudgetSettings, String> {
+    Ok(state.settings.lock().unwrap().clone())
+}
+
+#[tauri::command]
+pub async fn set_context_budget(
+    app: AppHandle,
+    state: State<'_, ContextBudgetState>,
+    settings: ContextBudgetSettings,
+) -> Result<(), String> {
+    if !(MIN_BUDGET..=MAX_BUDGET).contains(&settings.max_tokens) {
+        return Err(format!("The token budget must be between {} and {}", MIN_BUDGET, MAX_BUDGET));
+    }
+    if settings.model.trim().is_empty() {
+        return Err("A model name is required".to_string());
+    }
+    let path = persistence::data_file(&app, SETTINGS_FILE)?;
+    persistence::save_json(&path, &settings)?;
+    tracing::info!("🧮 Context budget set to {} tokens for {}", settings.max_tokens, settings.model);
+    *state.settings.lock().unwrap() = settings;
+    Ok(())
+}
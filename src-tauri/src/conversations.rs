@@ -0,0 +1,382 @@
+//! AI chat transcripts, stored in the history database instead of the webview's store so they
+//! survive a cleared webview, can be searched and are part of data exports. A conversation can
+//! belong to a focus session, its messages can point at the capture they were about, and a
+//! conversation can be branched at any message to try a different question from there.
+
+use rusqlite::{params, Connection, OptionalExtension};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::history::HistoryState;
+
+const MAX_TITLE_LENGTH: usize = 200;
+/// Characters of the first question used as the title of an untitled conversation
+const AUTO_TITLE_LENGTH: usize = 60;
+const DEFAULT_TITLE: &str = "New Conversation";
+const DEFAULT_LIST_LIMIT: u32 = 50;
+const MAX_LIST_LIMIT: u32 = 500;
+
+/// Conversation tables, applied by the history database's migrations
+pub(crate) const SCHEMA: &str = r#"
+CREATE TABLE conversations (
+    id INTEGER PRIMARY KEY,
+    title TEXT NOT NULL,
+    parent_id INTEGER REFERENCES conversations(id) ON DELETE SET NULL,
+    branched_from_message INTEGER,
+    session_id TEXT,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL
+);
+CREATE TABLE conversation_messages (
+    id INTEGER PRIMARY KEY,
+    conversation_id INTEGER NOT NULL REFERENCES conversations(id) ON DELETE CASCADE,
+    role TEXT NOT NULL,
+    content TEXT NOT NULL,
+    capture_hash TEXT,
+    created_at INTEGER NOT NULL
+);
+CREATE INDEX conversation_messages_conversation ON conversation_messages(conversation_id, id);
+CREATE INDEX conversations_session ON conversations(session_id);
+CREATE VIRTUAL TABLE conversation_messages_fts USING fts5(content, content='conversation_messages', content_rowid='id');
+CREATE TRIGGER conversation_messages_insert AFTER INSERT ON conversation_messages BEGIN
+    INSERT INTO conversation_messages_fts(rowid, content) VALUES (new.id, new.content);
+END;
+CREATE TRIGGER conversation_messages_delete AFTER DELETE ON conversation_messages BEGIN
+    INSERT INTO conversation_messages_fts(conversation_messages_fts, rowid, content) VALUES ('delete', old.id, old.content);
+END;
+CREATE TRIGGER conversation_messages_update AFTER UPDATE ON conversation_messages BEGIN
+    INSERT INTO conversation_messages_fts(conversation_messages_fts, rowid, content) VALUES ('delete', old.id, old.content);
+    INSERT INTO conversation_messages_fts(rowid, content) VALUES (new.id, new.content);
+END;
+"#;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MessageRole {
+    User,
+    Assistant,
+    System,
+}
+
+impl MessageRole {
+    fn as_str(self) -> &'static str {
+        match self {
+            MessageRole::User => "user",
+            MessageRole::Assistant => "assistant",
+            MessageRole::System => "system",
+        }
+    }
+
+    fn parse(role: &str) -> Self {
+        match role {
+            "user" => MessageRole::User,
+            "system" => MessageRole::System,
+            _ => MessageRole::Assistant,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ConversationMessage {
+    pub id: i64,
+    pub conversation_id: i64,
+    pub role: MessageRole,
+    pub content: String,
+    /// The capture the message was about, by its `hash`
+    pub capture_hash: Option<String>,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct NewMessage {
+    pub role: MessageRole,
+    pub content: String,
+    #[serde(default)]
+    pub capture_hash: Option<String>,
+    /// When it was said, for imported transcripts; defaults to now
+    #[serde(default)]
+    pub created_at: Option<i64>,
+}
+
+/// A conversation without its messages, as listed and searched
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ConversationSummary {
+    pub id: i64,
+    pub title: String,
+    /// The conversation this one was branched from
+    pub parent_id: Option<i64>,
+    /// The last message copied from the parent
+    pub branched_from_message: Option<i64>,
+    /// The focus session it belongs to
+    pub session_id: Option<String>,
+    pub message_count: u32,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Conversation {
+    #[serde(flatten)]
+    pub summary: ConversationSummary,
+    pub messages: Vec<ConversationMessage>,
+}
+
+fn validate_title(title: &str) -> Result<String, String> {
+    let title = title.trim();
+    if title.is_empty() || title.chars().count() > MAX_TITLE_LENGTH {
+        return Err(format!("Conversation title must be 1 to {} characters", MAX_TITLE_LENGTH));
+    }
+    Ok(title.to_string())
+}
+
+/// The start of the first question, on one line
+fn auto_title(content: &str) -> String {
+    let line = content.split_whitespace().collect::<Vec<_>>().join(" ");
+    if line.chars().count() <= AUTO_TITLE_LENGTH {
+        return line;
+    }
+    let cut: String = line.chars().take(AUTO_TITLE_LENGTH).collect();
+    format!("{}…", cut.trim_end())
+}
+
+const SUMMARY_COLUMNS: &str = "c.id, c.title, c.parent_id, c.branched_from_message, c.session_id,
+    (SELECT COUNT(*) FROM conversation_messages m WHERE m.conversation_id = c.id), c.created_at, c.updated_at";
+
+fn summary_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<ConversationSummary> {
+    Ok(ConversationSummary {
+        id: row.get(0)?,
+        title: row.get(1)?,
+        parent_id: row.get(2)?,
+        branched_from_message: row.get(3)?,
+        session_id: row.get(4)?,
+        message_count: row.get(5)?,
+        created_at: row.get(6)?,
+        updated_at: row.get(7)?,
+    })
+}
+
+fn message_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<ConversationMessage> {
+    let role: String = row.get(2)?;
+    Ok(ConversationMessage {
+        id: row.get(0)?,
+        conversation_id: row.get(1)?,
+        role: MessageRole::parse(&role),
+        content: row.get(3)?,
+        capture_hash: row.get(4)?,
+        created_at: row.get(5)?,
+    })
+}
+
+fn load_summary(db: &Connection, id: i64) -> rusqlite::Result<Option<ConversationSummary>> {
+    db.query_row(&format!("SELECT {} FROM conversations c WHERE c.id = ?1", SUMMARY_COLUMNS), [id], summary_from_row)
+        .optional()
+}
+
+fn load_conversation(db: &Connection, id: i64) -> rusqlite::Result<Option<Conversation>> {
+    let Some(summary) = load_summary(db, id)? else {
+        return Ok(None);
+    };
+    let messages = db
+        .prepare(
+            "SELECT id, conversation_id, role, content, capture_hash, created_at FROM conversation_messages
+             WHERE conversation_id = ?1 ORDER BY id",
+        )?
+        .query_map([id], message_from_row)?
+        .collect::<rusqlite::Result<_>>()?;
+    Ok(Some(Conversation { summary, messages }))
+}
+
+fn missing(id: i64) -> String {
+    format!("No conversation with id {}", id)
+}
+
+/// Start an empty conversation, optionally as part of a focus session
+#[tauri::command]
+pub async fn create_conversation(
+    state: State<'_, HistoryState>,
+    title: Option<String>,
+    session_id: Option<String>,
+) -> Result<Conversation, String> {
+    let title = title.as_deref().map(validate_title).transpose()?.unwrap_or_else(|| DEFAULT_TITLE.to_string());
+    let now = chrono::Utc::now().timestamp();
+    let created = state.with_db(|db| {
+        db.execute(
+            "INSERT INTO conversations (title, session_id, created_at, updated_at) VALUES (?1, ?2, ?3, ?3)",
+            params![title, session_id, now],
+        )?;
+        load_conversation(db, db.last_insert_rowid())
+    })?;
+    let created = created.ok_or("Created conversation disappeared")?;
+    tracing::info!("💬 Created conversation {}", created.summary.id);
+    Ok(created)
+}
+
+/// Add a message to the end of a conversation. The first user message names a conversation
+/// still carrying the default title.
+#[tauri::command]
+pub async fn append_message(
+    state: State<'_, HistoryState>,
+    conversation_id: i64,
+    message: NewMessage,
+) -> Result<ConversationMessage, String> {
+    let now = chrono::Utc::now().timestamp();
+    let created_at = message.created_at.unwrap_or(now);
+    let title = (message.role == MessageRole::User).then(|| auto_title(&message.content)).filter(|t| !t.is_empty());
+    let appended = state.with_db(|db| {
+        let tx = db.transaction()?;
+        let changed = tx.execute(
+            "UPDATE conversations SET updated_at = MAX(updated_at, ?2),
+                    title = CASE WHEN ?3 IS NOT NULL AND title = ?4
+                                  AND NOT EXISTS (SELECT 1 FROM conversation_messages WHERE conversation_id = ?1 AND role = 'user')
+                             THEN ?3 ELSE title END
+             WHERE id = ?1",
+            params![conversation_id, created_at, title, DEFAULT_TITLE],
+        )?;
+        if changed == 0 {
+            return Ok(None);
+        }
+        tx.execute(
+            "INSERT INTO conversation_messages (conversation_id, role, content, capture_hash, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![conversation_id, message.role.as_str(), message.content, message.capture_hash, created_at],
+        )?;
+        let id = tx.last_insert_rowid();
+        tx.commit()?;
+        Ok(Some(id))
+    })?;
+    let id = appended.ok_or_else(|| missing(conversation_id))?;
+    Ok(ConversationMessage {
+        id,
+        conversation_id,
+        role: message.role,
+        content: message.content,
+        capture_hash: message.capture_hash,
+        created_at,
+    })
+}
+
+/// Replace the text of a stored message, e.g. after the student edits their question
+#[tauri::command]
+pub async fn update_message(state: State<'_, HistoryState>, id: i64, content: String) -> Result<(), String> {
+    let changed = state.with_db(|db| {
+        let tx = db.transaction()?;
+        let changed = tx.execute("UPDATE conversation_messages SET content = ?2 WHERE id = ?1", params![id, content])?;
+        tx.execute(
+            "UPDATE conversations SET updated_at = ?2
+             WHERE id = (SELECT conversation_id FROM conversation_messages WHERE id = ?1)",
+            params![id, chrono::Utc::now().timestamp()],
+        )?;
+        tx.commit()?;
+        Ok(changed)
+    })?;
+    if changed == 0 {
+        return Err(format!("No message with id {}", id));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_conversation(state: State<'_, HistoryState>, id: i64) -> Result<Conversation, String> {
+    state.with_db(|db| load_conversation(db, id))?.ok_or_else(|| missing(id))
+}
+
+/// Conversations, most recently active first; `session_id` keeps only those of one focus session
+#[tauri::command]
+pub async fn list_conversations(
+    state: State<'_, HistoryState>,
+    session_id: Option<String>,
+    limit: Option<u32>,
+) -> Result<Vec<ConversationSummary>, String> {
+    let limit = limit.unwrap_or(DEFAULT_LIST_LIMIT).clamp(1, MAX_LIST_LIMIT);
+    state.with_db(|db| {
+        db.prepare(&format!(
+            "SELECT {} FROM conversations c WHERE ?1 IS NULL OR c.session_id = ?1
+             ORDER BY c.updated_at DESC, c.id DESC LIMIT ?2",
+            SUMMARY_COLUMNS
+        ))?
+        .query_map(params![session_id, limit], summary_from_row)?
+        .collect()
+    })
+}
+
+/// Conversations with messages matching `query`, best match first
+#[tauri::command]
+pub async fn search_conversations(
+    state: State<'_, HistoryState>,
+    query: String,
+    limit: Option<u32>,
+) -> Result<Vec<ConversationSummary>, String> {
+    let query = crate::history::fts_query(&query);
+    if query.is_empty() {
+        return Err("Search query is empty".to_string());
+    }
+    let limit = limit.unwrap_or(DEFAULT_LIST_LIMIT).clamp(1, MAX_LIST_LIMIT);
+    state.with_db(|db| {
+        db.prepare(&format!(
+            "SELECT {} FROM conversations c
+             JOIN (SELECT m.conversation_id, MIN(conversation_messages_fts.rank) AS best
+                   FROM conversation_messages_fts JOIN conversation_messages m ON m.id = conversation_messages_fts.rowid
+                   WHERE conversation_messages_fts MATCH ?1 GROUP BY m.conversation_id) hits ON hits.conversation_id = c.id
+             ORDER BY hits.best LIMIT ?2",
+            SUMMARY_COLUMNS
+        ))?
+        .query_map(params![query, limit], summary_from_row)?
+        .collect()
+    })
+}
+
+/// Copy a conversation up to and including `message_id` into a new conversation, so the chat can
+/// continue differently from there while the original stays as it was
+#[tauri::command]
+pub async fn branch_conversation(
+    state: State<'_, HistoryState>,
+    id: i64,
+    message_id: i64,
+    title: Option<String>,
+) -> Result<Conversation, String> {
+    let title = title.as_deref().map(validate_title).transpose()?;
+    let now = chrono::Utc::now().timestamp();
+    let branched = state.with_db(|db| {
+        let tx = db.transaction()?;
+        let parent = tx
+            .query_row(
+                "SELECT c.title, c.session_id FROM conversations c
+                 JOIN conversation_messages m ON m.conversation_id = c.id WHERE c.id = ?1 AND m.id = ?2",
+                params![id, message_id],
+                |row| Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?)),
+            )
+            .optional()?;
+        let Some((parent_title, session_id)) = parent else {
+            return Ok(None);
+        };
+        tx.execute(
+            "INSERT INTO conversations (title, parent_id, branched_from_message, session_id, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?5)",
+            params![title.unwrap_or(parent_title), id, message_id, session_id, now],
+        )?;
+        let branch = tx.last_insert_rowid();
+        tx.execute(
+            "INSERT INTO conversation_messages (conversation_id, role, content, capture_hash, created_at)
+             SELECT ?1, role, content, capture_hash, created_at FROM conversation_messages
+             WHERE conversation_id = ?2 AND id <= ?3 ORDER BY id",
+            params![branch, id, message_id],
+        )?;
+        tx.commit()?;
+        load_conversation(db, branch)
+    })?;
+    let branched = branched.ok_or_else(|| format!("Conversation {} has no message {}", id, message_id))?;
+    tracing::info!("🌿 Branched conversation {} at message {} into {}", id, message_id, branched.summary.id);
+    Ok(branched)
+}
+
+/// Delete a conversation and its messages; branches of it are kept
+#[tauri::command]
+pub async fn delete_conversation(state: State<'_, HistoryState>, id: i64) -> Result<(), String> {
+    let deleted = state.with_db(|db| db.execute("DELETE FROM conversations WHERE id = ?1", [id]))?;
+    if deleted == 0 {
+        return Err(missing(id));
+    }
+    tracing::info!("🗑️ Deleted conversation {}", id);
+    Ok(())
+}
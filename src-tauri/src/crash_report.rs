@@ -0,0 +1,122 @@
+//! Panic capture: every backend panic is written to `crash_reports/` with a backtrace and
+//! announced through `app-error`. Background loops that panic are restarted by the watchdog.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager, State};
+
+use crate::persistence;
+
+pub(crate) const CRASH_DIR: &str = "crash_reports";
+/// Older reports are deleted once there are more than this
+const MAX_REPORTS: usize = 20;
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CrashReport {
+    pub id: String,
+    pub timestamp: i64,
+    pub app_version: String,
+    pub thread: Option<String>,
+    pub message: String,
+    /// `file:line:column` of the panic
+    pub location: Option<String>,
+    pub backtrace: String,
+}
+
+/// Payload of the `app-error` event
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AppError {
+    /// What went wrong, currently always "panic"
+    pub kind: String,
+    pub message: String,
+    /// Id of the crash report with the details
+    pub crash_id: Option<String>,
+    pub timestamp: i64,
+}
+
+#[derive(Clone, Default)]
+pub struct CrashReportState {
+    last: Arc<Mutex<Option<CrashReport>>>,
+}
+
+fn crash_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = persistence::shared_data_file(app, CRASH_DIR)?;
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create crash report directory: {}", e))?;
+    Ok(dir)
+}
+
+/// Report files, oldest first (names start with the timestamp)
+fn report_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map(|entries| entries.flatten().map(|e| e.path()).collect())
+        .unwrap_or_default();
+    files.retain(|p| p.extension().is_some_and(|ext| ext == "json"));
+    files.sort();
+    files
+}
+
+fn write_report(app: &AppHandle, report: &CrashReport) -> Result<(), String> {
+    let dir = crash_dir(app)?;
+    persistence::save_json(&dir.join(format!("{}-{}.json", report.timestamp, report.id)), report)?;
+    let files = report_files(&dir);
+    for old in files.iter().take(files.len().saturating_sub(MAX_REPORTS)) {
+        let _ = std::fs::remove_file(old);
+    }
+    Ok(())
+}
+
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "Unknown panic".to_string())
+}
+
+/// Install the panic hook and load the most recent report from earlier runs
+pub fn install(app: &AppHandle) {
+    let state = app.state::<CrashReportState>().inner().clone();
+    if let Some(latest) = crash_dir(app).ok().and_then(|dir| report_files(&dir).pop()) {
+        *state.last.lock().unwrap() = persistence::load_json(&latest);
+    }
+
+    let app = app.clone();
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let report = CrashReport {
+            id: uuid::Uuid::new_v4().simple().to_string(),
+            timestamp: chrono::Utc::now().timestamp_millis(),
+            app_version: app.package_info().version.to_string(),
+            thread: std::thread::current().name().map(String::from),
+            message: panic_message(info.payload()),
+            location: info.location().map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column())),
+            backtrace: std::backtrace::Backtrace::force_capture().to_string(),
+        };
+        tracing::error!("💥 Panic at {}: {}", report.location.as_deref().unwrap_or("unknown location"), report.message);
+        if let Err(e) = write_report(&app, &report) {
+            tracing::error!("Failed to write crash report: {}", e);
+        }
+        let _ = crate::event_bus::emit(
+            &app,
+            crate::events::APP_ERROR,
+            AppError {
+                kind: "panic".to_string(),
+                message: report.message.clone(),
+                crash_id: Some(report.id.clone()),
+                timestamp: report.timestamp,
+            },
+        );
+        // Never panic inside the hook, even if the lock was poisoned
+        *state.last.lock().unwrap_or_else(|e| e.into_inner()) = Some(report);
+        previous(info);
+    }));
+}
+
+#[tauri::command]
+pub async fn get_last_crash_report(
+    state: State<'_, CrashReportState>,
+) -> Result<Option<CrashReport>, String> {
+    Ok(state.last.lock().unwrap().clone())
+}
@@ -0,0 +1,345 @@
+//! Lesson plans: a curriculum of units and lessons with prerequisites, imported from a JSON or
+//! YAML file and stored in the history database along with which lessons are done. The next
+//! recommended lesson is the first one, in plan order, that isn't done and whose
+//! prerequisites all are.
+
+use rusqlite::{params, Connection, OptionalExtension};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use tauri::State;
+
+use crate::history::HistoryState;
+
+const MAX_FILE_BYTES: u64 = 5 * 1024 * 1024;
+
+/// Curriculum tables, applied by the history database's migrations
+pub(crate) const SCHEMA: &str = r#"
+CREATE TABLE curricula (
+    id TEXT PRIMARY KEY,
+    title TEXT NOT NULL,
+    definition TEXT NOT NULL,
+    imported_at INTEGER NOT NULL
+);
+CREATE TABLE lesson_progress (
+    curriculum_id TEXT NOT NULL REFERENCES curricula(id) ON DELETE CASCADE,
+    lesson_id TEXT NOT NULL,
+    completed_at INTEGER NOT NULL,
+    PRIMARY KEY (curriculum_id, lesson_id)
+);
+"#;
+
+/// A curriculum file's contents
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Curriculum {
+    /// Stable id; importing a file with the same id replaces the plan and keeps progress on
+    /// lessons that still exist
+    pub id: String,
+    pub title: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub units: Vec<Unit>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Unit {
+    pub id: String,
+    pub title: String,
+    pub lessons: Vec<Lesson>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Lesson {
+    /// Unique within the curriculum
+    pub id: String,
+    pub title: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Ids of lessons to finish first, from any unit
+    #[serde(default)]
+    pub prerequisites: Vec<String>,
+    #[serde(default)]
+    pub estimated_minutes: Option<u32>,
+    /// Exercise topics the lesson covers
+    #[serde(default)]
+    pub topics: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct LessonStatus {
+    pub unit_id: String,
+    pub lesson: Lesson,
+    pub completed_at: Option<i64>,
+    /// Every prerequisite is done
+    pub available: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CurriculumProgress {
+    pub curriculum: Curriculum,
+    /// Every lesson in plan order
+    pub lessons: Vec<LessonStatus>,
+    pub completed: u32,
+    pub total: u32,
+    /// The first available lesson that isn't done; `None` once the plan is finished
+    pub next: Option<LessonStatus>,
+    pub imported_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CurriculumSummary {
+    pub id: String,
+    pub title: String,
+    pub completed: u32,
+    pub total: u32,
+    pub imported_at: i64,
+}
+
+/// Non-empty, unique lesson ids, known prerequisites and no prerequisite cycles
+fn validate(curriculum: &Curriculum) -> Result<(), String> {
+    if curriculum.id.trim().is_empty() || curriculum.title.trim().is_empty() {
+        return Err("A curriculum needs an id and a title".to_string());
+    }
+    let lessons: Vec<&Lesson> = curriculum.units.iter().flat_map(|unit| &unit.lessons).collect();
+    if lessons.is_empty() {
+        return Err("The curriculum has no lessons".to_string());
+    }
+    let mut ids = HashSet::new();
+    for lesson in &lessons {
+        if lesson.id.trim().is_empty() {
+            return Err(format!("Lesson '{}' has no id", lesson.title));
+        }
+        if !ids.insert(lesson.id.as_str()) {
+            return Err(format!("Lesson id '{}' is used twice", lesson.id));
+        }
+    }
+    let prerequisites: HashMap<&str, &[String]> =
+        lessons.iter().map(|lesson| (lesson.id.as_str(), lesson.prerequisites.as_slice())).collect();
+    for lesson in &lessons {
+        if let Some(missing) = lesson.prerequisites.iter().find(|p| !ids.contains(p.as_str())) {
+            return Err(format!("Lesson '{}' requires unknown lesson '{}'", lesson.id, missing));
+        }
+    }
+
+    // Depth-first search; a lesson reached again while still on the path closes a cycle
+    fn visit<'a>(
+        id: &'a str,
+        prerequisites: &HashMap<&'a str, &'a [String]>,
+        on_path: &mut HashSet<&'a str>,
+        done: &mut HashSet<&'a str>,
+    ) -> Result<(), String> {
+        if done.contains(id) {
+            return Ok(());
+        }
+        if !on_path.insert(id) {
+            return Err(format!("Lesson '{}' is its own prerequisite", id));
+        }
+        for prerequisite in prerequisites[id] {
+            visit(prerequisite, prerequisites, on_path, done)?;
+        }
+        on_path.remove(id);
+        done.insert(id);
+        Ok(())
+    }
+    let (mut on_path, mut done) = (HashSet::new(), HashSet::new());
+    for lesson in &lessons {
+        visit(&lesson.id, &prerequisites, &mut on_path, &mut done)?;
+    }
+    Ok(())
+}
+
+fn progress(curriculum: Curriculum, completed: &HashMap<String, i64>, imported_at: i64) -> CurriculumProgress {
+    let lessons: Vec<LessonStatus> = curriculum
+        .units
+        .iter()
+        .flat_map(|unit| unit.lessons.iter().map(move |lesson| (unit, lesson)))
+        .map(|(unit, lesson)| LessonStatus {
+            unit_id: unit.id.clone(),
+            completed_at: completed.get(&lesson.id).copied(),
+            available: lesson.prerequisites.iter().all(|p| completed.contains_key(p)),
+            lesson: lesson.clone(),
+        })
+        .collect();
+    let done = lessons.iter().filter(|status| status.completed_at.is_some()).count() as u32;
+    CurriculumProgress {
+        next: lessons.iter().find(|status| status.available && status.completed_at.is_none()).cloned(),
+        completed: done,
+        total: lessons.len() as u32,
+        lessons,
+        curriculum,
+        imported_at,
+    }
+}
+
+fn load(db: &Connection, id: &str) -> rusqlite::Result<Option<CurriculumProgress>> {
+    let row: Option<(String, i64)> = db
+        .query_row("SELECT definition, imported_at FROM curricula WHERE id = ?1", [id], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })
+        .optional()?;
+    let Some((definition, imported_at)) = row else {
+        return Ok(None);
+    };
+    let curriculum: Curriculum = serde_json::from_str(&definition)
+        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(2, rusqlite::types::Type::Text, Box::new(e)))?;
+    let completed: HashMap<String, i64> = db
+        .prepare("SELECT lesson_id, completed_at FROM lesson_progress WHERE curriculum_id = ?1")?
+        .query_map([id], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<rusqlite::Result<_>>()?;
+    Ok(Some(progress(curriculum, &completed, imported_at)))
+}
+
+/// Curricula with every lesson done
+pub(crate) fn completed_count(db: &Connection) -> rusqlite::Result<u32> {
+    let ids: Vec<String> =
+        db.prepare("SELECT id FROM curricula")?.query_map([], |row| row.get(0))?.collect::<rusqlite::Result<_>>()?;
+    let mut completed = 0;
+    for id in ids {
+        if load(db, &id)?.is_some_and(|progress| progress.completed == progress.total) {
+            completed += 1;
+        }
+    }
+    Ok(completed)
+}
+
+fn get(state: &HistoryState, id: &str) -> Result<CurriculumProgress, String> {
+    state.with_db(|db| load(db, id))?.ok_or_else(|| format!("No curriculum with id {}", id))
+}
+
+/// Find a lesson by id, in `curriculum_id` or else in whichever curriculum has it. Returns the
+/// curriculum's id with the lesson.
+pub(crate) fn find_lesson(
+    state: &HistoryState,
+    lesson_id: &str,
+    curriculum_id: Option<&str>,
+) -> Result<(String, LessonStatus), String> {
+    let ids: Vec<String> = match curriculum_id {
+        Some(id) => vec![id.to_string()],
+        None => state.with_db(|db| {
+            let ids = db.prepare("SELECT id FROM curricula ORDER BY title")?.query_map([], |row| row.get(0))?.collect();
+            ids
+        })?,
+    };
+    let mut found = Vec::new();
+    for id in ids {
+        if let Some(status) = get(state, &id)?.lessons.into_iter().find(|status| status.lesson.id == lesson_id) {
+            found.push((id, status));
+        }
+    }
+    match found.len() {
+        0 => Err(format!("No lesson '{}' was found", lesson_id)),
+        1 => Ok(found.remove(0)),
+        _ => Err(format!("Several curricula have a lesson '{}'; name the curriculum too", lesson_id)),
+    }
+}
+
+/// Import a curriculum from a `.json`, `.yaml` or `.yml` file, replacing one with the same id
+#[tauri::command]
+pub async fn import_curriculum(state: State<'_, HistoryState>, path: String) -> Result<CurriculumProgress, String> {
+    let size = tokio::fs::metadata(&path).await.map_err(|e| format!("Failed to read {}: {}", path, e))?.len();
+    if size > MAX_FILE_BYTES {
+        return Err(format!("Curriculum files over {} MB aren't supported", MAX_FILE_BYTES / (1024 * 1024)));
+    }
+    let contents = tokio::fs::read_to_string(&path).await.map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let lower = path.to_lowercase();
+    let curriculum: Curriculum = if lower.ends_with(".yaml") || lower.ends_with(".yml") {
+        serde_yaml::from_str(&contents).map_err(|e| format!("Invalid curriculum YAML: {}", e))?
+    } else if lower.ends_with(".json") {
+        serde_json::from_str(&contents).map_err(|e| format!("Invalid curriculum JSON: {}", e))?
+    } else {
+        return Err("Curriculum files must be .json, .yaml or .yml".to_string());
+    };
+    validate(&curriculum)?;
+
+    let definition = serde_json::to_string(&curriculum).map_err(|e| format!("Failed to store curriculum: {}", e))?;
+    let lesson_ids: Vec<String> =
+        curriculum.units.iter().flat_map(|unit| &unit.lessons).map(|lesson| lesson.id.clone()).collect();
+    let id = curriculum.id.clone();
+    state.with_db(|db| {
+        let tx = db.transaction()?;
+        tx.execute(
+            "INSERT INTO curricula (id, title, definition, imported_at) VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(id) DO UPDATE SET title = excluded.title, definition = excluded.definition,
+                 imported_at = excluded.imported_at",
+            params![id, curriculum.title, definition, chrono::Utc::now().timestamp()],
+        )?;
+        // Progress on lessons the new version dropped
+        let kept = serde_json::to_string(&lesson_ids).unwrap_or_default();
+        tx.execute(
+            "DELETE FROM lesson_progress WHERE curriculum_id = ?1 AND lesson_id NOT IN (SELECT value FROM json_each(?2))",
+            params![id, kept],
+        )?;
+        tx.commit()
+    })?;
+    tracing::info!("📚 Imported curriculum {} ({} lessons)", id, lesson_ids.len());
+    get(&state, &id)
+}
+
+#[tauri::command]
+pub async fn list_curricula(state: State<'_, HistoryState>) -> Result<Vec<CurriculumSummary>, String> {
+    let ids: Vec<String> = state.with_db(|db| {
+        let ids = db.prepare("SELECT id FROM curricula ORDER BY title")?.query_map([], |row| row.get(0))?.collect();
+        ids
+    })?;
+    let mut summaries = Vec::with_capacity(ids.len());
+    for id in ids {
+        let progress = get(&state, &id)?;
+        summaries.push(CurriculumSummary {
+            id,
+            title: progress.curriculum.title,
+            completed: progress.completed,
+            total: progress.total,
+            imported_at: progress.imported_at,
+        });
+    }
+    Ok(summaries)
+}
+
+/// A curriculum with each lesson's completion state and the next recommended lesson
+#[tauri::command]
+pub async fn get_curriculum(state: State<'_, HistoryState>, id: String) -> Result<CurriculumProgress, String> {
+    get(&state, &id)
+}
+
+/// Mark a lesson done, or not done again with `completed: false`
+#[tauri::command]
+pub async fn set_lesson_completed(
+    state: State<'_, HistoryState>,
+    curriculum_id: String,
+    lesson_id: String,
+    completed: bool,
+) -> Result<CurriculumProgress, String> {
+    let current = get(&state, &curriculum_id)?;
+    if !current.lessons.iter().any(|status| status.lesson.id == lesson_id) {
+        return Err(format!("Curriculum {} has no lesson '{}'", curriculum_id, lesson_id));
+    }
+    state.with_db(|db| {
+        if completed {
+            db.execute(
+                "INSERT OR IGNORE INTO lesson_progress (curriculum_id, lesson_id, completed_at) VALUES (?1, ?2, ?3)",
+                params![curriculum_id, lesson_id, chrono::Utc::now().timestamp()],
+            )
+        } else {
+            db.execute(
+                "DELETE FROM lesson_progress WHERE curriculum_id = ?1 AND lesson_id = ?2",
+                params![curriculum_id, lesson_id],
+            )
+        }
+    })?;
+    get(&state, &curriculum_id)
+}
+
+/// The next recommended lesson; `None` once every lesson is done
+#[tauri::command]
+pub async fn get_next_lesson(state: State<'_, HistoryState>, curriculum_id: String) -> Result<Option<LessonStatus>, String> {
+    Ok(get(&state, &curriculum_id)?.next)
+}
+
+#[tauri::command]
+pub async fn delete_curriculum(state: State<'_, HistoryState>, id: String) -> Result<(), String> {
+    let deleted = state.with_db(|db| db.execute("DELETE FROM curricula WHERE id = ?1", [&id]))?;
+    if deleted == 0 {
+        return Err(format!("No curriculum with id {}", id));
+    }
+    Ok(())
+}
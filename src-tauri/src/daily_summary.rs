@@ -0,0 +1,307 @@
+//! End-of-day summaries. At the configured time the day's time on task, content mix,
+//! exercises, flashcards, lessons, notes and imported documents are collected into a record in
+//! the history database and announced with `daily-summary-ready`. The AI client lives in the
+//! frontend, so when a narrative is wanted the frontend writes it and stores it with
+//! `set_daily_summary_narrative`.
+
+use chrono::{Local, NaiveDate, NaiveTime};
+use rusqlite::{params, Connection, OptionalExtension};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+
+use crate::content_class::{ContentClassState, ContentTime};
+use crate::history::HistoryState;
+use crate::persistence;
+
+const SETTINGS_FILE: &str = "daily_summary_settings.json";
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Daily summary table, applied by the history database's migrations
+pub(crate) const SCHEMA: &str = r#"
+CREATE TABLE daily_summaries (
+    day TEXT PRIMARY KEY,
+    summary TEXT NOT NULL,
+    narrative TEXT,
+    created_at INTEGER NOT NULL
+);
+"#;
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DailySummarySettings {
+    pub enabled: bool,
+    /// Local time the summary is made, as `HH:MM`
+    pub time: String,
+    /// Ask the frontend for an AI-written narrative with each summary
+    pub request_narrative: bool,
+}
+
+impl Default for DailySummarySettings {
+    fn default() -> Self {
+        Self { enabled: true, time: "20:00".to_string(), request_narrative: false }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TopicActivity {
+    pub topic: String,
+    pub attempts: u32,
+    pub correct: u32,
+}
+
+/// Payload of the `daily-summary-ready` event
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DailySummary {
+    /// Local date, `YYYY-MM-DD`
+    pub day: String,
+    /// Time on task, as counted for achievements
+    pub study_minutes: u32,
+    /// Time per kind of content seen in captures (while the app was running)
+    pub content_time: Vec<ContentTime>,
+    /// Exercise topics practiced, most attempts first
+    pub topics: Vec<TopicActivity>,
+    pub exercises_attempted: u32,
+    pub exercises_correct: u32,
+    pub flashcards_reviewed: u32,
+    /// Titles of lessons finished
+    pub lessons_completed: Vec<String>,
+    /// Titles of notes written
+    pub notes: Vec<String>,
+    /// Titles of documents imported
+    pub documents: Vec<String>,
+    /// Captures linked from the day's notes, by hash
+    pub notable_captures: Vec<String>,
+    /// Titles of badges unlocked
+    pub badges: Vec<String>,
+    /// AI-written recap, once the frontend has stored one
+    pub narrative: Option<String>,
+    /// Whether the frontend should write the narrative
+    pub narrative_requested: bool,
+    pub created_at: i64,
+}
+
+#[derive(Clone, Default)]
+pub struct DailySummaryState {
+    settings: Arc<Mutex<DailySummarySettings>>,
+}
+
+/// Load the saved settings
+pub fn init(app: &AppHandle) {
+    let settings: DailySummarySettings = persistence::data_file(app, SETTINGS_FILE)
+        .map(|path| persistence::load_json(&path))
+        .unwrap_or_default();
+    *app.state::<DailySummaryState>().settings.lock().unwrap() = settings;
+}
+
+/// Check every minute whether today's summary is due and make it
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(CHECK_INTERVAL).await;
+            let settings = app.state::<DailySummaryState>().settings.lock().unwrap().clone();
+            let Ok(time) = NaiveTime::parse_from_str(&settings.time, "%H:%M") else {
+                continue;
+            };
+            let now = Local::now();
+            if !settings.enabled || now.time() < time {
+                continue;
+            }
+            let day = now.date_naive();
+            let history = app.state::<HistoryState>();
+            match history.with_db(|db| load(db, &day.to_string())) {
+                Ok(None) => {}
+                Ok(Some(_)) => continue,
+                Err(e) => {
+                    tracing::debug!("Daily summary check skipped: {}", e);
+                    continue;
+                }
+            }
+            match build_and_store(&app, day, settings.request_narrative) {
+                Ok(summary) => {
+                    tracing::info!("📅 Daily summary ready for {}", summary.day);
+                    let _ = crate::event_bus::emit(&app, crate::events::DAILY_SUMMARY_READY, summary);
+                }
+                Err(e) => tracing::warn!("⚠️ Failed to make the daily summary: {}", e),
+            }
+        }
+    });
+}
+
+fn strings(db: &Connection, sql: &str, day: &str) -> rusqlite::Result<Vec<String>> {
+    db.prepare(sql)?.query_map([day], |row| row.get(0))?.collect()
+}
+
+/// Everything recorded in the history database on `day`
+fn collect(db: &Connection, day: &str) -> rusqlite::Result<DailySummary> {
+    let local_day = |column: &str| format!("date({}, 'unixepoch', 'localtime') = ?1", column);
+    let study_minutes = db
+        .query_row("SELECT MAX(active_minutes, focus_minutes) FROM study_days WHERE day = ?1", [day], |row| row.get(0))
+        .optional()?
+        .unwrap_or(0);
+    let topics: Vec<TopicActivity> = db
+        .prepare(&format!(
+            "SELECT e.topic, COUNT(*), SUM(a.correct) FROM exercise_attempts a JOIN exercises e ON e.id = a.exercise_id
+             WHERE {} GROUP BY e.topic ORDER BY COUNT(*) DESC, e.topic",
+            local_day("a.attempted_at")
+        ))?
+        .query_map([day], |row| Ok(TopicActivity { topic: row.get(0)?, attempts: row.get(1)?, correct: row.get(2)? }))?
+        .collect::<rusqlite::Result<_>>()?;
+    let flashcards_reviewed = db.query_row(
+        &format!("SELECT COUNT(*) FROM flashcard_reviews WHERE {}", local_day("reviewed_at")),
+        [day],
+        |row| row.get(0),
+    )?;
+
+    // Lesson titles live in the stored curriculum definitions
+    let lesson_rows: Vec<(String, String)> = db
+        .prepare(&format!(
+            "SELECT p.lesson_id, c.definition FROM lesson_progress p JOIN curricula c ON c.id = p.curriculum_id
+             WHERE {} ORDER BY p.completed_at",
+            local_day("p.completed_at")
+        ))?
+        .query_map([day], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<rusqlite::Result<_>>()?;
+    let lessons_completed = lesson_rows
+        .into_iter()
+        .map(|(lesson_id, definition)| {
+            serde_json::from_str::<crate::curriculum::Curriculum>(&definition)
+                .ok()
+                .and_then(|curriculum| {
+                    curriculum.units.into_iter().flat_map(|unit| unit.lessons).find(|lesson| lesson.id == lesson_id)
+                })
+                .map(|lesson| lesson.title)
+                .unwrap_or(lesson_id)
+        })
+        .collect();
+
+    let badge_ids = strings(db, &format!("SELECT id FROM achievements WHERE {}", local_day("unlocked_at")), day)?;
+    Ok(DailySummary {
+        day: day.to_string(),
+        study_minutes,
+        content_time: Vec::new(),
+        exercises_attempted: topics.iter().map(|t| t.attempts).sum(),
+        exercises_correct: topics.iter().map(|t| t.correct).sum(),
+        topics,
+        flashcards_reviewed,
+        lessons_completed,
+        notes: strings(db, &format!("SELECT title FROM notes WHERE {} ORDER BY created_at", local_day("created_at")), day)?,
+        documents: strings(
+            db,
+            &format!("SELECT title FROM history_items WHERE kind = 'document' AND {} ORDER BY created_at", local_day("created_at")),
+            day,
+        )?,
+        notable_captures: strings(
+            db,
+            &format!(
+                "SELECT DISTINCT l.target FROM note_links l JOIN notes n ON n.id = l.note_id
+                 WHERE l.kind = 'capture' AND {}",
+                local_day("n.created_at")
+            ),
+            day,
+        )?,
+        badges: crate::achievements::badge_titles(&badge_ids),
+        narrative: None,
+        narrative_requested: false,
+        created_at: chrono::Utc::now().timestamp(),
+    })
+}
+
+fn load(db: &Connection, day: &str) -> rusqlite::Result<Option<DailySummary>> {
+    let row: Option<(String, Option<String>)> = db
+        .query_row("SELECT summary, narrative FROM daily_summaries WHERE day = ?1", [day], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })
+        .optional()?;
+    Ok(row.and_then(|(summary, narrative)| {
+        let mut summary: DailySummary = serde_json::from_str(&summary).ok()?;
+        summary.narrative = narrative;
+        Some(summary)
+    }))
+}
+
+/// Collect and store the summary for `day`, replacing an earlier one but keeping its narrative
+fn build_and_store(app: &AppHandle, day: NaiveDate, request_narrative: bool) -> Result<DailySummary, String> {
+    let day_key = day.to_string();
+    let history = app.state::<HistoryState>();
+    let mut summary = history.with_db(|db| collect(db, &day_key))?;
+    let start = day.and_time(NaiveTime::MIN).and_local_timezone(Local).earliest().map(|t| t.timestamp());
+    if let Some(start) = start {
+        summary.content_time = app.state::<ContentClassState>().totals(start, start + 24 * 60 * 60);
+    }
+    summary.narrative_requested = request_narrative;
+
+    let json = serde_json::to_string(&summary).map_err(|e| format!("Failed to store the daily summary: {}", e))?;
+    let narrative = history.with_db(|db| {
+        db.execute(
+            "INSERT INTO daily_summaries (day, summary, created_at) VALUES (?1, ?2, ?3)
+             ON CONFLICT(day) DO UPDATE SET summary = excluded.summary, created_at = excluded.created_at",
+            params![day_key, json, summary.created_at],
+        )?;
+        db.query_row("SELECT narrative FROM daily_summaries WHERE day = ?1", [&day_key], |row| row.get(0))
+    })?;
+    summary.narrative = narrative;
+    Ok(summary)
+}
+
+fn parse_day(day: Option<String>) -> Result<NaiveDate, String> {
+    match day {
+        Some(day) => NaiveDate::parse_from_str(&day, "%Y-%m-%d").map_err(|e| format!("Invalid date '{}': {}", day, e)),
+        None => Ok(Local::now().date_naive()),
+    }
+}
+
+#[tauri::command]
+pub async fn get_daily_summary_settings(state: State<'_, DailySummaryState>) -> Result<DailySummarySettings, String> {
+    Ok(state.settings.lock().unwrap().clone())
+}
+
+#[tauri::command]
+pub async fn set_daily_summary_settings(
+    app: AppHandle,
+    state: State<'_, DailySummaryState>,
+    settings: DailySummarySettings,
+) -> Result<(), String> {
+    NaiveTime::parse_from_str(&settings.time, "%H:%M").map_err(|_| format!("Invalid time '{}', expected HH:MM", settings.time))?;
+    let path = persistence::data_file(&app, SETTINGS_FILE)?;
+    persistence::save_json(&path, &settings)?;
+    *state.settings.lock().unwrap() = settings;
+    Ok(())
+}
+
+/// Make (or remake) the summary for a day, today by default, without waiting for the scheduled time
+#[tauri::command]
+pub async fn generate_daily_summary(
+    app: AppHandle,
+    state: State<'_, DailySummaryState>,
+    day: Option<String>,
+) -> Result<DailySummary, String> {
+    let day = parse_day(day)?;
+    let request_narrative = state.settings.lock().unwrap().request_narrative;
+    let summary = build_and_store(&app, day, request_narrative)?;
+    let _ = crate::event_bus::emit(&app, crate::events::DAILY_SUMMARY_READY, summary.clone());
+    Ok(summary)
+}
+
+/// The stored summary for a day (today by default), if one was made
+#[tauri::command]
+pub async fn get_daily_summary(state: State<'_, HistoryState>, day: Option<String>) -> Result<Option<DailySummary>, String> {
+    let day = parse_day(day)?.to_string();
+    state.with_db(|db| load(db, &day))
+}
+
+/// Store the AI-written narrative for a day's summary
+#[tauri::command]
+pub async fn set_daily_summary_narrative(
+    state: State<'_, HistoryState>,
+    day: String,
+    narrative: String,
+) -> Result<DailySummary, String> {
+    let day = parse_day(Some(day))?.to_string();
+    let summary = state.with_db(|db| {
+        db.execute("UPDATE daily_summaries SET narrative = ?2 WHERE day = ?1", params![day, narrative.trim()])?;
+        load(db, &day)
+    })?;
+    summary.ok_or_else(|| format!("No daily summary for {}", day))
+}
@@ -0,0 +1,349 @@
+//! Export and import of everything the active profile stores locally: settings, the history
+//! database (history, notes, conversations, flashcards, exercises, curricula, achievements,
+//! summaries), imported document pages and captures, as one zip. Importing replaces the
+//! profile's database and documents, then restarts the app so every module reloads.
+
+use rusqlite::{params, Connection, OpenFlags};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, State};
+use zip::write::SimpleFileOptions;
+use zip::{ZipArchive, ZipWriter};
+
+use crate::history::{self, HistoryState};
+use crate::persistence;
+
+/// Bumped when the archive layout changes
+const FORMAT_VERSION: u32 = 1;
+const MANIFEST: &str = "manifest.json";
+const DATABASE_ENTRY: &str = "history.db";
+const SETTINGS_PREFIX: &str = "settings/";
+const DOCUMENTS_PREFIX: &str = "documents/";
+const CAPTURES_PREFIX: &str = "captures/";
+const DOCUMENTS_DIR: &str = "documents";
+/// Files in the data directory that belong to all profiles (or to the parent) and are
+/// neither exported nor overwritten
+const SHARED_FILES: &[&str] = &["profiles.json", "log_settings.json", "update_settings.json", "teacher_pin.json"];
+/// Settings only the teacher may change (role policy, sandbox limits, AI providers) and the
+/// consent log. Exported, but an import keeps the ones already here, or a crafted archive
+/// would get around the teacher-only commands.
+const PROTECTED_FILES: &[&str] = &["role_policy.json", "sandbox_settings.json", "ai_providers.json", "consent_log.json"];
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DataExport {
+    pub path: String,
+    pub size_bytes: u64,
+    /// Files inside the zip, besides the manifest
+    pub file_count: usize,
+    /// Schema version of the exported database
+    pub schema_version: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Manifest {
+    format_version: u32,
+    app_version: String,
+    created_at: i64,
+    schema_version: usize,
+    /// Where documents lived on the exporting machine; stored page image paths start with it
+    documents_dir: String,
+    files: Vec<String>,
+}
+
+/// Top-level settings files of the profile's data directory
+fn settings_files(profile_dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(profile_dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && path.extension().is_some_and(|e| e == "json"))
+        .filter(|path| {
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            !SHARED_FILES.contains(&name.as_ref())
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+/// Every file below `dir`, skipping the top-level entries in `skip`
+fn walk(dir: &Path, skip: &[&str]) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(current) = pending.pop() {
+        let Ok(entries) = fs::read_dir(&current) else { continue };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if current == dir && skip.iter().any(|s| entry.file_name() == *s) {
+                continue;
+            }
+            if path.is_dir() {
+                pending.push(path);
+            } else if path.is_file() {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+/// Name of `path` in the zip: `prefix` plus its path below `base`, with forward slashes
+fn entry_name(prefix: &str, base: &Path, path: &Path) -> String {
+    let relative = path.strip_prefix(base).unwrap_or(path);
+    let parts: Vec<String> = relative.components().map(|c| c.as_os_str().to_string_lossy().into_owned()).collect();
+    format!("{}{}", prefix, parts.join("/"))
+}
+
+struct ExportSources {
+    database: PathBuf,
+    profile_dir: PathBuf,
+    documents_dir: PathBuf,
+    captures_dir: PathBuf,
+    /// The default profile's captures directory also holds the other profiles' captures
+    skip_capture_dirs: Vec<&'static str>,
+    app_version: String,
+}
+
+fn write_archive(path: &Path, sources: &ExportSources) -> Result<usize, String> {
+    let file = fs::File::create(path).map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+    let mut zip = ZipWriter::new(file);
+    let mut files = Vec::new();
+    let mut add = |zip: &mut ZipWriter<fs::File>, name: String, source: &Path| -> Result<(), String> {
+        let mut input = fs::File::open(source).map_err(|e| format!("Failed to read {}: {}", source.display(), e))?;
+        zip.start_file(name.as_str(), SimpleFileOptions::default().large_file(true))
+            .map_err(|e| format!("Failed to add {} to the export: {}", name, e))?;
+        io::copy(&mut input, zip).map_err(|e| format!("Failed to add {} to the export: {}", name, e))?;
+        files.push(name);
+        Ok(())
+    };
+
+    add(&mut zip, DATABASE_ENTRY.to_string(), &sources.database)?;
+    for settings in settings_files(&sources.profile_dir) {
+        add(&mut zip, entry_name(SETTINGS_PREFIX, &sources.profile_dir, &settings), &settings)?;
+    }
+    for document in walk(&sources.documents_dir, &[]) {
+        add(&mut zip, entry_name(DOCUMENTS_PREFIX, &sources.documents_dir, &document), &document)?;
+    }
+    for capture in walk(&sources.captures_dir, &sources.skip_capture_dirs) {
+        add(&mut zip, entry_name(CAPTURES_PREFIX, &sources.captures_dir, &capture), &capture)?;
+    }
+
+    let manifest = Manifest {
+        format_version: FORMAT_VERSION,
+        app_version: sources.app_version.clone(),
+        created_at: chrono::Utc::now().timestamp(),
+        schema_version: history::schema_version(),
+        documents_dir: sources.documents_dir.to_string_lossy().into_owned(),
+        files,
+    };
+    let json = serde_json::to_vec_pretty(&manifest).map_err(|e| format!("Failed to serialize the manifest: {}", e))?;
+    zip.start_file(MANIFEST, SimpleFileOptions::default())
+        .and_then(|_| io::Write::write_all(&mut zip, &json).map_err(Into::into))
+        .map_err(|e| format!("Failed to add the manifest to the export: {}", e))?;
+    zip.finish().map_err(|e| format!("Failed to finish the export: {}", e))?;
+    Ok(manifest.files.len())
+}
+
+/// Write all of the active profile's data to a zip at `path`
+#[tauri::command]
+pub async fn export_all_data(app: AppHandle, history: State<'_, HistoryState>, path: String) -> Result<DataExport, String> {
+    let path = PathBuf::from(path);
+    let profile_dir = persistence::data_file(&app, "")?;
+    // A consistent copy of the database, even while other commands write to it
+    let database = std::env::temp_dir().join(format!("ai-teacher-export-{}.db", uuid::Uuid::new_v4().simple()));
+    history.with_db(|db| db.execute("VACUUM INTO ?1", [database.to_string_lossy()]).map(|_| ()))?;
+
+    let sources = ExportSources {
+        database: database.clone(),
+        documents_dir: profile_dir.join(DOCUMENTS_DIR),
+        profile_dir,
+        captures_dir: crate::commands::captures_dir(),
+        skip_capture_dirs: if crate::profiles::active_subdir().is_none() { vec!["profiles"] } else { Vec::new() },
+        app_version: app.package_info().version.to_string(),
+    };
+    let archive_path = path.clone();
+    let written = tauri::async_runtime::spawn_blocking(move || write_archive(&archive_path, &sources))
+        .await
+        .map_err(|e| format!("Failed to write the export: {}", e));
+    let _ = fs::remove_file(&database);
+    let file_count = written??;
+
+    let size_bytes = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+    tracing::info!("📤 Exported {} files to {}", file_count, path.display());
+    Ok(DataExport {
+        path: path.to_string_lossy().to_string(),
+        size_bytes,
+        file_count,
+        schema_version: history::schema_version(),
+    })
+}
+
+fn read_manifest(archive: &mut ZipArchive<fs::File>) -> Result<Manifest, String> {
+    let mut text = String::new();
+    archive
+        .by_name(MANIFEST)
+        .map_err(|_| "Not a data export: the manifest is missing".to_string())?
+        .read_to_string(&mut text)
+        .map_err(|e| format!("Failed to read the manifest: {}", e))?;
+    serde_json::from_str(&text).map_err(|e| format!("Invalid manifest: {}", e))
+}
+
+/// Extract the database to `target`, check its version against the manifest and this app, and
+/// point stored page images at this machine's documents directory
+fn stage_database(
+    archive: &mut ZipArchive<fs::File>,
+    manifest: &Manifest,
+    target: &Path,
+    documents_dir: &Path,
+) -> Result<(), String> {
+    {
+        let mut entry = archive
+            .by_name(DATABASE_ENTRY)
+            .map_err(|_| "The export has no database".to_string())?;
+        let mut output = fs::File::create(target).map_err(|e| format!("Failed to create {}: {}", target.display(), e))?;
+        io::copy(&mut entry, &mut output).map_err(|e| format!("Failed to extract the database: {}", e))?;
+    }
+    let db = Connection::open_with_flags(target, OpenFlags::SQLITE_OPEN_READ_WRITE)
+        .map_err(|e| format!("Failed to open the exported database: {}", e))?;
+    let version: usize = db
+        .pragma_query_value(None, "user_version", |row| row.get(0))
+        .map_err(|e| format!("Failed to read the exported database version: {}", e))?;
+    if version != manifest.schema_version {
+        return Err(format!(
+            "The export is inconsistent: its manifest says schema {} but the database is at {}",
+            manifest.schema_version, version
+        ));
+    }
+    let old_dir = manifest.documents_dir.as_str();
+    let new_dir = documents_dir.to_string_lossy();
+    if !old_dir.is_empty() && old_dir != new_dir {
+        db.execute(
+            "UPDATE history_text SET image_path = ?2 || substr(image_path, length(?1) + 1)
+             WHERE substr(image_path, 1, length(?1)) = ?1",
+            params![old_dir, new_dir],
+        )
+        .map_err(|e| format!("Failed to update document paths: {}", e))?;
+    }
+    Ok(())
+}
+
+fn remove_database(path: &Path) -> Result<(), String> {
+    for suffix in ["", "-wal", "-shm"] {
+        let file = PathBuf::from(format!("{}{}", path.display(), suffix));
+        if file.exists() {
+            fs::remove_file(&file).map_err(|e| format!("Failed to remove {}: {}", file.display(), e))?;
+        }
+    }
+    Ok(())
+}
+
+/// Unpack the settings, documents and captures entries
+fn extract_files(
+    archive: &mut ZipArchive<fs::File>,
+    profile_dir: &Path,
+    documents_dir: &Path,
+    captures_dir: &Path,
+) -> Result<usize, String> {
+    let mut extracted = 0;
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index).map_err(|e| format!("Failed to read the export: {}", e))?;
+        if entry.is_dir() {
+            continue;
+        }
+        // Skips names that would escape the target directory
+        let Some(name) = entry.enclosed_name() else { continue };
+        let (base, relative) = if let Ok(relative) = name.strip_prefix(SETTINGS_PREFIX) {
+            let file_name = relative.to_string_lossy();
+            if relative.components().count() != 1
+                || SHARED_FILES.contains(&file_name.as_ref())
+                || PROTECTED_FILES.contains(&file_name.as_ref())
+            {
+                continue;
+            }
+            (profile_dir, relative.to_path_buf())
+        } else if let Ok(relative) = name.strip_prefix(DOCUMENTS_PREFIX) {
+            (documents_dir, relative.to_path_buf())
+        } else if let Ok(relative) = name.strip_prefix(CAPTURES_PREFIX) {
+            (captures_dir, relative.to_path_buf())
+        } else {
+            continue;
+        };
+        let target = base.join(relative);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        let mut output = fs::File::create(&target).map_err(|e| format!("Failed to create {}: {}", target.display(), e))?;
+        io::copy(&mut entry, &mut output).map_err(|e| format!("Failed to extract {}: {}", name.display(), e))?;
+        extracted += 1;
+    }
+    Ok(extracted)
+}
+
+/// Replace the active profile's data with an export made by `export_all_data`, then restart.
+/// The role policy, sandbox, AI provider settings and consent log are kept as they are.
+/// Exports from a newer app version (with a database schema this one doesn't know) are refused;
+/// older ones are migrated when the app starts again.
+#[tauri::command]
+pub async fn import_all_data(app: AppHandle, history: State<'_, HistoryState>, path: String) -> Result<(), String> {
+    let file = fs::File::open(&path).map_err(|e| format!("Failed to open {}: {}", path, e))?;
+    let mut archive = ZipArchive::new(file).map_err(|e| format!("Not a data export: {}", e))?;
+    let manifest = read_manifest(&mut archive)?;
+    if manifest.format_version != FORMAT_VERSION {
+        return Err(format!(
+            "Unsupported export format {} (this version reads format {})",
+            manifest.format_version, FORMAT_VERSION
+        ));
+    }
+    if manifest.schema_version > history::schema_version() {
+        return Err(format!(
+            "The export comes from a newer version ({}) of the app; update before importing",
+            manifest.app_version
+        ));
+    }
+
+    let profile_dir = persistence::data_file(&app, "")?;
+    let database = persistence::data_file(&app, history::DB_FILE)?;
+    let documents_dir = profile_dir.join(DOCUMENTS_DIR);
+    let staged = database.with_extension("db.import");
+    let captures_dir = crate::commands::captures_dir();
+
+    let extracted = tauri::async_runtime::spawn_blocking({
+        let history = history.inner().clone();
+        move || -> Result<usize, String> {
+            if let Err(e) = stage_database(&mut archive, &manifest, &staged, &documents_dir) {
+                let _ = fs::remove_file(&staged);
+                return Err(e);
+            }
+            // Nothing has been touched until here; from now on the app restarts either way
+            history.close();
+            remove_database(&database)?;
+            fs::rename(&staged, &database).map_err(|e| format!("Failed to replace the database: {}", e))?;
+            if documents_dir.exists() {
+                fs::remove_dir_all(&documents_dir).map_err(|e| format!("Failed to remove old documents: {}", e))?;
+            }
+            extract_files(&mut archive, &profile_dir, &documents_dir, &captures_dir)
+        }
+    })
+    .await
+    .map_err(|e| format!("Failed to import: {}", e))?;
+
+    match extracted {
+        Ok(count) => {
+            tracing::info!("📥 Imported {} files from {}, restarting", count, path);
+            app.restart();
+        }
+        Err(e) => {
+            // Reopen whatever database is in place if the import got as far as closing it
+            if history.with_db(|_| Ok(())).is_err() {
+                history::init(&app);
+            }
+            Err(e)
+        }
+    }
+}
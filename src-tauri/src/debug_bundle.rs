@@ -0,0 +1,261 @@
+//! Support bundles: one zip with recent logs, crash reports, settings (secrets scrubbed), the
+//! command policy, a fresh diagnostics run and, only when the user agreed to it, the last
+//! capture. Users attach the file to an issue instead of collecting the pieces by hand.
+
+use base64::{engine::general_purpose, Engine as _};
+use image::imageops::FilterType;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+use crate::frame_delta::FrameDeltaState;
+use crate::persistence;
+
+const BUNDLE_DIR: &str = "debug_bundles";
+/// Settings files copied into the bundle, from the active profile or the shared directory; user
+/// data (activity, outbox, queue) stays out
+const SETTINGS_FILES: &[&str] = &[
+    "log_settings.json",
+    "update_settings.json",
+    "capture_settings.json",
+    "math_ocr_settings.json",
+    "integration_server.json",
+    "sync_settings.json",
+    "distraction_rules.json",
+    "project_roots.json",
+];
+/// Object keys whose values are replaced in settings, matched as substrings
+const SECRET_KEYS: &[&str] = &["token", "password", "secret", "api_key", "apikey"];
+/// Daily log files included, newest first
+const LOG_FILES: usize = 2;
+/// Larger log files are cut to their last this many bytes
+const MAX_LOG_BYTES: u64 = 5 * 1024 * 1024;
+const CRASH_REPORTS: usize = 3;
+/// Pixel size of the blocks a redacted screenshot is reduced to
+const REDACT_BLOCK: u32 = 12;
+
+/// What the user agreed to share of their screen
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ScreenshotConsent {
+    #[default]
+    None,
+    /// Pixelated so text can't be read, but layout and colors remain
+    Redacted,
+    Full,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DebugBundle {
+    pub path: String,
+    pub size_bytes: u64,
+    /// Files inside the zip
+    pub files: Vec<String>,
+    /// Screenshot actually included; `none` when nothing had been captured yet
+    pub screenshot: ScreenshotConsent,
+}
+
+#[derive(Serialize)]
+struct Manifest<'a> {
+    app_version: String,
+    created_at: i64,
+    platform: &'a str,
+    screenshot: ScreenshotConsent,
+    files: &'a [String],
+}
+
+/// Replace the values of secret-looking keys, at any depth
+fn scrub_secrets(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                let key = key.to_lowercase();
+                if SECRET_KEYS.iter().any(|secret| key.contains(secret)) {
+                    *value = serde_json::Value::String("[redacted]".to_string());
+                } else {
+                    scrub_secrets(value);
+                }
+            }
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(scrub_secrets),
+        _ => {}
+    }
+}
+
+/// The last `MAX_LOG_BYTES` of a file
+fn read_tail(path: &Path) -> Result<Vec<u8>, String> {
+    let mut file = fs::File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let length = file.metadata().map(|m| m.len()).unwrap_or(0);
+    if length > MAX_LOG_BYTES {
+        file.seek(SeekFrom::Start(length - MAX_LOG_BYTES))
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    }
+    let mut contents = Vec::new();
+    file.read_to_end(&mut contents)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    Ok(contents)
+}
+
+/// Files in a directory whose name starts with `prefix`, newest name first
+fn newest_files(dir: &Path, prefix: &str, count: usize) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .map(|entries| entries.flatten().map(|e| e.path()).collect())
+        .unwrap_or_default();
+    files.retain(|p| p.is_file() && p.file_name().is_some_and(|n| n.to_string_lossy().starts_with(prefix)));
+    files.sort();
+    files.into_iter().rev().take(count).collect()
+}
+
+/// PNG of the capture, pixelated when redacted
+fn screenshot_png(image_base64: &str, consent: ScreenshotConsent) -> Result<Vec<u8>, String> {
+    let bytes = general_purpose::STANDARD
+        .decode(image_base64)
+        .map_err(|e| format!("Failed to decode capture: {}", e))?;
+    if consent != ScreenshotConsent::Redacted {
+        return Ok(bytes);
+    }
+    let image = image::load_from_memory(&bytes).map_err(|e| format!("Failed to load capture: {}", e))?;
+    let (width, height) = (image.width(), image.height());
+    let small = image.resize_exact((width / REDACT_BLOCK).max(1), (height / REDACT_BLOCK).max(1), FilterType::Triangle);
+    let pixelated = small.resize_exact(width, height, FilterType::Nearest);
+    let mut png = Vec::new();
+    pixelated
+        .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+        .map_err(|e| format!("Failed to encode capture: {}", e))?;
+    Ok(png)
+}
+
+struct BundleWriter {
+    zip: ZipWriter<fs::File>,
+    files: Vec<String>,
+}
+
+impl BundleWriter {
+    fn add(&mut self, name: &str, contents: &[u8]) -> Result<(), String> {
+        self.zip
+            .start_file(name, SimpleFileOptions::default())
+            .and_then(|_| self.zip.write_all(contents).map_err(Into::into))
+            .map_err(|e| format!("Failed to add {} to the bundle: {}", name, e))?;
+        self.files.push(name.to_string());
+        Ok(())
+    }
+
+    fn add_json<T: Serialize>(&mut self, name: &str, value: &T) -> Result<(), String> {
+        let json = serde_json::to_vec_pretty(value).map_err(|e| format!("Failed to serialize {}: {}", name, e))?;
+        self.add(name, &json)
+    }
+}
+
+/// Everything gathered on the async side, written out on a blocking thread
+struct BundleContents {
+    app_version: String,
+    diagnostics: Result<crate::diagnostics::DiagnosticsReport, String>,
+    screenshot: Option<(String, ScreenshotConsent)>,
+    /// Shared data directory, holding logs and crash reports
+    shared_dir: PathBuf,
+    /// The active profile's data directory, holding its settings
+    profile_dir: PathBuf,
+}
+
+fn write_bundle(path: &Path, contents: BundleContents) -> Result<(Vec<String>, ScreenshotConsent), String> {
+    let file = fs::File::create(path).map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+    let mut bundle = BundleWriter { zip: ZipWriter::new(file), files: Vec::new() };
+
+    let log_dir = contents.shared_dir.join(crate::logging::LOG_DIR);
+    for log in newest_files(&log_dir, crate::logging::LOG_FILE_PREFIX, LOG_FILES) {
+        let name = log.file_name().unwrap_or_default().to_string_lossy().to_string();
+        bundle.add(&format!("logs/{}", name), &read_tail(&log)?)?;
+    }
+
+    let crash_dir = contents.shared_dir.join(crate::crash_report::CRASH_DIR);
+    for report in newest_files(&crash_dir, "", CRASH_REPORTS) {
+        let name = report.file_name().unwrap_or_default().to_string_lossy().to_string();
+        let data = fs::read(&report).map_err(|e| format!("Failed to read {}: {}", report.display(), e))?;
+        bundle.add(&format!("crash_reports/{}", name), &data)?;
+    }
+
+    for name in SETTINGS_FILES {
+        let text = fs::read_to_string(contents.profile_dir.join(name))
+            .or_else(|_| fs::read_to_string(contents.shared_dir.join(name)));
+        let Ok(text) = text else { continue };
+        let mut value: serde_json::Value = serde_json::from_str(&text).unwrap_or(serde_json::Value::String(text));
+        scrub_secrets(&mut value);
+        bundle.add_json(&format!("settings/{}", name), &value)?;
+    }
+
+    bundle.add_json(
+        "policy.json",
+        &serde_json::json!({ "allowed_commands": crate::commands::ALLOWED_COMMANDS }),
+    )?;
+    match &contents.diagnostics {
+        Ok(report) => bundle.add_json("diagnostics.json", report)?,
+        Err(e) => bundle.add("diagnostics_error.txt", e.as_bytes())?,
+    }
+
+    let mut included = ScreenshotConsent::None;
+    if let Some((image_base64, consent)) = &contents.screenshot {
+        bundle.add("last_capture.png", &screenshot_png(image_base64, *consent)?)?;
+        included = *consent;
+    }
+
+    let files = bundle.files.clone();
+    bundle.add_json(
+        "manifest.json",
+        &Manifest {
+            app_version: contents.app_version,
+            created_at: chrono::Utc::now().timestamp(),
+            platform: std::env::consts::OS,
+            screenshot: included,
+            files: &files,
+        },
+    )?;
+    bundle.zip.finish().map_err(|e| format!("Failed to finish the bundle: {}", e))?;
+    Ok((bundle.files, included))
+}
+
+/// Zip logs, crash reports, settings, policy and diagnostics into a file for bug reports. The
+/// last capture is included only with the user's consent, pixelated when `redacted`.
+#[tauri::command]
+pub async fn create_debug_bundle(
+    app: AppHandle,
+    screenshot: Option<ScreenshotConsent>,
+) -> Result<DebugBundle, String> {
+    let consent = screenshot.unwrap_or_default();
+    let screenshot = match consent {
+        ScreenshotConsent::None => None,
+        consent => app
+            .state::<FrameDeltaState>()
+            .latest()
+            .filter(|frame| !frame.image_base64.is_empty())
+            .map(|frame| (frame.image_base64, consent)),
+    };
+    let contents = BundleContents {
+        app_version: app.package_info().version.to_string(),
+        diagnostics: crate::diagnostics::run_diagnostics(app.clone()).await,
+        screenshot,
+        shared_dir: persistence::shared_data_file(&app, "")?,
+        profile_dir: persistence::data_file(&app, "")?,
+    };
+
+    let dir = persistence::shared_data_file(&app, BUNDLE_DIR)?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create bundle directory: {}", e))?;
+    let path = dir.join(format!("ai-teacher-debug-{}.zip", chrono::Local::now().format("%Y%m%d-%H%M%S")));
+
+    let bundle_path = path.clone();
+    let (files, included) = tauri::async_runtime::spawn_blocking(move || write_bundle(&bundle_path, contents))
+        .await
+        .map_err(|e| format!("Failed to write the bundle: {}", e))??;
+    let size_bytes = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+    tracing::info!("📦 Debug bundle written to {} ({} files)", path.display(), files.len());
+    Ok(DebugBundle {
+        path: path.to_string_lossy().to_string(),
+        size_bytes,
+        files,
+        screenshot: included,
+    })
+}
@@ -0,0 +1,226 @@
+//! `aiteacher://` links, so a browser page or another app can hand work to the tutor:
+//! `aiteacher://capture?window=Code` captures a window and `aiteacher://lesson/42` opens a
+//! lesson. A link is untrusted input, so only these routes exist, every parameter is checked,
+//! and the action goes through the same role, rate limit and consent checks as the command
+//! it stands for. Each link brings the main window forward and is answered with a
+//! `deep-link-opened` event. Links arriving while the app runs are forwarded to this instance.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_deep_link::DeepLinkExt;
+use url::Url;
+
+use crate::commands::{CaptureWindowParams, WindowCaptureResult};
+use crate::consent::{ConsentScope, ConsentState};
+use crate::curriculum::LessonStatus;
+use crate::history::HistoryState;
+use crate::rate_limit::RateLimiterState;
+use crate::roles::RolesState;
+
+const SCHEME: &str = "aiteacher";
+const MAX_URL_CHARS: usize = 2048;
+const MAX_VALUE_CHARS: usize = 256;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DeepLinkAction {
+    /// `aiteacher://capture?window=<title>` or `?process=<name>`, with `&ocr=true` to read its text
+    Capture { window_title: Option<String>, process_name: Option<String>, ocr: bool },
+    /// `aiteacher://lesson/<id>`, with `?curriculum=<id>` when several curricula have that lesson
+    Lesson { lesson_id: String, curriculum_id: Option<String> },
+}
+
+/// Payload of the `deep-link-opened` event
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DeepLinkOpened {
+    pub url: String,
+    /// None when the link wasn't valid
+    pub action: Option<DeepLinkAction>,
+    pub capture: Option<WindowCaptureResult>,
+    pub lesson: Option<LessonStatus>,
+    /// Curriculum of `lesson`
+    pub curriculum_id: Option<String>,
+    pub error: Option<String>,
+    pub timestamp: i64,
+}
+
+/// A query value: present once, short, and free of control characters
+fn check_value(key: &str, value: &str) -> Result<String, String> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Err(format!("'{}' is empty", key));
+    }
+    if value.chars().count() > MAX_VALUE_CHARS {
+        return Err(format!("'{}' is longer than {} characters", key, MAX_VALUE_CHARS));
+    }
+    if value.chars().any(char::is_control) {
+        return Err(format!("'{}' contains control characters", key));
+    }
+    Ok(value.to_string())
+}
+
+/// The query parameters, refusing any not in `allowed` and any given twice
+fn query(url: &Url, allowed: &[&str]) -> Result<Vec<(String, String)>, String> {
+    let mut pairs: Vec<(String, String)> = Vec::new();
+    for (key, value) in url.query_pairs() {
+        if !allowed.contains(&key.as_ref()) {
+            return Err(format!("Unknown parameter '{}'; expected {}", key, allowed.join(", ")));
+        }
+        if pairs.iter().any(|(seen, _)| *seen == key) {
+            return Err(format!("'{}' is given twice", key));
+        }
+        pairs.push((key.to_string(), check_value(&key, &value)?));
+    }
+    Ok(pairs)
+}
+
+fn param(pairs: &[(String, String)], key: &str) -> Option<String> {
+    pairs.iter().find(|(name, _)| name == key).map(|(_, value)| value.clone())
+}
+
+/// Validate a link and turn it into the action it asks for
+fn parse(link: &str) -> Result<DeepLinkAction, String> {
+    if link.len() > MAX_URL_CHARS {
+        return Err(format!("The link is longer than {} characters", MAX_URL_CHARS));
+    }
+    let url = Url::parse(link).map_err(|e| format!("Invalid link: {}", e))?;
+    if url.scheme() != SCHEME {
+        return Err(format!("Not an {}:// link", SCHEME));
+    }
+    let segments: Vec<String> = url
+        .path_segments()
+        .map(|segments| segments.filter(|s| !s.is_empty()).map(|s| s.to_string()).collect())
+        .unwrap_or_default();
+
+    match url.host_str().unwrap_or_default() {
+        "capture" => {
+            if !segments.is_empty() {
+                return Err("Capture links take no path, e.g. aiteacher://capture?window=Code".to_string());
+            }
+            let pairs = query(&url, &["window", "process", "ocr"])?;
+            let ocr = match param(&pairs, "ocr").as_deref() {
+                None | Some("false") | Some("0") => false,
+                Some("true") | Some("1") => true,
+                Some(other) => return Err(format!("'ocr' must be true or false, not '{}'", other)),
+            };
+            let (window_title, process_name) = (param(&pairs, "window"), param(&pairs, "process"));
+            if window_title.is_none() && process_name.is_none() {
+                return Err("Name the window to capture with 'window' or 'process'".to_string());
+            }
+            Ok(DeepLinkAction::Capture { window_title, process_name, ocr })
+        }
+        "lesson" => {
+            let [id] = segments.as_slice() else {
+                return Err("Lesson links name one lesson, e.g. aiteacher://lesson/42".to_string());
+            };
+            let id = percent_encoding::percent_decode_str(id).decode_utf8().map_err(|_| "Invalid lesson id".to_string())?;
+            let lesson_id = check_value("lesson", &id)?;
+            let curriculum_id = param(&query(&url, &["curriculum"])?, "curriculum");
+            Ok(DeepLinkAction::Lesson { lesson_id, curriculum_id })
+        }
+        other => Err(format!("Unknown link '{}://{}'; expected capture or lesson", SCHEME, other)),
+    }
+}
+
+/// Refuse what the equivalent command call would be refused
+fn authorize(app: &AppHandle, command: &str) -> Result<(), String> {
+    app.state::<RolesState>().check_invoke(command)?;
+    app.state::<RateLimiterState>().check(command).map_err(|limited| limited.to_string())
+}
+
+async fn perform(app: &AppHandle, action: &DeepLinkAction, opened: &mut DeepLinkOpened) -> Result<(), String> {
+    match action {
+        DeepLinkAction::Capture { window_title, process_name, ocr } => {
+            let command = if *ocr { "capture_window_with_ocr" } else { "capture_window" };
+            authorize(app, command)?;
+            // Captures from a link need the student's standing consent, as monitoring does
+            let scope = if *ocr { ConsentScope::ScreenAndText } else { ConsentScope::Screen };
+            app.state::<ConsentState>().require(scope)?;
+            let options = CaptureWindowParams {
+                window_title: window_title.clone(),
+                process_name: process_name.clone(),
+                ..Default::default()
+            };
+            opened.capture = Some(if *ocr {
+                crate::commands::capture_window_with_ocr(app.clone(), options).await?
+            } else {
+                crate::commands::capture_window(app.clone(), options).await?
+            });
+        }
+        DeepLinkAction::Lesson { lesson_id, curriculum_id } => {
+            authorize(app, "get_curriculum")?;
+            let (curriculum_id, lesson) =
+                crate::curriculum::find_lesson(&app.state::<HistoryState>(), lesson_id, curriculum_id.as_deref())?;
+            opened.curriculum_id = Some(curriculum_id);
+            opened.lesson = Some(lesson);
+        }
+    }
+    Ok(())
+}
+
+/// Act on one link and report it with `deep-link-opened`
+async fn open(app: &AppHandle, link: String) {
+    if let Some(window) = app.get_webview_window("main") {
+        window.unminimize().unwrap_or_default();
+        window.set_focus().unwrap_or_default();
+    }
+    let mut opened = DeepLinkOpened {
+        url: link.clone(),
+        action: None,
+        capture: None,
+        lesson: None,
+        curriculum_id: None,
+        error: None,
+        timestamp: chrono::Utc::now().timestamp_millis(),
+    };
+    let result = match parse(&link) {
+        Ok(action) => {
+            let result = perform(app, &action, &mut opened).await;
+            opened.action = Some(action);
+            result
+        }
+        Err(e) => Err(e),
+    };
+    match result {
+        Ok(()) => tracing::info!("🔗 Opened {}", link),
+        Err(e) => {
+            tracing::warn!("⚠️ Refused link {}: {}", link, e);
+            opened.error = Some(e);
+        }
+    }
+    let _ = crate::event_bus::emit(app, crate::events::DEEP_LINK_OPENED, opened);
+}
+
+fn open_all(app: &AppHandle, urls: Vec<Url>) {
+    for url in urls {
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move { open(&app, url.to_string()).await });
+    }
+}
+
+/// Register the scheme with the OS and handle links, including the one that started the app
+pub fn init(app: &AppHandle) {
+    // Installers register the scheme on macOS; elsewhere the running executable does it
+    #[cfg(any(target_os = "windows", target_os = "linux"))]
+    if let Err(e) = app.deep_link().register_all() {
+        tracing::warn!("⚠️ Failed to register {}:// links: {}", SCHEME, e);
+    }
+
+    let handle = app.clone();
+    app.deep_link().on_open_url(move |event| open_all(&handle, event.urls()));
+    match app.deep_link().get_current() {
+        Ok(Some(urls)) => open_all(app, urls),
+        Ok(None) => {}
+        Err(e) => tracing::warn!("⚠️ Failed to read the link the app was opened with: {}", e),
+    }
+}
+
+/// A second launch, e.g. from a link, focuses this instance instead of starting another. The
+/// link itself is forwarded to `on_open_url`.
+pub fn on_second_instance(app: &AppHandle, _args: Vec<String>, _cwd: String) {
+    if let Some(window) = app.get_webview_window("main") {
+        window.unminimize().unwrap_or_default();
+        window.set_focus().unwrap_or_default();
+    }
+}
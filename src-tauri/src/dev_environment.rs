@@ -0,0 +1,129 @@
+//! Installed developer toolchains in one call: where each tool resolves on PATH and its
+//! version. Replaces asking for `node --version` and friends one at a time through
+//! `execute_command`.
+
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+const VERSION_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Tool name, executables to look for (first found wins) and the version flag
+const TOOLS: &[(&str, &[&str], &str)] = &[
+    ("node", &["node"], "--version"),
+    ("npm", &["npm"], "--version"),
+    ("python", &["python3", "python", "py"], "--version"),
+    ("rustc", &["rustc"], "--version"),
+    ("cargo", &["cargo"], "--version"),
+    ("java", &["java"], "-version"),
+    ("docker", &["docker"], "--version"),
+    ("git", &["git"], "--version"),
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ToolInfo {
+    pub name: String,
+    pub found: bool,
+    /// The executable PATH resolves to
+    pub path: Option<String>,
+    /// e.g. "20.11.1"; None when found but the version couldn't be read
+    pub version: Option<String>,
+    /// First line of the version output
+    pub version_output: Option<String>,
+    /// Other copies further down PATH, shadowed by `path`
+    pub shadowed: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DevEnvironment {
+    pub os: String,
+    pub arch: String,
+    pub tools: Vec<ToolInfo>,
+}
+
+/// File names an executable may have: with PATHEXT extensions on Windows
+fn candidate_names(program: &str) -> Vec<String> {
+    if !cfg!(target_os = "windows") {
+        return vec![program.to_string()];
+    }
+    let extensions = std::env::var("PATHEXT").unwrap_or_else(|_| ".COM;.EXE;.BAT;.CMD".to_string());
+    extensions.split(';').filter(|e| !e.is_empty()).map(|e| format!("{}{}", program, e.to_lowercase())).collect()
+}
+
+/// Every match for `program` on PATH, in PATH order
+pub(crate) fn find_on_path(program: &str) -> Vec<PathBuf> {
+    let Some(path) = std::env::var_os("PATH") else {
+        return Vec::new();
+    };
+    let names = candidate_names(program);
+    let mut found: Vec<PathBuf> = Vec::new();
+    for dir in std::env::split_paths(&path) {
+        for name in &names {
+            let candidate = dir.join(name);
+            if candidate.is_file() && !found.contains(&candidate) {
+                found.push(candidate);
+                break;
+            }
+        }
+    }
+    found
+}
+
+/// Windows' "python" placeholders in WindowsApps open the Store instead of running Python
+fn is_store_alias(path: &Path) -> bool {
+    cfg!(target_os = "windows") && path.to_string_lossy().to_lowercase().contains("\\microsoft\\windowsapps\\")
+}
+
+fn version_regex() -> &'static Regex {
+    static VERSION: OnceLock<Regex> = OnceLock::new();
+    VERSION.get_or_init(|| Regex::new(r"\d+\.\d+(?:\.\d+)?(?:[-_.+][0-9A-Za-z.]+)?").unwrap())
+}
+
+/// Run the version flag and return the first output line (java prints to stderr)
+async fn version_output(path: &Path, flag: &str) -> Option<String> {
+    let mut command = tokio::process::Command::new(path);
+    command.arg(flag).kill_on_drop(true);
+    let output = tokio::time::timeout(VERSION_TIMEOUT, command.output()).await.ok()?.ok()?;
+    let text = if output.stdout.iter().any(|b| !b.is_ascii_whitespace()) { output.stdout } else { output.stderr };
+    String::from_utf8_lossy(&text).lines().map(str::trim).find(|line| !line.is_empty()).map(str::to_string)
+}
+
+async fn probe(name: &str, programs: &[&str], flag: &str) -> ToolInfo {
+    let matches: Vec<PathBuf> = programs
+        .iter()
+        .map(|program| find_on_path(program).into_iter().filter(|path| !is_store_alias(path)).collect::<Vec<_>>())
+        .find(|matches| !matches.is_empty())
+        .unwrap_or_default();
+    let Some(path) = matches.first().cloned() else {
+        return ToolInfo {
+            name: name.to_string(),
+            found: false,
+            path: None,
+            version: None,
+            version_output: None,
+            shadowed: Vec::new(),
+        };
+    };
+    let output = version_output(&path, flag).await;
+    let version = output.as_deref().and_then(|o| version_regex().find(o)).map(|m| m.as_str().to_string());
+    ToolInfo {
+        name: name.to_string(),
+        found: true,
+        path: Some(path.to_string_lossy().into_owned()),
+        version,
+        version_output: output,
+        shadowed: matches.iter().skip(1).map(|p| p.to_string_lossy().into_owned()).collect(),
+    }
+}
+
+/// Which of node, npm, python, rustc, cargo, java, docker and git are installed, with their
+/// versions and PATH locations
+#[tauri::command]
+pub async fn detect_dev_environment() -> Result<DevEnvironment, String> {
+    let probes = TOOLS.iter().map(|(name, programs, flag)| probe(name, programs, flag));
+    let tools = futures_util::future::join_all(probes).await;
+    Ok(DevEnvironment { os: std::env::consts::OS.to_string(), arch: std::env::consts::ARCH.to_string(), tools })
+}
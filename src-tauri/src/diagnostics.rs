@@ -0,0 +1,207 @@
+//! Self-test behind the troubleshooting screen: each check reports pass/warn/fail with a
+//! human-readable message instead of the user discovering problems through empty results.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::Instant;
+use tauri::{AppHandle, Manager};
+
+use crate::connectivity::ConnectivityState;
+use crate::screen_capture::ScreenCapture;
+
+/// Free space below this is a warning, below `DISK_FAIL_BYTES` a failure
+const DISK_WARN_BYTES: u64 = 1024 * 1024 * 1024;
+const DISK_FAIL_BYTES: u64 = 100 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticStatus {
+    Pass,
+    Skipped,
+    Warn,
+    Fail,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DiagnosticCheck {
+    /// Stable identifier, e.g. "screen_capture"
+    pub id: String,
+    pub label: String,
+    pub status: DiagnosticStatus,
+    pub message: String,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DiagnosticsReport {
+    pub generated_at: i64,
+    pub platform: String,
+    /// The worst status among the checks that ran
+    pub overall: DiagnosticStatus,
+    pub checks: Vec<DiagnosticCheck>,
+}
+
+async fn timed<F>(id: &str, label: &str, check: F) -> DiagnosticCheck
+where
+    F: std::future::Future<Output = (DiagnosticStatus, String)>,
+{
+    let started = Instant::now();
+    let (status, message) = check.await;
+    DiagnosticCheck {
+        id: id.to_string(),
+        label: label.to_string(),
+        status,
+        message,
+        duration_ms: started.elapsed().as_millis() as u64,
+    }
+}
+
+async fn check_screen_capture(app: &AppHandle) -> (DiagnosticStatus, String) {
+    match ScreenCapture::new().capture_full_screen(app).await {
+        Ok(result) if !result.image_base64.is_empty() => (DiagnosticStatus::Pass, "Captured the screen".to_string()),
+        Ok(_) => (DiagnosticStatus::Fail, "Screen capture returned an empty image".to_string()),
+        Err(e) => (DiagnosticStatus::Fail, e),
+    }
+}
+
+/// Run a short PowerShell snippet, returning trimmed stdout
+#[cfg(target_os = "windows")]
+fn run_powershell(script: &str) -> Result<String, String> {
+    let output = std::process::Command::new("powershell")
+        .args(["-NoProfile", "-ExecutionPolicy", "Bypass", "-Command", script])
+        .output()
+        .map_err(|e| format!("Failed to start PowerShell: {}", e))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(target_os = "windows")]
+async fn check_winrt() -> (DiagnosticStatus, String) {
+    let script = r#"
+        [Windows.Media.Ocr.OcrEngine, Windows.Media, ContentType=WindowsRuntime] | Out-Null
+        $PSVersionTable.PSVersion.ToString()
+    "#;
+    match run_powershell(script) {
+        Ok(version) => (DiagnosticStatus::Pass, format!("PowerShell {} can load Windows Runtime types", version)),
+        Err(e) => (DiagnosticStatus::Fail, format!("PowerShell can't load Windows Runtime types: {}", e)),
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+async fn check_winrt() -> (DiagnosticStatus, String) {
+    (DiagnosticStatus::Skipped, "Only used on Windows".to_string())
+}
+
+#[cfg(target_os = "windows")]
+async fn check_ocr_languages() -> (DiagnosticStatus, String) {
+    let script = r#"
+        [Windows.Media.Ocr.OcrEngine, Windows.Media, ContentType=WindowsRuntime] | Out-Null
+        ([Windows.Media.Ocr.OcrEngine]::AvailableRecognizerLanguages | ForEach-Object { $_.LanguageTag }) -join ','
+    "#;
+    match run_powershell(script) {
+        Ok(languages) if languages.is_empty() => (
+            DiagnosticStatus::Fail,
+            "No OCR languages installed. Add a language with OCR support in Windows Settings.".to_string(),
+        ),
+        Ok(languages) => (DiagnosticStatus::Pass, format!("OCR languages: {}", languages.replace(',', ", "))),
+        Err(e) => (DiagnosticStatus::Fail, format!("Failed to list OCR languages: {}", e)),
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+async fn check_ocr_languages() -> (DiagnosticStatus, String) {
+    (DiagnosticStatus::Skipped, "OCR is only available on Windows".to_string())
+}
+
+#[cfg(target_os = "windows")]
+pub(crate) fn free_bytes(path: &Path) -> Result<u64, String> {
+    let root = path
+        .ancestors()
+        .last()
+        .ok_or("Captures directory has no root")?
+        .to_string_lossy()
+        .replace('\'', "''");
+    run_powershell(&format!("[System.IO.DriveInfo]::new('{}').AvailableFreeSpace", root))?
+        .parse()
+        .map_err(|e| format!("Failed to read free space: {}", e))
+}
+
+#[cfg(not(target_os = "windows"))]
+pub(crate) fn free_bytes(path: &Path) -> Result<u64, String> {
+    let output = std::process::Command::new("df")
+        .arg("-Pk")
+        .arg(path)
+        .output()
+        .map_err(|e| format!("Failed to run df: {}", e))?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .nth(1)
+        .and_then(|line| line.split_whitespace().nth(3))
+        .and_then(|kb| kb.parse::<u64>().ok())
+        .map(|kb| kb * 1024)
+        .ok_or_else(|| "Failed to read free space from df".to_string())
+}
+
+async fn check_disk_space() -> (DiagnosticStatus, String) {
+    let dir = crate::commands::captures_dir();
+    // The captures folder may not exist yet; measure the closest existing parent
+    let Some(existing) = dir.ancestors().find(|p| p.exists()) else {
+        return (DiagnosticStatus::Fail, format!("No existing parent for {}", dir.display()));
+    };
+    match free_bytes(existing) {
+        Ok(bytes) => {
+            let message = format!("{:.1} GB free for {}", bytes as f64 / 1e9, dir.display());
+            let status = if bytes < DISK_FAIL_BYTES {
+                DiagnosticStatus::Fail
+            } else if bytes < DISK_WARN_BYTES {
+                DiagnosticStatus::Warn
+            } else {
+                DiagnosticStatus::Pass
+            };
+            (status, message)
+        }
+        Err(e) => (DiagnosticStatus::Warn, e),
+    }
+}
+
+async fn check_ai_endpoint(app: &AppHandle) -> (DiagnosticStatus, String) {
+    let endpoint = app.state::<ConnectivityState>().endpoint.lock().unwrap().clone();
+    let status = crate::connectivity::probe(&endpoint).await;
+    match (status.online, status.latency_ms) {
+        (true, Some(latency)) => (DiagnosticStatus::Pass, format!("Reached {} in {} ms", endpoint, latency)),
+        (true, None) => (DiagnosticStatus::Pass, format!("Reached {}", endpoint)),
+        (false, _) => (
+            DiagnosticStatus::Fail,
+            status.error.unwrap_or_else(|| format!("Could not reach {}", endpoint)),
+        ),
+    }
+}
+
+/// Check everything the tutor depends on and report what's broken
+#[tauri::command]
+pub async fn run_diagnostics(app: AppHandle) -> Result<DiagnosticsReport, String> {
+    let checks = vec![
+        timed("screen_capture", "Screen capture", check_screen_capture(&app)).await,
+        timed("powershell_winrt", "PowerShell / Windows Runtime", check_winrt()).await,
+        timed("ocr_languages", "OCR languages", check_ocr_languages()).await,
+        timed("disk_space", "Disk space for captures", check_disk_space()).await,
+        timed("ai_endpoint", "AI endpoint", check_ai_endpoint(&app)).await,
+    ];
+    let overall = checks
+        .iter()
+        .map(|c| c.status)
+        .filter(|s| *s != DiagnosticStatus::Skipped)
+        .max()
+        .unwrap_or(DiagnosticStatus::Pass);
+    tracing::info!("🩺 Diagnostics finished: {:?}", overall);
+
+    Ok(DiagnosticsReport {
+        generated_at: chrono::Utc::now().timestamp(),
+        platform: std::env::consts::OS.to_string(),
+        overall,
+        checks,
+    })
+}
@@ -0,0 +1,246 @@
+//! Compiler and runtime errors pulled out of OCR or terminal text. Recognizes rustc, tsc,
+//! Python tracebacks and gcc/clang output and returns each error as file, line, code and
+//! message, so the tutor works from clean errors instead of a screenful of noisy text. Errors
+//! pointing into an approved project folder come with the surrounding source lines.
+
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use tauri::State;
+
+use crate::project_files::ProjectRootsState;
+
+const MAX_TEXT_BYTES: usize = 512 * 1024;
+/// Lines after a rustc header searched for its `--> file:line:col`
+const RUSTC_LOCATION_LOOKAHEAD: usize = 4;
+/// Source lines attached on each side of an error's line
+const CONTEXT_LINES: u32 = 10;
+/// Source files bigger than this get no context
+const MAX_SOURCE_BYTES: u64 = 1024 * 1024;
+
+/// Source lines around an error
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct SourceContext {
+    /// The file inside the approved project folder
+    pub path: String,
+    /// 1-based line number of `lines[0]`
+    pub first_line: u32,
+    pub lines: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ParsedDiagnostic {
+    /// "rustc", "tsc", "python" or "gcc"
+    pub tool: String,
+    /// "error" or "warning"
+    pub severity: String,
+    pub file: Option<String>,
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+    /// e.g. "E0308", "TS2322" or "NameError"
+    pub code: Option<String>,
+    pub message: String,
+    /// Lines around `line` when the file is inside an approved project folder
+    pub source: Option<SourceContext>,
+}
+
+struct Patterns {
+    rustc_header: Regex,
+    rustc_location: Regex,
+    tsc: Regex,
+    tsc_pretty: Regex,
+    python_frame: Regex,
+    python_exception: Regex,
+    gcc: Regex,
+}
+
+fn patterns() -> &'static Patterns {
+    static PATTERNS: OnceLock<Patterns> = OnceLock::new();
+    PATTERNS.get_or_init(|| Patterns {
+        rustc_header: Regex::new(r"^(error|warning)(?:\[([A-Z0-9OoIl]{5})\])?: (.+)$").unwrap(),
+        rustc_location: Regex::new(r"^-+>\s*(.+?):(\d+):(\d+)$").unwrap(),
+        tsc: Regex::new(r"^(.+?)\((\d+),(\d+)\): (error|warning) (TS\d+): (.+)$").unwrap(),
+        tsc_pretty: Regex::new(r"^(.+?):(\d+):(\d+) - (error|warning) (TS\d+): (.+)$").unwrap(),
+        python_frame: Regex::new(r#"^File "(.+?)", line (\d+)"#).unwrap(),
+        python_exception: Regex::new(r"^([A-Za-z_][\w.]*(?:Error|Exception|Exit|Interrupt|Warning)|StopIteration)(?::\s*(.*))?$")
+            .unwrap(),
+        gcc: Regex::new(r"^(.+?):(\d+):(?:(\d+):)? (?:fatal )?(error|warning): (.+?)(?: \[(-W[\w=-]+)\])?$").unwrap(),
+    })
+}
+
+/// Collapse the whitespace OCR scatters around
+fn normalize_line(line: &str) -> String {
+    line.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Fix `O` and `l` that OCR reads in place of digits in an error code such as `E0308`
+fn normalize_code(code: &str) -> String {
+    let mut chars = code.chars();
+    let prefix: String = chars.by_ref().take(1).collect();
+    let digits: String = chars
+        .map(|c| match c {
+            'O' | 'o' => '0',
+            'I' | 'l' => '1',
+            c => c,
+        })
+        .collect();
+    format!("{}{}", prefix, digits)
+}
+
+fn number(text: Option<regex::Match<'_>>) -> Option<u32> {
+    text.and_then(|m| m.as_str().parse().ok())
+}
+
+/// Parse every recognized error and warning in `text`, in order of appearance
+pub(crate) fn parse(text: &str) -> Vec<ParsedDiagnostic> {
+    let p = patterns();
+    let lines: Vec<String> = text.lines().map(normalize_line).collect();
+    let mut diagnostics: Vec<ParsedDiagnostic> = Vec::new();
+    // Innermost frame of the traceback being read
+    let mut python_frame: Option<(String, u32)> = None;
+    let mut in_traceback = false;
+
+    for (index, line) in lines.iter().enumerate() {
+        if line.starts_with("Traceback (most recent call last)") {
+            in_traceback = true;
+            python_frame = None;
+            continue;
+        }
+        if in_traceback {
+            if let Some(c) = p.python_frame.captures(line) {
+                python_frame = Some((c[1].to_string(), c[2].parse().unwrap_or_default()));
+                continue;
+            }
+            if let Some(c) = p.python_exception.captures(line) {
+                let (file, line) = python_frame.take().unzip();
+                diagnostics.push(ParsedDiagnostic {
+                    tool: "python".to_string(),
+                    severity: "error".to_string(),
+                    file,
+                    line,
+                    column: None,
+                    code: Some(c[1].to_string()),
+                    message: c.get(2).map(|m| m.as_str().to_string()).unwrap_or_default(),
+                    source: None,
+                });
+                in_traceback = false;
+            }
+            // Source lines and carets between frames
+            continue;
+        }
+
+        if let Some(c) = p.tsc.captures(line).or_else(|| p.tsc_pretty.captures(line)) {
+            diagnostics.push(ParsedDiagnostic {
+                tool: "tsc".to_string(),
+                severity: c[4].to_string(),
+                file: Some(c[1].to_string()),
+                line: number(c.get(2)),
+                column: number(c.get(3)),
+                code: Some(c[5].to_string()),
+                message: c[6].to_string(),
+                source: None,
+            });
+        } else if let Some(c) = p.rustc_header.captures(line) {
+            // Summary lines such as "error: could not compile `app`" have no location
+            let location = lines
+                .iter()
+                .skip(index + 1)
+                .take(RUSTC_LOCATION_LOOKAHEAD)
+                .find_map(|l| p.rustc_location.captures(l));
+            if location.is_none() && c.get(2).is_none() {
+                continue;
+            }
+            diagnostics.push(ParsedDiagnostic {
+                tool: "rustc".to_string(),
+                severity: c[1].to_string(),
+                file: location.as_ref().map(|l| l[1].to_string()),
+                line: location.as_ref().and_then(|l| number(l.get(2))),
+                column: location.as_ref().and_then(|l| number(l.get(3))),
+                code: c.get(2).map(|m| normalize_code(m.as_str())),
+                message: c[3].to_string(),
+                source: None,
+            });
+        } else if let Some(c) = p.gcc.captures(line) {
+            diagnostics.push(ParsedDiagnostic {
+                tool: "gcc".to_string(),
+                severity: c[4].to_string(),
+                file: Some(c[1].to_string()),
+                line: number(c.get(2)),
+                column: number(c.get(3)),
+                code: c.get(6).map(|m| m.as_str().to_string()),
+                message: c[5].to_string(),
+                source: None,
+            });
+        }
+    }
+
+    // OCR of a scrolling terminal often repeats the same error
+    let mut unique: Vec<ParsedDiagnostic> = Vec::with_capacity(diagnostics.len());
+    for diagnostic in diagnostics {
+        if !unique.contains(&diagnostic) {
+            unique.push(diagnostic);
+        }
+    }
+    unique
+}
+
+/// The file an error names, if it lies inside an approved folder. Relative paths (as compilers
+/// print them) are tried against each approved folder.
+fn resolve_source(roots: &ProjectRootsState, file: &str) -> Option<PathBuf> {
+    let candidates: Vec<PathBuf> = if std::path::Path::new(file).is_absolute() {
+        vec![PathBuf::from(file)]
+    } else {
+        roots.roots.lock().unwrap().iter().map(|root| root.join(file)).collect()
+    };
+    candidates
+        .iter()
+        .filter_map(|candidate| roots.resolve(&candidate.to_string_lossy()).ok())
+        .find(|path| std::fs::metadata(path).is_ok_and(|m| m.is_file() && m.len() <= MAX_SOURCE_BYTES))
+}
+
+fn source_context(roots: &ProjectRootsState, file: &str, line: u32) -> Option<SourceContext> {
+    let path = resolve_source(roots, file)?;
+    let bytes = std::fs::read(&path).ok()?;
+    if crate::project_files::looks_binary(&bytes) {
+        return None;
+    }
+    let text = String::from_utf8_lossy(&bytes);
+    let first_line = line.saturating_sub(CONTEXT_LINES).max(1);
+    let lines: Vec<String> = text
+        .lines()
+        .skip(first_line as usize - 1)
+        .take((line - first_line + CONTEXT_LINES + 1) as usize)
+        .map(str::to_string)
+        .collect();
+    // The line is past the end: OCR misread the number or the file changed since
+    if lines.len() < (line - first_line + 1) as usize {
+        return None;
+    }
+    Some(SourceContext { path: path.to_string_lossy().into_owned(), first_line, lines })
+}
+
+/// Attach the surrounding source lines to errors whose file is inside an approved folder
+pub(crate) fn attach_sources(roots: &ProjectRootsState, diagnostics: &mut [ParsedDiagnostic]) {
+    for diagnostic in diagnostics {
+        if let (Some(file), Some(line)) = (&diagnostic.file, diagnostic.line.filter(|l| *l > 0)) {
+            diagnostic.source = source_context(roots, file, line);
+        }
+    }
+}
+
+/// Structured errors and warnings found in OCR or terminal text, with source context where the
+/// file is inside an approved project folder
+#[tauri::command]
+pub async fn parse_diagnostics(
+    roots: State<'_, ProjectRootsState>,
+    text: String,
+) -> Result<Vec<ParsedDiagnostic>, String> {
+    if text.len() > MAX_TEXT_BYTES {
+        return Err(format!("Text is limited to {} KB", MAX_TEXT_BYTES / 1024));
+    }
+    let mut diagnostics = parse(&text);
+    attach_sources(&roots, &mut diagnostics);
+    Ok(diagnostics)
+}
@@ -0,0 +1,317 @@
+//! Word definitions for double-clicking a word in a capture. Lookups go to an offline
+//! dictionary first: a SQLite database shared by all profiles, filled once per language from a
+//! Wiktionary extract (the JSON-lines dumps published by kaikki.org) with `import_dictionary`.
+//! Words it doesn't know are looked up on Wiktionary when the machine is online, and the
+//! answer is kept so the same word works offline afterwards.
+
+use rusqlite::{params, Connection};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::io::BufRead;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+
+use crate::connectivity::ConnectivityState;
+use crate::persistence;
+
+const DB_FILE: &str = "dictionary.db";
+const SCHEMA: &str = "CREATE TABLE IF NOT EXISTS senses (
+    lang TEXT NOT NULL,
+    word TEXT NOT NULL COLLATE NOCASE,
+    part_of_speech TEXT NOT NULL,
+    definition TEXT NOT NULL,
+    example TEXT,
+    source TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS senses_word ON senses (lang, word);";
+const MAX_WORD_CHARS: usize = 64;
+/// Senses returned per lookup
+const MAX_SENSES: usize = 12;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DefinitionSource {
+    /// The offline dictionary
+    Offline,
+    /// Wiktionary, looked up just now
+    Online,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Sense {
+    /// e.g. "noun" or "verb"
+    pub part_of_speech: String,
+    pub definition: String,
+    pub example: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Definition {
+    /// The word the senses belong to, which may be the base form of the one looked up
+    pub word: String,
+    pub lang: String,
+    pub senses: Vec<Sense>,
+    pub source: DefinitionSource,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DictionaryImport {
+    pub lang: String,
+    pub words: u64,
+    pub senses: u64,
+}
+
+#[derive(Clone, Default)]
+pub struct DictionaryState {
+    db: Arc<Mutex<Option<Connection>>>,
+}
+
+impl DictionaryState {
+    /// Run `f` on the database, opening it on first use
+    fn with_db<T>(&self, app: &AppHandle, f: impl FnOnce(&mut Connection) -> rusqlite::Result<T>) -> Result<T, String> {
+        let mut db = self.db.lock().unwrap();
+        if db.is_none() {
+            let path = persistence::shared_data_file(app, DB_FILE)?;
+            let connection = Connection::open(&path)
+                .and_then(|connection| connection.execute_batch(SCHEMA).map(|_| connection))
+                .map_err(|e| format!("Failed to open the dictionary {}: {}", path.display(), e))?;
+            *db = Some(connection);
+        }
+        f(db.as_mut().unwrap()).map_err(|e| format!("Dictionary database error: {}", e))
+    }
+}
+
+/// "en" from "en-GB": dictionaries are per language, not per region
+fn primary_language(lang: &str) -> Result<String, String> {
+    let primary = lang.split(['-', '_']).next().unwrap_or_default().to_ascii_lowercase();
+    if !(2..=3).contains(&primary.len()) || !primary.chars().all(|c| c.is_ascii_lowercase()) {
+        return Err(format!("'{}' is not a language code like \"en\" or \"de\"", lang));
+    }
+    Ok(primary)
+}
+
+/// The word as double-clicked, without surrounding punctuation
+fn clean_word(word: &str) -> Result<String, String> {
+    let word = word.trim().trim_matches(|c: char| !c.is_alphanumeric());
+    if word.is_empty() {
+        return Err("There is no word to look up".to_string());
+    }
+    if word.chars().count() > MAX_WORD_CHARS {
+        return Err(format!("Words are limited to {} characters", MAX_WORD_CHARS));
+    }
+    Ok(word.to_string())
+}
+
+/// The word itself, then likely base forms of an inflected English word ("studies" → "study")
+fn candidates(word: &str, lang: &str) -> Vec<String> {
+    let mut forms = vec![word.to_string()];
+    if lang == "en" {
+        let lower = word.to_lowercase();
+        let stems = [("ies", "y"), ("es", ""), ("s", ""), ("ied", "y"), ("ed", ""), ("ed", "e"), ("ing", ""), ("ing", "e")];
+        for (suffix, replacement) in stems {
+            if let Some(stem) = lower.strip_suffix(suffix).filter(|stem| stem.len() >= 2) {
+                let form = format!("{}{}", stem, replacement);
+                if !forms.contains(&form) {
+                    forms.push(form);
+                }
+            }
+        }
+    }
+    forms
+}
+
+fn lookup_offline(app: &AppHandle, state: &DictionaryState, word: &str, lang: &str) -> Result<Option<Definition>, String> {
+    for form in candidates(word, lang) {
+        let senses = state.with_db(app, |db| {
+            let mut statement = db.prepare_cached(
+                "SELECT part_of_speech, definition, example FROM senses WHERE lang = ?1 AND word = ?2 ORDER BY rowid LIMIT ?3",
+            )?;
+            let rows = statement.query_map(params![lang, form, MAX_SENSES as i64], |row| {
+                Ok(Sense { part_of_speech: row.get(0)?, definition: row.get(1)?, example: row.get(2)? })
+            })?;
+            rows.collect::<rusqlite::Result<Vec<_>>>()
+        })?;
+        if !senses.is_empty() {
+            return Ok(Some(Definition { word: form, lang: lang.to_string(), senses, source: DefinitionSource::Offline }));
+        }
+    }
+    Ok(None)
+}
+
+/// Text of a Wiktionary HTML fragment
+fn strip_html(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    let text = text.replace("&amp;", "&").replace("&lt;", "<").replace("&gt;", ">").replace("&quot;", "\"").replace("&#39;", "'");
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Senses from the Wiktionary REST API, which groups them by the language of the entry
+async fn lookup_online(word: &str, lang: &str) -> Result<Vec<Sense>, String> {
+    let mut url = url::Url::parse(&format!("https://{}.wiktionary.org/api/rest_v1/page/definition/", lang))
+        .map_err(|e| format!("Invalid Wiktionary URL: {}", e))?;
+    url.path_segments_mut().map_err(|_| "Invalid Wiktionary URL".to_string())?.push(word);
+    let client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let response = client.get(url).send().await.map_err(|e| format!("Wiktionary request failed: {}", e))?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(Vec::new());
+    }
+    if !response.status().is_success() {
+        return Err(format!("Wiktionary lookup failed (HTTP {})", response.status()));
+    }
+    let body: Value = response.json().await.map_err(|e| format!("Unexpected Wiktionary response: {}", e))?;
+    let entries = body[lang].as_array().cloned().unwrap_or_default();
+    let senses = entries
+        .iter()
+        .flat_map(|entry| {
+            let part_of_speech = entry["partOfSpeech"].as_str().unwrap_or_default().to_lowercase();
+            entry["definitions"].as_array().cloned().unwrap_or_default().into_iter().map(move |definition| Sense {
+                part_of_speech: part_of_speech.clone(),
+                definition: strip_html(definition["definition"].as_str().unwrap_or_default()),
+                example: definition["examples"][0].as_str().map(strip_html),
+            })
+        })
+        .filter(|sense| !sense.definition.is_empty())
+        .take(MAX_SENSES)
+        .collect();
+    Ok(senses)
+}
+
+fn store(app: &AppHandle, state: &DictionaryState, word: &str, lang: &str, senses: &[Sense], source: &str) -> Result<(), String> {
+    state.with_db(app, |db| {
+        let tx = db.transaction()?;
+        {
+            let mut insert = tx.prepare_cached(
+                "INSERT INTO senses (lang, word, part_of_speech, definition, example, source) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            )?;
+            for sense in senses {
+                insert.execute(params![lang, word, sense.part_of_speech, sense.definition, sense.example, source])?;
+            }
+        }
+        tx.commit()
+    })
+}
+
+/// One line of a kaikki.org Wiktionary extract
+#[derive(Deserialize)]
+struct ExtractEntry {
+    word: String,
+    #[serde(default)]
+    lang_code: Option<String>,
+    #[serde(default)]
+    pos: String,
+    #[serde(default)]
+    senses: Vec<ExtractSense>,
+}
+
+#[derive(Deserialize)]
+struct ExtractSense {
+    #[serde(default)]
+    glosses: Vec<String>,
+    #[serde(default)]
+    examples: Vec<ExtractExample>,
+}
+
+#[derive(Deserialize)]
+struct ExtractExample {
+    #[serde(default)]
+    text: Option<String>,
+}
+
+fn import_extract(app: &AppHandle, state: &DictionaryState, path: &std::path::Path, lang: &str) -> Result<DictionaryImport, String> {
+    let file = std::fs::File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let reader = std::io::BufReader::new(file);
+    state.with_db(app, |db| {
+        let tx = db.transaction()?;
+        // Re-importing a language replaces its earlier extract but keeps words cached from Wiktionary
+        tx.execute("DELETE FROM senses WHERE lang = ?1 AND source = 'extract'", params![lang])?;
+        let (mut words, mut senses) = (0, 0);
+        {
+            let mut insert = tx.prepare_cached(
+                "INSERT INTO senses (lang, word, part_of_speech, definition, example, source) VALUES (?1, ?2, ?3, ?4, ?5, 'extract')",
+            )?;
+            for line in reader.lines().map_while(Result::ok) {
+                let Ok(entry) = serde_json::from_str::<ExtractEntry>(&line) else {
+                    continue;
+                };
+                if entry.lang_code.as_deref().is_some_and(|code| code != lang) {
+                    continue;
+                }
+                let mut added = false;
+                for sense in &entry.senses {
+                    let Some(gloss) = sense.glosses.last() else {
+                        continue;
+                    };
+                    let example = sense.examples.iter().find_map(|example| example.text.clone());
+                    insert.execute(params![lang, entry.word, entry.pos, gloss, example])?;
+                    senses += 1;
+                    added = true;
+                }
+                words += added as u64;
+            }
+        }
+        tx.commit()?;
+        Ok(DictionaryImport { lang: lang.to_string(), words, senses })
+    })
+}
+
+/// Define a word, e.g. one double-clicked in a capture: from the offline dictionary, or from
+/// Wiktionary when it isn't there, the machine is online and `online_fallback` isn't false
+#[tauri::command]
+pub async fn define_term(
+    app: AppHandle,
+    state: State<'_, DictionaryState>,
+    word: String,
+    lang: String,
+    online_fallback: Option<bool>,
+) -> Result<Definition, String> {
+    let word = clean_word(&word)?;
+    let lang = primary_language(&lang)?;
+    if let Some(definition) = lookup_offline(&app, &state, &word, &lang)? {
+        return Ok(definition);
+    }
+    if !online_fallback.unwrap_or(true) || !app.state::<ConnectivityState>().is_online() {
+        return Err(format!("'{}' isn't in the offline {} dictionary", word, lang));
+    }
+    let senses = lookup_online(&word, &lang).await?;
+    if senses.is_empty() {
+        return Err(format!("No definition of '{}' was found", word));
+    }
+    if let Err(e) = store(&app, &state, &word, &lang, &senses, "wiktionary") {
+        tracing::warn!("⚠️ Failed to keep the definition of '{}' offline: {}", word, e);
+    }
+    Ok(Definition { word, lang, senses, source: DefinitionSource::Online })
+}
+
+/// Fill the offline dictionary for `lang` from a Wiktionary JSON-lines extract (kaikki.org),
+/// replacing an earlier import of the same language
+#[tauri::command]
+pub async fn import_dictionary(
+    app: AppHandle,
+    state: State<'_, DictionaryState>,
+    path: String,
+    lang: String,
+) -> Result<DictionaryImport, String> {
+    let path = std::path::absolute(&path).map_err(|e| format!("Invalid path {}: {}", path, e))?;
+    let lang = primary_language(&lang)?;
+    let state = state.inner().clone();
+    let import = tauri::async_runtime::spawn_blocking(move || import_extract(&app, &state, &path, &lang))
+        .await
+        .map_err(|e| format!("Dictionary import failed: {}", e))??;
+    tracing::info!("📖 Imported {} words ({} senses) into the {} dictionary", import.words, import.senses, import.lang);
+    Ok(import)
+}
@@ -0,0 +1,321 @@
+use regex::{Regex, RegexBuilder};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+
+use crate::persistence;
+use crate::watchdog::Heartbeat;
+
+const RULES_FILE: &str = "distraction_rules.json";
+const POLL_INTERVAL: Duration = Duration::from_secs(15);
+/// A few missed polls mean the distraction watcher is stuck
+const WATCH_SILENCE: Duration = Duration::from_secs(120);
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ActivityClass {
+    Study,
+    Neutral,
+    Distraction,
+}
+
+/// User-defined classification lists. Keywords match case-insensitively against the
+/// window title, process name and URL; regexes are case-insensitive too.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DistractionRules {
+    pub enabled: bool,
+    pub study_keywords: Vec<String>,
+    pub distraction_keywords: Vec<String>,
+    #[serde(default)]
+    pub study_patterns: Vec<String>,
+    #[serde(default)]
+    pub distraction_patterns: Vec<String>,
+    /// How long a distraction has to last before `distraction-detected` is emitted
+    pub nudge_after_secs: u64,
+}
+
+impl Default for DistractionRules {
+    fn default() -> Self {
+        let words = |list: &[&str]| list.iter().map(|s| s.to_string()).collect();
+        Self {
+            enabled: false,
+            study_keywords: words(&[
+                "khan academy", "wikipedia", "stack overflow", "docs", "documentation", "coursera",
+                "edx", "google docs", "visual studio code", "devenv", "pycharm", "notion", "overleaf",
+            ]),
+            distraction_keywords: words(&[
+                "youtube", "netflix", "twitch", "tiktok", "instagram", "facebook", "reddit",
+                "twitter", "discord", "steam", "epic games",
+            ]),
+            study_patterns: Vec::new(),
+            distraction_patterns: Vec::new(),
+            nudge_after_secs: 120,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Classification {
+    pub class: ActivityClass,
+    /// The keyword or pattern that decided the class
+    pub matched_rule: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct StreakStatus {
+    pub class: ActivityClass,
+    pub since: i64,
+    pub duration_secs: i64,
+    pub window_title: String,
+    /// Longest uninterrupted study streak since the app started
+    pub longest_study_secs: i64,
+}
+
+/// Payload of the `distraction-detected` event
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DistractionDetected {
+    pub window_title: String,
+    pub process_name: String,
+    pub url: Option<String>,
+    pub matched_rule: Option<String>,
+    pub streak_secs: i64,
+    pub timestamp: i64,
+}
+
+struct Streak {
+    class: ActivityClass,
+    since: i64,
+    window_title: String,
+    nudged: bool,
+}
+
+struct CompiledRules {
+    rules: DistractionRules,
+    study: Vec<Regex>,
+    distraction: Vec<Regex>,
+}
+
+impl CompiledRules {
+    fn compile(rules: DistractionRules) -> Result<Self, String> {
+        let compile_all = |patterns: &[String]| {
+            patterns
+                .iter()
+                .map(|p| {
+                    RegexBuilder::new(p)
+                        .case_insensitive(true)
+                        .build()
+                        .map_err(|e| format!("Invalid pattern '{}': {}", p, e))
+                })
+                .collect::<Result<Vec<_>, _>>()
+        };
+        Ok(Self {
+            study: compile_all(&rules.study_patterns)?,
+            distraction: compile_all(&rules.distraction_patterns)?,
+            rules,
+        })
+    }
+
+    /// Study patterns are the most specific rules and win outright; otherwise distraction rules
+    /// beat study keywords, so a video titled "Wikipedia speedrun - YouTube" is still a distraction
+    fn classify(&self, haystack: &str) -> Classification {
+        let lower = haystack.to_lowercase();
+        let keyword = |list: &[String]| list.iter().find(|k| !k.is_empty() && lower.contains(&k.to_lowercase())).cloned();
+        let pattern = |list: &[Regex]| list.iter().find(|r| r.is_match(haystack)).map(|r| r.as_str().to_string());
+
+        if let Some(rule) = pattern(&self.study) {
+            return Classification { class: ActivityClass::Study, matched_rule: Some(rule) };
+        }
+        if let Some(rule) = pattern(&self.distraction).or_else(|| keyword(&self.rules.distraction_keywords)) {
+            return Classification { class: ActivityClass::Distraction, matched_rule: Some(rule) };
+        }
+        if let Some(rule) = keyword(&self.rules.study_keywords) {
+            return Classification { class: ActivityClass::Study, matched_rule: Some(rule) };
+        }
+        Classification { class: ActivityClass::Neutral, matched_rule: None }
+    }
+}
+
+#[derive(Clone)]
+pub struct DistractionState {
+    compiled: Arc<Mutex<CompiledRules>>,
+    streak: Arc<Mutex<Option<Streak>>>,
+    longest_study_secs: Arc<Mutex<i64>>,
+}
+
+impl Default for DistractionState {
+    fn default() -> Self {
+        Self {
+            compiled: Arc::new(Mutex::new(
+                CompiledRules::compile(DistractionRules::default()).expect("default rules compile"),
+            )),
+            streak: Arc::new(Mutex::new(None)),
+            longest_study_secs: Arc::new(Mutex::new(0)),
+        }
+    }
+}
+
+impl DistractionState {
+    /// Longest study streak so far, including the one still running
+    pub fn longest_study_secs(&self) -> i64 {
+        let now = chrono::Utc::now().timestamp();
+        let current = self
+            .streak
+            .lock()
+            .unwrap()
+            .as_ref()
+            .filter(|s| s.class == ActivityClass::Study)
+            .map(|s| now - s.since)
+            .unwrap_or(0);
+        (*self.longest_study_secs.lock().unwrap()).max(current)
+    }
+}
+
+fn haystack(window_title: &str, process_name: &str, url: Option<&str>) -> String {
+    format!("{} {} {}", window_title, process_name, url.unwrap_or_default())
+}
+
+/// Classify the window and update the running streak, emitting `distraction-detected`
+/// once per streak when a distraction lasts longer than the configured threshold.
+pub fn observe(app: &AppHandle, window_title: &str, process_name: &str, url: Option<&str>) -> Classification {
+    let state = app.state::<DistractionState>();
+    let (classification, nudge_after) = {
+        let compiled = state.compiled.lock().unwrap();
+        (
+            compiled.classify(&haystack(window_title, process_name, url)),
+            compiled.rules.nudge_after_secs as i64,
+        )
+    };
+
+    let now = chrono::Utc::now().timestamp();
+    let mut streak = state.streak.lock().unwrap();
+    let continues = streak.as_ref().map(|s| s.class == classification.class).unwrap_or(false);
+    if !continues {
+        if let Some(previous) = streak.as_ref().filter(|s| s.class == ActivityClass::Study) {
+            let mut longest = state.longest_study_secs.lock().unwrap();
+            *longest = (*longest).max(now - previous.since);
+        }
+        *streak = Some(Streak {
+            class: classification.class,
+            since: now,
+            window_title: window_title.to_string(),
+            nudged: false,
+        });
+    }
+
+    let current = streak.as_mut().expect("streak was just set");
+    current.window_title = window_title.to_string();
+    let streak_secs = now - current.since;
+    if current.class == ActivityClass::Distraction && !current.nudged && streak_secs >= nudge_after {
+        current.nudged = true;
+        tracing::info!("🔔 Distracted for {}s in \"{}\"", streak_secs, window_title);
+        let _ = crate::event_bus::emit(
+            app,
+            crate::events::DISTRACTION_DETECTED,
+            DistractionDetected {
+                window_title: window_title.to_string(),
+                process_name: process_name.to_string(),
+                url: url.map(String::from),
+                matched_rule: classification.matched_rule.clone(),
+                streak_secs,
+                timestamp: now,
+            },
+        );
+    }
+
+    classification
+}
+
+fn persist(app: &AppHandle, rules: &DistractionRules) -> Result<(), String> {
+    let path = persistence::data_file(app, RULES_FILE)?;
+    persistence::save_json(&path, rules)
+}
+
+/// Load saved rules and start polling the active window while detection is enabled
+pub fn start(app: AppHandle) {
+    let rules: Option<DistractionRules> = match persistence::data_file(&app, RULES_FILE) {
+        Ok(path) => persistence::load_json(&path),
+        Err(e) => {
+            tracing::warn!("⚠️ {}", e);
+            None
+        }
+    };
+    if let Some(rules) = rules {
+        match CompiledRules::compile(rules) {
+            Ok(compiled) => *app.state::<DistractionState>().compiled.lock().unwrap() = compiled,
+            Err(e) => tracing::warn!("⚠️ Ignoring saved rules: {}", e),
+        }
+    }
+
+    let loop_app = app.clone();
+    crate::watchdog::spawn_supervised(&app, "Distraction watcher", WATCH_SILENCE, move |heartbeat| {
+        watch_active_window(loop_app.clone(), heartbeat)
+    });
+}
+
+/// Follow the foreground window, feeding it to the distraction rules
+async fn watch_active_window(app: AppHandle, heartbeat: Heartbeat) {
+    loop {
+        tokio::time::sleep(POLL_INTERVAL).await;
+        heartbeat.beat();
+        let enabled = app.state::<DistractionState>().compiled.lock().unwrap().rules.enabled;
+        if !enabled {
+            continue;
+        }
+        match crate::commands::get_system_context().await {
+            Ok(context) => {
+                observe(&app, &context.active_window_title, &context.active_window, None);
+            }
+            Err(e) => tracing::warn!("⚠️ Failed to read active window: {}", e),
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn get_distraction_rules(
+    state: State<'_, DistractionState>,
+) -> Result<DistractionRules, String> {
+    Ok(state.compiled.lock().unwrap().rules.clone())
+}
+
+#[tauri::command]
+pub async fn set_distraction_rules(
+    app: AppHandle,
+    state: State<'_, DistractionState>,
+    rules: DistractionRules,
+) -> Result<(), String> {
+    let compiled = CompiledRules::compile(rules)?;
+    persist(&app, &compiled.rules)?;
+    *state.compiled.lock().unwrap() = compiled;
+    // Start counting afresh under the new rules
+    *state.streak.lock().unwrap() = None;
+    Ok(())
+}
+
+/// Classify a window (and optionally the browser URL the frontend knows about) and update the streak
+#[tauri::command]
+pub async fn classify_activity(
+    app: AppHandle,
+    window_title: String,
+    process_name: String,
+    url: Option<String>,
+) -> Result<Classification, String> {
+    Ok(observe(&app, &window_title, &process_name, url.as_deref()))
+}
+
+#[tauri::command]
+pub async fn get_activity_streak(
+    state: State<'_, DistractionState>,
+) -> Result<Option<StreakStatus>, String> {
+    let now = chrono::Utc::now().timestamp();
+    let longest_study_secs = state.longest_study_secs();
+    let streak = state.streak.lock().unwrap();
+    Ok(streak.as_ref().map(|s| StreakStatus {
+        class: s.class,
+        since: s.since,
+        duration_secs: now - s.since,
+        window_title: s.window_title.clone(),
+        longest_study_secs,
+    }))
+}
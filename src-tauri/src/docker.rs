@@ -0,0 +1,324 @@
+//! Typed Docker context for the tutor. Talks to the Docker Engine API over the local socket
+//! (the `docker_engine` named pipe on Windows, `/var/run/docker.sock` elsewhere, or a local
+//! `DOCKER_HOST`) instead of parsing `docker ps` output. Only GET requests are ever sent, and
+//! each command passes the same allowlist and role checks as the matching `docker` CLI call.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::AppHandle;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+#[cfg(target_os = "windows")]
+const DEFAULT_ENDPOINT: &str = r"\\.\pipe\docker_engine";
+#[cfg(not(target_os = "windows"))]
+const DEFAULT_ENDPOINT: &str = "/var/run/docker.sock";
+const DEFAULT_LOG_TAIL: u32 = 100;
+const MAX_LOG_TAIL: u32 = 5000;
+/// Larger responses are refused rather than buffered
+const MAX_RESPONSE_BYTES: usize = 32 * 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ContainerPort {
+    pub private_port: u16,
+    /// Host port it's published on, if any
+    pub public_port: Option<u16>,
+    /// "tcp", "udp" or "sctp"
+    pub protocol: String,
+    /// Host address it's published on
+    pub ip: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ContainerSummary {
+    /// Short (12 character) id
+    pub id: String,
+    pub names: Vec<String>,
+    pub image: String,
+    /// "running", "exited", "restarting", ...
+    pub state: String,
+    /// Human-readable status, e.g. "Up 5 minutes"
+    pub status: String,
+    pub created: i64,
+    pub ports: Vec<ContainerPort>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ContainerLogLine {
+    /// "stdout" or "stderr"
+    pub stream: String,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ContainerStats {
+    /// Share of one CPU, so it can exceed 100 on multi-core hosts; None when the engine doesn't
+    /// report the system totals needed (e.g. Windows containers)
+    pub cpu_percent: Option<f64>,
+    pub memory_bytes: Option<u64>,
+    pub memory_limit_bytes: Option<u64>,
+    pub network_rx_bytes: u64,
+    pub network_tx_bytes: u64,
+    pub pids: Option<u64>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct RawPort {
+    private_port: u16,
+    #[serde(default)]
+    public_port: Option<u16>,
+    #[serde(rename = "Type")]
+    protocol: String,
+    #[serde(rename = "IP", default)]
+    ip: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct RawContainer {
+    id: String,
+    #[serde(default)]
+    names: Vec<String>,
+    image: String,
+    state: String,
+    status: String,
+    created: i64,
+    #[serde(default)]
+    ports: Vec<RawPort>,
+}
+
+#[derive(Deserialize, Default)]
+struct RawCpuUsage {
+    total_usage: u64,
+}
+
+#[derive(Deserialize, Default)]
+struct RawCpuStats {
+    #[serde(default)]
+    cpu_usage: RawCpuUsage,
+    system_cpu_usage: Option<u64>,
+    online_cpus: Option<u64>,
+}
+
+#[derive(Deserialize, Default)]
+struct RawMemoryStats {
+    usage: Option<u64>,
+    limit: Option<u64>,
+}
+
+#[derive(Deserialize, Default)]
+struct RawNetwork {
+    rx_bytes: u64,
+    tx_bytes: u64,
+}
+
+#[derive(Deserialize, Default)]
+struct RawPids {
+    current: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct RawStats {
+    #[serde(default)]
+    cpu_stats: RawCpuStats,
+    #[serde(default)]
+    precpu_stats: RawCpuStats,
+    #[serde(default)]
+    memory_stats: RawMemoryStats,
+    #[serde(default)]
+    networks: HashMap<String, RawNetwork>,
+    #[serde(default)]
+    pids_stats: RawPids,
+}
+
+trait Connection: AsyncRead + AsyncWrite + Unpin + Send {}
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> Connection for T {}
+
+/// The socket or pipe to use: `DOCKER_HOST` when it names a local one, otherwise the default
+fn endpoint() -> Result<String, String> {
+    match std::env::var("DOCKER_HOST") {
+        Ok(host) if host.starts_with("unix://") => Ok(host["unix://".len()..].to_string()),
+        Ok(host) if host.starts_with("npipe://") => Ok(host["npipe://".len()..].replace('/', "\\")),
+        Ok(host) if !host.is_empty() => {
+            Err(format!("DOCKER_HOST {} isn't a local socket; only local Docker engines are supported", host))
+        }
+        _ => Ok(DEFAULT_ENDPOINT.to_string()),
+    }
+}
+
+async fn connect(endpoint: &str) -> Result<Box<dyn Connection>, String> {
+    let unreachable = |e: std::io::Error| format!("Docker isn't running or reachable at {}: {}", endpoint, e);
+    #[cfg(target_os = "windows")]
+    {
+        let pipe = tokio::net::windows::named_pipe::ClientOptions::new().open(endpoint).map_err(unreachable)?;
+        Ok(Box::new(pipe))
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        let socket = tokio::net::UnixStream::connect(endpoint).await.map_err(unreachable)?;
+        Ok(Box::new(socket))
+    }
+}
+
+/// Undo `Transfer-Encoding: chunked`
+fn dechunk(mut body: &[u8]) -> Result<Vec<u8>, String> {
+    let mut decoded = Vec::new();
+    loop {
+        let line_end = body.windows(2).position(|w| w == b"\r\n").ok_or("Truncated chunked response")?;
+        let size_field = String::from_utf8_lossy(&body[..line_end]);
+        let size = usize::from_str_radix(size_field.split(';').next().unwrap_or("").trim(), 16)
+            .map_err(|_| format!("Invalid chunk size '{}'", size_field))?;
+        body = &body[line_end + 2..];
+        if size == 0 {
+            return Ok(decoded);
+        }
+        let chunk = body.get(..size).ok_or("Truncated chunked response")?;
+        decoded.extend_from_slice(chunk);
+        body = body.get(size + 2..).unwrap_or_default();
+    }
+}
+
+/// GET `path` from the Engine API, returning the body of a successful response
+async fn get(path: &str) -> Result<Vec<u8>, String> {
+    let mut connection = connect(&endpoint()?).await?;
+    // HTTP/1.0 so the engine closes the connection after the response
+    let request = format!("GET {} HTTP/1.0\r\nHost: docker\r\nAccept: application/json\r\n\r\n", path);
+    connection
+        .write_all(request.as_bytes())
+        .await
+        .map_err(|e| format!("Failed to send the Docker request: {}", e))?;
+    let mut response = Vec::new();
+    connection
+        .take(MAX_RESPONSE_BYTES as u64 + 1)
+        .read_to_end(&mut response)
+        .await
+        .map_err(|e| format!("Failed to read the Docker response: {}", e))?;
+    if response.len() > MAX_RESPONSE_BYTES {
+        return Err("The Docker response is too large".to_string());
+    }
+
+    let header_end = response
+        .windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .ok_or("Malformed Docker response")?;
+    let head = String::from_utf8_lossy(&response[..header_end]).to_string();
+    let body = &response[header_end + 4..];
+    let status: u16 = head.split_whitespace().nth(1).and_then(|s| s.parse().ok()).ok_or("Malformed Docker response")?;
+    let chunked = head
+        .lines()
+        .any(|line| line.to_ascii_lowercase().starts_with("transfer-encoding:") && line.to_ascii_lowercase().contains("chunked"));
+    let body = if chunked { dechunk(body)? } else { body.to_vec() };
+
+    if !(200..300).contains(&status) {
+        // Errors come as {"message": "..."}
+        let message = serde_json::from_slice::<serde_json::Value>(&body)
+            .ok()
+            .and_then(|v| v.get("message").and_then(|m| m.as_str()).map(str::to_string))
+            .unwrap_or_else(|| String::from_utf8_lossy(&body).trim().to_string());
+        return Err(format!("Docker error {}: {}", status, message));
+    }
+    Ok(body)
+}
+
+/// Container ids and names go into the URL path, so only their own characters are accepted
+fn validate_container(id: &str) -> Result<(), String> {
+    let valid = !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-'));
+    if !valid {
+        return Err(format!("Invalid container id or name '{}'", id));
+    }
+    Ok(())
+}
+
+fn check_policy(app: &AppHandle, subcommand: &str) -> Result<(), String> {
+    crate::commands::check_command_permitted(app, "docker", &[subcommand.to_string()])
+}
+
+/// Split the engine's multiplexed log stream (8-byte frame headers naming stdout or stderr).
+/// Containers with a TTY send plain text instead, which is all stdout.
+fn demux_logs(body: &[u8]) -> Vec<ContainerLogLine> {
+    let multiplexed = body.len() >= 8 && matches!(body[0], 0..=2) && body[1..4] == [0, 0, 0];
+    let mut frames: Vec<(&str, &[u8])> = Vec::new();
+    if multiplexed {
+        let mut rest = body;
+        while rest.len() >= 8 {
+            let stream = if rest[0] == 2 { "stderr" } else { "stdout" };
+            let size = u32::from_be_bytes([rest[4], rest[5], rest[6], rest[7]]) as usize;
+            let end = (8 + size).min(rest.len());
+            frames.push((stream, &rest[8..end]));
+            rest = &rest[end..];
+        }
+    } else {
+        frames.push(("stdout", body));
+    }
+
+    let mut lines: Vec<ContainerLogLine> = Vec::new();
+    for (stream, data) in frames {
+        for text in String::from_utf8_lossy(data).lines() {
+            lines.push(ContainerLogLine { stream: stream.to_string(), text: text.to_string() });
+        }
+    }
+    lines
+}
+
+/// Containers with their image, state and published ports; stopped ones too with `all`
+#[tauri::command]
+pub async fn list_containers(app: AppHandle, all: Option<bool>) -> Result<Vec<ContainerSummary>, String> {
+    check_policy(&app, "ps")?;
+    let body = get(&format!("/containers/json?all={}", all.unwrap_or(false))).await?;
+    let containers: Vec<RawContainer> =
+        serde_json::from_slice(&body).map_err(|e| format!("Failed to parse the container list: {}", e))?;
+    Ok(containers
+        .into_iter()
+        .map(|c| ContainerSummary {
+            id: c.id.chars().take(12).collect(),
+            names: c.names.into_iter().map(|n| n.trim_start_matches('/').to_string()).collect(),
+            image: c.image,
+            state: c.state,
+            status: c.status,
+            created: c.created,
+            ports: c
+                .ports
+                .into_iter()
+                .map(|p| ContainerPort { private_port: p.private_port, public_port: p.public_port, protocol: p.protocol, ip: p.ip })
+                .collect(),
+        })
+        .collect())
+}
+
+/// The last `tail` lines (default 100) of a container's output
+#[tauri::command]
+pub async fn container_logs(app: AppHandle, id: String, tail: Option<u32>) -> Result<Vec<ContainerLogLine>, String> {
+    check_policy(&app, "logs")?;
+    validate_container(&id)?;
+    let tail = tail.unwrap_or(DEFAULT_LOG_TAIL).clamp(1, MAX_LOG_TAIL);
+    let body = get(&format!("/containers/{}/logs?stdout=1&stderr=1&tail={}", id, tail)).await?;
+    Ok(demux_logs(&body))
+}
+
+/// One sample of a container's CPU, memory, network and process counts
+#[tauri::command]
+pub async fn container_stats(app: AppHandle, id: String) -> Result<ContainerStats, String> {
+    check_policy(&app, "stats")?;
+    validate_container(&id)?;
+    // Without one-shot the engine waits for a second sample, so CPU usage can be computed
+    let body = get(&format!("/containers/{}/stats?stream=false", id)).await?;
+    let stats: RawStats = serde_json::from_slice(&body).map_err(|e| format!("Failed to parse container stats: {}", e))?;
+
+    let cpu_delta = stats.cpu_stats.cpu_usage.total_usage.saturating_sub(stats.precpu_stats.cpu_usage.total_usage);
+    let cpu_percent = match (stats.cpu_stats.system_cpu_usage, stats.precpu_stats.system_cpu_usage) {
+        (Some(system), Some(previous)) if system > previous => {
+            let cpus = stats.cpu_stats.online_cpus.unwrap_or(1) as f64;
+            Some(cpu_delta as f64 / (system - previous) as f64 * cpus * 100.0)
+        }
+        _ => None,
+    };
+    Ok(ContainerStats {
+        cpu_percent,
+        memory_bytes: stats.memory_stats.usage,
+        memory_limit_bytes: stats.memory_stats.limit,
+        network_rx_bytes: stats.networks.values().map(|n| n.rx_bytes).sum(),
+        network_tx_bytes: stats.networks.values().map(|n| n.tx_bytes).sum(),
+        pids: stats.pids_stats.current,
+    })
+}
@@ -0,0 +1,216 @@
+//! Document import: the text of PDF and DOCX files dropped into the app (and, on Windows,
+//! rendered PDF page images) is stored in the history, so the tutor can discuss the actual
+//! assignment sheet instead of a screenshot of it. Scanned PDF pages without a text layer are
+//! read with OCR from their page image. Dropped image files are kept the same way, with their
+//! OCR text.
+
+use base64::{engine::general_purpose, Engine as _};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use std::io::{Cursor, Read};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+use crate::cancellation::{CancellationState, OperationGuard};
+use crate::history::{HistoryItem, HistoryPart, HistoryState};
+use crate::persistence;
+use crate::powershell_host::{PowerShellHostState, WINRT_PRELUDE};
+
+const MAX_FILE_BYTES: u64 = 50 * 1024 * 1024;
+/// PDF pages rendered to images; later pages are imported as text only
+const MAX_PAGE_IMAGES: usize = 30;
+const PAGE_IMAGE_WIDTH: u32 = 1400;
+const DOCUMENTS_DIR: &str = "documents";
+
+/// Text of each PDF page
+fn pdf_pages(bytes: &[u8]) -> Result<Vec<String>, String> {
+    pdf_extract::extract_text_from_mem_by_pages(bytes).map_err(|e| format!("Failed to read PDF: {}", e))
+}
+
+/// Text of a DOCX body: one line per paragraph, with the runs' tabs and line breaks kept
+fn docx_text(bytes: &[u8]) -> Result<String, String> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).map_err(|e| format!("Failed to open DOCX: {}", e))?;
+    let mut xml = String::new();
+    archive
+        .by_name("word/document.xml")
+        .map_err(|e| format!("Not a Word document: {}", e))?
+        .read_to_string(&mut xml)
+        .map_err(|e| format!("Failed to read DOCX: {}", e))?;
+
+    let mut reader = Reader::from_str(&xml);
+    let mut text = String::new();
+    let (mut in_run, mut in_text) = (false, false);
+    loop {
+        match reader.read_event().map_err(|e| format!("Failed to parse DOCX: {}", e))? {
+            Event::Start(e) => match e.local_name().as_ref() {
+                b"r" => in_run = true,
+                b"t" => in_text = true,
+                _ => {}
+            },
+            Event::End(e) => match e.local_name().as_ref() {
+                b"r" => in_run = false,
+                b"t" => in_text = false,
+                b"p" => text.push('\n'),
+                _ => {}
+            },
+            // `tab` also appears in paragraph tab-stop definitions, outside runs
+            Event::Empty(e) => match e.local_name().as_ref() {
+                b"tab" if in_run => text.push('\t'),
+                b"br" | b"cr" if in_run => text.push('\n'),
+                b"p" => text.push('\n'),
+                _ => {}
+            },
+            Event::Text(e) if in_text => {
+                text.push_str(&e.unescape().map_err(|e| format!("Failed to parse DOCX: {}", e))?);
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(text)
+}
+
+/// Render the first pages of `{path}` to `page-N.png` files in `{out_dir}` with `Windows.Data.Pdf`,
+/// printing each file's path
+fn render_pages_script(path: &Path, out_dir: &Path) -> String {
+    format!(r#"
+[Windows.Data.Pdf.PdfDocument, Windows.Data.Pdf, ContentType=WindowsRuntime] | Out-Null
+$file = Await ([Windows.Storage.StorageFile]::GetFileFromPathAsync('{}')) ([Windows.Storage.StorageFile])
+$pdf = Await ([Windows.Data.Pdf.PdfDocument]::LoadFromFileAsync($file)) ([Windows.Data.Pdf.PdfDocument])
+$count = [Math]::Min([int]$pdf.PageCount, {})
+for ($i = 0; $i -lt $count; $i++) {{
+    $page = $pdf.GetPage([uint32]$i)
+    $options = New-Object Windows.Data.Pdf.PdfPageRenderOptions
+    $options.DestinationWidth = [uint32]{}
+    $stream = New-Object Windows.Storage.Streams.InMemoryRandomAccessStream
+    AwaitAction ($page.RenderToStreamAsync($stream, $options))
+    $target = Join-Path '{}' ('page-' + ($i + 1) + '.png')
+    [System.IO.File]::WriteAllBytes($target, (Read-StreamBytes $stream))
+    $page.Dispose()
+    $target
+}}
+"#,
+        path.to_string_lossy().replace('\'', "''"),
+        MAX_PAGE_IMAGES,
+        PAGE_IMAGE_WIDTH,
+        out_dir.to_string_lossy().replace('\'', "''"),
+    )
+}
+
+/// Page images of a PDF, in page order; none outside Windows
+async fn render_pages(
+    app: &AppHandle,
+    path: &Path,
+    out_dir: &Path,
+    operation: &OperationGuard,
+) -> Result<Vec<PathBuf>, String> {
+    if !cfg!(target_os = "windows") {
+        return Ok(Vec::new());
+    }
+    std::fs::create_dir_all(out_dir).map_err(|e| format!("Failed to create {}: {}", out_dir.display(), e))?;
+    let script = format!("{}{}", WINRT_PRELUDE, render_pages_script(path, out_dir));
+    let output = app.state::<PowerShellHostState>().run(&script, Some(operation), |_| {}).await?;
+    if !output.success() {
+        return Err(format!("Failed to render PDF pages: {}", output.stderr.trim()));
+    }
+    Ok(output.stdout.lines().map(str::trim).filter(|line| !line.is_empty()).map(PathBuf::from).collect())
+}
+
+async fn read_limited(path: &Path, kind: &str) -> Result<Vec<u8>, String> {
+    let size = tokio::fs::metadata(path)
+        .await
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?
+        .len();
+    if size > MAX_FILE_BYTES {
+        return Err(format!("{} over {} MB can't be imported", kind, MAX_FILE_BYTES / (1024 * 1024)));
+    }
+    tokio::fs::read(path).await.map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+}
+
+/// Import a PDF or DOCX file into the history, one part per PDF page
+pub(crate) async fn import(app: &AppHandle, path: &Path, operation: &OperationGuard) -> Result<HistoryItem, String> {
+    let extension = path.extension().map(|e| e.to_string_lossy().to_lowercase()).unwrap_or_default();
+    if extension != "pdf" && extension != "docx" {
+        return Err("Only PDF and DOCX documents can be imported".to_string());
+    }
+    let bytes = read_limited(path, "Documents").await?;
+
+    let is_pdf = extension == "pdf";
+    let texts = tauri::async_runtime::spawn_blocking(move || {
+        if is_pdf {
+            pdf_pages(&bytes)
+        } else {
+            docx_text(&bytes).map(|text| vec![text])
+        }
+    })
+    .await
+    .map_err(|e| format!("Failed to read {}: {}", path.display(), e))??;
+
+    let mut images = Vec::new();
+    if is_pdf {
+        let out_dir = persistence::data_file(app, DOCUMENTS_DIR)?.join(uuid::Uuid::new_v4().simple().to_string());
+        images = render_pages(app, path, &out_dir, operation).await.unwrap_or_else(|e| {
+            tracing::warn!("⚠️ Importing {} without page images: {}", path.display(), e);
+            Vec::new()
+        });
+    }
+
+    let mut parts = Vec::with_capacity(texts.len());
+    for (index, text) in texts.into_iter().enumerate() {
+        let image = images.get(index);
+        let mut text = text.trim().to_string();
+        // A scanned page has no text layer
+        if let (true, Some(image)) = (text.is_empty(), image) {
+            let png = tokio::fs::read(image).await.map_err(|e| format!("Failed to read page image: {}", e))?;
+            text = crate::commands::ocr_image(app, general_purpose::STANDARD.encode(png), operation).await?;
+        }
+        parts.push(HistoryPart {
+            part: index as u32 + 1,
+            text,
+            image_path: image.map(|image| image.to_string_lossy().into_owned()),
+        });
+    }
+
+    let title = path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+    let history = app.state::<HistoryState>();
+    let id = history.insert("document", &title, Some(&path.to_string_lossy()), &parts)?;
+    tracing::info!("📄 Imported {} ({} parts, {} page images)", path.display(), parts.len(), images.len());
+    history.get(id)?.ok_or_else(|| "Imported document disappeared".to_string())
+}
+
+/// Import an image file into the history: a PNG copy next to the documents and its OCR text
+pub(crate) async fn import_image(app: &AppHandle, path: &Path, operation: &OperationGuard) -> Result<HistoryItem, String> {
+    let bytes = read_limited(path, "Images").await?;
+    let png = tauri::async_runtime::spawn_blocking(move || {
+        let image = image::load_from_memory(&bytes).map_err(|e| format!("Not a readable image: {}", e))?;
+        let mut png = Vec::new();
+        image
+            .write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)
+            .map_err(|e| format!("Failed to convert the image: {}", e))?;
+        Ok::<_, String>(png)
+    })
+    .await
+    .map_err(|e| format!("Failed to read {}: {}", path.display(), e))??;
+
+    let out_dir = persistence::data_file(app, DOCUMENTS_DIR)?.join(uuid::Uuid::new_v4().simple().to_string());
+    std::fs::create_dir_all(&out_dir).map_err(|e| format!("Failed to create {}: {}", out_dir.display(), e))?;
+    let copy = out_dir.join("image.png");
+    std::fs::write(&copy, &png).map_err(|e| format!("Failed to save {}: {}", copy.display(), e))?;
+    let text = crate::commands::ocr_image(app, general_purpose::STANDARD.encode(&png), operation).await?;
+
+    let parts = [HistoryPart { part: 1, text, image_path: Some(copy.to_string_lossy().into_owned()) }];
+    let title = path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+    let history = app.state::<HistoryState>();
+    let id = history.insert("image", &title, Some(&path.to_string_lossy()), &parts)?;
+    tracing::info!("🖼️ Imported {} ({} characters of text)", path.display(), parts[0].text.len());
+    history.get(id)?.ok_or_else(|| "Imported image disappeared".to_string())
+}
+
+/// Import a PDF or DOCX file into the history and return it with its text (one part per PDF
+/// page). Find it again with `search_history`.
+#[tauri::command]
+pub async fn import_document(app: AppHandle, path: String, request_id: Option<String>) -> Result<HistoryItem, String> {
+    let path = std::path::absolute(&path).map_err(|e| format!("Invalid path {}: {}", path, e))?;
+    let operation = app.state::<CancellationState>().register(request_id.as_deref());
+    import(&app, &path, &operation).await
+}
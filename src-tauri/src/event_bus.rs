@@ -0,0 +1,175 @@
+//! Every backend event goes out through `emit`, which numbers it and keeps it in a small ring
+//! buffer. When the webview reloads (dev hot reload, or a renderer crash) the events emitted
+//! while nothing was listening would otherwise be lost; the frontend remembers the last `seq`
+//! it saw and fetches what it missed with `get_missed_events`. Object payloads carry their
+//! number in a `seq` field. Large payloads (captures) are kept in the capture buffer, under its
+//! memory cap. Which events there are, and their payloads, is declared in `events.rs`.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::capture_buffer::{Buffered, CaptureBufferState};
+use crate::events::Event;
+
+/// Events kept for replay
+const CAPACITY: usize = 500;
+/// Payload bytes kept for replay; the oldest events go first once it is exceeded
+const MAX_BUFFER_BYTES: usize = 16 * 1024 * 1024;
+/// Larger payloads, e.g. full-screen captures, are replayed without their payload
+const MAX_PAYLOAD_BYTES: usize = 1024 * 1024;
+/// Payloads larger than this are kept in the capture buffer rather than in the event list
+const INLINE_PAYLOAD_BYTES: usize = 64 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct BufferedEvent {
+    pub seq: u64,
+    pub event: String,
+    /// None when it was too large to keep
+    pub payload: Option<Value>,
+    pub timestamp: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct MissedEvents {
+    /// Events after the requested `seq`, oldest first
+    pub events: Vec<BufferedEvent>,
+    /// Number of the latest event emitted, to continue from
+    pub latest_seq: u64,
+    /// Whether some missed events had already left the buffer
+    pub truncated: bool,
+}
+
+#[derive(Clone)]
+enum KeptPayload {
+    Inline(Value),
+    /// JSON in the capture buffer
+    Buffered(Arc<Buffered>),
+}
+
+struct KeptEvent {
+    seq: u64,
+    event: String,
+    payload: Option<KeptPayload>,
+    timestamp: i64,
+    size: usize,
+}
+
+#[derive(Default)]
+struct Buffer {
+    next_seq: u64,
+    events: VecDeque<KeptEvent>,
+    bytes: usize,
+}
+
+#[derive(Clone)]
+pub struct EventBusState {
+    buffer: Arc<Mutex<Buffer>>,
+    captures: CaptureBufferState,
+}
+
+impl EventBusState {
+    pub fn new(captures: CaptureBufferState) -> Self {
+        Self { buffer: Arc::default(), captures }
+    }
+}
+
+/// Number `payload`, keep it for replay and emit it as `event` to the webview
+pub(crate) fn emit<P: Serialize>(app: &AppHandle, event: Event<P>, payload: P) -> tauri::Result<()> {
+    send(app, event.name(), payload)
+}
+
+/// Emit an event whose name has an `{id}` in it, e.g. a monitor's own channel
+pub(crate) fn emit_scoped<P: Serialize>(app: &AppHandle, event: Event<P>, id: &str, payload: P) -> tauri::Result<()> {
+    send(app, &event.name().replace("{id}", id), payload)
+}
+
+fn send<S: Serialize>(app: &AppHandle, event: &str, payload: S) -> tauri::Result<()> {
+    let mut payload = serde_json::to_value(payload)?;
+    let state = app.state::<EventBusState>();
+    let mut buffer = state.buffer.lock().unwrap();
+    buffer.next_seq += 1;
+    let seq = buffer.next_seq;
+    if let Value::Object(object) = &mut payload {
+        object.insert("seq".to_string(), seq.into());
+    }
+
+    let json = serde_json::to_vec(&payload).unwrap_or_default();
+    let size = json.len();
+    let kept = match size {
+        0..=INLINE_PAYLOAD_BYTES => Some(KeptPayload::Inline(payload.clone())),
+        _ if size <= MAX_PAYLOAD_BYTES => Some(KeptPayload::Buffered(Arc::new(state.captures.hold(json)))),
+        _ => None,
+    };
+    let kept_size = if kept.is_some() { size } else { 0 };
+    buffer.events.push_back(KeptEvent {
+        seq,
+        event: event.to_string(),
+        payload: kept,
+        timestamp: chrono::Utc::now().timestamp_millis(),
+        size: kept_size,
+    });
+    buffer.bytes += kept_size;
+    let mut evicted = Vec::new();
+    while buffer.events.len() > CAPACITY || buffer.bytes > MAX_BUFFER_BYTES {
+        let Some(oldest) = buffer.events.pop_front() else {
+            break;
+        };
+        buffer.bytes -= oldest.size;
+        evicted.push(oldest);
+    }
+    // Emitting while holding the lock keeps events in `seq` order
+    let result = app.emit(event, payload);
+    // Evicted payloads that were spilled delete their files, outside the lock
+    drop(buffer);
+    drop(evicted);
+    result
+}
+
+/// How full the replay buffer is
+pub(crate) struct QueueDepth {
+    pub events: usize,
+    pub bytes: usize,
+    /// Events emitted since the app started
+    pub emitted: u64,
+}
+
+pub(crate) fn depth(app: &AppHandle) -> QueueDepth {
+    let state = app.state::<EventBusState>();
+    let buffer = state.buffer.lock().unwrap();
+    QueueDepth { events: buffer.events.len(), bytes: buffer.bytes, emitted: buffer.next_seq }
+}
+
+/// Events emitted after `since_seq`, e.g. while the webview was reloading
+#[tauri::command]
+pub async fn get_missed_events(state: State<'_, EventBusState>, since_seq: u64) -> Result<MissedEvents, String> {
+    let (kept, latest_seq, truncated) = {
+        let buffer = state.buffer.lock().unwrap();
+        let kept: Vec<(u64, String, Option<KeptPayload>, i64)> = buffer
+            .events
+            .iter()
+            .filter(|event| event.seq > since_seq)
+            .map(|event| (event.seq, event.event.clone(), event.payload.clone(), event.timestamp))
+            .collect();
+        let oldest = buffer.events.front().map(|event| event.seq).unwrap_or(buffer.next_seq + 1);
+        (kept, buffer.next_seq, since_seq + 1 < oldest && since_seq < buffer.next_seq)
+    };
+    // Buffered payloads may have been spilled to disk
+    let events = tauri::async_runtime::spawn_blocking(move || {
+        kept.into_iter()
+            .map(|(seq, event, payload, timestamp)| {
+                let payload = payload.and_then(|payload| match payload {
+                    KeptPayload::Inline(value) => Some(value),
+                    KeptPayload::Buffered(json) => json.get().ok().and_then(|json| serde_json::from_slice(&json).ok()),
+                });
+                BufferedEvent { seq, event, payload, timestamp }
+            })
+            .collect()
+    })
+    .await
+    .map_err(|e| format!("Failed to read missed events: {}", e))?;
+    Ok(MissedEvents { events, latest_seq, truncated })
+}
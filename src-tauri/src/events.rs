@@ -0,0 +1,114 @@
+//! Every event the backend emits, with the type of its payload. Events are emitted through
+//! these constants (`event_bus::emit(app, events::NARRATION, narration)`), so an event can only
+//! be sent with the payload declared here, and `bindings.rs` generates the frontend's
+//! `EventPayloads` from the same list: the TypeScript field names are the serialized ones.
+
+use schemars::gen::SchemaGenerator;
+use std::marker::PhantomData;
+
+use crate::achievements::Badge;
+use crate::ai::{AiComplete, AiError, AiToken};
+use crate::ai_usage::BudgetExceeded;
+use crate::capture_progress::CaptureProgress;
+use crate::commands::CaptureResult;
+use crate::connectivity::ConnectivityStatus;
+use crate::consent::ConsentStatus;
+use crate::crash_report::AppError;
+use crate::daily_summary::DailySummary;
+use crate::deep_link::DeepLinkOpened;
+use crate::distraction::DistractionDetected;
+use crate::file_drop::ContentIngested;
+use crate::file_watcher::FileChange;
+use crate::flashcards::FlashcardsDue;
+use crate::focus_timer::FocusPhaseChange;
+use crate::homework::HomeworkContext;
+use crate::models::ModelDownloadProgress;
+use crate::monitoring::TextDetected;
+use crate::narrator::Narration;
+use crate::observation::ObservationStatus;
+use crate::ocr_languages::OcrLanguageProgress;
+use crate::process_manager::{JobExit, JobOutput};
+use crate::remote_view::RemoteViewStatus;
+use crate::request_queue::PendingRequest;
+use crate::roles::RoleStatus;
+use crate::screen_recording::RecordingFinished;
+use crate::terminal::{TerminalCommandBlocked, TerminalExit, TerminalOutput};
+use crate::updater::{UpdateInfo, UpdateProgress};
+use crate::watchdog::SubsystemUnhealthy;
+
+/// An event name tied to the type of its payload
+pub struct Event<P> {
+    name: &'static str,
+    payload: PhantomData<fn(P)>,
+}
+
+impl<P> Clone for Event<P> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<P> Copy for Event<P> {}
+
+impl<P> Event<P> {
+    const fn new(name: &'static str) -> Self {
+        Self { name, payload: PhantomData }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+/// Declares each event as a constant, and the catalog of names and payload types for the bindings
+macro_rules! events {
+    ($($(#[$doc:meta])* $constant:ident = $event:literal => $payload:ty),* $(,)?) => {
+        $($(#[$doc])* pub const $constant: Event<$payload> = Event::new($event);)*
+
+        /// Event names with the TypeScript type of their payload, registering payload types with `gen`
+        pub fn catalog(gen: &mut SchemaGenerator) -> Vec<(&'static str, String)> {
+            vec![$(($event, crate::bindings::ts_type(&gen.subschema_for::<$payload>()))),*]
+        }
+    };
+}
+
+events! {
+    APP_READY = "app-ready" => (),
+    SCREEN_CHANGED = "screen-changed" => CaptureResult,
+    /// A monitor's own channel; `{id}` is the monitor id
+    MONITOR_SCREEN_CHANGED = "screen-changed:{id}" => CaptureResult,
+    HOMEWORK_CONTEXT_DETECTED = "homework-context-detected" => HomeworkContext,
+    CONNECTIVITY_CHANGED = "connectivity-changed" => ConnectivityStatus,
+    PENDING_REQUEST_READY = "pending-request-ready" => PendingRequest,
+    JOB_OUTPUT = "job-output" => JobOutput,
+    JOB_EXITED = "job-exited" => JobExit,
+    TERMINAL_OUTPUT = "terminal-output" => TerminalOutput,
+    TERMINAL_EXITED = "terminal-exited" => TerminalExit,
+    TERMINAL_COMMAND_BLOCKED = "terminal-command-blocked" => TerminalCommandBlocked,
+    FILE_CHANGED = "file-changed" => FileChange,
+    FOCUS_PHASE_CHANGED = "focus-phase-changed" => FocusPhaseChange,
+    DISTRACTION_DETECTED = "distraction-detected" => DistractionDetected,
+    CAPTURE_PROGRESS = "capture-progress" => CaptureProgress,
+    APP_ERROR = "app-error" => AppError,
+    SCREEN_RECORDING_FINISHED = "screen-recording-finished" => RecordingFinished,
+    OCR_LANGUAGE_PROGRESS = "ocr-language-progress" => OcrLanguageProgress,
+    UPDATE_AVAILABLE = "update-available" => UpdateInfo,
+    UPDATE_PROGRESS = "update-progress" => UpdateProgress,
+    ROLE_CHANGED = "role-changed" => RoleStatus,
+    REMOTE_VIEW_CHANGED = "remote-view-changed" => RemoteViewStatus,
+    FLASHCARDS_DUE = "flashcards-due" => FlashcardsDue,
+    ACHIEVEMENT_UNLOCKED = "achievement-unlocked" => Badge,
+    DAILY_SUMMARY_READY = "daily-summary-ready" => DailySummary,
+    CONSENT_CHANGED = "consent-changed" => ConsentStatus,
+    OBSERVATION_CHANGED = "observation-changed" => ObservationStatus,
+    CONTENT_INGESTED = "content-ingested" => ContentIngested,
+    TEXT_DETECTED = "text-detected" => TextDetected,
+    BUDGET_EXCEEDED = "budget-exceeded" => BudgetExceeded,
+    AI_TOKEN = "ai-token" => AiToken,
+    AI_COMPLETE = "ai-complete" => AiComplete,
+    AI_ERROR = "ai-error" => AiError,
+    MODEL_DOWNLOAD_PROGRESS = "model-download-progress" => ModelDownloadProgress,
+    NARRATION = "narration" => Narration,
+    SUBSYSTEM_UNHEALTHY = "subsystem-unhealthy" => SubsystemUnhealthy,
+    DEEP_LINK_OPENED = "deep-link-opened" => DeepLinkOpened,
+}
@@ -0,0 +1,269 @@
+//! Exercise and quiz progress: the frontend registers exercises, records each attempt, and
+//! reads per-topic mastery from here, so learning progress lives in the history database
+//! rather than in component state.
+
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use tauri::State;
+
+use crate::history::HistoryState;
+
+/// Attempts counted for a topic's recent accuracy
+const RECENT_ATTEMPTS: usize = 20;
+
+/// Exercise tables, applied by the history database's migrations
+pub(crate) const SCHEMA: &str = r#"
+CREATE TABLE exercises (
+    id INTEGER PRIMARY KEY,
+    key TEXT UNIQUE,
+    topic TEXT NOT NULL,
+    prompt TEXT NOT NULL,
+    answer TEXT,
+    difficulty INTEGER,
+    created_at INTEGER NOT NULL
+);
+CREATE INDEX exercises_topic ON exercises(topic);
+CREATE TABLE exercise_attempts (
+    id INTEGER PRIMARY KEY,
+    exercise_id INTEGER NOT NULL REFERENCES exercises(id) ON DELETE CASCADE,
+    answer TEXT,
+    correct INTEGER NOT NULL,
+    duration_secs INTEGER,
+    attempted_at INTEGER NOT NULL
+);
+CREATE INDEX exercise_attempts_exercise ON exercise_attempts(exercise_id, attempted_at);
+"#;
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct NewExercise {
+    /// The frontend's own id for the exercise; registering the same key again updates it
+    /// instead of adding a duplicate
+    #[serde(default)]
+    pub key: Option<String>,
+    pub topic: String,
+    pub prompt: String,
+    /// Expected answer, if there is one
+    #[serde(default)]
+    pub answer: Option<String>,
+    /// 1 (easiest) to 5
+    #[serde(default)]
+    pub difficulty: Option<u8>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Exercise {
+    pub id: i64,
+    pub key: Option<String>,
+    pub topic: String,
+    pub prompt: String,
+    pub answer: Option<String>,
+    pub difficulty: Option<u8>,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ExerciseAttempt {
+    pub id: i64,
+    pub exercise_id: i64,
+    /// What the student answered
+    pub answer: Option<String>,
+    pub correct: bool,
+    pub duration_secs: Option<u32>,
+    pub attempted_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TopicMastery {
+    pub topic: String,
+    pub exercises: u32,
+    /// Exercises with at least one attempt
+    pub attempted: u32,
+    /// Exercises whose latest attempt was correct
+    pub mastered: u32,
+    pub attempts: u32,
+    pub correct_attempts: u32,
+    /// Share of the topic's last attempts that were correct, 0 to 1
+    pub recent_accuracy: Option<f64>,
+    /// `mastered / exercises`, 0 to 1
+    pub mastery: f64,
+    pub last_attempt_at: Option<i64>,
+}
+
+fn exercise_from_row(row: &Row) -> rusqlite::Result<Exercise> {
+    Ok(Exercise {
+        id: row.get(0)?,
+        key: row.get(1)?,
+        topic: row.get(2)?,
+        prompt: row.get(3)?,
+        answer: row.get(4)?,
+        difficulty: row.get(5)?,
+        created_at: row.get(6)?,
+    })
+}
+
+fn attempt_from_row(row: &Row) -> rusqlite::Result<ExerciseAttempt> {
+    Ok(ExerciseAttempt {
+        id: row.get(0)?,
+        exercise_id: row.get(1)?,
+        answer: row.get(2)?,
+        correct: row.get(3)?,
+        duration_secs: row.get(4)?,
+        attempted_at: row.get(5)?,
+    })
+}
+
+fn load_exercise(db: &Connection, id: i64) -> rusqlite::Result<Option<Exercise>> {
+    db.query_row(
+        "SELECT id, key, topic, prompt, answer, difficulty, created_at FROM exercises WHERE id = ?1",
+        [id],
+        exercise_from_row,
+    )
+    .optional()
+}
+
+/// Add an exercise, or update the one registered with the same `key`
+#[tauri::command]
+pub async fn register_exercise(state: State<'_, HistoryState>, exercise: NewExercise) -> Result<Exercise, String> {
+    let topic = exercise.topic.trim();
+    if topic.is_empty() || exercise.prompt.trim().is_empty() {
+        return Err("An exercise needs a topic and a prompt".to_string());
+    }
+    if exercise.difficulty.is_some_and(|d| !(1..=5).contains(&d)) {
+        return Err("Difficulty must be between 1 and 5".to_string());
+    }
+    let key = exercise.key.as_deref().map(str::trim).filter(|k| !k.is_empty());
+    let registered = state.with_db(|db| {
+        let id: i64 = db.query_row(
+            "INSERT INTO exercises (key, topic, prompt, answer, difficulty, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+             ON CONFLICT(key) DO UPDATE SET topic = excluded.topic, prompt = excluded.prompt,
+                 answer = excluded.answer, difficulty = excluded.difficulty
+             RETURNING id",
+            params![key, topic, exercise.prompt, exercise.answer, exercise.difficulty, chrono::Utc::now().timestamp()],
+            |row| row.get(0),
+        )?;
+        load_exercise(db, id)
+    })?;
+    registered.ok_or_else(|| "Registered exercise disappeared".to_string())
+}
+
+/// Exercises, optionally of one topic, oldest first
+#[tauri::command]
+pub async fn list_exercises(state: State<'_, HistoryState>, topic: Option<String>) -> Result<Vec<Exercise>, String> {
+    state.with_db(|db| {
+        let mut statement = db.prepare(
+            "SELECT id, key, topic, prompt, answer, difficulty, created_at FROM exercises
+             WHERE ?1 IS NULL OR topic = ?1 ORDER BY created_at, id",
+        )?;
+        let exercises = statement.query_map([topic], exercise_from_row)?.collect();
+        exercises
+    })
+}
+
+#[tauri::command]
+pub async fn record_attempt(
+    state: State<'_, HistoryState>,
+    exercise_id: i64,
+    correct: bool,
+    answer: Option<String>,
+    duration_secs: Option<u32>,
+) -> Result<ExerciseAttempt, String> {
+    let attempt = state.with_db(|db| {
+        if load_exercise(db, exercise_id)?.is_none() {
+            return Ok(None);
+        }
+        db.query_row(
+            "INSERT INTO exercise_attempts (exercise_id, answer, correct, duration_secs, attempted_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)
+             RETURNING id, exercise_id, answer, correct, duration_secs, attempted_at",
+            params![exercise_id, answer, correct, duration_secs, chrono::Utc::now().timestamp()],
+            attempt_from_row,
+        )
+        .map(Some)
+    })?;
+    attempt.ok_or_else(|| format!("No exercise with id {}", exercise_id))
+}
+
+/// An exercise's attempts, newest first
+#[tauri::command]
+pub async fn get_attempts(state: State<'_, HistoryState>, exercise_id: i64) -> Result<Vec<ExerciseAttempt>, String> {
+    state.with_db(|db| {
+        let mut statement = db.prepare(
+            "SELECT id, exercise_id, answer, correct, duration_secs, attempted_at FROM exercise_attempts
+             WHERE exercise_id = ?1 ORDER BY attempted_at DESC, id DESC",
+        )?;
+        let attempts = statement.query_map([exercise_id], attempt_from_row)?.collect();
+        attempts
+    })
+}
+
+/// Progress per topic (or just `topic`), sorted by topic
+#[tauri::command]
+pub async fn get_mastery(state: State<'_, HistoryState>, topic: Option<String>) -> Result<Vec<TopicMastery>, String> {
+    let (exercises, attempts) = state.with_db(|db| {
+        let exercises: Vec<(i64, String)> = db
+            .prepare("SELECT id, topic FROM exercises WHERE ?1 IS NULL OR topic = ?1")?
+            .query_map([&topic], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<rusqlite::Result<_>>()?;
+        let attempts: Vec<(i64, bool, i64)> = db
+            .prepare(
+                "SELECT a.exercise_id, a.correct, a.attempted_at FROM exercise_attempts a
+                 JOIN exercises e ON e.id = a.exercise_id
+                 WHERE ?1 IS NULL OR e.topic = ?1 ORDER BY a.attempted_at, a.id",
+            )?
+            .query_map([&topic], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok((exercises, attempts))
+    })?;
+
+    let topic_of: HashMap<i64, &str> = exercises.iter().map(|(id, topic)| (*id, topic.as_str())).collect();
+    let mut topics: BTreeMap<&str, TopicMastery> = BTreeMap::new();
+    for (_, topic) in &exercises {
+        topics
+            .entry(topic)
+            .or_insert_with(|| TopicMastery {
+                topic: topic.clone(),
+                exercises: 0,
+                attempted: 0,
+                mastered: 0,
+                attempts: 0,
+                correct_attempts: 0,
+                recent_accuracy: None,
+                mastery: 0.0,
+                last_attempt_at: None,
+            })
+            .exercises += 1;
+    }
+
+    // Attempts are in time order, so the last one seen per exercise is its latest
+    let mut latest: HashMap<i64, bool> = HashMap::new();
+    let mut recent: HashMap<&str, Vec<bool>> = HashMap::new();
+    for (exercise_id, correct, attempted_at) in &attempts {
+        let Some(topic) = topic_of.get(exercise_id) else {
+            continue;
+        };
+        let Some(mastery) = topics.get_mut(topic) else {
+            continue;
+        };
+        mastery.attempts += 1;
+        mastery.correct_attempts += u32::from(*correct);
+        mastery.last_attempt_at = Some(*attempted_at);
+        latest.insert(*exercise_id, *correct);
+        recent.entry(topic).or_default().push(*correct);
+    }
+    for (exercise_id, correct) in &latest {
+        if let Some(mastery) = topic_of.get(exercise_id).and_then(|topic| topics.get_mut(topic)) {
+            mastery.attempted += 1;
+            mastery.mastered += u32::from(*correct);
+        }
+    }
+    for (topic, mastery) in topics.iter_mut() {
+        mastery.mastery = mastery.mastered as f64 / mastery.exercises.max(1) as f64;
+        if let Some(results) = recent.get(topic) {
+            let last = &results[results.len().saturating_sub(RECENT_ATTEMPTS)..];
+            mastery.recent_accuracy = Some(last.iter().filter(|c| **c).count() as f64 / last.len() as f64);
+        }
+    }
+    Ok(topics.into_values().collect())
+}
@@ -0,0 +1,74 @@
+//! Files dropped onto the app window. Images are read with OCR, documents go through the
+//! document import and audio recordings are transcribed; either way the result lands in the
+//! history and a `content-ingested` event reports it, one per file.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, DragDropEvent, Manager, Window, WindowEvent};
+
+use crate::cancellation::CancellationState;
+use crate::history::HistoryItem;
+
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "bmp", "gif", "webp", "tif", "tiff"];
+const DOCUMENT_EXTENSIONS: &[&str] = &["pdf", "docx"];
+
+/// Payload of the `content-ingested` event
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ContentIngested {
+    pub path: String,
+    /// "image", "document", "audio" or "unsupported"
+    pub kind: String,
+    /// The history item holding the extracted text; None when ingestion failed
+    pub item: Option<HistoryItem>,
+    pub error: Option<String>,
+}
+
+fn kind_of(path: &Path) -> &'static str {
+    let extension = path.extension().map(|e| e.to_string_lossy().to_lowercase()).unwrap_or_default();
+    if IMAGE_EXTENSIONS.contains(&extension.as_str()) {
+        "image"
+    } else if DOCUMENT_EXTENSIONS.contains(&extension.as_str()) {
+        "document"
+    } else if crate::transcription::AUDIO_EXTENSIONS.contains(&extension.as_str()) {
+        "audio"
+    } else {
+        "unsupported"
+    }
+}
+
+async fn ingest(app: &AppHandle, path: &Path) -> ContentIngested {
+    let kind = kind_of(path);
+    let operation = app.state::<CancellationState>().register(None);
+    let result = match kind {
+        "image" => crate::documents::import_image(app, path, &operation).await,
+        "document" => crate::documents::import(app, path, &operation).await,
+        "audio" => crate::transcription::import(app, path, &Default::default(), &operation).await,
+        _ => Err("Only images, PDF and DOCX files and audio recordings can be dropped here".to_string()),
+    };
+    if let Err(e) = &result {
+        tracing::warn!("⚠️ Failed to ingest dropped file {}: {}", path.display(), e);
+    }
+    let (item, error) = match result {
+        Ok(item) => (Some(item), None),
+        Err(e) => (None, Some(e)),
+    };
+    ContentIngested { path: path.to_string_lossy().into_owned(), kind: kind.to_string(), item, error }
+}
+
+fn ingest_all(app: AppHandle, paths: Vec<PathBuf>) {
+    tauri::async_runtime::spawn(async move {
+        for path in paths.into_iter().filter(|path| !path.is_dir()) {
+            let ingested = ingest(&app, &path).await;
+            let _ = crate::event_bus::emit(&app, crate::events::CONTENT_INGESTED, ingested);
+        }
+    });
+}
+
+/// Window event hook: ingest whatever is dropped onto a window
+pub fn handle(window: &Window, event: &WindowEvent) {
+    if let WindowEvent::DragDrop(DragDropEvent::Drop { paths, .. }) = event {
+        tracing::info!("📥 {} file(s) dropped", paths.len());
+        ingest_all(window.app_handle().clone(), paths.clone());
+    }
+}
@@ -0,0 +1,224 @@
+use notify::event::ModifyKind;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, State};
+
+use crate::project_files::{is_ignored_path, looks_binary, ProjectRootsState};
+
+/// Editors often write a file several times per save; collapse events for the same path within this window
+const DEBOUNCE_WINDOW: Duration = Duration::from_millis(500);
+
+/// Only files up to this size are snapshotted for diff summaries
+const MAX_SNAPSHOT_BYTES: u64 = 256 * 1024;
+const MAX_SNAPSHOTS: usize = 200;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FileChangeKind {
+    Created,
+    Modified,
+    Removed,
+    Renamed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DiffSummary {
+    pub lines_added: usize,
+    pub lines_removed: usize,
+}
+
+/// Payload of the `file-changed` event
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FileChange {
+    pub path: String,
+    pub kind: FileChangeKind,
+    pub timestamp: i64,
+    /// Line counts compared to the previous version, when it was known
+    pub diff_summary: Option<DiffSummary>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FileWatcherStatus {
+    pub running: bool,
+    pub watched_roots: Vec<String>,
+}
+
+#[derive(Clone, Default)]
+pub struct FileWatcherState {
+    watcher: Arc<Mutex<Option<RecommendedWatcher>>>,
+    watched: Arc<Mutex<Vec<PathBuf>>>,
+    /// Last known contents of recently changed text files, used to summarize the next change
+    snapshots: Arc<Mutex<HashMap<PathBuf, String>>>,
+    last_emitted: Arc<Mutex<HashMap<PathBuf, (FileChangeKind, Instant)>>>,
+}
+
+fn change_kind(kind: &EventKind) -> Option<FileChangeKind> {
+    match kind {
+        EventKind::Create(_) => Some(FileChangeKind::Created),
+        EventKind::Modify(ModifyKind::Name(_)) => Some(FileChangeKind::Renamed),
+        EventKind::Modify(ModifyKind::Metadata(_)) => None,
+        EventKind::Modify(_) => Some(FileChangeKind::Modified),
+        EventKind::Remove(_) => Some(FileChangeKind::Removed),
+        _ => None,
+    }
+}
+
+fn read_text(path: &Path) -> Option<String> {
+    let metadata = fs::metadata(path).ok()?;
+    if !metadata.is_file() || metadata.len() > MAX_SNAPSHOT_BYTES {
+        return None;
+    }
+    let bytes = fs::read(path).ok()?;
+    if looks_binary(&bytes) {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&bytes).to_string())
+}
+
+/// Update the snapshot for `path` and summarize how it changed since the previous one
+fn summarize_change(state: &FileWatcherState, path: &Path, kind: FileChangeKind) -> Option<DiffSummary> {
+    let mut snapshots = state.snapshots.lock().unwrap();
+    if kind == FileChangeKind::Removed {
+        snapshots.remove(path);
+        return None;
+    }
+
+    let current = read_text(path)?;
+    let previous = snapshots.get(path).cloned();
+    if snapshots.len() >= MAX_SNAPSHOTS && previous.is_none() {
+        snapshots.clear();
+    }
+    snapshots.insert(path.to_path_buf(), current.clone());
+
+    let previous = previous?;
+    let diff = similar::TextDiff::from_lines(&previous, &current);
+    let mut summary = DiffSummary { lines_added: 0, lines_removed: 0 };
+    for change in diff.iter_all_changes() {
+        match change.tag() {
+            similar::ChangeTag::Insert => summary.lines_added += 1,
+            similar::ChangeTag::Delete => summary.lines_removed += 1,
+            similar::ChangeTag::Equal => {}
+        }
+    }
+    Some(summary)
+}
+
+fn handle_event(app: &AppHandle, event: Event) {
+    let Some(kind) = change_kind(&event.kind) else { return };
+    let state = app.state::<FileWatcherState>();
+
+    for path in event.paths {
+        if is_ignored_path(&path) || path.is_dir() {
+            continue;
+        }
+
+        {
+            let mut last_emitted = state.last_emitted.lock().unwrap();
+            let now = Instant::now();
+            if let Some((last_kind, at)) = last_emitted.get(&path) {
+                if *last_kind == kind && now.duration_since(*at) < DEBOUNCE_WINDOW {
+                    continue;
+                }
+            }
+            last_emitted.retain(|_, (_, at)| now.duration_since(*at) < DEBOUNCE_WINDOW);
+            last_emitted.insert(path.clone(), (kind, now));
+        }
+
+        let change = FileChange {
+            path: path.to_string_lossy().to_string(),
+            kind,
+            timestamp: chrono::Utc::now().timestamp(),
+            diff_summary: summarize_change(&state, &path, kind),
+        };
+        let _ = crate::event_bus::emit(app, crate::events::FILE_CHANGED, change);
+    }
+}
+
+/// Bring the watched folders in line with the approved project roots. Does nothing while the watcher is stopped.
+pub fn refresh(app: &AppHandle) {
+    let state = app.state::<FileWatcherState>();
+    let mut watcher = state.watcher.lock().unwrap();
+    let Some(watcher) = watcher.as_mut() else { return };
+
+    let roots = app.state::<ProjectRootsState>().roots.lock().unwrap().clone();
+    let mut watched = state.watched.lock().unwrap();
+
+    for root in watched.iter().filter(|root| !roots.contains(root)) {
+        if let Err(e) = watcher.unwatch(root) {
+            tracing::warn!("⚠️ Failed to unwatch {}: {}", root.display(), e);
+        }
+    }
+    watched.retain(|root| roots.contains(root));
+
+    for root in roots {
+        if watched.contains(&root) {
+            continue;
+        }
+        match watcher.watch(&root, RecursiveMode::Recursive) {
+            Ok(()) => {
+                tracing::info!("👀 Watching {}", root.display());
+                watched.push(root);
+            }
+            Err(e) => tracing::warn!("⚠️ Failed to watch {}: {}", root.display(), e),
+        }
+    }
+}
+
+fn status(state: &FileWatcherState) -> FileWatcherStatus {
+    FileWatcherStatus {
+        running: state.watcher.lock().unwrap().is_some(),
+        watched_roots: state
+            .watched
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|root| root.to_string_lossy().to_string())
+            .collect(),
+    }
+}
+
+/// Start watching every approved project root for saved files
+#[tauri::command]
+pub async fn start_file_watcher(
+    app: AppHandle,
+    state: State<'_, FileWatcherState>,
+) -> Result<FileWatcherStatus, String> {
+    {
+        let mut watcher = state.watcher.lock().unwrap();
+        if watcher.is_none() {
+            let handle = app.clone();
+            let created = notify::recommended_watcher(move |result: notify::Result<Event>| match result {
+                Ok(event) => handle_event(&handle, event),
+                Err(e) => tracing::warn!("⚠️ Watch error: {}", e),
+            })
+            .map_err(|e| format!("Failed to start file watcher: {}", e))?;
+            *watcher = Some(created);
+        }
+    }
+    refresh(&app);
+    Ok(status(&state))
+}
+
+#[tauri::command]
+pub async fn stop_file_watcher(
+    state: State<'_, FileWatcherState>,
+) -> Result<(), String> {
+    // Dropping the watcher unregisters every watch
+    *state.watcher.lock().unwrap() = None;
+    state.watched.lock().unwrap().clear();
+    state.snapshots.lock().unwrap().clear();
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_file_watcher_status(
+    state: State<'_, FileWatcherState>,
+) -> Result<FileWatcherStatus, String> {
+    Ok(status(&state))
+}
@@ -0,0 +1,236 @@
+//! Flashcards with SM-2 spaced repetition, stored in the history database. Each grade moves a
+//! card's next review further out (or back to tomorrow after a miss); `flashcards-due` is
+//! emitted when cards come due.
+
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+
+use crate::history::HistoryState;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+const DEFAULT_DECK: &str = "default";
+const DEFAULT_DUE_LIMIT: u32 = 20;
+const MAX_DUE_LIMIT: u32 = 500;
+const INITIAL_EASE: f64 = 2.5;
+const MIN_EASE: f64 = 1.3;
+const DAY_SECS: i64 = 24 * 60 * 60;
+
+/// Flashcard tables, applied by the history database's migrations
+pub(crate) const SCHEMA: &str = r#"
+CREATE TABLE flashcards (
+    id INTEGER PRIMARY KEY,
+    deck TEXT NOT NULL,
+    front TEXT NOT NULL,
+    back TEXT NOT NULL,
+    note_id INTEGER REFERENCES notes(id) ON DELETE SET NULL,
+    ease REAL NOT NULL,
+    interval_days INTEGER NOT NULL,
+    repetitions INTEGER NOT NULL,
+    lapses INTEGER NOT NULL,
+    due_at INTEGER NOT NULL,
+    last_reviewed_at INTEGER,
+    created_at INTEGER NOT NULL
+);
+CREATE INDEX flashcards_due ON flashcards(due_at);
+CREATE TABLE flashcard_reviews (
+    id INTEGER PRIMARY KEY,
+    card_id INTEGER NOT NULL REFERENCES flashcards(id) ON DELETE CASCADE,
+    grade INTEGER NOT NULL,
+    reviewed_at INTEGER NOT NULL,
+    interval_days INTEGER NOT NULL
+);
+"#;
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Flashcard {
+    pub id: i64,
+    pub deck: String,
+    pub front: String,
+    pub back: String,
+    /// The note the card was made from
+    pub note_id: Option<i64>,
+    /// SM-2 ease factor; higher means intervals grow faster
+    pub ease: f64,
+    pub interval_days: u32,
+    /// Successful reviews in a row
+    pub repetitions: u32,
+    /// Times the card was forgotten after being learned
+    pub lapses: u32,
+    pub due_at: i64,
+    pub last_reviewed_at: Option<i64>,
+    pub created_at: i64,
+}
+
+/// Payload of the `flashcards-due` event
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FlashcardsDue {
+    pub due_count: u32,
+    /// Decks with due cards
+    pub decks: Vec<String>,
+}
+
+const CARD_COLUMNS: &str = "id, deck, front, back, note_id, ease, interval_days, repetitions, lapses, due_at, last_reviewed_at, created_at";
+
+fn card_from_row(row: &Row) -> rusqlite::Result<Flashcard> {
+    Ok(Flashcard {
+        id: row.get(0)?,
+        deck: row.get(1)?,
+        front: row.get(2)?,
+        back: row.get(3)?,
+        note_id: row.get(4)?,
+        ease: row.get(5)?,
+        interval_days: row.get(6)?,
+        repetitions: row.get(7)?,
+        lapses: row.get(8)?,
+        due_at: row.get(9)?,
+        last_reviewed_at: row.get(10)?,
+        created_at: row.get(11)?,
+    })
+}
+
+fn load_card(db: &Connection, id: i64) -> rusqlite::Result<Option<Flashcard>> {
+    db.query_row(&format!("SELECT {} FROM flashcards WHERE id = ?1", CARD_COLUMNS), [id], card_from_row)
+        .optional()
+}
+
+/// Apply an SM-2 review: grades 3-5 are recalled (5 = effortless), 0-2 forgotten
+fn schedule(card: &mut Flashcard, grade: u8, now: i64) {
+    let grade = grade.min(5);
+    if grade >= 3 {
+        card.interval_days = match card.repetitions {
+            0 => 1,
+            1 => 6,
+            _ => (card.interval_days as f64 * card.ease).round() as u32,
+        };
+        card.repetitions += 1;
+    } else {
+        if card.repetitions > 0 {
+            card.lapses += 1;
+        }
+        card.repetitions = 0;
+        card.interval_days = 1;
+    }
+    let miss = (5 - grade) as f64;
+    card.ease = (card.ease + 0.1 - miss * (0.08 + miss * 0.02)).max(MIN_EASE);
+    card.due_at = now + card.interval_days as i64 * DAY_SECS;
+    card.last_reviewed_at = Some(now);
+}
+
+fn due_summary(db: &Connection, now: i64) -> rusqlite::Result<FlashcardsDue> {
+    let mut statement = db.prepare("SELECT deck, COUNT(*) FROM flashcards WHERE due_at <= ?1 GROUP BY deck ORDER BY deck")?;
+    let decks: Vec<(String, u32)> =
+        statement.query_map([now], |row| Ok((row.get(0)?, row.get(1)?)))?.collect::<rusqlite::Result<_>>()?;
+    Ok(FlashcardsDue {
+        due_count: decks.iter().map(|(_, count)| count).sum(),
+        decks: decks.into_iter().map(|(deck, _)| deck).collect(),
+    })
+}
+
+/// Check for due cards every minute and emit `flashcards-due` whenever more are due than at
+/// the last announcement
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let history = app.state::<HistoryState>().inner().clone();
+        let mut announced = 0;
+        loop {
+            match history.with_db(|db| due_summary(db, chrono::Utc::now().timestamp())) {
+                Ok(due) if due.due_count > announced => {
+                    tracing::info!("🃏 {} flashcards due", due.due_count);
+                    announced = due.due_count;
+                    let _ = crate::event_bus::emit(&app, crate::events::FLASHCARDS_DUE, due);
+                }
+                Ok(due) => announced = due.due_count,
+                Err(e) => tracing::debug!("Flashcard check skipped: {}", e),
+            }
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
+
+/// Add a card, due right away
+#[tauri::command]
+pub async fn add_card(
+    state: State<'_, HistoryState>,
+    front: String,
+    back: String,
+    deck: Option<String>,
+    note_id: Option<i64>,
+) -> Result<Flashcard, String> {
+    let (front, back) = (front.trim(), back.trim());
+    if front.is_empty() || back.is_empty() {
+        return Err("A card needs both a front and a back".to_string());
+    }
+    let deck = deck.as_deref().map(str::trim).filter(|d| !d.is_empty()).unwrap_or(DEFAULT_DECK);
+    let now = chrono::Utc::now().timestamp();
+    let card = state.with_db(|db| {
+        db.execute(
+            "INSERT INTO flashcards (deck, front, back, note_id, ease, interval_days, repetitions, lapses, due_at, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, 0, 0, 0, ?6, ?6)",
+            params![deck, front, back, note_id, INITIAL_EASE, now],
+        )?;
+        load_card(db, db.last_insert_rowid())
+    })?;
+    card.ok_or_else(|| "Added card disappeared".to_string())
+}
+
+/// Cards due for review, most overdue first
+#[tauri::command]
+pub async fn get_due_cards(
+    state: State<'_, HistoryState>,
+    deck: Option<String>,
+    limit: Option<u32>,
+) -> Result<Vec<Flashcard>, String> {
+    let limit = limit.unwrap_or(DEFAULT_DUE_LIMIT).clamp(1, MAX_DUE_LIMIT);
+    state.with_db(|db| {
+        let mut statement = db.prepare(&format!(
+            "SELECT {} FROM flashcards WHERE due_at <= ?1 AND (?2 IS NULL OR deck = ?2) ORDER BY due_at LIMIT ?3",
+            CARD_COLUMNS
+        ))?;
+        let cards = statement
+            .query_map(params![chrono::Utc::now().timestamp(), deck, limit], card_from_row)?
+            .collect();
+        cards
+    })
+}
+
+/// Record a review with an SM-2 grade from 0 (blackout) to 5 (perfect recall) and schedule the
+/// next one
+#[tauri::command]
+pub async fn grade_card(state: State<'_, HistoryState>, id: i64, grade: u8) -> Result<Flashcard, String> {
+    if grade > 5 {
+        return Err("Grade must be between 0 and 5".to_string());
+    }
+    let now = chrono::Utc::now().timestamp();
+    let card = state.with_db(|db| {
+        let Some(mut card) = load_card(db, id)? else {
+            return Ok(None);
+        };
+        schedule(&mut card, grade, now);
+        let tx = db.transaction()?;
+        tx.execute(
+            "UPDATE flashcards SET ease = ?2, interval_days = ?3, repetitions = ?4, lapses = ?5, due_at = ?6,
+                    last_reviewed_at = ?7
+             WHERE id = ?1",
+            params![id, card.ease, card.interval_days, card.repetitions, card.lapses, card.due_at, now],
+        )?;
+        tx.execute(
+            "INSERT INTO flashcard_reviews (card_id, grade, reviewed_at, interval_days) VALUES (?1, ?2, ?3, ?4)",
+            params![id, grade, now, card.interval_days],
+        )?;
+        tx.commit()?;
+        Ok(Some(card))
+    })?;
+    card.ok_or_else(|| format!("No flashcard with id {}", id))
+}
+
+#[tauri::command]
+pub async fn delete_card(state: State<'_, HistoryState>, id: i64) -> Result<(), String> {
+    let deleted = state.with_db(|db| db.execute("DELETE FROM flashcards WHERE id = ?1", [id]))?;
+    if deleted == 0 {
+        return Err(format!("No flashcard with id {}", id));
+    }
+    Ok(())
+}
@@ -0,0 +1,296 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::persistence;
+use crate::screen_capture::ScreenCaptureState;
+
+const SESSION_FILE: &str = "focus_session.json";
+const TICK_INTERVAL: Duration = Duration::from_secs(1);
+/// Lengths of sessions started automatically (see `start_if_idle`)
+const DEFAULT_FOCUS_MINUTES: u32 = 25;
+const DEFAULT_BREAK_MINUTES: u32 = 5;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FocusPhase {
+    Focus,
+    Break,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FocusSession {
+    pub id: String,
+    pub focus_minutes: u32,
+    pub break_minutes: u32,
+    pub phase: FocusPhase,
+    pub phase_started_at: i64,
+    /// When the current phase ends; `None` while paused
+    pub phase_ends_at: Option<i64>,
+    /// Seconds left in the current phase when it was paused
+    pub paused_remaining_secs: Option<i64>,
+    pub completed_cycles: u32,
+    /// Stop screen monitoring captures while on a break
+    pub pause_monitoring_during_breaks: bool,
+}
+
+impl FocusSession {
+    pub fn paused(&self) -> bool {
+        self.paused_remaining_secs.is_some()
+    }
+
+    fn phase_secs(&self, phase: FocusPhase) -> i64 {
+        match phase {
+            FocusPhase::Focus => self.focus_minutes as i64 * 60,
+            FocusPhase::Break => self.break_minutes as i64 * 60,
+        }
+    }
+}
+
+/// Payload of the `focus-phase-changed` event
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FocusPhaseChange {
+    /// The session after the change; `None` when it was stopped
+    pub session: Option<FocusSession>,
+    /// "started", "phase_completed", "paused", "resumed" or "stopped"
+    pub reason: String,
+}
+
+#[derive(Clone, Default)]
+pub struct FocusTimerState {
+    pub session: Arc<Mutex<Option<FocusSession>>>,
+}
+
+fn persist(app: &AppHandle, session: &Option<FocusSession>) {
+    let result = persistence::data_file(app, SESSION_FILE).and_then(|path| persistence::save_json(&path, session));
+    if let Err(e) = result {
+        tracing::warn!("⚠️ Failed to persist session: {}", e);
+    }
+}
+
+/// Pause screen monitoring while a break is running, if the session asks for it
+fn apply_monitoring_pause(app: &AppHandle, session: Option<&FocusSession>) {
+    let paused = session
+        .map(|s| s.pause_monitoring_during_breaks && s.phase == FocusPhase::Break && !s.paused())
+        .unwrap_or(false);
+    app.state::<ScreenCaptureState>().paused.store(paused, Ordering::Relaxed);
+}
+
+fn notify(app: &AppHandle, session: &FocusSession) {
+    let (title, body) = match session.phase {
+        FocusPhase::Focus => (
+            "Break's over",
+            format!("Time to focus for {} minutes.", session.focus_minutes),
+        ),
+        FocusPhase::Break => (
+            "Nice work!",
+            format!("Take a {} minute break.", session.break_minutes),
+        ),
+    };
+    if let Err(e) = app.notification().builder().title(title).body(body).show() {
+        tracing::warn!("⚠️ Failed to show notification: {}", e);
+    }
+}
+
+/// Persist the session and announce the change. Called with the session lock held, so saves
+/// and events go out in the order the changes were made.
+fn announce(app: &AppHandle, session: &Option<FocusSession>, reason: &str) {
+    persist(app, session);
+    apply_monitoring_pause(app, session.as_ref());
+    let _ = crate::event_bus::emit(
+        app,
+        crate::events::FOCUS_PHASE_CHANGED,
+        FocusPhaseChange {
+            session: session.clone(),
+            reason: reason.to_string(),
+        },
+    );
+}
+
+/// Replace the session, persist it, and announce the change
+fn publish(app: &AppHandle, state: &FocusTimerState, session: Option<FocusSession>, reason: &str) {
+    let mut current = state.session.lock().unwrap();
+    *current = session;
+    announce(app, &current, reason);
+}
+
+/// Change the running session under one lock, so the timer and the commands can't overwrite
+/// each other's changes. It is persisted and announced when `change` returns true.
+fn update(
+    app: &AppHandle,
+    state: &FocusTimerState,
+    reason: &str,
+    change: impl FnOnce(&mut FocusSession) -> bool,
+) -> Result<FocusSession, String> {
+    let mut current = state.session.lock().unwrap();
+    let session = current.as_mut().ok_or("No focus session is running")?;
+    let changed = change(session);
+    let session = session.clone();
+    if changed {
+        announce(app, &current, reason);
+    }
+    Ok(session)
+}
+
+/// Move to the next phase if the current one has run out
+fn advance(app: &AppHandle, state: &FocusTimerState) {
+    let now = chrono::Utc::now().timestamp();
+    let mut advanced = false;
+    let mut focus_minutes = 0;
+    let result = update(app, state, "phase_completed", |next| {
+        // Catch up on phases that elapsed while the app was closed
+        while let Some(end) = next.phase_ends_at.filter(|end| now >= *end) {
+            advanced = true;
+            if next.phase == FocusPhase::Focus {
+                next.completed_cycles += 1;
+                // Only focus time that passed while the app was running counts as time on task
+                if now - end < TICK_INTERVAL.as_secs() as i64 * 2 {
+                    focus_minutes += next.focus_minutes;
+                }
+                next.phase = FocusPhase::Break;
+            } else {
+                next.phase = FocusPhase::Focus;
+            }
+            next.phase_started_at = end;
+            next.phase_ends_at = Some(end + next.phase_secs(next.phase).max(1));
+        }
+        advanced
+    });
+    let Some(next) = result.ok().filter(|_| advanced) else {
+        return;
+    };
+
+    if focus_minutes > 0 {
+        crate::achievements::log_minutes(app, crate::achievements::MinuteKind::Focus, focus_minutes);
+    }
+    tracing::info!("⏱️ Entering {:?} phase ({} cycles done)", next.phase, next.completed_cycles);
+    notify(app, &next);
+}
+
+/// Restore the session from a previous run and start the timer loop
+pub fn start(app: AppHandle) {
+    let restored: Option<FocusSession> = match persistence::data_file(&app, SESSION_FILE) {
+        Ok(path) => persistence::load_json(&path),
+        Err(e) => {
+            tracing::warn!("⚠️ {}", e);
+            None
+        }
+    };
+    if let Some(session) = &restored {
+        tracing::info!("📥 Restored {:?} phase of focus session {}", session.phase, session.id);
+    }
+    apply_monitoring_pause(&app, restored.as_ref());
+    *app.state::<FocusTimerState>().session.lock().unwrap() = restored;
+
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<FocusTimerState>().inner().clone();
+        loop {
+            advance(&app, &state);
+            tokio::time::sleep(TICK_INTERVAL).await;
+        }
+    });
+}
+
+fn new_session(minutes: u32, break_minutes: u32, pause_monitoring_during_breaks: bool) -> FocusSession {
+    let now = chrono::Utc::now().timestamp();
+    FocusSession {
+        id: uuid::Uuid::new_v4().to_string(),
+        focus_minutes: minutes,
+        break_minutes,
+        phase: FocusPhase::Focus,
+        phase_started_at: now,
+        phase_ends_at: Some(now + minutes as i64 * 60),
+        paused_remaining_secs: None,
+        completed_cycles: 0,
+        pause_monitoring_during_breaks,
+    }
+}
+
+/// Start a session of the default lengths unless one is already running, e.g. when homework is
+/// detected. Returns the new session.
+pub(crate) fn start_if_idle(app: &AppHandle) -> Option<FocusSession> {
+    let state = app.state::<FocusTimerState>();
+    let mut current = state.session.lock().unwrap();
+    if current.is_some() {
+        return None;
+    }
+    let session = new_session(DEFAULT_FOCUS_MINUTES, DEFAULT_BREAK_MINUTES, true);
+    *current = Some(session.clone());
+    tracing::info!("▶️ Started {}/{} minute focus session", DEFAULT_FOCUS_MINUTES, DEFAULT_BREAK_MINUTES);
+    announce(app, &current, "started");
+    Some(session)
+}
+
+/// Start a new focus session, replacing any running one
+#[tauri::command]
+pub async fn start_focus_session(
+    app: AppHandle,
+    state: State<'_, FocusTimerState>,
+    minutes: u32,
+    break_minutes: u32,
+    pause_monitoring_during_breaks: Option<bool>,
+) -> Result<FocusSession, String> {
+    if minutes == 0 || minutes > 240 {
+        return Err("Focus length must be between 1 and 240 minutes".to_string());
+    }
+    if break_minutes > 120 {
+        return Err("Break length must be at most 120 minutes".to_string());
+    }
+
+    let session = new_session(minutes, break_minutes, pause_monitoring_during_breaks.unwrap_or(true));
+    tracing::info!("▶️ Started {}/{} minute focus session", minutes, break_minutes);
+    publish(&app, &state, Some(session.clone()), "started");
+    Ok(session)
+}
+
+#[tauri::command]
+pub async fn pause_focus_session(
+    app: AppHandle,
+    state: State<'_, FocusTimerState>,
+) -> Result<FocusSession, String> {
+    update(&app, &state, "paused", |session| {
+        let Some(end) = session.phase_ends_at.take() else {
+            return false;
+        };
+        session.paused_remaining_secs = Some((end - chrono::Utc::now().timestamp()).max(0));
+        true
+    })
+}
+
+#[tauri::command]
+pub async fn resume_focus_session(
+    app: AppHandle,
+    state: State<'_, FocusTimerState>,
+) -> Result<FocusSession, String> {
+    update(&app, &state, "resumed", |session| {
+        let Some(remaining) = session.paused_remaining_secs.take() else {
+            return false;
+        };
+        session.phase_ends_at = Some(chrono::Utc::now().timestamp() + remaining);
+        true
+    })
+}
+
+#[tauri::command]
+pub async fn stop_focus_session(
+    app: AppHandle,
+    state: State<'_, FocusTimerState>,
+) -> Result<bool, String> {
+    let mut current = state.session.lock().unwrap();
+    if current.take().is_none() {
+        return Ok(false);
+    }
+    announce(&app, &current, "stopped");
+    Ok(true)
+}
+
+#[tauri::command]
+pub async fn get_focus_session(
+    state: State<'_, FocusTimerState>,
+) -> Result<Option<FocusSession>, String> {
+    Ok(state.session.lock().unwrap().clone())
+}
@@ -0,0 +1,233 @@
+//! Delta encoding for `screen-changed` events. Between monitoring captures most of the screen
+//! is unchanged, so instead of a full PNG each event can carry just the changed tiles, with a
+//! full keyframe every N frames. `get_full_frame` lets the frontend resync at any time.
+
+use base64::{engine::general_purpose, Engine as _};
+use image::{ImageFormat, RgbaImage};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::io::Cursor;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::State;
+
+use crate::commands::CaptureResult;
+
+/// Frames are compared in square tiles of this many pixels
+const TILE_SIZE: u32 = 64;
+const DEFAULT_KEYFRAME_INTERVAL: u32 = 10;
+/// Above this share of changed tiles a keyframe is cheaper than the tiles
+const MAX_CHANGED_RATIO: f64 = 0.5;
+
+/// A changed rectangle of the frame
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DeltaTile {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+    /// PNG of just this rectangle
+    pub image_base64: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FrameDelta {
+    /// Increases by one per emitted frame; a gap means the frontend should call `get_full_frame`
+    pub sequence: u64,
+    /// True when `image_base64` holds the whole frame and `tiles` is empty
+    pub keyframe: bool,
+    pub width: u32,
+    pub height: u32,
+    pub tiles: Vec<DeltaTile>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FrameDeltaSettings {
+    pub enabled: bool,
+    /// Send a full frame every this many frames
+    pub keyframe_interval: u32,
+}
+
+#[derive(Clone)]
+pub struct FrameDeltaState {
+    enabled: Arc<AtomicBool>,
+    keyframe_interval: Arc<AtomicU32>,
+    /// Latest full capture of each monitor, for `get_full_frame`
+    last_full: Arc<Mutex<HashMap<String, CaptureResult>>>,
+}
+
+impl Default for FrameDeltaState {
+    fn default() -> Self {
+        Self {
+            enabled: Arc::new(AtomicBool::new(false)),
+            keyframe_interval: Arc::new(AtomicU32::new(DEFAULT_KEYFRAME_INTERVAL)),
+            last_full: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+}
+
+/// Per-stream encoder state, owned by the monitoring loop
+#[derive(Default)]
+pub struct DeltaEncoder {
+    previous: Option<RgbaImage>,
+    sequence: u64,
+    since_keyframe: u32,
+}
+
+fn encode_png(image: &RgbaImage) -> Result<String, String> {
+    let mut png = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .map_err(|e| format!("Failed to encode tile: {}", e))?;
+    Ok(general_purpose::STANDARD.encode(png))
+}
+
+fn tile_changed(previous: &RgbaImage, current: &RgbaImage, x: u32, y: u32, width: u32, height: u32) -> bool {
+    (y..y + height).any(|row| {
+        let start = ((row * current.width() + x) * 4) as usize;
+        let end = start + (width * 4) as usize;
+        previous.as_raw()[start..end] != current.as_raw()[start..end]
+    })
+}
+
+/// x, y, width, height
+type Rect = (u32, u32, u32, u32);
+
+/// Changed tiles, with horizontally adjacent ones in a row merged into one rectangle, plus the
+/// changed and total tile counts
+fn changed_rects(previous: &RgbaImage, current: &RgbaImage) -> (Vec<Rect>, usize, usize) {
+    let (width, height) = current.dimensions();
+    let mut rects: Vec<Rect> = Vec::new();
+    let (mut changed, mut total) = (0, 0);
+    for y in (0..height).step_by(TILE_SIZE as usize) {
+        let tile_h = TILE_SIZE.min(height - y);
+        let mut run: Option<(u32, u32)> = None;
+        for x in (0..width).step_by(TILE_SIZE as usize) {
+            let tile_w = TILE_SIZE.min(width - x);
+            total += 1;
+            if tile_changed(previous, current, x, y, tile_w, tile_h) {
+                changed += 1;
+                run = Some(run.map_or((x, tile_w), |(start, w)| (start, w + tile_w)));
+            } else if let Some((start, w)) = run.take() {
+                rects.push((start, y, w, tile_h));
+            }
+        }
+        if let Some((start, w)) = run {
+            rects.push((start, y, w, tile_h));
+        }
+    }
+    (rects, changed, total)
+}
+
+impl DeltaEncoder {
+    /// Replace the full image in `result` with the changed tiles when delta mode is on. The
+    /// full capture is always kept for `get_full_frame`.
+    pub fn apply(&mut self, state: &FrameDeltaState, monitor_id: &str, result: &mut CaptureResult) -> Result<(), String> {
+        if !state.enabled.load(Ordering::Relaxed) {
+            state.last_full.lock().unwrap().insert(monitor_id.to_string(), result.clone());
+            // Start with a keyframe when delta mode is switched back on
+            self.previous = None;
+            return Ok(());
+        }
+
+        let bytes = general_purpose::STANDARD
+            .decode(&result.image_base64)
+            .map_err(|e| format!("Failed to decode capture: {}", e))?;
+        let current = image::load_from_memory(&bytes)
+            .map_err(|e| format!("Failed to load capture: {}", e))?
+            .to_rgba8();
+        let (width, height) = current.dimensions();
+        self.sequence += 1;
+
+        let interval = state.keyframe_interval.load(Ordering::Relaxed).max(1);
+        let tiles = match self.previous.as_ref() {
+            Some(previous) if previous.dimensions() == current.dimensions() && self.since_keyframe + 1 < interval => {
+                let (rects, changed, total) = changed_rects(previous, &current);
+                if (changed as f64) <= total as f64 * MAX_CHANGED_RATIO {
+                    let tiles = rects
+                        .into_iter()
+                        .map(|(x, y, w, h)| {
+                            let tile = image::imageops::crop_imm(&current, x, y, w, h).to_image();
+                            Ok(DeltaTile { x, y, width: w, height: h, image_base64: encode_png(&tile)? })
+                        })
+                        .collect::<Result<Vec<_>, String>>()?;
+                    Some(tiles)
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        };
+
+        // What `get_full_frame` returns: this frame as a keyframe
+        let mut full = result.clone();
+        full.delta = Some(FrameDelta { sequence: self.sequence, keyframe: true, width, height, tiles: Vec::new() });
+        state.last_full.lock().unwrap().insert(monitor_id.to_string(), full);
+
+        let keyframe = tiles.is_none();
+        if keyframe {
+            self.since_keyframe = 0;
+        } else {
+            self.since_keyframe += 1;
+            result.image_base64 = String::new();
+        }
+        result.delta = Some(FrameDelta {
+            sequence: self.sequence,
+            keyframe,
+            width,
+            height,
+            tiles: tiles.unwrap_or_default(),
+        });
+        self.previous = Some(current);
+        Ok(())
+    }
+}
+
+impl FrameDeltaState {
+    /// Drop the stored frame of a stopped monitor
+    pub fn forget(&self, monitor_id: &str) {
+        self.last_full.lock().unwrap().remove(monitor_id);
+    }
+
+    /// The most recent full frame of any monitor
+    pub fn latest(&self) -> Option<CaptureResult> {
+        self.last_full.lock().unwrap().values().max_by_key(|f| f.timestamp).cloned()
+    }
+}
+
+#[tauri::command]
+pub async fn get_frame_delta_settings(state: State<'_, FrameDeltaState>) -> Result<FrameDeltaSettings, String> {
+    Ok(FrameDeltaSettings {
+        enabled: state.enabled.load(Ordering::Relaxed),
+        keyframe_interval: state.keyframe_interval.load(Ordering::Relaxed),
+    })
+}
+
+/// Switch `screen-changed` events between full frames and changed tiles
+#[tauri::command]
+pub async fn set_frame_delta_settings(
+    state: State<'_, FrameDeltaState>,
+    settings: FrameDeltaSettings,
+) -> Result<(), String> {
+    if settings.keyframe_interval == 0 {
+        return Err("keyframe_interval must be at least 1".to_string());
+    }
+    state.enabled.store(settings.enabled, Ordering::Relaxed);
+    state.keyframe_interval.store(settings.keyframe_interval, Ordering::Relaxed);
+    Ok(())
+}
+
+/// The latest full frame of a monitor (or of whichever monitor captured last), for resyncing
+/// after a missed delta
+#[tauri::command]
+pub async fn get_full_frame(
+    state: State<'_, FrameDeltaState>,
+    monitor_id: Option<String>,
+) -> Result<CaptureResult, String> {
+    let frame = match monitor_id {
+        Some(id) => state.last_full.lock().unwrap().get(&id).cloned(),
+        None => state.latest(),
+    };
+    frame.ok_or_else(|| "No frame captured yet".to_string())
+}
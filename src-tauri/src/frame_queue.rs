@@ -0,0 +1,66 @@
+//! A small bounded queue between a capture loop and whatever handles its frames. When the
+//! handler falls behind, the oldest waiting frame is dropped to make room: a stale frame is
+//! worth less than the current one, and an unbounded queue of screenshots only grows.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use tokio::sync::Notify;
+
+struct Inner<T> {
+    items: VecDeque<T>,
+    closed: bool,
+}
+
+/// Single consumer: `pop` wakes one waiter
+pub(crate) struct FrameQueue<T> {
+    inner: Mutex<Inner<T>>,
+    ready: Notify,
+    capacity: usize,
+}
+
+impl<T> FrameQueue<T> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inner: Mutex::new(Inner { items: VecDeque::with_capacity(capacity), closed: false }),
+            ready: Notify::new(),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Queue a frame, returning how many older ones were dropped to fit it
+    pub fn push(&self, item: T) -> usize {
+        let mut dropped = 0;
+        {
+            let mut inner = self.inner.lock().unwrap();
+            while inner.items.len() >= self.capacity {
+                inner.items.pop_front();
+                dropped += 1;
+            }
+            inner.items.push_back(item);
+        }
+        self.ready.notify_one();
+        dropped
+    }
+
+    /// The oldest frame, waiting for one; None once the queue is closed and empty
+    pub async fn pop(&self) -> Option<T> {
+        loop {
+            {
+                let mut inner = self.inner.lock().unwrap();
+                if let Some(item) = inner.items.pop_front() {
+                    return Some(item);
+                }
+                if inner.closed {
+                    return None;
+                }
+            }
+            self.ready.notified().await;
+        }
+    }
+
+    /// No more frames will come; `pop` returns what is left, then None
+    pub fn close(&self) {
+        self.inner.lock().unwrap().closed = true;
+        self.ready.notify_one();
+    }
+}
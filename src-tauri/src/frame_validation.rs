@@ -0,0 +1,39 @@
+//! Post-capture sanity checks. `PrintWindow` returns an all-black (or all-white) bitmap for
+//! some hardware-accelerated windows, which would otherwise be OCR'd and analyzed as if real.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Luminance standard deviation (0-255 scale) below which a frame counts as blank
+const UNIFORM_STDDEV: f64 = 3.0;
+/// Frames are downscaled to at most this size before measuring
+const SAMPLE_SIZE: u32 = 64;
+
+/// How a window capture was produced
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CaptureMethod {
+    PrintWindow,
+    /// PrintWindow on a minimized window that was restored invisibly for the capture
+    MinimizedRestore,
+    /// Windows.Graphics.Capture, which reads the window's DWM surface
+    GraphicsCapture,
+    /// The window's rectangle cut out of a screen capture; only correct while it's on top
+    ScreenCrop,
+}
+
+/// Whether an encoded image is (nearly) one flat color
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+pub fn is_near_uniform(image_bytes: &[u8]) -> bool {
+    let Ok(image) = image::load_from_memory(image_bytes) else {
+        return false;
+    };
+    let sample = image.thumbnail(SAMPLE_SIZE, SAMPLE_SIZE).to_luma8();
+    let count = sample.len() as f64;
+    if count == 0.0 {
+        return true;
+    }
+    let mean = sample.iter().map(|&v| v as f64).sum::<f64>() / count;
+    let variance = sample.iter().map(|&v| (v as f64 - mean).powi(2)).sum::<f64>() / count;
+    variance.sqrt() < UNIFORM_STDDEV
+}
@@ -0,0 +1,306 @@
+use git2::{BranchType, Delta, DiffOptions, Patch, Repository, RepositoryOpenFlags, Sort, Status, StatusOptions};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::project_files::ProjectRootsState;
+
+/// Patches beyond this many bytes (in total) are omitted from `get_git_diff`
+const MAX_PATCH_BYTES: usize = 200 * 1024;
+const MAX_COMMITS: usize = 100;
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitFileStatus {
+    pub path: String,
+    /// One of "new", "modified", "deleted", "renamed", "typechange" or "conflicted"
+    pub status: String,
+    pub staged: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitStatus {
+    pub branch: Option<String>,
+    pub head_commit: Option<String>,
+    pub upstream: Option<String>,
+    pub ahead: usize,
+    pub behind: usize,
+    pub files: Vec<GitFileStatus>,
+    pub clean: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitDiffFile {
+    pub path: String,
+    pub old_path: Option<String>,
+    pub status: String,
+    pub additions: usize,
+    pub deletions: usize,
+    /// Unified diff text; omitted for binary files or once the size limit is reached
+    pub patch: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitDiff {
+    pub staged: bool,
+    pub files: Vec<GitDiffFile>,
+    pub additions: usize,
+    pub deletions: usize,
+    /// Whether some patches were left out because of the size limit
+    pub truncated: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitCommit {
+    pub id: String,
+    pub short_id: String,
+    pub summary: String,
+    pub message: String,
+    pub author_name: String,
+    pub author_email: String,
+    pub timestamp: i64,
+}
+
+/// Open the repository containing `repo`, which must lie inside an approved project folder.
+/// The search for `.git` stops at that folder, so a repository above it is never opened.
+fn open_repository(roots: &ProjectRootsState, repo: &str) -> Result<Repository, String> {
+    let (path, root) = roots.resolve_in_root(repo)?;
+    // Ceiling directories themselves aren't searched, so the ceiling is the root's parent
+    let ceiling = root.parent().map(|parent| parent.to_path_buf());
+    Repository::open_ext(&path, RepositoryOpenFlags::empty(), ceiling.iter())
+        .map_err(|e| format!("{} is not inside a git repository: {}", repo, e.message()))
+}
+
+/// Open the repository and run `read` on a blocking thread, since libgit2 reads from disk
+async fn with_repository<T: Send + 'static>(
+    roots: &ProjectRootsState,
+    repo: String,
+    read: impl FnOnce(&Repository) -> Result<T, String> + Send + 'static,
+) -> Result<T, String> {
+    let roots = roots.clone();
+    tauri::async_runtime::spawn_blocking(move || read(&open_repository(&roots, &repo)?))
+        .await
+        .map_err(|e| format!("Failed to read the git repository: {}", e))?
+}
+
+fn git_error(context: &str, e: git2::Error) -> String {
+    format!("{}: {}", context, e.message())
+}
+
+fn index_status(status: Status) -> Option<&'static str> {
+    if status.contains(Status::INDEX_NEW) {
+        Some("new")
+    } else if status.contains(Status::INDEX_MODIFIED) {
+        Some("modified")
+    } else if status.contains(Status::INDEX_DELETED) {
+        Some("deleted")
+    } else if status.contains(Status::INDEX_RENAMED) {
+        Some("renamed")
+    } else if status.contains(Status::INDEX_TYPECHANGE) {
+        Some("typechange")
+    } else {
+        None
+    }
+}
+
+fn worktree_status(status: Status) -> Option<&'static str> {
+    if status.contains(Status::WT_NEW) {
+        Some("new")
+    } else if status.contains(Status::WT_MODIFIED) {
+        Some("modified")
+    } else if status.contains(Status::WT_DELETED) {
+        Some("deleted")
+    } else if status.contains(Status::WT_RENAMED) {
+        Some("renamed")
+    } else if status.contains(Status::WT_TYPECHANGE) {
+        Some("typechange")
+    } else {
+        None
+    }
+}
+
+fn delta_status(delta: Delta) -> &'static str {
+    match delta {
+        Delta::Added | Delta::Untracked => "new",
+        Delta::Deleted => "deleted",
+        Delta::Renamed => "renamed",
+        Delta::Copied => "copied",
+        Delta::Typechange => "typechange",
+        Delta::Conflicted => "conflicted",
+        _ => "modified",
+    }
+}
+
+#[tauri::command]
+pub async fn get_git_status(
+    roots: State<'_, ProjectRootsState>,
+    repo: String,
+) -> Result<GitStatus, String> {
+    with_repository(&roots, repo, read_status).await
+}
+
+fn read_status(repository: &Repository) -> Result<GitStatus, String> {
+    let head = repository.head().ok();
+    let branch = head.as_ref().filter(|h| h.is_branch()).and_then(|h| h.shorthand().map(String::from));
+    let head_oid = head.as_ref().and_then(|h| h.target());
+
+    let mut upstream = None;
+    let (mut ahead, mut behind) = (0, 0);
+    if let (Some(name), Some(local)) = (branch.as_deref(), head_oid) {
+        if let Ok(upstream_branch) = repository.find_branch(name, BranchType::Local).and_then(|b| b.upstream()) {
+            upstream = upstream_branch.name().ok().flatten().map(String::from);
+            if let Some(remote) = upstream_branch.get().target() {
+                if let Ok((a, b)) = repository.graph_ahead_behind(local, remote) {
+                    ahead = a;
+                    behind = b;
+                }
+            }
+        }
+    }
+
+    let mut options = StatusOptions::new();
+    options.include_untracked(true).recurse_untracked_dirs(true);
+    let statuses = repository
+        .statuses(Some(&mut options))
+        .map_err(|e| git_error("Failed to read git status", e))?;
+
+    let mut files = Vec::new();
+    for entry in statuses.iter() {
+        let Some(path) = entry.path() else { continue };
+        let status = entry.status();
+        if status.contains(Status::CONFLICTED) {
+            files.push(GitFileStatus { path: path.to_string(), status: "conflicted".to_string(), staged: false });
+            continue;
+        }
+        if let Some(staged) = index_status(status) {
+            files.push(GitFileStatus { path: path.to_string(), status: staged.to_string(), staged: true });
+        }
+        if let Some(unstaged) = worktree_status(status) {
+            files.push(GitFileStatus { path: path.to_string(), status: unstaged.to_string(), staged: false });
+        }
+    }
+
+    Ok(GitStatus {
+        branch,
+        head_commit: head_oid.map(|oid| oid.to_string()),
+        upstream,
+        ahead,
+        behind,
+        clean: files.is_empty(),
+        files,
+    })
+}
+
+/// Diff of the staged changes (index vs HEAD) or the unstaged ones (working tree vs index)
+#[tauri::command]
+pub async fn get_git_diff(
+    roots: State<'_, ProjectRootsState>,
+    repo: String,
+    staged: bool,
+) -> Result<GitDiff, String> {
+    with_repository(&roots, repo, move |repository| read_diff(repository, staged)).await
+}
+
+fn read_diff(repository: &Repository, staged: bool) -> Result<GitDiff, String> {
+    let mut options = DiffOptions::new();
+    let diff = if staged {
+        // No HEAD yet (fresh repository) means everything in the index is new
+        let head_tree = repository.head().ok().and_then(|h| h.peel_to_tree().ok());
+        repository.diff_tree_to_index(head_tree.as_ref(), None, Some(&mut options))
+    } else {
+        options.include_untracked(true).recurse_untracked_dirs(true).show_untracked_content(true);
+        repository.diff_index_to_workdir(None, Some(&mut options))
+    }
+    .map_err(|e| git_error("Failed to compute diff", e))?;
+
+    let mut files = Vec::new();
+    let (mut additions, mut deletions) = (0, 0);
+    let mut patch_bytes = 0;
+    let mut truncated = false;
+
+    for index in 0..diff.deltas().len() {
+        let Some(delta) = diff.get_delta(index) else { continue };
+        let new_path = delta.new_file().path().map(|p| p.to_string_lossy().to_string());
+        let old_path = delta.old_file().path().map(|p| p.to_string_lossy().to_string());
+
+        let mut file = GitDiffFile {
+            path: new_path.clone().or_else(|| old_path.clone()).unwrap_or_default(),
+            old_path: old_path.filter(|old| Some(old) != new_path.as_ref()),
+            status: delta_status(delta.status()).to_string(),
+            additions: 0,
+            deletions: 0,
+            patch: None,
+        };
+
+        if let Ok(Some(mut patch)) = Patch::from_diff(&diff, index) {
+            if let Ok((_, added, deleted)) = patch.line_stats() {
+                file.additions = added;
+                file.deletions = deleted;
+            }
+            if !delta.flags().is_binary() {
+                let text = patch
+                    .to_buf()
+                    .ok()
+                    .and_then(|buf| buf.as_str().map(String::from));
+                if let Some(text) = text {
+                    if patch_bytes + text.len() <= MAX_PATCH_BYTES {
+                        patch_bytes += text.len();
+                        file.patch = Some(text);
+                    } else {
+                        truncated = true;
+                    }
+                }
+            }
+        }
+
+        additions += file.additions;
+        deletions += file.deletions;
+        files.push(file);
+    }
+
+    Ok(GitDiff {
+        staged,
+        files,
+        additions,
+        deletions,
+        truncated,
+    })
+}
+
+/// The most recent commits reachable from HEAD, newest first
+#[tauri::command]
+pub async fn get_recent_commits(
+    roots: State<'_, ProjectRootsState>,
+    repo: String,
+    n: usize,
+) -> Result<Vec<GitCommit>, String> {
+    with_repository(&roots, repo, move |repository| read_commits(repository, n)).await
+}
+
+fn read_commits(repository: &Repository, n: usize) -> Result<Vec<GitCommit>, String> {
+    let mut revwalk = repository.revwalk().map_err(|e| git_error("Failed to walk history", e))?;
+    if revwalk.push_head().is_err() {
+        // No commits yet
+        return Ok(Vec::new());
+    }
+    revwalk.set_sorting(Sort::TIME).map_err(|e| git_error("Failed to walk history", e))?;
+
+    let mut commits = Vec::new();
+    for oid in revwalk.take(n.min(MAX_COMMITS)) {
+        let oid = oid.map_err(|e| git_error("Failed to walk history", e))?;
+        let commit = repository
+            .find_commit(oid)
+            .map_err(|e| git_error("Failed to read commit", e))?;
+        let author = commit.author();
+        let id = oid.to_string();
+        commits.push(GitCommit {
+            short_id: id.chars().take(7).collect(),
+            id,
+            summary: commit.summary().unwrap_or_default().to_string(),
+            message: commit.message().unwrap_or_default().to_string(),
+            author_name: author.name().unwrap_or_default().to_string(),
+            author_email: author.email().unwrap_or_default().to_string(),
+            timestamp: commit.time().seconds(),
+        });
+    }
+    Ok(commits)
+}
@@ -0,0 +1,183 @@
+//! Handwriting recognition. Pen strokes (from the frontend's ink canvas) go through the Windows
+//! Ink recognizer, which needs stroke data; captured images of handwritten work (OneNote,
+//! whiteboard apps) are cleaned up and upscaled before Windows OCR, which reads neat handwriting
+//! far better at a larger size and with the paper texture flattened out.
+
+use base64::{engine::general_purpose, Engine as _};
+use image::imageops::FilterType;
+use image::{GrayImage, ImageFormat};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::io::Cursor;
+use tauri::{AppHandle, Manager};
+
+use crate::cancellation::CancellationState;
+
+/// Images narrower than this are upscaled before OCR
+const MIN_OCR_WIDTH: u32 = 1600;
+/// Upscaling stops at this factor to keep the image a reasonable size
+const MAX_UPSCALE: u32 = 3;
+/// Share of darkest/brightest pixels ignored when stretching contrast
+const CONTRAST_CLIP: f64 = 0.01;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema)]
+pub struct InkPoint {
+    pub x: f32,
+    pub y: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct InkStroke {
+    pub points: Vec<InkPoint>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum HandwritingMethod {
+    /// Windows Ink recognizer on pen strokes
+    Ink,
+    /// Windows OCR on a preprocessed image
+    Ocr,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct HandwritingRecognition {
+    /// Best guess, one line per recognized group of strokes (or per OCR line)
+    pub text: String,
+    /// Alternative readings per line, best first (ink only)
+    pub alternatives: Vec<Vec<String>>,
+    pub method: HandwritingMethod,
+}
+
+/// First gray level, walking `levels` in order, past the `clip` most extreme pixels
+fn clipped_level(histogram: &[usize; 256], levels: impl Iterator<Item = usize>, clip: usize) -> Option<usize> {
+    let mut seen = 0;
+    for level in levels {
+        seen += histogram[level];
+        if seen > clip {
+            return Some(level);
+        }
+    }
+    None
+}
+
+/// Grayscale, stretch the contrast so faint pencil becomes dark, and upscale small captures
+pub(crate) fn prepare_for_ocr(image_bytes: &[u8]) -> Result<Vec<u8>, String> {
+    let image = image::load_from_memory(image_bytes).map_err(|e| format!("Failed to load image: {}", e))?;
+    let mut gray: GrayImage = image.to_luma8();
+
+    let mut histogram = [0usize; 256];
+    for pixel in gray.pixels() {
+        histogram[pixel.0[0] as usize] += 1;
+    }
+    let clip = (gray.len() as f64 * CONTRAST_CLIP) as usize;
+    let low = clipped_level(&histogram, 0..256, clip).unwrap_or(0) as f32;
+    let high = clipped_level(&histogram, (0..256).rev(), clip).unwrap_or(255) as f32;
+    if high - low > 1.0 {
+        for pixel in gray.pixels_mut() {
+            pixel.0[0] = ((pixel.0[0] as f32 - low) * 255.0 / (high - low)).clamp(0.0, 255.0) as u8;
+        }
+    }
+
+    if gray.width() < MIN_OCR_WIDTH {
+        let factor = MIN_OCR_WIDTH.div_ceil(gray.width().max(1)).min(MAX_UPSCALE);
+        gray = image::imageops::resize(&gray, gray.width() * factor, gray.height() * factor, FilterType::CatmullRom);
+    }
+
+    let mut png = Vec::new();
+    gray.write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .map_err(|e| format!("Failed to encode image: {}", e))?;
+    Ok(png)
+}
+
+/// Recognize pen strokes with `InkRecognizerContainer`. Prints one JSON array of text
+/// candidates per recognized word group.
+#[cfg(target_os = "windows")]
+fn recognize_strokes(strokes: &[InkStroke]) -> Result<Vec<Vec<String>>, String> {
+    use std::process::Command;
+
+    let strokes_json = serde_json::to_string(strokes).map_err(|e| format!("Failed to serialize strokes: {}", e))?;
+    let strokes_base64 = general_purpose::STANDARD.encode(strokes_json);
+    let script = format!(r#"
+        $ErrorActionPreference = 'Stop'
+        Add-Type -AssemblyName System.Runtime.WindowsRuntime
+        $asTaskGeneric = ([System.WindowsRuntimeSystemExtensions].GetMethods() | Where-Object {{
+            $_.Name -eq 'AsTask' -and $_.GetParameters().Count -eq 1 -and $_.GetParameters()[0].ParameterType.Name -eq 'IAsyncOperation`1'
+        }})[0]
+        function Await($operation, [Type]$resultType) {{
+            $task = $asTaskGeneric.MakeGenericMethod($resultType).Invoke($null, @($operation))
+            $task.Wait(-1) | Out-Null
+            $task.Result
+        }}
+
+        [Windows.UI.Input.Inking.InkStrokeBuilder, Windows.UI, ContentType=WindowsRuntime] | Out-Null
+        [Windows.UI.Input.Inking.InkStrokeContainer, Windows.UI, ContentType=WindowsRuntime] | Out-Null
+        [Windows.UI.Input.Inking.InkRecognizerContainer, Windows.UI, ContentType=WindowsRuntime] | Out-Null
+
+        $strokes = [System.Text.Encoding]::UTF8.GetString([Convert]::FromBase64String('{}')) | ConvertFrom-Json
+        $builder = New-Object Windows.UI.Input.Inking.InkStrokeBuilder
+        $container = New-Object Windows.UI.Input.Inking.InkStrokeContainer
+        foreach ($stroke in $strokes) {{
+            $points = New-Object 'System.Collections.Generic.List[Windows.Foundation.Point]'
+            foreach ($p in $stroke.points) {{ $points.Add((New-Object Windows.Foundation.Point($p.x, $p.y))) }}
+            if ($points.Count -gt 0) {{ $container.AddStroke($builder.CreateStroke($points)) }}
+        }}
+
+        $recognizer = New-Object Windows.UI.Input.Inking.InkRecognizerContainer
+        $results = Await ($recognizer.RecognizeAsync($container, [Windows.UI.Input.Inking.InkRecognitionTarget]::All)) `
+            ([System.Collections.Generic.IReadOnlyList[Windows.UI.Input.Inking.InkRecognitionResult]])
+        $groups = @($results | ForEach-Object {{ ,@($_.GetTextCandidates()) }})
+        ConvertTo-Json -InputObject $groups -Depth 3 -Compress
+    "#, strokes_base64);
+
+    let output = Command::new("powershell")
+        .args(["-NoProfile", "-ExecutionPolicy", "Bypass", "-Command", &script])
+        .output()
+        .map_err(|e| format!("Failed to start ink recognition: {}", e))?;
+    if !output.status.success() {
+        return Err(format!("Ink recognition failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stdout = stdout.trim();
+    if stdout.is_empty() {
+        return Ok(Vec::new());
+    }
+    serde_json::from_str(stdout).map_err(|e| format!("Failed to parse ink recognition result: {}", e))
+}
+
+#[cfg(not(target_os = "windows"))]
+fn recognize_strokes(_strokes: &[InkStroke]) -> Result<Vec<Vec<String>>, String> {
+    Err("Ink recognition is only available on Windows".to_string())
+}
+
+/// Read handwriting from pen strokes (preferred, via Windows Ink) or from an image of
+/// handwritten work (via preprocessed OCR)
+#[tauri::command]
+pub async fn recognize_handwriting(
+    app: AppHandle,
+    image_base64: Option<String>,
+    strokes: Option<Vec<InkStroke>>,
+) -> Result<HandwritingRecognition, String> {
+    if let Some(strokes) = strokes.filter(|s| !s.is_empty()) {
+        let alternatives = tauri::async_runtime::spawn_blocking(move || recognize_strokes(&strokes))
+            .await
+            .map_err(|e| format!("Ink recognition failed: {}", e))??;
+        let text = alternatives
+            .iter()
+            .filter_map(|candidates| candidates.first().cloned())
+            .collect::<Vec<_>>()
+            .join(" ");
+        return Ok(HandwritingRecognition { text, alternatives, method: HandwritingMethod::Ink });
+    }
+
+    let image_base64 = image_base64.ok_or("Either image_base64 or strokes is required")?;
+    let image_bytes = general_purpose::STANDARD
+        .decode(image_base64.trim())
+        .map_err(|e| format!("Invalid base64 image: {}", e))?;
+    let prepared = tauri::async_runtime::spawn_blocking(move || prepare_for_ocr(&image_bytes))
+        .await
+        .map_err(|e| format!("Failed to prepare image: {}", e))??;
+    let operation = app.state::<CancellationState>().register(None);
+    let text = crate::commands::ocr_image(&app, general_purpose::STANDARD.encode(prepared), &operation).await?;
+    Ok(HandwritingRecognition { text, alternatives: Vec::new(), method: HandwritingMethod::Ocr })
+}
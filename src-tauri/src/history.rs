@@ -0,0 +1,276 @@
+//! The profile's searchable history: a SQLite database of things the tutor can look back on,
+//! such as imported assignment sheets. Each item's text is stored in parts (one per page) and
+//! indexed with FTS5. Other stores (notes) keep their tables in the same database, set up by
+//! `MIGRATIONS`.
+
+use rusqlite::{params, Connection, OptionalExtension};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager, State};
+
+use crate::persistence;
+
+pub(crate) const DB_FILE: &str = "history.db";
+const DEFAULT_SEARCH_LIMIT: u32 = 20;
+const MAX_SEARCH_LIMIT: u32 = 200;
+
+const SCHEMA: &str = r#"
+CREATE TABLE history_items (
+    id INTEGER PRIMARY KEY,
+    kind TEXT NOT NULL,
+    title TEXT NOT NULL,
+    source TEXT,
+    created_at INTEGER NOT NULL
+);
+CREATE TABLE history_text (
+    id INTEGER PRIMARY KEY,
+    item_id INTEGER NOT NULL REFERENCES history_items(id) ON DELETE CASCADE,
+    part INTEGER NOT NULL,
+    text TEXT NOT NULL,
+    image_path TEXT
+);
+CREATE INDEX history_text_item ON history_text(item_id, part);
+CREATE VIRTUAL TABLE history_fts USING fts5(text, content='history_text', content_rowid='id');
+CREATE TRIGGER history_text_insert AFTER INSERT ON history_text BEGIN
+    INSERT INTO history_fts(rowid, text) VALUES (new.id, new.text);
+END;
+CREATE TRIGGER history_text_delete AFTER DELETE ON history_text BEGIN
+    INSERT INTO history_fts(history_fts, rowid, text) VALUES ('delete', old.id, old.text);
+END;
+"#;
+
+/// Schema changes in order; a database at `user_version` N has the first N applied
+const MIGRATIONS: &[&str] = &[
+    SCHEMA,
+    crate::notes::SCHEMA,
+    crate::flashcards::SCHEMA,
+    crate::exercises::SCHEMA,
+    crate::curriculum::SCHEMA,
+    crate::achievements::SCHEMA,
+    crate::daily_summary::SCHEMA,
+    crate::retention::SCHEMA,
+    crate::conversations::SCHEMA,
+    crate::prompts::SCHEMA,
+    crate::ai_usage::SCHEMA,
+];
+
+/// One stored item; `parts` holds its text, e.g. one entry per document page
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct HistoryItem {
+    pub id: i64,
+    /// What the item is, e.g. `document`
+    pub kind: String,
+    pub title: String,
+    /// Where it came from, e.g. the imported file's path
+    pub source: Option<String>,
+    pub created_at: i64,
+    pub parts: Vec<HistoryPart>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct HistoryPart {
+    /// 1-based position, e.g. the page number
+    pub part: u32,
+    pub text: String,
+    /// Rendered image of the part (PDF pages on Windows)
+    pub image_path: Option<String>,
+}
+
+/// A search hit: the item and the part whose text matched
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct HistoryMatch {
+    pub item_id: i64,
+    pub kind: String,
+    pub title: String,
+    pub source: Option<String>,
+    pub created_at: i64,
+    pub part: u32,
+    /// The matching text around the hit, with the terms in [brackets]
+    pub snippet: String,
+}
+
+#[derive(Clone, Default)]
+pub struct HistoryState {
+    db: Arc<Mutex<Option<Connection>>>,
+}
+
+fn open(app: &AppHandle) -> Result<Connection, String> {
+    let path = persistence::data_file(app, DB_FILE)?;
+    let mut db = Connection::open(&path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    db.execute_batch("PRAGMA journal_mode = WAL; PRAGMA foreign_keys = ON;")
+        .map_err(|e| format!("Failed to set up the history database: {}", e))?;
+    let version: usize = db
+        .pragma_query_value(None, "user_version", |row| row.get(0))
+        .map_err(|e| format!("Failed to read the history database version: {}", e))?;
+    for (index, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+        let tx = db.transaction().map_err(|e| format!("Failed to migrate the history database: {}", e))?;
+        tx.execute_batch(migration)
+            .and_then(|_| tx.pragma_update(None, "user_version", index + 1))
+            .and_then(|_| tx.commit())
+            .map_err(|e| format!("Failed to migrate the history database to version {}: {}", index + 1, e))?;
+    }
+    Ok(db)
+}
+
+/// Open the history database
+pub fn init(app: &AppHandle) {
+    match open(app) {
+        Ok(db) => *app.state::<HistoryState>().db.lock().unwrap() = Some(db),
+        Err(e) => tracing::warn!("⚠️ History is unavailable: {}", e),
+    }
+}
+
+/// Schema version of a fully migrated database
+pub(crate) fn schema_version() -> usize {
+    MIGRATIONS.len()
+}
+
+impl HistoryState {
+    /// Close the database so its file can be replaced; commands fail until the app restarts
+    pub(crate) fn close(&self) {
+        self.db.lock().unwrap().take();
+    }
+
+    /// Fold the write-ahead log into the database file and close it, so nothing is left
+    /// half-written when the app exits
+    pub(crate) fn flush_and_close(&self) {
+        let Some(db) = self.db.lock().unwrap().take() else {
+            return;
+        };
+        if let Err(e) = db.execute_batch("PRAGMA wal_checkpoint(TRUNCATE);") {
+            tracing::warn!("⚠️ Failed to checkpoint the history database: {}", e);
+        }
+        if let Err((_, e)) = db.close() {
+            tracing::warn!("⚠️ Failed to close the history database: {}", e);
+        }
+    }
+
+    /// Run `f` on the database connection
+    pub(crate) fn with_db<T>(&self, f: impl FnOnce(&mut Connection) -> rusqlite::Result<T>) -> Result<T, String> {
+        let mut db = self.db.lock().unwrap();
+        let db = db.as_mut().ok_or("The history database isn't open")?;
+        f(db).map_err(|e| format!("History database error: {}", e))
+    }
+
+    /// Store an item with its text parts and return its id
+    pub(crate) fn insert(&self, kind: &str, title: &str, source: Option<&str>, parts: &[HistoryPart]) -> Result<i64, String> {
+        self.with_db(|db| {
+            let tx = db.transaction()?;
+            tx.execute(
+                "INSERT INTO history_items (kind, title, source, created_at) VALUES (?1, ?2, ?3, ?4)",
+                params![kind, title, source, chrono::Utc::now().timestamp()],
+            )?;
+            let id = tx.last_insert_rowid();
+            for part in parts {
+                tx.execute(
+                    "INSERT INTO history_text (item_id, part, text, image_path) VALUES (?1, ?2, ?3, ?4)",
+                    params![id, part.part, part.text, part.image_path],
+                )?;
+            }
+            tx.commit()?;
+            Ok(id)
+        })
+    }
+
+    pub(crate) fn get(&self, id: i64) -> Result<Option<HistoryItem>, String> {
+        self.with_db(|db| {
+            let item = db
+                .query_row(
+                    "SELECT id, kind, title, source, created_at FROM history_items WHERE id = ?1",
+                    [id],
+                    |row| {
+                        Ok(HistoryItem {
+                            id: row.get(0)?,
+                            kind: row.get(1)?,
+                            title: row.get(2)?,
+                            source: row.get(3)?,
+                            created_at: row.get(4)?,
+                            parts: Vec::new(),
+                        })
+                    },
+                )
+                .optional()?;
+            let Some(mut item) = item else {
+                return Ok(None);
+            };
+            let mut statement =
+                db.prepare("SELECT part, text, image_path FROM history_text WHERE item_id = ?1 ORDER BY part")?;
+            item.parts = statement
+                .query_map([id], |row| Ok(HistoryPart { part: row.get(0)?, text: row.get(1)?, image_path: row.get(2)? }))?
+                .collect::<rusqlite::Result<_>>()?;
+            Ok(Some(item))
+        })
+    }
+}
+
+/// Each word of the query as a quoted FTS5 term, so punctuation in it can't break the syntax
+pub(crate) fn fts_query(query: &str) -> String {
+    query
+        .split_whitespace()
+        .map(|term| format!("\"{}\"", term.replace('"', "\"\"")))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Full-text search, best matches first; `kind` limits it to one kind of item
+#[tauri::command]
+pub async fn search_history(
+    state: State<'_, HistoryState>,
+    query: String,
+    kind: Option<String>,
+    limit: Option<u32>,
+) -> Result<Vec<HistoryMatch>, String> {
+    let query = fts_query(&query);
+    if query.is_empty() {
+        return Ok(Vec::new());
+    }
+    let limit = limit.unwrap_or(DEFAULT_SEARCH_LIMIT).clamp(1, MAX_SEARCH_LIMIT);
+    state.with_db(|db| {
+        let mut statement = db.prepare(
+            "SELECT i.id, i.kind, i.title, i.source, i.created_at, t.part,
+                    snippet(history_fts, 0, '[', ']', '…', 16)
+             FROM history_fts
+             JOIN history_text t ON t.id = history_fts.rowid
+             JOIN history_items i ON i.id = t.item_id
+             WHERE history_fts MATCH ?1 AND (?2 IS NULL OR i.kind = ?2)
+             ORDER BY rank
+             LIMIT ?3",
+        )?;
+        let matches = statement
+            .query_map(params![query, kind, limit], |row| {
+                Ok(HistoryMatch {
+                    item_id: row.get(0)?,
+                    kind: row.get(1)?,
+                    title: row.get(2)?,
+                    source: row.get(3)?,
+                    created_at: row.get(4)?,
+                    part: row.get(5)?,
+                    snippet: row.get(6)?,
+                })
+            })?
+            .collect();
+        matches
+    })
+}
+
+#[tauri::command]
+pub async fn get_history_item(state: State<'_, HistoryState>, id: i64) -> Result<HistoryItem, String> {
+    state.get(id)?.ok_or_else(|| format!("No history item with id {}", id))
+}
+
+/// Delete an item, its text and any page images stored with it
+#[tauri::command]
+pub async fn delete_history_item(state: State<'_, HistoryState>, id: i64) -> Result<(), String> {
+    let item = state.get(id)?.ok_or_else(|| format!("No history item with id {}", id))?;
+    state.with_db(|db| db.execute("DELETE FROM history_items WHERE id = ?1", [id]))?;
+    for path in item.parts.iter().filter_map(|part| part.image_path.as_deref()).map(std::path::Path::new) {
+        let _ = std::fs::remove_file(path);
+        // Page images share a directory per item, removed once empty
+        if let Some(dir) = path.parent() {
+            let _ = std::fs::remove_dir(dir);
+        }
+    }
+    tracing::info!("🗑️ Deleted history item {} ({})", id, item.title);
+    Ok(())
+}
@@ -0,0 +1,281 @@
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use tauri::{AppHandle, Manager, State};
+
+/// Processes that are treated as code editors / IDEs
+const IDE_PROCESSES: &[&str] = &[
+    "code", "devenv", "idea64", "pycharm64", "clion64", "webstorm64", "rider64",
+    "sublime_text", "notepad++", "eclipse", "atom",
+];
+
+/// Processes that are treated as word processors
+const WORD_PROCESSORS: &[&str] = &["winword", "soffice", "soffice.bin", "wordpad"];
+
+/// Browsers, used to recognize web-based editors like Google Docs
+const BROWSERS: &[&str] = &["chrome", "msedge", "firefox", "brave", "opera"];
+
+/// Dedicated PDF viewers
+const PDF_VIEWERS: &[&str] = &["acrord32", "acrobat", "sumatrapdf", "foxitpdfreader"];
+
+const ESSAY_KEYWORDS: &[&str] = &[
+    "essay", "thesis", "introduction", "conclusion", "bibliography", "works cited", "word count",
+];
+
+const WORKSHEET_OCR_KEYWORDS: &[&str] = &["name:", "date:", "question 1", "answer:", "show your work"];
+
+/// Minimum score before a context is reported
+const DETECTION_THRESHOLD: f32 = 0.6;
+
+fn assignment_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        Regex::new(r"(?i)\b(homework|assignments?|coursework|problem[ _-]?set|hw[ _-]?\d+|lab[ _-]?\d+|exercises?)\b")
+            .expect("valid assignment regex")
+    })
+}
+
+fn worksheet_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        Regex::new(r"(?i)(worksheet|problem[ _-]?set|exercises?|hw[ _-]?\d+|quiz|practice)")
+            .expect("valid worksheet regex")
+    })
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum HomeworkKind {
+    Essay,
+    CodingAssignment,
+    Worksheet,
+}
+
+/// Payload of the `homework-context-detected` event
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct HomeworkContext {
+    pub kind: HomeworkKind,
+    pub confidence: f32,
+    pub signals: Vec<String>,
+    pub window_title: String,
+    pub process_name: String,
+    /// Auto-start is on: a focus session was started for this context unless one was running
+    pub auto_start_session: bool,
+    pub timestamp: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct HomeworkDetectionSettings {
+    pub enabled: bool,
+    /// Start a focus session (25/5 minutes) when homework is detected and none is running
+    pub auto_start_session: bool,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct HomeworkDetectionParams {
+    #[serde(default)]
+    pub window_title: Option<String>,
+    #[serde(default)]
+    pub process_name: Option<String>,
+    #[serde(default)]
+    pub ocr_text: Option<String>,
+}
+
+#[derive(Clone)]
+pub struct HomeworkDetectionState {
+    pub enabled: Arc<AtomicBool>,
+    pub auto_start_session: Arc<AtomicBool>,
+    /// Last reported kind + window title, so the same context isn't emitted on every capture
+    last_signature: Arc<Mutex<Option<String>>>,
+}
+
+impl Default for HomeworkDetectionState {
+    fn default() -> Self {
+        Self {
+            enabled: Arc::new(AtomicBool::new(false)),
+            auto_start_session: Arc::new(AtomicBool::new(false)),
+            last_signature: Arc::new(Mutex::new(None)),
+        }
+    }
+}
+
+struct Candidate {
+    kind: HomeworkKind,
+    score: f32,
+    signals: Vec<String>,
+}
+
+impl Candidate {
+    fn new(kind: HomeworkKind) -> Self {
+        Self { kind, score: 0.0, signals: Vec::new() }
+    }
+
+    fn add(&mut self, weight: f32, signal: impl Into<String>) {
+        self.score += weight;
+        self.signals.push(signal.into());
+    }
+}
+
+fn normalize_process(process_name: &str) -> String {
+    process_name.trim().to_lowercase().trim_end_matches(".exe").to_string()
+}
+
+/// Score the window metadata and OCR text against each kind of assignment
+/// and return the best match above the detection threshold.
+pub fn detect(window_title: &str, process_name: &str, ocr_text: Option<&str>) -> Option<HomeworkContext> {
+    let process = normalize_process(process_name);
+    let title = window_title.to_lowercase();
+    let ocr = ocr_text.map(|t| t.to_lowercase()).unwrap_or_default();
+
+    let is_ide = IDE_PROCESSES.contains(&process.as_str());
+    let is_browser = BROWSERS.contains(&process.as_str());
+    let is_google_docs = is_browser && title.contains("google docs");
+    let is_word_processor = WORD_PROCESSORS.contains(&process.as_str()) || title.ends_with("- word");
+    let is_pdf = PDF_VIEWERS.contains(&process.as_str()) || title.contains(".pdf");
+    let title_mentions_assignment = assignment_pattern().is_match(&title);
+
+    let mut essay = Candidate::new(HomeworkKind::Essay);
+    if is_google_docs {
+        essay.add(0.4, "Google Docs in browser");
+    } else if is_word_processor {
+        essay.add(0.4, format!("word processor ({})", process_name));
+    }
+    if let Some(keyword) = ESSAY_KEYWORDS.iter().find(|k| title.contains(*k)) {
+        essay.add(0.4, format!("title mentions \"{}\"", keyword));
+    }
+    let essay_hits = ESSAY_KEYWORDS.iter().filter(|k| ocr.contains(*k)).count();
+    if essay_hits >= 2 {
+        essay.add(0.3, format!("{} essay keywords on screen", essay_hits));
+    } else if essay_hits == 1 {
+        essay.add(0.15, "essay keyword on screen");
+    }
+    if title_mentions_assignment && (is_google_docs || is_word_processor) {
+        essay.add(0.2, "document title looks like an assignment");
+    }
+
+    let mut coding = Candidate::new(HomeworkKind::CodingAssignment);
+    if is_ide {
+        coding.add(0.4, format!("code editor ({})", process_name));
+        if title_mentions_assignment {
+            coding.add(0.4, "project path looks like a homework folder");
+        }
+        if assignment_pattern().is_match(&ocr) {
+            coding.add(0.2, "assignment wording in editor content");
+        }
+    }
+
+    let mut worksheet = Candidate::new(HomeworkKind::Worksheet);
+    if is_pdf {
+        worksheet.add(0.4, "PDF document");
+        if worksheet_pattern().is_match(&title) {
+            worksheet.add(0.4, "file name looks like a worksheet");
+        }
+        let worksheet_hits = WORKSHEET_OCR_KEYWORDS.iter().filter(|k| ocr.contains(*k)).count();
+        if worksheet_hits > 0 {
+            worksheet.add(0.2, format!("{} worksheet markers on page", worksheet_hits));
+        }
+    }
+
+    let best = [essay, coding, worksheet]
+        .into_iter()
+        .max_by(|a, b| a.score.total_cmp(&b.score))?;
+
+    if best.score < DETECTION_THRESHOLD {
+        return None;
+    }
+
+    Some(HomeworkContext {
+        kind: best.kind,
+        confidence: best.score.min(1.0),
+        signals: best.signals,
+        window_title: window_title.to_string(),
+        process_name: process_name.to_string(),
+        auto_start_session: false,
+        timestamp: chrono::Utc::now().timestamp(),
+    })
+}
+
+/// Run detection for a capture and emit `homework-context-detected` when a new
+/// context is found. Does nothing while detection mode is disabled.
+pub fn observe(app: &AppHandle, window_title: &str, process_name: &str, ocr_text: Option<&str>) {
+    if !app.state::<HomeworkDetectionState>().enabled.load(Ordering::Relaxed) {
+        return;
+    }
+    if let Some(context) = detect(window_title, process_name, ocr_text) {
+        announce(app, context);
+    }
+}
+
+/// Emit `homework-context-detected` for a detected context, unless it is the one seen last, and
+/// start a focus session for it when auto-start is on
+fn announce(app: &AppHandle, mut context: HomeworkContext) {
+    let state = app.state::<HomeworkDetectionState>();
+    let signature = format!("{:?}|{}", context.kind, context.window_title);
+    {
+        let mut last = state.last_signature.lock().unwrap();
+        if last.as_deref() == Some(signature.as_str()) {
+            return;
+        }
+        *last = Some(signature);
+    }
+
+    context.auto_start_session = state.auto_start_session.load(Ordering::Relaxed);
+    tracing::info!(
+        "📚 Detected {:?} (confidence {:.2}) in \"{}\"",
+        context.kind, context.confidence, context.window_title
+    );
+    let auto_start = context.auto_start_session;
+    let _ = crate::event_bus::emit(app, crate::events::HOMEWORK_CONTEXT_DETECTED, context);
+    if auto_start && crate::focus_timer::start_if_idle(app).is_some() {
+        tracing::info!("📚 Started a focus session for the homework");
+    }
+}
+
+#[tauri::command]
+pub async fn get_homework_detection_settings(
+    state: State<'_, HomeworkDetectionState>,
+) -> Result<HomeworkDetectionSettings, String> {
+    Ok(HomeworkDetectionSettings {
+        enabled: state.enabled.load(Ordering::Relaxed),
+        auto_start_session: state.auto_start_session.load(Ordering::Relaxed),
+    })
+}
+
+#[tauri::command]
+pub async fn set_homework_detection(
+    state: State<'_, HomeworkDetectionState>,
+    settings: HomeworkDetectionSettings,
+) -> Result<(), String> {
+    state.enabled.store(settings.enabled, Ordering::Relaxed);
+    state.auto_start_session.store(settings.auto_start_session, Ordering::Relaxed);
+    // Forget the last context so re-enabling reports the current window again
+    *state.last_signature.lock().unwrap() = None;
+    Ok(())
+}
+
+/// Check the given window metadata (or the active window when omitted) for a homework context
+#[tauri::command]
+pub async fn detect_homework_context(
+    app: AppHandle,
+    options: HomeworkDetectionParams,
+) -> Result<Option<HomeworkContext>, String> {
+    let (window_title, process_name) = match (options.window_title, options.process_name) {
+        (None, None) => {
+            let context = crate::commands::get_system_context().await?;
+            (context.active_window_title, context.active_window)
+        }
+        (title, process) => (title.unwrap_or_default(), process.unwrap_or_default()),
+    };
+
+    let state = app.state::<HomeworkDetectionState>();
+    let mut context = detect(&window_title, &process_name, options.ocr_text.as_deref());
+    if let Some(context) = context.as_mut() {
+        context.auto_start_session = state.auto_start_session.load(Ordering::Relaxed);
+        if state.enabled.load(Ordering::Relaxed) {
+            announce(&app, context.clone());
+        }
+    }
+    Ok(context)
+}
@@ -0,0 +1,109 @@
+//! Downscaling for captures and monitoring frames. The `image` crate's filters run on one core
+//! without SIMD, which shows up as CPU spikes while monitoring; `fast_image_resize` does the
+//! same filters with SSE4.1/AVX2 or NEON. Which one is used is a setting, and the fast path
+//! falls back to the `image` crate if it fails.
+
+use image::imageops::FilterType;
+use image::RgbaImage;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager, State};
+
+use crate::persistence;
+
+const SETTINGS_FILE: &str = "resize_settings.json";
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ResizeBackend {
+    /// The `image` crate, one core and no SIMD
+    #[default]
+    Image,
+    /// `fast_image_resize`, using the CPU's SIMD instructions
+    Simd,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct ResizeSettings {
+    backend: ResizeBackend,
+}
+
+#[derive(Clone, Default)]
+pub struct ResizeState {
+    backend: Arc<Mutex<ResizeBackend>>,
+}
+
+impl ResizeState {
+    pub fn get(&self) -> ResizeBackend {
+        *self.backend.lock().unwrap()
+    }
+}
+
+/// Load the saved backend choice
+pub fn init(app: &AppHandle) {
+    let settings: ResizeSettings = persistence::data_file(app, SETTINGS_FILE)
+        .map(|path| persistence::load_json(&path))
+        .unwrap_or_default();
+    *app.state::<ResizeState>().backend.lock().unwrap() = settings.backend;
+}
+
+fn resize_simd(image: &RgbaImage, width: u32, height: u32, filter: FilterType) -> Result<RgbaImage, String> {
+    use fast_image_resize::images::Image;
+    use fast_image_resize::{PixelType, ResizeAlg, ResizeOptions, Resizer};
+
+    let algorithm = match filter {
+        FilterType::Nearest => ResizeAlg::Nearest,
+        FilterType::Triangle => ResizeAlg::Convolution(fast_image_resize::FilterType::Bilinear),
+        FilterType::CatmullRom => ResizeAlg::Convolution(fast_image_resize::FilterType::CatmullRom),
+        FilterType::Gaussian => ResizeAlg::Convolution(fast_image_resize::FilterType::Gaussian),
+        FilterType::Lanczos3 => ResizeAlg::Convolution(fast_image_resize::FilterType::Lanczos3),
+    };
+    let source = Image::from_vec_u8(image.width(), image.height(), image.as_raw().clone(), PixelType::U8x4)
+        .map_err(|e| format!("Failed to read the image: {}", e))?;
+    let mut resized = Image::new(width, height, PixelType::U8x4);
+    Resizer::new()
+        .resize(&source, &mut resized, &ResizeOptions::new().resize_alg(algorithm))
+        .map_err(|e| format!("Failed to resize: {}", e))?;
+    RgbaImage::from_raw(width, height, resized.into_vec()).ok_or_else(|| "Resized image has the wrong size".to_string())
+}
+
+/// Scale `image` to exactly `width` x `height`. CPU-heavy: call it from a blocking thread.
+pub(crate) fn resize(backend: ResizeBackend, image: &RgbaImage, width: u32, height: u32, filter: FilterType) -> RgbaImage {
+    let (width, height) = (width.max(1), height.max(1));
+    if backend == ResizeBackend::Simd {
+        match resize_simd(image, width, height, filter) {
+            Ok(resized) => return resized,
+            Err(e) => tracing::warn!("⚠️ SIMD resize failed, using the image crate: {}", e),
+        }
+    }
+    image::imageops::resize(image, width, height, filter)
+}
+
+/// Scale `image` down to at most `max_width`, keeping its aspect ratio
+pub(crate) fn fit_width(backend: ResizeBackend, image: RgbaImage, max_width: u32, filter: FilterType) -> RgbaImage {
+    if image.width() <= max_width {
+        return image;
+    }
+    let height = (image.height() as u64 * max_width as u64 / image.width() as u64) as u32;
+    resize(backend, &image, max_width, height, filter)
+}
+
+#[tauri::command]
+pub async fn get_resize_backend(state: State<'_, ResizeState>) -> Result<ResizeBackend, String> {
+    Ok(state.get())
+}
+
+/// Choose how captures and monitoring frames are downscaled; saved across restarts
+#[tauri::command]
+pub async fn set_resize_backend(
+    app: AppHandle,
+    state: State<'_, ResizeState>,
+    backend: ResizeBackend,
+) -> Result<(), String> {
+    *state.backend.lock().unwrap() = backend;
+    let path = persistence::data_file(&app, SETTINGS_FILE)?;
+    persistence::save_json(&path, &ResizeSettings { backend })?;
+    tracing::info!("🖼️ Resize backend set to {:?}", backend);
+    Ok(())
+}
@@ -0,0 +1,118 @@
+//! Keyboard and mouse counts from the native helper's input hook (see `helper/src/input.rs`),
+//! read once a second and handed to activity tracking and typing practice. The one hook serves
+//! both: it is installed while either of them wants it and removed when neither does.
+
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::watchdog::Heartbeat;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// Longer than a helper call may take
+const POLL_SILENCE: Duration = Duration::from_secs(120);
+
+/// Input since the previous poll. Never contains what was typed or clicked.
+#[derive(Deserialize)]
+struct InputCounts {
+    keystrokes: u64,
+    clicks: u64,
+    /// Keys pressed in the typing practice window, backspaces included
+    typing_keys: u64,
+    typing_backspaces: u64,
+}
+
+#[derive(Default)]
+struct Users {
+    activity: bool,
+    /// Process of the running typing session's practice window
+    typing_process: Option<String>,
+    polling: bool,
+}
+
+impl Users {
+    fn any(&self) -> bool {
+        self.activity || self.typing_process.is_some()
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct InputHookState {
+    users: Arc<Mutex<Users>>,
+}
+
+fn update(app: &AppHandle, change: impl FnOnce(&mut Users)) {
+    let state = app.state::<InputHookState>();
+    let mut users = state.users.lock().unwrap();
+    change(&mut users);
+    if users.any() && !users.polling {
+        users.polling = true;
+        let loop_app = app.clone();
+        crate::watchdog::spawn_supervised(app, "Input hook", POLL_SILENCE, move |heartbeat| {
+            poll(loop_app.clone(), heartbeat)
+        });
+    }
+}
+
+fn require_helper(app: &AppHandle) -> Result<(), String> {
+    if crate::native_helper::in_use(app) {
+        Ok(())
+    } else {
+        Err("Counting keystrokes needs the native helper (ai-teacher-helper), which only runs on Windows".to_string())
+    }
+}
+
+/// Count keystrokes and clicks for activity tracking, or stop counting them
+pub(crate) fn set_activity(app: &AppHandle, enabled: bool) -> Result<(), String> {
+    if enabled {
+        require_helper(app)?;
+    }
+    update(app, |users| users.activity = enabled);
+    Ok(())
+}
+
+/// Count keys typed into `process` for the typing session, or stop with `None`
+pub(crate) fn set_typing_process(app: &AppHandle, process: Option<String>) -> Result<(), String> {
+    if process.is_some() {
+        require_helper(app)?;
+    }
+    update(app, |users| users.typing_process = process);
+    Ok(())
+}
+
+/// Read the helper's counts until nobody needs them, then remove the hook
+async fn poll(app: AppHandle, heartbeat: Heartbeat) {
+    let state = app.state::<InputHookState>().inner().clone();
+    loop {
+        heartbeat.beat();
+        let typing_process = state.users.lock().unwrap().typing_process.clone();
+        let params = json!({ "typing_process": typing_process });
+        match crate::native_helper::call::<InputCounts>(&app, "input_counts", params, None).await {
+            Some(Ok(counts)) => {
+                crate::activity::record_input(&app, counts.keystrokes, counts.clicks);
+                if counts.typing_keys > 0 {
+                    crate::typing_practice::record_second(&app, counts.typing_keys, counts.typing_backspaces);
+                }
+            }
+            Some(Err(e)) => tracing::warn!("⚠️ Failed to read input counts: {}", e),
+            None => {
+                tracing::warn!("⚠️ Native helper unavailable, input isn't being counted");
+                *state.users.lock().unwrap() = Users::default();
+                break;
+            }
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+
+        if !state.users.lock().unwrap().any() {
+            let _ = crate::native_helper::call::<serde_json::Value>(&app, "input_stop", json!({}), None).await;
+            // Unless someone started counting again while the hook was being removed
+            let mut users = state.users.lock().unwrap();
+            if !users.any() {
+                users.polling = false;
+                break;
+            }
+        }
+    }
+}
@@ -0,0 +1,180 @@
+//! `tracing` setup: JSON logs in a daily-rotated file under the app data dir, human-readable
+//! logs on stderr, and an in-memory ring of recent entries for the diagnostics panel.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, State};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Subscriber};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, reload, Registry};
+
+use crate::persistence;
+
+pub(crate) const LOG_DIR: &str = "logs";
+pub(crate) const LOG_FILE_PREFIX: &str = "ai-teacher.log";
+const SETTINGS_FILE: &str = "log_settings.json";
+/// Daily log files kept before the oldest is deleted
+const MAX_LOG_FILES: usize = 7;
+/// Entries kept in memory for `get_recent_logs`
+const RECENT_CAPACITY: usize = 1000;
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct LogEntry {
+    /// Unix timestamp in milliseconds
+    pub timestamp: i64,
+    pub level: String,
+    pub target: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct LogSettings {
+    level: String,
+}
+
+impl Default for LogSettings {
+    fn default() -> Self {
+        Self { level: "info".to_string() }
+    }
+}
+
+type FilterHandle = reload::Handle<LevelFilter, Registry>;
+
+#[derive(Clone, Default)]
+pub struct LoggingState {
+    filter: Arc<Mutex<Option<FilterHandle>>>,
+    recent: Arc<Mutex<VecDeque<LogEntry>>>,
+    /// Flushes the file writer when dropped, so it lives as long as the app
+    guard: Arc<Mutex<Option<WorkerGuard>>>,
+}
+
+/// Collects the message and any structured fields of an event into one line
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+    fields: Vec<String>,
+}
+
+impl Visit for MessageVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = value.to_string();
+        } else {
+            self.fields.push(format!("{}={}", field.name(), value));
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        if field.name() == "message" {
+            self.message = format!("{:?}", value);
+        } else {
+            self.fields.push(format!("{}={:?}", field.name(), value));
+        }
+    }
+}
+
+struct RecentLogs(Arc<Mutex<VecDeque<LogEntry>>>);
+
+impl<S: Subscriber> Layer<S> for RecentLogs {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+        let mut message = visitor.message;
+        if !visitor.fields.is_empty() {
+            message = format!("{} {}", message, visitor.fields.join(" "));
+        }
+
+        let mut recent = self.0.lock().unwrap();
+        if recent.len() == RECENT_CAPACITY {
+            recent.pop_front();
+        }
+        recent.push_back(LogEntry {
+            timestamp: chrono::Utc::now().timestamp_millis(),
+            level: event.metadata().level().to_string(),
+            target: event.metadata().target().to_string(),
+            message,
+        });
+    }
+}
+
+fn parse_level(level: &str) -> Result<LevelFilter, String> {
+    level
+        .parse::<LevelFilter>()
+        .map_err(|_| format!("Unknown log level '{}'. Use trace, debug, info, warn, error or off", level))
+}
+
+/// Install the global subscriber. Call first in `setup`; events emitted before this are dropped.
+pub fn init(app: &AppHandle, state: &LoggingState) {
+    let settings: LogSettings = persistence::shared_data_file(app, SETTINGS_FILE)
+        .map(|path| persistence::load_json(&path))
+        .unwrap_or_default();
+    let level = parse_level(&settings.level).unwrap_or(LevelFilter::INFO);
+    let (filter, handle) = reload::Layer::new(level);
+
+    let file_layer = persistence::shared_data_file(app, LOG_DIR)
+        .and_then(|dir| {
+            RollingFileAppender::builder()
+                .rotation(Rotation::DAILY)
+                .filename_prefix(LOG_FILE_PREFIX)
+                .max_log_files(MAX_LOG_FILES)
+                .build(dir)
+                .map_err(|e| format!("Failed to open log file: {}", e))
+        })
+        .map(|appender| {
+            let (writer, guard) = tracing_appender::non_blocking(appender);
+            *state.guard.lock().unwrap() = Some(guard);
+            fmt::layer().json().with_ansi(false).with_writer(writer)
+        });
+    // Nothing is listening to `tracing` yet, so these go straight to stderr
+    let file_layer = file_layer
+        .map_err(|e| eprintln!("[logging] ⚠️ {}", e))
+        .ok();
+
+    let result = tracing_subscriber::registry()
+        .with(filter)
+        .with(file_layer)
+        .with(fmt::layer().with_writer(std::io::stderr))
+        .with(RecentLogs(state.recent.clone()))
+        .try_init();
+    match result {
+        Ok(()) => *state.filter.lock().unwrap() = Some(handle),
+        Err(e) => eprintln!("[logging] ⚠️ Failed to install logger: {}", e),
+    }
+}
+
+/// Change the log level for the rest of this run and future runs
+#[tauri::command]
+pub async fn set_log_level(
+    app: AppHandle,
+    state: State<'_, LoggingState>,
+    level: String,
+) -> Result<(), String> {
+    let filter = parse_level(&level)?;
+    if let Some(handle) = state.filter.lock().unwrap().as_ref() {
+        handle
+            .modify(|current| *current = filter)
+            .map_err(|e| format!("Failed to change log level: {}", e))?;
+    }
+    let path = persistence::shared_data_file(&app, SETTINGS_FILE)?;
+    persistence::save_json(&path, &LogSettings { level: filter.to_string().to_lowercase() })?;
+    tracing::info!("Log level set to {}", filter);
+    Ok(())
+}
+
+/// The most recent log entries, oldest first
+#[tauri::command]
+pub async fn get_recent_logs(
+    state: State<'_, LoggingState>,
+    n: usize,
+) -> Result<Vec<LogEntry>, String> {
+    let recent = state.recent.lock().unwrap();
+    Ok(recent.iter().skip(recent.len().saturating_sub(n)).cloned().collect())
+}
@@ -1,46 +1,274 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod accessibility;
+mod achievements;
+mod activity;
+mod ai;
+mod ai_tools;
+mod ai_usage;
+mod benchmark;
+mod bindings;
+mod cancellation;
+mod capture_backend;
+mod capture_buffer;
+mod capture_diff;
+mod capture_progress;
+#[cfg(feature = "cli")]
+mod cli;
+mod clipboard;
+mod command_catalog;
 mod commands;
+mod connectivity;
+mod consent;
+mod content_class;
+mod context_budget;
+mod conversations;
+mod crash_report;
+mod curriculum;
+mod daily_summary;
+mod data_export;
+mod debug_bundle;
+mod deep_link;
+mod dev_environment;
+mod diagnostics;
+mod diagnostics_parser;
+mod dictionary;
+mod distraction;
+mod docker;
+mod documents;
+mod event_bus;
+mod events;
+mod exercises;
+mod file_drop;
+mod file_watcher;
+mod flashcards;
+mod focus_timer;
+mod frame_delta;
+mod frame_queue;
+mod frame_validation;
+mod git_insight;
+mod handwriting;
+mod history;
+mod homework;
+mod image_resize;
+mod input_hook;
+mod logging;
+mod math_ocr;
+mod metrics;
+mod models;
+mod monitoring;
+mod narrator;
+mod native_helper;
+mod notes;
+mod observation;
+mod ocr_cache;
+mod ocr_languages;
+mod ocr_layout;
+mod persistence;
+mod ports;
+mod powershell_host;
+mod process_manager;
 mod process_monitor;
+mod profiles;
+mod project_analysis;
+mod project_files;
+mod prompts;
+mod rate_limit;
+mod recent_activity;
+mod remote_view;
+mod roles;
+mod request_queue;
+mod retention;
+mod sandbox;
 mod screen_capture;
+mod screen_recording;
+mod script_runner;
+mod secret_filter;
+mod shutdown;
+mod sync;
+mod temp_files;
+mod terminal;
+mod text_analysis;
+mod transcription;
+mod translation;
+mod typing_practice;
+mod updater;
+mod watchdog;
+mod webcam;
+mod window_elements;
+mod window_match;
+mod worksheet;
+mod ws_server;
 
-use tauri::{Emitter, Manager};
+use tauri::Manager;
 
 fn main() {
-    tauri::Builder::default()
-        .plugin(tauri_plugin_shell::init())
-        .plugin(tauri_plugin_store::Builder::default().build())
-        .manage(screen_capture::ScreenCaptureState::default())
-        .invoke_handler(tauri::generate_handler![
-            commands::capture_screen,
-            commands::get_active_window,
-            commands::get_system_context,
-            commands::list_windows_by_process,
-            commands::capture_window,
-            commands::capture_window_with_ocr,
-            commands::extract_text_from_image,
-            commands::start_monitoring,
-            commands::stop_monitoring,
-            commands::get_capture_interval,
-            commands::set_capture_interval,
-            commands::minimize_window,
-            commands::maximize_window,
-            commands::close_window,
-            commands::execute_command,
-        ])
+    // `--export-bindings [path]` only writes the TypeScript bindings, for `npm run bindings` and CI
+    let mut args = std::env::args().skip(1);
+    if args.next().as_deref() == Some("--export-bindings") {
+        let path = args
+            .next()
+            .map(std::path::PathBuf::from)
+            .unwrap_or_else(|| std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../src/types/bindings.ts"));
+        // Runs without logging set up, so this goes straight to stderr
+        match bindings::export(&path) {
+            Ok(true) => eprintln!("[bindings] 📝 Wrote TypeScript bindings to {}", path.display()),
+            Ok(false) => {}
+            Err(e) => {
+                eprintln!("[bindings] ⚠️ {}", e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    let context = tauri::generate_context!();
+    // `ai-teacher capture ...` runs the capture pipeline without the GUI
+    #[cfg(feature = "cli")]
+    if cli::requested() {
+        std::process::exit(cli::run(context));
+    }
+
+    let builder = tauri::Builder::default()
+        // First, so a second launch hands over its link before starting anything
+        .plugin(tauri_plugin_single_instance::init(deep_link::on_second_instance))
+        .plugin(tauri_plugin_deep_link::init());
+    with_state(builder)
+        .on_window_event(file_drop::handle)
+        .invoke_handler(command_catalog::invoke_handler())
         .setup(|app| {
+            logging::init(app.handle(), &app.state::<logging::LoggingState>());
+            crash_report::install(app.handle());
+            if cfg!(debug_assertions) {
+                let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../src/types/bindings.ts");
+                match bindings::export(&path) {
+                    Ok(true) => tracing::info!("📝 Wrote TypeScript bindings to {}", path.display()),
+                    Ok(false) => {}
+                    Err(e) => tracing::warn!("⚠️ {}", e),
+                }
+            }
+            watchdog::init(app.handle());
+            // Before anything reads per-profile data
+            profiles::init(app.handle());
+            roles::init(app.handle());
+            sandbox::init(app.handle());
+            context_budget::init(app.handle());
+            ai::init(app.handle());
+            ai_usage::init(app.handle());
+            translation::init(app.handle());
+
             // Show and focus the main window
             if let Some(window) = app.get_webview_window("main") {
                 window.show().unwrap_or_default();
                 window.set_focus().unwrap_or_default();
             }
+
+            temp_files::init();
+            capture_backend::init(app.handle());
+            image_resize::init(app.handle());
+            capture_buffer::init(app.handle());
+            math_ocr::init(app.handle());
+            worksheet::init(app.handle());
+            request_queue::init(app.handle());
+            project_files::init(app.handle());
+            activity::init(app.handle());
+            history::init(app.handle());
+            daily_summary::init(app.handle());
+            retention::init(app.handle());
+            observation::init(app.handle());
+            connectivity::start(app.handle().clone());
+            consent::start(app.handle().clone());
+            observation::start(app.handle().clone());
+            focus_timer::start(app.handle().clone());
+            flashcards::start(app.handle().clone());
+            achievements::start(app.handle().clone());
+            daily_summary::start(app.handle().clone());
+            retention::start(app.handle().clone());
+            distraction::start(app.handle().clone());
+            sync::start(app.handle().clone());
+            ws_server::init(app.handle().clone());
+            updater::init(app.handle());
+            powershell_host::init(app.handle());
+            // Last, so a link that started the app finds everything ready
+            deep_link::init(app.handle());
             
             // Emit initial ready event
-            app.emit("app-ready", ()).unwrap();
+            event_bus::emit(app.handle(), events::APP_READY, ()).unwrap();
             Ok(())
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(context)
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            if let tauri::RunEvent::Exit = event {
+                shutdown::run(app);
+            }
+        });
 }
 
+/// Plugins and state shared by the app and the headless CLI
+fn with_state(builder: tauri::Builder<tauri::Wry>) -> tauri::Builder<tauri::Wry> {
+    // Spilled capture frames are temp files, removed with the others on exit
+    let temp_files = temp_files::TempFilesState::default();
+    let capture_buffer = capture_buffer::CaptureBufferState::new(temp_files.clone());
+    builder
+        .plugin(tauri_plugin_shell::init())
+        .plugin(tauri_plugin_store::Builder::default().build())
+        .plugin(tauri_plugin_notification::init())
+        .plugin(tauri_plugin_updater::Builder::new().build())
+        .manage(event_bus::EventBusState::new(capture_buffer.clone()))
+        .manage(metrics::MetricsState::default())
+        .manage(ocr_cache::OcrCacheState::default())
+        .manage(logging::LoggingState::default())
+        .manage(crash_report::CrashReportState::default())
+        .manage(watchdog::WatchdogState::default())
+        .manage(profiles::ProfilesState::default())
+        .manage(roles::RolesState::default())
+        .manage(rate_limit::RateLimiterState::default())
+        .manage(powershell_host::PowerShellHostState::default())
+        .manage(native_helper::NativeHelperState::default())
+        .manage(screen_capture::ScreenCaptureState::default())
+        .manage(capture_diff::CaptureDiffState::default())
+        .manage(capture_backend::CaptureBackendState::default())
+        .manage(image_resize::ResizeState::default())
+        .manage(screen_recording::ScreenRecordingState::default())
+        .manage(recent_activity::RecentActivityState::new(capture_buffer.clone()))
+        .manage(frame_delta::FrameDeltaState::default())
+        .manage(monitoring::MonitoringState::default())
+        .manage(consent::ConsentState::default())
+        .manage(observation::ObservationState::default())
+        .manage(content_class::ContentClassState::default())
+        .manage(math_ocr::MathOcrState::default())
+        .manage(history::HistoryState::default())
+        .manage(cancellation::CancellationState::default())
+        .manage(temp_files)
+        .manage(homework::HomeworkDetectionState::default())
+        .manage(connectivity::ConnectivityState::default())
+        .manage(request_queue::RequestQueueState::default())
+        .manage(process_manager::ProcessManagerState::default())
+        .manage(terminal::TerminalState::default())
+        .manage(project_files::ProjectRootsState::default())
+        .manage(file_watcher::FileWatcherState::default())
+        .manage(input_hook::InputHookState::default())
+        .manage(activity::ActivityState::default())
+        .manage(focus_timer::FocusTimerState::default())
+        .manage(daily_summary::DailySummaryState::default())
+        .manage(retention::RetentionState::default())
+        .manage(script_runner::ScriptRunnerState::default())
+        .manage(sandbox::SandboxState::default())
+        .manage(context_budget::ContextBudgetState::default())
+        .manage(ai::AiState::default())
+        .manage(ai_usage::AiUsageState::default())
+        .manage(models::ModelsState::default())
+        .manage(dictionary::DictionaryState::default())
+        .manage(narrator::NarratorState::default())
+        .manage(typing_practice::TypingPracticeState::default())
+        .manage(worksheet::WorksheetState::default())
+        .manage(translation::TranslationState::default())
+        .manage(distraction::DistractionState::default())
+        .manage(sync::SyncState::default())
+        .manage(ws_server::IntegrationServerState::default())
+        .manage(remote_view::RemoteViewState::new(capture_buffer.clone()))
+        .manage(capture_buffer)
+        .manage(updater::UpdaterState::default())
+}
@@ -0,0 +1,120 @@
+//! Formula recognition. Windows OCR turns equations into noise, so math images go to a
+//! LaTeX-OCR server instead. The default endpoint matches pix2tex's API
+//! (`python -m pix2tex.api.run`), which takes the image as a multipart `file` field.
+
+use base64::{engine::general_purpose, Engine as _};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, State};
+
+use crate::persistence;
+
+const SETTINGS_FILE: &str = "math_ocr_settings.json";
+const DEFAULT_ENDPOINT: &str = "http://127.0.0.1:8502/predict/";
+/// Model inference on CPU can take a while for large images
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct MathOcrSettings {
+    /// URL the image is POSTed to
+    pub endpoint: String,
+}
+
+impl Default for MathOcrSettings {
+    fn default() -> Self {
+        Self { endpoint: DEFAULT_ENDPOINT.to_string() }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct MathRecognition {
+    pub latex: String,
+    pub duration_ms: u64,
+}
+
+#[derive(Clone, Default)]
+pub struct MathOcrState {
+    settings: Arc<Mutex<MathOcrSettings>>,
+}
+
+/// Load the saved endpoint
+pub fn init(app: &AppHandle) {
+    let settings: MathOcrSettings = persistence::data_file(app, SETTINGS_FILE)
+        .map(|path| persistence::load_json(&path))
+        .unwrap_or_default();
+    *app.state::<MathOcrState>().settings.lock().unwrap() = settings;
+}
+
+/// pix2tex answers with a bare JSON string; other servers tend to wrap it in an object
+fn extract_latex(body: &str) -> Option<String> {
+    let latex = match serde_json::from_str::<serde_json::Value>(body) {
+        Ok(serde_json::Value::String(latex)) => latex,
+        Ok(value) => ["latex", "text", "result"]
+            .iter()
+            .find_map(|key| value[key].as_str())
+            .map(String::from)?,
+        Err(_) => body.to_string(),
+    };
+    let latex = latex.trim().to_string();
+    (!latex.is_empty()).then_some(latex)
+}
+
+/// Send a formula image to the LaTeX-OCR server and return the LaTeX it reads
+pub(crate) async fn recognize(app: &AppHandle, image_bytes: Vec<u8>) -> Result<String, String> {
+    let endpoint = app.state::<MathOcrState>().settings.lock().unwrap().endpoint.clone();
+    let part = reqwest::multipart::Part::bytes(image_bytes)
+        .file_name("formula.png")
+        .mime_str("image/png")
+        .map_err(|e| format!("Failed to build request: {}", e))?;
+    let client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let body = client
+        .post(&endpoint)
+        .multipart(reqwest::multipart::Form::new().part("file", part))
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| format!("Math OCR server at {} failed: {}", endpoint, e))?
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read math OCR response: {}", e))?;
+    extract_latex(&body).ok_or_else(|| "No formula recognized".to_string())
+}
+
+/// Read a formula from an image and return it as LaTeX
+#[tauri::command]
+pub async fn recognize_math(app: AppHandle, image_base64: String) -> Result<MathRecognition, String> {
+    let image_bytes = general_purpose::STANDARD
+        .decode(image_base64.trim())
+        .map_err(|e| format!("Invalid base64 image: {}", e))?;
+
+    let started = Instant::now();
+    let latex = recognize(&app, image_bytes).await?;
+    tracing::info!("🧮 Recognized formula ({} chars)", latex.len());
+    Ok(MathRecognition {
+        latex,
+        duration_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
+#[tauri::command]
+pub async fn get_math_ocr_settings(state: State<'_, MathOcrState>) -> Result<MathOcrSettings, String> {
+    Ok(state.settings.lock().unwrap().clone())
+}
+
+#[tauri::command]
+pub async fn set_math_ocr_settings(
+    app: AppHandle,
+    state: State<'_, MathOcrState>,
+    settings: MathOcrSettings,
+) -> Result<(), String> {
+    url::Url::parse(&settings.endpoint).map_err(|e| format!("Invalid endpoint URL: {}", e))?;
+    let path = persistence::data_file(&app, SETTINGS_FILE)?;
+    persistence::save_json(&path, &settings)?;
+    *state.settings.lock().unwrap() = settings;
+    Ok(())
+}
@@ -0,0 +1,233 @@
+//! Counters and latency histograms for captures, OCR and AI calls since the app started,
+//! shown by the performance panel through `get_metrics`. When enabled in the integration
+//! server settings, the same numbers are served in the Prometheus text format at
+//! `/metrics` on that server's port.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+
+/// Full-screen and window captures
+pub(crate) const CAPTURE: &str = "capture";
+/// OCR runs, cache hits excluded
+pub(crate) const OCR: &str = "ocr";
+/// AI completions, streamed or not, across failovers
+pub(crate) const AI_CALL: &str = "ai_call";
+pub(crate) const OCR_CACHE_HITS: &str = "ocr_cache_hits";
+pub(crate) const OCR_CACHE_MISSES: &str = "ocr_cache_misses";
+/// Monitor frames dropped because OCR or emission couldn't keep up
+pub(crate) const FRAMES_DROPPED: &str = "frames_dropped";
+
+/// Upper bounds of the latency buckets in milliseconds; slower calls land in the overflow bucket
+const BUCKETS_MS: [f64; 11] = [10.0, 25.0, 50.0, 100.0, 250.0, 500.0, 1000.0, 2500.0, 5000.0, 10000.0, 30000.0];
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Counter {
+    pub name: String,
+    pub value: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct LatencyBucket {
+    /// Upper bound; None for the overflow bucket
+    pub le_ms: Option<f64>,
+    /// Calls at or under `le_ms`, the faster buckets included
+    pub count: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct LatencyHistogram {
+    pub name: String,
+    pub count: u64,
+    pub errors: u64,
+    /// Time spent in all the calls together
+    pub sum_ms: f64,
+    pub avg_ms: f64,
+    /// Estimated from the buckets: the bound of the bucket the percentile falls in
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub max_ms: f64,
+    pub buckets: Vec<LatencyBucket>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Metrics {
+    /// Unix milliseconds the counts start from
+    pub since: i64,
+    pub counters: Vec<Counter>,
+    pub histograms: Vec<LatencyHistogram>,
+    /// Share of OCR requests answered from the cache; None before the first one
+    pub ocr_cache_hit_rate: Option<f64>,
+    /// Events waiting in the replay buffer for a reloading webview
+    pub event_queue_depth: usize,
+    pub event_queue_bytes: usize,
+    /// Events emitted since the app started
+    pub events_emitted: u64,
+}
+
+#[derive(Default)]
+struct Histogram {
+    /// Calls per bucket, the last one being the overflow bucket
+    counts: [u64; BUCKETS_MS.len() + 1],
+    errors: u64,
+    sum_ms: f64,
+    max_ms: f64,
+}
+
+impl Histogram {
+    fn count(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    fn cumulative(&self) -> Vec<LatencyBucket> {
+        let mut total = 0;
+        self.counts
+            .iter()
+            .enumerate()
+            .map(|(index, count)| {
+                total += count;
+                LatencyBucket { le_ms: BUCKETS_MS.get(index).copied(), count: total }
+            })
+            .collect()
+    }
+
+    fn percentile(&self, p: f64) -> f64 {
+        let target = (self.count() as f64 * p).ceil() as u64;
+        self.cumulative()
+            .into_iter()
+            .find(|bucket| bucket.count >= target.max(1))
+            .and_then(|bucket| bucket.le_ms)
+            .map_or(self.max_ms, |bound| bound.min(self.max_ms))
+    }
+}
+
+struct Registry {
+    since: i64,
+    counters: BTreeMap<&'static str, u64>,
+    histograms: BTreeMap<&'static str, Histogram>,
+}
+
+#[derive(Clone)]
+pub struct MetricsState {
+    registry: Arc<Mutex<Registry>>,
+}
+
+impl Default for MetricsState {
+    fn default() -> Self {
+        Self {
+            registry: Arc::new(Mutex::new(Registry {
+                since: chrono::Utc::now().timestamp_millis(),
+                counters: BTreeMap::new(),
+                histograms: BTreeMap::new(),
+            })),
+        }
+    }
+}
+
+impl MetricsState {
+    pub fn increment(&self, name: &'static str) {
+        self.add(name, 1);
+    }
+
+    pub fn add(&self, name: &'static str, value: u64) {
+        *self.registry.lock().unwrap().counters.entry(name).or_default() += value;
+    }
+
+    /// Record one call to `name` that took `elapsed`
+    pub fn observe(&self, name: &'static str, elapsed: Duration, ok: bool) {
+        let ms = elapsed.as_secs_f64() * 1000.0;
+        let mut registry = self.registry.lock().unwrap();
+        let histogram = registry.histograms.entry(name).or_default();
+        let bucket = BUCKETS_MS.iter().position(|bound| ms <= *bound).unwrap_or(BUCKETS_MS.len());
+        histogram.counts[bucket] += 1;
+        histogram.sum_ms += ms;
+        histogram.max_ms = histogram.max_ms.max(ms);
+        if !ok {
+            histogram.errors += 1;
+        }
+    }
+}
+
+/// Run `operation`, recording how long it took under `name`
+pub(crate) async fn timed<T>(
+    app: &AppHandle,
+    name: &'static str,
+    operation: impl Future<Output = Result<T, String>>,
+) -> Result<T, String> {
+    let started = Instant::now();
+    let result = operation.await;
+    app.state::<MetricsState>().observe(name, started.elapsed(), result.is_ok());
+    result
+}
+
+fn snapshot(app: &AppHandle) -> Metrics {
+    let queue = crate::event_bus::depth(app);
+    let state = app.state::<MetricsState>();
+    let registry = state.registry.lock().unwrap();
+    let round = |ms: f64| (ms * 100.0).round() / 100.0;
+    let counter = |name| registry.counters.get(name).copied().unwrap_or_default();
+    let (hits, misses) = (counter(OCR_CACHE_HITS), counter(OCR_CACHE_MISSES));
+    Metrics {
+        since: registry.since,
+        counters: registry.counters.iter().map(|(name, value)| Counter { name: name.to_string(), value: *value }).collect(),
+        histograms: registry
+            .histograms
+            .iter()
+            .map(|(name, histogram)| {
+                let count = histogram.count();
+                LatencyHistogram {
+                    name: name.to_string(),
+                    count,
+                    errors: histogram.errors,
+                    sum_ms: round(histogram.sum_ms),
+                    avg_ms: round(if count > 0 { histogram.sum_ms / count as f64 } else { 0.0 }),
+                    p50_ms: round(histogram.percentile(0.5)),
+                    p95_ms: round(histogram.percentile(0.95)),
+                    max_ms: round(histogram.max_ms),
+                    buckets: histogram.cumulative(),
+                }
+            })
+            .collect(),
+        ocr_cache_hit_rate: (hits + misses > 0).then(|| round(hits as f64 / (hits + misses) as f64)),
+        event_queue_depth: queue.events,
+        event_queue_bytes: queue.bytes,
+        events_emitted: queue.emitted,
+    }
+}
+
+/// The metrics in the Prometheus text exposition format
+pub(crate) fn prometheus(app: &AppHandle) -> String {
+    let metrics = snapshot(app);
+    let mut out = String::new();
+    for counter in &metrics.counters {
+        let name = format!("ai_teacher_{}_total", counter.name);
+        let _ = writeln!(out, "# TYPE {} counter\n{} {}", name, name, counter.value);
+    }
+    for histogram in &metrics.histograms {
+        let name = format!("ai_teacher_{}_duration_seconds", histogram.name);
+        let _ = writeln!(out, "# TYPE {} histogram", name);
+        for bucket in &histogram.buckets {
+            let le = bucket.le_ms.map_or("+Inf".to_string(), |ms| (ms / 1000.0).to_string());
+            let _ = writeln!(out, "{}_bucket{{le=\"{}\"}} {}", name, le, bucket.count);
+        }
+        let _ = writeln!(out, "{}_sum {}\n{}_count {}", name, histogram.sum_ms / 1000.0, name, histogram.count);
+        let errors = format!("ai_teacher_{}_errors_total", histogram.name);
+        let _ = writeln!(out, "# TYPE {} counter\n{} {}", errors, errors, histogram.errors);
+    }
+    let _ = writeln!(out, "# TYPE ai_teacher_event_queue_depth gauge\nai_teacher_event_queue_depth {}", metrics.event_queue_depth);
+    let _ = writeln!(out, "# TYPE ai_teacher_event_queue_bytes gauge\nai_teacher_event_queue_bytes {}", metrics.event_queue_bytes);
+    let _ = writeln!(out, "# TYPE ai_teacher_events_emitted_total counter\nai_teacher_events_emitted_total {}", metrics.events_emitted);
+    out
+}
+
+/// Capture, OCR and AI call counts and latencies since the app started, with the OCR cache
+/// hit rate and the event queue depth
+#[tauri::command]
+pub async fn get_metrics(app: AppHandle) -> Result<Metrics, String> {
+    Ok(snapshot(&app))
+}
@@ -0,0 +1,483 @@
+//! Local GGUF models for fully offline tutoring when Ollama isn't installed. Models download
+//! into a folder shared by all profiles, resuming a partial download where it stopped, after a
+//! free-space check; progress arrives as `model-download-progress` events and the file is only
+//! kept once its SHA-256 matches. A downloaded model is loaded in-process through the llama.cpp
+//! bindings (the `llama-cpp` feature) and answers the `llamacpp` AI provider. whisper.cpp's ggml
+//! speech models download the same way for offline transcription.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, State};
+use tokio::sync::mpsc;
+
+use crate::ai::{ChatRequest, TokenUsage};
+use crate::cancellation::CancellationState;
+use crate::persistence;
+
+#[cfg(feature = "llama-cpp")]
+mod llama;
+
+/// Stands in for the bindings in builds without the `llama-cpp` feature
+#[cfg(not(feature = "llama-cpp"))]
+mod llama {
+    use std::path::Path;
+
+    use crate::ai::{ChatRequest, TokenUsage};
+
+    const UNAVAILABLE: &str = "This build can't run local models; it was built without the llama-cpp feature";
+
+    pub(super) struct LlamaModel;
+
+    pub(super) fn load(_path: &Path) -> Result<LlamaModel, String> {
+        Err(UNAVAILABLE.to_string())
+    }
+
+    pub(super) fn generate(
+        _model: &LlamaModel,
+        _request: &ChatRequest,
+        _max_tokens: u32,
+        _on_text: impl FnMut(String) -> bool,
+    ) -> Result<TokenUsage, String> {
+        Err(UNAVAILABLE.to_string())
+    }
+}
+
+const MODELS_DIR: &str = "models";
+const REGISTRY_FILE: &str = "models.json";
+/// Free space left over after a download, so the disk isn't filled to the last byte
+const DISK_MARGIN_BYTES: u64 = 512 * 1024 * 1024;
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(20);
+const GGUF_MAGIC: &[u8; 4] = b"GGUF";
+/// whisper.cpp's ggml format: the magic 0x67676d6c stored little-endian
+const GGML_MAGIC: &[u8; 4] = b"lmgg";
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct LocalModel {
+    pub id: String,
+    pub url: String,
+    /// Expected SHA-256 of the file, lowercase hex
+    pub sha256: String,
+    pub size_bytes: u64,
+    pub downloaded_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ModelDownloadRequest {
+    /// Name for the model: letters, digits, '.', '-' and '_'
+    pub id: String,
+    /// HTTPS address of the .gguf (or whisper.cpp .bin) file
+    pub url: String,
+    /// The file's published SHA-256, lowercase or uppercase hex
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ModelDownloadStage {
+    Downloading,
+    Verifying,
+    Done,
+    Failed,
+}
+
+/// Payload of the `model-download-progress` event
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ModelDownloadProgress {
+    pub id: String,
+    pub stage: ModelDownloadStage,
+    /// Including bytes kept from an earlier, interrupted download
+    pub downloaded_bytes: u64,
+    pub total_bytes: Option<u64>,
+    pub detail: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ModelVerification {
+    pub id: String,
+    pub ok: bool,
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct LoadedModelStatus {
+    pub model_id: String,
+    pub loaded_at: i64,
+}
+
+struct LoadedModel {
+    status: LoadedModelStatus,
+    /// Held for a whole answer, so requests take turns
+    model: Mutex<llama::LlamaModel>,
+}
+
+#[derive(Clone, Default)]
+pub struct ModelsState {
+    /// Serializes changes to the registry and the model folder
+    registry: Arc<Mutex<()>>,
+    downloading: Arc<Mutex<Vec<String>>>,
+    /// The model answering the `llamacpp` provider; an answer in progress keeps its own handle
+    loaded: Arc<Mutex<Option<Arc<LoadedModel>>>>,
+}
+
+/// Takes a model id off the in-progress list when the download ends either way
+struct DownloadSlot {
+    state: ModelsState,
+    id: String,
+}
+
+impl Drop for DownloadSlot {
+    fn drop(&mut self) {
+        self.state.downloading.lock().unwrap().retain(|id| id != &self.id);
+    }
+}
+
+fn models_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = persistence::shared_data_file(app, MODELS_DIR)?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create the models folder: {}", e))?;
+    Ok(dir)
+}
+
+fn model_path(dir: &Path, id: &str) -> PathBuf {
+    dir.join(format!("{}.gguf", id))
+}
+
+fn load_registry(app: &AppHandle) -> Result<Vec<LocalModel>, String> {
+    Ok(persistence::load_json(&persistence::shared_data_file(app, REGISTRY_FILE)?))
+}
+
+fn save_registry(app: &AppHandle, models: &[LocalModel]) -> Result<(), String> {
+    persistence::save_json(&persistence::shared_data_file(app, REGISTRY_FILE)?, &models)
+}
+
+fn validate_id(id: &str) -> Result<(), String> {
+    let valid_chars = id.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'));
+    if id.is_empty() || id.len() > 64 || !valid_chars || id.starts_with('.') {
+        return Err("Model names must be 1 to 64 letters, digits, '.', '-' or '_'".to_string());
+    }
+    Ok(())
+}
+
+fn find_model(app: &AppHandle, id: &str) -> Result<LocalModel, String> {
+    load_registry(app)?
+        .into_iter()
+        .find(|m| m.id == id)
+        .ok_or_else(|| format!("No downloaded model named '{}'", id))
+}
+
+/// Path of a downloaded model's file
+pub(crate) fn model_file(app: &AppHandle, id: &str) -> Result<PathBuf, String> {
+    find_model(app, id)?;
+    let path = model_path(&models_dir(app)?, id);
+    if !path.is_file() {
+        return Err(format!("The file of model '{}' is missing; download it again", id));
+    }
+    Ok(path)
+}
+
+/// SHA-256 of a file as lowercase hex, read in blocks
+fn sha256_file(path: &Path) -> Result<String, String> {
+    let mut file = fs::File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 1024 * 1024];
+    loop {
+        let read = file.read(&mut buffer).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// A GGUF (llama.cpp) or ggml (whisper.cpp) model file
+fn has_model_header(path: &Path) -> bool {
+    let mut magic = [0u8; 4];
+    fs::File::open(path).and_then(|mut f| f.read_exact(&mut magic)).is_ok() && (&magic == GGUF_MAGIC || &magic == GGML_MAGIC)
+}
+
+fn emit(app: &AppHandle, progress: ModelDownloadProgress) {
+    let _ = crate::event_bus::emit(app, crate::events::MODEL_DOWNLOAD_PROGRESS, progress);
+}
+
+/// Download to `part`, continuing from its current length when the server supports ranges.
+/// Returns the file's total size.
+async fn download_to(
+    app: &AppHandle,
+    request: &ModelDownloadRequest,
+    part: &Path,
+    operation: &crate::cancellation::OperationGuard,
+) -> Result<u64, String> {
+    let existing = fs::metadata(part).map(|m| m.len()).unwrap_or(0);
+    let client = reqwest::Client::builder()
+        .connect_timeout(CONNECT_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let mut builder = client.get(&request.url);
+    if existing > 0 {
+        builder = builder.header(reqwest::header::RANGE, format!("bytes={}-", existing));
+    }
+    let mut response = builder.send().await.map_err(|e| format!("Download failed: {}", e))?;
+    let status = response.status();
+    if status == reqwest::StatusCode::RANGE_NOT_SATISFIABLE && existing > 0 {
+        // The partial file already holds everything
+        return Ok(existing);
+    }
+    if !status.is_success() {
+        return Err(format!("Download failed: HTTP {}", status));
+    }
+    let resumed = status == reqwest::StatusCode::PARTIAL_CONTENT;
+    let mut downloaded = if resumed { existing } else { 0 };
+    let total = response.content_length().map(|length| length + downloaded);
+    if resumed {
+        tracing::info!("📦 Resuming '{}' at {} MB", request.id, existing / 1024 / 1024);
+    }
+
+    if let Some(total) = total {
+        let free = crate::diagnostics::free_bytes(part.parent().unwrap_or(part))?;
+        let needed = total - downloaded + DISK_MARGIN_BYTES;
+        if free < needed {
+            return Err(format!(
+                "Not enough disk space: the model needs {:.1} GB, {:.1} GB is free",
+                needed as f64 / 1e9,
+                free as f64 / 1e9
+            ));
+        }
+    }
+
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(resumed)
+        .write(true)
+        .truncate(!resumed)
+        .open(part)
+        .map_err(|e| format!("Failed to open {}: {}", part.display(), e))?;
+    let mut last_event = Instant::now();
+    while let Some(chunk) = response.chunk().await.map_err(|e| format!("Download broke off: {}", e))? {
+        // The partial file is kept, so a cancelled download resumes next time
+        operation.check()?;
+        file.write_all(&chunk).map_err(|e| format!("Failed to write {}: {}", part.display(), e))?;
+        downloaded += chunk.len() as u64;
+        if last_event.elapsed() >= PROGRESS_INTERVAL {
+            last_event = Instant::now();
+            emit(app, ModelDownloadProgress {
+                id: request.id.clone(),
+                stage: ModelDownloadStage::Downloading,
+                downloaded_bytes: downloaded,
+                total_bytes: total,
+                detail: None,
+            });
+        }
+    }
+    file.flush().map_err(|e| format!("Failed to write {}: {}", part.display(), e))?;
+    Ok(downloaded)
+}
+
+async fn download_and_verify(
+    app: &AppHandle,
+    request: &ModelDownloadRequest,
+    operation: &crate::cancellation::OperationGuard,
+) -> Result<LocalModel, String> {
+    let dir = models_dir(app)?;
+    let part = dir.join(format!("{}.gguf.part", request.id));
+    let size = download_to(app, request, &part, operation).await?;
+
+    emit(app, ModelDownloadProgress {
+        id: request.id.clone(),
+        stage: ModelDownloadStage::Verifying,
+        downloaded_bytes: size,
+        total_bytes: Some(size),
+        detail: None,
+    });
+    let hashed = part.clone();
+    let sha256 = tokio::task::spawn_blocking(move || sha256_file(&hashed))
+        .await
+        .map_err(|e| format!("Verification failed: {}", e))??;
+    if sha256 != request.sha256 {
+        // A corrupt or wrong file can't be resumed into a right one
+        let _ = fs::remove_file(&part);
+        return Err(format!("Checksum mismatch: expected {}, got {}", request.sha256, sha256));
+    }
+    if !has_model_header(&part) {
+        let _ = fs::remove_file(&part);
+        return Err("The download is not a GGUF or ggml model".to_string());
+    }
+
+    let state = app.state::<ModelsState>();
+    let _registry = state.registry.lock().unwrap();
+    fs::rename(&part, model_path(&dir, &request.id)).map_err(|e| format!("Failed to store the model: {}", e))?;
+    let model = LocalModel {
+        id: request.id.clone(),
+        url: request.url.clone(),
+        sha256,
+        size_bytes: size,
+        downloaded_at: chrono::Utc::now().timestamp(),
+    };
+    let mut models = load_registry(app)?;
+    models.retain(|m| m.id != model.id);
+    models.push(model.clone());
+    save_registry(app, &models)?;
+    Ok(model)
+}
+
+/// Downloaded models
+#[tauri::command]
+pub async fn list_local_models(app: AppHandle) -> Result<Vec<LocalModel>, String> {
+    load_registry(&app)
+}
+
+/// Download a GGUF model, resuming an interrupted download, and keep it once its SHA-256
+/// matches. Progress arrives as `model-download-progress` events; `request_id` makes it
+/// cancellable with `cancel_operation`.
+#[tauri::command]
+pub async fn download_model(
+    app: AppHandle,
+    state: State<'_, ModelsState>,
+    request: ModelDownloadRequest,
+    request_id: Option<String>,
+) -> Result<LocalModel, String> {
+    validate_id(&request.id)?;
+    let url = url::Url::parse(&request.url).map_err(|e| format!("Invalid model URL: {}", e))?;
+    if url.scheme() != "https" {
+        return Err("Models can only be downloaded over HTTPS".to_string());
+    }
+    let request = ModelDownloadRequest { sha256: request.sha256.trim().to_lowercase(), ..request };
+    if request.sha256.len() != 64 || !request.sha256.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err("The SHA-256 must be 64 hex digits".to_string());
+    }
+    {
+        let mut downloading = state.downloading.lock().unwrap();
+        if downloading.contains(&request.id) {
+            return Err(format!("'{}' is already downloading", request.id));
+        }
+        downloading.push(request.id.clone());
+    }
+    let _slot = DownloadSlot { state: state.inner().clone(), id: request.id.clone() };
+
+    tracing::info!("📦 Downloading model '{}' from {}", request.id, request.url);
+    let operation = app.state::<CancellationState>().register(request_id.as_deref());
+    let result = download_and_verify(&app, &request, &operation).await;
+    let (stage, detail) = match &result {
+        Ok(_) => (ModelDownloadStage::Done, None),
+        Err(e) => (ModelDownloadStage::Failed, Some(e.clone())),
+    };
+    let size = result.as_ref().map(|m| m.size_bytes).unwrap_or(0);
+    emit(&app, ModelDownloadProgress {
+        id: request.id.clone(),
+        stage,
+        downloaded_bytes: size,
+        total_bytes: result.as_ref().ok().map(|m| m.size_bytes),
+        detail,
+    });
+    match &result {
+        Ok(model) => tracing::info!("📦 Model '{}' ready ({} MB)", model.id, model.size_bytes / 1024 / 1024),
+        Err(e) => tracing::warn!("⚠️ Model download '{}' failed: {}", request.id, e),
+    }
+    result
+}
+
+/// Re-hash a downloaded model to check it is still intact
+#[tauri::command]
+pub async fn verify_model(app: AppHandle, id: String) -> Result<ModelVerification, String> {
+    let model = find_model(&app, &id)?;
+    let path = model_path(&models_dir(&app)?, &id);
+    let sha256 = tokio::task::spawn_blocking(move || sha256_file(&path))
+        .await
+        .map_err(|e| format!("Verification failed: {}", e))??;
+    Ok(ModelVerification { ok: sha256 == model.sha256, id, sha256 })
+}
+
+/// Delete a downloaded model, or the partial file of an interrupted download
+#[tauri::command]
+pub async fn delete_model(app: AppHandle, state: State<'_, ModelsState>, id: String) -> Result<(), String> {
+    validate_id(&id)?;
+    if state.downloading.lock().unwrap().contains(&id) {
+        return Err(format!("'{}' is downloading; cancel it first", id));
+    }
+    if state.loaded.lock().unwrap().as_ref().is_some_and(|m| m.status.model_id == id) {
+        return Err(format!("'{}' is running; stop it first", id));
+    }
+    let dir = models_dir(&app)?;
+    let _registry = state.registry.lock().unwrap();
+    let mut models = load_registry(&app)?;
+    let known = models.iter().any(|m| m.id == id);
+    let mut removed = false;
+    for path in [model_path(&dir, &id), dir.join(format!("{}.gguf.part", id))] {
+        if path.exists() {
+            fs::remove_file(&path).map_err(|e| format!("Failed to delete {}: {}", path.display(), e))?;
+            removed = true;
+        }
+    }
+    if !known && !removed {
+        return Err(format!("No downloaded model named '{}'", id));
+    }
+    models.retain(|m| m.id != id);
+    save_registry(&app, &models)?;
+    tracing::info!("🗑️ Deleted model '{}'", id);
+    Ok(())
+}
+
+/// Load a downloaded model for the `llamacpp` AI provider, replacing any model already loaded
+#[tauri::command]
+pub async fn start_local_model(
+    app: AppHandle,
+    state: State<'_, ModelsState>,
+    id: String,
+) -> Result<LoadedModelStatus, String> {
+    let path = model_file(&app, &id)?;
+    let model = tauri::async_runtime::spawn_blocking(move || llama::load(&path))
+        .await
+        .map_err(|e| format!("Failed to load the model: {}", e))??;
+    let status = LoadedModelStatus { model_id: id, loaded_at: chrono::Utc::now().timestamp() };
+    let loaded = Arc::new(LoadedModel { status: status.clone(), model: Mutex::new(model) });
+    // The replaced model is freed once an answer still using it ends
+    *state.loaded.lock().unwrap() = Some(loaded);
+    tracing::info!("🦙 Loaded local model '{}'", status.model_id);
+    Ok(status)
+}
+
+/// Unload the local model, if any
+#[tauri::command]
+pub async fn stop_local_model(state: State<'_, ModelsState>) -> Result<(), String> {
+    if let Some(loaded) = state.loaded.lock().unwrap().take() {
+        tracing::info!("🦙 Unloaded local model '{}'", loaded.status.model_id);
+    }
+    Ok(())
+}
+
+/// The local model loaded, if any
+#[tauri::command]
+pub async fn get_loaded_local_model(state: State<'_, ModelsState>) -> Result<Option<LoadedModelStatus>, String> {
+    Ok(state.loaded.lock().unwrap().as_ref().map(|loaded| loaded.status.clone()))
+}
+
+/// Answer `request` with the loaded model, sending the text to `deltas` as it is generated.
+/// Generation stops early once `deltas` is closed.
+pub(crate) async fn generate(
+    app: &AppHandle,
+    request: &ChatRequest,
+    max_tokens: u32,
+    deltas: mpsc::Sender<String>,
+) -> Result<Option<TokenUsage>, String> {
+    let loaded = app
+        .state::<ModelsState>()
+        .loaded
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or("No local model is loaded; start one first")?;
+    let request = request.clone();
+    let usage = tauri::async_runtime::spawn_blocking(move || {
+        let model = loaded.model.lock().unwrap();
+        llama::generate(&model, &request, max_tokens, |text| deltas.blocking_send(text).is_ok())
+    })
+    .await
+    .map_err(|e| format!("The local model failed: {}", e))??;
+    Ok(Some(usage))
+}
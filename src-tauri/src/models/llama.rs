@@ -0,0 +1,136 @@
+//! In-process inference for local GGUF models through the llama.cpp bindings. One context is
+//! created per request and dropped after it, so nothing of one conversation leaks into the next.
+
+use llama_cpp_2::context::params::LlamaContextParams;
+use llama_cpp_2::llama_backend::LlamaBackend;
+use llama_cpp_2::llama_batch::LlamaBatch;
+use llama_cpp_2::model::params::LlamaModelParams;
+use llama_cpp_2::model::LlamaChatMessage;
+use llama_cpp_2::sampling::LlamaSampler;
+use std::num::NonZeroU32;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use crate::ai::{ChatRequest, ChatRole, TokenUsage};
+
+pub(super) use llama_cpp_2::model::LlamaModel;
+
+/// Upper bound on the context window; smaller when the model was trained on less
+const CONTEXT_TOKENS: u32 = 4096;
+/// Prompt tokens decoded per call
+const BATCH_TOKENS: usize = 512;
+const TEMPERATURE: f32 = 0.7;
+const TOP_P: f32 = 0.95;
+
+/// llama.cpp's backend is set up once per process and lives until exit
+fn backend() -> Result<&'static LlamaBackend, String> {
+    static BACKEND: OnceLock<Result<LlamaBackend, String>> = OnceLock::new();
+    BACKEND
+        .get_or_init(|| LlamaBackend::init().map_err(|e| format!("Failed to start llama.cpp: {}", e)))
+        .as_ref()
+        .map_err(Clone::clone)
+}
+
+/// Load a GGUF file. Slow for large models: call it from a blocking thread.
+pub(super) fn load(path: &Path) -> Result<LlamaModel, String> {
+    LlamaModel::load_from_file(backend()?, path, &LlamaModelParams::default())
+        .map_err(|e| format!("Failed to load the model: {}", e))
+}
+
+fn chat_message(role: &str, content: &str) -> Result<LlamaChatMessage, String> {
+    LlamaChatMessage::new(role.to_string(), content.to_string()).map_err(|e| format!("Invalid chat message: {}", e))
+}
+
+/// Answer `request` with `model`, passing the text to `on_text` as it is generated, and return
+/// the tokens used. Stops early when `on_text` returns false. Blocks for the whole answer.
+pub(super) fn generate(
+    model: &LlamaModel,
+    request: &ChatRequest,
+    max_tokens: u32,
+    mut on_text: impl FnMut(String) -> bool,
+) -> Result<TokenUsage, String> {
+    let mut chat = Vec::new();
+    if let Some(system) = &request.system {
+        chat.push(chat_message("system", system)?);
+    }
+    for message in &request.messages {
+        let role = match message.role {
+            ChatRole::User => "user",
+            ChatRole::Assistant => "assistant",
+        };
+        chat.push(chat_message(role, &message.content)?);
+    }
+    let template = model.chat_template(None).map_err(|e| format!("The model has no chat template: {}", e))?;
+    let prompt = model
+        .apply_chat_template(&template, &chat, true)
+        .map_err(|e| format!("Failed to build the prompt: {}", e))?;
+
+    let vocab = model.vocab();
+    let tokens = vocab.tokenize(prompt.as_bytes(), true, true);
+    let context_tokens = CONTEXT_TOKENS.min(model.n_ctx_train()).max(1);
+    if tokens.is_empty() || tokens.len() >= context_tokens as usize {
+        return Err(format!(
+            "The conversation doesn't fit the local model ({} tokens, at most {})",
+            tokens.len(),
+            context_tokens
+        ));
+    }
+    let params = LlamaContextParams::default()
+        .with_n_ctx(NonZeroU32::new(context_tokens))
+        .with_n_batch(BATCH_TOKENS as u32);
+    let mut context = model
+        .new_context(backend()?, params)
+        .map_err(|e| format!("Failed to create a llama.cpp context: {}", e))?;
+
+    // The prompt goes in batch-sized chunks, with logits only for its last token
+    let mut batch = LlamaBatch::new(BATCH_TOKENS, 1);
+    for (chunk_index, chunk) in tokens.chunks(BATCH_TOKENS).enumerate() {
+        batch.clear();
+        for (offset, token) in chunk.iter().enumerate() {
+            let position = chunk_index * BATCH_TOKENS + offset;
+            batch
+                .add(*token, position as i32, &[0], position == tokens.len() - 1)
+                .map_err(|e| format!("Failed to queue the prompt: {}", e))?;
+        }
+        context.decode(&mut batch).map_err(|e| format!("llama.cpp failed to read the prompt: {}", e))?;
+    }
+
+    let seed = u32::from_le_bytes(uuid::Uuid::new_v4().as_bytes()[..4].try_into().unwrap());
+    let mut sampler = LlamaSampler::chain_simple([
+        LlamaSampler::top_p(TOP_P, 1),
+        LlamaSampler::temp(TEMPERATURE),
+        LlamaSampler::dist(seed),
+    ]);
+    let mut position = tokens.len();
+    let mut generated = 0;
+    // Bytes of a character split across tokens, held until it is complete
+    let mut pending: Vec<u8> = Vec::new();
+    while generated < max_tokens && position < context_tokens as usize {
+        let token = sampler.sample(&context, batch.n_tokens() - 1);
+        if vocab.is_eog(token) {
+            break;
+        }
+        generated += 1;
+        pending.extend(vocab.token_to_piece(token, false, None));
+        let complete = match std::str::from_utf8(&pending) {
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            _ => pending.len(),
+        };
+        let text = String::from_utf8_lossy(&pending[..complete]).into_owned();
+        pending.drain(..complete);
+        if !text.is_empty() && !on_text(text) {
+            break;
+        }
+
+        batch.clear();
+        batch
+            .add(token, position as i32, &[0], true)
+            .map_err(|e| format!("Failed to queue a token: {}", e))?;
+        context.decode(&mut batch).map_err(|e| format!("llama.cpp failed to generate: {}", e))?;
+        position += 1;
+    }
+    if !pending.is_empty() {
+        on_text(String::from_utf8_lossy(&pending).into_owned());
+    }
+    Ok(TokenUsage { input_tokens: tokens.len() as u32, output_tokens: generated })
+}
@@ -0,0 +1,436 @@
+//! Screen monitors: background loops that capture a target at an interval and emit
+//! `screen-changed:{id}` whenever the image changes. Full-screen monitors also emit the
+//! original `screen-changed` event. Monitors given a text pattern OCR each changed frame and
+//! emit `text-detected` when new text matches it. Capturing and handling frames run side by
+//! side; when handling falls behind, the oldest waiting frames are dropped and counted in the
+//! `frames_dropped` metric.
+
+use schemars::JsonSchema;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+
+use crate::cancellation::CancellationState;
+use crate::capture_buffer::{Buffered, CaptureBufferState};
+use crate::capture_diff::{self, CaptureDiffState};
+use crate::commands::{CaptureResult, CaptureWindowParams};
+use crate::consent::{ConsentScope, ConsentState};
+use crate::content_class::ContentClassState;
+use crate::diagnostics_parser::ParsedDiagnostic;
+use crate::frame_delta::{DeltaEncoder, FrameDeltaState};
+use crate::frame_queue::FrameQueue;
+use crate::image_resize::ResizeState;
+use crate::metrics::MetricsState;
+use crate::project_files::ProjectRootsState;
+use crate::recent_activity::RecentActivityState;
+use crate::remote_view::RemoteViewState;
+use crate::screen_capture::{ScreenCapture, ScreenCaptureState};
+use crate::watchdog::{Heartbeat, CAPTURE_LOOP_SILENCE};
+
+const MAX_MONITORS: usize = 8;
+const MAX_INTERVAL_SECONDS: u64 = 60;
+const MAX_PATTERN_LENGTH: usize = 500;
+/// Changed frames waiting for OCR and emission; older ones are dropped past this
+const FRAME_QUEUE_CAPACITY: usize = 2;
+
+/// What a monitor captures
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum MonitorTarget {
+    #[default]
+    FullScreen,
+    /// Whichever window is in the foreground at each capture
+    ActiveWindow,
+    /// The first window matching the filters, as in `capture_window`
+    Window {
+        #[serde(default)]
+        process_name: Option<String>,
+        #[serde(default)]
+        window_title: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct MonitorOptions {
+    #[serde(default)]
+    pub target: MonitorTarget,
+    /// Seconds between captures; follows `set_capture_interval` when omitted
+    #[serde(default)]
+    pub interval_seconds: Option<u64>,
+    /// OCR every changed frame and include the text, and its diff from the previous frame,
+    /// in the event
+    #[serde(default)]
+    pub ocr: bool,
+    /// Regex; OCR every changed frame and emit `text-detected` for new lines matching it
+    #[serde(default)]
+    pub text_pattern: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct MonitorInfo {
+    pub id: String,
+    /// Name of the event this monitor emits
+    pub event: String,
+    pub target: MonitorTarget,
+    pub interval_seconds: Option<u64>,
+    pub ocr: bool,
+    pub text_pattern: Option<String>,
+    pub started_at: i64,
+}
+
+/// Payload of the `text-detected` event
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TextDetected {
+    pub monitor_id: String,
+    pub pattern: String,
+    pub window_title: String,
+    pub process_name: String,
+    /// Lines of OCR text that match and weren't on the previous frame
+    pub lines: Vec<String>,
+    /// Errors parsed from the frame, with source lines when the file is in an approved folder
+    pub diagnostics: Vec<ParsedDiagnostic>,
+    pub timestamp: i64,
+}
+
+struct RunningMonitor {
+    info: MonitorInfo,
+    /// OCRs its frames, for its own options or for capture diffs
+    reads_text: bool,
+    stop: Arc<AtomicBool>,
+}
+
+#[derive(Clone, Default)]
+pub struct MonitoringState {
+    monitors: Arc<Mutex<HashMap<String, RunningMonitor>>>,
+}
+
+impl MonitoringState {
+    pub(crate) fn running_count(&self) -> usize {
+        self.monitors.lock().unwrap().len()
+    }
+}
+
+/// Capture the target, along with the captured window's title and process name for window targets
+pub(crate) async fn capture_target(
+    app: &AppHandle,
+    target: &MonitorTarget,
+) -> Result<(CaptureResult, Option<(String, String)>), String> {
+    let (process_name, window_title) = match target {
+        MonitorTarget::FullScreen => {
+            let result = ScreenCapture::new().capture_full_screen(app).await?;
+            return Ok((result, None));
+        }
+        MonitorTarget::ActiveWindow => (Some(crate::commands::get_active_window().await?), None),
+        MonitorTarget::Window { process_name, window_title } => (process_name.clone(), window_title.clone()),
+    };
+
+    let operation = app.state::<CancellationState>().register(None);
+    let params = CaptureWindowParams { process_name, window_title, ..Default::default() };
+    let capture = crate::commands::capture_window_stages(app, params, &operation).await?;
+    let result = CaptureResult {
+        image_base64: capture.image_base64,
+        hash: capture.hash,
+        timestamp: capture.timestamp,
+        diff: None,
+        delta: None,
+        ocr_text: None,
+        classification: None,
+    };
+    Ok((result, Some((capture.window_title, capture.process_name))))
+}
+
+/// A changed frame waiting to be handled
+struct Frame {
+    /// Without its image, which waits in the capture buffer
+    result: CaptureResult,
+    image: Buffered,
+    window: String,
+    process: String,
+}
+
+/// The frame's base64 image, read back from the capture buffer (from disk if it was spilled)
+async fn take_image(image: Buffered) -> Result<String, String> {
+    let bytes = tauri::async_runtime::spawn_blocking(move || image.get())
+        .await
+        .map_err(|e| format!("Failed to read the frame: {}", e))??;
+    String::from_utf8(bytes).map_err(|e| format!("Failed to read the frame: {}", e))
+}
+
+async fn run_monitor(app: AppHandle, info: MonitorInfo, stop: Arc<AtomicBool>, heartbeat: Heartbeat) {
+    let queue = FrameQueue::new(FRAME_QUEUE_CAPACITY);
+    // Capturing keeps its pace while frames are handled; a slow handler costs frames, not memory
+    tokio::join!(capture_frames(&app, &info, &stop, &heartbeat, &queue), handle_frames(&app, &info, &stop, &queue));
+}
+
+/// Capture the target at the monitor's interval and queue each frame that changed
+async fn capture_frames(
+    app: &AppHandle,
+    info: &MonitorInfo,
+    stop: &AtomicBool,
+    heartbeat: &Heartbeat,
+    queue: &FrameQueue<Frame>,
+) {
+    let capture_state = app.state::<ScreenCaptureState>().inner().clone();
+    let captures = app.state::<CaptureBufferState>().inner().clone();
+    let recent_activity = app.state::<RecentActivityState>().inner().clone();
+    let metrics = app.state::<MetricsState>().inner().clone();
+    let resize = app.state::<ResizeState>().inner().clone();
+    let mut last_hash = String::new();
+
+    while !stop.load(Ordering::Relaxed) {
+        let interval_secs = info
+            .interval_seconds
+            .unwrap_or_else(|| capture_state.interval_seconds.load(Ordering::Relaxed));
+        tokio::time::sleep(Duration::from_secs(interval_secs)).await;
+        heartbeat.beat();
+        if stop.load(Ordering::Relaxed) {
+            break;
+        }
+        if capture_state.is_paused() {
+            continue;
+        }
+
+        let (mut result, window) = match capture_target(app, &info.target).await {
+            Ok(capture) => capture,
+            Err(e) => {
+                tracing::info!("Monitor {} capture error: {}", info.id, e);
+                continue;
+            }
+        };
+        if result.hash == last_hash {
+            continue;
+        }
+        last_hash = result.hash.clone();
+
+        if matches!(info.target, MonitorTarget::FullScreen) {
+            recent_activity.record(result.image_base64.clone(), chrono::Utc::now().timestamp_millis(), resize.get());
+        }
+        // Full-screen captures only know the foreground process
+        let (window, process) = match window {
+            Some(window) => window,
+            None => {
+                let process = crate::commands::get_active_window().await.unwrap_or_default();
+                (process.clone(), process)
+            }
+        };
+        let image = captures.hold(std::mem::take(&mut result.image_base64).into_bytes());
+        let dropped = queue.push(Frame { result, image, window, process });
+        if dropped > 0 {
+            tracing::debug!("Monitor {} is behind, dropped {} frame(s)", info.id, dropped);
+            metrics.add(crate::metrics::FRAMES_DROPPED, dropped as u64);
+        }
+    }
+    queue.close();
+}
+
+/// OCR, match, classify and emit queued frames, oldest first
+async fn handle_frames(app: &AppHandle, info: &MonitorInfo, stop: &AtomicBool, queue: &FrameQueue<Frame>) {
+    let diff_state = app.state::<CaptureDiffState>().inner().clone();
+    let delta_state = app.state::<FrameDeltaState>().inner().clone();
+    let content_state = app.state::<ContentClassState>().inner().clone();
+    let remote_view = app.state::<RemoteViewState>().inner().clone();
+    let resize = app.state::<ResizeState>().inner().clone();
+    let mut delta_encoder = DeltaEncoder::default();
+    // Window and text of this monitor's previous OCR, for `ocr: true`
+    let mut last_ocr: Option<(String, String)> = None;
+    // Validated in `start`
+    let pattern = info.text_pattern.as_deref().and_then(|p| Regex::new(p).ok());
+    // Matching lines of the previous frame, so a lingering error is reported once
+    let mut last_matches: HashSet<String> = HashSet::new();
+
+    while let Some(Frame { mut result, image, window, process }) = queue.pop().await {
+        if stop.load(Ordering::Relaxed) {
+            break;
+        }
+        result.image_base64 = match take_image(image).await {
+            Ok(image) => image,
+            Err(e) => {
+                tracing::warn!("⚠️ Monitor {} dropped a frame: {}", info.id, e);
+                continue;
+            }
+        };
+        let mut ocr_text = None;
+        // Diffs can be switched on after the monitor started: only read text consent covers
+        let diffs = diff_state.enabled.load(Ordering::Relaxed)
+            && app.state::<ConsentState>().require(ConsentScope::ScreenAndText).is_ok();
+        if info.ocr || pattern.is_some() || diffs {
+            let operation = app.state::<CancellationState>().register(None);
+            match crate::commands::ocr_image(app, result.image_base64.clone(), &operation).await {
+                Ok(text) if info.ocr => {
+                    result.diff = capture_diff::diff_since(last_ocr.as_ref(), &window, &text);
+                    result.ocr_text = Some(text.clone());
+                    last_ocr = Some((window.clone(), text.clone()));
+                    ocr_text = Some(text);
+                }
+                Ok(text) => {
+                    if diffs {
+                        result.diff = diff_state.observe(&window, &text);
+                    }
+                    ocr_text = Some(text);
+                }
+                Err(e) => tracing::warn!("⚠️ OCR for monitor {} failed: {}", info.id, e),
+            }
+        }
+        if let (Some(pattern), Some(text)) = (&pattern, &ocr_text) {
+            let matches: HashSet<String> =
+                text.lines().map(str::trim).filter(|line| pattern.is_match(line)).map(str::to_string).collect();
+            let mut lines: Vec<String> = matches.difference(&last_matches).cloned().collect();
+            if !lines.is_empty() {
+                lines.sort();
+                tracing::info!("🔎 Monitor {} saw {} new matching line(s)", info.id, lines.len());
+                let mut diagnostics = crate::diagnostics_parser::parse(text);
+                crate::diagnostics_parser::attach_sources(&app.state::<ProjectRootsState>(), &mut diagnostics);
+                let _ = crate::event_bus::emit(
+                    app,
+                    crate::events::TEXT_DETECTED,
+                    TextDetected {
+                        monitor_id: info.id.clone(),
+                        pattern: pattern.as_str().to_string(),
+                        window_title: window.clone(),
+                        process_name: process.clone(),
+                        lines,
+                        diagnostics,
+                        timestamp: chrono::Utc::now().timestamp_millis(),
+                    },
+                );
+            }
+            last_matches = matches;
+        }
+        result.classification = Some(content_state.observe(&window, &process, ocr_text.as_deref()));
+        // Before delta encoding, which may replace the image with changed tiles
+        remote_view.publish(&result.image_base64, resize.get()).await;
+        if let Err(e) = delta_encoder.apply(&delta_state, &info.id, &mut result) {
+            tracing::warn!("⚠️ Frame delta failed, sending the full frame: {}", e);
+        }
+        if matches!(info.target, MonitorTarget::FullScreen) {
+            let _ = crate::event_bus::emit(app, crate::events::SCREEN_CHANGED, result.clone());
+        }
+        let _ = crate::event_bus::emit_scoped(app, crate::events::MONITOR_SCREEN_CHANGED, &info.id, result);
+    }
+}
+
+fn start(app: &AppHandle, options: MonitorOptions) -> Result<MonitorInfo, String> {
+    let reads_text = options.ocr
+        || options.text_pattern.is_some()
+        || app.state::<CaptureDiffState>().enabled.load(Ordering::Relaxed);
+    let scope = if reads_text { ConsentScope::ScreenAndText } else { ConsentScope::Screen };
+    app.state::<ConsentState>().require(scope)?;
+    if let Some(interval) = options.interval_seconds {
+        if !(1..=MAX_INTERVAL_SECONDS).contains(&interval) {
+            return Err(format!("Interval must be between 1 and {} seconds", MAX_INTERVAL_SECONDS));
+        }
+    }
+    if let Some(pattern) = &options.text_pattern {
+        if pattern.is_empty() || pattern.len() > MAX_PATTERN_LENGTH {
+            return Err(format!("The text pattern must be 1 to {} characters", MAX_PATTERN_LENGTH));
+        }
+        Regex::new(pattern).map_err(|e| format!("Invalid text pattern: {}", e))?;
+    }
+
+    let state = app.state::<MonitoringState>();
+
+    let mut monitors = state.monitors.lock().unwrap();
+    if monitors.len() >= MAX_MONITORS {
+        return Err(format!("At most {} monitors can run at once", MAX_MONITORS));
+    }
+    let id = uuid::Uuid::new_v4().simple().to_string()[..8].to_string();
+    let info = MonitorInfo {
+        event: crate::events::MONITOR_SCREEN_CHANGED.name().replace("{id}", &id),
+        id: id.clone(),
+        target: options.target,
+        interval_seconds: options.interval_seconds,
+        ocr: options.ocr,
+        text_pattern: options.text_pattern,
+        started_at: chrono::Utc::now().timestamp(),
+    };
+    let stop = Arc::new(AtomicBool::new(false));
+    monitors.insert(id.clone(), RunningMonitor { info: info.clone(), reads_text, stop: stop.clone() });
+    drop(monitors);
+
+    let (loop_app, loop_info) = (app.clone(), info.clone());
+    crate::watchdog::spawn_supervised(app, format!("Screen monitor {}", id), CAPTURE_LOOP_SILENCE, move |heartbeat| {
+        run_monitor(loop_app.clone(), loop_info.clone(), stop.clone(), heartbeat)
+    });
+    tracing::info!("👁️ Started monitor {} ({:?})", id, info.target);
+    Ok(info)
+}
+
+/// Start a monitor and return its id. Several monitors can run at once, each with its own
+/// target, interval and event channel.
+#[tauri::command]
+pub async fn start_monitoring(app: AppHandle, options: Option<MonitorOptions>) -> Result<MonitorInfo, String> {
+    start(&app, options.unwrap_or_default())
+}
+
+/// Watch a window and emit `text-detected` when OCR text matching `pattern` (a regex such as
+/// `error TS\d+` or `Traceback`) appears. Returns the monitor, stopped with `stop_monitoring`.
+#[tauri::command]
+pub async fn watch_for_text(
+    app: AppHandle,
+    target_window: MonitorTarget,
+    pattern: String,
+    interval: Option<u64>,
+) -> Result<MonitorInfo, String> {
+    start(
+        &app,
+        MonitorOptions { target: target_window, interval_seconds: interval, ocr: false, text_pattern: Some(pattern) },
+    )
+}
+
+fn stop(app: &AppHandle, stopped: Vec<RunningMonitor>) {
+    let delta_state = app.state::<FrameDeltaState>();
+    for monitor in stopped {
+        monitor.stop.store(true, Ordering::Relaxed);
+        delta_state.forget(&monitor.info.id);
+        tracing::info!("👁️ Stopped monitor {}", monitor.info.id);
+    }
+}
+
+/// Stop every monitor, returning how many were running
+pub(crate) fn stop_all(app: &AppHandle) -> usize {
+    let stopped: Vec<RunningMonitor> =
+        app.state::<MonitoringState>().monitors.lock().unwrap().drain().map(|(_, monitor)| monitor).collect();
+    let count = stopped.len();
+    stop(app, stopped);
+    count
+}
+
+/// Stop the monitors that OCR their frames, watch for text or feed capture diffs
+pub(crate) fn stop_ocr_monitors(app: &AppHandle) {
+    let stopped: Vec<RunningMonitor> = {
+        let state = app.state::<MonitoringState>();
+        let mut monitors = state.monitors.lock().unwrap();
+        let ids: Vec<String> = monitors.values().filter(|m| m.reads_text).map(|m| m.info.id.clone()).collect();
+        ids.iter().filter_map(|id| monitors.remove(id)).collect()
+    };
+    stop(app, stopped);
+}
+
+/// Stop one monitor, or all of them when no id is given
+#[tauri::command]
+pub async fn stop_monitoring(
+    app: AppHandle,
+    state: State<'_, MonitoringState>,
+    id: Option<String>,
+) -> Result<(), String> {
+    let stopped: Vec<RunningMonitor> = {
+        let mut monitors = state.monitors.lock().unwrap();
+        match id {
+            Some(id) => vec![monitors.remove(&id).ok_or_else(|| format!("No monitor with id {}", id))?],
+            None => monitors.drain().map(|(_, monitor)| monitor).collect(),
+        }
+    };
+    stop(&app, stopped);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn list_monitors(state: State<'_, MonitoringState>) -> Result<Vec<MonitorInfo>, String> {
+    let mut monitors: Vec<MonitorInfo> = state.monitors.lock().unwrap().values().map(|m| m.info.clone()).collect();
+    monitors.sort_by_key(|m| m.started_at);
+    Ok(monitors)
+}
@@ -0,0 +1,313 @@
+//! Narrator mode for visually impaired students: the watched window is captured and read with
+//! OCR at an interval, and when enough new text appears it is read aloud by the system voice,
+//! or summarized by the AI first. Narrations are spaced at least `min_gap_seconds` apart and
+//! text arriving in between is gathered for the next one. Each narration is also sent as a
+//! `narration` event so the frontend can show it as a caption.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, State};
+
+use crate::ai::{ChatMessage, ChatRequest, ChatRole};
+use crate::cancellation::CancellationState;
+use crate::consent::{ConsentScope, ConsentState};
+use crate::monitoring::MonitorTarget;
+use crate::screen_capture::ScreenCaptureState;
+use crate::watchdog::{Heartbeat, CAPTURE_LOOP_SILENCE};
+
+const DEFAULT_INTERVAL_SECONDS: u64 = 3;
+const MAX_INTERVAL_SECONDS: u64 = 60;
+const DEFAULT_MIN_NEW_CHARS: usize = 40;
+const DEFAULT_MIN_GAP_SECONDS: u64 = 10;
+const MAX_GAP_SECONDS: u64 = 600;
+/// Characters read aloud at most per narration; the rest is left out
+const MAX_SPOKEN_CHARS: usize = 800;
+const SPEECH_POLL_INTERVAL: Duration = Duration::from_millis(200);
+const SUMMARY_PROMPT: &str = "You narrate a student's screen for a visually impaired student. \
+Summarize the new on-screen text below in one or two short spoken sentences. Plain text only.";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum NarrationMode {
+    /// Read the new lines as they are
+    #[default]
+    Read,
+    /// Have the AI summarize them first, reading them as they are if that fails
+    Summarize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct NarratorOptions {
+    pub mode: NarrationMode,
+    /// Seconds between captures
+    pub interval_seconds: u64,
+    /// New characters needed before anything is narrated, so a blinking cursor or clock stays quiet
+    pub min_new_chars: usize,
+    /// Shortest time between two narrations
+    pub min_gap_seconds: u64,
+    /// Only narrate, don't speak: for frontends with their own voice
+    pub muted: bool,
+}
+
+impl Default for NarratorOptions {
+    fn default() -> Self {
+        Self {
+            mode: NarrationMode::Read,
+            interval_seconds: DEFAULT_INTERVAL_SECONDS,
+            min_new_chars: DEFAULT_MIN_NEW_CHARS,
+            min_gap_seconds: DEFAULT_MIN_GAP_SECONDS,
+            muted: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct NarratorInfo {
+    pub target: MonitorTarget,
+    pub options: NarratorOptions,
+    pub started_at: i64,
+}
+
+/// Payload of the `narration` event
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Narration {
+    /// What was (or, when muted, would have been) spoken
+    pub text: String,
+    pub window_title: String,
+    /// The new lines the narration is about
+    pub lines: Vec<String>,
+    pub summarized: bool,
+    pub timestamp: i64,
+}
+
+struct RunningNarrator {
+    info: NarratorInfo,
+    stop: Arc<AtomicBool>,
+}
+
+#[derive(Clone, Default)]
+pub struct NarratorState {
+    running: Arc<Mutex<Option<RunningNarrator>>>,
+}
+
+/// The system voice: SAPI through PowerShell on Windows, `say` on macOS, espeak on Linux. The
+/// text goes in on stdin so it can't be read as part of the command.
+fn speech_command() -> tokio::process::Command {
+    if cfg!(target_os = "windows") {
+        let mut command = tokio::process::Command::new("powershell");
+        command.args([
+            "-NoProfile",
+            "-Command",
+            "Add-Type -AssemblyName System.Speech; \
+             (New-Object System.Speech.Synthesis.SpeechSynthesizer).Speak([Console]::In.ReadToEnd())",
+        ]);
+        command
+    } else if cfg!(target_os = "macos") {
+        tokio::process::Command::new("say")
+    } else {
+        let mut command = tokio::process::Command::new("espeak-ng");
+        command.arg("--stdin");
+        command
+    }
+}
+
+/// Speak `text` and wait until done, or stop speaking as soon as `stop` is set
+async fn speak(text: &str, stop: &AtomicBool) -> Result<(), String> {
+    use tokio::io::AsyncWriteExt;
+
+    let mut child = speech_command()
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| format!("Failed to start the system voice: {}", e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes()).await.map_err(|e| format!("Failed to pass text to the voice: {}", e))?;
+    }
+    loop {
+        if stop.load(Ordering::Relaxed) {
+            let _ = child.kill().await;
+            return Ok(());
+        }
+        if let Some(status) = child.try_wait().map_err(|e| format!("The system voice failed: {}", e))? {
+            return if status.success() { Ok(()) } else { Err(format!("The system voice exited with {}", status)) };
+        }
+        tokio::time::sleep(SPEECH_POLL_INTERVAL).await;
+    }
+}
+
+fn spoken_text(lines: &[String]) -> String {
+    let text = lines.join(". ");
+    if text.chars().count() <= MAX_SPOKEN_CHARS {
+        return text;
+    }
+    let mut kept: String = text.chars().take(MAX_SPOKEN_CHARS).collect();
+    kept.push_str("… and more");
+    kept
+}
+
+/// What to say about `lines`, and whether the AI summarized them
+async fn narration_text(app: &AppHandle, mode: NarrationMode, lines: &[String]) -> (String, bool) {
+    if mode == NarrationMode::Summarize {
+        let request = ChatRequest {
+            system: Some(SUMMARY_PROMPT.to_string()),
+            messages: vec![ChatMessage { role: ChatRole::User, content: lines.join("\n") }],
+            max_tokens: Some(120),
+        };
+        match crate::ai::complete(app, &request).await {
+            Ok(response) => return (response.text.trim().to_string(), true),
+            Err(e) => tracing::warn!("⚠️ Narrator summary failed, reading instead: {}", e),
+        }
+    }
+    (spoken_text(lines), false)
+}
+
+/// Lines of `text` that weren't on the previous capture of the same window
+fn new_lines(previous: Option<&(String, String)>, window: &str, text: &str) -> Vec<String> {
+    match previous {
+        Some((previous_window, _)) if previous_window == window => {
+            crate::capture_diff::diff_since(previous, window, text).map(|diff| diff.added_lines).unwrap_or_default()
+        }
+        // A window seen for the first time is new from top to bottom
+        _ => text.lines().map(str::trim).filter(|line| !line.is_empty()).map(str::to_string).collect(),
+    }
+}
+
+async fn run_narrator(app: AppHandle, info: NarratorInfo, stop: Arc<AtomicBool>, heartbeat: Heartbeat) {
+    let options = info.options.clone();
+    let mut last: Option<(String, String)> = None;
+    let mut last_hash = String::new();
+    let mut pending: Vec<String> = Vec::new();
+    let mut last_narration: Option<Instant> = None;
+
+    while !stop.load(Ordering::Relaxed) {
+        tokio::time::sleep(Duration::from_secs(options.interval_seconds)).await;
+        heartbeat.beat();
+        if stop.load(Ordering::Relaxed) {
+            break;
+        }
+        if let Err(e) = app.state::<ConsentState>().require(ConsentScope::ScreenAndText) {
+            tracing::info!("🗣️ Narrator stopped: {}", e);
+            let state = app.state::<NarratorState>();
+            let mut running = state.running.lock().unwrap();
+            if running.as_ref().is_some_and(|r| Arc::ptr_eq(&r.stop, &stop)) {
+                *running = None;
+            }
+            break;
+        }
+        if app.state::<ScreenCaptureState>().is_paused() {
+            continue;
+        }
+
+        let (capture, window) = match crate::monitoring::capture_target(&app, &info.target).await {
+            Ok(capture) => capture,
+            Err(e) => {
+                tracing::debug!("Narrator capture error: {}", e);
+                continue;
+            }
+        };
+        if capture.hash != last_hash {
+            last_hash = capture.hash.clone();
+            let window = match window {
+                Some((title, _)) => title,
+                None => crate::commands::get_active_window().await.unwrap_or_default(),
+            };
+            let operation = app.state::<CancellationState>().register(None);
+            match crate::commands::ocr_image(&app, capture.image_base64, &operation).await {
+                Ok(text) => {
+                    for line in new_lines(last.as_ref(), &window, &text) {
+                        if !pending.contains(&line) {
+                            pending.push(line);
+                        }
+                    }
+                    last = Some((window, text));
+                }
+                Err(e) => tracing::warn!("⚠️ Narrator OCR failed: {}", e),
+            }
+        }
+
+        let new_chars: usize = pending.iter().map(|line| line.chars().count()).sum();
+        let gap_passed = last_narration.is_none_or(|at| at.elapsed() >= Duration::from_secs(options.min_gap_seconds));
+        if new_chars < options.min_new_chars || !gap_passed {
+            continue;
+        }
+        let lines = std::mem::take(&mut pending);
+        let (text, summarized) = narration_text(&app, options.mode, &lines).await;
+        if text.is_empty() || stop.load(Ordering::Relaxed) {
+            continue;
+        }
+        let window_title = last.as_ref().map(|(window, _)| window.clone()).unwrap_or_default();
+        let _ = crate::event_bus::emit(
+            &app,
+            crate::events::NARRATION,
+            Narration { text: text.clone(), window_title, lines, summarized, timestamp: chrono::Utc::now().timestamp_millis() },
+        );
+        if !options.muted {
+            if let Err(e) = speak(&text, &stop).await {
+                tracing::warn!("⚠️ Narrator couldn't speak: {}", e);
+            }
+        }
+        last_narration = Some(Instant::now());
+    }
+}
+
+/// Narrate new text appearing in `target` aloud, spaced at least `min_gap_seconds` apart.
+/// Only one narrator runs at a time; stop it with `stop_narrator`.
+#[tauri::command]
+pub async fn start_narrator(
+    app: AppHandle,
+    state: State<'_, NarratorState>,
+    target: MonitorTarget,
+    options: Option<NarratorOptions>,
+) -> Result<NarratorInfo, String> {
+    app.state::<ConsentState>().require(ConsentScope::ScreenAndText)?;
+    let options = options.unwrap_or_default();
+    if !(1..=MAX_INTERVAL_SECONDS).contains(&options.interval_seconds) {
+        return Err(format!("Interval must be between 1 and {} seconds", MAX_INTERVAL_SECONDS));
+    }
+    if options.min_gap_seconds > MAX_GAP_SECONDS {
+        return Err(format!("The gap between narrations can be at most {} seconds", MAX_GAP_SECONDS));
+    }
+
+    let mut running = state.running.lock().unwrap();
+    if running.is_some() {
+        return Err("The narrator is already running; stop it first".to_string());
+    }
+    let info = NarratorInfo { target, options, started_at: chrono::Utc::now().timestamp() };
+    let stop = Arc::new(AtomicBool::new(false));
+    *running = Some(RunningNarrator { info: info.clone(), stop: stop.clone() });
+    drop(running);
+
+    let (loop_app, loop_info) = (app.clone(), info.clone());
+    crate::watchdog::spawn_supervised(&app, "Narrator", CAPTURE_LOOP_SILENCE, move |heartbeat| {
+        run_narrator(loop_app.clone(), loop_info.clone(), stop.clone(), heartbeat)
+    });
+    tracing::info!("🗣️ Started narrator ({:?}, {:?})", info.target, info.options.mode);
+    Ok(info)
+}
+
+/// Stop the narrator, cutting off anything being spoken. Returns whether it was running.
+#[tauri::command]
+pub async fn stop_narrator(app: AppHandle) -> Result<bool, String> {
+    Ok(stop(&app))
+}
+
+/// Stop the narrator if it is running, also on exit. Returns whether it was.
+pub fn stop(app: &AppHandle) -> bool {
+    let Some(narrator) = app.state::<NarratorState>().running.lock().unwrap().take() else {
+        return false;
+    };
+    narrator.stop.store(true, Ordering::Relaxed);
+    tracing::info!("🗣️ Stopped narrator");
+    true
+}
+
+#[tauri::command]
+pub async fn get_narrator(state: State<'_, NarratorState>) -> Result<Option<NarratorInfo>, String> {
+    Ok(state.running.lock().unwrap().as_ref().map(|narrator| narrator.info.clone()))
+}
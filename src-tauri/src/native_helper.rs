@@ -0,0 +1,169 @@
+//! Client for `ai-teacher-helper`, the precompiled sidecar (see `helper/`) that enumerates
+//! windows, captures them with PrintWindow or Windows.Graphics.Capture, runs Windows OCR and
+//! counts input without compiling C# at runtime.
+//! It speaks JSON lines over stdio; one helper process is started on first use and kept.
+//!
+//! Calls return `None` when the helper isn't bundled or won't start, so callers fall back to
+//! the PowerShell scripts.
+
+#![cfg_attr(not(target_os = "windows"), allow(dead_code))]
+
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::async_runtime::Receiver;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_shell::process::{CommandChild, CommandEvent};
+use tauri_plugin_shell::ShellExt;
+use tokio::sync::Mutex;
+
+use crate::cancellation::OperationGuard;
+
+const SIDECAR: &str = "ai-teacher-helper";
+const CALL_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Deserialize)]
+struct Response {
+    id: Option<u64>,
+    #[serde(default)]
+    result: Value,
+    error: Option<String>,
+}
+
+struct HelperProcess {
+    child: CommandChild,
+    events: Receiver<CommandEvent>,
+    next_id: u64,
+}
+
+impl HelperProcess {
+    fn spawn(app: &AppHandle) -> Result<Self, String> {
+        let (events, child) = app
+            .shell()
+            .sidecar(SIDECAR)
+            .and_then(|command| command.spawn())
+            .map_err(|e| format!("Failed to start {}: {}", SIDECAR, e))?;
+        Ok(Self { child, events, next_id: 1 })
+    }
+
+    /// Send one request and wait for the response with the same id. The outer error means the
+    /// helper couldn't be talked to; the inner one is an error the helper answered with.
+    async fn request(&mut self, method: &str, params: Value) -> Result<Result<Value, String>, String> {
+        let id = self.next_id;
+        self.next_id += 1;
+        let line = json!({ "id": id, "method": method, "params": params }).to_string() + "\n";
+        self.child
+            .write(line.as_bytes())
+            .map_err(|e| format!("Failed to send request to {}: {}", SIDECAR, e))?;
+
+        loop {
+            match self.events.recv().await {
+                Some(CommandEvent::Stdout(bytes)) => {
+                    let Ok(response) = serde_json::from_slice::<Response>(bytes.trim_ascii()) else {
+                        continue;
+                    };
+                    if response.id != Some(id) {
+                        continue;
+                    }
+                    return Ok(match response.error {
+                        Some(error) => Err(error),
+                        None => Ok(response.result),
+                    });
+                }
+                Some(CommandEvent::Stderr(bytes)) => {
+                    tracing::debug!("[native_helper] {}", String::from_utf8_lossy(&bytes).trim_end());
+                }
+                Some(CommandEvent::Error(e)) => return Err(format!("{} failed: {}", SIDECAR, e)),
+                Some(CommandEvent::Terminated(_)) | None => return Err(format!("{} exited", SIDECAR)),
+                Some(_) => {}
+            }
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct NativeHelperState {
+    process: Arc<Mutex<Option<HelperProcess>>>,
+    /// Set once the sidecar failed to start; the PowerShell path is used from then on
+    unavailable: Arc<AtomicBool>,
+}
+
+/// Whether calls go to the helper; false once it failed to start
+pub(crate) fn in_use(app: &AppHandle) -> bool {
+    cfg!(target_os = "windows") && !app.state::<NativeHelperState>().unavailable.load(Ordering::Relaxed)
+}
+
+/// Kill the helper on exit, unless it is in the middle of a call
+pub fn stop(app: &AppHandle) {
+    if let Ok(mut process) = app.state::<NativeHelperState>().process.try_lock() {
+        if let Some(helper) = process.take() {
+            let _ = helper.child.kill();
+        }
+    }
+}
+
+/// Call a helper method. `None` means the helper isn't available, or the call couldn't be
+/// delivered, and the caller should use its PowerShell fallback; cancelling `operation` kills
+/// the helper (a new one starts next call).
+pub(crate) async fn call<T: DeserializeOwned>(
+    app: &AppHandle,
+    method: &str,
+    params: Value,
+    operation: Option<&OperationGuard>,
+) -> Option<Result<T, String>> {
+    if !cfg!(target_os = "windows") {
+        return None;
+    }
+    let state = app.state::<NativeHelperState>();
+    if state.unavailable.load(Ordering::Relaxed) {
+        return None;
+    }
+
+    let mut process = state.process.lock().await;
+    if process.is_none() {
+        match HelperProcess::spawn(app) {
+            Ok(spawned) => *process = Some(spawned),
+            Err(e) => {
+                tracing::warn!("⚠️ Native helper unavailable, using PowerShell: {}", e);
+                state.unavailable.store(true, Ordering::Relaxed);
+                return None;
+            }
+        }
+    }
+    let helper = process.as_mut()?;
+
+    if let Some(operation) = operation {
+        operation.attach(helper.child.pid());
+    }
+    let result = match tokio::time::timeout(CALL_TIMEOUT, helper.request(method, params)).await {
+        Ok(result) => result,
+        Err(_) => Err(format!("{} timed out after {}s", SIDECAR, CALL_TIMEOUT.as_secs())),
+    };
+    if let Some(operation) = operation {
+        operation.detach();
+    }
+    if let Some(Err(e)) = operation.map(|operation| operation.check()) {
+        if let Some(helper) = process.take() {
+            let _ = helper.child.kill();
+        }
+        return Some(Err(e));
+    }
+
+    // A helper that died or stopped answering is replaced on the next call
+    let result = match result {
+        Ok(result) => result,
+        Err(e) => {
+            tracing::warn!("⚠️ {}, using PowerShell for this call", e);
+            if let Some(helper) = process.take() {
+                let _ = helper.child.kill();
+            }
+            return None;
+        }
+    };
+    Some(result.and_then(|value| {
+        serde_json::from_value(value).map_err(|e| format!("Unexpected {} response: {}", SIDECAR, e))
+    }))
+}
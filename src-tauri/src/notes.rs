@@ -0,0 +1,284 @@
+//! Knowledge-base notes, such as the lesson summaries the tutor writes, stored in the history
+//! database so they stay searchable offline. A note can link to the captures, focus sessions
+//! and history items it was written about.
+
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::history::HistoryState;
+
+const MAX_TITLE_LENGTH: usize = 200;
+const DEFAULT_SEARCH_LIMIT: u32 = 50;
+const MAX_SEARCH_LIMIT: u32 = 500;
+
+/// Notes tables, applied by the history database's migrations
+pub(crate) const SCHEMA: &str = r#"
+CREATE TABLE notes (
+    id INTEGER PRIMARY KEY,
+    title TEXT NOT NULL,
+    body TEXT NOT NULL,
+    tags TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL
+);
+CREATE TABLE note_links (
+    note_id INTEGER NOT NULL REFERENCES notes(id) ON DELETE CASCADE,
+    kind TEXT NOT NULL,
+    target TEXT NOT NULL,
+    PRIMARY KEY (note_id, kind, target)
+);
+CREATE INDEX note_links_target ON note_links(kind, target);
+CREATE VIRTUAL TABLE notes_fts USING fts5(title, body, tags, content='notes', content_rowid='id');
+CREATE TRIGGER notes_insert AFTER INSERT ON notes BEGIN
+    INSERT INTO notes_fts(rowid, title, body, tags) VALUES (new.id, new.title, new.body, new.tags);
+END;
+CREATE TRIGGER notes_delete AFTER DELETE ON notes BEGIN
+    INSERT INTO notes_fts(notes_fts, rowid, title, body, tags) VALUES ('delete', old.id, old.title, old.body, old.tags);
+END;
+CREATE TRIGGER notes_update AFTER UPDATE ON notes BEGIN
+    INSERT INTO notes_fts(notes_fts, rowid, title, body, tags) VALUES ('delete', old.id, old.title, old.body, old.tags);
+    INSERT INTO notes_fts(rowid, title, body, tags) VALUES (new.id, new.title, new.body, new.tags);
+END;
+"#;
+
+/// What a note link points at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum NoteLinkKind {
+    /// A capture, by its `hash`
+    Capture,
+    /// A focus session, by its `id`
+    Session,
+    /// A history item such as an imported document, by its `id`
+    HistoryItem,
+}
+
+impl NoteLinkKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            NoteLinkKind::Capture => "capture",
+            NoteLinkKind::Session => "session",
+            NoteLinkKind::HistoryItem => "history_item",
+        }
+    }
+
+    fn parse(kind: &str) -> Option<Self> {
+        [NoteLinkKind::Capture, NoteLinkKind::Session, NoteLinkKind::HistoryItem]
+            .into_iter()
+            .find(|k| k.as_str() == kind)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct NoteLink {
+    pub kind: NoteLinkKind,
+    pub target: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Note {
+    pub id: i64,
+    pub title: String,
+    /// Markdown
+    pub body: String,
+    pub tags: Vec<String>,
+    pub links: Vec<NoteLink>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct NewNote {
+    pub title: String,
+    #[serde(default)]
+    pub body: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub links: Vec<NoteLink>,
+}
+
+/// Changes to a note; omitted fields are kept, given lists replace the old ones
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct NoteUpdate {
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub body: Option<String>,
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
+    #[serde(default)]
+    pub links: Option<Vec<NoteLink>>,
+}
+
+fn validate_title(title: &str) -> Result<String, String> {
+    let title = title.trim();
+    if title.is_empty() || title.chars().count() > MAX_TITLE_LENGTH {
+        return Err(format!("Note title must be 1 to {} characters", MAX_TITLE_LENGTH));
+    }
+    Ok(title.to_string())
+}
+
+/// Trimmed, non-empty tags without duplicates, in the given order
+fn clean_tags(tags: Vec<String>) -> Vec<String> {
+    let mut cleaned: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.trim().to_string();
+        if !tag.is_empty() && !cleaned.iter().any(|t| t.eq_ignore_ascii_case(&tag)) {
+            cleaned.push(tag);
+        }
+    }
+    cleaned
+}
+
+fn replace_links(tx: &Transaction, id: i64, links: &[NoteLink]) -> rusqlite::Result<()> {
+    tx.execute("DELETE FROM note_links WHERE note_id = ?1", [id])?;
+    for link in links {
+        tx.execute(
+            "INSERT OR IGNORE INTO note_links (note_id, kind, target) VALUES (?1, ?2, ?3)",
+            params![id, link.kind.as_str(), link.target],
+        )?;
+    }
+    Ok(())
+}
+
+fn load_note(db: &Connection, id: i64) -> rusqlite::Result<Option<Note>> {
+    let note = db
+        .query_row(
+            "SELECT id, title, body, tags, created_at, updated_at FROM notes WHERE id = ?1",
+            [id],
+            |row| {
+                let tags: String = row.get(3)?;
+                Ok(Note {
+                    id: row.get(0)?,
+                    title: row.get(1)?,
+                    body: row.get(2)?,
+                    tags: serde_json::from_str(&tags).unwrap_or_default(),
+                    links: Vec::new(),
+                    created_at: row.get(4)?,
+                    updated_at: row.get(5)?,
+                })
+            },
+        )
+        .optional()?;
+    let Some(mut note) = note else {
+        return Ok(None);
+    };
+    let mut statement = db.prepare("SELECT kind, target FROM note_links WHERE note_id = ?1 ORDER BY kind, target")?;
+    note.links = statement
+        .query_map([id], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?
+        .filter_map(|link| match link {
+            Ok((kind, target)) => NoteLinkKind::parse(&kind).map(|kind| Ok(NoteLink { kind, target })),
+            Err(e) => Some(Err(e)),
+        })
+        .collect::<rusqlite::Result<_>>()?;
+    Ok(Some(note))
+}
+
+fn note_or_missing(note: Option<Note>, id: i64) -> Result<Note, String> {
+    note.ok_or_else(|| format!("No note with id {}", id))
+}
+
+#[tauri::command]
+pub async fn create_note(state: State<'_, HistoryState>, note: NewNote) -> Result<Note, String> {
+    let title = validate_title(&note.title)?;
+    let tags = serde_json::to_string(&clean_tags(note.tags)).map_err(|e| format!("Failed to store tags: {}", e))?;
+    let now = chrono::Utc::now().timestamp();
+    let created = state.with_db(|db| {
+        let tx = db.transaction()?;
+        tx.execute(
+            "INSERT INTO notes (title, body, tags, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?4)",
+            params![title, note.body, tags, now],
+        )?;
+        let id = tx.last_insert_rowid();
+        replace_links(&tx, id, &note.links)?;
+        tx.commit()?;
+        load_note(db, id)
+    })?;
+    let created = created.ok_or("Created note disappeared")?;
+    tracing::info!("📝 Created note {} ({})", created.id, created.title);
+    Ok(created)
+}
+
+#[tauri::command]
+pub async fn get_note(state: State<'_, HistoryState>, id: i64) -> Result<Note, String> {
+    note_or_missing(state.with_db(|db| load_note(db, id))?, id)
+}
+
+#[tauri::command]
+pub async fn update_note(state: State<'_, HistoryState>, id: i64, update: NoteUpdate) -> Result<Note, String> {
+    let title = update.title.as_deref().map(validate_title).transpose()?;
+    let tags = update
+        .tags
+        .map(|tags| serde_json::to_string(&clean_tags(tags)))
+        .transpose()
+        .map_err(|e| format!("Failed to store tags: {}", e))?;
+    let updated = state.with_db(|db| {
+        let tx = db.transaction()?;
+        let changed = tx.execute(
+            "UPDATE notes SET title = COALESCE(?2, title), body = COALESCE(?3, body), tags = COALESCE(?4, tags),
+                    updated_at = ?5
+             WHERE id = ?1",
+            params![id, title, update.body, tags, chrono::Utc::now().timestamp()],
+        )?;
+        if changed == 0 {
+            return Ok(None);
+        }
+        if let Some(links) = &update.links {
+            replace_links(&tx, id, links)?;
+        }
+        tx.commit()?;
+        load_note(db, id)
+    })?;
+    note_or_missing(updated, id)
+}
+
+#[tauri::command]
+pub async fn delete_note(state: State<'_, HistoryState>, id: i64) -> Result<(), String> {
+    let deleted = state.with_db(|db| db.execute("DELETE FROM notes WHERE id = ?1", [id]))?;
+    if deleted == 0 {
+        return Err(format!("No note with id {}", id));
+    }
+    tracing::info!("🗑️ Deleted note {}", id);
+    Ok(())
+}
+
+/// Notes matching `query` (full text over title, body and tags, best first), or the most
+/// recently updated without one; `link` keeps only notes linked to that capture, session or item
+#[tauri::command]
+pub async fn search_notes(
+    state: State<'_, HistoryState>,
+    query: Option<String>,
+    link: Option<NoteLink>,
+    limit: Option<u32>,
+) -> Result<Vec<Note>, String> {
+    let query = query.map(|q| crate::history::fts_query(&q)).filter(|q| !q.is_empty());
+    let limit = limit.unwrap_or(DEFAULT_SEARCH_LIMIT).clamp(1, MAX_SEARCH_LIMIT);
+    let (link_kind, link_target) = match &link {
+        Some(link) => (Some(link.kind.as_str()), Some(link.target.as_str())),
+        None => (None, None),
+    };
+    state.with_db(|db| {
+        let link_filter = "(?2 IS NULL OR EXISTS (SELECT 1 FROM note_links l WHERE l.note_id = n.id AND l.kind = ?2 AND l.target = ?3))";
+        let sql = if query.is_some() {
+            format!(
+                "SELECT n.id FROM notes_fts JOIN notes n ON n.id = notes_fts.rowid
+                 WHERE notes_fts MATCH ?1 AND {} ORDER BY rank LIMIT ?4",
+                link_filter
+            )
+        } else {
+            format!("SELECT n.id FROM notes n WHERE ?1 IS NULL AND {} ORDER BY n.updated_at DESC LIMIT ?4", link_filter)
+        };
+        let ids: Vec<i64> = db
+            .prepare(&sql)?
+            .query_map(params![query, link_kind, link_target, limit], |row| row.get(0))?
+            .collect::<rusqlite::Result<_>>()?;
+        let mut notes = Vec::with_capacity(ids.len());
+        for id in ids {
+            notes.extend(load_note(db, id)?);
+        }
+        Ok(notes)
+    })
+}
@@ -0,0 +1,245 @@
+//! Whether the student's screen is being observed, always visible to them: a global pause for
+//! monitoring and screen recording, a tray icon whose dot turns red while monitors capture or
+//! the screen is recorded (amber while paused), and an optional always-on-top indicator dot.
+//! `observation-changed` carries every change.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::image::Image;
+use tauri::tray::TrayIconBuilder;
+use tauri::{AppHandle, Manager, State, WebviewUrl, WebviewWindowBuilder};
+
+use crate::monitoring::MonitoringState;
+use crate::persistence;
+use crate::screen_capture::ScreenCaptureState;
+use crate::screen_recording::{self, ScreenRecordingState};
+
+const SETTINGS_FILE: &str = "observation_settings.json";
+const TRAY_ID: &str = "main";
+const INDICATOR_LABEL: &str = "recording-indicator";
+const INDICATOR_SIZE: f64 = 18.0;
+/// Gap between the indicator and the top-right corner of the primary monitor
+const INDICATOR_MARGIN: f64 = 12.0;
+const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+const MAX_PAUSE_MINUTES: u32 = 8 * 60;
+const OBSERVED_COLOR: [u8; 3] = [220, 38, 38];
+const PAUSED_COLOR: [u8; 3] = [245, 158, 11];
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct ObservationSettings {
+    /// Show a small always-on-top dot while the screen is being captured
+    pub show_indicator: bool,
+}
+
+/// Payload of the `observation-changed` event
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ObservationStatus {
+    /// Monitors are running and capturing, or the screen is being recorded
+    pub observed: bool,
+    /// Monitoring is paused by the student or a focus-timer break
+    pub paused: bool,
+    /// When the student's pause ends; None for an open-ended pause or when not paused
+    pub paused_until: Option<i64>,
+    pub monitors: usize,
+    /// A screen recording is running
+    pub recording: bool,
+}
+
+#[derive(Clone, Default)]
+pub struct ObservationState {
+    settings: Arc<Mutex<ObservationSettings>>,
+    /// The student's pause: Some(None) until resumed, Some(Some(t)) until `t`
+    pause: Arc<Mutex<Option<Option<i64>>>>,
+    last: Arc<Mutex<Option<ObservationStatus>>>,
+}
+
+fn status(app: &AppHandle) -> ObservationStatus {
+    let capture = app.state::<ScreenCaptureState>();
+    let monitors = app.state::<MonitoringState>().running_count();
+    let recording = app.state::<ScreenRecordingState>().is_recording();
+    let paused = capture.is_paused();
+    ObservationStatus {
+        observed: (monitors > 0 || recording) && !paused,
+        paused,
+        paused_until: app.state::<ObservationState>().pause.lock().unwrap().flatten(),
+        monitors,
+        recording,
+    }
+}
+
+/// The app icon with a colored dot in its bottom-right corner
+fn icon_with_dot(base: &Image<'_>, color: [u8; 3]) -> Image<'static> {
+    let (width, height) = (base.width(), base.height());
+    let mut rgba = base.rgba().to_vec();
+    let radius = width.min(height) as f64 * 0.22;
+    let (cx, cy) = (width as f64 - radius - 1.0, height as f64 - radius - 1.0);
+    for y in 0..height {
+        for x in 0..width {
+            let distance = ((x as f64 + 0.5 - cx).powi(2) + (y as f64 + 0.5 - cy).powi(2)).sqrt();
+            if distance <= radius {
+                let i = ((y * width + x) * 4) as usize;
+                rgba[i..i + 4].copy_from_slice(&[color[0], color[1], color[2], 255]);
+            }
+        }
+    }
+    Image::new_owned(rgba, width, height)
+}
+
+fn update_tray(app: &AppHandle, status: &ObservationStatus) {
+    let (Some(tray), Some(base)) = (app.tray_by_id(TRAY_ID), app.default_window_icon()) else {
+        return;
+    };
+    let (icon, tooltip) = if status.observed {
+        (icon_with_dot(base, OBSERVED_COLOR), "AI Teacher - watching your screen")
+    } else if status.paused && (status.monitors > 0 || status.recording) {
+        (icon_with_dot(base, PAUSED_COLOR), "AI Teacher - monitoring paused")
+    } else {
+        (base.clone().to_owned(), "AI Teacher - not watching")
+    };
+    if let Err(e) = tray.set_icon(Some(icon)).and_then(|_| tray.set_tooltip(Some(tooltip))) {
+        tracing::warn!("⚠️ Failed to update the tray icon: {}", e);
+    }
+}
+
+fn update_indicator(app: &AppHandle, visible: bool) {
+    if let Some(window) = app.get_webview_window(INDICATOR_LABEL) {
+        if !visible {
+            let _ = window.close();
+        }
+        return;
+    }
+    if !visible {
+        return;
+    }
+    let (x, y) = match app.primary_monitor() {
+        Ok(Some(monitor)) => {
+            let scale = monitor.scale_factor();
+            let right = (monitor.position().x as f64 + monitor.size().width as f64) / scale;
+            (right - INDICATOR_SIZE - INDICATOR_MARGIN, monitor.position().y as f64 / scale + INDICATOR_MARGIN)
+        }
+        _ => (INDICATOR_MARGIN, INDICATOR_MARGIN),
+    };
+    let window = WebviewWindowBuilder::new(app, INDICATOR_LABEL, WebviewUrl::App("indicator.html".into()))
+        .title("Screen is being observed")
+        .inner_size(INDICATOR_SIZE, INDICATOR_SIZE)
+        .position(x, y)
+        .decorations(false)
+        .transparent(true)
+        .shadow(false)
+        .resizable(false)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .focused(false)
+        .build();
+    match window {
+        // Clicks go through to whatever is underneath
+        Ok(window) => {
+            let _ = window.set_ignore_cursor_events(true);
+        }
+        Err(e) => tracing::warn!("⚠️ Failed to show the recording indicator: {}", e),
+    }
+}
+
+/// End an expired pause, stop a recording that runs while paused, then reflect the current
+/// state in the tray, the indicator and an `observation-changed` event when it changed
+fn refresh(app: &AppHandle) -> ObservationStatus {
+    let state = app.state::<ObservationState>();
+    let expired = state.pause.lock().unwrap().flatten().is_some_and(|until| until <= chrono::Utc::now().timestamp());
+    if expired {
+        state.pause.lock().unwrap().take();
+        app.state::<ScreenCaptureState>().user_paused.store(false, Ordering::Relaxed);
+        tracing::info!("▶️ Monitoring pause ended");
+    }
+
+    let status = status(app);
+    if status.paused && status.recording {
+        tracing::info!("⏸️ Stopping the screen recording while observation is paused");
+        let handle = app.clone();
+        tauri::async_runtime::spawn_blocking(move || screen_recording::stop(&handle));
+    }
+    let show_indicator = state.settings.lock().unwrap().show_indicator;
+    let mut last = state.last.lock().unwrap();
+    if last.as_ref() != Some(&status) {
+        update_tray(app, &status);
+        update_indicator(app, show_indicator && status.observed);
+        let _ = crate::event_bus::emit(app, crate::events::OBSERVATION_CHANGED, status.clone());
+        *last = Some(status.clone());
+    }
+    status
+}
+
+/// Load the settings and add the tray icon
+pub fn init(app: &AppHandle) {
+    let settings: ObservationSettings = persistence::data_file(app, SETTINGS_FILE)
+        .map(|path| persistence::load_json(&path))
+        .unwrap_or_default();
+    *app.state::<ObservationState>().settings.lock().unwrap() = settings;
+
+    let mut tray = TrayIconBuilder::with_id(TRAY_ID).tooltip("AI Teacher - not watching");
+    if let Some(icon) = app.default_window_icon() {
+        tray = tray.icon(icon.clone());
+    }
+    if let Err(e) = tray.build(app) {
+        tracing::warn!("⚠️ Failed to create the tray icon: {}", e);
+    }
+}
+
+/// Follow monitors starting and stopping, focus-timer breaks and pause expiry
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            refresh(&app);
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
+
+/// Pause every monitor for `duration_minutes`, or until `resume_monitoring` when omitted
+#[tauri::command]
+pub async fn pause_monitoring(
+    app: AppHandle,
+    state: State<'_, ObservationState>,
+    duration_minutes: Option<u32>,
+) -> Result<ObservationStatus, String> {
+    if duration_minutes.is_some_and(|m| !(1..=MAX_PAUSE_MINUTES).contains(&m)) {
+        return Err(format!("A pause lasts between 1 and {} minutes", MAX_PAUSE_MINUTES));
+    }
+    let until = duration_minutes.map(|m| chrono::Utc::now().timestamp() + m as i64 * 60);
+    *state.pause.lock().unwrap() = Some(until);
+    app.state::<ScreenCaptureState>().user_paused.store(true, Ordering::Relaxed);
+    tracing::info!("⏸️ Monitoring paused for {:?} minutes", duration_minutes);
+    Ok(refresh(&app))
+}
+
+#[tauri::command]
+pub async fn resume_monitoring(app: AppHandle, state: State<'_, ObservationState>) -> Result<ObservationStatus, String> {
+    state.pause.lock().unwrap().take();
+    app.state::<ScreenCaptureState>().user_paused.store(false, Ordering::Relaxed);
+    tracing::info!("▶️ Monitoring resumed");
+    Ok(refresh(&app))
+}
+
+#[tauri::command]
+pub async fn get_observation_status(app: AppHandle) -> Result<ObservationStatus, String> {
+    Ok(refresh(&app))
+}
+
+/// Show or hide the always-on-top dot shown while the screen is being captured
+#[tauri::command]
+pub async fn set_recording_indicator(
+    app: AppHandle,
+    state: State<'_, ObservationState>,
+    enabled: bool,
+) -> Result<(), String> {
+    let settings = ObservationSettings { show_indicator: enabled };
+    let path = persistence::data_file(&app, SETTINGS_FILE)?;
+    persistence::save_json(&path, &settings)?;
+    *state.settings.lock().unwrap() = settings;
+    // Force the indicator to follow the new setting
+    state.last.lock().unwrap().take();
+    refresh(&app);
+    Ok(())
+}
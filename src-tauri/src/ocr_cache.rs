@@ -0,0 +1,48 @@
+//! Recent OCR results keyed by the image, so a frame read twice (a capture and then
+//! `extract_text_from_image` on it, or the narrator and a monitor on the same window) runs
+//! OCR once. Hits and misses are counted in the metrics.
+
+use sha2::{Digest, Sha256};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+/// Results kept, the least recently used going first
+const CAPACITY: usize = 32;
+
+#[derive(Clone, Default)]
+pub struct OcrCacheState {
+    entries: Arc<Mutex<VecDeque<(String, String)>>>,
+}
+
+impl OcrCacheState {
+    /// Key for an image; plain text and word boxes are cached apart
+    pub fn key(image_base64: &str, word_boxes: bool) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(image_base64.as_bytes());
+        hasher.update([word_boxes as u8]);
+        hex::encode(hasher.finalize())
+    }
+
+    pub fn get(&self, key: &str) -> Option<String> {
+        let mut entries = self.entries.lock().unwrap();
+        let index = entries.iter().position(|(k, _)| k == key)?;
+        let entry = entries.remove(index)?;
+        let text = entry.1.clone();
+        entries.push_back(entry);
+        Some(text)
+    }
+
+    pub fn insert(&self, key: String, text: String) {
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|(k, _)| *k != key);
+        entries.push_back((key, text));
+        while entries.len() > CAPACITY {
+            entries.pop_front();
+        }
+    }
+
+    /// Forget every result, e.g. once another OCR language is installed
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+}
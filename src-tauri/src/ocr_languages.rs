@@ -0,0 +1,211 @@
+//! OCR language packs. Windows OCR silently returns nothing for languages without an installed
+//! recognizer, so the frontend can check what's available and install the missing capability
+//! (`Language.OCR~~~<tag>~0.0.1.0`), following along through `ocr-language-progress` events.
+
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::ocr_cache::OcrCacheState;
+
+/// How often an `installing` event is repeated while the capability downloads
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
+
+fn language_tag_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"^[A-Za-z]{2,3}(-[A-Za-z0-9]{2,8})*$").expect("valid language tag regex"))
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OcrLanguageStage {
+    Checking,
+    /// Waiting for the elevated install; repeated every few seconds
+    Installing,
+    Verifying,
+    Done,
+    /// Installing needs administrator rights; the Language settings page was opened instead
+    OpenedSettings,
+    Failed,
+}
+
+/// Payload of the `ocr-language-progress` event
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct OcrLanguageProgress {
+    pub tag: String,
+    pub stage: OcrLanguageStage,
+    pub elapsed_seconds: u64,
+    pub detail: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct OcrLanguageInstall {
+    pub tag: String,
+    /// Whether OCR for the language works now
+    pub installed: bool,
+    pub already_installed: bool,
+    pub opened_settings: bool,
+}
+
+fn emit(app: &AppHandle, tag: &str, stage: OcrLanguageStage, elapsed_seconds: u64, detail: Option<String>) {
+    let _ = crate::event_bus::emit(
+        app,
+        crate::events::OCR_LANGUAGE_PROGRESS,
+        OcrLanguageProgress { tag: tag.to_string(), stage, elapsed_seconds, detail },
+    );
+}
+
+/// True when `available` has a recognizer for `tag` ("en" matches "en-US")
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn has_language(available: &[String], tag: &str) -> bool {
+    let tag = tag.to_lowercase();
+    available.iter().any(|language| {
+        let language = language.to_lowercase();
+        language == tag || language.starts_with(&format!("{}-", tag))
+    })
+}
+
+#[cfg(target_os = "windows")]
+fn available_languages() -> Result<Vec<String>, String> {
+    use std::process::Command;
+
+    let script = r#"
+        [Windows.Media.Ocr.OcrEngine, Windows.Media, ContentType=WindowsRuntime] | Out-Null
+        $tags = @([Windows.Media.Ocr.OcrEngine]::AvailableRecognizerLanguages | ForEach-Object { $_.LanguageTag })
+        ConvertTo-Json -InputObject $tags -Compress
+    "#;
+    let output = Command::new("powershell")
+        .args(["-NoProfile", "-ExecutionPolicy", "Bypass", "-Command", script])
+        .output()
+        .map_err(|e| format!("Failed to list OCR languages: {}", e))?;
+    if !output.status.success() {
+        return Err(format!("Failed to list OCR languages: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    serde_json::from_slice(&output.stdout).map_err(|e| format!("Failed to parse OCR languages: {}", e))
+}
+
+#[cfg(not(target_os = "windows"))]
+fn available_languages() -> Result<Vec<String>, String> {
+    Err("OCR not implemented for this platform".to_string())
+}
+
+/// Add the OCR capability from an elevated PowerShell (UAC prompt). The inner script is passed
+/// with -EncodedCommand so the tag never needs quoting twice.
+#[cfg(target_os = "windows")]
+fn install_capability(app: &AppHandle, tag: &str) -> Result<(), String> {
+    use base64::{engine::general_purpose, Engine as _};
+    use std::process::Command;
+    use std::time::Instant;
+
+    let inner = format!(
+        "try {{ Add-WindowsCapability -Online -Name 'Language.OCR~~~{}~0.0.1.0' -ErrorAction Stop | Out-Null; exit 0 }} catch {{ exit 1 }}",
+        tag
+    );
+    let utf16: Vec<u8> = inner.encode_utf16().flat_map(|unit| unit.to_le_bytes()).collect();
+    let script = format!(
+        "$p = Start-Process powershell -Verb RunAs -Wait -PassThru -WindowStyle Hidden -ArgumentList '-NoProfile','-EncodedCommand','{}'; exit $p.ExitCode",
+        general_purpose::STANDARD.encode(utf16)
+    );
+    let mut child = Command::new("powershell")
+        .args(["-NoProfile", "-ExecutionPolicy", "Bypass", "-Command", &script])
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to start installer: {}", e))?;
+
+    let started = Instant::now();
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) => {
+                std::thread::sleep(HEARTBEAT_INTERVAL);
+                emit(app, tag, OcrLanguageStage::Installing, started.elapsed().as_secs(), None);
+            }
+            Err(e) => return Err(format!("Failed to wait for installer: {}", e)),
+        }
+    };
+    if status.success() {
+        return Ok(());
+    }
+    let mut stderr = String::new();
+    if let Some(mut pipe) = child.stderr.take() {
+        use std::io::Read;
+        let _ = pipe.read_to_string(&mut stderr);
+    }
+    Err(match stderr.trim() {
+        "" => format!("Installing the {} OCR language failed (exit code {:?})", tag, status.code()),
+        message => message.to_string(),
+    })
+}
+
+#[cfg(target_os = "windows")]
+fn open_language_settings() -> Result<(), String> {
+    std::process::Command::new("explorer")
+        .arg("ms-settings:regionlanguage")
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to open Language settings: {}", e))
+}
+
+#[cfg(target_os = "windows")]
+fn install(app: &AppHandle, tag: &str) -> Result<OcrLanguageInstall, String> {
+    emit(app, tag, OcrLanguageStage::Checking, 0, None);
+    if has_language(&available_languages()?, tag) {
+        emit(app, tag, OcrLanguageStage::Done, 0, None);
+        return Ok(OcrLanguageInstall { tag: tag.to_string(), installed: true, already_installed: true, opened_settings: false });
+    }
+
+    emit(app, tag, OcrLanguageStage::Installing, 0, None);
+    if let Err(e) = install_capability(app, tag) {
+        // Usually a declined UAC prompt: let the user add the language by hand
+        tracing::warn!("⚠️ OCR language install failed, opening settings: {}", e);
+        open_language_settings()?;
+        emit(app, tag, OcrLanguageStage::OpenedSettings, 0, Some(e));
+        return Ok(OcrLanguageInstall { tag: tag.to_string(), installed: false, already_installed: false, opened_settings: true });
+    }
+
+    emit(app, tag, OcrLanguageStage::Verifying, 0, None);
+    let installed = has_language(&available_languages()?, tag);
+    if installed {
+        emit(app, tag, OcrLanguageStage::Done, 0, None);
+    } else {
+        emit(app, tag, OcrLanguageStage::Failed, 0, Some("Capability installed but no recognizer is available yet; a restart may be required".to_string()));
+    }
+    Ok(OcrLanguageInstall { tag: tag.to_string(), installed, already_installed: false, opened_settings: false })
+}
+
+#[cfg(not(target_os = "windows"))]
+fn install(app: &AppHandle, tag: &str) -> Result<OcrLanguageInstall, String> {
+    emit(app, tag, OcrLanguageStage::Failed, 0, Some("OCR languages can only be installed on Windows".to_string()));
+    Err("OCR languages can only be installed on Windows".to_string())
+}
+
+/// Language tags Windows OCR can recognize right now
+#[tauri::command]
+pub async fn list_ocr_languages() -> Result<Vec<String>, String> {
+    tauri::async_runtime::spawn_blocking(available_languages)
+        .await
+        .map_err(|e| format!("Failed to list OCR languages: {}", e))?
+}
+
+/// Install the OCR language pack for a BCP-47 tag such as "de-DE", emitting
+/// `ocr-language-progress` along the way
+#[tauri::command]
+pub async fn install_ocr_language(app: AppHandle, tag: String) -> Result<OcrLanguageInstall, String> {
+    let tag = tag.trim().to_string();
+    if !language_tag_pattern().is_match(&tag) {
+        return Err(format!("Invalid language tag: {}", tag));
+    }
+    tracing::info!("🌐 Installing OCR language {}", tag);
+    let handle = app.clone();
+    let installed = tauri::async_runtime::spawn_blocking(move || install(&handle, &tag))
+        .await
+        .map_err(|e| format!("OCR language install failed: {}", e))??;
+    // Images read before may read differently now
+    app.state::<OcrCacheState>().clear();
+    Ok(installed)
+}
@@ -0,0 +1,371 @@
+//! Layout reconstruction from OCR word boxes. Plain OCR text reads tables and multi-column
+//! pages left to right across the whole image, which scrambles them; grouping the words by
+//! position recovers headings, paragraphs, text columns and table cells.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::cancellation::CancellationState;
+
+/// Words further apart than this many word heights start a new segment (cell or column)
+const SEGMENT_GAP: f32 = 1.5;
+/// Segments whose vertical centers are within this many word heights share a row
+const ROW_TOLERANCE: f32 = 0.5;
+/// Rows further apart than this many word heights end a table or paragraph
+const BLOCK_GAP: f32 = 1.0;
+/// Multi-segment runs averaging more words per segment than this are prose columns, not tables
+const MAX_WORDS_PER_CELL: f32 = 6.0;
+/// Rows this much taller than the typical word are headings (level 2, level 1)
+const HEADING_RATIOS: [f32; 2] = [1.3, 1.8];
+const MAX_HEADING_WORDS: usize = 12;
+
+/// A word recognized by OCR, in image pixels
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct OcrWord {
+    pub text: String,
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    /// Index of the OCR line the word belongs to
+    pub line: u32,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema)]
+pub struct BoundingBox {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl BoundingBox {
+    fn right(&self) -> f32 {
+        self.x + self.width
+    }
+
+    fn bottom(&self) -> f32 {
+        self.y + self.height
+    }
+
+    fn center_x(&self) -> f32 {
+        self.x + self.width / 2.0
+    }
+
+    fn center_y(&self) -> f32 {
+        self.y + self.height / 2.0
+    }
+
+    fn union(&self, other: &BoundingBox) -> BoundingBox {
+        let (x, y) = (self.x.min(other.x), self.y.min(other.y));
+        BoundingBox {
+            x,
+            y,
+            width: self.right().max(other.right()) - x,
+            height: self.bottom().max(other.bottom()) - y,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LayoutBlock {
+    Heading { level: u8, text: String, bounds: BoundingBox },
+    Paragraph { text: String, bounds: BoundingBox },
+    /// Cells by row; the first row is usually the header
+    Table { rows: Vec<Vec<String>>, bounds: BoundingBox },
+    /// Side-by-side text columns, each in reading order
+    Columns { columns: Vec<String>, bounds: BoundingBox },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DocumentLayout {
+    /// Blocks from top to bottom
+    pub blocks: Vec<LayoutBlock>,
+    /// The same blocks rendered as Markdown, for prompts
+    pub markdown: String,
+    pub word_count: usize,
+}
+
+/// Words of one OCR line with no large gap between them
+struct Segment {
+    text: String,
+    bounds: BoundingBox,
+    words: usize,
+    /// Tallest word, which tracks font size better than the box height
+    word_height: f32,
+}
+
+struct Row {
+    segments: Vec<Segment>,
+    bounds: BoundingBox,
+}
+
+impl Row {
+    fn text(&self) -> String {
+        self.segments.iter().map(|s| s.text.as_str()).collect::<Vec<_>>().join(" ")
+    }
+
+    fn words(&self) -> usize {
+        self.segments.iter().map(|s| s.words).sum()
+    }
+
+    fn word_height(&self) -> f32 {
+        self.segments.iter().map(|s| s.word_height).fold(0.0, f32::max)
+    }
+}
+
+fn median(mut values: Vec<f32>) -> f32 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    values.sort_by(f32::total_cmp);
+    values[values.len() / 2]
+}
+
+fn word_bounds(word: &OcrWord) -> BoundingBox {
+    BoundingBox { x: word.x, y: word.y, width: word.width, height: word.height }
+}
+
+/// Split each OCR line into segments wherever the words are far apart
+fn segments(words: &[OcrWord], word_height: f32) -> Vec<Segment> {
+    let mut lines: Vec<Vec<&OcrWord>> = Vec::new();
+    let mut sorted: Vec<&OcrWord> = words.iter().collect();
+    sorted.sort_by(|a, b| a.line.cmp(&b.line).then(a.x.total_cmp(&b.x)));
+    for word in sorted {
+        match lines.last_mut() {
+            Some(line) if line[0].line == word.line => line.push(word),
+            _ => lines.push(vec![word]),
+        }
+    }
+
+    let mut segments = Vec::new();
+    for line in lines {
+        let mut current: Option<Segment> = None;
+        for word in line {
+            let bounds = word_bounds(word);
+            match current.as_mut() {
+                Some(segment) if bounds.x - segment.bounds.right() <= word_height * SEGMENT_GAP => {
+                    segment.text.push(' ');
+                    segment.text.push_str(&word.text);
+                    segment.bounds = segment.bounds.union(&bounds);
+                    segment.words += 1;
+                    segment.word_height = segment.word_height.max(word.height);
+                }
+                _ => {
+                    segments.extend(current.take());
+                    current = Some(Segment { text: word.text.clone(), bounds, words: 1, word_height: word.height });
+                }
+            }
+        }
+        segments.extend(current);
+    }
+    segments
+}
+
+/// Group segments that sit at the same height into rows, top to bottom
+fn rows(mut segments: Vec<Segment>, word_height: f32) -> Vec<Row> {
+    segments.sort_by(|a, b| a.bounds.center_y().total_cmp(&b.bounds.center_y()));
+    let mut rows: Vec<Row> = Vec::new();
+    for segment in segments {
+        match rows.last_mut() {
+            Some(row) if (segment.bounds.center_y() - row.bounds.center_y()).abs() <= word_height * ROW_TOLERANCE => {
+                row.bounds = row.bounds.union(&segment.bounds);
+                row.segments.push(segment);
+            }
+            _ => rows.push(Row { bounds: segment.bounds, segments: vec![segment] }),
+        }
+    }
+    for row in &mut rows {
+        row.segments.sort_by(|a, b| a.bounds.x.total_cmp(&b.bounds.x));
+    }
+    rows
+}
+
+/// Horizontal extents of the columns in a run of rows, or None when some row has two segments
+/// in the same column (the rows don't line up)
+fn column_ranges(rows: &[Row], word_height: f32) -> Option<Vec<(f32, f32)>> {
+    let mut extents: Vec<(f32, f32)> = rows
+        .iter()
+        .flat_map(|row| row.segments.iter().map(|s| (s.bounds.x, s.bounds.right())))
+        .collect();
+    extents.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let mut ranges: Vec<(f32, f32)> = Vec::new();
+    for (start, end) in extents {
+        match ranges.last_mut() {
+            Some(range) if start <= range.1 + word_height * ROW_TOLERANCE => range.1 = range.1.max(end),
+            _ => ranges.push((start, end)),
+        }
+    }
+    if ranges.len() < 2 {
+        return None;
+    }
+    let aligned = rows.iter().all(|row| {
+        let mut used = vec![false; ranges.len()];
+        row.segments.iter().all(|segment| {
+            let column = column_of(&ranges, segment);
+            !std::mem::replace(&mut used[column], true)
+        })
+    });
+    aligned.then_some(ranges)
+}
+
+fn column_of(ranges: &[(f32, f32)], segment: &Segment) -> usize {
+    let center = segment.bounds.center_x();
+    ranges
+        .iter()
+        .position(|(start, end)| center >= *start && center <= *end)
+        .unwrap_or_else(|| {
+            // Centers can only fall outside when ranges are separated by less than a segment
+            ranges
+                .iter()
+                .enumerate()
+                .min_by(|a, b| (a.1 .0 - center).abs().total_cmp(&(b.1 .0 - center).abs()))
+                .map(|(i, _)| i)
+                .unwrap_or(0)
+        })
+}
+
+fn union_bounds<'a>(rows: impl IntoIterator<Item = &'a Row>) -> BoundingBox {
+    let mut rows = rows.into_iter();
+    let first = rows.next().map(|r| r.bounds).unwrap_or(BoundingBox { x: 0.0, y: 0.0, width: 0.0, height: 0.0 });
+    rows.fold(first, |bounds, row| bounds.union(&row.bounds))
+}
+
+/// A table or text columns from a run of aligned multi-segment rows
+fn grid_block(rows: &[Row], ranges: &[(f32, f32)]) -> LayoutBlock {
+    let bounds = union_bounds(rows);
+    let segment_count: usize = rows.iter().map(|r| r.segments.len()).sum();
+    let words: usize = rows.iter().map(Row::words).sum();
+    let mut cells: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| {
+            let mut cells = vec![String::new(); ranges.len()];
+            for segment in &row.segments {
+                cells[column_of(ranges, segment)] = segment.text.clone();
+            }
+            cells
+        })
+        .collect();
+
+    if words as f32 / segment_count as f32 > MAX_WORDS_PER_CELL {
+        let columns = (0..ranges.len())
+            .map(|column| {
+                cells
+                    .iter_mut()
+                    .map(|row| std::mem::take(&mut row[column]))
+                    .filter(|text| !text.is_empty())
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .collect();
+        return LayoutBlock::Columns { columns, bounds };
+    }
+    LayoutBlock::Table { rows: cells, bounds }
+}
+
+fn heading_level(row: &Row, word_height: f32) -> Option<u8> {
+    if row.words() > MAX_HEADING_WORDS || word_height <= 0.0 {
+        return None;
+    }
+    let ratio = row.word_height() / word_height;
+    if ratio >= HEADING_RATIOS[1] {
+        Some(1)
+    } else if ratio >= HEADING_RATIOS[0] {
+        Some(2)
+    } else {
+        None
+    }
+}
+
+/// Rebuild headings, paragraphs, tables and text columns from OCR words
+pub fn analyze(words: &[OcrWord]) -> DocumentLayout {
+    let word_height = median(words.iter().map(|w| w.height).collect());
+    let rows = rows(segments(words, word_height), word_height);
+    let close = |above: &Row, below: &Row| below.bounds.y - above.bounds.bottom() <= word_height * BLOCK_GAP;
+
+    let mut blocks = Vec::new();
+    let mut paragraph: Vec<&Row> = Vec::new();
+    let flush = |paragraph: &mut Vec<&Row>, blocks: &mut Vec<LayoutBlock>| {
+        if !paragraph.is_empty() {
+            let text = paragraph.iter().map(|r| r.text()).collect::<Vec<_>>().join(" ");
+            blocks.push(LayoutBlock::Paragraph { text, bounds: union_bounds(paragraph.iter().copied()) });
+            paragraph.clear();
+        }
+    };
+
+    let mut i = 0;
+    while i < rows.len() {
+        // Longest run of close multi-segment rows starting here
+        let mut end = i;
+        while end < rows.len() && rows[end].segments.len() > 1 && (end == i || close(&rows[end - 1], &rows[end])) {
+            end += 1;
+        }
+        if end - i >= 2 {
+            if let Some(ranges) = column_ranges(&rows[i..end], word_height) {
+                flush(&mut paragraph, &mut blocks);
+                blocks.push(grid_block(&rows[i..end], &ranges));
+                i = end;
+                continue;
+            }
+        }
+
+        let row = &rows[i];
+        if let Some(level) = heading_level(row, word_height) {
+            flush(&mut paragraph, &mut blocks);
+            blocks.push(LayoutBlock::Heading { level, text: row.text(), bounds: row.bounds });
+        } else {
+            if paragraph.last().is_some_and(|last| !close(last, row)) {
+                flush(&mut paragraph, &mut blocks);
+            }
+            paragraph.push(row);
+        }
+        i += 1;
+    }
+    flush(&mut paragraph, &mut blocks);
+
+    DocumentLayout { markdown: to_markdown(&blocks), blocks, word_count: words.len() }
+}
+
+fn escape_cell(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+fn to_markdown(blocks: &[LayoutBlock]) -> String {
+    let mut parts = Vec::new();
+    for block in blocks {
+        match block {
+            LayoutBlock::Heading { level, text, .. } => parts.push(format!("{} {}", "#".repeat(*level as usize), text)),
+            LayoutBlock::Paragraph { text, .. } => parts.push(text.clone()),
+            LayoutBlock::Table { rows, .. } => {
+                let mut lines = Vec::new();
+                for (index, row) in rows.iter().enumerate() {
+                    let cells: Vec<String> = row.iter().map(|c| escape_cell(c)).collect();
+                    lines.push(format!("| {} |", cells.join(" | ")));
+                    if index == 0 {
+                        lines.push(format!("|{}", " --- |".repeat(row.len())));
+                    }
+                }
+                parts.push(lines.join("\n"));
+            }
+            LayoutBlock::Columns { columns, .. } => parts.extend(columns.iter().cloned()),
+        }
+    }
+    parts.join("\n\n")
+}
+
+/// OCR an image and return its layout (headings, paragraphs, tables, text columns) instead of
+/// a flat string
+#[tauri::command]
+pub async fn extract_layout(
+    app: AppHandle,
+    image_base64: String,
+    request_id: Option<String>,
+) -> Result<DocumentLayout, String> {
+    let operation = app.state::<CancellationState>().register(request_id.as_deref());
+    let words = crate::commands::ocr_words(&app, image_base64, &operation).await?;
+    let layout = analyze(&words);
+    tracing::info!("📐 Extracted layout: {} blocks from {} words", layout.blocks.len(), layout.word_count);
+    Ok(layout)
+}
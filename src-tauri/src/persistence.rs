@@ -0,0 +1,51 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+/// Path of a file inside the active profile's data directory, creating the directory if needed
+pub fn data_file(app: &AppHandle, name: &str) -> Result<PathBuf, String> {
+    let mut dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data directory: {}", e))?;
+    if let Some(profile_dir) = crate::profiles::active_subdir() {
+        dir = dir.join(profile_dir);
+    }
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create app data directory: {}", e))?;
+    Ok(dir.join(name))
+}
+
+/// Path of a file shared by all profiles (logs, crash reports, the profile list)
+pub fn shared_data_file(app: &AppHandle, name: &str) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data directory: {}", e))?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create app data directory: {}", e))?;
+    Ok(dir.join(name))
+}
+
+/// Load a JSON file, falling back to the default value when it doesn't exist or is unreadable
+pub fn load_json<T: DeserializeOwned + Default>(path: &Path) -> T {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(_) => return T::default(),
+    };
+    serde_json::from_str(&contents).unwrap_or_else(|e| {
+        tracing::warn!("⚠️ Ignoring corrupt file {}: {}", path.display(), e);
+        T::default()
+    })
+}
+
+/// Write a JSON file atomically (write to a sibling temp file, then rename)
+pub fn save_json<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(value)
+        .map_err(|e| format!("Failed to serialize {}: {}", path.display(), e))?;
+    let temp_path = path.with_extension("json.tmp");
+    fs::write(&temp_path, json)
+        .map_err(|e| format!("Failed to write {}: {}", temp_path.display(), e))?;
+    fs::rename(&temp_path, path)
+        .map_err(|e| format!("Failed to replace {}: {}", path.display(), e))
+}
@@ -0,0 +1,181 @@
+//! Which processes listen on which ports, so the tutor can answer "why does localhost:3000
+//! not load?" by checking what is actually listening. Windows asks the TCP/UDP tables through
+//! PowerShell, Linux reads `/proc`, macOS runs `lsof`.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::powershell_host::PowerShellHostState;
+
+const LIST_PORTS_SCRIPT: &str = r#"
+$names = @{}
+Get-Process | ForEach-Object { $names[[int]$_.Id] = $_.ProcessName }
+$tcp = Get-NetTCPConnection -State Listen -ErrorAction SilentlyContinue | ForEach-Object {
+    [pscustomobject]@{ protocol = 'tcp'; address = $_.LocalAddress; port = [int]$_.LocalPort; pid = [uint32]$_.OwningProcess; process_name = $names[[int]$_.OwningProcess] }
+}
+$udp = Get-NetUDPEndpoint -ErrorAction SilentlyContinue | ForEach-Object {
+    [pscustomobject]@{ protocol = 'udp'; address = $_.LocalAddress; port = [int]$_.LocalPort; pid = [uint32]$_.OwningProcess; process_name = $names[[int]$_.OwningProcess] }
+}
+ConvertTo-Json -InputObject @(@($tcp) + @($udp)) -Compress
+"#;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ListeningPort {
+    /// "tcp" or "udp"
+    pub protocol: String,
+    /// Local address, e.g. `127.0.0.1`, `0.0.0.0` or `::`
+    pub address: String,
+    pub port: u16,
+    /// Owning process; None when the OS doesn't reveal it (e.g. another user's process)
+    pub pid: Option<u32>,
+    pub process_name: Option<String>,
+}
+
+#[cfg(target_os = "linux")]
+mod procfs {
+    use super::ListeningPort;
+    use std::collections::HashMap;
+    use std::fs;
+    use std::net::{Ipv4Addr, Ipv6Addr};
+
+    /// `/proc/net/tcp` state of a listening socket
+    const TCP_LISTEN: &str = "0A";
+    /// `/proc/net/udp` state of an unconnected (bound) socket
+    const UDP_UNCONNECTED: &str = "07";
+
+    /// Decode `0100007F:0BB8`; each 32-bit word of the address is in little-endian order
+    fn parse_address(field: &str) -> Option<(String, u16)> {
+        let (address, port) = field.split_once(':')?;
+        let port = u16::from_str_radix(port, 16).ok()?;
+        let mut bytes = Vec::with_capacity(16);
+        for i in 0..address.len() / 8 {
+            let word = u32::from_str_radix(address.get(i * 8..i * 8 + 8)?, 16).ok()?;
+            bytes.extend_from_slice(&word.to_le_bytes());
+        }
+        let address = match bytes.len() {
+            4 => Ipv4Addr::new(bytes[0], bytes[1], bytes[2], bytes[3]).to_string(),
+            16 => Ipv6Addr::from(<[u8; 16]>::try_from(bytes).ok()?).to_string(),
+            _ => return None,
+        };
+        Some((address, port))
+    }
+
+    /// Socket inode to owning pid, for the processes we may inspect
+    fn socket_owners() -> HashMap<u64, u32> {
+        let mut owners = HashMap::new();
+        let Ok(entries) = fs::read_dir("/proc") else {
+            return owners;
+        };
+        for entry in entries.flatten() {
+            let Ok(pid) = entry.file_name().to_string_lossy().parse::<u32>() else { continue };
+            let Ok(fds) = fs::read_dir(entry.path().join("fd")) else { continue };
+            for fd in fds.flatten() {
+                let Ok(target) = fs::read_link(fd.path()) else { continue };
+                let target = target.to_string_lossy();
+                if let Some(inode) = target.strip_prefix("socket:[").and_then(|t| t.strip_suffix(']')) {
+                    if let Ok(inode) = inode.parse() {
+                        owners.insert(inode, pid);
+                    }
+                }
+            }
+        }
+        owners
+    }
+
+    pub fn list() -> Vec<ListeningPort> {
+        let owners = socket_owners();
+        let mut ports = Vec::new();
+        for (file, protocol, state) in [
+            ("/proc/net/tcp", "tcp", TCP_LISTEN),
+            ("/proc/net/tcp6", "tcp", TCP_LISTEN),
+            ("/proc/net/udp", "udp", UDP_UNCONNECTED),
+            ("/proc/net/udp6", "udp", UDP_UNCONNECTED),
+        ] {
+            let Ok(table) = fs::read_to_string(file) else { continue };
+            for line in table.lines().skip(1) {
+                let fields: Vec<&str> = line.split_whitespace().collect();
+                if fields.len() < 10 || fields[3] != state {
+                    continue;
+                }
+                let Some((address, port)) = parse_address(fields[1]) else { continue };
+                let pid = fields[9].parse().ok().and_then(|inode: u64| owners.get(&inode).copied());
+                let process_name = pid
+                    .and_then(|pid| fs::read_to_string(format!("/proc/{}/comm", pid)).ok())
+                    .map(|name| name.trim().to_string());
+                ports.push(ListeningPort { protocol: protocol.to_string(), address, port, pid, process_name });
+            }
+        }
+        ports
+    }
+}
+
+/// Parse `lsof -F pcn` output: `p<pid>`, `c<command>`, then one `n<address>:<port>` per socket
+#[cfg(not(target_os = "linux"))]
+fn parse_lsof(output: &str, protocol: &str) -> Vec<ListeningPort> {
+    let (mut pid, mut name) = (None, None);
+    let mut ports = Vec::new();
+    for line in output.lines() {
+        let (tag, value) = line.split_at(line.len().min(1));
+        match tag {
+            "p" => pid = value.parse().ok(),
+            "c" => name = Some(value.to_string()),
+            "n" => {
+                // UDP sockets with a peer show `local->remote`
+                let local = value.split("->").next().unwrap_or(value);
+                let Some((address, port)) = local.rsplit_once(':') else { continue };
+                let Ok(port) = port.parse() else { continue };
+                ports.push(ListeningPort {
+                    protocol: protocol.to_string(),
+                    address: address.trim_matches(['[', ']']).replace('*', "0.0.0.0"),
+                    port,
+                    pid,
+                    process_name: name.clone(),
+                });
+            }
+            _ => {}
+        }
+    }
+    ports
+}
+
+async fn list_ports(app: &AppHandle) -> Result<Vec<ListeningPort>, String> {
+    if cfg!(target_os = "windows") {
+        let output = app.state::<PowerShellHostState>().run(LIST_PORTS_SCRIPT, None, |_| {}).await?;
+        if !output.success() {
+            return Err(format!("Failed to list ports: {}", output.stderr.trim()));
+        }
+        return serde_json::from_str(output.stdout.trim()).map_err(|e| format!("Failed to parse port list: {}", e));
+    }
+
+    #[cfg(target_os = "linux")]
+    return tauri::async_runtime::spawn_blocking(procfs::list)
+        .await
+        .map_err(|e| format!("Failed to list ports: {}", e));
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let mut ports = Vec::new();
+        let queries: [(&str, &[&str]); 2] =
+            [("tcp", &["-nP", "-iTCP", "-sTCP:LISTEN", "-Fpcn"]), ("udp", &["-nP", "-iUDP", "-Fpcn"])];
+        for (protocol, args) in queries {
+            let output = tokio::process::Command::new("lsof")
+                .args(args)
+                .output()
+                .await
+                .map_err(|e| format!("Failed to run lsof: {}", e))?;
+            ports.extend(parse_lsof(&String::from_utf8_lossy(&output.stdout), protocol));
+        }
+        Ok(ports)
+    }
+}
+
+/// Listening TCP ports and bound UDP ports with their processes, optionally only `port`
+#[tauri::command]
+pub async fn get_listening_ports(app: AppHandle, port: Option<u16>) -> Result<Vec<ListeningPort>, String> {
+    let mut ports: Vec<ListeningPort> =
+        list_ports(&app).await?.into_iter().filter(|p| port.is_none_or(|wanted| p.port == wanted)).collect();
+    ports.sort_by(|a, b| (a.port, &a.protocol, &a.address).cmp(&(b.port, &b.protocol, &b.address)));
+    ports.dedup();
+    Ok(ports)
+}
@@ -0,0 +1,324 @@
+//! Warm PowerShell hosts for the capture and OCR scripts. Starting `powershell.exe` and
+//! compiling the `Add-Type` helpers costs 1-2s per call; a host started once keeps both warm
+//! and runs script after script in one STA runspace until the app exits.
+//!
+//! Protocol: each request is one stdin line with the base64 (UTF-8) script. The script's
+//! `[Console]::Error` lines stream through as they are written, followed by `END_MARKER`; then
+//! one stdout line `<exit code> <base64 stdout>` completes the request.
+
+#![cfg_attr(not(target_os = "windows"), allow(dead_code))]
+
+use base64::{engine::general_purpose, Engine as _};
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+
+use crate::cancellation::OperationGuard;
+
+/// Idle hosts kept around; extra hosts started for concurrent scripts exit after their run
+const POOL_SIZE: usize = 2;
+const SCRIPT_TIMEOUT: Duration = Duration::from_secs(120);
+const END_MARKER: &str = "<<ai-teacher-host-end>>";
+
+/// Script prefix for WinRT APIs: `Await $operation ([ResultType])`, `AwaitAction $action` and
+/// `Read-StreamBytes $randomAccessStream`
+pub(crate) const WINRT_PRELUDE: &str = r#"
+$ErrorActionPreference = 'Stop'
+Add-Type -AssemblyName System.Runtime.WindowsRuntime
+$asTaskMethods = [System.WindowsRuntimeSystemExtensions].GetMethods() | Where-Object { $_.Name -eq 'AsTask' -and $_.GetParameters().Count -eq 1 }
+$asTaskGeneric = ($asTaskMethods | Where-Object { $_.GetParameters()[0].ParameterType.Name -eq 'IAsyncOperation`1' })[0]
+$asTaskAction = ($asTaskMethods | Where-Object { -not $_.IsGenericMethod -and $_.GetParameters()[0].ParameterType.Name -eq 'IAsyncAction' })[0]
+function Await($operation, [Type]$resultType) {
+    $task = $asTaskGeneric.MakeGenericMethod($resultType).Invoke($null, @($operation))
+    $task.Wait(-1) | Out-Null
+    $task.Result
+}
+function AwaitAction($action) {
+    $asTaskAction.Invoke($null, @($action)).Wait(-1) | Out-Null
+}
+[Windows.Storage.StorageFile, Windows.Storage, ContentType=WindowsRuntime] | Out-Null
+[Windows.Storage.Streams.InMemoryRandomAccessStream, Windows.Storage.Streams, ContentType=WindowsRuntime] | Out-Null
+function Read-StreamBytes($stream) {
+    $size = [uint32]$stream.Size
+    $reader = New-Object Windows.Storage.Streams.DataReader($stream.GetInputStreamAt(0))
+    Await ($reader.LoadAsync($size)) ([uint32]) | Out-Null
+    $bytes = New-Object byte[] $size
+    $reader.ReadBytes($bytes)
+    $reader.Dispose()
+    ,$bytes
+}
+"#;
+
+const HOST_SCRIPT: &str = r#"
+[Console]::OutputEncoding = [System.Text.Encoding]::UTF8
+$protocol = [Console]::Out
+$runspace = [RunspaceFactory]::CreateRunspace()
+$runspace.ApartmentState = 'STA'
+$runspace.ThreadOptions = 'ReuseThread'
+$runspace.Open()
+while ($true) {
+    $line = [Console]::In.ReadLine()
+    if ($null -eq $line) { break }
+    $script = [System.Text.Encoding]::UTF8.GetString([Convert]::FromBase64String($line))
+    $captured = New-Object System.IO.StringWriter
+    [Console]::SetOut($captured)
+    $ps = [PowerShell]::Create()
+    $ps.Runspace = $runspace
+    $exitCode = 0
+    $results = @()
+    try {
+        # Local scope, so variables and functions don't leak into the next script
+        $results = $ps.AddScript($script, $true).Invoke()
+    } catch {
+        $inner = $_.Exception.InnerException
+        if ($inner -is [System.Management.Automation.ExitException]) {
+            $exitCode = [int]$inner.Argument
+        } else {
+            $exitCode = 1
+            [Console]::Error.WriteLine($_.Exception.Message)
+        }
+    }
+    foreach ($record in $ps.Streams.Error) { [Console]::Error.WriteLine($record.ToString()) }
+    $ps.Dispose()
+    [Console]::SetOut($protocol)
+    $lines = foreach ($result in $results) { if ($result -is [string]) { $result } else { ($result | Out-String).TrimEnd() } }
+    $text = $captured.ToString() + ($lines -join "`n")
+    [Console]::Error.WriteLine('<<ai-teacher-host-end>>') # END_MARKER
+    [Console]::Error.Flush()
+    $protocol.WriteLine("$exitCode " + [Convert]::ToBase64String([System.Text.Encoding]::UTF8.GetBytes($text)))
+    $protocol.Flush()
+}
+"#;
+
+/// Loaded by the first host so the first capture doesn't pay for it
+const WARM_UP_SCRIPT: &str = r#"
+Add-Type -AssemblyName System.Drawing, System.Windows.Forms
+[Windows.Media.Ocr.OcrEngine, Windows.Media, ContentType=WindowsRuntime] | Out-Null
+"#;
+
+#[derive(Debug, Clone)]
+pub struct ScriptOutput {
+    pub stdout: String,
+    pub stderr: String,
+    pub exit_code: i32,
+}
+
+impl ScriptOutput {
+    pub fn success(&self) -> bool {
+        self.exit_code == 0
+    }
+}
+
+struct HostProcess {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: Receiver<String>,
+    stderr: Receiver<String>,
+}
+
+impl Drop for HostProcess {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Forward lines from a pipe into a channel, so reads can time out
+fn line_channel(pipe: impl std::io::Read + Send + 'static) -> Receiver<String> {
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        for line in BufReader::new(pipe).lines().map_while(Result::ok) {
+            if sender.send(line).is_err() {
+                break;
+            }
+        }
+    });
+    receiver
+}
+
+fn recv_before(receiver: &Receiver<String>, deadline: Instant) -> Result<String, String> {
+    receiver
+        .recv_timeout(deadline.saturating_duration_since(Instant::now()))
+        .map_err(|e| match e {
+            RecvTimeoutError::Timeout => format!("PowerShell script timed out after {}s", SCRIPT_TIMEOUT.as_secs()),
+            RecvTimeoutError::Disconnected => "PowerShell host exited".to_string(),
+        })
+}
+
+impl HostProcess {
+    fn spawn() -> Result<Self, String> {
+        let encoded: Vec<u8> = HOST_SCRIPT.encode_utf16().flat_map(|unit| unit.to_le_bytes()).collect();
+        let mut child = Command::new("powershell")
+            .args(["-NoProfile", "-NonInteractive", "-ExecutionPolicy", "Bypass", "-EncodedCommand"])
+            .arg(general_purpose::STANDARD.encode(encoded))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("Failed to start PowerShell host: {}", e))?;
+        let stdin = child.stdin.take().ok_or("PowerShell host has no stdin")?;
+        let stdout = line_channel(child.stdout.take().ok_or("PowerShell host has no stdout")?);
+        let stderr = line_channel(child.stderr.take().ok_or("PowerShell host has no stderr")?);
+        Ok(Self { child, stdin, stdout, stderr })
+    }
+
+    fn send(&mut self, script: &str) -> Result<(), String> {
+        let line = general_purpose::STANDARD.encode(script.as_bytes());
+        writeln!(self.stdin, "{}", line)
+            .and_then(|_| self.stdin.flush())
+            .map_err(|e| format!("Failed to send script to PowerShell host: {}", e))
+    }
+
+    fn receive(&mut self, on_stderr: &mut dyn FnMut(&str)) -> Result<ScriptOutput, String> {
+        let deadline = Instant::now() + SCRIPT_TIMEOUT;
+        let mut stderr = String::new();
+        loop {
+            let line = recv_before(&self.stderr, deadline)?;
+            if line == END_MARKER {
+                break;
+            }
+            on_stderr(&line);
+            stderr.push_str(&line);
+            stderr.push('\n');
+        }
+
+        let response = recv_before(&self.stdout, deadline)?;
+        let (code, encoded) = response.split_once(' ').unwrap_or((response.as_str(), ""));
+        let exit_code = code
+            .parse()
+            .map_err(|_| format!("Unexpected PowerShell host response: {}", response))?;
+        let stdout = general_purpose::STANDARD
+            .decode(encoded)
+            .map_err(|e| format!("Failed to decode PowerShell host output: {}", e))?;
+        Ok(ScriptOutput { stdout: String::from_utf8_lossy(&stdout).to_string(), stderr, exit_code })
+    }
+}
+
+/// How the hosts' recent scripts went, for the watchdog
+#[derive(Debug, Clone, Default)]
+pub struct HostHealth {
+    pub consecutive_failures: u32,
+    pub last_error: Option<String>,
+    /// Unix milliseconds
+    pub last_success: Option<i64>,
+}
+
+#[derive(Clone, Default)]
+pub struct PowerShellHostState {
+    idle: Arc<Mutex<Vec<HostProcess>>>,
+    health: Arc<Mutex<HostHealth>>,
+    /// Set on exit, after which hosts are no longer kept warm
+    closed: Arc<AtomicBool>,
+}
+
+impl PowerShellHostState {
+    /// Run a script in a warm host, starting one if none is idle. `on_stderr` sees each
+    /// `[Console]::Error` line as it is written; cancelling `operation` kills the host. Waiting
+    /// on the host happens on a blocking thread, so the async runtime keeps going meanwhile.
+    pub async fn run(
+        &self,
+        script: &str,
+        operation: Option<&OperationGuard>,
+        mut on_stderr: impl FnMut(&str),
+    ) -> Result<ScriptOutput, String> {
+        let state = self.clone();
+        let script = script.to_string();
+        let mut host = tauri::async_runtime::spawn_blocking(move || state.start(&script))
+            .await
+            .map_err(|e| format!("PowerShell host failed: {}", e))??;
+
+        if let Some(operation) = operation {
+            operation.attach(host.child.id());
+        }
+        // stderr lines come back over a channel, so `on_stderr` runs here rather than on the
+        // blocking thread and may borrow
+        let (lines_tx, mut lines) = tokio::sync::mpsc::unbounded_channel::<String>();
+        let receiving = tauri::async_runtime::spawn_blocking(move || {
+            let result = host.receive(&mut |line: &str| {
+                let _ = lines_tx.send(line.to_string());
+            });
+            (host, result)
+        });
+        while let Some(line) = lines.recv().await {
+            on_stderr(&line);
+        }
+        let (host, result) = receiving.await.map_err(|e| format!("PowerShell host failed: {}", e))?;
+        if let Some(operation) = operation {
+            operation.detach();
+            operation.check()?;
+        }
+        self.finish(host, result)
+    }
+
+    /// `run` for threads outside the async runtime, e.g. the warm-up
+    pub fn run_blocking(&self, script: &str) -> Result<ScriptOutput, String> {
+        let mut host = self.start(script)?;
+        let result = host.receive(&mut |_| {});
+        self.finish(host, result)
+    }
+
+    /// Hand `script` to a pooled host, or a fresh one if none is idle
+    fn start(&self, script: &str) -> Result<HostProcess, String> {
+        // A pooled host may have died since its last run; then a fresh one is started
+        let pooled = self.idle.lock().unwrap().pop();
+        match pooled.and_then(|mut host| host.send(script).is_ok().then_some(host)) {
+            Some(host) => Ok(host),
+            None => {
+                let mut fresh = HostProcess::spawn()?;
+                fresh.send(script)?;
+                Ok(fresh)
+            }
+        }
+    }
+
+    /// Record how the run went and pool the host again if it's still usable
+    fn finish(&self, host: HostProcess, result: Result<ScriptOutput, String>) -> Result<ScriptOutput, String> {
+        let mut health = self.health.lock().unwrap();
+        match &result {
+            Ok(_) => {
+                health.consecutive_failures = 0;
+                health.last_success = Some(chrono::Utc::now().timestamp_millis());
+            }
+            Err(e) => {
+                health.consecutive_failures += 1;
+                health.last_error = Some(e.clone());
+            }
+        }
+        drop(health);
+
+        // A host that failed mid-protocol is in an unknown state; drop (and kill) it
+        if result.is_ok() && !self.closed.load(Ordering::Relaxed) {
+            let mut idle = self.idle.lock().unwrap();
+            if idle.len() < POOL_SIZE {
+                idle.push(host);
+            }
+        }
+        result
+    }
+
+    pub fn health(&self) -> HostHealth {
+        self.health.lock().unwrap().clone()
+    }
+
+    /// Kill the idle hosts; a host running a script is killed once the script returns
+    pub fn shutdown(&self) {
+        self.closed.store(true, Ordering::Relaxed);
+        self.idle.lock().unwrap().clear();
+    }
+}
+
+/// Start a host in the background so the first capture or OCR call finds it warm
+pub fn init(app: &AppHandle) {
+    if !cfg!(target_os = "windows") {
+        return;
+    }
+    let state = app.state::<PowerShellHostState>().inner().clone();
+    std::thread::spawn(move || match state.run_blocking(WARM_UP_SCRIPT) {
+        Ok(_) => tracing::info!("⚡ PowerShell host ready"),
+        Err(e) => tracing::warn!("⚠️ Failed to warm up PowerShell host: {}", e),
+    });
+}
@@ -0,0 +1,205 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read};
+use std::process::{Child, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+
+use crate::commands::{build_process_command, check_command_permitted};
+use crate::sandbox::Confinement;
+
+/// Upper bound on concurrently running jobs so a runaway agent can't fork-bomb the machine
+const MAX_RUNNING_JOBS: usize = 8;
+const EXIT_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct JobInfo {
+    pub id: String,
+    pub command: String,
+    pub args: Vec<String>,
+    pub pid: u32,
+    pub started_at: i64,
+}
+
+/// Payload of the `job-output` event, one per line of output
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct JobOutput {
+    pub job_id: String,
+    pub stream: &'static str,
+    pub line: String,
+    pub timestamp: i64,
+}
+
+/// Payload of the `job-exited` event
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct JobExit {
+    pub job_id: String,
+    pub exit_code: Option<i32>,
+    pub killed: bool,
+}
+
+struct RunningJob {
+    info: JobInfo,
+    child: Arc<Mutex<Child>>,
+    killed: Arc<Mutex<bool>>,
+    /// Kills what the job left running once it is dropped from the table
+    _sandbox: Confinement,
+}
+
+#[derive(Clone, Default)]
+pub struct ProcessManagerState {
+    jobs: Arc<Mutex<HashMap<String, RunningJob>>>,
+}
+
+fn stream_lines<R: Read + Send + 'static>(app: AppHandle, job_id: String, stream: &'static str, reader: R) {
+    thread::spawn(move || {
+        for line in BufReader::new(reader).lines() {
+            let Ok(line) = line else { break };
+            let _ = crate::event_bus::emit(
+                &app,
+                crate::events::JOB_OUTPUT,
+                JobOutput {
+                    job_id: job_id.clone(),
+                    stream,
+                    line,
+                    timestamp: chrono::Utc::now().timestamp(),
+                },
+            );
+        }
+    });
+}
+
+/// Poll the child until it exits, then drop it from the job table and emit `job-exited`
+fn watch_exit(app: AppHandle, jobs: Arc<Mutex<HashMap<String, RunningJob>>>, job_id: String, child: Arc<Mutex<Child>>, killed: Arc<Mutex<bool>>) {
+    thread::spawn(move || loop {
+        let status = child.lock().unwrap().try_wait();
+        match status {
+            Ok(Some(status)) => {
+                jobs.lock().unwrap().remove(&job_id);
+                let killed = *killed.lock().unwrap();
+                tracing::info!("Job {} exited ({:?}, killed: {})", job_id, status.code(), killed);
+                let _ = crate::event_bus::emit(
+                    &app,
+                    crate::events::JOB_EXITED,
+                    JobExit {
+                        job_id,
+                        exit_code: status.code(),
+                        killed,
+                    },
+                );
+                break;
+            }
+            Ok(None) => thread::sleep(EXIT_POLL_INTERVAL),
+            Err(e) => {
+                tracing::warn!("⚠️ Failed to poll job {}: {}", job_id, e);
+                jobs.lock().unwrap().remove(&job_id);
+                break;
+            }
+        }
+    });
+}
+
+/// Kill the job with everything it started (npm and docker leave child processes behind),
+/// falling back to killing just the job's own process
+fn kill_job_inner(job: &RunningJob) -> Result<(), String> {
+    *job.killed.lock().unwrap() = true;
+    match crate::cancellation::kill_process_tree(job.info.pid) {
+        Ok(()) => return Ok(()),
+        Err(e) => tracing::warn!("⚠️ {}", e),
+    }
+    job.child
+        .lock()
+        .unwrap()
+        .kill()
+        .map_err(|e| format!("Failed to kill job {}: {}", job.info.id, e))
+}
+
+/// Kill every running job. Called when the app exits so nothing is orphaned.
+pub fn kill_all(app: &AppHandle) {
+    let state = app.state::<ProcessManagerState>();
+    let jobs = state.jobs.lock().unwrap();
+    for job in jobs.values() {
+        if let Err(e) = kill_job_inner(job) {
+            tracing::warn!("⚠️ {}", e);
+        }
+    }
+}
+
+/// Spawn an allowlisted command that keeps running (e.g. `docker compose logs -f`) under the
+/// sandbox limits and stream its output as `job-output` events
+#[tauri::command]
+pub async fn start_long_running_command(
+    app: AppHandle,
+    state: State<'_, ProcessManagerState>,
+    command: String,
+    args: Vec<String>,
+) -> Result<JobInfo, String> {
+    check_command_permitted(&app, &command, &args)?;
+
+    if state.jobs.lock().unwrap().len() >= MAX_RUNNING_JOBS {
+        return Err(format!(
+            "Too many running jobs (max {}). Stop one before starting another.",
+            MAX_RUNNING_JOBS
+        ));
+    }
+
+    let mut process = build_process_command(&command, &args);
+    process.stdin(Stdio::null()).stdout(Stdio::piped()).stderr(Stdio::piped());
+    let (mut child, sandbox) = crate::sandbox::spawn(&app, process)?;
+
+    let info = JobInfo {
+        id: uuid::Uuid::new_v4().to_string(),
+        command,
+        args,
+        pid: child.id(),
+        started_at: chrono::Utc::now().timestamp(),
+    };
+    tracing::info!("▶️ Started job {} (pid {}): {} {:?}", info.id, info.pid, info.command, info.args);
+
+    if let Some(stdout) = child.stdout.take() {
+        stream_lines(app.clone(), info.id.clone(), "stdout", stdout);
+    }
+    if let Some(stderr) = child.stderr.take() {
+        stream_lines(app.clone(), info.id.clone(), "stderr", stderr);
+    }
+
+    let child = Arc::new(Mutex::new(child));
+    let killed = Arc::new(Mutex::new(false));
+    state.jobs.lock().unwrap().insert(
+        info.id.clone(),
+        RunningJob {
+            info: info.clone(),
+            child: child.clone(),
+            killed: killed.clone(),
+            _sandbox: sandbox,
+        },
+    );
+    watch_exit(app, state.jobs.clone(), info.id.clone(), child, killed);
+
+    Ok(info)
+}
+
+#[tauri::command]
+pub async fn list_running_jobs(
+    state: State<'_, ProcessManagerState>,
+) -> Result<Vec<JobInfo>, String> {
+    let jobs = state.jobs.lock().unwrap();
+    let mut infos: Vec<JobInfo> = jobs.values().map(|job| job.info.clone()).collect();
+    infos.sort_by_key(|info| info.started_at);
+    Ok(infos)
+}
+
+#[tauri::command]
+pub async fn kill_job(
+    state: State<'_, ProcessManagerState>,
+    id: String,
+) -> Result<bool, String> {
+    let jobs = state.jobs.lock().unwrap();
+    match jobs.get(&id) {
+        Some(job) => kill_job_inner(job).map(|_| true),
+        None => Ok(false),
+    }
+}
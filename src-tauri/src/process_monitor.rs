@@ -4,3 +4,150 @@ pub mod windows;
 #[cfg(not(target_os = "windows"))]
 pub mod unix;
 
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use tauri::{AppHandle, Manager};
+
+use crate::powershell_host::PowerShellHostState;
+
+/// Levels of descendants included below the requested process
+const MAX_TREE_DEPTH: usize = 16;
+
+const LIST_PROCESSES_SCRIPT: &str = r#"
+$procs = Get-CimInstance Win32_Process | ForEach-Object {
+    [pscustomobject]@{
+        pid = [uint32]$_.ProcessId
+        parent_pid = [uint32]$_.ParentProcessId
+        name = $_.Name
+        command_line = $_.CommandLine
+        started_at = if ($_.CreationDate) { ([DateTimeOffset]$_.CreationDate).ToUnixTimeSeconds() } else { $null }
+    }
+}
+ConvertTo-Json -InputObject @($procs) -Compress
+"#;
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ProcessInfo {
+    pub pid: u32,
+    pub parent_pid: Option<u32>,
+    pub name: String,
+    /// Full command line; None when the OS doesn't reveal it (e.g. another user's process)
+    pub command_line: Option<String>,
+    /// Unix timestamp; only known on Windows
+    pub started_at: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ProcessNode {
+    #[serde(flatten)]
+    pub process: ProcessInfo,
+    pub children: Vec<ProcessNode>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ProcessTree {
+    /// The requested process with its descendants
+    pub root: ProcessNode,
+    /// Parent, grandparent and so on, nearest first
+    pub ancestors: Vec<ProcessInfo>,
+}
+
+async fn list_processes(app: &AppHandle) -> Result<Vec<ProcessInfo>, String> {
+    if cfg!(target_os = "windows") {
+        let output = app.state::<PowerShellHostState>().run(LIST_PROCESSES_SCRIPT, None, |_| {}).await?;
+        if !output.success() {
+            return Err(format!("Failed to list processes: {}", output.stderr.trim()));
+        }
+        return serde_json::from_str(output.stdout.trim()).map_err(|e| format!("Failed to parse process list: {}", e));
+    }
+
+    let output = tokio::process::Command::new("ps")
+        .args(["-ww", "-A", "-o", "pid=,ppid=,args="])
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run ps: {}", e))?;
+    if !output.status.success() {
+        return Err(format!("ps failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(stdout
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let pid = fields.next()?.parse().ok()?;
+            let parent_pid = fields.next()?.parse().ok();
+            let command_line = fields.collect::<Vec<_>>().join(" ");
+            let program = command_line.split_whitespace().next().unwrap_or_default();
+            let name = program.rsplit('/').next().unwrap_or(program).to_string();
+            Some(ProcessInfo {
+                pid,
+                parent_pid,
+                name,
+                command_line: (!command_line.is_empty()).then_some(command_line),
+                started_at: None,
+            })
+        })
+        .collect())
+}
+
+/// Whether `child` really descends from `parent`: Windows reuses the pids of exited parents,
+/// so a parent that started after the child is someone else
+fn is_parent(parent: &ProcessInfo, child: &ProcessInfo) -> bool {
+    parent.pid != child.pid
+        && child.parent_pid == Some(parent.pid)
+        && match (parent.started_at, child.started_at) {
+            (Some(parent_start), Some(child_start)) => parent_start <= child_start,
+            _ => true,
+        }
+}
+
+fn build_node(
+    process: &ProcessInfo,
+    children_of: &HashMap<u32, Vec<&ProcessInfo>>,
+    visited: &mut HashSet<u32>,
+    depth: usize,
+) -> ProcessNode {
+    visited.insert(process.pid);
+    let mut children = Vec::new();
+    if depth < MAX_TREE_DEPTH {
+        for child in children_of.get(&process.pid).into_iter().flatten() {
+            if is_parent(process, child) && !visited.contains(&child.pid) {
+                children.push(build_node(child, children_of, visited, depth + 1));
+            }
+        }
+    }
+    ProcessNode { process: process.clone(), children }
+}
+
+/// A process with its descendants (and their command lines) and its ancestors, e.g. to check
+/// that `npm run dev` actually started the node server
+#[tauri::command]
+pub async fn get_process_tree(app: AppHandle, pid: u32) -> Result<ProcessTree, String> {
+    let processes = list_processes(&app).await?;
+    let by_pid: HashMap<u32, &ProcessInfo> = processes.iter().map(|p| (p.pid, p)).collect();
+    let root = by_pid.get(&pid).ok_or_else(|| format!("No process with pid {}", pid))?;
+
+    let mut children_of: HashMap<u32, Vec<&ProcessInfo>> = HashMap::new();
+    for process in &processes {
+        if let Some(parent_pid) = process.parent_pid {
+            children_of.entry(parent_pid).or_default().push(process);
+        }
+    }
+    for children in children_of.values_mut() {
+        children.sort_by_key(|p| (p.started_at, p.pid));
+    }
+
+    let mut ancestors = Vec::new();
+    let mut seen = HashSet::from([pid]);
+    let mut current = *root;
+    while let Some(parent) = current.parent_pid.and_then(|ppid| by_pid.get(&ppid)) {
+        if !is_parent(parent, current) || !seen.insert(parent.pid) {
+            break;
+        }
+        ancestors.push((*parent).clone());
+        current = parent;
+    }
+
+    Ok(ProcessTree { root: build_node(root, &children_of, &mut HashSet::new(), 0), ancestors })
+}
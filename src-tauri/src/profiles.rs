@@ -0,0 +1,151 @@
+//! Student profiles, so siblings sharing a PC keep separate settings, history, captures and
+//! usage stats. The default profile lives directly in the app data directory (where everything
+//! was before profiles existed); others get `profiles/<id>/`. The profile is fixed for the
+//! lifetime of the process: switching saves the choice and restarts the app, so every module
+//! reloads its state from the new directory.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use tauri::{AppHandle, Manager, State};
+
+use crate::persistence;
+
+const PROFILES_FILE: &str = "profiles.json";
+const PROFILES_DIR: &str = "profiles";
+const DEFAULT_PROFILE_ID: &str = "default";
+const MAX_NAME_LENGTH: usize = 40;
+
+/// Data directory of the running profile relative to the app data directory; None for the
+/// default profile
+static ACTIVE_SUBDIR: OnceLock<Option<PathBuf>> = OnceLock::new();
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Profile {
+    pub id: String,
+    pub name: String,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ProfilesFile {
+    profiles: Vec<Profile>,
+    active: String,
+}
+
+impl Default for ProfilesFile {
+    fn default() -> Self {
+        Self {
+            profiles: vec![default_profile()],
+            active: DEFAULT_PROFILE_ID.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ProfileList {
+    pub profiles: Vec<Profile>,
+    /// Id of the profile the app is running as
+    pub active: String,
+}
+
+#[derive(Clone, Default)]
+pub struct ProfilesState {
+    file: Arc<Mutex<ProfilesFile>>,
+}
+
+fn default_profile() -> Profile {
+    Profile { id: DEFAULT_PROFILE_ID.to_string(), name: "Default".to_string(), created_at: 0 }
+}
+
+fn subdir(id: &str) -> Option<PathBuf> {
+    (id != DEFAULT_PROFILE_ID).then(|| Path::new(PROFILES_DIR).join(id))
+}
+
+/// Directory of the running profile's data relative to the app data directory (and to the
+/// captures directory); None for the default profile or before `init`
+pub fn active_subdir() -> Option<&'static Path> {
+    ACTIVE_SUBDIR.get().and_then(|dir| dir.as_deref())
+}
+
+fn save(app: &AppHandle, file: &ProfilesFile) -> Result<(), String> {
+    let path = persistence::shared_data_file(app, PROFILES_FILE)?;
+    persistence::save_json(&path, file)
+}
+
+/// Load the profile list and select the active profile. Must run before any module loads its
+/// data with `persistence::data_file`.
+pub fn init(app: &AppHandle) {
+    let mut file: ProfilesFile = persistence::shared_data_file(app, PROFILES_FILE)
+        .map(|path| persistence::load_json(&path))
+        .unwrap_or_default();
+    if !file.profiles.iter().any(|p| p.id == DEFAULT_PROFILE_ID) {
+        file.profiles.insert(0, default_profile());
+    }
+    if !file.profiles.iter().any(|p| p.id == file.active) {
+        tracing::warn!("⚠️ Active profile {} no longer exists, using the default profile", file.active);
+        file.active = DEFAULT_PROFILE_ID.to_string();
+    }
+
+    let _ = ACTIVE_SUBDIR.set(subdir(&file.active));
+    if file.active != DEFAULT_PROFILE_ID {
+        tracing::info!("👤 Running as profile {}", file.active);
+    }
+    *app.state::<ProfilesState>().file.lock().unwrap() = file;
+}
+
+#[tauri::command]
+pub async fn list_profiles(state: State<'_, ProfilesState>) -> Result<ProfileList, String> {
+    let file = state.file.lock().unwrap();
+    Ok(ProfileList { profiles: file.profiles.clone(), active: file.active.clone() })
+}
+
+/// Add a profile with its own data directory; it becomes active after `switch_profile`
+#[tauri::command]
+pub async fn create_profile(
+    app: AppHandle,
+    state: State<'_, ProfilesState>,
+    name: String,
+) -> Result<Profile, String> {
+    let name = name.trim().to_string();
+    if name.is_empty() || name.chars().count() > MAX_NAME_LENGTH {
+        return Err(format!("Profile name must be 1 to {} characters", MAX_NAME_LENGTH));
+    }
+
+    let mut file = state.file.lock().unwrap();
+    if file.profiles.iter().any(|p| p.name.eq_ignore_ascii_case(&name)) {
+        return Err(format!("A profile named '{}' already exists", name));
+    }
+    let profile = Profile {
+        id: uuid::Uuid::new_v4().simple().to_string()[..8].to_string(),
+        name,
+        created_at: chrono::Utc::now().timestamp(),
+    };
+    if let Some(dir) = subdir(&profile.id) {
+        let dir = persistence::shared_data_file(&app, &dir.to_string_lossy())?;
+        std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create profile directory: {}", e))?;
+    }
+    file.profiles.push(profile.clone());
+    save(&app, &file)?;
+    tracing::info!("👤 Created profile {} ({})", profile.name, profile.id);
+    Ok(profile)
+}
+
+/// Make a profile active and restart the app into it
+#[tauri::command]
+pub async fn switch_profile(app: AppHandle, state: State<'_, ProfilesState>, id: String) -> Result<(), String> {
+    {
+        let mut file = state.file.lock().unwrap();
+        if !file.profiles.iter().any(|p| p.id == id) {
+            return Err(format!("No profile with id {}", id));
+        }
+        if file.active == id {
+            return Ok(());
+        }
+        file.active = id.clone();
+        save(&app, &file)?;
+    }
+    tracing::info!("👤 Switching to profile {}, restarting", id);
+    app.restart();
+}
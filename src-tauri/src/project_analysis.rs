@@ -0,0 +1,323 @@
+//! What kind of project a folder holds and how it is run. Reads package.json, Cargo.toml,
+//! pyproject.toml and requirements.txt in the root and its direct subfolders and suggests the
+//! canonical install/run/test commands. Nothing is executed.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+use crate::project_files::{self, ProjectRootsState};
+
+/// Manifests bigger than this aren't real project files
+const MAX_MANIFEST_BYTES: u64 = 512 * 1024;
+/// package.json scripts worth suggesting, in the order they're listed
+const NODE_SCRIPTS: &[(&str, &str)] =
+    &[("dev", "dev"), ("start", "run"), ("build", "build"), ("test", "test"), ("lint", "lint")];
+/// Dependency name and the framework it indicates
+const NODE_FRAMEWORKS: &[(&str, &str)] = &[
+    ("next", "Next.js"),
+    ("nuxt", "Nuxt"),
+    ("@sveltejs/kit", "SvelteKit"),
+    ("@angular/core", "Angular"),
+    ("react", "React"),
+    ("vue", "Vue"),
+    ("svelte", "Svelte"),
+    ("@tauri-apps/api", "Tauri"),
+    ("electron", "Electron"),
+    ("express", "Express"),
+    ("vite", "Vite"),
+    ("jest", "Jest"),
+    ("vitest", "Vitest"),
+];
+const RUST_FRAMEWORKS: &[(&str, &str)] = &[
+    ("tauri", "Tauri"),
+    ("axum", "Axum"),
+    ("actix-web", "Actix Web"),
+    ("rocket", "Rocket"),
+    ("bevy", "Bevy"),
+    ("tokio", "Tokio"),
+];
+const PYTHON_FRAMEWORKS: &[(&str, &str)] = &[
+    ("django", "Django"),
+    ("flask", "Flask"),
+    ("fastapi", "FastAPI"),
+    ("streamlit", "Streamlit"),
+    ("pytest", "pytest"),
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SuggestedCommand {
+    /// "install", "dev", "run", "build", "test" or "lint"
+    pub purpose: String,
+    pub command: String,
+    /// Folder to run it in, relative to the analyzed root ("" for the root itself)
+    pub cwd: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DetectedProject {
+    /// Manifest the project was detected from, relative to the analyzed root
+    pub manifest: String,
+    /// "javascript", "typescript", "rust" or "python"
+    pub language: String,
+    pub name: Option<String>,
+    /// npm, yarn, pnpm, bun, cargo, pip, poetry, uv or pdm
+    pub package_manager: String,
+    pub frameworks: Vec<String>,
+    pub commands: Vec<SuggestedCommand>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ProjectAnalysis {
+    pub root: String,
+    pub projects: Vec<DetectedProject>,
+}
+
+fn read_manifest(path: &Path) -> Option<String> {
+    let metadata = fs::metadata(path).ok()?;
+    if !metadata.is_file() || metadata.len() > MAX_MANIFEST_BYTES {
+        return None;
+    }
+    fs::read_to_string(path).ok()
+}
+
+fn frameworks<'a>(known: &[(&str, &str)], dependencies: impl Iterator<Item = &'a str> + Clone) -> Vec<String> {
+    known
+        .iter()
+        .filter(|(dependency, _)| dependencies.clone().any(|d| d.eq_ignore_ascii_case(dependency)))
+        .map(|(_, framework)| framework.to_string())
+        .collect()
+}
+
+fn suggest(commands: &mut Vec<SuggestedCommand>, cwd: &str, purpose: &str, command: impl Into<String>) {
+    commands.push(SuggestedCommand { purpose: purpose.to_string(), command: command.into(), cwd: cwd.to_string() });
+}
+
+fn node_project(dir: &Path, cwd: &str) -> Option<DetectedProject> {
+    let manifest: serde_json::Value = serde_json::from_str(&read_manifest(&dir.join("package.json"))?).ok()?;
+    // `packageManager` ("pnpm@9.1.0") wins over lockfiles
+    let declared = manifest["packageManager"].as_str().and_then(|pm| pm.split('@').next());
+    let package_manager = match declared {
+        Some(pm @ ("npm" | "yarn" | "pnpm" | "bun")) => pm,
+        _ if dir.join("pnpm-lock.yaml").is_file() => "pnpm",
+        _ if dir.join("yarn.lock").is_file() => "yarn",
+        _ if dir.join("bun.lockb").is_file() || dir.join("bun.lock").is_file() => "bun",
+        _ => "npm",
+    };
+
+    let dependencies: Vec<&str> = ["dependencies", "devDependencies"]
+        .iter()
+        .filter_map(|key| manifest[key].as_object())
+        .flat_map(|deps| deps.keys().map(String::as_str))
+        .collect();
+    let typescript = dir.join("tsconfig.json").is_file() || dependencies.contains(&"typescript");
+
+    let mut commands = Vec::new();
+    suggest(&mut commands, cwd, "install", format!("{} install", package_manager));
+    let scripts = manifest["scripts"].as_object();
+    for (script, purpose) in NODE_SCRIPTS {
+        if !scripts.is_some_and(|s| s.contains_key(*script)) {
+            continue;
+        }
+        let command = match (package_manager, *script) {
+            ("npm", "start" | "test") => format!("npm {}", script),
+            ("npm" | "bun", _) => format!("{} run {}", package_manager, script),
+            _ => format!("{} {}", package_manager, script),
+        };
+        suggest(&mut commands, cwd, purpose, command);
+    }
+
+    Some(DetectedProject {
+        manifest: join(cwd, "package.json"),
+        language: if typescript { "typescript" } else { "javascript" }.to_string(),
+        name: manifest["name"].as_str().map(str::to_string),
+        package_manager: package_manager.to_string(),
+        frameworks: frameworks(NODE_FRAMEWORKS, dependencies.iter().copied()),
+        commands,
+    })
+}
+
+fn rust_project(dir: &Path, cwd: &str) -> Option<DetectedProject> {
+    let manifest: toml::Table = read_manifest(&dir.join("Cargo.toml"))?.parse().ok()?;
+    let dependencies: Vec<&str> = ["dependencies", "dev-dependencies", "build-dependencies"]
+        .iter()
+        .filter_map(|key| manifest.get(*key).and_then(|d| d.as_table()))
+        .chain(manifest.get("workspace").and_then(|w| w.get("dependencies")).and_then(|d| d.as_table()))
+        .flat_map(|deps| deps.keys().map(String::as_str))
+        .collect();
+    let workspace = manifest.contains_key("workspace");
+    let has_binary = dir.join("src/main.rs").is_file() || manifest.get("bin").is_some();
+
+    let mut commands = Vec::new();
+    let all = if workspace { " --workspace" } else { "" };
+    suggest(&mut commands, cwd, "build", format!("cargo build{}", all));
+    if has_binary {
+        suggest(&mut commands, cwd, "run", "cargo run");
+    }
+    suggest(&mut commands, cwd, "test", format!("cargo test{}", all));
+    suggest(&mut commands, cwd, "lint", format!("cargo clippy{}", all));
+
+    Some(DetectedProject {
+        manifest: join(cwd, "Cargo.toml"),
+        language: "rust".to_string(),
+        name: manifest.get("package").and_then(|p| p.get("name")).and_then(|n| n.as_str()).map(str::to_string),
+        package_manager: "cargo".to_string(),
+        frameworks: frameworks(RUST_FRAMEWORKS, dependencies.iter().copied()),
+        commands,
+    })
+}
+
+/// Package name of a PEP 508 requirement such as `Django>=4.2` or `uvicorn[standard]`
+fn requirement_name(requirement: &str) -> &str {
+    let end = requirement.find(|c: char| !(c.is_alphanumeric() || matches!(c, '-' | '_' | '.'))).unwrap_or(requirement.len());
+    &requirement[..end]
+}
+
+fn python_project(dir: &Path, cwd: &str) -> Option<DetectedProject> {
+    let (manifest_name, manifest) = match read_manifest(&dir.join("pyproject.toml")) {
+        Some(text) => ("pyproject.toml", text.parse::<toml::Table>().ok()?),
+        None => {
+            // A bare requirements.txt still makes a (pip) project
+            let requirements = read_manifest(&dir.join("requirements.txt"))?;
+            let mut manifest = toml::Table::new();
+            let lines = requirements
+                .lines()
+                .map(str::trim)
+                .filter(|l| !l.is_empty() && !l.starts_with('#') && !l.starts_with('-'))
+                .map(|l| toml::Value::String(l.to_string()))
+                .collect();
+            manifest.insert("requirements".to_string(), toml::Value::Array(lines));
+            ("requirements.txt", manifest)
+        }
+    };
+    let tool = manifest.get("tool").and_then(|t| t.as_table());
+    let poetry = tool.and_then(|t| t.get("poetry"));
+    let project = manifest.get("project");
+
+    let mut dependencies: Vec<String> = project
+        .and_then(|p| p.get("dependencies"))
+        .into_iter()
+        .chain(project.and_then(|p| p.get("optional-dependencies")).and_then(|o| o.as_table()).into_iter().flat_map(|o| o.values()))
+        .chain(manifest.get("requirements"))
+        .filter_map(|list| list.as_array())
+        .flatten()
+        .filter_map(|r| r.as_str())
+        .map(|r| requirement_name(r).to_string())
+        .collect();
+    for key in ["dependencies", "dev-dependencies"] {
+        if let Some(deps) = poetry.and_then(|p| p.get(key)).and_then(|d| d.as_table()) {
+            dependencies.extend(deps.keys().cloned());
+        }
+    }
+    if let Some(groups) = poetry.and_then(|p| p.get("group")).and_then(|g| g.as_table()) {
+        for deps in groups.values().filter_map(|g| g.get("dependencies")).filter_map(|d| d.as_table()) {
+            dependencies.extend(deps.keys().cloned());
+        }
+    }
+    // requirements-dev.txt commonly lists the test runner
+    if let Some(dev) = read_manifest(&dir.join("requirements-dev.txt")) {
+        dependencies.extend(dev.lines().map(|l| requirement_name(l.trim()).to_string()));
+    }
+
+    let package_manager = if poetry.is_some() || dir.join("poetry.lock").is_file() {
+        "poetry"
+    } else if tool.is_some_and(|t| t.contains_key("uv")) || dir.join("uv.lock").is_file() {
+        "uv"
+    } else if tool.is_some_and(|t| t.contains_key("pdm")) || dir.join("pdm.lock").is_file() {
+        "pdm"
+    } else {
+        "pip"
+    };
+    let run = |command: &str| match package_manager {
+        "pip" => command.to_string(),
+        pm => format!("{} run {}", pm, command),
+    };
+
+    let mut commands = Vec::new();
+    let install = match (package_manager, manifest_name) {
+        ("uv", _) => "uv sync",
+        ("pip", "requirements.txt") => "pip install -r requirements.txt",
+        ("pip", _) => "pip install -e .",
+        ("poetry", _) => "poetry install",
+        _ => "pdm install",
+    };
+    suggest(&mut commands, cwd, "install", install);
+    let detected = frameworks(PYTHON_FRAMEWORKS, dependencies.iter().map(String::as_str));
+    let has = |framework: &str| detected.iter().any(|f| f == framework);
+    if dir.join("manage.py").is_file() {
+        suggest(&mut commands, cwd, "run", run("python manage.py runserver"));
+    } else if has("Flask") {
+        suggest(&mut commands, cwd, "run", run("flask run"));
+    } else if has("FastAPI") && dir.join("main.py").is_file() {
+        suggest(&mut commands, cwd, "run", run("uvicorn main:app --reload"));
+    } else if has("Streamlit") && dir.join("app.py").is_file() {
+        suggest(&mut commands, cwd, "run", run("streamlit run app.py"));
+    } else if dir.join("main.py").is_file() {
+        suggest(&mut commands, cwd, "run", run("python main.py"));
+    }
+    if dir.join("manage.py").is_file() {
+        suggest(&mut commands, cwd, "test", run("python manage.py test"));
+    } else if has("pytest") {
+        suggest(&mut commands, cwd, "test", run("pytest"));
+    } else {
+        suggest(&mut commands, cwd, "test", run("python -m unittest"));
+    }
+
+    Some(DetectedProject {
+        manifest: join(cwd, manifest_name),
+        language: "python".to_string(),
+        name: project
+            .or(poetry)
+            .and_then(|p| p.get("name"))
+            .and_then(|n| n.as_str())
+            .map(str::to_string),
+        package_manager: package_manager.to_string(),
+        frameworks: detected,
+        commands,
+    })
+}
+
+fn join(cwd: &str, file: &str) -> String {
+    if cwd.is_empty() {
+        file.to_string()
+    } else {
+        format!("{}/{}", cwd, file)
+    }
+}
+
+fn analyze_dir(dir: &Path, cwd: &str, projects: &mut Vec<DetectedProject>) {
+    projects.extend(node_project(dir, cwd));
+    projects.extend(rust_project(dir, cwd));
+    projects.extend(python_project(dir, cwd));
+}
+
+/// Detect the projects in `root` (an approved project folder) and its direct subfolders, with
+/// the commands that install, run, build and test them. The commands are suggestions only.
+#[tauri::command]
+pub async fn analyze_project(roots: State<'_, ProjectRootsState>, root: String) -> Result<ProjectAnalysis, String> {
+    let resolved = roots.resolve(&root)?;
+    if !resolved.is_dir() {
+        return Err(format!("{} is not a folder", root));
+    }
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut projects = Vec::new();
+        analyze_dir(&resolved, "", &mut projects);
+        let mut subdirs: Vec<PathBuf> = fs::read_dir(&resolved)
+            .map_err(|e| format!("Failed to read {}: {}", resolved.display(), e))?
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+            .map(|e| e.path())
+            .filter(|p| !project_files::is_ignored_path(Path::new(p.file_name().unwrap_or_default())))
+            .collect();
+        subdirs.sort();
+        for dir in subdirs {
+            let cwd = dir.file_name().unwrap_or_default().to_string_lossy().into_owned();
+            analyze_dir(&dir, &cwd, &mut projects);
+        }
+        Ok(ProjectAnalysis { root: resolved.to_string_lossy().into_owned(), projects })
+    })
+    .await
+    .map_err(|e| format!("Failed to analyze {}: {}", root, e))?
+}
@@ -0,0 +1,292 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager, State};
+
+use crate::persistence;
+
+const ROOTS_FILE: &str = "project_roots.json";
+
+/// Files larger than this are truncated when read and skipped when searching
+const MAX_FILE_BYTES: u64 = 1024 * 1024;
+const MAX_SEARCH_RESULTS: usize = 200;
+
+/// Directories that are never worth showing to the tutor (dependencies, build output, VCS data)
+const SKIPPED_DIRS: &[&str] = &["node_modules", ".git", "target", "dist", "build", "__pycache__", ".venv", "venv"];
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ProjectFile {
+    pub path: String,
+    pub content: String,
+    pub size: u64,
+    /// Whether the content was cut off at the size limit
+    pub truncated: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DirectoryEntry {
+    pub name: String,
+    pub path: String,
+    pub is_dir: bool,
+    pub size: u64,
+    pub modified: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SearchMatch {
+    pub path: String,
+    pub line_number: usize,
+    pub line: String,
+}
+
+/// Folders the user has approved for the tutor to read
+#[derive(Clone, Default)]
+pub struct ProjectRootsState {
+    pub roots: Arc<Mutex<Vec<PathBuf>>>,
+}
+
+impl ProjectRootsState {
+    /// Canonicalize `path` and make sure it lies inside one of the approved roots.
+    /// Canonicalizing first means `..` segments and symlinks can't escape the sandbox.
+    pub fn resolve(&self, path: &str) -> Result<PathBuf, String> {
+        self.resolve_in_root(path).map(|(resolved, _)| resolved)
+    }
+
+    /// Like `resolve`, also returning the outermost approved root the path lies in
+    pub fn resolve_in_root(&self, path: &str) -> Result<(PathBuf, PathBuf), String> {
+        let resolved = fs::canonicalize(path).map_err(|e| format!("Cannot access {}: {}", path, e))?;
+        let roots = self.roots.lock().unwrap();
+        match roots.iter().filter(|root| resolved.starts_with(root)).min_by_key(|root| root.components().count()) {
+            Some(root) => Ok((resolved.clone(), root.clone())),
+            None => Err(format!("{} is outside the approved project folders", path)),
+        }
+    }
+}
+
+fn persist(app: &AppHandle, roots: &[PathBuf]) -> Result<(), String> {
+    let path = persistence::data_file(app, ROOTS_FILE)?;
+    persistence::save_json(&path, &roots)
+}
+
+/// Load the approved roots saved by a previous run, dropping folders that no longer exist
+pub fn init(app: &AppHandle) {
+    let loaded: Vec<PathBuf> = match persistence::data_file(app, ROOTS_FILE) {
+        Ok(path) => persistence::load_json(&path),
+        Err(e) => {
+            tracing::warn!("⚠️ {}", e);
+            Vec::new()
+        }
+    };
+    let roots: Vec<PathBuf> = loaded.into_iter().filter(|root| root.is_dir()).collect();
+    *app.state::<ProjectRootsState>().roots.lock().unwrap() = roots;
+}
+
+fn display_path(path: &Path) -> String {
+    path.to_string_lossy().to_string()
+}
+
+fn is_skipped_dir(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .map(|name| SKIPPED_DIRS.contains(&name))
+        .unwrap_or(false)
+}
+
+/// Whether any component of `path` is a dependency, build output or VCS directory
+pub(crate) fn is_ignored_path(path: &Path) -> bool {
+    path.components()
+        .filter_map(|component| component.as_os_str().to_str())
+        .any(|name| SKIPPED_DIRS.contains(&name))
+}
+
+/// Heuristic binary check: text files don't contain NUL bytes
+pub(crate) fn looks_binary(bytes: &[u8]) -> bool {
+    bytes.iter().take(8000).any(|b| *b == 0)
+}
+
+fn search_file(path: &Path, query: &str, results: &mut Vec<SearchMatch>) {
+    let Ok(metadata) = fs::metadata(path) else { return };
+    if metadata.len() > MAX_FILE_BYTES {
+        return;
+    }
+    let Ok(bytes) = fs::read(path) else { return };
+    if looks_binary(&bytes) {
+        return;
+    }
+    let text = String::from_utf8_lossy(&bytes);
+    for (index, line) in text.lines().enumerate() {
+        if results.len() >= MAX_SEARCH_RESULTS {
+            return;
+        }
+        if line.to_lowercase().contains(query) {
+            results.push(SearchMatch {
+                path: display_path(path),
+                line_number: index + 1,
+                line: line.trim().chars().take(300).collect(),
+            });
+        }
+    }
+}
+
+fn search_dir(dir: &Path, query: &str, results: &mut Vec<SearchMatch>) {
+    let Ok(entries) = fs::read_dir(dir) else { return };
+    let mut paths: Vec<PathBuf> = entries.filter_map(|e| e.ok()).map(|e| e.path()).collect();
+    paths.sort();
+    for path in paths {
+        if results.len() >= MAX_SEARCH_RESULTS {
+            return;
+        }
+        // Don't follow symlinks out of the approved root
+        let Ok(metadata) = fs::symlink_metadata(&path) else { continue };
+        if metadata.is_dir() {
+            if !is_skipped_dir(&path) {
+                search_dir(&path, query, results);
+            }
+        } else if metadata.is_file() {
+            search_file(&path, query, results);
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn get_project_roots(
+    state: State<'_, ProjectRootsState>,
+) -> Result<Vec<String>, String> {
+    let roots = state.roots.lock().unwrap();
+    Ok(roots.iter().map(|root| display_path(root)).collect())
+}
+
+/// Approve a folder for the tutor to read. Only call this in response to an explicit user choice.
+#[tauri::command]
+pub async fn add_project_root(
+    app: AppHandle,
+    state: State<'_, ProjectRootsState>,
+    path: String,
+) -> Result<Vec<String>, String> {
+    let root = fs::canonicalize(&path).map_err(|e| format!("Cannot access {}: {}", path, e))?;
+    if !root.is_dir() {
+        return Err(format!("{} is not a folder", path));
+    }
+
+    let mut roots = state.roots.lock().unwrap();
+    if !roots.contains(&root) {
+        tracing::debug!("📁 Approved project root {}", root.display());
+        roots.push(root);
+        persist(&app, &roots)?;
+    }
+    let approved = roots.iter().map(|root| display_path(root)).collect();
+    drop(roots);
+    crate::file_watcher::refresh(&app);
+    Ok(approved)
+}
+
+#[tauri::command]
+pub async fn remove_project_root(
+    app: AppHandle,
+    state: State<'_, ProjectRootsState>,
+    path: String,
+) -> Result<bool, String> {
+    let root = fs::canonicalize(&path).unwrap_or_else(|_| PathBuf::from(&path));
+    let mut roots = state.roots.lock().unwrap();
+    let before = roots.len();
+    roots.retain(|r| *r != root);
+    let removed = roots.len() != before;
+    if removed {
+        persist(&app, &roots)?;
+        drop(roots);
+        crate::file_watcher::refresh(&app);
+    }
+    Ok(removed)
+}
+
+#[tauri::command]
+pub async fn read_project_file(
+    state: State<'_, ProjectRootsState>,
+    path: String,
+) -> Result<ProjectFile, String> {
+    let resolved = state.resolve(&path)?;
+    if !resolved.is_file() {
+        return Err(format!("{} is not a file", path));
+    }
+
+    let file = File::open(&resolved).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let size = file.metadata().map_err(|e| format!("Failed to read {}: {}", path, e))?.len();
+    // Only the part that is returned is read, however large the file
+    let mut bytes = Vec::new();
+    file.take(MAX_FILE_BYTES)
+        .read_to_end(&mut bytes)
+        .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    if looks_binary(&bytes) {
+        return Err(format!("{} is a binary file", path));
+    }
+
+    let truncated = size > MAX_FILE_BYTES;
+    let content = String::from_utf8_lossy(&bytes).to_string();
+
+    Ok(ProjectFile {
+        path: display_path(&resolved),
+        content,
+        size,
+        truncated,
+    })
+}
+
+#[tauri::command]
+pub async fn list_directory(
+    state: State<'_, ProjectRootsState>,
+    path: String,
+) -> Result<Vec<DirectoryEntry>, String> {
+    let resolved = state.resolve(&path)?;
+    let entries = fs::read_dir(&resolved).map_err(|e| format!("Failed to list {}: {}", path, e))?;
+
+    let mut listing: Vec<DirectoryEntry> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            let modified = metadata
+                .modified()
+                .ok()
+                .map(|time| chrono::DateTime::<chrono::Utc>::from(time).timestamp());
+            Some(DirectoryEntry {
+                name: entry.file_name().to_string_lossy().to_string(),
+                path: display_path(&entry.path()),
+                is_dir: metadata.is_dir(),
+                size: metadata.len(),
+                modified,
+            })
+        })
+        .collect();
+
+    // Folders first, then alphabetical
+    listing.sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase())));
+    Ok(listing)
+}
+
+/// Case-insensitive text search across the text files under `root`
+#[tauri::command]
+pub async fn search_in_files(
+    state: State<'_, ProjectRootsState>,
+    root: String,
+    query: String,
+) -> Result<Vec<SearchMatch>, String> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return Err("Search query is empty".to_string());
+    }
+    let resolved = state.resolve(&root)?;
+
+    tokio::task::spawn_blocking(move || {
+        let mut results = Vec::new();
+        if resolved.is_file() {
+            search_file(&resolved, &query, &mut results);
+        } else {
+            search_dir(&resolved, &query, &mut results);
+        }
+        results
+    })
+    .await
+    .map_err(|e| format!("Search failed: {}", e))
+}
@@ -0,0 +1,238 @@
+//! Named prompt templates, such as system prompts for a math tutor or a code reviewer, kept in
+//! the history database so they can be changed without rebuilding the app. Templates use
+//! `{{variable}}` placeholders and every save adds a new version; the latest one is used unless
+//! a version is asked for. A template named `tutor` replaces the built-in tutor system prompt.
+
+use regex::Regex;
+use rusqlite::{params, Connection, OptionalExtension};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use tauri::State;
+
+use crate::history::HistoryState;
+
+const MAX_NAME_LENGTH: usize = 64;
+const MAX_BODY_BYTES: usize = 64 * 1024;
+
+/// Prompt template tables, applied by the history database's migrations
+pub(crate) const SCHEMA: &str = r#"
+CREATE TABLE prompt_templates (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL,
+    version INTEGER NOT NULL,
+    description TEXT NOT NULL,
+    body TEXT NOT NULL,
+    defaults TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    UNIQUE (name, version)
+);
+"#;
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PromptVariable {
+    pub name: String,
+    /// Used when rendering without a value for it
+    pub default: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PromptTemplate {
+    pub name: String,
+    pub version: u32,
+    pub description: String,
+    pub body: String,
+    /// The placeholders in `body`, in order of first use
+    pub variables: Vec<PromptVariable>,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct NewPromptTemplate {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub body: String,
+    /// Default values by variable name
+    #[serde(default)]
+    pub defaults: HashMap<String, String>,
+}
+
+fn placeholder() -> &'static Regex {
+    static PLACEHOLDER: OnceLock<Regex> = OnceLock::new();
+    PLACEHOLDER.get_or_init(|| Regex::new(r"\{\{\s*([A-Za-z_][A-Za-z0-9_]*)\s*\}\}").unwrap())
+}
+
+/// Placeholder names in order of first use
+fn variable_names(body: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for captures in placeholder().captures_iter(body) {
+        if !names.iter().any(|n| n == &captures[1]) {
+            names.push(captures[1].to_string());
+        }
+    }
+    names
+}
+
+fn validate_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    let valid_chars = name.chars().all(|c| c.is_alphanumeric() || matches!(c, ' ' | '-' | '_' | '.'));
+    if name.is_empty() || name.chars().count() > MAX_NAME_LENGTH || !valid_chars {
+        return Err(format!(
+            "Prompt names must be 1 to {} letters, digits, spaces, '-', '_' or '.'",
+            MAX_NAME_LENGTH
+        ));
+    }
+    Ok(name.to_string())
+}
+
+fn template_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<PromptTemplate> {
+    let body: String = row.get(3)?;
+    let defaults: String = row.get(4)?;
+    let defaults: HashMap<String, String> = serde_json::from_str(&defaults).unwrap_or_default();
+    let variables = variable_names(&body)
+        .into_iter()
+        .map(|name| PromptVariable { default: defaults.get(&name).cloned(), name })
+        .collect();
+    Ok(PromptTemplate {
+        name: row.get(0)?,
+        version: row.get(1)?,
+        description: row.get(2)?,
+        body,
+        variables,
+        created_at: row.get(5)?,
+    })
+}
+
+const TEMPLATE_COLUMNS: &str = "name, version, description, body, defaults, created_at";
+
+/// The given version of a template, or its latest
+fn load_template(db: &Connection, name: &str, version: Option<u32>) -> rusqlite::Result<Option<PromptTemplate>> {
+    db.query_row(
+        &format!(
+            "SELECT {} FROM prompt_templates WHERE name = ?1 AND (?2 IS NULL OR version = ?2)
+             ORDER BY version DESC LIMIT 1",
+            TEMPLATE_COLUMNS
+        ),
+        params![name, version],
+        template_from_row,
+    )
+    .optional()
+}
+
+fn missing(name: &str, version: Option<u32>) -> String {
+    match version {
+        Some(version) => format!("Prompt '{}' has no version {}", name, version),
+        None => format!("No prompt named '{}'", name),
+    }
+}
+
+/// Fill every placeholder from `vars`, falling back to the template's defaults
+fn render(template: &PromptTemplate, vars: &HashMap<String, String>) -> Result<String, String> {
+    let missing: Vec<&str> = template
+        .variables
+        .iter()
+        .filter(|v| v.default.is_none() && !vars.contains_key(&v.name))
+        .map(|v| v.name.as_str())
+        .collect();
+    if !missing.is_empty() {
+        return Err(format!("Prompt '{}' needs values for: {}", template.name, missing.join(", ")));
+    }
+    let rendered = placeholder().replace_all(&template.body, |captures: &regex::Captures<'_>| {
+        let name = &captures[1];
+        vars.get(name)
+            .or_else(|| template.variables.iter().find(|v| v.name == name).and_then(|v| v.default.as_ref()))
+            .cloned()
+            .unwrap_or_default()
+    });
+    Ok(rendered.into_owned())
+}
+
+/// Save a template as a new version of `name`, creating it when it doesn't exist yet
+#[tauri::command]
+pub async fn save_prompt(state: State<'_, HistoryState>, template: NewPromptTemplate) -> Result<PromptTemplate, String> {
+    let name = validate_name(&template.name)?;
+    if template.body.trim().is_empty() || template.body.len() > MAX_BODY_BYTES {
+        return Err(format!("Prompt text must be 1 byte to {} KB", MAX_BODY_BYTES / 1024));
+    }
+    let names = variable_names(&template.body);
+    let defaults: HashMap<String, String> = template.defaults.into_iter().filter(|(k, _)| names.contains(k)).collect();
+    let defaults = serde_json::to_string(&defaults).map_err(|e| format!("Failed to store defaults: {}", e))?;
+    let saved = state.with_db(|db| {
+        let tx = db.transaction()?;
+        let version: u32 = tx.query_row(
+            "SELECT COALESCE(MAX(version), 0) + 1 FROM prompt_templates WHERE name = ?1",
+            [&name],
+            |row| row.get(0),
+        )?;
+        tx.execute(
+            "INSERT INTO prompt_templates (name, version, description, body, defaults, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![name, version, template.description.trim(), template.body, defaults, chrono::Utc::now().timestamp()],
+        )?;
+        tx.commit()?;
+        load_template(db, &name, Some(version))
+    })?;
+    let saved = saved.ok_or("Saved prompt disappeared")?;
+    tracing::info!("🧾 Saved prompt '{}' version {}", saved.name, saved.version);
+    Ok(saved)
+}
+
+/// A template's latest version, or the given one
+#[tauri::command]
+pub async fn get_prompt(
+    state: State<'_, HistoryState>,
+    name: String,
+    version: Option<u32>,
+) -> Result<PromptTemplate, String> {
+    state.with_db(|db| load_template(db, &name, version))?.ok_or_else(|| missing(&name, version))
+}
+
+/// The latest version of every template, by name
+#[tauri::command]
+pub async fn list_prompts(state: State<'_, HistoryState>) -> Result<Vec<PromptTemplate>, String> {
+    state.with_db(|db| {
+        db.prepare(&format!(
+            "SELECT {} FROM prompt_templates t
+             WHERE version = (SELECT MAX(version) FROM prompt_templates WHERE name = t.name)
+             ORDER BY name",
+            TEMPLATE_COLUMNS
+        ))?
+        .query_map([], template_from_row)?
+        .collect()
+    })
+}
+
+/// Every saved version of a template, newest first
+#[tauri::command]
+pub async fn list_prompt_versions(state: State<'_, HistoryState>, name: String) -> Result<Vec<PromptTemplate>, String> {
+    state.with_db(|db| {
+        db.prepare(&format!("SELECT {} FROM prompt_templates WHERE name = ?1 ORDER BY version DESC", TEMPLATE_COLUMNS))?
+            .query_map([&name], template_from_row)?
+            .collect()
+    })
+}
+
+/// Delete a template with all its versions
+#[tauri::command]
+pub async fn delete_prompt(state: State<'_, HistoryState>, name: String) -> Result<(), String> {
+    let deleted = state.with_db(|db| db.execute("DELETE FROM prompt_templates WHERE name = ?1", [&name]))?;
+    if deleted == 0 {
+        return Err(missing(&name, None));
+    }
+    tracing::info!("🗑️ Deleted prompt '{}'", name);
+    Ok(())
+}
+
+/// The template's text with its `{{variables}}` filled in from `vars` or their defaults
+#[tauri::command]
+pub async fn render_prompt(
+    state: State<'_, HistoryState>,
+    name: String,
+    vars: Option<HashMap<String, String>>,
+    version: Option<u32>,
+) -> Result<String, String> {
+    let template = state.with_db(|db| load_template(db, &name, version))?.ok_or_else(|| missing(&name, version))?;
+    render(&template, &vars.unwrap_or_default())
+}
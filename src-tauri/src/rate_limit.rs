@@ -0,0 +1,59 @@
+//! Per-command rate limits for expensive commands, declared with `rate_limit:` in the command
+//! catalog. A call over the limit is refused before it runs: `invoke` rejects with a
+//! `RateLimited` object instead of the usual error string, so the frontend (or an agent loop)
+//! knows exactly how long to back off.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::command_catalog::RateLimit;
+
+/// Error returned for a call over its command's rate limit
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "kind", rename = "rate_limited")]
+pub struct RateLimited {
+    pub command: String,
+    pub retry_after_ms: u64,
+}
+
+impl fmt::Display for RateLimited {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "'{}' is rate limited; retry in {} ms", self.command, self.retry_after_ms)
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct RateLimiterState {
+    /// Start times of the calls inside each command's current window, oldest first
+    calls: Arc<Mutex<HashMap<String, VecDeque<Instant>>>>,
+}
+
+impl RateLimiterState {
+    /// Record a call to `command`, or refuse it when the catalog limit is used up
+    pub fn check(&self, command: &str) -> Result<(), RateLimited> {
+        let Some(limit) = crate::command_catalog::rate_limit(command) else {
+            return Ok(());
+        };
+        self.check_limit(command, limit)
+    }
+
+    fn check_limit(&self, command: &str, limit: RateLimit) -> Result<(), RateLimited> {
+        let window = Duration::from_secs(limit.per_seconds.into());
+        let now = Instant::now();
+        let mut calls = self.calls.lock().unwrap();
+        let recent = calls.entry(command.to_string()).or_default();
+        while recent.front().is_some_and(|start| now.duration_since(*start) >= window) {
+            recent.pop_front();
+        }
+        if let Some(oldest) = recent.front().filter(|_| recent.len() >= limit.max_calls as usize) {
+            let retry_after = window.saturating_sub(now.duration_since(*oldest));
+            return Err(RateLimited { command: command.to_string(), retry_after_ms: retry_after.as_millis() as u64 });
+        }
+        recent.push_back(now);
+        Ok(())
+    }
+}
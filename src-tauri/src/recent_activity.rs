@@ -0,0 +1,161 @@
+//! Rolling buffer of downscaled monitoring frames, exported as a GIF so the tutor can
+//! replay what happened just before the student asked for help. The pixels are kept in the
+//! capture buffer, which moves older frames to disk past its memory cap.
+
+use base64::{engine::general_purpose, Engine as _};
+use image::codecs::gif::{GifEncoder, Repeat};
+use image::imageops::FilterType;
+use image::{Delay, Frame, RgbaImage};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use tauri::State;
+
+use crate::capture_buffer::{CaptureBufferState, FrameId};
+use crate::image_resize::{self, ResizeBackend};
+
+/// Frames are downscaled to at most this width before buffering
+const FRAME_WIDTH: u32 = 480;
+/// Frames older than this are dropped
+const RETAIN_MS: i64 = 60_000;
+/// Hard cap on buffered frames (~0.5 MB each)
+const MAX_FRAMES: usize = 60;
+/// GIF quantization speed, 1 (best) to 30 (fastest)
+const GIF_SPEED: i32 = 20;
+/// How long the last frame stays up before the animation loops
+const LAST_FRAME_MS: i64 = 1_000;
+
+struct BufferedFrame {
+    /// Unix timestamp in milliseconds
+    timestamp: i64,
+    /// RGBA pixels in the capture buffer
+    pixels: FrameId,
+    width: u32,
+    height: u32,
+}
+
+#[derive(Clone)]
+pub struct RecentActivityState {
+    frames: Arc<Mutex<VecDeque<BufferedFrame>>>,
+    buffer: CaptureBufferState,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RecentActivityGif {
+    pub gif_base64: String,
+    pub frame_count: usize,
+    /// Time span covered by the animation
+    pub duration_ms: u64,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl RecentActivityState {
+    pub fn new(buffer: CaptureBufferState) -> Self {
+        Self { frames: Arc::default(), buffer }
+    }
+
+    /// Buffer a monitoring capture. Decoding and scaling happen off the async runtime.
+    pub fn record(&self, image_base64: String, timestamp: i64, resize: ResizeBackend) {
+        let (frames, buffer) = (self.frames.clone(), self.buffer.clone());
+        tauri::async_runtime::spawn_blocking(move || {
+            let Ok(bytes) = general_purpose::STANDARD.decode(image_base64) else { return };
+            let Ok(image) = image::load_from_memory(&bytes) else { return };
+            let image = image_resize::fit_width(resize, image.to_rgba8(), FRAME_WIDTH, FilterType::Triangle);
+
+            let (width, height) = image.dimensions();
+            // Locked before buffering, so frames enter the buffer in the order they are listed
+            let mut frames = frames.lock().unwrap();
+            let pixels = buffer.put(image.into_raw());
+            frames.push_back(BufferedFrame { timestamp, pixels, width, height });
+            while frames.len() > MAX_FRAMES || frames.front().is_some_and(|f| timestamp - f.timestamp > RETAIN_MS) {
+                if let Some(frame) = frames.pop_front() {
+                    buffer.remove(frame.pixels);
+                }
+            }
+        });
+    }
+}
+
+fn encode_gif(frames: Vec<(i64, RgbaImage)>, now: i64) -> Result<RecentActivityGif, String> {
+    let (width, height) = frames[0].1.dimensions();
+    let span = (now - frames[0].0).max(0) as u64;
+    let frame_count = frames.len();
+
+    let mut gif = Vec::new();
+    {
+        let mut encoder = GifEncoder::new_with_speed(&mut gif, GIF_SPEED);
+        encoder
+            .set_repeat(Repeat::Infinite)
+            .map_err(|e| format!("Failed to encode GIF: {}", e))?;
+        // Each frame stays up until the next capture, so the replay runs in real time
+        let next_times: Vec<i64> = frames.iter().skip(1).map(|(t, _)| *t).collect();
+        for (i, (timestamp, image)) in frames.into_iter().enumerate() {
+            let shown_ms = next_times.get(i).map(|next| next - timestamp).unwrap_or(LAST_FRAME_MS).max(20);
+            // The screen resolution may have changed mid-buffer
+            let image = if image.dimensions() == (width, height) {
+                image
+            } else {
+                image::imageops::resize(&image, width, height, FilterType::Triangle)
+            };
+            let delay = Delay::from_numer_denom_ms(shown_ms as u32, 1);
+            encoder
+                .encode_frame(Frame::from_parts(image, 0, 0, delay))
+                .map_err(|e| format!("Failed to encode GIF frame: {}", e))?;
+        }
+    }
+
+    Ok(RecentActivityGif {
+        gif_base64: general_purpose::STANDARD.encode(&gif),
+        frame_count,
+        duration_ms: span,
+        width,
+        height,
+    })
+}
+
+/// Animate the monitoring captures from the last `seconds` (up to 60) as a GIF
+#[tauri::command]
+pub async fn export_recent_activity_gif(
+    state: State<'_, RecentActivityState>,
+    seconds: u32,
+) -> Result<RecentActivityGif, String> {
+    if seconds == 0 {
+        return Err("seconds must be at least 1".to_string());
+    }
+    let now = chrono::Utc::now().timestamp_millis();
+    let cutoff = now - (seconds as i64 * 1000).min(RETAIN_MS);
+    let frames: Vec<(i64, FrameId, u32, u32)> = {
+        let buffered = state.frames.lock().unwrap();
+        // Only changed frames are buffered, so the one before the cutoff is still on screen
+        // at the start of the window
+        let first = buffered.iter().rposition(|f| f.timestamp <= cutoff).unwrap_or(0);
+        buffered
+            .iter()
+            .skip(first)
+            .map(|f| (f.timestamp.max(cutoff), f.pixels, f.width, f.height))
+            .collect()
+    };
+    if frames.is_empty() {
+        return Err("No recent activity recorded. Is monitoring running?".to_string());
+    }
+
+    let buffer = state.buffer.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        // Spilled frames are read back from disk
+        let frames = frames
+            .into_iter()
+            .map(|(timestamp, pixels, width, height)| {
+                let image = RgbaImage::from_raw(width, height, buffer.get(pixels)?)
+                    .ok_or_else(|| "A buffered frame has the wrong size".to_string())?;
+                Ok((timestamp, image))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        encode_gif(frames, now)
+    })
+        .await
+        .map_err(|e| format!("Failed to encode GIF: {}", e))??;
+    tracing::info!("🎞️ Exported {} frames of recent activity ({} ms)", result.frame_count, result.duration_ms);
+    Ok(result)
+}
@@ -0,0 +1,382 @@
+//! Remote view: lets a human tutor watch the frames the monitors capture, from a browser.
+//! Starting a session opens a small HTTP + WebSocket server and shows a one-time 6-digit code;
+//! the tutor opens `http://<ip>:<port>/`, enters the code, and gets the frames as a WebSocket
+//! MJPEG stream (one binary JPEG message per changed frame).
+//!
+//! The stream is plain HTTP, so the server listens on localhost unless the session is opened
+//! to the local network explicitly. Sessions need the student's screen consent, and frames
+//! stop when it ends. One viewer per session: the code is spent by the first successful
+//! connection, and a few wrong codes void it. Nothing is streamed while capture is paused.
+
+use base64::{engine::general_purpose, Engine as _};
+use futures_util::{SinkExt, StreamExt};
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::DynamicImage;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Manager, State};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream, UdpSocket};
+use tokio::sync::watch;
+use tokio::task::JoinSet;
+use tokio_tungstenite::tungstenite::handshake::server::{ErrorResponse, Request, Response};
+use tokio_tungstenite::tungstenite::http::StatusCode;
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::capture_buffer::{Buffered, CaptureBufferState};
+use crate::consent::{ConsentScope, ConsentState};
+use crate::image_resize::{self, ResizeBackend};
+
+const DEFAULT_PORT: u16 = 17346;
+const MAX_FAILED_CODES: u32 = 5;
+/// Frames are scaled down to at most this width before JPEG encoding
+const MAX_FRAME_WIDTH: u32 = 1280;
+const JPEG_QUALITY: u8 = 70;
+const HEADER_TIMEOUT: Duration = Duration::from_secs(5);
+
+const VIEWER_PAGE: &str = r#"<!doctype html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>AI Teacher remote view</title>
+<style>
+  body { margin: 0; background: #111; color: #ddd; font-family: sans-serif; }
+  #status { padding: 8px 12px; font-size: 14px; }
+  #view { display: block; max-width: 100vw; max-height: calc(100vh - 40px); margin: 0 auto; }
+</style>
+</head>
+<body>
+<div id="status">Connecting…</div>
+<img id="view" alt="">
+<script>
+  const status = document.getElementById('status');
+  const view = document.getElementById('view');
+  const code = new URLSearchParams(location.search).get('code') || prompt('Code shown in AI Teacher');
+  const socket = new WebSocket(`ws://${location.host}/?code=${encodeURIComponent(code || '')}`);
+  socket.binaryType = 'blob';
+  socket.onmessage = (event) => {
+    if (typeof event.data === 'string') {
+      status.textContent = event.data;
+      return;
+    }
+    const url = URL.createObjectURL(event.data);
+    view.onload = () => URL.revokeObjectURL(url);
+    view.src = url;
+  };
+  socket.onclose = () => { status.textContent = 'Session ended'; };
+</script>
+</body>
+</html>
+"#;
+
+/// Payload of `remote-view-changed` and result of the remote view commands
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct RemoteViewStatus {
+    pub active: bool,
+    /// One-time code for the tutor; gone once a viewer has connected
+    pub code: Option<String>,
+    /// Addresses the tutor's browser can open
+    pub urls: Vec<String>,
+    pub viewer_connected: bool,
+}
+
+#[derive(Default)]
+struct Session {
+    code: Option<String>,
+    urls: Vec<String>,
+    failed_codes: u32,
+    viewer_connected: bool,
+}
+
+#[derive(Clone)]
+pub struct RemoteViewState {
+    session: Arc<Mutex<Option<Session>>>,
+    server: Arc<Mutex<Option<JoinHandle<()>>>>,
+    /// Latest JPEG frame, kept in the capture buffer
+    frames: watch::Sender<Option<Arc<Buffered>>>,
+    captures: CaptureBufferState,
+}
+
+impl RemoteViewState {
+    pub fn new(captures: CaptureBufferState) -> Self {
+        Self {
+            session: Arc::default(),
+            server: Arc::default(),
+            frames: watch::channel(None).0,
+            captures,
+        }
+    }
+
+    fn status(&self) -> RemoteViewStatus {
+        match self.session.lock().unwrap().as_ref() {
+            Some(session) => RemoteViewStatus {
+                active: true,
+                code: session.code.clone(),
+                urls: session.urls.clone(),
+                viewer_connected: session.viewer_connected,
+            },
+            None => RemoteViewStatus::default(),
+        }
+    }
+
+    /// Stream a captured frame (base64 PNG) to the viewer, if one is connected
+    pub async fn publish(&self, image_base64: &str, resize: ResizeBackend) {
+        let watching = self.session.lock().unwrap().as_ref().is_some_and(|s| s.viewer_connected);
+        if !watching {
+            return;
+        }
+        let image_base64 = image_base64.to_string();
+        match tauri::async_runtime::spawn_blocking(move || to_jpeg(&image_base64, resize)).await {
+            Ok(Ok(jpeg)) => {
+                let _ = self.frames.send(Some(Arc::new(self.captures.hold(jpeg))));
+            }
+            Ok(Err(e)) => tracing::warn!("⚠️ Remote view frame dropped: {}", e),
+            Err(e) => tracing::warn!("⚠️ Remote view frame dropped: {}", e),
+        }
+    }
+
+    /// Spend the one-time code, or count a wrong one (voiding the code after too many)
+    fn redeem(&self, presented: Option<&str>) -> Result<(), String> {
+        let mut guard = self.session.lock().unwrap();
+        let session = guard.as_mut().ok_or("No remote view session")?;
+        if session.code.is_some() && session.code.as_deref() == presented {
+            session.code = None;
+            session.viewer_connected = true;
+            return Ok(());
+        }
+        session.failed_codes += 1;
+        if session.failed_codes >= MAX_FAILED_CODES {
+            session.code = None;
+        }
+        Err("Invalid or already used code".to_string())
+    }
+}
+
+fn to_jpeg(image_base64: &str, resize: ResizeBackend) -> Result<Vec<u8>, String> {
+    let bytes = general_purpose::STANDARD
+        .decode(image_base64)
+        .map_err(|e| format!("Failed to decode frame: {}", e))?;
+    let image = image::load_from_memory(&bytes).map_err(|e| format!("Failed to decode frame: {}", e))?;
+    let image = image_resize::fit_width(resize, image.to_rgba8(), MAX_FRAME_WIDTH, FilterType::Triangle);
+    let mut jpeg = Vec::new();
+    JpegEncoder::new_with_quality(&mut jpeg, JPEG_QUALITY)
+        .encode_image(&DynamicImage::ImageRgba8(image).to_rgb8())
+        .map_err(|e| format!("Failed to encode frame: {}", e))?;
+    Ok(jpeg)
+}
+
+fn new_code() -> String {
+    let random = u32::from_le_bytes(uuid::Uuid::new_v4().as_bytes()[..4].try_into().unwrap());
+    format!("{:06}", random % 1_000_000)
+}
+
+/// This machine's address on the local network: the source address the OS would use to
+/// reach the internet (nothing is sent)
+async fn local_ip() -> Option<std::net::IpAddr> {
+    let socket = UdpSocket::bind(("0.0.0.0", 0)).await.ok()?;
+    socket.connect(("8.8.8.8", 80)).await.ok()?;
+    socket.local_addr().ok().map(|addr| addr.ip())
+}
+
+fn code_from_query(query: &str) -> Option<&str> {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == "code")
+        .map(|(_, value)| value)
+}
+
+/// Whether the request waiting on `stream` is a WebSocket upgrade (rather than a page load)
+async fn is_websocket_upgrade(stream: &TcpStream) -> bool {
+    let mut buffer = [0u8; 4096];
+    let peeked = tokio::time::timeout(HEADER_TIMEOUT, async {
+        loop {
+            let n = stream.peek(&mut buffer).await.unwrap_or(0);
+            if n == 0 || n == buffer.len() || buffer[..n].windows(4).any(|w| w == b"\r\n\r\n") {
+                return n;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    })
+    .await
+    .unwrap_or(0);
+    String::from_utf8_lossy(&buffer[..peeked]).to_ascii_lowercase().contains("upgrade: websocket")
+}
+
+async fn serve_page(mut stream: TcpStream) {
+    // Read the request first; closing with unread data can reset the connection mid-response
+    let mut request = [0u8; 4096];
+    let _ = stream.read(&mut request).await;
+    let response = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n{}",
+        VIEWER_PAGE.len(),
+        VIEWER_PAGE
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+    let _ = stream.shutdown().await;
+}
+
+async fn handle_connection(app: AppHandle, stream: TcpStream) {
+    if !is_websocket_upgrade(&stream).await {
+        serve_page(stream).await;
+        return;
+    }
+
+    let state = app.state::<RemoteViewState>().inner().clone();
+    let consent = app.state::<ConsentState>().inner().clone();
+    // The error type is fixed by tungstenite's handshake callback
+    #[allow(clippy::result_large_err)]
+    let authorize = |request: &Request, response: Response| -> Result<Response, ErrorResponse> {
+        let redeemed = consent
+            .require(ConsentScope::Screen)
+            .and_then(|_| state.redeem(request.uri().query().and_then(code_from_query)));
+        match redeemed {
+            Ok(()) => Ok(response),
+            Err(e) => {
+                let mut error = ErrorResponse::new(Some(e));
+                *error.status_mut() = StatusCode::UNAUTHORIZED;
+                Err(error)
+            }
+        }
+    };
+    let socket = match tokio_tungstenite::accept_hdr_async(stream, authorize).await {
+        Ok(socket) => socket,
+        Err(e) => {
+            tracing::info!("🚫 Rejected remote viewer: {}", e);
+            emit_status(&app);
+            return;
+        }
+    };
+    tracing::info!("👀 Remote viewer connected");
+    emit_status(&app);
+
+    let (mut sink, mut incoming) = socket.split();
+    let mut frames = state.frames.subscribe();
+    let greeting = if frames.borrow().is_some() { "Connected" } else { "Connected, waiting for the next captured frame…" };
+    if sink.send(Message::Text(greeting.to_string())).await.is_ok() {
+        frames.mark_changed();
+        loop {
+            tokio::select! {
+                changed = frames.changed() => {
+                    let frame = match changed {
+                        Ok(()) => frames.borrow_and_update().clone(),
+                        Err(_) => break,
+                    };
+                    let Some(frame) = frame else { continue };
+                    if let Err(e) = consent.require(ConsentScope::Screen) {
+                        let _ = sink.send(Message::Text(e)).await;
+                        break;
+                    }
+                    // The frame may have been spilled to disk
+                    let Ok(Ok(jpeg)) = tauri::async_runtime::spawn_blocking(move || frame.get()).await else {
+                        continue;
+                    };
+                    if sink.send(Message::Binary(jpeg)).await.is_err() {
+                        break;
+                    }
+                }
+                message = incoming.next() => match message {
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    Some(Ok(_)) => {}
+                },
+            }
+        }
+    }
+
+    if let Some(session) = state.session.lock().unwrap().as_mut() {
+        session.viewer_connected = false;
+    }
+    tracing::info!("👋 Remote viewer disconnected");
+    emit_status(&app);
+}
+
+/// Accept connections until aborted; stopping the server drops every open connection
+async fn serve(app: AppHandle, listener: TcpListener) {
+    let mut connections = JoinSet::new();
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => {
+                    connections.spawn(handle_connection(app.clone(), stream));
+                }
+                Err(e) => tracing::warn!("⚠️ Accept failed: {}", e),
+            },
+            Some(_) = connections.join_next() => {}
+        }
+    }
+}
+
+fn stop_session(state: &RemoteViewState) {
+    if let Some(handle) = state.server.lock().unwrap().take() {
+        handle.abort();
+        tracing::info!("⏹️ Remote view stopped");
+    }
+    *state.session.lock().unwrap() = None;
+    let _ = state.frames.send(None);
+}
+
+/// End the session, if any, when consent ends
+pub fn stop(app: &AppHandle) {
+    let state = app.state::<RemoteViewState>();
+    if state.session.lock().unwrap().is_some() {
+        stop_session(&state);
+        emit_status(app);
+    }
+}
+
+fn emit_status(app: &AppHandle) -> RemoteViewStatus {
+    let status = app.state::<RemoteViewState>().status();
+    let _ = crate::event_bus::emit(app, crate::events::REMOTE_VIEW_CHANGED, status.clone());
+    status
+}
+
+#[tauri::command]
+pub async fn get_remote_view_status(state: State<'_, RemoteViewState>) -> Result<RemoteViewStatus, String> {
+    Ok(state.status())
+}
+
+/// Start a remote view session with a fresh one-time code, replacing any running one. It
+/// listens on localhost only unless `local_network` is set.
+#[tauri::command]
+pub async fn start_remote_view(
+    app: AppHandle,
+    state: State<'_, RemoteViewState>,
+    port: Option<u16>,
+    local_network: Option<bool>,
+) -> Result<RemoteViewStatus, String> {
+    app.state::<ConsentState>().require(ConsentScope::Screen)?;
+    let port = port.unwrap_or(DEFAULT_PORT);
+    if port < 1024 {
+        return Err("Port must be 1024 or higher".to_string());
+    }
+    let local_network = local_network.unwrap_or(false);
+    stop_session(&state);
+
+    // Frames travel unencrypted, so other machines only get in when asked for; the code
+    // still guards access either way
+    let host = if local_network { "0.0.0.0" } else { "127.0.0.1" };
+    let listener = TcpListener::bind((host, port))
+        .await
+        .map_err(|e| format!("Failed to listen on port {}: {}", port, e))?;
+    let mut urls = vec![format!("http://127.0.0.1:{}/", port)];
+    if local_network {
+        if let Some(ip) = local_ip().await.filter(|ip| !ip.is_loopback()) {
+            urls.insert(0, format!("http://{}:{}/", ip, port));
+        }
+    }
+    *state.session.lock().unwrap() = Some(Session { code: Some(new_code()), urls, ..Default::default() });
+    *state.server.lock().unwrap() = Some(tauri::async_runtime::spawn(serve(app.clone(), listener)));
+    tracing::info!("📡 Remote view listening on {}:{}", host, port);
+    Ok(emit_status(&app))
+}
+
+/// End the remote view session, disconnecting the viewer
+#[tauri::command]
+pub async fn stop_remote_view(app: AppHandle, state: State<'_, RemoteViewState>) -> Result<RemoteViewStatus, String> {
+    stop_session(&state);
+    Ok(emit_status(&app))
+}
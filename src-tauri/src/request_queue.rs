@@ -0,0 +1,168 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager, State};
+
+use crate::connectivity::ConnectivityState;
+use crate::persistence;
+
+const QUEUE_FILE: &str = "pending_requests.json";
+
+/// An AI request that couldn't be sent while offline
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PendingRequest {
+    pub id: String,
+    /// What the payload is for, e.g. "chat" or "screen-analysis"
+    pub kind: String,
+    pub payload: serde_json::Value,
+    pub created_at: i64,
+    /// Number of times the request was handed to the frontend for sending
+    pub attempts: u32,
+    pub last_dispatched_at: Option<i64>,
+    /// Handed to the frontend and not yet completed or failed; skipped by `flush`
+    #[serde(default)]
+    pub in_flight: bool,
+}
+
+#[derive(Clone, Default)]
+pub struct RequestQueueState {
+    pub requests: Arc<Mutex<Vec<PendingRequest>>>,
+}
+
+fn persist(app: &AppHandle, requests: &[PendingRequest]) -> Result<(), String> {
+    let path = persistence::data_file(app, QUEUE_FILE)?;
+    persistence::save_json(&path, &requests)
+}
+
+/// Load the queue persisted by a previous run
+pub fn init(app: &AppHandle) {
+    let loaded: Vec<PendingRequest> = match persistence::data_file(app, QUEUE_FILE) {
+        Ok(path) => persistence::load_json(&path),
+        Err(e) => {
+            tracing::warn!("⚠️ {}", e);
+            Vec::new()
+        }
+    };
+    // Sends that were in flight when the app closed never finished: retry them
+    let loaded: Vec<PendingRequest> = loaded
+        .into_iter()
+        .map(|request| PendingRequest { in_flight: false, ..request })
+        .collect();
+    if !loaded.is_empty() {
+        tracing::info!("📥 Restored {} pending request(s)", loaded.len());
+    }
+    *app.state::<RequestQueueState>().requests.lock().unwrap() = loaded;
+}
+
+/// Hand each queued request that isn't already in flight to the frontend via
+/// `pending-request-ready`, once. The frontend confirms with `complete_pending_request`,
+/// or puts the request back with `fail_pending_request` to have it retried on a later flush.
+pub fn flush(app: &AppHandle) {
+    let state = app.state::<RequestQueueState>();
+    let mut requests = state.requests.lock().unwrap();
+    let waiting = requests.iter().filter(|r| !r.in_flight).count();
+    if waiting == 0 {
+        return;
+    }
+
+    tracing::info!("📤 Flushing {} pending request(s)", waiting);
+    let now = chrono::Utc::now().timestamp();
+    for request in requests.iter_mut().filter(|r| !r.in_flight) {
+        request.attempts += 1;
+        request.last_dispatched_at = Some(now);
+        request.in_flight = true;
+        let _ = crate::event_bus::emit(app, crate::events::PENDING_REQUEST_READY, request.clone());
+    }
+
+    if let Err(e) = persist(app, &requests) {
+        tracing::warn!("⚠️ Failed to persist queue: {}", e);
+    }
+}
+
+#[tauri::command]
+pub async fn enqueue_ai_request(
+    app: AppHandle,
+    state: State<'_, RequestQueueState>,
+    connectivity: State<'_, ConnectivityState>,
+    kind: String,
+    payload: serde_json::Value,
+) -> Result<PendingRequest, String> {
+    let request = PendingRequest {
+        id: uuid::Uuid::new_v4().to_string(),
+        kind,
+        payload,
+        created_at: chrono::Utc::now().timestamp(),
+        attempts: 0,
+        last_dispatched_at: None,
+        in_flight: false,
+    };
+
+    {
+        let mut requests = state.requests.lock().unwrap();
+        requests.push(request.clone());
+        persist(&app, &requests)?;
+    }
+
+    // The endpoint may already be reachable again (e.g. a transient failure)
+    if connectivity.is_online() {
+        flush(&app);
+    }
+
+    Ok(request)
+}
+
+#[tauri::command]
+pub async fn get_pending_requests(
+    state: State<'_, RequestQueueState>,
+) -> Result<Vec<PendingRequest>, String> {
+    Ok(state.requests.lock().unwrap().clone())
+}
+
+/// Remove a request that was sent successfully
+#[tauri::command]
+pub async fn complete_pending_request(
+    app: AppHandle,
+    state: State<'_, RequestQueueState>,
+    id: String,
+) -> Result<(), String> {
+    let mut requests = state.requests.lock().unwrap();
+    requests.retain(|r| r.id != id);
+    persist(&app, &requests)
+}
+
+/// Put back a request whose send failed, so the next flush retries it
+#[tauri::command]
+pub async fn fail_pending_request(
+    app: AppHandle,
+    state: State<'_, RequestQueueState>,
+    id: String,
+) -> Result<(), String> {
+    let mut requests = state.requests.lock().unwrap();
+    let request = requests
+        .iter_mut()
+        .find(|r| r.id == id)
+        .ok_or_else(|| format!("No pending request with id {}", id))?;
+    request.in_flight = false;
+    persist(&app, &requests)
+}
+
+/// Drop a queued request, returning whether it was in the queue
+pub fn remove(app: &AppHandle, state: &RequestQueueState, id: &str) -> Result<bool, String> {
+    let mut requests = state.requests.lock().unwrap();
+    let before = requests.len();
+    requests.retain(|r| r.id != id);
+    let removed = requests.len() != before;
+    if removed {
+        persist(app, &requests)?;
+    }
+    Ok(removed)
+}
+
+#[tauri::command]
+pub async fn cancel_pending_request(
+    app: AppHandle,
+    state: State<'_, RequestQueueState>,
+    id: String,
+) -> Result<bool, String> {
+    remove(&app, &state, &id)
+}
@@ -0,0 +1,265 @@
+//! Retention for sensitive data: saved captures are deleted after a number of days and the
+//! text stored in history (OCR and extracted document text) is blanked after another, checked
+//! by a background task. `purge_all_captures` deletes every capture at once, but only with a
+//! short-lived token from `prepare_capture_purge`, so a single stray call can't wipe them.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Manager, State};
+
+use crate::history::HistoryState;
+use crate::persistence;
+
+const SETTINGS_FILE: &str = "retention_settings.json";
+const CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+/// Delay before the first check, so it doesn't compete with startup
+const FIRST_CHECK_DELAY: Duration = Duration::from_secs(60);
+const PURGE_TOKEN_TTL_SECS: i64 = 60;
+const DAY_SECS: u64 = 24 * 60 * 60;
+
+/// Keeps history search in step when retention blanks stored text; applied by the history
+/// database's migrations
+pub(crate) const SCHEMA: &str = r#"
+CREATE TRIGGER history_text_update AFTER UPDATE OF text ON history_text BEGIN
+    INSERT INTO history_fts(history_fts, rowid, text) VALUES ('delete', old.id, old.text);
+    INSERT INTO history_fts(rowid, text) VALUES (new.id, new.text);
+END;
+"#;
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RetentionSettings {
+    /// Delete saved captures (and recordings) older than this many days; None keeps them
+    pub capture_days: Option<u32>,
+    /// Blank the stored text of history items older than this many days; None keeps it
+    pub ocr_text_days: Option<u32>,
+}
+
+impl Default for RetentionSettings {
+    fn default() -> Self {
+        Self { capture_days: Some(30), ocr_text_days: None }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct RetentionReport {
+    pub captures_deleted: u32,
+    pub bytes_freed: u64,
+    /// Text parts blanked in history
+    pub texts_anonymized: u32,
+    pub ran_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PurgeConfirmation {
+    /// Pass to `purge_all_captures` to confirm
+    pub token: String,
+    pub expires_at: i64,
+    /// What would be deleted
+    pub captures: u32,
+    pub bytes: u64,
+}
+
+#[derive(Clone, Default)]
+pub struct RetentionState {
+    settings: Arc<Mutex<RetentionSettings>>,
+    last_report: Arc<Mutex<Option<RetentionReport>>>,
+    /// Outstanding purge token and its expiry
+    purge_token: Arc<Mutex<Option<(String, i64)>>>,
+}
+
+/// Saved captures of the active profile, with their sizes and modification times
+fn capture_files() -> Vec<(PathBuf, u64, SystemTime)> {
+    let root = crate::commands::captures_dir();
+    // The default profile's directory also holds the other profiles' captures
+    let skip_profiles = crate::profiles::active_subdir().is_none();
+    let mut files = Vec::new();
+    let mut pending = vec![root.clone()];
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else { continue };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if skip_profiles && dir == root && entry.file_name() == "profiles" {
+                continue;
+            }
+            let Ok(metadata) = entry.metadata() else { continue };
+            if metadata.is_dir() {
+                pending.push(path);
+            } else if metadata.is_file() {
+                files.push((path, metadata.len(), metadata.modified().unwrap_or(SystemTime::now())));
+            }
+        }
+    }
+    files
+}
+
+/// Delete the given files, returning how many went and the bytes freed
+fn delete_files(files: &[(PathBuf, u64, SystemTime)]) -> (u32, u64) {
+    let (mut deleted, mut freed) = (0, 0);
+    for (path, size, _) in files {
+        match fs::remove_file(path) {
+            Ok(()) => {
+                deleted += 1;
+                freed += size;
+            }
+            Err(e) => tracing::warn!("⚠️ Failed to delete capture {}: {}", path.display(), e),
+        }
+    }
+    (deleted, freed)
+}
+
+fn older_than(files: Vec<(PathBuf, u64, SystemTime)>, days: u32) -> Vec<(PathBuf, u64, SystemTime)> {
+    let cutoff = SystemTime::now() - Duration::from_secs(days as u64 * DAY_SECS);
+    files.into_iter().filter(|(_, _, modified)| *modified < cutoff).collect()
+}
+
+fn is_capture_root(path: &Path) -> bool {
+    path == crate::commands::captures_dir()
+}
+
+/// Remove directories left empty below the captures directory (e.g. old recording folders)
+fn remove_empty_dirs(files: &[(PathBuf, u64, SystemTime)]) {
+    for (path, _, _) in files {
+        let mut dir = path.parent();
+        while let Some(current) = dir.filter(|d| !is_capture_root(d)) {
+            if fs::remove_dir(current).is_err() {
+                break;
+            }
+            dir = current.parent();
+        }
+    }
+}
+
+/// Apply the retention settings once
+fn enforce(app: &AppHandle, settings: &RetentionSettings) -> RetentionReport {
+    let mut report = RetentionReport { ran_at: chrono::Utc::now().timestamp(), ..Default::default() };
+    if let Some(days) = settings.capture_days {
+        let expired = older_than(capture_files(), days);
+        (report.captures_deleted, report.bytes_freed) = delete_files(&expired);
+        remove_empty_dirs(&expired);
+    }
+    if let Some(days) = settings.ocr_text_days {
+        let cutoff = report.ran_at - days as i64 * DAY_SECS as i64;
+        let anonymized = app.state::<HistoryState>().with_db(|db| {
+            db.execute(
+                "UPDATE history_text SET text = '' WHERE text != ''
+                 AND item_id IN (SELECT id FROM history_items WHERE created_at < ?1)",
+                [cutoff],
+            )
+        });
+        match anonymized {
+            Ok(count) => report.texts_anonymized = count as u32,
+            Err(e) => tracing::warn!("⚠️ Failed to anonymize old history text: {}", e),
+        }
+    }
+    if report.captures_deleted > 0 || report.texts_anonymized > 0 {
+        tracing::info!(
+            "🧹 Retention: deleted {} captures ({} bytes), anonymized {} text parts",
+            report.captures_deleted,
+            report.bytes_freed,
+            report.texts_anonymized
+        );
+    }
+    report
+}
+
+/// Load the saved settings
+pub fn init(app: &AppHandle) {
+    let settings: RetentionSettings = persistence::data_file(app, SETTINGS_FILE)
+        .map(|path| persistence::load_json(&path))
+        .unwrap_or_default();
+    *app.state::<RetentionState>().settings.lock().unwrap() = settings;
+}
+
+/// Apply the retention settings shortly after startup and then every hour
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(FIRST_CHECK_DELAY).await;
+        loop {
+            let state = app.state::<RetentionState>().inner().clone();
+            let settings = state.settings.lock().unwrap().clone();
+            let task_app = app.clone();
+            match tauri::async_runtime::spawn_blocking(move || enforce(&task_app, &settings)).await {
+                Ok(report) => *state.last_report.lock().unwrap() = Some(report),
+                Err(e) => tracing::warn!("⚠️ Retention check failed: {}", e),
+            }
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn get_retention_settings(state: State<'_, RetentionState>) -> Result<RetentionSettings, String> {
+    Ok(state.settings.lock().unwrap().clone())
+}
+
+/// Save retention settings and apply them right away
+#[tauri::command]
+pub async fn set_retention_settings(
+    app: AppHandle,
+    state: State<'_, RetentionState>,
+    settings: RetentionSettings,
+) -> Result<RetentionReport, String> {
+    if settings.capture_days == Some(0) || settings.ocr_text_days == Some(0) {
+        return Err("Retention periods must be at least one day".to_string());
+    }
+    let path = persistence::data_file(&app, SETTINGS_FILE)?;
+    persistence::save_json(&path, &settings)?;
+    *state.settings.lock().unwrap() = settings.clone();
+
+    let task_app = app.clone();
+    let report = tauri::async_runtime::spawn_blocking(move || enforce(&task_app, &settings))
+        .await
+        .map_err(|e| format!("Failed to apply retention settings: {}", e))?;
+    *state.last_report.lock().unwrap() = Some(report.clone());
+    Ok(report)
+}
+
+/// What the last retention run removed, if it has run since startup
+#[tauri::command]
+pub async fn get_retention_report(state: State<'_, RetentionState>) -> Result<Option<RetentionReport>, String> {
+    Ok(state.last_report.lock().unwrap().clone())
+}
+
+/// Count what `purge_all_captures` would delete and issue the token that confirms it
+#[tauri::command]
+pub async fn prepare_capture_purge(state: State<'_, RetentionState>) -> Result<PurgeConfirmation, String> {
+    let files = tauri::async_runtime::spawn_blocking(capture_files)
+        .await
+        .map_err(|e| format!("Failed to list captures: {}", e))?;
+    let token = uuid::Uuid::new_v4().simple().to_string();
+    let expires_at = chrono::Utc::now().timestamp() + PURGE_TOKEN_TTL_SECS;
+    *state.purge_token.lock().unwrap() = Some((token.clone(), expires_at));
+    Ok(PurgeConfirmation {
+        token,
+        expires_at,
+        captures: files.len() as u32,
+        bytes: files.iter().map(|(_, size, _)| size).sum(),
+    })
+}
+
+/// Delete every saved capture and recording of the active profile. `token` must come from
+/// `prepare_capture_purge` within the last minute and works once.
+#[tauri::command]
+pub async fn purge_all_captures(state: State<'_, RetentionState>, token: String) -> Result<RetentionReport, String> {
+    let issued = state.purge_token.lock().unwrap().take();
+    match issued {
+        Some((expected, expires_at)) if expected == token && chrono::Utc::now().timestamp() <= expires_at => {}
+        Some((expected, _)) if expected == token => return Err("The purge confirmation expired; request a new one".to_string()),
+        _ => return Err("Invalid purge confirmation token".to_string()),
+    }
+
+    let report = tauri::async_runtime::spawn_blocking(|| {
+        let files = capture_files();
+        let (captures_deleted, bytes_freed) = delete_files(&files);
+        remove_empty_dirs(&files);
+        RetentionReport { captures_deleted, bytes_freed, texts_anonymized: 0, ran_at: chrono::Utc::now().timestamp() }
+    })
+    .await
+    .map_err(|e| format!("Failed to purge captures: {}", e))?;
+    tracing::info!("🧹 Purged {} captures ({} bytes)", report.captures_deleted, report.bytes_freed);
+    Ok(report)
+}
@@ -0,0 +1,311 @@
+//! Student and teacher roles. Once a teacher PIN is set the app runs in student mode:
+//! commands listed in the profile's role policy are refused at the invoke layer, and
+//! `execute_command`, jobs and the tutor terminal are limited to the student allowlist.
+//! Entering the PIN unlocks teacher mode for a while. Without a PIN nothing is restricted,
+//! as before roles existed.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, State};
+
+use crate::persistence;
+
+/// Per profile
+const POLICY_FILE: &str = "role_policy.json";
+/// Shared by all profiles
+const PIN_FILE: &str = "teacher_pin.json";
+/// Wrong PINs and the lockout, kept across restarts so restarting doesn't reset them
+const ATTEMPTS_FILE: &str = "teacher_pin_attempts.json";
+const TEACHER_SESSION: Duration = Duration::from_secs(30 * 60);
+const MAX_FAILED_ATTEMPTS: u32 = 5;
+const LOCKOUT: Duration = Duration::from_secs(60);
+/// Commands that stay teacher-only whatever the policy says
+const ALWAYS_TEACHER_ONLY: &[&str] = &[
+    "set_role_policy",
+    "set_sandbox_settings",
+    "set_ai_providers",
+    "set_provider_api_key",
+    "set_ai_budget",
+    "download_model",
+    "delete_model",
+    "import_dictionary",
+    "get_answer_key",
+    "save_answer_key",
+    "delete_answer_key",
+    "import_all_data",
+    "start_remote_view",
+];
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    Student,
+    Teacher,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RolePolicy {
+    /// Programs a student may run through `execute_command`, jobs and the terminal; each must
+    /// also be on the global allowlist
+    pub student_allowed_commands: Vec<String>,
+    /// Backend commands refused in student mode
+    pub teacher_only_commands: Vec<String>,
+}
+
+impl Default for RolePolicy {
+    fn default() -> Self {
+        Self {
+            student_allowed_commands: ["git", "npm"].map(String::from).to_vec(),
+            teacher_only_commands: [
+                "add_project_root",
+                "remove_project_root",
+                "set_distraction_rules",
+                "set_activity_tracking",
+                "set_capture_backend",
+                "set_sync_settings",
+                "set_sync_token",
+                "set_integration_server",
+                "regenerate_integration_token",
+                "install_ocr_language",
+                "install_update",
+                "set_update_settings",
+                "set_log_level",
+                "create_profile",
+            ]
+            .map(String::from)
+            .to_vec(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct StoredPin {
+    salt: String,
+    hash: String,
+}
+
+/// Payload of `role-changed` and result of the role commands
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RoleStatus {
+    pub role: Role,
+    pub pin_set: bool,
+    /// Seconds until teacher mode locks again
+    pub teacher_seconds_left: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Attempts {
+    failed: u32,
+    /// Unix time the lockout ends
+    locked_until: Option<i64>,
+}
+
+#[derive(Clone, Default)]
+pub struct RolesState {
+    pin: Arc<Mutex<Option<StoredPin>>>,
+    policy: Arc<Mutex<RolePolicy>>,
+    unlocked_until: Arc<Mutex<Option<Instant>>>,
+    attempts: Arc<Mutex<Attempts>>,
+}
+
+fn hash_pin(salt: &str, pin: &str) -> String {
+    hex::encode(Sha256::digest(format!("{}:{}", salt, pin).as_bytes()))
+}
+
+fn validate_pin(pin: &str) -> Result<(), String> {
+    if (4..=8).contains(&pin.len()) && pin.chars().all(|c| c.is_ascii_digit()) {
+        Ok(())
+    } else {
+        Err("The PIN must be 4 to 8 digits".to_string())
+    }
+}
+
+impl RolesState {
+    pub fn role(&self) -> Role {
+        if self.pin.lock().unwrap().is_none() {
+            return Role::Teacher;
+        }
+        match *self.unlocked_until.lock().unwrap() {
+            Some(until) if Instant::now() < until => Role::Teacher,
+            _ => Role::Student,
+        }
+    }
+
+    fn status(&self) -> RoleStatus {
+        let seconds_left = self
+            .unlocked_until
+            .lock()
+            .unwrap()
+            .and_then(|until| until.checked_duration_since(Instant::now()))
+            .map(|left| left.as_secs());
+        let pin_set = self.pin.lock().unwrap().is_some();
+        RoleStatus { role: self.role(), pin_set, teacher_seconds_left: seconds_left.filter(|_| pin_set) }
+    }
+
+    /// Check a PIN, locking out further attempts for a minute after repeated failures
+    fn verify_pin(&self, app: &AppHandle, pin: &str) -> Result<(), String> {
+        let mut attempts = self.attempts.lock().unwrap();
+        let now = chrono::Utc::now().timestamp();
+        if let Some(until) = attempts.locked_until {
+            if now < until {
+                return Err(format!("Too many wrong PINs. Try again in {} seconds", until - now));
+            }
+            attempts.locked_until = None;
+        }
+        let matches = self
+            .pin
+            .lock()
+            .unwrap()
+            .as_ref()
+            .is_some_and(|stored| hash_pin(&stored.salt, pin) == stored.hash);
+        let result = if matches {
+            attempts.failed = 0;
+            Ok(())
+        } else {
+            attempts.failed += 1;
+            if attempts.failed >= MAX_FAILED_ATTEMPTS {
+                attempts.failed = 0;
+                attempts.locked_until = Some(now + LOCKOUT.as_secs() as i64);
+            }
+            Err("Wrong PIN".to_string())
+        };
+        persist_attempts(app, &attempts);
+        result
+    }
+
+    /// Refuse teacher-only backend commands in student mode
+    pub fn check_invoke(&self, command: &str) -> Result<(), String> {
+        if self.role() == Role::Teacher {
+            return Ok(());
+        }
+        let teacher_only = ALWAYS_TEACHER_ONLY.contains(&command)
+            || self.policy.lock().unwrap().teacher_only_commands.iter().any(|c| c == command);
+        if teacher_only {
+            return Err(format!("'{}' is only available in teacher mode", command));
+        }
+        Ok(())
+    }
+
+    /// Limit programs run for a student to the student allowlist
+    pub fn check_program(&self, command: &str) -> Result<(), String> {
+        if self.role() == Role::Teacher {
+            return Ok(());
+        }
+        let policy = self.policy.lock().unwrap();
+        if policy.student_allowed_commands.iter().any(|allowed| allowed.eq_ignore_ascii_case(command)) {
+            return Ok(());
+        }
+        Err(format!(
+            "Command '{}' needs teacher mode. Students may run: {}",
+            command,
+            policy.student_allowed_commands.join(", ")
+        ))
+    }
+}
+
+fn persist_attempts(app: &AppHandle, attempts: &Attempts) {
+    let result = persistence::shared_data_file(app, ATTEMPTS_FILE).and_then(|path| persistence::save_json(&path, attempts));
+    if let Err(e) = result {
+        tracing::warn!("⚠️ Failed to persist PIN attempts: {}", e);
+    }
+}
+
+/// Load the teacher PIN, the PIN attempts and the active profile's role policy. Runs after
+/// `profiles::init`.
+pub fn init(app: &AppHandle) {
+    let state = app.state::<RolesState>();
+    let pin: StoredPin = persistence::shared_data_file(app, PIN_FILE)
+        .map(|path| persistence::load_json(&path))
+        .unwrap_or_default();
+    *state.pin.lock().unwrap() = (!pin.hash.is_empty()).then_some(pin);
+    *state.attempts.lock().unwrap() = persistence::shared_data_file(app, ATTEMPTS_FILE)
+        .map(|path| persistence::load_json(&path))
+        .unwrap_or_default();
+    *state.policy.lock().unwrap() = persistence::data_file(app, POLICY_FILE)
+        .map(|path| persistence::load_json(&path))
+        .unwrap_or_default();
+}
+
+fn emit_status(app: &AppHandle, state: &RolesState) -> RoleStatus {
+    let status = state.status();
+    let _ = crate::event_bus::emit(app, crate::events::ROLE_CHANGED, status.clone());
+    status
+}
+
+#[tauri::command]
+pub async fn get_role_status(state: State<'_, RolesState>) -> Result<RoleStatus, String> {
+    Ok(state.status())
+}
+
+/// Set or change the teacher PIN. Changing it takes the current PIN (or an unlocked teacher mode).
+#[tauri::command]
+pub async fn set_teacher_pin(
+    app: AppHandle,
+    state: State<'_, RolesState>,
+    current_pin: Option<String>,
+    new_pin: String,
+) -> Result<RoleStatus, String> {
+    validate_pin(&new_pin)?;
+    let pin_set = state.pin.lock().unwrap().is_some();
+    if pin_set && state.role() != Role::Teacher {
+        state.verify_pin(&app, current_pin.as_deref().unwrap_or_default())?;
+    }
+
+    let salt = uuid::Uuid::new_v4().simple().to_string();
+    let stored = StoredPin { hash: hash_pin(&salt, &new_pin), salt };
+    let path = persistence::shared_data_file(&app, PIN_FILE)?;
+    persistence::save_json(&path, &stored)?;
+    *state.pin.lock().unwrap() = Some(stored);
+    // Whoever set the PIN is the teacher; start the session so they aren't locked out mid-setup
+    *state.unlocked_until.lock().unwrap() = Some(Instant::now() + TEACHER_SESSION);
+    tracing::info!("🔑 Teacher PIN {}", if pin_set { "changed" } else { "set" });
+    Ok(emit_status(&app, &state))
+}
+
+/// Enter teacher mode for 30 minutes
+#[tauri::command]
+pub async fn unlock_teacher_mode(
+    app: AppHandle,
+    state: State<'_, RolesState>,
+    pin: String,
+) -> Result<RoleStatus, String> {
+    if state.pin.lock().unwrap().is_none() {
+        return Err("No teacher PIN is set".to_string());
+    }
+    if let Err(e) = state.verify_pin(&app, &pin) {
+        tracing::warn!("🔒 Teacher mode unlock failed: {}", e);
+        return Err(e);
+    }
+    *state.unlocked_until.lock().unwrap() = Some(Instant::now() + TEACHER_SESSION);
+    tracing::info!("🔓 Teacher mode unlocked");
+    Ok(emit_status(&app, &state))
+}
+
+/// Return to student mode
+#[tauri::command]
+pub async fn lock_teacher_mode(app: AppHandle, state: State<'_, RolesState>) -> Result<RoleStatus, String> {
+    *state.unlocked_until.lock().unwrap() = None;
+    tracing::info!("🔒 Teacher mode locked");
+    Ok(emit_status(&app, &state))
+}
+
+#[tauri::command]
+pub async fn get_role_policy(state: State<'_, RolesState>) -> Result<RolePolicy, String> {
+    Ok(state.policy.lock().unwrap().clone())
+}
+
+/// Change what students of the active profile may do (teacher mode only)
+#[tauri::command]
+pub async fn set_role_policy(
+    app: AppHandle,
+    state: State<'_, RolesState>,
+    policy: RolePolicy,
+) -> Result<(), String> {
+    let path = persistence::data_file(&app, POLICY_FILE)?;
+    persistence::save_json(&path, &policy)?;
+    *state.policy.lock().unwrap() = policy;
+    Ok(())
+}
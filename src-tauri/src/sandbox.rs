@@ -0,0 +1,278 @@
+//! Limits for commands run by `execute_command` and `confirm_execution`, and for background
+//! jobs started by `process_manager`. On Windows each command starts suspended inside a Job
+//! Object capped in memory, process count and, where Windows supports it, CPU and (optionally)
+//! outgoing network traffic; everything left in the job is killed when the command ends or
+//! overruns its time limit. Background jobs have no time limit. Elsewhere only the time limit
+//! applies.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::process::{Child, Output, Stdio};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+
+use crate::persistence;
+
+const SETTINGS_FILE: &str = "sandbox_settings.json";
+const MAX_MEMORY_MB: u64 = 16 * 1024;
+const MAX_RUNTIME_SECONDS: u64 = 30 * 60;
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct SandboxSettings {
+    pub enabled: bool,
+    /// Memory for the command and everything it starts
+    pub max_memory_mb: u64,
+    /// Share of total CPU time, 1-100
+    pub max_cpu_percent: u32,
+    pub max_runtime_seconds: u64,
+    /// Throttle outgoing network traffic to a byte per second. Off by default: anything that
+    /// downloads (git fetch, npm install, docker pull) would hang until the time limit.
+    pub block_network: bool,
+}
+
+impl Default for SandboxSettings {
+    fn default() -> Self {
+        Self { enabled: true, max_memory_mb: 1024, max_cpu_percent: 50, max_runtime_seconds: 120, block_network: false }
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct SandboxState {
+    settings: Arc<Mutex<SandboxSettings>>,
+}
+
+#[cfg(windows)]
+mod job {
+    use super::SandboxSettings;
+    use std::ptr;
+    use windows_sys::Win32::Foundation::{CloseHandle, HANDLE, INVALID_HANDLE_VALUE};
+    use windows_sys::Win32::System::Diagnostics::ToolHelp::{
+        CreateToolhelp32Snapshot, Thread32First, Thread32Next, TH32CS_SNAPTHREAD, THREADENTRY32,
+    };
+    use windows_sys::Win32::System::JobObjects::{
+        AssignProcessToJobObject, CreateJobObjectW, JobObjectCpuRateControlInformation,
+        JobObjectExtendedLimitInformation, JobObjectNetRateControlInformation, SetInformationJobObject,
+        JOBOBJECTINFOCLASS, JOBOBJECT_CPU_RATE_CONTROL_INFORMATION, JOBOBJECT_EXTENDED_LIMIT_INFORMATION,
+        JOBOBJECT_NET_RATE_CONTROL_INFORMATION, JOB_OBJECT_CPU_RATE_CONTROL_ENABLE,
+        JOB_OBJECT_CPU_RATE_CONTROL_HARD_CAP, JOB_OBJECT_LIMIT_ACTIVE_PROCESS, JOB_OBJECT_LIMIT_JOB_MEMORY,
+        JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE, JOB_OBJECT_NET_RATE_CONTROL_ENABLE,
+        JOB_OBJECT_NET_RATE_CONTROL_MAX_BANDWIDTH,
+    };
+    use windows_sys::Win32::System::Threading::{OpenThread, ResumeThread, THREAD_SUSPEND_RESUME};
+
+    /// Processes the command may have alive at once, itself included
+    const MAX_PROCESSES: u32 = 32;
+
+    /// Closing the handle kills every process still in the job
+    pub struct Job(HANDLE);
+
+    // The handle is only used to assign processes and is closed once
+    unsafe impl Send for Job {}
+
+    impl Drop for Job {
+        fn drop(&mut self) {
+            unsafe { CloseHandle(self.0) };
+        }
+    }
+
+    fn last_error(context: &str) -> String {
+        format!("{}: {}", context, std::io::Error::last_os_error())
+    }
+
+    fn set<T>(job: &Job, class: JOBOBJECTINFOCLASS, info: &T, what: &str) -> Result<(), String> {
+        let ok = unsafe {
+            SetInformationJobObject(job.0, class, info as *const T as *const _, std::mem::size_of::<T>() as u32)
+        };
+        if ok == 0 {
+            return Err(last_error(&format!("Failed to apply the sandbox {} limit", what)));
+        }
+        Ok(())
+    }
+
+    /// Rate controls need Windows 8 (CPU) or 10 (network) and can be refused, e.g. inside
+    /// another job; the command then runs without them rather than not at all
+    fn set_or_warn<T>(job: &Job, class: JOBOBJECTINFOCLASS, info: &T, what: &str) {
+        if let Err(e) = set(job, class, info, what) {
+            tracing::warn!("⚠️ {}; running without it", e);
+        }
+    }
+
+    pub fn create(settings: &SandboxSettings) -> Result<Job, String> {
+        let handle = unsafe { CreateJobObjectW(ptr::null(), ptr::null()) };
+        if handle.is_null() {
+            return Err(last_error("Failed to create the sandbox job"));
+        }
+        let job = Job(handle);
+
+        let mut limits = JOBOBJECT_EXTENDED_LIMIT_INFORMATION::default();
+        limits.BasicLimitInformation.LimitFlags =
+            JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE | JOB_OBJECT_LIMIT_JOB_MEMORY | JOB_OBJECT_LIMIT_ACTIVE_PROCESS;
+        limits.BasicLimitInformation.ActiveProcessLimit = MAX_PROCESSES;
+        limits.JobMemoryLimit = (settings.max_memory_mb * 1024 * 1024) as usize;
+        set(&job, JobObjectExtendedLimitInformation, &limits, "memory")?;
+
+        let mut cpu = JOBOBJECT_CPU_RATE_CONTROL_INFORMATION {
+            ControlFlags: JOB_OBJECT_CPU_RATE_CONTROL_ENABLE | JOB_OBJECT_CPU_RATE_CONTROL_HARD_CAP,
+            ..Default::default()
+        };
+        // In hundredths of a percent
+        cpu.Anonymous.CpuRate = settings.max_cpu_percent * 100;
+        set_or_warn(&job, JobObjectCpuRateControlInformation, &cpu, "CPU");
+
+        if settings.block_network {
+            let network = JOBOBJECT_NET_RATE_CONTROL_INFORMATION {
+                MaxBandwidth: 1,
+                ControlFlags: JOB_OBJECT_NET_RATE_CONTROL_ENABLE | JOB_OBJECT_NET_RATE_CONTROL_MAX_BANDWIDTH,
+                DscpTag: 0,
+            };
+            set_or_warn(&job, JobObjectNetRateControlInformation, &network, "network");
+        }
+        Ok(job)
+    }
+
+    /// Put the suspended process into the job, then let it run
+    pub fn assign_and_resume(job: &Job, process: HANDLE, pid: u32) -> Result<(), String> {
+        if unsafe { AssignProcessToJobObject(job.0, process) } == 0 {
+            return Err(last_error("Failed to place the command in the sandbox"));
+        }
+        let snapshot = unsafe { CreateToolhelp32Snapshot(TH32CS_SNAPTHREAD, 0) };
+        if snapshot == INVALID_HANDLE_VALUE {
+            return Err(last_error("Failed to start the sandboxed command"));
+        }
+        let mut entry = THREADENTRY32 { dwSize: std::mem::size_of::<THREADENTRY32>() as u32, ..Default::default() };
+        let mut resumed = 0;
+        let mut more = unsafe { Thread32First(snapshot, &mut entry) } != 0;
+        while more {
+            if entry.th32OwnerProcessID == pid {
+                let thread = unsafe { OpenThread(THREAD_SUSPEND_RESUME, 0, entry.th32ThreadID) };
+                if !thread.is_null() {
+                    unsafe {
+                        ResumeThread(thread);
+                        CloseHandle(thread);
+                    }
+                    resumed += 1;
+                }
+            }
+            more = unsafe { Thread32Next(snapshot, &mut entry) } != 0;
+        }
+        unsafe { CloseHandle(snapshot) };
+        if resumed == 0 {
+            return Err("Failed to start the sandboxed command".to_string());
+        }
+        Ok(())
+    }
+}
+
+fn validate(settings: &SandboxSettings) -> Result<(), String> {
+    if !(64..=MAX_MEMORY_MB).contains(&settings.max_memory_mb) {
+        return Err(format!("The memory limit must be between 64 and {} MB", MAX_MEMORY_MB));
+    }
+    if !(1..=100).contains(&settings.max_cpu_percent) {
+        return Err("The CPU limit must be between 1 and 100 percent".to_string());
+    }
+    if !(1..=MAX_RUNTIME_SECONDS).contains(&settings.max_runtime_seconds) {
+        return Err(format!("The time limit must be between 1 and {} seconds", MAX_RUNTIME_SECONDS));
+    }
+    Ok(())
+}
+
+pub fn init(app: &AppHandle) {
+    let settings: SandboxSettings = persistence::data_file(app, SETTINGS_FILE)
+        .map(|path| persistence::load_json(&path))
+        .unwrap_or_default();
+    *app.state::<SandboxState>().settings.lock().unwrap() = settings;
+}
+
+/// Run `command` to completion under the sandbox limits and collect its output
+pub(crate) async fn run(app: &AppHandle, command: std::process::Command) -> Result<Output, String> {
+    let settings = app.state::<SandboxState>().settings.lock().unwrap().clone();
+    let mut command = tokio::process::Command::from(command);
+    command.stdin(Stdio::null()).stdout(Stdio::piped()).stderr(Stdio::piped()).kill_on_drop(true);
+    if !settings.enabled {
+        return command.output().await.map_err(|e| format!("Failed to execute command: {}", e));
+    }
+
+    #[cfg(windows)]
+    let job = {
+        const CREATE_SUSPENDED: u32 = 0x4;
+        command.creation_flags(CREATE_SUSPENDED);
+        job::create(&settings)?
+    };
+    let child = command.spawn().map_err(|e| format!("Failed to execute command: {}", e))?;
+    // On failure the suspended child is killed when dropped
+    #[cfg(windows)]
+    job::assign_and_resume(
+        &job,
+        child.raw_handle().ok_or("Failed to start the sandboxed command")?,
+        child.id().unwrap_or_default(),
+    )?;
+
+    let limit = Duration::from_secs(settings.max_runtime_seconds);
+    match tokio::time::timeout(limit, child.wait_with_output()).await {
+        Ok(output) => output.map_err(|e| format!("Failed to execute command: {}", e)),
+        Err(_) => Err(format!("Command stopped after the {}-second sandbox time limit", settings.max_runtime_seconds)),
+    }
+}
+
+/// The job object a background command runs in; dropping it kills whatever the command left
+/// running
+#[derive(Default)]
+pub(crate) struct Confinement {
+    #[cfg(windows)]
+    _job: Option<job::Job>,
+}
+
+/// Start `command` under the sandbox limits other than the time limit, for background jobs that
+/// run until they are stopped
+pub(crate) fn spawn(app: &AppHandle, mut command: std::process::Command) -> Result<(Child, Confinement), String> {
+    let settings = app.state::<SandboxState>().settings.lock().unwrap().clone();
+    if !settings.enabled {
+        let child = command.spawn().map_err(|e| format!("Failed to start command: {}", e))?;
+        return Ok((child, Confinement::default()));
+    }
+
+    #[cfg(windows)]
+    {
+        use std::os::windows::io::AsRawHandle;
+        use std::os::windows::process::CommandExt;
+
+        const CREATE_SUSPENDED: u32 = 0x4;
+        let job = job::create(&settings)?;
+        let mut child = command
+            .creation_flags(CREATE_SUSPENDED)
+            .spawn()
+            .map_err(|e| format!("Failed to start command: {}", e))?;
+        if let Err(e) = job::assign_and_resume(&job, child.as_raw_handle(), child.id()) {
+            let _ = child.kill();
+            return Err(e);
+        }
+        Ok((child, Confinement { _job: Some(job) }))
+    }
+    #[cfg(not(windows))]
+    {
+        let child = command.spawn().map_err(|e| format!("Failed to start command: {}", e))?;
+        Ok((child, Confinement::default()))
+    }
+}
+
+#[tauri::command]
+pub async fn get_sandbox_settings(state: State<'_, SandboxState>) -> Result<SandboxSettings, String> {
+    Ok(state.settings.lock().unwrap().clone())
+}
+
+/// Change the limits for executed commands (teacher mode only)
+#[tauri::command]
+pub async fn set_sandbox_settings(
+    app: AppHandle,
+    state: State<'_, SandboxState>,
+    settings: SandboxSettings,
+) -> Result<(), String> {
+    validate(&settings)?;
+    let path = persistence::data_file(&app, SETTINGS_FILE)?;
+    persistence::save_json(&path, &settings)?;
+    tracing::info!("🧱 Sandbox settings changed: {:?}", settings);
+    *state.settings.lock().unwrap() = settings;
+    Ok(())
+}
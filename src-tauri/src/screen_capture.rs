@@ -1,19 +1,76 @@
-use std::sync::atomic::AtomicU64;
-use std::sync::Arc;
+use futures_util::future::{BoxFuture, FutureExt, Shared};
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager};
+
+use crate::commands::CaptureResult;
+use crate::image_resize::{ResizeBackend, ResizeState};
+use crate::powershell_host::PowerShellHostState;
+
+/// Coalesces overlapping runs of one operation: while a run is in flight, later callers wait
+/// for it and get a copy of its result instead of starting their own
+pub struct SingleFlight<T: Clone> {
+    in_flight: Mutex<Option<Shared<BoxFuture<'static, T>>>>,
+}
+
+impl<T: Clone> Default for SingleFlight<T> {
+    fn default() -> Self {
+        Self { in_flight: Mutex::new(None) }
+    }
+}
+
+impl<T: Clone + Send + Sync + 'static> SingleFlight<T> {
+    /// Run `operation`, or join the run already in flight (dropping `operation` unpolled)
+    pub async fn run<F>(&self, operation: F) -> T
+    where
+        F: Future<Output = T> + Send + 'static,
+    {
+        let shared = self
+            .in_flight
+            .lock()
+            .unwrap()
+            .get_or_insert_with(|| operation.boxed().shared())
+            .clone();
+        let result = shared.clone().await;
+        // The first caller to finish clears the slot; a newer run may already have replaced it
+        let mut in_flight = self.in_flight.lock().unwrap();
+        if in_flight.as_ref().is_some_and(|current| current.ptr_eq(&shared)) {
+            *in_flight = None;
+        }
+        result
+    }
+}
 
 #[derive(Clone)]
 pub struct ScreenCaptureState {
     pub interval_seconds: Arc<AtomicU64>,
+    /// Skip monitoring captures (e.g. during a focus-timer break)
+    pub paused: Arc<AtomicBool>,
+    /// Skip monitoring captures because the student paused them with `pause_monitoring`
+    pub user_paused: Arc<AtomicBool>,
+    /// Full-screen capture currently running, shared by overlapping callers
+    full_screen: Arc<SingleFlight<Result<CaptureResult, String>>>,
 }
 
 impl Default for ScreenCaptureState {
     fn default() -> Self {
         Self {
             interval_seconds: Arc::new(AtomicU64::new(3)),
+            paused: Arc::new(AtomicBool::new(false)),
+            user_paused: Arc::new(AtomicBool::new(false)),
+            full_screen: Arc::default(),
         }
     }
 }
 
+impl ScreenCaptureState {
+    /// Whether monitoring captures are skipped for any reason
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Relaxed) || self.user_paused.load(Ordering::Relaxed)
+    }
+}
+
 pub struct ScreenCapture;
 
 impl ScreenCapture {
@@ -21,14 +78,18 @@ impl ScreenCapture {
         Self
     }
 
-    pub async fn capture_full_screen(
-        &self,
-        _state: &ScreenCaptureState,
-    ) -> Result<crate::commands::CaptureResult, String> {
+    /// Capture the virtual screen. Calls made while a capture is running get that capture's
+    /// result rather than starting a second PowerShell process.
+    pub async fn capture_full_screen(&self, app: &AppHandle) -> Result<CaptureResult, String> {
+        let host = app.state::<PowerShellHostState>().inner().clone();
+        let resize = app.state::<ResizeState>().get();
+        let state = app.state::<ScreenCaptureState>();
+        let capture = state.full_screen.run(Self::capture_full_screen_once(host, resize));
+        crate::metrics::timed(app, crate::metrics::CAPTURE, capture).await
+    }
+
+    async fn capture_full_screen_once(host: PowerShellHostState, resize: ResizeBackend) -> Result<CaptureResult, String> {
         use std::time::{SystemTime, UNIX_EPOCH};
-        use sha2::{Sha256, Digest};
-        use hex;
-        use std::process::Command;
 
         #[cfg(target_os = "windows")]
         {
@@ -50,71 +111,16 @@ impl ScreenCapture {
                 [Convert]::ToBase64String($bytes)
             "#;
 
-            let output = Command::new("powershell")
-                .arg("-Command")
-                .arg(ps_script)
-                .output()
-                .map_err(|e| format!("Failed to execute PowerShell: {}", e))?;
-
-            if !output.status.success() {
-                let error = String::from_utf8_lossy(&output.stderr);
-                return Err(format!("PowerShell error: {}", error));
+            let output = host.run(ps_script, None, |_| {}).await?;
+            if !output.success() {
+                return Err(format!("PowerShell error: {}", output.stderr));
             }
 
-            let image_base64 = String::from_utf8(output.stdout)
-                .map_err(|e| format!("Failed to parse PowerShell output: {}", e))?
-                .trim()
-                .to_string();
-
-            // Decode base64 to bytes for processing
-            use base64::{engine::general_purpose, Engine as _};
-            let image_bytes = general_purpose::STANDARD
-                .decode(&image_base64)
-                .map_err(|e| format!("Failed to decode base64: {}", e))?;
-
-            // Store original length before potential move
-            let original_len = image_bytes.len();
-
-            // Calculate hash
-            let mut hasher = Sha256::new();
-            hasher.update(&image_bytes);
-            let hash = hex::encode(hasher.finalize());
-
-            // Compress if too large (max 2MB)
-            let final_bytes = if image_bytes.len() > 2_000_000 {
-                // Load and resize image
-                let img = image::load_from_memory(&image_bytes)
-                    .map_err(|e| format!("Failed to load image: {}", e))?;
-                let resized = img.resize(
-                    (img.width() as f32 * 0.7) as u32,
-                    (img.height() as f32 * 0.7) as u32,
-                    image::imageops::FilterType::Lanczos3,
-                );
-                // Save to PNG bytes
-                let mut compressed = Vec::new();
-                {
-                    use image::ImageEncoder;
-                    let encoder = image::codecs::png::PngEncoder::new(&mut compressed);
-                    encoder
-                        .write_image(
-                            &resized.to_rgba8(),
-                            resized.width(),
-                            resized.height(),
-                            image::ColorType::Rgba8.into(),
-                        )
-                        .map_err(|e| format!("Failed to encode compressed PNG: {}", e))?;
-                }
-                compressed
-            } else {
-                image_bytes
-            };
-
-            // Re-encode to base64 if we compressed
-            let final_base64 = if final_bytes.len() != original_len {
-                general_purpose::STANDARD.encode(&final_bytes)
-            } else {
-                image_base64
-            };
+            let image_base64 = output.stdout.trim().to_string();
+            // Hashing, scaling and re-encoding are CPU work; keep them off the async runtime
+            let (final_base64, hash) = tauri::async_runtime::spawn_blocking(move || Self::compress(image_base64, resize))
+                .await
+                .map_err(|e| format!("Failed to process the capture: {}", e))??;
 
             // Get timestamp
             let timestamp = SystemTime::now()
@@ -126,14 +132,57 @@ impl ScreenCapture {
                 image_base64: final_base64,
                 hash,
                 timestamp,
+                diff: None,
+                delta: None,
+                ocr_text: None,
+                classification: None,
             })
         }
 
         #[cfg(not(target_os = "windows"))]
         {
             // Fallback for non-Windows platforms
+            let _ = (host, resize);
             Err("Screen capture not implemented for this platform".to_string())
         }
     }
-}
 
+    /// Hash the PNG capture and, when it is over 2MB, scale it to 70% and re-encode it.
+    /// Returns the base64 image and the hash of the original.
+    #[cfg(target_os = "windows")]
+    fn compress(image_base64: String, resize: ResizeBackend) -> Result<(String, String), String> {
+        use base64::{engine::general_purpose, Engine as _};
+        use sha2::{Digest, Sha256};
+
+        let image_bytes = general_purpose::STANDARD
+            .decode(&image_base64)
+            .map_err(|e| format!("Failed to decode base64: {}", e))?;
+
+        let mut hasher = Sha256::new();
+        hasher.update(&image_bytes);
+        let hash = hex::encode(hasher.finalize());
+
+        if image_bytes.len() <= 2_000_000 {
+            return Ok((image_base64, hash));
+        }
+        let img = image::load_from_memory(&image_bytes)
+            .map_err(|e| format!("Failed to load image: {}", e))?
+            .to_rgba8();
+        let resized = crate::image_resize::resize(
+            resize,
+            &img,
+            (img.width() as f32 * 0.7) as u32,
+            (img.height() as f32 * 0.7) as u32,
+            image::imageops::FilterType::Lanczos3,
+        );
+        let mut compressed = Vec::new();
+        {
+            use image::ImageEncoder;
+            let encoder = image::codecs::png::PngEncoder::new(&mut compressed);
+            encoder
+                .write_image(&resized, resized.width(), resized.height(), image::ColorType::Rgba8.into())
+                .map_err(|e| format!("Failed to encode compressed PNG: {}", e))?;
+        }
+        Ok((general_purpose::STANDARD.encode(&compressed), hash))
+    }
+}
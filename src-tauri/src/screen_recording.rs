@@ -0,0 +1,311 @@
+//! Short screen recordings encoded by ffmpeg, so the tutor can review what the student
+//! attempted instead of a single frame. ffmpeg is looked up next to the executable, then
+//! on PATH; `AI_TEACHER_FFMPEG` overrides both.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, State};
+
+use crate::consent::{ConsentScope, ConsentState};
+use crate::screen_capture::ScreenCaptureState;
+
+const FFMPEG_ENV: &str = "AI_TEACHER_FFMPEG";
+const DEFAULT_FPS: u32 = 10;
+const MAX_FPS: u32 = 30;
+const DEFAULT_MAX_SECONDS: u32 = 30;
+const MAX_SECONDS: u32 = 300;
+/// How long ffmpeg gets to finalize the file after being asked to stop
+const STOP_TIMEOUT: Duration = Duration::from_secs(5);
+const EXIT_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// What to record
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RecordingTarget {
+    /// The whole (virtual) screen
+    Screen,
+    /// A rectangle in screen coordinates
+    Region { x: i32, y: i32, width: u32, height: u32 },
+    /// A window, by its exact title
+    Window { title: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RecordingInfo {
+    pub id: String,
+    /// Where the MP4 is being written
+    pub path: String,
+    pub target: RecordingTarget,
+    pub fps: u32,
+    pub max_seconds: u32,
+    pub started_at: i64,
+}
+
+/// Payload of the `screen-recording-finished` event, also returned by `stop_screen_recording`
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RecordingFinished {
+    pub id: String,
+    pub path: String,
+    pub duration_ms: u64,
+    /// False when the recording ended on its own at `max_seconds` (or ffmpeg failed)
+    pub stopped: bool,
+    /// ffmpeg's last error line if it exited unsuccessfully
+    pub error: Option<String>,
+}
+
+struct ActiveRecording {
+    info: RecordingInfo,
+    child: Arc<Mutex<Child>>,
+    started: Instant,
+    last_error: Arc<Mutex<Option<String>>>,
+}
+
+#[derive(Clone, Default)]
+pub struct ScreenRecordingState {
+    active: Arc<Mutex<Option<ActiveRecording>>>,
+}
+
+impl ScreenRecordingState {
+    pub fn is_recording(&self) -> bool {
+        self.active.lock().unwrap().is_some()
+    }
+}
+
+pub(crate) fn ffmpeg_path() -> PathBuf {
+    if let Some(path) = std::env::var_os(FFMPEG_ENV) {
+        return PathBuf::from(path);
+    }
+    let name = if cfg!(target_os = "windows") { "ffmpeg.exe" } else { "ffmpeg" };
+    std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(|dir| dir.join(name)))
+        .filter(|bundled| bundled.exists())
+        .unwrap_or_else(|| PathBuf::from(name))
+}
+
+/// ffmpeg input arguments for the target
+#[cfg(target_os = "windows")]
+fn input_args(target: &RecordingTarget) -> Result<Vec<String>, String> {
+    let mut args = vec!["-f".to_string(), "gdigrab".to_string()];
+    match target {
+        RecordingTarget::Screen => args.extend(["-i".to_string(), "desktop".to_string()]),
+        RecordingTarget::Region { x, y, width, height } => args.extend([
+            "-offset_x".to_string(),
+            x.to_string(),
+            "-offset_y".to_string(),
+            y.to_string(),
+            "-video_size".to_string(),
+            format!("{}x{}", width, height),
+            "-i".to_string(),
+            "desktop".to_string(),
+        ]),
+        RecordingTarget::Window { title } => args.extend(["-i".to_string(), format!("title={}", title)]),
+    }
+    Ok(args)
+}
+
+#[cfg(not(target_os = "windows"))]
+fn input_args(target: &RecordingTarget) -> Result<Vec<String>, String> {
+    let display = std::env::var("DISPLAY").unwrap_or_else(|_| ":0".to_string());
+    let mut args = vec!["-f".to_string(), "x11grab".to_string()];
+    match target {
+        RecordingTarget::Screen => args.extend(["-i".to_string(), display]),
+        RecordingTarget::Region { x, y, width, height } => args.extend([
+            "-video_size".to_string(),
+            format!("{}x{}", width, height),
+            "-i".to_string(),
+            format!("{}+{},{}", display, x, y),
+        ]),
+        RecordingTarget::Window { .. } => {
+            return Err("Recording a single window is only supported on Windows".to_string());
+        }
+    }
+    Ok(args)
+}
+
+fn recordings_dir() -> Result<PathBuf, String> {
+    let dir = crate::commands::captures_dir().join("recordings");
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create recordings directory: {}", e))?;
+    Ok(dir)
+}
+
+/// Keep ffmpeg's stderr drained (it blocks on a full pipe) and remember its last line
+fn drain_stderr(child: &mut Child, last_error: Arc<Mutex<Option<String>>>) {
+    let Some(stderr) = child.stderr.take() else { return };
+    thread::spawn(move || {
+        for line in BufReader::new(stderr).lines() {
+            let Ok(line) = line else { break };
+            if !line.trim().is_empty() {
+                *last_error.lock().unwrap() = Some(line);
+            }
+        }
+    });
+}
+
+fn finished(recording: &ActiveRecording, stopped: bool, success: bool) -> RecordingFinished {
+    RecordingFinished {
+        id: recording.info.id.clone(),
+        path: recording.info.path.clone(),
+        duration_ms: recording.started.elapsed().as_millis() as u64,
+        stopped,
+        error: if success { None } else { recording.last_error.lock().unwrap().clone() },
+    }
+}
+
+/// Wait for ffmpeg to end on its own (at `max_seconds`) and announce it, unless
+/// `stop_screen_recording` took the recording over first
+fn watch_exit(app: AppHandle, id: String, child: Arc<Mutex<Child>>) {
+    thread::spawn(move || loop {
+        let status = child.lock().unwrap().try_wait();
+        match status {
+            Ok(Some(status)) => {
+                let state = app.state::<ScreenRecordingState>();
+                let mut active = state.active.lock().unwrap();
+                if active.as_ref().is_some_and(|r| r.info.id == id) {
+                    let recording = active.take().unwrap();
+                    let result = finished(&recording, false, status.success());
+                    tracing::info!("🎬 Recording {} finished after {} ms", id, result.duration_ms);
+                    let _ = crate::event_bus::emit(&app, crate::events::SCREEN_RECORDING_FINISHED, result);
+                }
+                break;
+            }
+            Ok(None) => thread::sleep(EXIT_POLL_INTERVAL),
+            Err(e) => {
+                tracing::warn!("⚠️ Failed to poll ffmpeg for recording {}: {}", id, e);
+                break;
+            }
+        }
+    });
+}
+
+/// Start recording the screen, a region or a window to an MP4. Only one recording runs at a
+/// time; it stops by itself after `max_seconds` (default 30).
+#[tauri::command]
+pub async fn start_screen_recording(
+    app: AppHandle,
+    state: State<'_, ScreenRecordingState>,
+    target: RecordingTarget,
+    fps: Option<u32>,
+    max_seconds: Option<u32>,
+) -> Result<RecordingInfo, String> {
+    app.state::<ConsentState>().require(ConsentScope::Screen)?;
+    if app.state::<ScreenCaptureState>().is_paused() {
+        return Err("Screen capture is paused".to_string());
+    }
+    let mut active = state.active.lock().unwrap();
+    if let Some(recording) = active.as_ref() {
+        return Err(format!("Recording {} is already running", recording.info.id));
+    }
+    if let RecordingTarget::Region { width, height, .. } = target {
+        if width == 0 || height == 0 {
+            return Err("Recording region must not be empty".to_string());
+        }
+    }
+
+    let fps = fps.unwrap_or(DEFAULT_FPS).clamp(1, MAX_FPS);
+    let max_seconds = max_seconds.unwrap_or(DEFAULT_MAX_SECONDS).clamp(1, MAX_SECONDS);
+    let id = uuid::Uuid::new_v4().simple().to_string();
+    let path = recordings_dir()?.join(format!("recording_{}_{}.mp4", chrono::Local::now().format("%Y%m%d_%H%M%S"), &id[..8]));
+
+    let mut command = Command::new(ffmpeg_path());
+    command
+        .args(["-hide_banner", "-loglevel", "error", "-y", "-framerate", &fps.to_string()])
+        .args(input_args(&target)?)
+        .args(["-t", &max_seconds.to_string()])
+        // libx264 needs even dimensions
+        .args(["-vf", "pad=ceil(iw/2)*2:ceil(ih/2)*2", "-c:v", "libx264", "-preset", "ultrafast", "-pix_fmt", "yuv420p"])
+        .arg(&path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped());
+    let mut child = command.spawn().map_err(|e| {
+        format!("Failed to start ffmpeg ({}). Install ffmpeg or set {}.", e, FFMPEG_ENV)
+    })?;
+
+    let last_error = Arc::new(Mutex::new(None));
+    drain_stderr(&mut child, last_error.clone());
+    let info = RecordingInfo {
+        id: id.clone(),
+        path: path.to_string_lossy().to_string(),
+        target,
+        fps,
+        max_seconds,
+        started_at: chrono::Utc::now().timestamp(),
+    };
+    let child = Arc::new(Mutex::new(child));
+    *active = Some(ActiveRecording {
+        info: info.clone(),
+        child: child.clone(),
+        started: Instant::now(),
+        last_error,
+    });
+    drop(active);
+
+    watch_exit(app, id, child);
+    tracing::info!("🎬 Recording {:?} at {} fps to {}", info.target, fps, info.path);
+    Ok(info)
+}
+
+/// Ask ffmpeg to finish the file and wait for it to exit, killing it after `STOP_TIMEOUT`.
+/// Returns whether it exited cleanly.
+fn finalize(child: &Mutex<Child>) -> bool {
+    let mut child = child.lock().unwrap();
+    // "q" makes ffmpeg write the MP4 trailer; killing it would leave an unplayable file
+    if let Some(stdin) = child.stdin.as_mut() {
+        let _ = stdin.write_all(b"q");
+        let _ = stdin.flush();
+    }
+    let deadline = Instant::now() + STOP_TIMEOUT;
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) => return status.success(),
+            Ok(None) if Instant::now() < deadline => thread::sleep(EXIT_POLL_INTERVAL),
+            _ => {
+                tracing::warn!("⚠️ ffmpeg didn't stop in time, killing it");
+                let _ = child.kill();
+                let _ = child.wait();
+                return false;
+            }
+        }
+    }
+}
+
+/// Finish the running recording, if any, leaving a playable file: on exit, or when consent
+/// ends. Blocks while ffmpeg finalizes.
+pub fn stop(app: &AppHandle) {
+    let recording = app.state::<ScreenRecordingState>().active.lock().unwrap().take();
+    if let Some(recording) = recording {
+        finalize(&recording.child);
+        tracing::info!("🎬 Recording {} stopped", recording.info.id);
+    }
+}
+
+/// Stop the running recording and wait for ffmpeg to finalize the file
+#[tauri::command]
+pub async fn stop_screen_recording(
+    app: AppHandle,
+    state: State<'_, ScreenRecordingState>,
+) -> Result<RecordingFinished, String> {
+    let recording = state
+        .active
+        .lock()
+        .unwrap()
+        .take()
+        .ok_or("No recording is running")?;
+
+    let child = recording.child.clone();
+    let success = tauri::async_runtime::spawn_blocking(move || finalize(&child))
+        .await
+        .map_err(|e| format!("Failed to stop recording: {}", e))?;
+
+    let result = finished(&recording, true, success);
+    tracing::info!("🎬 Recording {} stopped after {} ms", result.id, result.duration_ms);
+    let _ = crate::event_bus::emit(&app, crate::events::SCREEN_RECORDING_FINISHED, result.clone());
+    Ok(result)
+}
@@ -0,0 +1,376 @@
+//! Multi-line scripts, run in two steps. `execute_script` only analyzes: it splits the script into
+//! its commands, checks each against the command policy and returns the verdicts as a dry run.
+//! Nothing runs until `confirm_execution` is called with the token from a fully permitted plan.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, State};
+
+use crate::commands::{self, CommandResult};
+
+const MAX_SCRIPT_BYTES: usize = 16 * 1024;
+const MAX_STEPS: usize = 50;
+/// How long a dry-run token can be confirmed
+const TOKEN_TTL_SECONDS: i64 = 5 * 60;
+const STEP_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// How a command is joined to the one before it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StepCondition {
+    /// First command, a new line or `;`
+    Always,
+    /// `&&`: only after the previous command succeeded
+    OnSuccess,
+    /// `||`: only after the previous command failed
+    OnFailure,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ScriptStep {
+    /// 1-based line of the script the command is on
+    pub line: usize,
+    /// The command as written
+    pub text: String,
+    pub program: Option<String>,
+    pub args: Vec<String>,
+    pub condition: StepCondition,
+    pub allowed: bool,
+    /// Why the policy blocks it
+    pub reason: Option<String>,
+}
+
+/// Dry-run report of `execute_script`
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ScriptPlan {
+    pub steps: Vec<ScriptStep>,
+    /// Every step is permitted, so the script can be confirmed
+    pub runnable: bool,
+    /// Pass to `confirm_execution` to run the script; None when it isn't runnable
+    pub token: Option<String>,
+    pub expires_at: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct StepOutcome {
+    pub text: String,
+    /// False when its `&&`/`||` condition wasn't met
+    pub ran: bool,
+    pub result: Option<CommandResult>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ScriptRun {
+    pub steps: Vec<StepOutcome>,
+    /// The last command that ran succeeded
+    pub success: bool,
+}
+
+struct PendingScript {
+    steps: Vec<ScriptStep>,
+    expires_at: i64,
+}
+
+#[derive(Clone, Default)]
+pub struct ScriptRunnerState {
+    /// Confirmable plans by token
+    pending: Arc<Mutex<HashMap<String, PendingScript>>>,
+}
+
+/// Split one command into words, honoring single and double quotes and backslash escapes
+fn split_words(text: &str) -> Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut quote: Option<char> = None;
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some('"'), '\\') => word.extend(chars.next()),
+            (Some(_), c) => word.push(c),
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                in_word = true;
+            }
+            (None, '\\') => {
+                word.extend(chars.next());
+                in_word = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            (None, c) => {
+                word.push(c);
+                in_word = true;
+            }
+        }
+    }
+    if quote.is_some() {
+        return Err("Unterminated quote".to_string());
+    }
+    if in_word {
+        words.push(word);
+    }
+    Ok(words)
+}
+
+/// Shell features whose effect can't be checked statically
+fn unsupported_syntax(text: &str) -> Option<&'static str> {
+    let mut quote: Option<char> = None;
+    for c in text.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some('\''), _) => {}
+            (_, '$') => return Some("Variables and command substitution ($) can't be checked; write the values out"),
+            (_, '`') => return Some("Command substitution (`) can't be checked; write the values out"),
+            (Some(_), _) => {}
+            (None, '\'' | '"') => quote = Some(c),
+            (None, '|') => return Some("Pipes aren't supported; run the commands separately"),
+            (None, '>' | '<') => return Some("Redirection isn't supported"),
+            (None, '&') => return Some("Background commands (&) aren't supported"),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Characters a shell (PowerShell, or cmd for batch shims like npm.cmd) would act on. Refused in
+/// any word, quoted or not, so no argument can mean more than the policy saw.
+const SHELL_METACHARACTERS: &[char] = &[';', '&', '|', '<', '>', '$', '`', '(', ')', '{', '}', '%', '^'];
+
+/// A word of a command that carries shell syntax
+fn metacharacter_in(words: &[String]) -> Option<String> {
+    words.iter().find_map(|word| {
+        word.chars()
+            .find(|c| SHELL_METACHARACTERS.contains(c) || c.is_control())
+            .map(|c| format!("'{}' contains '{}', which isn't allowed in arguments", word, c.escape_default()))
+    })
+}
+
+/// Split a line at `&&`, `||` and `;` outside quotes
+fn split_line(line: &str) -> Vec<(StepCondition, String)> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut condition = StepCondition::Always;
+    let mut quote: Option<char> = None;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        let next = match (quote, c) {
+            (Some(q), c) if c == q => {
+                quote = None;
+                None
+            }
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                None
+            }
+            (None, ';') => Some(StepCondition::Always),
+            (None, '&') if chars.peek() == Some(&'&') => Some(StepCondition::OnSuccess),
+            (None, '|') if chars.peek() == Some(&'|') => Some(StepCondition::OnFailure),
+            _ => None,
+        };
+        match next {
+            Some(next) => {
+                if next != StepCondition::Always {
+                    chars.next();
+                }
+                parts.push((condition, std::mem::take(&mut current)));
+                condition = next;
+            }
+            None => current.push(c),
+        }
+    }
+    parts.push((condition, current));
+    parts
+}
+
+fn analyze(app: &AppHandle, script: &str) -> Result<Vec<ScriptStep>, String> {
+    if script.len() > MAX_SCRIPT_BYTES {
+        return Err(format!("Scripts are limited to {} KB", MAX_SCRIPT_BYTES / 1024));
+    }
+    // Join `\` continuations, remembering the line each logical line starts on
+    let mut lines: Vec<(usize, String)> = Vec::new();
+    let mut continued = false;
+    for (index, raw) in script.lines().enumerate() {
+        let (text, continues) = match raw.trim_end().strip_suffix('\\') {
+            Some(text) => (text, true),
+            None => (raw, false),
+        };
+        match lines.last_mut() {
+            Some((_, line)) if continued => {
+                line.push(' ');
+                line.push_str(text);
+            }
+            _ => lines.push((index + 1, text.to_string())),
+        }
+        continued = continues;
+    }
+
+    let mut steps = Vec::new();
+    for (line_number, line) in lines {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') || trimmed.starts_with("REM ") {
+            continue;
+        }
+        for (condition, text) in split_line(trimmed) {
+            let text = text.trim().to_string();
+            // A trailing `;` is harmless, a dangling `&&` or `||` is not
+            if text.is_empty() && condition == StepCondition::Always {
+                continue;
+            }
+            if text.is_empty() {
+                steps.push(ScriptStep {
+                    line: line_number,
+                    text,
+                    program: None,
+                    args: Vec::new(),
+                    condition,
+                    allowed: false,
+                    reason: Some("Empty command next to an operator".to_string()),
+                });
+                continue;
+            }
+            let words = match unsupported_syntax(&text) {
+                Some(reason) => Err(reason.to_string()),
+                None => split_words(&text).and_then(|words| match metacharacter_in(&words) {
+                    Some(reason) => Err(reason),
+                    None => Ok(words),
+                }),
+            };
+            let words = match words {
+                Ok(words) => words,
+                Err(reason) => {
+                    steps.push(ScriptStep {
+                        line: line_number,
+                        text,
+                        program: None,
+                        args: Vec::new(),
+                        condition,
+                        allowed: false,
+                        reason: Some(reason),
+                    });
+                    continue;
+                }
+            };
+            let (program, args) = (words[0].clone(), words[1..].to_vec());
+            let verdict = commands::check_command_permitted(app, &program, &args);
+            steps.push(ScriptStep {
+                line: line_number,
+                text,
+                program: Some(program),
+                args,
+                condition,
+                allowed: verdict.is_ok(),
+                reason: verdict.err(),
+            });
+        }
+    }
+    if steps.is_empty() {
+        return Err("The script has no commands".to_string());
+    }
+    if steps.len() > MAX_STEPS {
+        return Err(format!("Scripts are limited to {} commands", MAX_STEPS));
+    }
+    Ok(steps)
+}
+
+/// Run the argv the plan analyzed, passed straight to the process rather than through a shell
+async fn run_step(app: &AppHandle, program: &str, args: &[String]) -> CommandResult {
+    let command = commands::build_process_command(program, args);
+    let failed = |error: String| CommandResult {
+        success: false,
+        stdout: String::new(),
+        stderr: String::new(),
+        exit_code: None,
+        error: Some(error),
+    };
+    match tokio::time::timeout(STEP_TIMEOUT, crate::sandbox::run(app, command)).await {
+        Err(_) => failed(format!("Timed out after {} seconds", STEP_TIMEOUT.as_secs())),
+        Ok(Err(e)) => failed(e),
+        Ok(Ok(output)) => {
+            let exit_code = output.status.code();
+            let success = output.status.success();
+            CommandResult {
+                success,
+                stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+                stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+                exit_code,
+                error: if success { None } else { Some(format!("Command failed with exit code: {:?}", exit_code)) },
+            }
+        }
+    }
+}
+
+/// Analyze a multi-line script without running it: every command with the policy's verdict.
+/// A fully permitted script comes back with a token for `confirm_execution`.
+#[tauri::command]
+pub async fn execute_script(
+    app: AppHandle,
+    state: State<'_, ScriptRunnerState>,
+    script: String,
+) -> Result<ScriptPlan, String> {
+    let steps = analyze(&app, &script)?;
+    let runnable = steps.iter().all(|step| step.allowed);
+    let (token, expires_at) = if runnable {
+        let now = chrono::Utc::now().timestamp();
+        let token = uuid::Uuid::new_v4().simple().to_string();
+        let expires_at = now + TOKEN_TTL_SECONDS;
+        let mut pending = state.pending.lock().unwrap();
+        pending.retain(|_, script| script.expires_at > now);
+        pending.insert(token.clone(), PendingScript { steps: steps.clone(), expires_at });
+        (Some(token), Some(expires_at))
+    } else {
+        (None, None)
+    };
+    Ok(ScriptPlan { steps, runnable, token, expires_at })
+}
+
+/// Run the script `token` was issued for by `execute_script`. Tokens work once; each command
+/// is checked against the policy again right before it runs.
+#[tauri::command]
+pub async fn confirm_execution(
+    app: AppHandle,
+    state: State<'_, ScriptRunnerState>,
+    token: String,
+) -> Result<ScriptRun, String> {
+    let PendingScript { steps, expires_at } =
+        state.pending.lock().unwrap().remove(&token).ok_or("Unknown or already used script token")?;
+    if expires_at < chrono::Utc::now().timestamp() {
+        return Err("The script confirmation expired; run execute_script again".to_string());
+    }
+
+    let mut outcomes = Vec::new();
+    let mut last_success = true;
+    for step in steps {
+        let should_run = match step.condition {
+            StepCondition::Always => true,
+            StepCondition::OnSuccess => last_success,
+            StepCondition::OnFailure => !last_success,
+        };
+        let (Some(program), true) = (step.program.as_deref(), should_run) else {
+            outcomes.push(StepOutcome { text: step.text, ran: false, result: None });
+            continue;
+        };
+        let result = match commands::check_command_permitted(&app, program, &step.args) {
+            Ok(()) => run_step(&app, program, &step.args).await,
+            Err(reason) => CommandResult {
+                success: false,
+                stdout: String::new(),
+                stderr: String::new(),
+                exit_code: None,
+                error: Some(reason),
+            },
+        };
+        tracing::info!("📜 Script step '{}' exited with {:?}", step.text, result.exit_code);
+        last_success = result.success;
+        outcomes.push(StepOutcome { text: step.text, ran: true, result: Some(result) });
+    }
+    Ok(ScriptRun { steps: outcomes, success: last_success })
+}
@@ -0,0 +1,147 @@
+//! Masks secrets in text bound for an AI provider: OCR of a terminal showing an API key, a
+//! window title with a token in its URL, a `.env` file on screen. Known key formats, password
+//! assignments and credentials in URLs are found by pattern, and anything else that looks like
+//! a random key by its character mix and entropy. Each secret becomes `[REDACTED:kind]` and the
+//! report says what was masked and where, never the secret itself.
+
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+const MAX_TEXT_BYTES: usize = 2 * 1024 * 1024;
+/// Shortest run of key characters judged by entropy
+const MIN_ENTROPY_LENGTH: usize = 24;
+/// Bits per character above which a mixed-case, digit-bearing run is treated as a key
+const ENTROPY_THRESHOLD: f64 = 4.0;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct Redaction {
+    /// What was found, e.g. "aws_access_key", "password" or "high_entropy"
+    pub kind: String,
+    /// 1-based line of the text it was on
+    pub line: usize,
+    /// Characters masked
+    pub length: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FilteredText {
+    pub text: String,
+    pub redactions: Vec<Redaction>,
+}
+
+/// Detectors in priority order; a `secret` group limits the mask to that part of the match
+fn detectors() -> &'static [(&'static str, Regex)] {
+    static DETECTORS: OnceLock<Vec<(&'static str, Regex)>> = OnceLock::new();
+    DETECTORS.get_or_init(|| {
+        [
+            ("private_key", r"-----BEGIN [A-Z ]*PRIVATE KEY-----[\s\S]*?(?:-----END [A-Z ]*PRIVATE KEY-----|\z)"),
+            ("aws_access_key", r"\b(?:AKIA|ASIA)[0-9A-Z]{16}\b"),
+            ("github_token", r"\b(?:gh[pousr]_[A-Za-z0-9]{36,}|github_pat_[A-Za-z0-9_]{22,})\b"),
+            ("anthropic_key", r"\bsk-ant-[A-Za-z0-9_-]{20,}"),
+            ("openai_key", r"\bsk-(?:proj-)?[A-Za-z0-9_-]{20,}"),
+            ("google_api_key", r"\bAIza[0-9A-Za-z_-]{35}\b"),
+            ("slack_token", r"\bxox[abposr]-[A-Za-z0-9-]{10,}"),
+            ("stripe_key", r"\b[sr]k_(?:live|test)_[A-Za-z0-9]{16,}"),
+            ("jwt", r"\beyJ[A-Za-z0-9_-]{8,}\.eyJ[A-Za-z0-9_-]{8,}\.[A-Za-z0-9_-]{8,}"),
+            ("url_credentials", r"[a-zA-Z][a-zA-Z0-9+.-]*://[^\s:/@]+:(?P<secret>[^\s@/]+)@"),
+            ("bearer_token", r"(?i)\bbearer\s+(?P<secret>[A-Za-z0-9._~+/=-]{16,})"),
+            (
+                "password",
+                r#"(?i)\b[\w.-]*(?:password|passwd|pwd|secret|token|api[_-]?key|access[_-]?key|private[_-]?key)["']?\s*[:=]\s*["']?(?P<secret>[^\s"',;]{4,})"#,
+            ),
+        ]
+        .into_iter()
+        .map(|(kind, pattern)| (kind, Regex::new(pattern).unwrap()))
+        .collect()
+    })
+}
+
+fn key_runs() -> &'static Regex {
+    static RUNS: OnceLock<Regex> = OnceLock::new();
+    RUNS.get_or_init(|| Regex::new(r"[A-Za-z0-9+/_=-]+").unwrap())
+}
+
+fn shannon_entropy(text: &str) -> f64 {
+    let mut counts = [0u32; 256];
+    for byte in text.bytes() {
+        counts[byte as usize] += 1;
+    }
+    let length = text.len() as f64;
+    counts
+        .iter()
+        .filter(|&&c| c > 0)
+        .map(|&c| {
+            let p = c as f64 / length;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// Random-looking keys: long, mixing upper and lower case with digits, and high in entropy.
+/// Words, paths and lowercase hex such as git hashes don't qualify.
+fn looks_random(run: &str) -> bool {
+    run.len() >= MIN_ENTROPY_LENGTH
+        && run.chars().any(|c| c.is_ascii_uppercase())
+        && run.chars().any(|c| c.is_ascii_lowercase())
+        && run.chars().any(|c| c.is_ascii_digit())
+        && shannon_entropy(run) >= ENTROPY_THRESHOLD
+}
+
+/// Byte ranges to mask with their kind, in text order and without overlaps
+fn find_secrets(text: &str) -> Vec<(usize, usize, &'static str)> {
+    let mut found: Vec<(usize, usize, &'static str)> = Vec::new();
+    let overlaps = |found: &[(usize, usize, &str)], start: usize, end: usize| {
+        found.iter().any(|&(s, e, _)| start < e && s < end)
+    };
+    for (kind, detector) in detectors() {
+        for captures in detector.captures_iter(text) {
+            let Some(range) = captures.name("secret").or_else(|| captures.get(0)) else {
+                continue;
+            };
+            if !overlaps(&found, range.start(), range.end()) {
+                found.push((range.start(), range.end(), kind));
+            }
+        }
+    }
+    for run in key_runs().find_iter(text) {
+        if looks_random(run.as_str()) && !overlaps(&found, run.start(), run.end()) {
+            found.push((run.start(), run.end(), "high_entropy"));
+        }
+    }
+    found.sort_by_key(|&(start, _, _)| start);
+    found
+}
+
+/// `text` with every detected secret masked, and what was masked
+pub(crate) fn filter(text: &str) -> FilteredText {
+    let mut output = String::with_capacity(text.len());
+    let mut redactions = Vec::new();
+    let mut last = 0;
+    for (start, end, kind) in find_secrets(text) {
+        output.push_str(&text[last..start]);
+        output.push_str(&format!("[REDACTED:{}]", kind));
+        redactions.push(Redaction {
+            kind: kind.to_string(),
+            line: text[..start].matches('\n').count() + 1,
+            length: text[start..end].chars().count(),
+        });
+        last = end;
+    }
+    output.push_str(&text[last..]);
+    FilteredText { text: output, redactions }
+}
+
+/// Mask API keys, passwords and other secrets in text before it is sent to an AI provider
+#[tauri::command]
+pub async fn filter_secrets(text: String) -> Result<FilteredText, String> {
+    if text.len() > MAX_TEXT_BYTES {
+        return Err(format!("Text is limited to {} MB", MAX_TEXT_BYTES / 1024 / 1024));
+    }
+    let filtered = filter(&text);
+    if !filtered.redactions.is_empty() {
+        tracing::info!("🔒 Masked {} secret(s)", filtered.redactions.len());
+    }
+    Ok(filtered)
+}
@@ -0,0 +1,32 @@
+//! What happens when the app exits, whether the last window closed or a restart was
+//! requested (a profile switch, a backup restore, an update). Background loops are stopped
+//! first so nothing writes to the history database while it is flushed, then every child
+//! process this app started is killed (PowerShell on Windows outlives its parent otherwise),
+//! and the temp files go last.
+
+use tauri::{AppHandle, Manager};
+
+use crate::history::HistoryState;
+use crate::powershell_host::PowerShellHostState;
+
+/// Stop everything the app started. Runs on `RunEvent::Exit`.
+pub fn run(app: &AppHandle) {
+    tracing::info!("👋 Shutting down");
+
+    let monitors = crate::monitoring::stop_all(app);
+    if monitors > 0 {
+        tracing::info!("👁️ Stopped {} monitor(s) on exit", monitors);
+    }
+    crate::narrator::stop(app);
+    crate::activity::stop(app);
+    crate::typing_practice::stop(app);
+    crate::screen_recording::stop(app);
+
+    crate::process_manager::kill_all(app);
+    crate::terminal::close_all(app);
+    crate::native_helper::stop(app);
+    app.state::<PowerShellHostState>().shutdown();
+
+    app.state::<HistoryState>().flush_and_close();
+    crate::temp_files::cleanup(app);
+}
@@ -0,0 +1,369 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+
+use crate::activity::ActivityState;
+use crate::content_class::{ContentClassState, ContentTime};
+use crate::distraction::DistractionState;
+use crate::focus_timer::FocusTimerState;
+use crate::persistence;
+
+const SETTINGS_FILE: &str = "sync_settings.json";
+const OUTBOX_FILE: &str = "sync_outbox.json";
+/// Sync status, so the next run's usage period starts at the last successful push
+const STATUS_FILE: &str = "sync_status.json";
+
+/// Keychain entry holding the dashboard auth token
+const KEYRING_SERVICE: &str = "ai-teacher";
+const KEYRING_USER: &str = "dashboard-sync-token";
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(20);
+const MAX_ATTEMPTS: u32 = 3;
+const RETRY_BASE_DELAY: Duration = Duration::from_secs(60);
+/// Session reports beyond this are dropped (oldest first) while the server is unreachable
+const MAX_OUTBOX: usize = 500;
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SyncSettings {
+    pub enabled: bool,
+    /// Base URL of the dashboard server; reports are POSTed to `{endpoint}/reports`
+    pub endpoint: Option<String>,
+    pub interval_minutes: u32,
+    /// Random identifier sent instead of any user or machine name
+    pub device_id: String,
+}
+
+impl Default for SyncSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: None,
+            interval_minutes: 60,
+            device_id: uuid::Uuid::new_v4().to_string(),
+        }
+    }
+}
+
+/// Usage totals for the period since the last successful sync. Deliberately carries no
+/// window titles, file names or typed text.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct UsageSummary {
+    pub period_start: i64,
+    pub period_end: i64,
+    pub total_keystrokes: u64,
+    pub total_clicks: u64,
+    pub active_minutes: usize,
+    pub focus_cycles_completed: u32,
+    pub longest_study_secs: i64,
+    /// Seconds spent on each kind of content (code, video, ...), most first
+    #[serde(default)]
+    pub content_time: Vec<ContentTime>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SessionReport {
+    pub id: String,
+    pub created_at: i64,
+    /// Report body produced by the frontend (topics covered, exercises done, ...)
+    pub report: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SyncPayload {
+    pub device_id: String,
+    pub generated_at: i64,
+    pub usage: UsageSummary,
+    pub session_reports: Vec<SessionReport>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct SyncStatus {
+    pub last_attempt_at: Option<i64>,
+    pub last_success_at: Option<i64>,
+    pub last_error: Option<String>,
+    pub consecutive_failures: u32,
+    pub pending_reports: usize,
+    pub has_token: bool,
+}
+
+#[derive(Clone, Default)]
+pub struct SyncState {
+    settings: Arc<Mutex<SyncSettings>>,
+    outbox: Arc<Mutex<Vec<SessionReport>>>,
+    status: Arc<Mutex<SyncStatus>>,
+    /// Held for the duration of a push so the background loop and `sync_now` don't overlap
+    in_flight: Arc<tokio::sync::Mutex<()>>,
+}
+
+fn keyring_entry() -> Result<keyring::Entry, String> {
+    keyring::Entry::new(KEYRING_SERVICE, KEYRING_USER).map_err(|e| format!("Failed to open keychain: {}", e))
+}
+
+fn read_token() -> Result<Option<String>, String> {
+    match keyring_entry()?.get_password() {
+        Ok(token) => Ok(Some(token)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(format!("Failed to read sync token from keychain: {}", e)),
+    }
+}
+
+fn persist_status(app: &AppHandle, status: &SyncStatus) -> Result<(), String> {
+    let path = persistence::data_file(app, STATUS_FILE)?;
+    persistence::save_json(&path, status)
+}
+
+fn persist_outbox(app: &AppHandle, outbox: &[SessionReport]) -> Result<(), String> {
+    let path = persistence::data_file(app, OUTBOX_FILE)?;
+    persistence::save_json(&path, &outbox)
+}
+
+/// Load settings, status and queued reports, then push on the configured interval while sync is enabled
+pub fn start(app: AppHandle) {
+    let state = app.state::<SyncState>().inner().clone();
+    match persistence::data_file(&app, SETTINGS_FILE) {
+        Ok(path) => {
+            let settings: SyncSettings = persistence::load_json(&path);
+            // Save once so the generated device id stays stable across runs
+            if let Err(e) = persistence::save_json(&path, &settings) {
+                tracing::warn!("⚠️ {}", e);
+            }
+            *state.settings.lock().unwrap() = settings;
+        }
+        Err(e) => tracing::warn!("⚠️ {}", e),
+    }
+    if let Ok(path) = persistence::data_file(&app, OUTBOX_FILE) {
+        *state.outbox.lock().unwrap() = persistence::load_json(&path);
+    }
+    if let Ok(path) = persistence::data_file(&app, STATUS_FILE) {
+        *state.status.lock().unwrap() = persistence::load_json(&path);
+    }
+
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let interval_minutes = state.settings.lock().unwrap().interval_minutes.max(1);
+            let interval = Duration::from_secs(interval_minutes as u64 * 60);
+            let failures = state.status.lock().unwrap().consecutive_failures;
+            // After a failure retry sooner, backing off exponentially up to the normal interval
+            let wait = match failures {
+                0 => interval,
+                n => (RETRY_BASE_DELAY * 2u32.pow(n.min(10) - 1)).min(interval),
+            };
+            tokio::time::sleep(wait).await;
+
+            // Read after the wait, so sync switched off meanwhile isn't pushed once more
+            let enabled = {
+                let settings = state.settings.lock().unwrap();
+                settings.enabled && settings.endpoint.is_some()
+            };
+            if enabled {
+                if let Err(e) = push(&app, &state).await {
+                    tracing::warn!("⚠️ Scheduled sync failed: {}", e);
+                }
+            }
+        }
+    });
+}
+
+fn build_payload(app: &AppHandle, state: &SyncState, period_start: i64, now: i64) -> SyncPayload {
+    let activity = crate::activity::metrics(&app.state::<ActivityState>(), period_start, now);
+    let focus_cycles_completed = app
+        .state::<FocusTimerState>()
+        .session
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|s| s.completed_cycles)
+        .unwrap_or(0);
+
+    SyncPayload {
+        device_id: state.settings.lock().unwrap().device_id.clone(),
+        generated_at: now,
+        usage: UsageSummary {
+            period_start,
+            period_end: now,
+            total_keystrokes: activity.total_keystrokes,
+            total_clicks: activity.total_clicks,
+            active_minutes: activity.active_minutes,
+            focus_cycles_completed,
+            longest_study_secs: app.state::<DistractionState>().longest_study_secs(),
+            content_time: app.state::<ContentClassState>().totals(period_start, now),
+        },
+        session_reports: state.outbox.lock().unwrap().clone(),
+    }
+}
+
+async fn send(endpoint: &str, token: Option<&str>, payload: &SyncPayload) -> Result<(), String> {
+    let url = format!("{}/reports", endpoint.trim_end_matches('/'));
+    let client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let mut request = client.post(&url).json(payload);
+    if let Some(token) = token {
+        request = request.bearer_auth(token);
+    }
+    request
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map(|_| ())
+        .map_err(|e| format!("Failed to push to {}: {}", url, e))
+}
+
+/// Push the usage summary and queued session reports, retrying with backoff
+async fn push(app: &AppHandle, state: &SyncState) -> Result<SyncStatus, String> {
+    let _guard = state.in_flight.lock().await;
+
+    let endpoint = state
+        .settings
+        .lock()
+        .unwrap()
+        .endpoint
+        .clone()
+        .ok_or("No sync endpoint configured")?;
+    let token = read_token()?;
+
+    let now = chrono::Utc::now().timestamp();
+    let period_start = state.status.lock().unwrap().last_success_at.unwrap_or(now - 24 * 60 * 60);
+    let payload = build_payload(app, state, period_start, now);
+    let sent_reports: Vec<String> = payload.session_reports.iter().map(|r| r.id.clone()).collect();
+
+    let mut result = Err(String::new());
+    for attempt in 1..=MAX_ATTEMPTS {
+        result = send(&endpoint, token.as_deref(), &payload).await;
+        if result.is_ok() || attempt == MAX_ATTEMPTS {
+            break;
+        }
+        tokio::time::sleep(Duration::from_secs(2u64.pow(attempt))).await;
+    }
+
+    let mut status = state.status.lock().unwrap();
+    status.last_attempt_at = Some(now);
+    match &result {
+        Ok(()) => {
+            tracing::info!("☁️ Pushed usage summary and {} session report(s)", sent_reports.len());
+            status.last_success_at = Some(now);
+            status.last_error = None;
+            status.consecutive_failures = 0;
+            let mut outbox = state.outbox.lock().unwrap();
+            outbox.retain(|r| !sent_reports.contains(&r.id));
+            if let Err(e) = persist_outbox(app, &outbox) {
+                tracing::warn!("⚠️ Failed to persist outbox: {}", e);
+            }
+        }
+        Err(e) => {
+            status.last_error = Some(e.clone());
+            status.consecutive_failures += 1;
+        }
+    }
+    status.pending_reports = state.outbox.lock().unwrap().len();
+    status.has_token = token.is_some();
+    let snapshot = status.clone();
+    drop(status);
+    if let Err(e) = persist_status(app, &snapshot) {
+        tracing::warn!("⚠️ Failed to persist sync status: {}", e);
+    }
+
+    result.map(|_| snapshot)
+}
+
+#[tauri::command]
+pub async fn get_sync_settings(
+    state: State<'_, SyncState>,
+) -> Result<SyncSettings, String> {
+    Ok(state.settings.lock().unwrap().clone())
+}
+
+#[tauri::command]
+pub async fn set_sync_settings(
+    app: AppHandle,
+    state: State<'_, SyncState>,
+    enabled: bool,
+    endpoint: Option<String>,
+    interval_minutes: u32,
+) -> Result<SyncSettings, String> {
+    if let Some(endpoint) = &endpoint {
+        let url = url::Url::parse(endpoint).map_err(|e| format!("Invalid sync endpoint: {}", e))?;
+        // Plain http only to a server on this machine
+        let local = match url.host() {
+            Some(url::Host::Domain(host)) => host == "localhost",
+            Some(url::Host::Ipv4(ip)) => ip.is_loopback(),
+            Some(url::Host::Ipv6(ip)) => ip.is_loopback(),
+            None => false,
+        };
+        if url.scheme() != "https" && !local {
+            return Err("The sync endpoint must use https".to_string());
+        }
+    }
+    if enabled && endpoint.is_none() {
+        return Err("Set a sync endpoint before enabling sync".to_string());
+    }
+
+    let mut settings = state.settings.lock().unwrap();
+    settings.enabled = enabled;
+    settings.endpoint = endpoint;
+    settings.interval_minutes = interval_minutes.clamp(5, 24 * 60);
+    let path = persistence::data_file(&app, SETTINGS_FILE)?;
+    persistence::save_json(&path, &*settings)?;
+    Ok(settings.clone())
+}
+
+/// Store the dashboard auth token in the OS keychain (pass `null` to remove it)
+#[tauri::command]
+pub async fn set_sync_token(
+    token: Option<String>,
+) -> Result<(), String> {
+    let entry = keyring_entry()?;
+    match token.filter(|t| !t.trim().is_empty()) {
+        Some(token) => entry
+            .set_password(token.trim())
+            .map_err(|e| format!("Failed to save sync token: {}", e)),
+        None => match entry.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(format!("Failed to remove sync token: {}", e)),
+        },
+    }
+}
+
+/// Queue a session report for the next sync
+#[tauri::command]
+pub async fn queue_session_report(
+    app: AppHandle,
+    state: State<'_, SyncState>,
+    report: serde_json::Value,
+) -> Result<String, String> {
+    let id = uuid::Uuid::new_v4().to_string();
+    let mut outbox = state.outbox.lock().unwrap();
+    outbox.push(SessionReport {
+        id: id.clone(),
+        created_at: chrono::Utc::now().timestamp(),
+        report,
+    });
+    if outbox.len() > MAX_OUTBOX {
+        let excess = outbox.len() - MAX_OUTBOX;
+        outbox.drain(..excess);
+    }
+    persist_outbox(&app, &outbox)?;
+    state.status.lock().unwrap().pending_reports = outbox.len();
+    Ok(id)
+}
+
+#[tauri::command]
+pub async fn sync_now(
+    app: AppHandle,
+    state: State<'_, SyncState>,
+) -> Result<SyncStatus, String> {
+    push(&app, state.inner()).await
+}
+
+#[tauri::command]
+pub async fn get_sync_status(
+    state: State<'_, SyncState>,
+) -> Result<SyncStatus, String> {
+    let mut status = state.status.lock().unwrap().clone();
+    status.pending_reports = state.outbox.lock().unwrap().len();
+    status.has_token = read_token().map(|t| t.is_some()).unwrap_or(false);
+    Ok(status)
+}
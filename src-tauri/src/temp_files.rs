@@ -0,0 +1,99 @@
+//! Scratch files the OCR pipeline hands to PowerShell, audio converted for transcription, and
+//! capture frames spilled to disk over the memory cap.
+//! Each file is deleted when its `TempFile` guard drops; anything left behind by a crash is
+//! swept on the next start.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Manager};
+
+/// Name prefixes of files this app creates in the system temp dir
+const OWNED_PREFIXES: &[&str] = &["ocr_temp_", "ocr_script_", "transcribe_", "capture_spill_"];
+
+/// Owned files older than this are removed by the startup sweep
+const STALE_AFTER: Duration = Duration::from_secs(30 * 60);
+
+/// Set to keep temp files around for debugging
+const KEEP_ENV: &str = "AI_TEACHER_KEEP_TEMP_FILES";
+
+#[derive(Clone, Default)]
+pub struct TempFilesState {
+    live: Arc<Mutex<HashSet<PathBuf>>>,
+}
+
+/// A registered temp file, deleted on drop
+pub struct TempFile {
+    path: PathBuf,
+    state: TempFilesState,
+}
+
+impl TempFile {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        self.state.live.lock().unwrap().remove(&self.path);
+        if std::env::var_os(KEEP_ENV).is_none() {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+impl TempFilesState {
+    /// Reserve a uniquely named file in the temp dir. `prefix` must be one of `OWNED_PREFIXES`
+    /// so the startup sweep can find it after a crash.
+    pub fn create(&self, prefix: &str, extension: &str) -> TempFile {
+        debug_assert!(OWNED_PREFIXES.contains(&prefix));
+        let path = std::env::temp_dir().join(format!("{}{}.{}", prefix, uuid::Uuid::new_v4().simple(), extension));
+        self.live.lock().unwrap().insert(path.clone());
+        TempFile {
+            path,
+            state: self.clone(),
+        }
+    }
+}
+
+fn is_stale(path: &Path, now: SystemTime) -> bool {
+    std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|modified| now.duration_since(modified).ok())
+        .map(|age| age >= STALE_AFTER)
+        .unwrap_or(false)
+}
+
+fn sweep_stale() {
+    let Ok(entries) = std::fs::read_dir(std::env::temp_dir()) else { return };
+    let now = SystemTime::now();
+    let mut removed = 0;
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        let owned = OWNED_PREFIXES.iter().any(|p| name.to_string_lossy().starts_with(p));
+        if owned && is_stale(&entry.path(), now) && std::fs::remove_file(entry.path()).is_ok() {
+            removed += 1;
+        }
+    }
+    if removed > 0 {
+        tracing::info!("🧹 Removed {} stale temp file(s)", removed);
+    }
+}
+
+/// Remove temp files orphaned by earlier runs
+pub fn init() {
+    tauri::async_runtime::spawn_blocking(sweep_stale);
+}
+
+/// Delete files whose operations are still running when the app exits
+pub fn cleanup(app: &AppHandle) {
+    if std::env::var_os(KEEP_ENV).is_some() {
+        return;
+    }
+    for path in app.state::<TempFilesState>().live.lock().unwrap().drain() {
+        let _ = std::fs::remove_file(path);
+    }
+}
@@ -0,0 +1,405 @@
+use portable_pty::{native_pty_system, Child, ChildKiller, CommandBuilder, MasterPty, PtySize};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use tauri::{AppHandle, Manager, State};
+
+use crate::commands::check_command_permitted;
+
+const MAX_TERMINALS: usize = 4;
+const DEFAULT_COLS: u16 = 100;
+const DEFAULT_ROWS: u16 = 30;
+
+/// Characters that would let one line run more than the command we validated, in bash or
+/// PowerShell: chaining, pipes, redirection, subexpressions, script blocks, arrays and splatting,
+/// variables, escapes and bash history expansion
+const SHELL_METACHARACTERS: &[char] = &[';', '&', '|', '<', '>', '(', ')', '{', '}', '@', '$', '`', '!'];
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TerminalInfo {
+    pub id: String,
+    pub shell: String,
+    pub cols: u16,
+    pub rows: u16,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct CreateTerminalParams {
+    #[serde(default)]
+    pub cols: Option<u16>,
+    #[serde(default)]
+    pub rows: Option<u16>,
+    #[serde(default)]
+    pub cwd: Option<String>,
+}
+
+/// Payload of the `terminal-output` event
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct TerminalOutput {
+    pub terminal_id: String,
+    pub data: String,
+}
+
+/// Payload of the `terminal-exited` event
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct TerminalExit {
+    pub terminal_id: String,
+    pub exit_code: Option<u32>,
+}
+
+/// Payload of the `terminal-command-blocked` event
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct TerminalCommandBlocked {
+    pub terminal_id: String,
+    pub command_line: String,
+    pub reason: String,
+}
+
+/// Tracks what the user is typing on the current line so it can be checked
+/// against the command policy before Enter is forwarded to the shell.
+#[derive(Default)]
+struct LineBuffer {
+    text: String,
+    /// Set once history recall, tab completion or cursor movement made the
+    /// buffer diverge from what the shell will actually run
+    unverifiable: bool,
+}
+
+struct TerminalSession {
+    info: TerminalInfo,
+    master: Box<dyn MasterPty + Send>,
+    writer: Box<dyn Write + Send>,
+    killer: Box<dyn ChildKiller + Send + Sync>,
+    line: LineBuffer,
+}
+
+#[derive(Clone, Default)]
+pub struct TerminalState {
+    sessions: Arc<Mutex<HashMap<String, TerminalSession>>>,
+}
+
+fn default_shell() -> String {
+    if cfg!(target_os = "windows") {
+        "powershell.exe".to_string()
+    } else {
+        std::env::var("SHELL").unwrap_or_else(|_| "bash".to_string())
+    }
+}
+
+/// Parse a command line into argv the way the policy sees it. Outside quotes, any character
+/// the shell gives meaning to (chaining, pipes, redirection, subexpressions, script blocks,
+/// splatting, variables) is refused; so are `$`, `!` and the backtick inside double quotes, which
+/// bash or PowerShell still expand there. Only single quotes are a literal.
+fn parse_command_line(line: &str) -> Result<Vec<String>, String> {
+    let refused = |c: char| Err(format!("'{}' is not permitted in the tutor terminal; quote it with single quotes if it's meant literally.", c));
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut in_word = false;
+    let mut quote: Option<char> = None;
+    for c in line.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some('"'), '$' | '`' | '!') => return refused(c),
+            (Some(_), c) => current.push(c),
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                in_word = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut current));
+                    in_word = false;
+                }
+            }
+            (None, c) if SHELL_METACHARACTERS.contains(&c) || c.is_control() => return refused(c),
+            (None, c) => {
+                current.push(c);
+                in_word = true;
+            }
+        }
+    }
+    if quote.is_some() {
+        return Err("Unterminated quote".to_string());
+    }
+    if in_word {
+        words.push(current);
+    }
+    Ok(words)
+}
+
+/// Validate a full line typed into the terminal against the command policy
+fn validate_line(app: &AppHandle, line: &LineBuffer) -> Result<(), String> {
+    if line.unverifiable {
+        return Err(
+            "This line was edited with history or tab completion and can't be verified. Type the command out in full."
+                .to_string(),
+        );
+    }
+    let text = line.text.trim();
+    if text.is_empty() {
+        return Ok(());
+    }
+    let words = parse_command_line(text)?;
+    let Some((command, args)) = words.split_first() else {
+        return Ok(());
+    };
+    check_command_permitted(app, command, args)
+}
+
+/// Decode as much valid UTF-8 as possible, keeping an incomplete trailing sequence for the next read
+fn decode_utf8(pending: &mut Vec<u8>) -> String {
+    match std::str::from_utf8(pending) {
+        Ok(text) => {
+            let text = text.to_string();
+            pending.clear();
+            text
+        }
+        Err(e) if e.error_len().is_none() => {
+            let valid = e.valid_up_to();
+            let text = String::from_utf8_lossy(&pending[..valid]).to_string();
+            pending.drain(..valid);
+            text
+        }
+        Err(_) => {
+            let text = String::from_utf8_lossy(pending).to_string();
+            pending.clear();
+            text
+        }
+    }
+}
+
+fn stream_output(app: AppHandle, terminal_id: String, mut reader: Box<dyn Read + Send>) {
+    thread::spawn(move || {
+        let mut buffer = [0u8; 4096];
+        let mut pending = Vec::new();
+        loop {
+            match reader.read(&mut buffer) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => {
+                    pending.extend_from_slice(&buffer[..n]);
+                    let data = decode_utf8(&mut pending);
+                    if !data.is_empty() {
+                        let _ = crate::event_bus::emit(
+                            &app,
+                            crate::events::TERMINAL_OUTPUT,
+                            TerminalOutput {
+                                terminal_id: terminal_id.clone(),
+                                data,
+                            },
+                        );
+                    }
+                }
+            }
+        }
+    });
+}
+
+fn watch_exit(app: AppHandle, terminal_id: String, mut child: Box<dyn Child + Send + Sync>) {
+    thread::spawn(move || {
+        let exit_code = child.wait().ok().map(|status| status.exit_code());
+        app.state::<TerminalState>().sessions.lock().unwrap().remove(&terminal_id);
+        tracing::info!("Terminal {} exited ({:?})", terminal_id, exit_code);
+        let _ = crate::event_bus::emit(&app, crate::events::TERMINAL_EXITED, TerminalExit { terminal_id, exit_code });
+    });
+}
+
+/// Kill every terminal's shell. Called when the app exits.
+pub fn close_all(app: &AppHandle) {
+    let state = app.state::<TerminalState>();
+    let mut sessions = state.sessions.lock().unwrap();
+    for session in sessions.values_mut() {
+        let _ = session.killer.kill();
+    }
+}
+
+#[tauri::command]
+pub async fn create_terminal(
+    app: AppHandle,
+    state: State<'_, TerminalState>,
+    options: CreateTerminalParams,
+) -> Result<TerminalInfo, String> {
+    if state.sessions.lock().unwrap().len() >= MAX_TERMINALS {
+        return Err(format!("Too many open terminals (max {}).", MAX_TERMINALS));
+    }
+
+    let size = PtySize {
+        rows: options.rows.unwrap_or(DEFAULT_ROWS),
+        cols: options.cols.unwrap_or(DEFAULT_COLS),
+        pixel_width: 0,
+        pixel_height: 0,
+    };
+    let pair = native_pty_system()
+        .openpty(size)
+        .map_err(|e| format!("Failed to open pseudo-terminal: {}", e))?;
+
+    let shell = default_shell();
+    let mut command = CommandBuilder::new(&shell);
+    if cfg!(target_os = "windows") {
+        command.arg("-NoLogo");
+    }
+    if let Some(cwd) = &options.cwd {
+        command.cwd(cwd);
+    }
+
+    let child = pair
+        .slave
+        .spawn_command(command)
+        .map_err(|e| format!("Failed to start shell: {}", e))?;
+    let reader = pair
+        .master
+        .try_clone_reader()
+        .map_err(|e| format!("Failed to read from terminal: {}", e))?;
+    let writer = pair
+        .master
+        .take_writer()
+        .map_err(|e| format!("Failed to write to terminal: {}", e))?;
+
+    let info = TerminalInfo {
+        id: uuid::Uuid::new_v4().to_string(),
+        shell,
+        cols: size.cols,
+        rows: size.rows,
+        created_at: chrono::Utc::now().timestamp(),
+    };
+    tracing::info!("▶️ Created terminal {} ({})", info.id, info.shell);
+
+    state.sessions.lock().unwrap().insert(
+        info.id.clone(),
+        TerminalSession {
+            info: info.clone(),
+            master: pair.master,
+            writer,
+            killer: child.clone_killer(),
+            line: LineBuffer::default(),
+        },
+    );
+    stream_output(app.clone(), info.id.clone(), reader);
+    watch_exit(app, info.id.clone(), child);
+
+    Ok(info)
+}
+
+/// Forward input to the shell. Keystrokes are passed through as typed, but Enter is
+/// only forwarded when the completed line passes the command policy; otherwise the
+/// line is cancelled with Ctrl+C and `terminal-command-blocked` is emitted.
+#[tauri::command]
+pub async fn write_to_terminal(
+    app: AppHandle,
+    state: State<'_, TerminalState>,
+    id: String,
+    data: String,
+) -> Result<(), String> {
+    let mut sessions = state.sessions.lock().unwrap();
+    let session = sessions
+        .get_mut(&id)
+        .ok_or_else(|| format!("Terminal {} not found", id))?;
+
+    let mut forward = String::new();
+    let mut chars = data.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\r' | '\n' => {
+                // Treat CRLF as a single Enter
+                if c == '\r' && chars.peek() == Some(&'\n') {
+                    chars.next();
+                }
+                match validate_line(&app, &session.line) {
+                    Ok(()) => forward.push('\r'),
+                    Err(reason) => {
+                        tracing::warn!("🚫 Blocked '{}': {}", session.line.text, reason);
+                        forward.push('\u{3}');
+                        let _ = crate::event_bus::emit(
+                            &app,
+                            crate::events::TERMINAL_COMMAND_BLOCKED,
+                            TerminalCommandBlocked {
+                                terminal_id: id.clone(),
+                                command_line: session.line.text.clone(),
+                                reason,
+                            },
+                        );
+                    }
+                }
+                session.line = LineBuffer::default();
+            }
+            '\u{7f}' | '\u{8}' => {
+                session.line.text.pop();
+                forward.push(c);
+            }
+            '\u{3}' => {
+                session.line = LineBuffer::default();
+                forward.push(c);
+            }
+            '\t' | '\u{1b}' => {
+                session.line.unverifiable = true;
+                forward.push(c);
+            }
+            c if c.is_control() => {
+                session.line.unverifiable = true;
+                forward.push(c);
+            }
+            c => {
+                session.line.text.push(c);
+                forward.push(c);
+            }
+        }
+    }
+
+    session
+        .writer
+        .write_all(forward.as_bytes())
+        .and_then(|_| session.writer.flush())
+        .map_err(|e| format!("Failed to write to terminal: {}", e))
+}
+
+#[tauri::command]
+pub async fn resize_terminal(
+    state: State<'_, TerminalState>,
+    id: String,
+    cols: u16,
+    rows: u16,
+) -> Result<(), String> {
+    let mut sessions = state.sessions.lock().unwrap();
+    let session = sessions
+        .get_mut(&id)
+        .ok_or_else(|| format!("Terminal {} not found", id))?;
+    session
+        .master
+        .resize(PtySize {
+            rows,
+            cols,
+            pixel_width: 0,
+            pixel_height: 0,
+        })
+        .map_err(|e| format!("Failed to resize terminal: {}", e))?;
+    session.info.cols = cols;
+    session.info.rows = rows;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn close_terminal(
+    state: State<'_, TerminalState>,
+    id: String,
+) -> Result<bool, String> {
+    let mut sessions = state.sessions.lock().unwrap();
+    match sessions.get_mut(&id) {
+        Some(session) => session
+            .killer
+            .kill()
+            .map(|_| true)
+            .map_err(|e| format!("Failed to close terminal: {}", e)),
+        None => Ok(false),
+    }
+}
+
+#[tauri::command]
+pub async fn list_terminals(
+    state: State<'_, TerminalState>,
+) -> Result<Vec<TerminalInfo>, String> {
+    let sessions = state.sessions.lock().unwrap();
+    Ok(sessions.values().map(|s| s.info.clone()).collect())
+}
@@ -0,0 +1,158 @@
+//! Local analysis of on-screen text: how hard it is to read (Flesch-Kincaid), its key terms and
+//! a short extractive summary. Everything is computed here without the AI, so the frontend can
+//! check whether material suits the student's level before any of it is sent anywhere. The
+//! readability formulas and the stop words are for English text.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+const MAX_TEXT_CHARS: usize = 200_000;
+const DEFAULT_SUMMARY_SENTENCES: usize = 3;
+const MAX_SUMMARY_SENTENCES: usize = 20;
+const KEY_TERMS: usize = 10;
+const STOP_WORDS: &[&str] = &[
+    "a", "about", "after", "all", "also", "an", "and", "any", "are", "as", "at", "be", "because", "been", "before",
+    "being", "between", "both", "but", "by", "can", "could", "did", "do", "does", "each", "for", "from", "had", "has",
+    "have", "he", "her", "his", "how", "i", "if", "in", "into", "is", "it", "its", "just", "more", "most", "my", "no",
+    "not", "of", "on", "one", "only", "or", "other", "our", "out", "over", "same", "she", "should", "so", "some",
+    "such", "than", "that", "the", "their", "them", "then", "there", "these", "they", "this", "those", "through", "to",
+    "too", "two", "under", "up", "use", "used", "very", "was", "we", "were", "what", "when", "where", "which", "while",
+    "who", "will", "with", "would", "you", "your",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct KeyTerm {
+    pub term: String,
+    pub count: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TextAnalysis {
+    pub words: u32,
+    pub sentences: u32,
+    pub syllables: u32,
+    /// Flesch reading ease: 100 is very easy, below 30 is very hard
+    pub reading_ease: f64,
+    /// Flesch-Kincaid grade level: the US school grade the text suits
+    pub grade_level: f64,
+    /// Most frequent words that aren't stop words, most frequent first
+    pub key_terms: Vec<KeyTerm>,
+    /// The sentences carrying the most key terms, in the order they appear in the text
+    pub summary: Vec<String>,
+}
+
+/// Sentences of `text`, split at ., ! and ? followed by whitespace, and at blank lines
+fn sentences(text: &str) -> Vec<String> {
+    let mut sentences = Vec::new();
+    for paragraph in text.split("\n\n") {
+        let mut current = String::new();
+        let mut chars = paragraph.chars().peekable();
+        while let Some(c) = chars.next() {
+            current.push(if c == '\n' { ' ' } else { c });
+            let at_end = matches!(c, '.' | '!' | '?') && chars.peek().is_none_or(|next| next.is_whitespace());
+            if at_end {
+                sentences.push(std::mem::take(&mut current));
+            }
+        }
+        sentences.push(current);
+    }
+    sentences
+        .into_iter()
+        .map(|sentence| sentence.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|sentence| sentence.chars().any(char::is_alphabetic))
+        .collect()
+}
+
+/// Lower-case words of `text`, keeping inner apostrophes and hyphens
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !(c.is_alphanumeric() || c == '\'' || c == '-'))
+        .map(|word| word.trim_matches(['\'', '-']).to_lowercase())
+        .filter(|word| word.chars().any(char::is_alphabetic))
+        .collect()
+}
+
+/// Vowel groups, less a silent final "e"; every word has at least one
+fn syllables(word: &str) -> u32 {
+    let mut count = 0;
+    let mut previous_vowel = false;
+    for c in word.chars() {
+        let vowel = matches!(c, 'a' | 'e' | 'i' | 'o' | 'u' | 'y');
+        if vowel && !previous_vowel {
+            count += 1;
+        }
+        previous_vowel = vowel;
+    }
+    if word.ends_with('e') && !word.ends_with("le") && count > 1 {
+        count -= 1;
+    }
+    count.max(1)
+}
+
+fn is_term(word: &str) -> bool {
+    word.chars().count() > 2 && !STOP_WORDS.contains(&word) && !word.chars().all(|c| c.is_ascii_digit())
+}
+
+/// Analyze `text`, summarizing it in at most `summary_sentences` sentences
+pub(crate) fn analyze(text: &str, summary_sentences: usize) -> TextAnalysis {
+    let sentences = sentences(text);
+    let words = words(text);
+    let syllables: u32 = words.iter().map(|word| syllables(word)).sum();
+
+    let (word_count, sentence_count) = (words.len() as f64, sentences.len().max(1) as f64);
+    let (reading_ease, grade_level) = if words.is_empty() {
+        (0.0, 0.0)
+    } else {
+        let words_per_sentence = word_count / sentence_count;
+        let syllables_per_word = syllables as f64 / word_count;
+        (
+            206.835 - 1.015 * words_per_sentence - 84.6 * syllables_per_word,
+            (0.39 * words_per_sentence + 11.8 * syllables_per_word - 15.59).max(0.0),
+        )
+    };
+
+    let mut counts: HashMap<&str, u32> = HashMap::new();
+    for word in words.iter().filter(|word| is_term(word)) {
+        *counts.entry(word.as_str()).or_default() += 1;
+    }
+    let mut key_terms: Vec<KeyTerm> = counts.iter().map(|(term, count)| KeyTerm { term: term.to_string(), count: *count }).collect();
+    key_terms.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.term.cmp(&b.term)));
+    key_terms.truncate(KEY_TERMS);
+
+    // Each sentence scores the average frequency of its terms, so long sentences aren't favoured
+    let mut scored: Vec<(usize, f64)> = sentences
+        .iter()
+        .enumerate()
+        .map(|(index, sentence)| {
+            let terms: Vec<String> = self::words(sentence).into_iter().filter(|word| is_term(word)).collect();
+            let total: u32 = terms.iter().map(|term| counts.get(term.as_str()).copied().unwrap_or_default()).sum();
+            (index, total as f64 / (terms.len().max(1) as f64).sqrt())
+        })
+        .collect();
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+    let mut chosen: Vec<usize> = scored.into_iter().take(summary_sentences).map(|(index, _)| index).collect();
+    chosen.sort_unstable();
+
+    TextAnalysis {
+        words: words.len() as u32,
+        sentences: sentences.len() as u32,
+        syllables,
+        reading_ease: (reading_ease * 10.0).round() / 10.0,
+        grade_level: (grade_level * 10.0).round() / 10.0,
+        key_terms,
+        summary: chosen.into_iter().map(|index| sentences[index].clone()).collect(),
+    }
+}
+
+/// Reading level, key terms and an extractive summary of `text`, computed locally
+#[tauri::command]
+pub async fn analyze_text(text: String, summary_sentences: Option<usize>) -> Result<TextAnalysis, String> {
+    if text.chars().count() > MAX_TEXT_CHARS {
+        return Err(format!("Text to analyze is limited to {} characters", MAX_TEXT_CHARS));
+    }
+    let summary_sentences = summary_sentences.unwrap_or(DEFAULT_SUMMARY_SENTENCES);
+    if summary_sentences > MAX_SUMMARY_SENTENCES {
+        return Err(format!("Summaries are limited to {} sentences", MAX_SUMMARY_SENTENCES));
+    }
+    Ok(analyze(&text, summary_sentences))
+}
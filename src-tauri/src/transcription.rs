@@ -0,0 +1,243 @@
+//! Offline transcription of voice memos and lecture recordings with whisper.cpp. Audio is
+//! decoded in-process (ffmpeg is only needed for Opus and WebM), transcribed through the
+//! whisper.cpp bindings (the `whisper-cpp` feature) with a ggml model downloaded through the
+//! `models` module, and stored in the history one part per minute of audio, so
+//! `search_history` finds what was said without any cloud service.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tauri::{AppHandle, Manager};
+
+use crate::cancellation::{CancellationState, OperationGuard};
+use crate::history::{HistoryItem, HistoryPart, HistoryState};
+use crate::temp_files::TempFilesState;
+
+mod audio;
+
+#[cfg(feature = "whisper-cpp")]
+mod whisper;
+
+/// Stands in for the bindings in builds without the `whisper-cpp` feature
+#[cfg(not(feature = "whisper-cpp"))]
+mod whisper {
+    use std::path::Path;
+
+    use super::Segment;
+
+    const UNAVAILABLE: &str = "This build can't transcribe audio; it was built without the whisper-cpp feature";
+
+    pub(super) struct WhisperContext;
+
+    pub(super) fn load(_path: &Path) -> Result<WhisperContext, String> {
+        Err(UNAVAILABLE.to_string())
+    }
+
+    pub(super) fn transcribe(
+        _model: &WhisperContext,
+        _samples: &[f32],
+        _language: &str,
+        _cancelled: impl Fn() -> bool + 'static,
+    ) -> Result<Vec<Segment>, String> {
+        Err(UNAVAILABLE.to_string())
+    }
+}
+
+pub(crate) const AUDIO_EXTENSIONS: &[&str] = &["wav", "mp3", "m4a", "ogg", "opus", "flac", "webm", "aac", "mp4"];
+const MAX_AUDIO_BYTES: u64 = 500 * 1024 * 1024;
+/// Model used when none is named: download a whisper.cpp model under this id
+const DEFAULT_MODEL_ID: &str = "whisper";
+/// Audio per history part, so a hit points at the right stretch of a long recording
+const PART_SECONDS: u64 = 60;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct TranscribeOptions {
+    /// Downloaded whisper.cpp model to use, "whisper" by default
+    #[serde(default)]
+    pub model_id: Option<String>,
+    /// Spoken language, e.g. "en" or "de"; detected when omitted
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Makes the transcription cancellable with `cancel_operation`
+    #[serde(default)]
+    pub request_id: Option<String>,
+}
+
+/// A stretch of recognized speech
+struct Segment {
+    /// Milliseconds from the start of the audio
+    from_ms: u64,
+    text: String,
+}
+
+fn validate_language(language: &str) -> Result<(), String> {
+    if language == "auto" || ((2..=3).contains(&language.len()) && language.chars().all(|c| c.is_ascii_lowercase())) {
+        Ok(())
+    } else {
+        Err(format!("'{}' is not a language code like \"en\"", language))
+    }
+}
+
+/// Run ffmpeg to completion, killing it if the operation is cancelled
+async fn run(mut command: tokio::process::Command, name: &str, operation: &OperationGuard) -> Result<(), String> {
+    operation.check()?;
+    let child = command
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| format!("Failed to start {}: {}", name, e))?;
+    if let Some(pid) = child.id() {
+        operation.attach(pid);
+    }
+    let output = child.wait_with_output().await.map_err(|e| format!("{} failed: {}", name, e))?;
+    operation.detach();
+    operation.check()?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let last = stderr.lines().rev().find(|line| !line.trim().is_empty()).unwrap_or_default();
+        return Err(format!("{} failed ({}): {}", name, output.status, last.trim()));
+    }
+    Ok(())
+}
+
+/// Decode on a blocking thread; `None` when the format needs ffmpeg
+async fn decode(path: std::path::PathBuf, operation: &OperationGuard) -> Result<Option<Vec<f32>>, String> {
+    let cancelled = operation.cancelled_flag();
+    tauri::async_runtime::spawn_blocking(move || audio::decode(&path, &cancelled))
+        .await
+        .map_err(|e| format!("Failed to decode the audio: {}", e))?
+}
+
+/// Convert audio the built-in decoders can't read to 16 kHz mono WAV with ffmpeg, saying so
+/// plainly when ffmpeg isn't installed
+async fn convert_with_ffmpeg(
+    path: &Path,
+    wav: &Path,
+    extension: &str,
+    operation: &OperationGuard,
+) -> Result<(), String> {
+    let ffmpeg = Some(crate::screen_recording::ffmpeg_path())
+        .filter(|path| path.is_file())
+        .or_else(|| crate::dev_environment::find_on_path("ffmpeg").into_iter().next())
+        .ok_or_else(|| {
+            format!(
+                "Transcribing .{} audio needs ffmpeg, which wasn't found next to the app or on PATH; \
+                 install ffmpeg or convert the recording to WAV, MP3, M4A or FLAC",
+                extension
+            )
+        })?;
+    let mut command = tokio::process::Command::new(ffmpeg);
+    command
+        .args(["-nostdin", "-y", "-i"])
+        .arg(path)
+        .args(["-ar", &audio::SAMPLE_RATE.to_string(), "-ac", "1", "-c:a", "pcm_s16le"])
+        .arg(wav);
+    run(command, "ffmpeg", operation).await
+}
+
+/// "[12:05]" for an offset in milliseconds
+fn timestamp(ms: u64) -> String {
+    let seconds = ms / 1000;
+    if seconds >= 3600 {
+        format!("[{}:{:02}:{:02}]", seconds / 3600, seconds / 60 % 60, seconds % 60)
+    } else {
+        format!("[{:02}:{:02}]", seconds / 60, seconds % 60)
+    }
+}
+
+/// Segments grouped into one part per minute of audio, each starting with its timestamp
+fn parts(segments: &[Segment]) -> Vec<HistoryPart> {
+    let mut parts: Vec<HistoryPart> = Vec::new();
+    let mut current_minute = None;
+    for segment in segments {
+        let text = segment.text.trim();
+        if text.is_empty() {
+            continue;
+        }
+        let minute = segment.from_ms / 1000 / PART_SECONDS;
+        if current_minute != Some(minute) {
+            current_minute = Some(minute);
+            parts.push(HistoryPart { part: parts.len() as u32 + 1, text: timestamp(segment.from_ms), image_path: None });
+        }
+        if let Some(part) = parts.last_mut() {
+            part.text.push(' ');
+            part.text.push_str(text);
+        }
+    }
+    parts
+}
+
+/// Transcribe an audio file into the history
+pub(crate) async fn import(
+    app: &AppHandle,
+    path: &Path,
+    options: &TranscribeOptions,
+    operation: &OperationGuard,
+) -> Result<HistoryItem, String> {
+    let extension = path.extension().map(|e| e.to_string_lossy().to_lowercase()).unwrap_or_default();
+    if !AUDIO_EXTENSIONS.contains(&extension.as_str()) {
+        return Err(format!("Only audio files can be transcribed ({})", AUDIO_EXTENSIONS.join(", ")));
+    }
+    let size = std::fs::metadata(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?.len();
+    if size > MAX_AUDIO_BYTES {
+        return Err(format!("Audio over {} MB can't be transcribed", MAX_AUDIO_BYTES / (1024 * 1024)));
+    }
+    let language = options.language.as_deref().unwrap_or("auto");
+    validate_language(language)?;
+    let model = crate::models::model_file(app, options.model_id.as_deref().unwrap_or(DEFAULT_MODEL_ID))?;
+    let model = tauri::async_runtime::spawn_blocking(move || whisper::load(&model))
+        .await
+        .map_err(|e| format!("Failed to load the speech model: {}", e))??;
+
+    let samples = match decode(path.to_path_buf(), operation).await? {
+        Some(samples) => samples,
+        None => {
+            let wav = app.state::<TempFilesState>().create("transcribe_", "wav");
+            convert_with_ffmpeg(path, wav.path(), &extension, operation).await?;
+            decode(wav.path().to_path_buf(), operation).await?.ok_or("ffmpeg wrote audio that can't be read")?
+        }
+    };
+    if samples.is_empty() {
+        return Err("The recording is empty".to_string());
+    }
+
+    let started = std::time::Instant::now();
+    let language = language.to_string();
+    let cancelled = operation.cancelled_flag();
+    let segments =
+        tauri::async_runtime::spawn_blocking(move || whisper::transcribe(&model, &samples, &language, cancelled))
+            .await
+            .map_err(|e| format!("Transcription failed: {}", e))?;
+    operation.check()?;
+    let parts = parts(&segments?);
+    if parts.is_empty() {
+        return Err("No speech was recognized".to_string());
+    }
+
+    let title = path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+    let history = app.state::<HistoryState>();
+    let id = history.insert("transcript", &title, Some(&path.to_string_lossy()), &parts)?;
+    tracing::info!(
+        "🎙️ Transcribed {} ({} minute(s) of speech) in {} s",
+        path.display(),
+        parts.len(),
+        started.elapsed().as_secs()
+    );
+    history.get(id)?.ok_or_else(|| "Transcript disappeared".to_string())
+}
+
+/// Transcribe a voice memo or lecture recording offline and store the text in the history,
+/// one part per minute with timestamps. Find it again with `search_history`.
+#[tauri::command]
+pub async fn transcribe_audio_file(
+    app: AppHandle,
+    path: String,
+    options: Option<TranscribeOptions>,
+) -> Result<HistoryItem, String> {
+    let path = std::path::absolute(&path).map_err(|e| format!("Invalid path {}: {}", path, e))?;
+    let options = options.unwrap_or_default();
+    let operation = app.state::<CancellationState>().register(options.request_id.as_deref());
+    import(&app, &path, &options, &operation).await
+}
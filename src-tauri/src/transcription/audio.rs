@@ -0,0 +1,128 @@
+//! Audio decoding for transcription: any container and codec symphonia reads, mixed down to
+//! mono and resampled to the 16 kHz whisper.cpp expects, one packet at a time so a long lecture
+//! never sits in memory at its original rate.
+
+use std::fs::File;
+use std::path::Path;
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{DecoderOptions, CODEC_TYPE_NULL};
+use symphonia::core::errors::Error;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+
+use crate::cancellation::CANCELLED;
+
+/// Sample rate whisper.cpp models are trained on
+pub(super) const SAMPLE_RATE: u32 = 16_000;
+
+/// Downmixes to mono and resamples by averaging the input falling into each output sample,
+/// a crude low-pass that is plenty for speech
+struct Resampler {
+    /// Input samples per output sample
+    step: f64,
+    seen: u64,
+    sum: f32,
+    count: u32,
+    output: Vec<f32>,
+}
+
+impl Resampler {
+    fn new(rate: u32) -> Self {
+        Self { step: rate as f64 / SAMPLE_RATE as f64, seen: 0, sum: 0.0, count: 0, output: Vec::new() }
+    }
+
+    fn push(&mut self, sample: f32) {
+        let index = (self.seen as f64 / self.step) as usize;
+        while self.output.len() < index {
+            // Upsampling leaves some outputs without input: they repeat the previous one
+            let value = if self.count > 0 {
+                self.sum / self.count as f32
+            } else {
+                self.output.last().copied().unwrap_or_default()
+            };
+            self.output.push(value);
+            self.sum = 0.0;
+            self.count = 0;
+        }
+        self.sum += sample;
+        self.count += 1;
+        self.seen += 1;
+    }
+
+    fn finish(mut self) -> Vec<f32> {
+        if self.count > 0 {
+            self.output.push(self.sum / self.count as f32);
+        }
+        self.output
+    }
+}
+
+/// 16 kHz mono samples of the file's first audio track, or `None` when its container or codec
+/// isn't one symphonia reads (Opus, WebM)
+pub(super) fn decode(path: &Path, cancelled: &dyn Fn() -> bool) -> Result<Option<Vec<f32>>, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let stream = MediaSourceStream::new(Box::new(file), Default::default());
+    let mut hint = Hint::new();
+    if let Some(extension) = path.extension().and_then(|e| e.to_str()) {
+        hint.with_extension(extension);
+    }
+    let probed = match symphonia::default::get_probe().format(
+        &hint,
+        stream,
+        &FormatOptions::default(),
+        &MetadataOptions::default(),
+    ) {
+        Ok(probed) => probed,
+        Err(Error::Unsupported(_)) => return Ok(None),
+        Err(e) => return Err(format!("Failed to read the audio: {}", e)),
+    };
+    let mut format = probed.format;
+    let track = format
+        .tracks()
+        .iter()
+        .find(|track| track.codec_params.codec != CODEC_TYPE_NULL)
+        .ok_or("The file has no audio track")?;
+    let track_id = track.id;
+    let rate = track.codec_params.sample_rate.ok_or("The audio track has no sample rate")?;
+    let mut decoder = match symphonia::default::get_codecs().make(&track.codec_params, &DecoderOptions::default()) {
+        Ok(decoder) => decoder,
+        Err(Error::Unsupported(_)) => return Ok(None),
+        Err(e) => return Err(format!("Failed to decode the audio: {}", e)),
+    };
+
+    let mut resampler = Resampler::new(rate);
+    let mut buffer: Option<SampleBuffer<f32>> = None;
+    loop {
+        if cancelled() {
+            return Err(CANCELLED.to_string());
+        }
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(Error::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(format!("Failed to read the audio: {}", e)),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            // A damaged packet costs a few milliseconds of audio, not the recording
+            Err(Error::DecodeError(_)) => continue,
+            Err(e) => return Err(format!("Failed to decode the audio: {}", e)),
+        };
+        let spec = *decoded.spec();
+        let channels = spec.channels.count().max(1);
+        let frames = decoded.capacity();
+        if buffer.as_ref().is_some_and(|buffer| buffer.capacity() < frames * channels) {
+            buffer = None;
+        }
+        let buffer = buffer.get_or_insert_with(|| SampleBuffer::new(frames as u64, spec));
+        buffer.copy_interleaved_ref(decoded);
+        for frame in buffer.samples().chunks(channels) {
+            resampler.push(frame.iter().sum::<f32>() / channels as f32);
+        }
+    }
+    Ok(Some(resampler.finish()))
+}
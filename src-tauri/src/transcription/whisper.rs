@@ -0,0 +1,50 @@
+//! In-process speech recognition through the whisper.cpp bindings. The model is loaded for each
+//! transcription and freed after it; recordings are long enough that loading is the small part.
+
+use std::path::Path;
+use whisper_rs::{FullParams, SamplingStrategy, WhisperContextParameters};
+
+use super::Segment;
+
+pub(super) use whisper_rs::WhisperContext;
+
+/// whisper.cpp gains little from more threads than this
+const MAX_THREADS: usize = 8;
+
+/// Load a ggml speech model. Call it from a blocking thread.
+pub(super) fn load(path: &Path) -> Result<WhisperContext, String> {
+    // whisper.cpp's own logging goes to the app log instead of stderr
+    whisper_rs::install_logging_hooks();
+    WhisperContext::new_with_params(path, WhisperContextParameters::default())
+        .map_err(|e| format!("Failed to load the speech model: {}", e))
+}
+
+/// Transcribe 16 kHz mono samples, stopping early once `cancelled` returns true
+pub(super) fn transcribe(
+    model: &WhisperContext,
+    samples: &[f32],
+    language: &str,
+    cancelled: impl Fn() -> bool + 'static,
+) -> Result<Vec<Segment>, String> {
+    let mut state = model.create_state().map_err(|e| format!("Failed to start whisper.cpp: {}", e))?;
+
+    let threads = std::thread::available_parallelism().map_or(4, |n| n.get().min(MAX_THREADS));
+    let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+    params.set_n_threads(threads as i32);
+    params.set_language(Some(language));
+    params.set_print_special(false);
+    params.set_print_progress(false);
+    params.set_print_realtime(false);
+    params.set_print_timestamps(false);
+    params.set_abort_callback_safe(cancelled);
+    state.full(params, samples).map_err(|e| format!("Transcription failed: {}", e))?;
+
+    state
+        .as_iter()
+        .map(|segment| {
+            let text = segment.to_str_lossy().map_err(|e| format!("Unreadable transcript: {}", e))?;
+            // whisper.cpp counts in centiseconds
+            Ok(Segment { from_ms: segment.start_timestamp().max(0) as u64 * 10, text: text.into_owned() })
+        })
+        .collect()
+}
@@ -0,0 +1,185 @@
+//! Translation of OCR text for students studying in a second language. The backend is a
+//! setting: a local model (the enabled Ollama or llama.cpp providers, so nothing leaves the
+//! machine), a LibreTranslate server, or the cloud AI providers in their failover order.
+//! `capture_window_with_ocr` can translate the text it extracts through the same service.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+
+use crate::ai::{ChatMessage, ChatRequest, ChatRole};
+use crate::persistence;
+
+const SETTINGS_FILE: &str = "translation.json";
+const MAX_TEXT_CHARS: usize = 20_000;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+/// Keychain id of the LibreTranslate API key, stored with `set_provider_api_key`
+const LIBRETRANSLATE_KEY_ID: &str = "libretranslate";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TranslationBackend {
+    /// The enabled Ollama and llama.cpp providers
+    #[default]
+    LocalModel,
+    /// A LibreTranslate server at `libretranslate_url`
+    LibreTranslate,
+    /// The AI providers in failover order, cloud ones included
+    Cloud,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct TranslationSettings {
+    pub backend: TranslationBackend,
+    /// e.g. "https://libretranslate.example.org"; its API key, if it needs one, is stored with
+    /// `set_provider_api_key` under the id "libretranslate"
+    pub libretranslate_url: Option<String>,
+}
+
+#[derive(Clone, Default)]
+pub struct TranslationState {
+    settings: Arc<Mutex<TranslationSettings>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Translation {
+    pub text: String,
+    /// Language the text was detected as, when the backend reports it
+    pub source_lang: Option<String>,
+    pub target_lang: String,
+    pub backend: TranslationBackend,
+}
+
+/// A language code like "en", "pt-BR" or "zh-Hans"
+fn validate_language(language: &str) -> Result<(), String> {
+    let mut parts = language.split('-');
+    let primary = parts.next().unwrap_or_default();
+    let valid = (2..=3).contains(&primary.len())
+        && primary.chars().all(|c| c.is_ascii_alphabetic())
+        && parts.all(|part| (2..=8).contains(&part.len()) && part.chars().all(|c| c.is_ascii_alphanumeric()));
+    if !valid {
+        return Err(format!("'{}' is not a language code like \"en\" or \"pt-BR\"", language));
+    }
+    Ok(())
+}
+
+async fn translate_with_ai(app: &AppHandle, text: &str, target_lang: &str, local: bool) -> Result<Translation, String> {
+    let request = ChatRequest {
+        system: Some(format!(
+            "Translate the user's text into the language with the code \"{}\". Keep line breaks, code, \
+             numbers, formulas and names unchanged. Reply with the translation only.",
+            target_lang
+        )),
+        messages: vec![ChatMessage { role: ChatRole::User, content: text.to_string() }],
+        max_tokens: None,
+    };
+    let response = if local {
+        crate::ai::complete_locally(app, &request).await?
+    } else {
+        crate::ai::complete(app, &request).await?
+    };
+    Ok(Translation {
+        text: response.text.trim().to_string(),
+        source_lang: None,
+        target_lang: target_lang.to_string(),
+        backend: if local { TranslationBackend::LocalModel } else { TranslationBackend::Cloud },
+    })
+}
+
+async fn translate_with_libretranslate(base_url: &str, text: &str, target_lang: &str) -> Result<Translation, String> {
+    // The server may be a public instance: keys and passwords in the text never leave the machine
+    let text = crate::secret_filter::filter(text).text;
+    let mut body = json!({ "q": text, "source": "auto", "target": target_lang, "format": "text" });
+    if let Some(key) = crate::ai::read_api_key(LIBRETRANSLATE_KEY_ID)? {
+        body["api_key"] = json!(key);
+    }
+    let client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let response = client
+        .post(format!("{}/translate", base_url.trim_end_matches('/')))
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| format!("LibreTranslate request failed: {}", e))?;
+    let status = response.status();
+    let body: Value = response.json().await.map_err(|e| format!("Unexpected LibreTranslate response: {}", e))?;
+    if !status.is_success() {
+        let error = body["error"].as_str().unwrap_or("no details");
+        return Err(format!("LibreTranslate failed (HTTP {}): {}", status, error));
+    }
+    let text = body["translatedText"].as_str().ok_or("LibreTranslate returned no translation")?;
+    Ok(Translation {
+        text: text.to_string(),
+        source_lang: body["detectedLanguage"]["language"].as_str().map(str::to_string),
+        target_lang: target_lang.to_string(),
+        backend: TranslationBackend::LibreTranslate,
+    })
+}
+
+/// Translate `text` into `target_lang` with the configured backend
+pub(crate) async fn translate(app: &AppHandle, text: &str, target_lang: &str) -> Result<Translation, String> {
+    validate_language(target_lang)?;
+    if text.trim().is_empty() {
+        return Err("There is no text to translate".to_string());
+    }
+    if text.chars().count() > MAX_TEXT_CHARS {
+        return Err(format!("Text to translate is limited to {} characters", MAX_TEXT_CHARS));
+    }
+    let settings = app.state::<TranslationState>().settings.lock().unwrap().clone();
+    let translation = match settings.backend {
+        TranslationBackend::LocalModel => translate_with_ai(app, text, target_lang, true).await,
+        TranslationBackend::Cloud => translate_with_ai(app, text, target_lang, false).await,
+        TranslationBackend::LibreTranslate => {
+            let url = settings.libretranslate_url.as_deref().ok_or("No LibreTranslate URL is configured")?;
+            translate_with_libretranslate(url, text, target_lang).await
+        }
+    }?;
+    tracing::info!("🌐 Translated {} characters into {} ({:?})", text.len(), target_lang, settings.backend);
+    Ok(translation)
+}
+
+pub fn init(app: &AppHandle) {
+    let settings: TranslationSettings = persistence::data_file(app, SETTINGS_FILE)
+        .map(|path| persistence::load_json(&path))
+        .unwrap_or_default();
+    *app.state::<TranslationState>().settings.lock().unwrap() = settings;
+}
+
+/// Translate text, e.g. OCR of a worksheet, into `target_lang` ("en", "de", "pt-BR", ...)
+#[tauri::command]
+pub async fn translate_text(app: AppHandle, text: String, target_lang: String) -> Result<Translation, String> {
+    translate(&app, &text, &target_lang).await
+}
+
+#[tauri::command]
+pub async fn get_translation_settings(state: State<'_, TranslationState>) -> Result<TranslationSettings, String> {
+    Ok(state.settings.lock().unwrap().clone())
+}
+
+#[tauri::command]
+pub async fn set_translation_settings(
+    app: AppHandle,
+    state: State<'_, TranslationState>,
+    settings: TranslationSettings,
+) -> Result<(), String> {
+    if let Some(url) = &settings.libretranslate_url {
+        let parsed = url::Url::parse(url).map_err(|e| format!("Invalid LibreTranslate URL: {}", e))?;
+        if !matches!(parsed.scheme(), "http" | "https") {
+            return Err("The LibreTranslate URL must start with http:// or https://".to_string());
+        }
+    }
+    if settings.backend == TranslationBackend::LibreTranslate && settings.libretranslate_url.is_none() {
+        return Err("The LibreTranslate backend needs its URL".to_string());
+    }
+    let path = persistence::data_file(&app, SETTINGS_FILE)?;
+    persistence::save_json(&path, &settings)?;
+    tracing::info!("🌐 Translation backend set to {:?}", settings.backend);
+    *state.settings.lock().unwrap() = settings;
+    Ok(())
+}
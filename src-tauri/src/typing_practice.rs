@@ -0,0 +1,171 @@
+//! Typing lessons: while a practice session runs, keystrokes typed into the practice window are
+//! counted and timed, and compared with the passage read from that window with OCR when the
+//! session started. As with activity tracking, which key was pressed is never recorded: the
+//! shared input hook (see `input_hook`) only tells an ordinary key from a backspace, so speed
+//! and accuracy can be measured without anything typed leaving the keyboard driver.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager, State};
+
+use crate::cancellation::CancellationState;
+use crate::consent::{ConsentScope, ConsentState};
+use crate::monitoring::MonitorTarget;
+
+/// Standard word length for words-per-minute figures
+const CHARS_PER_WORD: f64 = 5.0;
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TypingSessionStats {
+    pub window_title: String,
+    pub process_name: String,
+    /// Characters in the passage read from the practice window
+    pub target_chars: usize,
+    pub started_at: i64,
+    /// Keys pressed in the practice window, backspaces included
+    pub keystrokes: u64,
+    pub backspaces: u64,
+    /// Seconds from the first keystroke to the last
+    pub typing_seconds: f64,
+    /// Keys other than backspace per minute, in five-character words
+    pub gross_wpm: f64,
+    /// As `gross_wpm`, less the characters that were typed and then deleted
+    pub net_wpm: f64,
+    /// Share of typed characters that didn't have to be deleted, from 0 to 1
+    pub accuracy: f64,
+    /// Share of the passage typed so far, from 0 to 1
+    pub progress: f64,
+    pub running: bool,
+}
+
+struct TypingSession {
+    window_title: String,
+    process_name: String,
+    target_chars: usize,
+    started_at: i64,
+    keystrokes: u64,
+    backspaces: u64,
+    /// Unix milliseconds of the first and latest second with keystrokes
+    first_key_at: Option<i64>,
+    last_key_at: Option<i64>,
+    running: bool,
+}
+
+impl TypingSession {
+    fn stats(&self) -> TypingSessionStats {
+        let typed = self.keystrokes.saturating_sub(self.backspaces) as f64;
+        // Each backspace deletes one typed character
+        let kept = (typed - self.backspaces as f64).max(0.0);
+        let typing_seconds = match (self.first_key_at, self.last_key_at) {
+            // Counts arrive once a second, so the first second is part of the typing time
+            (Some(first), Some(last)) => (last - first) as f64 / 1000.0 + 1.0,
+            _ => 0.0,
+        };
+        let per_minute = |chars: f64| if typing_seconds > 0.0 { chars / CHARS_PER_WORD / (typing_seconds / 60.0) } else { 0.0 };
+        let round = |value: f64| (value * 100.0).round() / 100.0;
+        TypingSessionStats {
+            window_title: self.window_title.clone(),
+            process_name: self.process_name.clone(),
+            target_chars: self.target_chars,
+            started_at: self.started_at,
+            keystrokes: self.keystrokes,
+            backspaces: self.backspaces,
+            typing_seconds: round(typing_seconds),
+            gross_wpm: round(per_minute(typed)),
+            net_wpm: round(per_minute(kept)),
+            accuracy: round(if typed > 0.0 { kept / typed } else { 1.0 }),
+            progress: round(if self.target_chars > 0 { (kept / self.target_chars as f64).min(1.0) } else { 0.0 }),
+            running: self.running,
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct TypingPracticeState {
+    session: Arc<Mutex<Option<TypingSession>>>,
+}
+
+/// Add a second's keys from the shared input hook to the running session
+pub(crate) fn record_second(app: &AppHandle, keys: u64, backspaces: u64) {
+    let state = app.state::<TypingPracticeState>();
+    let mut session = state.session.lock().unwrap();
+    let Some(session) = session.as_mut().filter(|session| session.running) else {
+        return;
+    };
+    let now = chrono::Utc::now().timestamp_millis();
+    session.keystrokes += keys;
+    session.backspaces += backspaces.min(keys);
+    session.first_key_at.get_or_insert(now);
+    session.last_key_at = Some(now);
+}
+
+/// End the running session, if any, keeping its stats. Also called when the app exits.
+pub fn stop(app: &AppHandle) {
+    if let Err(e) = crate::input_hook::set_typing_process(app, None) {
+        tracing::warn!("⚠️ {}", e);
+    }
+    let state = app.state::<TypingPracticeState>();
+    if let Some(session) = state.session.lock().unwrap().as_mut() {
+        session.running = false;
+    };
+}
+
+/// Start a typing lesson in a practice window: its passage is read with OCR, then keystrokes
+/// typed into it are counted until `stop_typing_session`. Replaces any earlier session.
+#[tauri::command]
+pub async fn start_typing_session(
+    app: AppHandle,
+    state: State<'_, TypingPracticeState>,
+    window_title: Option<String>,
+    process_name: Option<String>,
+) -> Result<TypingSessionStats, String> {
+    app.state::<ConsentState>().require(ConsentScope::ScreenAndText)?;
+    if window_title.is_none() && process_name.is_none() {
+        return Err("Name the practice window by its title or process name".to_string());
+    }
+    let target = MonitorTarget::Window { process_name, window_title };
+    let (capture, window) = crate::monitoring::capture_target(&app, &target).await?;
+    let (window_title, process_name) = window.ok_or("The practice window wasn't found")?;
+    let operation = app.state::<CancellationState>().register(None);
+    let passage = crate::commands::ocr_image(&app, capture.image_base64, &operation).await?;
+    let target_chars = passage.split_whitespace().collect::<Vec<_>>().join(" ").chars().count();
+    if target_chars == 0 {
+        return Err("No text to type was found in the practice window".to_string());
+    }
+
+    stop(&app);
+    let session = TypingSession {
+        window_title,
+        process_name,
+        target_chars,
+        started_at: chrono::Utc::now().timestamp(),
+        keystrokes: 0,
+        backspaces: 0,
+        first_key_at: None,
+        last_key_at: None,
+        running: true,
+    };
+    crate::input_hook::set_typing_process(&app, Some(session.process_name.clone()))?;
+    let stats = session.stats();
+    *state.session.lock().unwrap() = Some(session);
+    tracing::info!("⌨️ Typing session started in {} ({} characters to type)", stats.window_title, target_chars);
+    Ok(stats)
+}
+
+/// Speed, accuracy and progress of the current or last typing session
+#[tauri::command]
+pub async fn get_typing_session_stats(state: State<'_, TypingPracticeState>) -> Result<Option<TypingSessionStats>, String> {
+    Ok(state.session.lock().unwrap().as_ref().map(TypingSession::stats))
+}
+
+/// End the typing session and return its final stats
+#[tauri::command]
+pub async fn stop_typing_session(app: AppHandle, state: State<'_, TypingPracticeState>) -> Result<Option<TypingSessionStats>, String> {
+    stop(&app);
+    let stats = state.session.lock().unwrap().as_ref().map(TypingSession::stats);
+    if let Some(stats) = &stats {
+        tracing::info!("⌨️ Typing session ended: {} net WPM, {:.0}% accuracy", stats.net_wpm, stats.accuracy * 100.0);
+    }
+    Ok(stats)
+}
@@ -0,0 +1,228 @@
+//! Self-update through the Tauri updater, with a stable/beta channel. Each channel has its own
+//! `latest.json` manifest. Release builds bake in the signing public key
+//! (`AI_TEACHER_UPDATER_PUBKEY`) and may override the manifest URL (`AI_TEACHER_UPDATE_URL`,
+//! where `{channel}` is replaced with the channel name).
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager, State};
+use tauri_plugin_updater::{Update, UpdaterExt};
+
+use crate::persistence;
+
+const SETTINGS_FILE: &str = "update_settings.json";
+const DEFAULT_ENDPOINT: &str =
+    "https://github.com/emiliancristea/ai-teacher/releases/download/updater-{channel}/latest.json";
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdateChannel {
+    #[default]
+    Stable,
+    Beta,
+}
+
+impl UpdateChannel {
+    fn as_str(self) -> &'static str {
+        match self {
+            UpdateChannel::Stable => "stable",
+            UpdateChannel::Beta => "beta",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct UpdateSettings {
+    pub channel: UpdateChannel,
+    /// Look for an update at startup and emit `update-available` when there is one
+    pub check_on_startup: bool,
+}
+
+impl Default for UpdateSettings {
+    fn default() -> Self {
+        Self { channel: UpdateChannel::Stable, check_on_startup: true }
+    }
+}
+
+/// Payload of `update-available` and result of `check_for_updates` when there is an update
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct UpdateInfo {
+    pub current_version: String,
+    pub version: String,
+    pub channel: UpdateChannel,
+    /// Release notes
+    pub notes: Option<String>,
+    /// RFC 3339 publish date
+    pub date: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdateStage {
+    Downloading,
+    Installing,
+    /// Installed; the app restarts next
+    Done,
+}
+
+/// Payload of the `update-progress` event
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct UpdateProgress {
+    pub stage: UpdateStage,
+    pub downloaded_bytes: u64,
+    pub total_bytes: Option<u64>,
+}
+
+#[derive(Clone, Default)]
+pub struct UpdaterState {
+    settings: Arc<Mutex<UpdateSettings>>,
+    /// Update found by the last check, installed by `install_update`
+    pending: Arc<Mutex<Option<Update>>>,
+    installing: Arc<AtomicBool>,
+}
+
+fn endpoint(channel: UpdateChannel) -> Result<url::Url, String> {
+    let template = option_env!("AI_TEACHER_UPDATE_URL").unwrap_or(DEFAULT_ENDPOINT);
+    url::Url::parse(&template.replace("{channel}", channel.as_str()))
+        .map_err(|e| format!("Invalid update URL: {}", e))
+}
+
+fn info(update: &Update, channel: UpdateChannel) -> UpdateInfo {
+    UpdateInfo {
+        current_version: update.current_version.clone(),
+        version: update.version.clone(),
+        channel,
+        notes: update.body.clone(),
+        date: update.date.map(|date| date.to_string()),
+    }
+}
+
+async fn check(app: &AppHandle, state: &UpdaterState) -> Result<Option<UpdateInfo>, String> {
+    let channel = state.settings.lock().unwrap().channel;
+    let mut builder = app
+        .updater_builder()
+        .endpoints(vec![endpoint(channel)?])
+        .map_err(|e| format!("Invalid update endpoint: {}", e))?;
+    if let Some(pubkey) = option_env!("AI_TEACHER_UPDATER_PUBKEY") {
+        builder = builder.pubkey(pubkey);
+    }
+    let update = builder
+        .build()
+        .map_err(|e| format!("Failed to set up the updater: {}", e))?
+        .check()
+        .await
+        .map_err(|e| format!("Update check failed: {}", e))?;
+
+    let found = update.as_ref().map(|update| info(update, channel));
+    *state.pending.lock().unwrap() = update;
+    Ok(found)
+}
+
+/// Load the channel setting and, if enabled, look for an update in the background
+pub fn init(app: &AppHandle) {
+    let settings: UpdateSettings = persistence::shared_data_file(app, SETTINGS_FILE)
+        .map(|path| persistence::load_json(&path))
+        .unwrap_or_default();
+    let check_on_startup = settings.check_on_startup && !cfg!(debug_assertions);
+    *app.state::<UpdaterState>().settings.lock().unwrap() = settings;
+    if !check_on_startup {
+        return;
+    }
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<UpdaterState>().inner().clone();
+        match check(&app, &state).await {
+            Ok(Some(update)) => {
+                tracing::info!("⬆️ Update available: {} -> {}", update.current_version, update.version);
+                let _ = crate::event_bus::emit(&app, crate::events::UPDATE_AVAILABLE, update);
+            }
+            Ok(None) => {}
+            Err(e) => tracing::warn!("⚠️ {}", e),
+        }
+    });
+}
+
+/// Look for a newer build on the configured channel; None when up to date
+#[tauri::command]
+pub async fn check_for_updates(
+    app: AppHandle,
+    state: State<'_, UpdaterState>,
+) -> Result<Option<UpdateInfo>, String> {
+    check(&app, &state).await
+}
+
+/// Download and install the update found by the last check (checking first if needed), emitting
+/// `update-progress`, then restart into the new version
+#[tauri::command]
+pub async fn install_update(app: AppHandle, state: State<'_, UpdaterState>) -> Result<(), String> {
+    if state.installing.swap(true, Ordering::SeqCst) {
+        return Err("An update is already being installed".to_string());
+    }
+    let result = async {
+        if state.pending.lock().unwrap().is_none() && check(&app, &state).await?.is_none() {
+            return Err("Already up to date".to_string());
+        }
+        let update = state.pending.lock().unwrap().clone().ok_or("Already up to date")?;
+        tracing::info!("⬆️ Installing update {}", update.version);
+
+        let mut downloaded = 0u64;
+        let progress_app = app.clone();
+        let installing_app = app.clone();
+        update
+            .download_and_install(
+                |chunk, total| {
+                    downloaded += chunk as u64;
+                    let _ = crate::event_bus::emit(
+                        &progress_app,
+                        crate::events::UPDATE_PROGRESS,
+                        UpdateProgress { stage: UpdateStage::Downloading, downloaded_bytes: downloaded, total_bytes: total },
+                    );
+                },
+                || {
+                    let _ = crate::event_bus::emit(
+                        &installing_app,
+                        crate::events::UPDATE_PROGRESS,
+                        UpdateProgress { stage: UpdateStage::Installing, downloaded_bytes: 0, total_bytes: None },
+                    );
+                },
+            )
+            .await
+            .map_err(|e| format!("Failed to install update: {}", e))?;
+        let _ = crate::event_bus::emit(
+            &app,
+            crate::events::UPDATE_PROGRESS,
+            UpdateProgress { stage: UpdateStage::Done, downloaded_bytes: 0, total_bytes: None },
+        );
+        Ok(())
+    }
+    .await;
+    state.installing.store(false, Ordering::SeqCst);
+    result?;
+    // Windows installers exit the app themselves; elsewhere the new binary needs a restart
+    app.restart();
+}
+
+#[tauri::command]
+pub async fn get_update_settings(state: State<'_, UpdaterState>) -> Result<UpdateSettings, String> {
+    Ok(state.settings.lock().unwrap().clone())
+}
+
+/// Switch between the stable and beta channel; a pending update from the old channel is dropped
+#[tauri::command]
+pub async fn set_update_settings(
+    app: AppHandle,
+    state: State<'_, UpdaterState>,
+    settings: UpdateSettings,
+) -> Result<(), String> {
+    let path = persistence::shared_data_file(&app, SETTINGS_FILE)?;
+    persistence::save_json(&path, &settings)?;
+    let mut current = state.settings.lock().unwrap();
+    if current.channel != settings.channel {
+        *state.pending.lock().unwrap() = None;
+    }
+    *current = settings;
+    Ok(())
+}
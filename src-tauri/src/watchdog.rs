@@ -0,0 +1,242 @@
+//! Liveness of the background work: screen monitors, the narrator, the distraction watcher and
+//! the warm PowerShell hosts that run OCR. Supervised tasks send heartbeats as they loop; a
+//! task that panics, or stays silent longer than it promised, is restarted after a delay that
+//! doubles with each failure in a row. Every time something turns unhealthy a
+//! `subsystem-unhealthy` event is emitted, and `get_subsystem_health` reports the current state.
+
+use futures_util::FutureExt;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, State};
+use tokio::sync::Notify;
+
+use crate::powershell_host::PowerShellHostState;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(10);
+const INITIAL_BACKOFF: Duration = Duration::from_secs(5);
+const MAX_BACKOFF: Duration = Duration::from_secs(300);
+/// A run lasting this long resets the backoff
+const STABLE_RUN: Duration = Duration::from_secs(300);
+/// Longest a capture-and-OCR loop may go without a heartbeat: a minute's interval plus a
+/// capture and an OCR run that both hit their timeouts
+pub(crate) const CAPTURE_LOOP_SILENCE: Duration = Duration::from_secs(300);
+/// Scripts failing in a row before the OCR hosts count as failing
+const OCR_FAILURES_UNHEALTHY: u32 = 3;
+const OCR_WORKERS: &str = "OCR workers";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthStatus {
+    Healthy,
+    /// Missed its heartbeats; about to be restarted
+    Stalled,
+    /// Crashed or stalled, waiting out the backoff before its restart
+    Restarting,
+    /// Running, but its recent work failed
+    Failing,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SubsystemHealth {
+    pub name: String,
+    pub status: HealthStatus,
+    /// Unix milliseconds of the latest heartbeat or successful run
+    pub last_heartbeat: Option<i64>,
+    pub restarts: u32,
+    pub last_error: Option<String>,
+    /// Unix milliseconds of the pending restart
+    pub next_restart_at: Option<i64>,
+}
+
+/// Payload of the `subsystem-unhealthy` event
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SubsystemUnhealthy {
+    pub name: String,
+    pub status: HealthStatus,
+    pub reason: String,
+    pub restarts: u32,
+    pub timestamp: i64,
+}
+
+struct Subsystem {
+    health: SubsystemHealth,
+    /// Longest allowed gap between heartbeats, for supervised tasks
+    max_silence: Option<Duration>,
+    last_beat: Instant,
+    restart: Arc<Notify>,
+}
+
+#[derive(Clone, Default)]
+pub struct WatchdogState {
+    subsystems: Arc<Mutex<HashMap<String, Subsystem>>>,
+}
+
+/// Handed to a supervised task, which calls `beat` each time around its loop
+#[derive(Clone)]
+pub struct Heartbeat {
+    name: String,
+    state: WatchdogState,
+}
+
+impl Heartbeat {
+    pub fn beat(&self) {
+        if let Some(subsystem) = self.state.subsystems.lock().unwrap().get_mut(&self.name) {
+            subsystem.last_beat = Instant::now();
+            subsystem.health.last_heartbeat = Some(chrono::Utc::now().timestamp_millis());
+            subsystem.health.status = HealthStatus::Healthy;
+            subsystem.health.next_restart_at = None;
+        }
+    }
+}
+
+impl WatchdogState {
+    fn register(&self, name: &str, max_silence: Option<Duration>) -> Arc<Notify> {
+        let restart = Arc::new(Notify::new());
+        self.subsystems.lock().unwrap().insert(
+            name.to_string(),
+            Subsystem {
+                health: SubsystemHealth {
+                    name: name.to_string(),
+                    status: HealthStatus::Healthy,
+                    last_heartbeat: None,
+                    restarts: 0,
+                    last_error: None,
+                    next_restart_at: None,
+                },
+                max_silence,
+                last_beat: Instant::now(),
+                restart: restart.clone(),
+            },
+        );
+        restart
+    }
+
+    /// Record `status` for `name`, emitting `subsystem-unhealthy`
+    fn report(&self, app: &AppHandle, name: &str, status: HealthStatus, reason: &str, restart_in: Option<Duration>) {
+        let mut subsystems = self.subsystems.lock().unwrap();
+        let Some(subsystem) = subsystems.get_mut(name) else {
+            return;
+        };
+        let health = &mut subsystem.health;
+        health.status = status;
+        health.last_error = Some(reason.to_string());
+        if let Some(delay) = restart_in {
+            health.restarts += 1;
+            health.next_restart_at = Some(chrono::Utc::now().timestamp_millis() + delay.as_millis() as i64);
+        }
+        let event = SubsystemUnhealthy {
+            name: name.to_string(),
+            status,
+            reason: reason.to_string(),
+            restarts: health.restarts,
+            timestamp: chrono::Utc::now().timestamp_millis(),
+        };
+        drop(subsystems);
+        let _ = crate::event_bus::emit(app, crate::events::SUBSYSTEM_UNHEALTHY, event);
+    }
+}
+
+fn backoff(failures: u32) -> Duration {
+    INITIAL_BACKOFF.saturating_mul(2u32.saturating_pow(failures.saturating_sub(1))).min(MAX_BACKOFF)
+}
+
+/// Run a background loop under the watchdog. `make` builds a fresh future for each run and is
+/// handed the heartbeat to call at least every `max_silence`. A run that panics (the panic
+/// itself is recorded by the crash report hook) or stalls is restarted with exponential
+/// backoff; a run that returns ends supervision.
+pub fn spawn_supervised<F, Fut>(app: &AppHandle, name: impl Into<String>, max_silence: Duration, make: F)
+where
+    F: Fn(Heartbeat) -> Fut + Send + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    let name = name.into();
+    let state = app.state::<WatchdogState>().inner().clone();
+    let restart = state.register(&name, Some(max_silence));
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut failures = 0;
+        loop {
+            let heartbeat = Heartbeat { name: name.clone(), state: state.clone() };
+            heartbeat.beat();
+            let started = Instant::now();
+            let reason = tokio::select! {
+                result = AssertUnwindSafe(make(heartbeat)).catch_unwind() => match result {
+                    Ok(()) => break,
+                    Err(_) => "panicked",
+                },
+                _ = restart.notified() => "stopped responding",
+            };
+            failures = if started.elapsed() >= STABLE_RUN { 1 } else { failures + 1 };
+            let delay = backoff(failures);
+            tracing::error!("💥 {} {}, restarting in {}s", name, reason, delay.as_secs());
+            state.report(&app, &name, HealthStatus::Restarting, reason, Some(delay));
+            tokio::time::sleep(delay).await;
+        }
+        // Unless a new task has taken over the name meanwhile
+        let mut subsystems = state.subsystems.lock().unwrap();
+        if subsystems.get(&name).is_some_and(|s| Arc::ptr_eq(&s.restart, &restart)) {
+            subsystems.remove(&name);
+        }
+    });
+}
+
+/// Flag supervised tasks that missed their heartbeats and check on the OCR hosts
+fn check(app: &AppHandle) {
+    let state = app.state::<WatchdogState>();
+    let stalled: Vec<(String, Arc<Notify>)> = state
+        .subsystems
+        .lock()
+        .unwrap()
+        .values()
+        .filter(|s| s.health.status == HealthStatus::Healthy && s.max_silence.is_some_and(|max| s.last_beat.elapsed() > max))
+        .map(|s| (s.health.name.clone(), s.restart.clone()))
+        .collect();
+    for (name, restart) in stalled {
+        tracing::warn!("⚠️ {} stopped sending heartbeats", name);
+        state.report(app, &name, HealthStatus::Stalled, "missed its heartbeats", None);
+        restart.notify_one();
+    }
+
+    let ocr = app.state::<PowerShellHostState>().health();
+    let previous = {
+        let mut subsystems = state.subsystems.lock().unwrap();
+        let Some(subsystem) = subsystems.get_mut(OCR_WORKERS) else {
+            return;
+        };
+        subsystem.health.last_heartbeat = ocr.last_success;
+        std::mem::replace(
+            &mut subsystem.health.status,
+            if ocr.consecutive_failures >= OCR_FAILURES_UNHEALTHY { HealthStatus::Failing } else { HealthStatus::Healthy },
+        )
+    };
+    if ocr.consecutive_failures >= OCR_FAILURES_UNHEALTHY && previous != HealthStatus::Failing {
+        let reason = ocr.last_error.unwrap_or_else(|| "scripts keep failing".to_string());
+        state.report(app, OCR_WORKERS, HealthStatus::Failing, &reason, None);
+    }
+}
+
+/// Start checking on the background tasks
+pub fn init(app: &AppHandle) {
+    app.state::<WatchdogState>().register(OCR_WORKERS, None);
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(CHECK_INTERVAL).await;
+            check(&app);
+        }
+    });
+}
+
+/// Health of each background subsystem
+#[tauri::command]
+pub async fn get_subsystem_health(state: State<'_, WatchdogState>) -> Result<Vec<SubsystemHealth>, String> {
+    let mut health: Vec<SubsystemHealth> =
+        state.subsystems.lock().unwrap().values().map(|subsystem| subsystem.health.clone()).collect();
+    health.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(health)
+}
@@ -0,0 +1,125 @@
+//! Webcam frames, so students can hold paper homework up to the camera. A frame is taken with
+//! Windows `MediaCapture` (Media Foundation) and returned like a screen capture, so it goes
+//! through the same OCR, handwriting and math recognition as screenshots.
+
+use base64::{engine::general_purpose, Engine as _};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Manager};
+
+use crate::cancellation::{CancellationState, OperationGuard};
+use crate::commands::CaptureResult;
+use crate::powershell_host::{PowerShellHostState, WINRT_PRELUDE};
+
+/// Loads the camera types on top of `WINRT_PRELUDE`
+const CAMERA_TYPES: &str = r#"
+[Windows.Devices.Enumeration.DeviceInformation, Windows.Devices.Enumeration, ContentType=WindowsRuntime] | Out-Null
+[Windows.Media.Capture.MediaCapture, Windows.Media.Capture, ContentType=WindowsRuntime] | Out-Null
+[Windows.Media.MediaProperties.ImageEncodingProperties, Windows.Media.MediaProperties, ContentType=WindowsRuntime] | Out-Null
+"#;
+
+/// Video capture devices as `{id, name, is_default}` JSON; the first one is the default
+const LIST_WEBCAMS_SCRIPT: &str = r#"
+$devices = Await ([Windows.Devices.Enumeration.DeviceInformation]::FindAllAsync([Windows.Devices.Enumeration.DeviceClass]::VideoCapture)) `
+    ([Windows.Devices.Enumeration.DeviceInformationCollection])
+$index = 0
+$cameras = foreach ($device in $devices) {
+    [PSCustomObject]@{ id = $device.Id; name = $device.Name; is_default = ($index++ -eq 0) }
+}
+ConvertTo-Json -InputObject @($cameras) -Compress
+"#;
+
+/// Take one photo from `{device_id}` (the default camera when empty) and print it as base64 PNG
+fn capture_frame_script(device_id: Option<&str>) -> String {
+    format!(r#"
+$deviceId = '{}'
+$settings = New-Object Windows.Media.Capture.MediaCaptureInitializationSettings
+$settings.StreamingCaptureMode = [Windows.Media.Capture.StreamingCaptureMode]::Video
+if ($deviceId) {{ $settings.VideoDeviceId = $deviceId }}
+$capture = New-Object Windows.Media.Capture.MediaCapture
+try {{
+    AwaitAction ($capture.InitializeAsync($settings))
+    $stream = New-Object Windows.Storage.Streams.InMemoryRandomAccessStream
+    AwaitAction ($capture.CapturePhotoToStreamAsync([Windows.Media.MediaProperties.ImageEncodingProperties]::CreatePng(), $stream))
+    [Convert]::ToBase64String((Read-StreamBytes $stream))
+}} finally {{
+    $capture.Dispose()
+}}
+"#, device_id.unwrap_or_default().replace('\'', "''"))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct WebcamDevice {
+    /// Pass as `device_id` to `capture_webcam_frame`
+    pub id: String,
+    pub name: String,
+    pub is_default: bool,
+}
+
+async fn run_script(app: &AppHandle, script: &str, operation: Option<&OperationGuard>) -> Result<String, String> {
+    if !cfg!(target_os = "windows") {
+        return Err("Webcam capture is only available on Windows".to_string());
+    }
+    let output = app
+        .state::<PowerShellHostState>()
+        .run(&format!("{}{}{}", WINRT_PRELUDE, CAMERA_TYPES, script), operation, |_| {})
+        .await?;
+    if !output.success() {
+        let stderr = output.stderr.trim();
+        // Desktop apps need "Let desktop apps access your camera" in the privacy settings
+        if stderr.contains("0x80070005") || stderr.contains("Access is denied") {
+            return Err("Camera access is blocked. Allow desktop apps to use the camera in Windows Settings > Privacy > Camera.".to_string());
+        }
+        return Err(format!("Webcam capture failed: {}", stderr));
+    }
+    Ok(output.stdout.trim().to_string())
+}
+
+/// List the cameras a frame can be taken from
+#[tauri::command]
+pub async fn list_webcams(app: AppHandle) -> Result<Vec<WebcamDevice>, String> {
+    let json = run_script(&app, LIST_WEBCAMS_SCRIPT, None).await?;
+    serde_json::from_str(&json).map_err(|e| format!("Failed to parse camera list: {}", e))
+}
+
+/// Take a photo with a webcam (the default camera without `device_id`), e.g. of homework held
+/// up to the camera. With `ocr`, the text is read with the handwriting preprocessing; the image
+/// can also be passed to `recognize_math` or `recognize_handwriting` like any capture.
+#[tauri::command]
+pub async fn capture_webcam_frame(
+    app: AppHandle,
+    device_id: Option<String>,
+    ocr: Option<bool>,
+    request_id: Option<String>,
+) -> Result<CaptureResult, String> {
+    let operation = app.state::<CancellationState>().register(request_id.as_deref());
+    let image_base64 = run_script(&app, &capture_frame_script(device_id.as_deref()), Some(&operation)).await?;
+    let image_bytes = general_purpose::STANDARD
+        .decode(&image_base64)
+        .map_err(|e| format!("Failed to decode webcam frame: {}", e))?;
+    if image_bytes.is_empty() {
+        return Err("The camera returned an empty frame".to_string());
+    }
+    tracing::info!("📷 Captured webcam frame ({} bytes)", image_bytes.len());
+    let hash = hex::encode(Sha256::digest(&image_bytes));
+
+    let ocr_text = if ocr.unwrap_or(false) {
+        let prepared = tauri::async_runtime::spawn_blocking(move || crate::handwriting::prepare_for_ocr(&image_bytes))
+            .await
+            .map_err(|e| format!("Failed to prepare webcam frame: {}", e))??;
+        Some(crate::commands::ocr_image(&app, general_purpose::STANDARD.encode(prepared), &operation).await?)
+    } else {
+        None
+    };
+
+    Ok(CaptureResult {
+        image_base64,
+        hash,
+        timestamp: chrono::Utc::now().timestamp(),
+        diff: None,
+        delta: None,
+        ocr_text,
+        classification: None,
+    })
+}
@@ -0,0 +1,148 @@
+//! Capture targets inside a window: a browser tab's page, one Windows Terminal pane, a child
+//! HWND. The window's UI Automation descendants are listed (native helper or script), the
+//! `ChildTarget` picks one, and the window capture is cropped to its rectangle so OCR doesn't
+//! pick up the tab strip, other panes or side-by-side documents.
+
+#![cfg_attr(not(target_os = "windows"), allow(dead_code))]
+
+use image::ImageFormat;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::io::Cursor;
+use tauri::{AppHandle, Manager};
+
+use crate::cancellation::OperationGuard;
+use crate::commands::CaptureWindowParams;
+use crate::powershell_host::PowerShellHostState;
+use crate::window_match::WindowMatcher;
+
+/// Which descendant of the matched window to capture; the first element (in tree order)
+/// matching every given field wins
+#[derive(Debug, Clone, Default, Deserialize, JsonSchema)]
+pub struct ChildTarget {
+    /// Element name, matched like `window_title` with the filter's match options. A browser
+    /// tab's `Document` element is named after the page title.
+    #[serde(default)]
+    pub name: Option<String>,
+    /// UI Automation control type, e.g. `Document`, `Pane`, `TabItem`, `Text`
+    #[serde(default)]
+    pub control_type: Option<String>,
+    /// Class name of the element or child window, e.g. `Chrome_RenderWidgetHostHWND`
+    #[serde(default)]
+    pub class_name: Option<String>,
+}
+
+/// A UI Automation element with its screen rectangle
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct UiElement {
+    pub name: String,
+    pub control_type: String,
+    pub class_name: String,
+    pub left: i32,
+    pub top: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+/// UI Automation descendants of window `{hwnd}` with a visible rectangle, as JSON
+fn list_elements_script(hwnd: i64) -> String {
+    format!(r#"
+Add-Type -AssemblyName UIAutomationClient, UIAutomationTypes
+$automation = [System.Windows.Automation.AutomationElement]
+$root = $automation::FromHandle([IntPtr]::new({hwnd}))
+$cache = New-Object System.Windows.Automation.CacheRequest
+foreach ($property in @($automation::NameProperty, $automation::ControlTypeProperty, $automation::ClassNameProperty, $automation::BoundingRectangleProperty)) {{
+    $cache.Add($property)
+}}
+$cache.Push()
+try {{
+    $found = $root.FindAll([System.Windows.Automation.TreeScope]::Descendants, [System.Windows.Automation.Condition]::TrueCondition)
+}} finally {{
+    $cache.Pop()
+}}
+$elements = foreach ($element in ($found | Select-Object -First 2000)) {{
+    $rect = $element.Cached.BoundingRectangle
+    if ($rect.IsEmpty -or $rect.Width -le 0 -or $rect.Height -le 0) {{ continue }}
+    [PSCustomObject]@{{
+        name = $element.Cached.Name
+        control_type = $element.Cached.ControlType.ProgrammaticName -replace '^ControlType\.', ''
+        class_name = $element.Cached.ClassName
+        left = [int]$rect.X
+        top = [int]$rect.Y
+        width = [int]$rect.Width
+        height = [int]$rect.Height
+    }}
+}}
+ConvertTo-Json -InputObject @($elements) -Compress
+"#)
+}
+
+/// List a window's UI Automation descendants with the native helper, or the script when it
+/// isn't available
+pub(crate) async fn list_elements(
+    app: &AppHandle,
+    hwnd: i64,
+    operation: Option<&OperationGuard>,
+) -> Result<Vec<UiElement>, String> {
+    let params = serde_json::json!({ "hwnd": hwnd });
+    if let Some(result) = crate::native_helper::call(app, "list_elements", params, operation).await {
+        return result;
+    }
+    let output = app.state::<PowerShellHostState>().run(&list_elements_script(hwnd), operation, |_| {}).await?;
+    if !output.success() {
+        return Err(format!("Failed to list window elements: {}", output.stderr.trim()));
+    }
+    serde_json::from_str(output.stdout.trim()).map_err(|e| format!("Failed to parse window elements: {}", e))
+}
+
+/// First element matching `target`; the name uses the window filter's match options
+pub(crate) fn find_element<'a>(
+    elements: &'a [UiElement],
+    target: &ChildTarget,
+    filter: &CaptureWindowParams,
+) -> Result<&'a UiElement, String> {
+    let name_filter = CaptureWindowParams {
+        window_title: target.name.clone(),
+        match_mode: filter.match_mode,
+        case_sensitive: filter.case_sensitive,
+        normalize: filter.normalize,
+        ignore_diacritics: filter.ignore_diacritics,
+        ..Default::default()
+    };
+    let name_matcher = WindowMatcher::new(&name_filter)?;
+    let same = |wanted: &Option<String>, actual: &str| wanted.as_ref().is_none_or(|w| w.eq_ignore_ascii_case(actual));
+    elements
+        .iter()
+        .find(|element| {
+            same(&target.control_type, &element.control_type)
+                && same(&target.class_name, &element.class_name)
+                && name_matcher.matches_title(&element.name)
+        })
+        .ok_or_else(|| format!("No element in the window matches {:?}", target))
+}
+
+/// Crop a window capture (PNG) to an element. `window` is the window's screen rectangle
+/// (left, top, width, height); the image may be scaled relative to it.
+pub(crate) fn crop_to_element(png: &[u8], window: [i64; 4], element: &UiElement) -> Result<Vec<u8>, String> {
+    let [left, top, width, height] = window;
+    if width <= 0 || height <= 0 {
+        return Err("Window has no visible rectangle".to_string());
+    }
+    let image = image::load_from_memory(png).map_err(|e| format!("Failed to decode capture: {}", e))?;
+    let scale_x = image.width() as f64 / width as f64;
+    let scale_y = image.height() as f64 / height as f64;
+    let x = |screen: i64| (((screen - left) as f64 * scale_x).round().max(0.0) as u32).min(image.width());
+    let y = |screen: i64| (((screen - top) as f64 * scale_y).round().max(0.0) as u32).min(image.height());
+    let (x0, x1) = (x(element.left.into()), x(i64::from(element.left) + i64::from(element.width)));
+    let (y0, y1) = (y(element.top.into()), y(i64::from(element.top) + i64::from(element.height)));
+    if x1 <= x0 || y1 <= y0 {
+        return Err(format!("Element '{}' is outside the window", element.name));
+    }
+
+    let mut cropped = Vec::new();
+    image
+        .crop_imm(x0, y0, x1 - x0, y1 - y0)
+        .write_to(&mut Cursor::new(&mut cropped), ImageFormat::Png)
+        .map_err(|e| format!("Failed to encode cropped capture: {}", e))?;
+    Ok(cropped)
+}
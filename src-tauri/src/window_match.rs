@@ -0,0 +1,208 @@
+//! Window matching for the capture commands. Windows are listed (with their titles read as
+//! UTF-16) by the native helper or a fixed script, and matched here in Rust, so titles like
+//! "数学の宿題 – Word" or "[Draft] notes" no longer go through PowerShell string interpolation
+//! and `-like` wildcards.
+
+#![cfg_attr(not(target_os = "windows"), allow(dead_code))]
+
+use regex::{Regex, RegexBuilder};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+use crate::cancellation::OperationGuard;
+use crate::commands::CaptureWindowParams;
+use crate::powershell_host::PowerShellHostState;
+
+/// Visible top-level windows in z-order, as `{hwnd, title, process_name, is_active}` JSON
+const LIST_WINDOWS_SCRIPT: &str = r#"
+Add-Type @"
+    using System;
+    using System.Collections.Generic;
+    using System.Runtime.InteropServices;
+    using System.Text;
+    public static class WindowList {
+        delegate bool EnumWindowsProc(IntPtr hWnd, IntPtr lParam);
+        [DllImport("user32.dll")] static extern bool EnumWindows(EnumWindowsProc enumProc, IntPtr lParam);
+        [DllImport("user32.dll")] static extern bool IsWindowVisible(IntPtr hWnd);
+        [DllImport("user32.dll", CharSet = CharSet.Unicode)] static extern int GetWindowTextLengthW(IntPtr hWnd);
+        [DllImport("user32.dll", CharSet = CharSet.Unicode)] static extern int GetWindowTextW(IntPtr hWnd, StringBuilder text, int count);
+        [DllImport("user32.dll")] public static extern IntPtr GetForegroundWindow();
+        [DllImport("user32.dll")] public static extern uint GetWindowThreadProcessId(IntPtr hWnd, out uint processId);
+
+        public static List<IntPtr> Visible() {
+            var handles = new List<IntPtr>();
+            EnumWindows((hWnd, lParam) => { if (IsWindowVisible(hWnd)) handles.Add(hWnd); return true; }, IntPtr.Zero);
+            return handles;
+        }
+
+        public static string Title(IntPtr hWnd) {
+            var text = new StringBuilder(GetWindowTextLengthW(hWnd) + 1);
+            GetWindowTextW(hWnd, text, text.Capacity);
+            return text.ToString();
+        }
+    }
+"@
+$foreground = [WindowList]::GetForegroundWindow()
+$names = @{}
+$windows = foreach ($hWnd in [WindowList]::Visible()) {
+    $processId = 0
+    [WindowList]::GetWindowThreadProcessId($hWnd, [ref]$processId) | Out-Null
+    if (-not $names.ContainsKey($processId)) {
+        $process = Get-Process -Id $processId -ErrorAction SilentlyContinue
+        $names[$processId] = if ($process) { $process.ProcessName } else { $null }
+    }
+    if ($names[$processId]) {
+        [PSCustomObject]@{
+            hwnd = $hWnd.ToInt64()
+            title = [WindowList]::Title($hWnd)
+            process_name = $names[$processId]
+            is_active = ($hWnd -eq $foreground)
+        }
+    }
+}
+ConvertTo-Json -InputObject @($windows) -Compress
+"#;
+
+/// A visible top-level window
+#[derive(Debug, Clone, Deserialize)]
+pub struct WindowEntry {
+    pub hwnd: i64,
+    pub title: String,
+    pub process_name: String,
+    pub is_active: bool,
+}
+
+/// List visible windows with the native helper, or the list script when it isn't available
+pub(crate) async fn list_windows(
+    app: &AppHandle,
+    operation: Option<&OperationGuard>,
+) -> Result<Vec<WindowEntry>, String> {
+    if let Some(result) = crate::native_helper::call(app, "list_windows", serde_json::json!({}), operation).await {
+        return result;
+    }
+    let output = app.state::<PowerShellHostState>().run(LIST_WINDOWS_SCRIPT, operation, |_| {}).await?;
+    if !output.success() {
+        return Err(format!("Failed to list windows: {}", output.stderr.trim()));
+    }
+    serde_json::from_str(output.stdout.trim()).map_err(|e| format!("Failed to parse window list: {}", e))
+}
+
+/// Error for a filter that matched nothing, naming the processes that do have windows
+pub(crate) fn not_found(windows: &[WindowEntry]) -> String {
+    let mut names: Vec<&str> = windows
+        .iter()
+        .filter(|window| !window.title.is_empty())
+        .map(|window| window.process_name.as_str())
+        .collect();
+    names.sort_unstable();
+    names.dedup();
+    format!("Window not found. Available processes with windows: {}", names.join(", "))
+}
+
+/// How `process_name` and `window_title` are compared; without one, the capture scripts'
+/// original rules apply
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchMode {
+    Exact,
+    Contains,
+    Regex,
+    /// The filter's characters appear in order, e.g. "py mng run" for "python manage.py runserver"
+    Fuzzy,
+}
+
+enum Pattern {
+    Text(String),
+    Regex(Regex),
+}
+
+/// A `CaptureWindowParams` filter, compiled once
+pub(crate) struct WindowMatcher {
+    process_name: Option<Pattern>,
+    window_title: Option<Pattern>,
+    mode: Option<MatchMode>,
+    case_sensitive: bool,
+    normalize: bool,
+    ignore_diacritics: bool,
+}
+
+impl WindowMatcher {
+    pub fn new(params: &CaptureWindowParams) -> Result<Self, String> {
+        let mut matcher = Self {
+            process_name: None,
+            window_title: None,
+            mode: params.match_mode,
+            case_sensitive: params.case_sensitive,
+            normalize: params.normalize,
+            ignore_diacritics: params.ignore_diacritics,
+        };
+        matcher.process_name = params.process_name.as_deref().map(|p| matcher.compile(p)).transpose()?;
+        matcher.window_title = params.window_title.as_deref().map(|p| matcher.compile(p)).transpose()?;
+        Ok(matcher)
+    }
+
+    fn compile(&self, pattern: &str) -> Result<Pattern, String> {
+        if self.mode != Some(MatchMode::Regex) {
+            return Ok(Pattern::Text(self.fold(pattern)));
+        }
+        RegexBuilder::new(pattern)
+            .case_insensitive(!self.case_sensitive)
+            .build()
+            .map(Pattern::Regex)
+            .map_err(|e| format!("Invalid pattern '{}': {}", pattern, e))
+    }
+
+    /// Canonical composition always; lowercase unless `case_sensitive`; compatibility forms
+    /// (full-width letters, ligatures) with `normalize`; accents and other combining marks
+    /// dropped with `ignore_diacritics`
+    fn fold(&self, text: &str) -> String {
+        let composed: String = if self.normalize { text.nfkc().collect() } else { text.nfc().collect() };
+        let cased = if self.case_sensitive { composed } else { composed.to_lowercase() };
+        if self.ignore_diacritics {
+            cased.nfd().filter(|c| !is_combining_mark(*c)).nfc().collect()
+        } else {
+            cased
+        }
+    }
+
+    fn field_matches(&self, pattern: &Pattern, text: &str) -> bool {
+        let text = self.fold(text);
+        match (pattern, self.mode) {
+            (Pattern::Regex(regex), _) => regex.is_match(&text),
+            (Pattern::Text(wanted), Some(MatchMode::Exact)) => text == *wanted,
+            (Pattern::Text(wanted), Some(MatchMode::Fuzzy)) => {
+                let mut remaining = text.chars();
+                wanted.chars().filter(|c| !c.is_whitespace()).all(|c| remaining.any(|t| t == c))
+            }
+            (Pattern::Text(wanted), _) => text.contains(wanted.as_str()),
+        }
+    }
+
+    /// Whether `title` matches the `window_title` pattern (any title does without one)
+    pub fn matches_title(&self, title: &str) -> bool {
+        self.window_title.as_ref().is_none_or(|p| self.field_matches(p, title))
+    }
+
+    pub fn matches(&self, window: &WindowEntry) -> bool {
+        if self.mode.is_some() {
+            let process_ok = self.process_name.as_ref().is_none_or(|p| self.field_matches(p, &window.process_name));
+            return process_ok && self.matches_title(&window.title);
+        }
+
+        // The capture scripts' rules: a process name with a title must match exactly, on its
+        // own it also matches with `.exe` appended or as a substring; titles match as substrings
+        let title_matches = || self.matches_title(&window.title);
+        let Some(Pattern::Text(name)) = &self.process_name else {
+            return title_matches();
+        };
+        let process = self.fold(&window.process_name);
+        if self.window_title.is_some() {
+            process == *name && title_matches()
+        } else {
+            process == *name || process == format!("{}.exe", name) || process.contains(name.as_str())
+        }
+    }
+}
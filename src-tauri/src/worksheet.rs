@@ -0,0 +1,410 @@
+//! Checking paper homework. A photo or scan of a worksheet (or a webcam frame taken on the
+//! spot) is cleaned up like handwriting and read with OCR word boxes; each line starting with
+//! a question number ("3.", "4b)", "Q5:") is taken as that question's answer. Math answers are
+//! also cropped out and read by the LaTeX-OCR server. Answers are then compared with an answer
+//! key the teacher saved, question by question.
+
+use base64::{engine::general_purpose, Engine as _};
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::io::Cursor;
+use std::sync::{Arc, Mutex, OnceLock};
+use tauri::{AppHandle, Manager, State};
+
+use crate::cancellation::CancellationState;
+use crate::ocr_layout::OcrWord;
+use crate::persistence;
+
+const ANSWER_KEYS_FILE: &str = "answer_keys.json";
+const MAX_QUESTIONS: usize = 200;
+/// Padding around a math answer's words when it is cropped for the LaTeX-OCR server, in word heights
+const CROP_PADDING: f32 = 0.5;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AnswerKind {
+    /// Words, compared ignoring case, spacing and punctuation
+    #[default]
+    Text,
+    /// A number, compared within `tolerance`
+    Number,
+    /// A formula or expression, also read with the LaTeX-OCR server
+    Math,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AnswerKeyQuestion {
+    /// The label written on the worksheet, e.g. "3" or "4b"
+    pub number: String,
+    /// Accepted answers; any of them counts as correct
+    pub answers: Vec<String>,
+    #[serde(default)]
+    pub kind: AnswerKind,
+    /// Largest difference still counted as correct for number answers
+    #[serde(default)]
+    pub tolerance: Option<f64>,
+    #[serde(default = "default_points")]
+    pub points: f64,
+}
+
+fn default_points() -> f64 {
+    1.0
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AnswerKey {
+    pub id: String,
+    pub title: String,
+    pub questions: Vec<AnswerKeyQuestion>,
+    #[serde(default)]
+    pub updated_at: i64,
+}
+
+/// An answer key without its answers, for listing to students
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AnswerKeySummary {
+    pub id: String,
+    pub title: String,
+    pub questions: usize,
+    pub max_score: f64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct QuestionGrade {
+    pub number: String,
+    /// What was read from the worksheet; None when the question wasn't found
+    pub given: Option<String>,
+    /// The formula read by the LaTeX-OCR server, for math questions
+    pub given_latex: Option<String>,
+    pub correct: bool,
+    pub points: f64,
+    pub max_points: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct WorksheetGrade {
+    pub answer_key_id: String,
+    pub title: String,
+    pub questions: Vec<QuestionGrade>,
+    pub score: f64,
+    pub max_score: f64,
+    /// Everything OCR read, to check a surprising grade against
+    pub ocr_text: String,
+    /// The worksheet image that was graded, e.g. the webcam frame
+    pub image_base64: String,
+}
+
+#[derive(Clone, Default)]
+pub struct WorksheetState {
+    keys: Arc<Mutex<Vec<AnswerKey>>>,
+}
+
+/// Load the saved answer keys
+pub fn init(app: &AppHandle) {
+    let keys: Vec<AnswerKey> = persistence::data_file(app, ANSWER_KEYS_FILE)
+        .map(|path| persistence::load_json(&path))
+        .unwrap_or_default();
+    *app.state::<WorksheetState>().keys.lock().unwrap() = keys;
+}
+
+fn persist(app: &AppHandle, keys: &[AnswerKey]) -> Result<(), String> {
+    let path = persistence::data_file(app, ANSWER_KEYS_FILE)?;
+    persistence::save_json(&path, &keys)
+}
+
+fn question_label() -> &'static Regex {
+    static LABEL: OnceLock<Regex> = OnceLock::new();
+    LABEL.get_or_init(|| Regex::new(r"^(?i:q(?:uestion)?\s*)?(\d{1,3}[a-z]?)[.):]$").unwrap())
+}
+
+/// "4b" and "4 B" alike
+fn normalize_label(label: &str) -> String {
+    label.chars().filter(|c| !c.is_whitespace()).collect::<String>().to_lowercase()
+}
+
+/// An answer read from the worksheet
+struct ReadAnswer {
+    label: String,
+    words: Vec<OcrWord>,
+}
+
+/// Answers by question label: the rest of each OCR line that starts with one, plus following
+/// lines up to the next label, so an answer wrapping onto a second line is kept whole
+fn read_answers(words: &[OcrWord]) -> Vec<ReadAnswer> {
+    let mut answers: Vec<ReadAnswer> = Vec::new();
+    let mut current_line = None;
+    for word in words {
+        let starts_line = current_line != Some(word.line);
+        current_line = Some(word.line);
+        if starts_line {
+            if let Some(captures) = question_label().captures(word.text.trim()) {
+                answers.push(ReadAnswer { label: normalize_label(&captures[1]), words: Vec::new() });
+                continue;
+            }
+        }
+        if let Some(answer) = answers.last_mut() {
+            answer.words.push(word.clone());
+        }
+    }
+    answers
+}
+
+fn answer_text(words: &[OcrWord]) -> String {
+    words.iter().map(|word| word.text.as_str()).collect::<Vec<_>>().join(" ")
+}
+
+/// The answer's words cut out of the worksheet as a PNG
+fn crop_answer(image: &image::DynamicImage, words: &[OcrWord]) -> Result<Vec<u8>, String> {
+    let height = words.iter().map(|word| word.height).fold(0.0, f32::max);
+    let padding = height * CROP_PADDING;
+    let left = words.iter().map(|word| word.x).fold(f32::MAX, f32::min) - padding;
+    let top = words.iter().map(|word| word.y).fold(f32::MAX, f32::min) - padding;
+    let right = words.iter().map(|word| word.x + word.width).fold(0.0, f32::max) + padding;
+    let bottom = words.iter().map(|word| word.y + word.height).fold(0.0, f32::max) + padding;
+    let (left, top) = (left.max(0.0) as u32, top.max(0.0) as u32);
+    let right = (right as u32).min(image.width());
+    let bottom = (bottom as u32).min(image.height());
+    if right <= left || bottom <= top {
+        return Err("The answer is outside the image".to_string());
+    }
+    let mut png = Vec::new();
+    image
+        .crop_imm(left, top, right - left, bottom - top)
+        .write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)
+        .map_err(|e| format!("Failed to encode the answer: {}", e))?;
+    Ok(png)
+}
+
+/// Lower case, letters and digits only
+fn normalize_text(text: &str) -> String {
+    text.chars().filter(|c| c.is_alphanumeric()).collect::<String>().to_lowercase()
+}
+
+/// The first number in `text`, reading a decimal comma as a point ("3,5 cm" is 3.5)
+fn parse_number(text: &str) -> Option<f64> {
+    static NUMBER: OnceLock<Regex> = OnceLock::new();
+    let number = NUMBER.get_or_init(|| Regex::new(r"-?\d+(?:[.,]\d+)?(?:/\d+)?").unwrap());
+    let found = number.find(&text.replace(['−', '–'], "-"))?.as_str().replace(',', ".");
+    let Some((numerator, denominator)) = found.split_once('/') else {
+        return found.parse().ok();
+    };
+    let (numerator, denominator): (f64, f64) = (numerator.parse().ok()?, denominator.parse().ok()?);
+    (denominator != 0.0).then(|| numerator / denominator)
+}
+
+/// A formula with spacing and equivalent notations evened out, so "\dfrac{1}{2}" and "1/2"
+/// or "2 \times x" and "2*x" compare equal
+fn normalize_math(latex: &str) -> String {
+    static FRACTION: OnceLock<Regex> = OnceLock::new();
+    static SINGLE_TERM: OnceLock<Regex> = OnceLock::new();
+    let fraction = FRACTION.get_or_init(|| Regex::new(r"\\frac\{?([^{}]+?)\}?\{([^{}]+)\}").unwrap());
+    let single_term = SINGLE_TERM.get_or_init(|| Regex::new(r"\((\w+)\)").unwrap());
+    let mut text: String = latex.chars().filter(|c| !c.is_whitespace()).collect();
+    for (from, to) in [
+        ("\\left", ""),
+        ("\\right", ""),
+        ("\\,", ""),
+        ("\\!", ""),
+        ("\\dfrac", "\\frac"),
+        ("\\tfrac", "\\frac"),
+        ("\\cdot", "*"),
+        ("\\times", "*"),
+        ("×", "*"),
+        ("·", "*"),
+        ("÷", "/"),
+        ("\\div", "/"),
+        ("−", "-"),
+    ] {
+        text = text.replace(from, to);
+    }
+    let text = fraction.replace_all(&text, "($1)/($2)");
+    let text = single_term.replace_all(&text, "$1");
+    text.replace(['{', '}'], "").to_lowercase()
+}
+
+fn is_correct(question: &AnswerKeyQuestion, given: &str, latex: Option<&str>) -> bool {
+    question.answers.iter().any(|expected| match question.kind {
+        AnswerKind::Text => normalize_text(expected) == normalize_text(given),
+        AnswerKind::Number => match (parse_number(expected), parse_number(given)) {
+            (Some(expected), Some(given)) => (expected - given).abs() <= question.tolerance.unwrap_or(1e-9),
+            _ => false,
+        },
+        AnswerKind::Math => {
+            let expected = normalize_math(expected);
+            [Some(given), latex].into_iter().flatten().any(|answer| normalize_math(answer) == expected)
+        }
+    })
+}
+
+fn validate_key(key: &AnswerKey) -> Result<(), String> {
+    if key.id.trim().is_empty() || key.title.trim().is_empty() {
+        return Err("An answer key needs an id and a title".to_string());
+    }
+    if key.questions.is_empty() || key.questions.len() > MAX_QUESTIONS {
+        return Err(format!("An answer key has 1 to {} questions", MAX_QUESTIONS));
+    }
+    let mut labels = std::collections::HashSet::new();
+    for question in &key.questions {
+        let label = normalize_label(&question.number);
+        if !question_label().is_match(&format!("{}.", label)) {
+            return Err(format!("'{}' is not a question number like \"3\" or \"4b\"", question.number));
+        }
+        if !labels.insert(label) {
+            return Err(format!("Question {} is in the answer key twice", question.number));
+        }
+        if question.answers.iter().all(|answer| answer.trim().is_empty()) {
+            return Err(format!("Question {} has no answer", question.number));
+        }
+        if question.kind == AnswerKind::Number && question.answers.iter().any(|answer| parse_number(answer).is_none()) {
+            return Err(format!("The answers to question {} must be numbers", question.number));
+        }
+        if !(question.points >= 0.0 && question.points.is_finite()) {
+            return Err(format!("Question {} has invalid points", question.number));
+        }
+    }
+    Ok(())
+}
+
+/// Grade a photo or scan of a paper worksheet against a saved answer key. Without an image a
+/// webcam frame is taken (from `device_id`, or the default camera).
+#[tauri::command]
+pub async fn grade_worksheet(
+    app: AppHandle,
+    state: State<'_, WorksheetState>,
+    image_base64: Option<String>,
+    answer_key_id: String,
+    device_id: Option<String>,
+    request_id: Option<String>,
+) -> Result<WorksheetGrade, String> {
+    let key = state
+        .keys
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|key| key.id == answer_key_id)
+        .cloned()
+        .ok_or_else(|| format!("No answer key with id {}", answer_key_id))?;
+    let image_base64 = match image_base64 {
+        Some(image) => image,
+        None => crate::webcam::capture_webcam_frame(app.clone(), device_id, Some(false), request_id.clone()).await?.image_base64,
+    };
+    let image_bytes = general_purpose::STANDARD
+        .decode(image_base64.trim())
+        .map_err(|e| format!("Invalid base64 image: {}", e))?;
+
+    let operation = app.state::<CancellationState>().register(request_id.as_deref());
+    let prepared = tauri::async_runtime::spawn_blocking(move || crate::handwriting::prepare_for_ocr(&image_bytes))
+        .await
+        .map_err(|e| format!("Failed to prepare the worksheet: {}", e))??;
+    let words = crate::commands::ocr_words(&app, general_purpose::STANDARD.encode(&prepared), &operation).await?;
+    if words.is_empty() {
+        return Err("No writing was found on the worksheet".to_string());
+    }
+    let answers = read_answers(&words);
+    let needs_math = key.questions.iter().any(|question| question.kind == AnswerKind::Math);
+    let prepared_image = if needs_math {
+        Some(image::load_from_memory(&prepared).map_err(|e| format!("Failed to load image: {}", e))?)
+    } else {
+        None
+    };
+
+    let mut questions = Vec::new();
+    for question in &key.questions {
+        let label = normalize_label(&question.number);
+        let answer = answers.iter().find(|answer| answer.label == label && !answer.words.is_empty());
+        let given = answer.map(|answer| answer_text(&answer.words));
+        let mut given_latex = None;
+        if let (Some(answer), Some(image), AnswerKind::Math) = (answer, &prepared_image, question.kind) {
+            operation.check()?;
+            match crop_answer(image, &answer.words) {
+                Ok(crop) => match crate::math_ocr::recognize(&app, crop).await {
+                    Ok(latex) => given_latex = Some(latex),
+                    Err(e) => tracing::warn!("⚠️ Math OCR of question {} failed, grading its text: {}", question.number, e),
+                },
+                Err(e) => tracing::warn!("⚠️ {}", e),
+            }
+        }
+        let correct = given.as_deref().is_some_and(|given| is_correct(question, given, given_latex.as_deref()));
+        questions.push(QuestionGrade {
+            number: question.number.clone(),
+            given,
+            given_latex,
+            correct,
+            points: if correct { question.points } else { 0.0 },
+            max_points: question.points,
+        });
+    }
+
+    let score = questions.iter().map(|question| question.points).sum();
+    let max_score = questions.iter().map(|question| question.max_points).sum();
+    tracing::info!("📝 Graded worksheet '{}': {}/{}", key.title, score, max_score);
+    Ok(WorksheetGrade {
+        answer_key_id: key.id,
+        title: key.title,
+        questions,
+        score,
+        max_score,
+        ocr_text: answer_text(&words),
+        image_base64,
+    })
+}
+
+/// Answer keys without their answers
+#[tauri::command]
+pub async fn list_answer_keys(state: State<'_, WorksheetState>) -> Result<Vec<AnswerKeySummary>, String> {
+    Ok(state
+        .keys
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|key| AnswerKeySummary {
+            id: key.id.clone(),
+            title: key.title.clone(),
+            questions: key.questions.len(),
+            max_score: key.questions.iter().map(|question| question.points).sum(),
+            updated_at: key.updated_at,
+        })
+        .collect())
+}
+
+#[tauri::command]
+pub async fn get_answer_key(state: State<'_, WorksheetState>, id: String) -> Result<AnswerKey, String> {
+    state
+        .keys
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|key| key.id == id)
+        .cloned()
+        .ok_or_else(|| format!("No answer key with id {}", id))
+}
+
+/// Save an answer key, replacing the one with the same id
+#[tauri::command]
+pub async fn save_answer_key(app: AppHandle, state: State<'_, WorksheetState>, key: AnswerKey) -> Result<AnswerKey, String> {
+    validate_key(&key)?;
+    let key = AnswerKey { updated_at: chrono::Utc::now().timestamp(), ..key };
+    let mut keys = state.keys.lock().unwrap();
+    match keys.iter_mut().find(|existing| existing.id == key.id) {
+        Some(existing) => *existing = key.clone(),
+        None => keys.push(key.clone()),
+    }
+    persist(&app, &keys)?;
+    tracing::info!("📝 Saved answer key '{}' ({} questions)", key.title, key.questions.len());
+    Ok(key)
+}
+
+/// Delete an answer key. Returns whether it existed.
+#[tauri::command]
+pub async fn delete_answer_key(app: AppHandle, state: State<'_, WorksheetState>, id: String) -> Result<bool, String> {
+    let mut keys = state.keys.lock().unwrap();
+    let before = keys.len();
+    keys.retain(|key| key.id != id);
+    if keys.len() == before {
+        return Ok(false);
+    }
+    persist(&app, &keys)?;
+    Ok(true)
+}
@@ -0,0 +1,356 @@
+//! Local WebSocket server so external tools (editor plugins, CLIs) can ask
+//! "what's on screen right now". Off by default, bound to 127.0.0.1 only, and
+//! every connection must present the token: `ws://127.0.0.1:<port>/?token=<token>`.
+//!
+//! Requests are JSON `{ "id": <any>, "method": "...", "params": {...} }` and get
+//! `{ "id": <same>, "result": ... }` or `{ "id": <same>, "error": "..." }` back. Rate-limited
+//! methods share the app's limits; over the limit the error is a `RateLimited` object.
+//!
+//! With `prometheus` on, a plain HTTP `GET /metrics?token=<token>` on the same port returns
+//! the app's metrics for scraping.
+
+use futures_util::{SinkExt, StreamExt};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Manager, State};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinSet;
+use tokio_tungstenite::tungstenite::handshake::server::{ErrorResponse, Request, Response};
+use tokio_tungstenite::tungstenite::http::StatusCode;
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::commands::CaptureWindowParams;
+use crate::consent::{ConsentScope, ConsentState};
+use crate::persistence;
+use crate::rate_limit::RateLimiterState;
+use crate::screen_capture::ScreenCapture;
+
+const SETTINGS_FILE: &str = "integration_server.json";
+const DEFAULT_PORT: u16 = 17345;
+
+/// Methods external tools may call
+const METHODS: &[&str] = &[
+    "ping",
+    "capture_screen",
+    "capture_window",
+    "capture_window_with_ocr",
+    "extract_text_from_image",
+    "get_system_context",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct IntegrationServerSettings {
+    pub enabled: bool,
+    pub port: u16,
+    pub token: String,
+    /// Also serve metrics for Prometheus at `http://127.0.0.1:<port>/metrics?token=<token>`
+    #[serde(default)]
+    pub prometheus: bool,
+}
+
+impl Default for IntegrationServerSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: DEFAULT_PORT,
+            token: new_token(),
+            prometheus: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct IntegrationServerStatus {
+    pub running: bool,
+    pub url: Option<String>,
+    pub settings: IntegrationServerSettings,
+    pub methods: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcRequest {
+    #[serde(default)]
+    id: Value,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Clone, Default)]
+pub struct IntegrationServerState {
+    settings: Arc<Mutex<IntegrationServerSettings>>,
+    server: Arc<Mutex<Option<JoinHandle<()>>>>,
+}
+
+fn new_token() -> String {
+    format!("{}{}", uuid::Uuid::new_v4().simple(), uuid::Uuid::new_v4().simple())
+}
+
+fn persist(app: &AppHandle, settings: &IntegrationServerSettings) -> Result<(), String> {
+    let path = persistence::data_file(app, SETTINGS_FILE)?;
+    persistence::save_json(&path, settings)
+}
+
+fn token_from_query(query: &str) -> Option<&str> {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == "token")
+        .map(|(_, value)| value)
+}
+
+fn to_value<T: Serialize>(result: Result<T, String>) -> Result<Value, String> {
+    result.and_then(|value| serde_json::to_value(value).map_err(|e| e.to_string()))
+}
+
+async fn dispatch(app: &AppHandle, method: &str, params: Value) -> Result<Value, String> {
+    let window_params = || -> Result<CaptureWindowParams, String> {
+        serde_json::from_value(params.clone()).map_err(|e| format!("Invalid params: {}", e))
+    };
+    // External tools capture under the same consent as monitoring
+    let consent = app.state::<ConsentState>();
+
+    match method {
+        "ping" => Ok(json!("pong")),
+        "capture_screen" => {
+            consent.require(ConsentScope::Screen)?;
+            to_value(ScreenCapture::new().capture_full_screen(app).await)
+        }
+        "capture_window" => {
+            consent.require(ConsentScope::Screen)?;
+            to_value(crate::commands::capture_window(app.clone(), window_params()?).await)
+        }
+        "capture_window_with_ocr" => {
+            consent.require(ConsentScope::ScreenAndText)?;
+            to_value(crate::commands::capture_window_with_ocr(app.clone(), window_params()?).await)
+        }
+        "extract_text_from_image" => {
+            let image = params
+                .get("image_base64")
+                .and_then(Value::as_str)
+                .ok_or("Missing params.image_base64")?;
+            to_value(crate::commands::extract_text_from_image(app.clone(), image.to_string(), None).await)
+        }
+        "get_system_context" => to_value(crate::commands::get_system_context().await),
+        other => Err(format!("Unknown method '{}'. Available: {}", other, METHODS.join(", "))),
+    }
+}
+
+/// Answer a plain HTTP `GET /metrics` with the Prometheus metrics. Returns false, leaving the
+/// stream untouched, for anything else (i.e. a WebSocket handshake).
+async fn serve_metrics(app: &AppHandle, stream: &mut TcpStream, token: &str) -> bool {
+    let mut request = [0u8; 2048];
+    let Ok(read) = stream.peek(&mut request).await else {
+        return false;
+    };
+    let head = String::from_utf8_lossy(&request[..read]).into_owned();
+    let target = head.strip_prefix("GET ").and_then(|rest| rest.split_whitespace().next()).unwrap_or_default();
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    if path != "/metrics" {
+        return false;
+    }
+    // Consume the request so closing the socket doesn't reset the connection
+    let _ = stream.read(&mut request).await;
+
+    let enabled = app.state::<IntegrationServerState>().settings.lock().unwrap().prometheus;
+    let (status, body) = if !enabled {
+        ("404 Not Found", "Prometheus metrics are turned off\n".to_string())
+    } else if token_from_query(query) != Some(token) {
+        ("401 Unauthorized", "Invalid or missing token\n".to_string())
+    } else {
+        ("200 OK", crate::metrics::prometheus(app))
+    };
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+    let _ = stream.shutdown().await;
+    true
+}
+
+async fn handle_connection(app: AppHandle, mut stream: TcpStream, token: String) {
+    if serve_metrics(&app, &mut stream, &token).await {
+        return;
+    }
+
+    // The error type is fixed by tungstenite's handshake callback
+    #[allow(clippy::result_large_err)]
+    let authorize = |request: &Request, response: Response| -> Result<Response, ErrorResponse> {
+        let presented = request.uri().query().and_then(token_from_query);
+        if presented == Some(token.as_str()) {
+            Ok(response)
+        } else {
+            let mut error = ErrorResponse::new(Some("Invalid or missing token".to_string()));
+            *error.status_mut() = StatusCode::UNAUTHORIZED;
+            Err(error)
+        }
+    };
+
+    let socket = match tokio_tungstenite::accept_hdr_async(stream, authorize).await {
+        Ok(socket) => socket,
+        Err(e) => {
+            tracing::info!("🚫 Rejected connection: {}", e);
+            return;
+        }
+    };
+    let (mut sink, mut stream) = socket.split();
+
+    while let Some(message) = stream.next().await {
+        let text = match message {
+            Ok(Message::Text(text)) => text,
+            Ok(Message::Close(_)) | Err(_) => break,
+            Ok(_) => continue,
+        };
+
+        let reply = match serde_json::from_str::<RpcRequest>(&text) {
+            Ok(request) => {
+                tracing::info!("📨 {}", request.method);
+                match app.state::<RateLimiterState>().check(&request.method) {
+                    Err(limited) => json!({ "id": request.id, "error": limited }),
+                    Ok(()) => match dispatch(&app, &request.method, request.params).await {
+                        Ok(result) => json!({ "id": request.id, "result": result }),
+                        Err(error) => json!({ "id": request.id, "error": error }),
+                    },
+                }
+            }
+            Err(e) => json!({ "id": Value::Null, "error": format!("Malformed request: {}", e) }),
+        };
+        if sink.send(Message::Text(reply.to_string())).await.is_err() {
+            break;
+        }
+    }
+}
+
+/// Accept connections until aborted. Connections live in a `JoinSet` owned by this
+/// future, so stopping the server also drops every open connection.
+async fn serve(app: AppHandle, listener: TcpListener, token: String) {
+    let mut connections = JoinSet::new();
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => {
+                    connections.spawn(handle_connection(app.clone(), stream, token.clone()));
+                }
+                Err(e) => tracing::warn!("⚠️ Accept failed: {}", e),
+            },
+            Some(_) = connections.join_next() => {}
+        }
+    }
+}
+
+fn stop_server(state: &IntegrationServerState) {
+    if let Some(handle) = state.server.lock().unwrap().take() {
+        handle.abort();
+        tracing::info!("⏹️ Integration server stopped");
+    }
+}
+
+async fn start_server(app: &AppHandle, state: &IntegrationServerState) -> Result<(), String> {
+    stop_server(state);
+    let (port, token) = {
+        let settings = state.settings.lock().unwrap();
+        (settings.port, settings.token.clone())
+    };
+    let listener = TcpListener::bind(("127.0.0.1", port))
+        .await
+        .map_err(|e| format!("Failed to listen on 127.0.0.1:{}: {}", port, e))?;
+    tracing::info!("🔌 Integration server listening on ws://127.0.0.1:{}", port);
+    let handle = tauri::async_runtime::spawn(serve(app.clone(), listener, token));
+    *state.server.lock().unwrap() = Some(handle);
+    Ok(())
+}
+
+fn status(state: &IntegrationServerState) -> IntegrationServerStatus {
+    let settings = state.settings.lock().unwrap().clone();
+    let running = state.server.lock().unwrap().is_some();
+    IntegrationServerStatus {
+        running,
+        url: running.then(|| format!("ws://127.0.0.1:{}/?token={}", settings.port, settings.token)),
+        settings,
+        methods: METHODS.iter().map(|m| m.to_string()).collect(),
+    }
+}
+
+/// Load settings and start the server if it was enabled in a previous run
+pub fn init(app: AppHandle) {
+    let state = app.state::<IntegrationServerState>().inner().clone();
+    if let Ok(path) = persistence::data_file(&app, SETTINGS_FILE) {
+        let settings: IntegrationServerSettings = persistence::load_json(&path);
+        if let Err(e) = persistence::save_json(&path, &settings) {
+            tracing::warn!("⚠️ {}", e);
+        }
+        *state.settings.lock().unwrap() = settings;
+    }
+
+    if state.settings.lock().unwrap().enabled {
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = start_server(&app, &state).await {
+                tracing::warn!("⚠️ {}", e);
+            }
+        });
+    }
+}
+
+#[tauri::command]
+pub async fn get_integration_server_status(
+    state: State<'_, IntegrationServerState>,
+) -> Result<IntegrationServerStatus, String> {
+    Ok(status(&state))
+}
+
+/// Turn the local integration server on or off, and its Prometheus metrics with `prometheus`
+#[tauri::command]
+pub async fn set_integration_server(
+    app: AppHandle,
+    state: State<'_, IntegrationServerState>,
+    enabled: bool,
+    port: Option<u16>,
+    prometheus: Option<bool>,
+) -> Result<IntegrationServerStatus, String> {
+    {
+        let mut settings = state.settings.lock().unwrap();
+        settings.enabled = enabled;
+        if let Some(prometheus) = prometheus {
+            settings.prometheus = prometheus;
+        }
+        if let Some(port) = port {
+            if port < 1024 {
+                return Err("Port must be 1024 or higher".to_string());
+            }
+            settings.port = port;
+        }
+        persist(&app, &settings)?;
+    }
+
+    if enabled {
+        start_server(&app, &state).await?;
+    } else {
+        stop_server(&state);
+    }
+    Ok(status(&state))
+}
+
+/// Replace the access token, disconnecting tools that use the old one
+#[tauri::command]
+pub async fn regenerate_integration_token(
+    app: AppHandle,
+    state: State<'_, IntegrationServerState>,
+) -> Result<IntegrationServerStatus, String> {
+    let enabled = {
+        let mut settings = state.settings.lock().unwrap();
+        settings.token = new_token();
+        persist(&app, &settings)?;
+        settings.enabled
+    };
+    if enabled {
+        start_server(&app, &state).await?;
+    }
+    Ok(status(&state))
+}
@@ -2,7 +2,11 @@ import { useState, useEffect } from "react";
 import { useChat } from "../hooks/useChat";
 import { useScreenCapture } from "../hooks/useScreenCapture";
 import { initializeGemini } from "../services/gemini";
-import { startMonitoring, stopMonitoring } from "../services/screenCapture";
+import {
+  grantMonitoringConsent,
+  revokeMonitoringConsent,
+  startMonitoring,
+} from "../services/screenCapture";
 import { MessageList } from "./MessageList";
 import { MessageInput } from "./MessageInput";
 import { Settings } from "./Settings";
@@ -46,17 +50,12 @@ export function ChatInterface() {
         const stored = localStorage.getItem("ai-teacher-settings");
         if (stored) {
           const parsed = JSON.parse(stored);
-          // Ensure screenCaptureEnabled exists (for backward compatibility)
-          if (parsed.screenCaptureEnabled === undefined) {
-            parsed.screenCaptureEnabled = false;
-          }
+          // Monitoring consent ends when the app exits, so capture starts off until re-enabled
+          parsed.screenCaptureEnabled = false;
           setSettings(parsed);
           if (parsed.geminiApiKey) {
             initializeGemini(parsed.geminiApiKey);
             setApiKeySet(true);
-            if (parsed.screenCaptureEnabled) {
-              await startMonitoring();
-            }
           }
         } else if (DEFAULT_API_KEY) {
           // Use default API key if no stored settings
@@ -102,10 +101,12 @@ export function ChatInterface() {
       initializeGemini(newSettings.geminiApiKey);
       setApiKeySet(true);
       // Start or stop monitoring based on setting
+      // Turning capture on in settings is the student's consent to being monitored
       if (newSettings.screenCaptureEnabled) {
+        await grantMonitoringConsent("screen");
         await startMonitoring();
       } else {
-        await stopMonitoring();
+        await revokeMonitoringConsent();
       }
     }
     if (newSettings.captureInterval !== settings.captureInterval) {
@@ -2,15 +2,21 @@ import { useState, useEffect, useCallback } from "react";
 import { sendMessageWithVision } from "../services/gemini";
 import { executeCommand } from "../services/screenCapture";
 import {
-  saveConversation,
   getConversation,
   createConversation,
   addMessageToConversation,
+  updateConversationMessage,
+  branchConversation,
   getCurrentConversationId,
   setCurrentConversationId,
 } from "../services/storage";
 import type { Message, Conversation, PendingCommandRequest } from "../types";
 
+// Messages get a numeric id once stored; error replies and unsent edits never are
+function isStoredId(id: string): boolean {
+  return /^\d+$/.test(id);
+}
+
 export function useChat(_screenshots: string[]) {
   const [conversation, setConversation] = useState<Conversation | null>(null);
   const [messages, setMessages] = useState<Message[]>([]);
@@ -51,25 +57,25 @@ export function useChat(_screenshots: string[]) {
     void loadCurrentConversation();
   }, [loadCurrentConversation]);
 
-  const persistMessages = useCallback(
-    (updated: Message[]) => {
-      if (!conversation) return;
-      const updatedConv: Conversation = { ...conversation, messages: updated };
-      setConversation(updatedConv);
-      saveConversation(updatedConv);
-    },
-    [conversation]
-  );
-
   const sendMessage = useCallback(
     async (content: string, fromMessageIndex?: number) => {
       if (!content.trim() || isLoading) return;
 
-      // If fromMessageIndex is provided, truncate messages from that point
+      // If fromMessageIndex is provided, continue in a branch holding the messages before it,
+      // so the stored original keeps its later messages
       let messagesToUse = messages;
+      let activeConversation = conversation;
       if (fromMessageIndex !== undefined && fromMessageIndex >= 0) {
         messagesToUse = messages.slice(0, fromMessageIndex);
         setMessages(messagesToUse);
+        if (conversation) {
+          const lastKept = messagesToUse.filter((m) => isStoredId(m.id)).pop();
+          activeConversation = lastKept
+            ? await branchConversation(conversation.id, lastKept.id)
+            : await createConversation("New Conversation");
+          setConversation(activeConversation);
+          await setCurrentConversationId(activeConversation.id);
+        }
       }
 
       setIsLoading(true);
@@ -133,9 +139,15 @@ export function useChat(_screenshots: string[]) {
 
         setMessages((prev) => [...prev, assistantMessage]);
 
-        if (conversation) {
-          await addMessageToConversation(conversation.id, userMessage);
-          await addMessageToConversation(conversation.id, assistantMessage);
+        if (activeConversation) {
+          // Swap in the stored ids so later edits and branches can refer to the messages
+          const storedUser = await addMessageToConversation(activeConversation.id, userMessage);
+          const storedAssistant = await addMessageToConversation(activeConversation.id, assistantMessage);
+          setMessages((prev) =>
+            prev.map((m) =>
+              m.id === userMessage.id ? storedUser : m.id === assistantMessage.id ? storedAssistant : m
+            )
+          );
         }
       } catch (error: any) {
         console.error("Error sending message:", error);
@@ -162,27 +174,20 @@ export function useChat(_screenshots: string[]) {
     await setCurrentConversationId(newConv.id);
   }, []);
 
-  const updateMessage = useCallback(
-    (messageId: string, newContent: string) => {
-      setMessages((prev) => {
-        const updated = prev.map((m) =>
-          m.id === messageId ? { ...m, content: newContent } : m
-        );
-        persistMessages(updated);
-        return updated;
-      });
-    },
-    [persistMessages]
-  );
+  const updateMessage = useCallback((messageId: string, newContent: string) => {
+    setMessages((prev) =>
+      prev.map((m) => (m.id === messageId ? { ...m, content: newContent } : m))
+    );
+    if (!isStoredId(messageId)) return;
+    updateConversationMessage(messageId, newContent).catch((error) =>
+      console.error("Failed to save edited message:", error)
+    );
+  }, []);
 
+  // The cleared transcript stays in the history; the chat continues in a new conversation
   const clearChat = useCallback(async () => {
-    setMessages([]);
-    if (conversation) {
-      const updatedConv: Conversation = { ...conversation, messages: [] };
-      setConversation(updatedConv);
-      saveConversation(updatedConv);
-    }
-  }, [conversation]);
+    await createNewConversation();
+  }, [createNewConversation]);
 
   const approveCommandRequest = useCallback(
     async (requestId: string) => {
@@ -0,0 +1,120 @@
+import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import type {
+  AiBudgetSettings,
+  AiComplete,
+  AiError,
+  AiTool,
+  AiToken,
+  AiUsageReport,
+  BudgetExceeded,
+  ChatRequest,
+  ChatResponse,
+  ProviderConfig,
+  ProviderInfo,
+  ProviderTest,
+  ToolChatResponse,
+} from "../types";
+
+/** Configured providers in failover order */
+export async function getAiProviders(): Promise<ProviderInfo[]> {
+  return await invoke<ProviderInfo[]>("get_ai_providers");
+}
+
+/** Replace the provider list; its order is the failover order (teacher mode only) */
+export async function setAiProviders(providers: ProviderConfig[]): Promise<void> {
+  await invoke("set_ai_providers", { providers });
+}
+
+/** Store a provider's API key in the OS keychain, or remove it with null (teacher mode only) */
+export async function setProviderApiKey(id: string, apiKey: string | null): Promise<void> {
+  await invoke("set_provider_api_key", { id, apiKey });
+}
+
+export async function testProvider(id: string): Promise<ProviderTest> {
+  return await invoke<ProviderTest>("test_provider", { id });
+}
+
+/** Answer with the first enabled provider that responds */
+export async function aiChat(request: ChatRequest): Promise<ChatResponse> {
+  return await invoke<ChatResponse>("ai_chat", { request });
+}
+
+export async function listAiTools(): Promise<AiTool[]> {
+  return await invoke<AiTool[]>("list_ai_tools");
+}
+
+/**
+ * Answer letting the AI capture windows, read project files and run allowlisted commands.
+ * Each call is checked and run in the backend; `tools` narrows what is offered.
+ */
+export async function aiChatWithTools(
+  request: ChatRequest,
+  tools?: string[],
+  maxRounds?: number
+): Promise<ToolChatResponse> {
+  return await invoke<ToolChatResponse>("ai_chat_with_tools", {
+    request,
+    tools: tools ?? null,
+    maxRounds: maxRounds ?? null,
+  });
+}
+
+export interface StreamHandlers {
+  onToken: (text: string) => void;
+  onComplete: (response: ChatResponse) => void;
+  onError: (error: string) => void;
+}
+
+/**
+ * Stream an answer from the first enabled provider that responds. Text arrives in batches
+ * every ~50ms; the returned function stops listening. Cancel with cancel_operation and the id.
+ */
+export async function streamChat(
+  request: ChatRequest,
+  handlers: StreamHandlers,
+  requestId: string = crypto.randomUUID()
+): Promise<{ requestId: string; stop: UnlistenFn }> {
+  // Subscribe before starting so no early token is missed
+  const unlisteners = await Promise.all([
+    listen<AiToken>("ai-token", (event) => {
+      if (event.payload.request_id === requestId) handlers.onToken(event.payload.text);
+    }),
+    listen<AiComplete>("ai-complete", (event) => {
+      if (event.payload.request_id !== requestId) return;
+      stop();
+      handlers.onComplete(event.payload.response);
+    }),
+    listen<AiError>("ai-error", (event) => {
+      if (event.payload.request_id !== requestId) return;
+      stop();
+      handlers.onError(event.payload.error);
+    }),
+  ]);
+  const stop = () => unlisteners.forEach((unlisten) => unlisten());
+  try {
+    await invoke<string>("ai_chat_stream", { request, requestId });
+  } catch (error) {
+    stop();
+    throw error;
+  }
+  return { requestId, stop };
+}
+
+/** Tokens and estimated cost between two Unix timestamps (seconds); defaults to this month */
+export async function getAiUsage(from?: number, to?: number): Promise<AiUsageReport> {
+  return await invoke<AiUsageReport>("get_ai_usage", { range: { from: from ?? null, to: to ?? null } });
+}
+
+export async function getAiBudget(): Promise<AiBudgetSettings> {
+  return await invoke<AiBudgetSettings>("get_ai_budget");
+}
+
+/** Change the monthly limit and model prices (teacher mode only) */
+export async function setAiBudget(settings: AiBudgetSettings): Promise<void> {
+  await invoke("set_ai_budget", { settings });
+}
+
+export async function onBudgetExceeded(handler: (event: BudgetExceeded) => void): Promise<UnlistenFn> {
+  return await listen<BudgetExceeded>("budget-exceeded", (event) => handler(event.payload));
+}
@@ -110,20 +110,6 @@ const contextOnlyRules: CommandRule[] = [
     category: "critical",
     reason: "Runs project scripts that may be long running or mutate cache.",
   },
-  {
-    command: "node",
-    argsPrefix: ["--version"],
-    level: "auto",
-    category: "context",
-    reason: "Reports installed Node.js version.",
-  },
-  {
-    command: "python",
-    argsPrefix: ["--version"],
-    level: "auto",
-    category: "context",
-    reason: "Reports installed Python version.",
-  },
   {
     command: "pwsh",
     argsPrefix: ["-Command", "Get-Process"],
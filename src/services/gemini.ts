@@ -1,6 +1,7 @@
 import { GoogleGenerativeAI } from "@google/generative-ai";
 import type { Message, SystemContext, PendingCommandRequest } from "../types";
 import systemPromptConfig from "../config/system-prompt.json";
+import { getCustomTutorPrompt } from "./prompts";
 import {
   analyzeConversationHistory,
   createContextSummary,
@@ -8,7 +9,17 @@ import {
   injectDynamicContext,
 } from "./contextManager";
 import { analyzeScreenshotForLearningNeeds, type ScreenshotAnalysis } from "./screenshotAnalysis";
-import { getSystemContext, captureWindowWithOCR, listWindowsByProcess, executeCommand } from "./screenCapture";
+import {
+  getSystemContext,
+  captureWindowWithOCR,
+  listWindowsByProcess,
+  executeCommand,
+  detectDevEnvironment,
+  getClipboardImage,
+  parseDiagnostics,
+  assembleContext,
+  filterSecrets,
+} from "./screenCapture";
 import { evaluateCommandPolicy } from "./commandPolicy";
 
 let genAI: GoogleGenerativeAI | null = null;
@@ -692,13 +703,40 @@ async function handleFunctionCall(
           process_name: result.process_name,
         });
         
+        // Clean, structured errors are easier for the model than raw OCR text
+        const diagnostics = result.ocr_text
+          ? await parseDiagnostics(result.ocr_text).catch(() => [])
+          : [];
+
+        // Long OCR text (a scrolled-back terminal, a whole document) is cut to the token budget
+        const budgetedText = result.ocr_text
+          ? await assembleContext([
+              { label: result.window_title || "Captured window", text: result.ocr_text, active_window: true },
+            ])
+              .then((context) => context.text)
+              .catch(() => result.ocr_text)
+          : "";
+        // Keys and passwords visible on screen never reach the model; without the filter the
+        // text isn't sent at all
+        const filtered = budgetedText
+          ? await filterSecrets(budgetedText).catch(() => ({ text: "", redactions: [] }))
+          : { text: "", redactions: [] };
+        const windowTitle = await filterSecrets(result.window_title || "")
+          .then((title) => title.text)
+          .catch(() => "");
+        if (filtered.redactions.length > 0) {
+          console.log(`[Gemini] 🔒 Masked ${filtered.redactions.length} secret(s) in OCR text`);
+        }
+
         return {
           name: functionName,
           response: {
             success: true,
             image_base64: result.image_base64,
-            ocr_text: result.ocr_text || "",
-            window_title: result.window_title,
+            ocr_text: filtered.text,
+            redactions: filtered.redactions,
+            diagnostics,
+            window_title: windowTitle,
             process_name: result.process_name,
             analysis: result.analysis || null,
           },
@@ -795,6 +833,39 @@ async function handleFunctionCall(
         }
       }
 
+      case "detect_dev_environment": {
+        onStatusUpdate?.("Checking installed tools...");
+        const environment = await detectDevEnvironment();
+        return {
+          name: functionName,
+          response: { success: true, ...environment },
+        };
+      }
+
+      case "get_clipboard_image": {
+        onStatusUpdate?.("Reading the clipboard...");
+        const image = await getClipboardImage(true);
+        if (!image) {
+          return {
+            name: functionName,
+            response: {
+              success: false,
+              error: "The clipboard doesn't contain an image. Ask the student to copy or snip the screenshot again.",
+            },
+          };
+        }
+        return {
+          name: functionName,
+          response: {
+            success: true,
+            image_base64: image.image_base64,
+            ocr_text: image.ocr_text || "",
+            width: image.width,
+            height: image.height,
+          },
+        };
+      }
+
       default:
         return {
           name: functionName,
@@ -941,7 +1012,8 @@ export async function sendMessageWithVision(
 
   // System prompt with dynamic context injection
   // Always inject context - even for new conversations, this helps guide the AI to ask contextual questions
-  let systemPrompt = systemPromptConfig.systemPrompt;
+  // A saved "tutor" prompt template replaces the bundled one
+  let systemPrompt = (await getCustomTutorPrompt()) ?? systemPromptConfig.systemPrompt;
   // Always inject context to guide teaching approach, even for new conversations
   // Include screenshot analysis insights, system context, and messages for greeting detection
   systemPrompt = injectDynamicContext(systemPrompt, conversationState, messages, contextSummary, screenshotAnalysis, systemContext);
@@ -1058,6 +1130,18 @@ Use this tool whenever terminal output is the source of truth (Docker, git, logs
                 required: ["command"],
               },
             },
+            {
+              name: "detect_dev_environment",
+              description: `List the installed developer toolchains (node, npm, python, rustc, cargo, java, docker, git) with their versions and PATH locations, including copies shadowed further down PATH.
+
+Use this instead of running version commands whenever the question depends on what is installed (e.g. "which Node version do I have?", "why does python not run?").`,
+            },
+            {
+              name: "get_clipboard_image",
+              description: `Read the image on the clipboard with its OCR text, e.g. a screenshot the student just took with the Snipping Tool or Print Screen.
+
+Use this when the student says they copied, snipped or pasted a screenshot, instead of capturing the screen again.`,
+            },
           ],
         },
       ];
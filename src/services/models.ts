@@ -0,0 +1,49 @@
+import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import type {
+  LoadedModelStatus,
+  LocalModel,
+  ModelDownloadProgress,
+  ModelDownloadRequest,
+  ModelVerification,
+} from "../types";
+
+export async function listLocalModels(): Promise<LocalModel[]> {
+  return await invoke<LocalModel[]>("list_local_models");
+}
+
+/**
+ * Download a GGUF model, resuming a partial download, and keep it once its SHA-256 matches
+ * (teacher mode only). Cancel with cancel_operation and the request id; the partial file stays.
+ */
+export async function downloadModel(request: ModelDownloadRequest, requestId?: string): Promise<LocalModel> {
+  return await invoke<LocalModel>("download_model", { request, requestId: requestId ?? null });
+}
+
+export async function onModelDownloadProgress(
+  handler: (progress: ModelDownloadProgress) => void
+): Promise<UnlistenFn> {
+  return await listen<ModelDownloadProgress>("model-download-progress", (event) => handler(event.payload));
+}
+
+export async function verifyModel(id: string): Promise<ModelVerification> {
+  return await invoke<ModelVerification>("verify_model", { id });
+}
+
+/** Delete a model or its partial download (teacher mode only) */
+export async function deleteModel(id: string): Promise<void> {
+  await invoke("delete_model", { id });
+}
+
+/** Load a model in-process for the llamacpp provider, replacing the one loaded */
+export async function startLocalModel(id: string): Promise<LoadedModelStatus> {
+  return await invoke<LoadedModelStatus>("start_local_model", { id });
+}
+
+export async function stopLocalModel(): Promise<void> {
+  await invoke("stop_local_model");
+}
+
+export async function getLoadedLocalModel(): Promise<LoadedModelStatus | null> {
+  return await invoke<LoadedModelStatus | null>("get_loaded_local_model");
+}
@@ -0,0 +1,50 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { PromptTemplate } from "../types";
+
+/** Stored template that replaces the bundled tutor system prompt */
+export const TUTOR_PROMPT_NAME = "tutor";
+
+export async function listPrompts(): Promise<PromptTemplate[]> {
+  return await invoke<PromptTemplate[]>("list_prompts");
+}
+
+export async function getPrompt(name: string, version?: number): Promise<PromptTemplate> {
+  return await invoke<PromptTemplate>("get_prompt", { name, version: version ?? null });
+}
+
+export async function listPromptVersions(name: string): Promise<PromptTemplate[]> {
+  return await invoke<PromptTemplate[]>("list_prompt_versions", { name });
+}
+
+/** Save as a new version; restoring an old version is saving its body again */
+export async function savePrompt(
+  name: string,
+  body: string,
+  description = "",
+  defaults: Record<string, string> = {}
+): Promise<PromptTemplate> {
+  return await invoke<PromptTemplate>("save_prompt", {
+    template: { name, body, description, defaults },
+  });
+}
+
+export async function deletePrompt(name: string): Promise<void> {
+  await invoke("delete_prompt", { name });
+}
+
+export async function renderPrompt(
+  name: string,
+  vars: Record<string, string> = {},
+  version?: number
+): Promise<string> {
+  return await invoke<string>("render_prompt", { name, vars, version: version ?? null });
+}
+
+/** The customized tutor system prompt, or null when none is saved */
+export async function getCustomTutorPrompt(): Promise<string | null> {
+  try {
+    return await renderPrompt(TUTOR_PROMPT_NAME);
+  } catch {
+    return null;
+  }
+}
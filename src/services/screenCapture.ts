@@ -6,7 +6,29 @@ import type {
   SystemContext,
   WindowCaptureResult,
   WindowInfo,
+  Definition,
+  TextAnalysis,
+  TypingSessionStats,
+  AnswerKey,
+  AnswerKeySummary,
+  WorksheetGrade,
+  DictionaryImport,
   CommandResult,
+  DevEnvironment,
+  ClipboardImage,
+  ParsedDiagnostic,
+  TokenCount,
+  ContextSection,
+  AssembledContext,
+  FilteredText,
+  MonitorTarget,
+  Narration,
+  NarratorInfo,
+  NarratorOptions,
+  Translation,
+  BenchmarkReport,
+  Metrics,
+  CaptureMemoryUsage,
 } from "../types";
 import { analyzeWindowCapture } from "./windowAnalysis";
 
@@ -26,6 +48,42 @@ export async function stopMonitoring(): Promise<void> {
   return await invoke("stop_monitoring");
 }
 
+/**
+ * Record the student's consent to monitoring; `start_monitoring` fails without it.
+ * Consent lasts for `durationMinutes`, or until the app exits when omitted.
+ */
+export async function grantMonitoringConsent(
+  scope: "screen" | "screen_and_text",
+  durationMinutes?: number
+): Promise<void> {
+  await invoke("grant_monitoring_consent", { scope, durationMinutes });
+}
+
+export async function revokeMonitoringConsent(): Promise<void> {
+  await invoke("revoke_monitoring_consent");
+}
+
+/**
+ * Read new text in `target` aloud with the system voice, or an AI summary of it. Needs
+ * screen-and-text consent; each narration also arrives as a `narration` event.
+ */
+export async function startNarrator(
+  target: MonitorTarget,
+  options?: Partial<NarratorOptions>
+): Promise<NarratorInfo> {
+  return await invoke<NarratorInfo>("start_narrator", { target, options: options ?? null });
+}
+
+export async function stopNarrator(): Promise<boolean> {
+  return await invoke<boolean>("stop_narrator");
+}
+
+export function listenToNarrations(callback: (narration: Narration) => void): Promise<() => void> {
+  return listen<Narration>("narration", (event) => {
+    callback(event.payload);
+  });
+}
+
 export async function getCaptureInterval(): Promise<number> {
   return await invoke<number>("get_capture_interval");
 }
@@ -54,6 +112,24 @@ export async function getSystemContext(): Promise<SystemContext> {
   return await invoke<SystemContext>("get_system_context");
 }
 
+export async function detectDevEnvironment(): Promise<DevEnvironment> {
+  return await invoke<DevEnvironment>("detect_dev_environment");
+}
+
+/**
+ * Structured compiler and runtime errors found in OCR or terminal text
+ */
+export async function parseDiagnostics(text: string): Promise<ParsedDiagnostic[]> {
+  return await invoke<ParsedDiagnostic[]>("parse_diagnostics", { text });
+}
+
+/**
+ * Read the image on the clipboard, optionally with its OCR text. Null when there is none.
+ */
+export async function getClipboardImage(ocr = false): Promise<ClipboardImage | null> {
+  return await invoke<ClipboardImage | null>("get_clipboard_image", { ocr });
+}
+
 /**
  * Capture a specific window by process name or window title
  */
@@ -82,11 +158,14 @@ export async function captureWindow(options?: {
 export async function captureWindowWithOCR(options?: {
   processName?: string;
   windowTitle?: string;
+  /** Also translate the OCR text into this language, e.g. "en" */
+  translateTo?: string;
 }): Promise<WindowCaptureResult> {
   // Build the params object for the struct
   const params: {
     process_name?: string;
     window_title?: string;
+    translate_to?: string;
   } = {};
   
   if (options?.processName !== undefined && options?.processName !== null) {
@@ -95,6 +174,9 @@ export async function captureWindowWithOCR(options?: {
   if (options?.windowTitle !== undefined && options?.windowTitle !== null) {
     params.window_title = options.windowTitle;
   }
+  if (options?.translateTo) {
+    params.translate_to = options.translateTo;
+  }
   
   console.log(`[screenCapture] Invoking capture_window_with_ocr with options:`, JSON.stringify(params));
   
@@ -170,3 +252,109 @@ export async function executeCommand(command: string, args: string[] = []): Prom
   return await invoke<CommandResult>("execute_command", { command, args });
 }
 
+/**
+ * Estimate the tokens a text uses for a model (the configured model when omitted)
+ */
+export async function countTokens(text: string, model?: string): Promise<TokenCount> {
+  return await invoke<TokenCount>("count_tokens", { text, model: model ?? null });
+}
+
+/**
+ * Join context sections within the token budget, active window and newest sections first
+ */
+export async function assembleContext(
+  sections: ContextSection[],
+  maxTokens?: number
+): Promise<AssembledContext> {
+  return await invoke<AssembledContext>("assemble_context", {
+    sections,
+    maxTokens: maxTokens ?? null,
+  });
+}
+
+/**
+ * Mask API keys, passwords and other secrets before text is sent to an AI provider
+ */
+export async function filterSecrets(text: string): Promise<FilteredText> {
+  return await invoke<FilteredText>("filter_secrets", { text });
+}
+
+/** Translate text into `targetLang` ("en", "pt-BR", ...) with the configured backend */
+export async function translateText(text: string, targetLang: string): Promise<Translation> {
+  return await invoke<Translation>("translate_text", { text, targetLang });
+}
+
+/** Reading level, key terms and a short summary of `text`, computed without the AI */
+export async function analyzeText(text: string, summarySentences?: number): Promise<TextAnalysis> {
+  return await invoke<TextAnalysis>("analyze_text", { text, summarySentences });
+}
+
+/** Start a typing lesson in the practice window; its passage is read with OCR */
+export async function startTypingSession(options: {
+  windowTitle?: string;
+  processName?: string;
+}): Promise<TypingSessionStats> {
+  return await invoke<TypingSessionStats>("start_typing_session", options);
+}
+
+export async function getTypingSessionStats(): Promise<TypingSessionStats | null> {
+  return await invoke<TypingSessionStats | null>("get_typing_session_stats");
+}
+
+export async function stopTypingSession(): Promise<TypingSessionStats | null> {
+  return await invoke<TypingSessionStats | null>("stop_typing_session");
+}
+
+/** Grade a paper worksheet; without `imageBase64` a webcam frame is taken */
+export async function gradeWorksheet(
+  answerKeyId: string,
+  options?: { imageBase64?: string; deviceId?: string; requestId?: string }
+): Promise<WorksheetGrade> {
+  return await invoke<WorksheetGrade>("grade_worksheet", { answerKeyId, ...options });
+}
+
+export async function listAnswerKeys(): Promise<AnswerKeySummary[]> {
+  return await invoke<AnswerKeySummary[]>("list_answer_keys");
+}
+
+export async function getAnswerKey(id: string): Promise<AnswerKey> {
+  return await invoke<AnswerKey>("get_answer_key", { id });
+}
+
+export async function saveAnswerKey(key: AnswerKey): Promise<AnswerKey> {
+  return await invoke<AnswerKey>("save_answer_key", { key });
+}
+
+export async function deleteAnswerKey(id: string): Promise<boolean> {
+  return await invoke<boolean>("delete_answer_key", { id });
+}
+
+/** Time capture, encoding and OCR on this machine; percentiles per stage */
+export async function runBenchmark(iterations?: number, requestId?: string): Promise<BenchmarkReport> {
+  return await invoke<BenchmarkReport>("run_benchmark", { iterations, requestId });
+}
+
+/** Capture, OCR and AI call latencies and counters since the app started */
+export async function getMetrics(): Promise<Metrics> {
+  return await invoke<Metrics>("get_metrics");
+}
+
+/** Memory and disk used by buffered capture frames */
+export async function getCaptureMemoryUsage(): Promise<CaptureMemoryUsage> {
+  return await invoke<CaptureMemoryUsage>("get_capture_memory_usage");
+}
+
+/** Cap the memory buffered frames may use (1 to 1024 MB); older frames past it move to disk */
+export async function setCaptureMemoryCap(memoryCapMb: number): Promise<CaptureMemoryUsage> {
+  return await invoke<CaptureMemoryUsage>("set_capture_memory_cap", { memoryCapMb });
+}
+
+/** Define a word, e.g. one double-clicked in a capture; offline first, then Wiktionary */
+export async function defineTerm(word: string, lang: string, onlineFallback?: boolean): Promise<Definition> {
+  return await invoke<Definition>("define_term", { word, lang, onlineFallback });
+}
+
+/** Fill the offline dictionary for `lang` from a kaikki.org Wiktionary JSON-lines extract */
+export async function importDictionary(path: string, lang: string): Promise<DictionaryImport> {
+  return await invoke<DictionaryImport>("import_dictionary", { path, lang });
+}
@@ -1,5 +1,12 @@
+import { invoke } from "@tauri-apps/api/core";
 import { Store } from "@tauri-apps/plugin-store";
-import type { Conversation, Message } from "../types";
+import type {
+  Conversation,
+  ConversationSummary,
+  Message,
+  StoredConversation,
+  StoredMessage,
+} from "../types";
 
 // Initialize store - use Store.load() to create and load the store
 let storeInstance: Store | null = null;
@@ -11,71 +18,140 @@ async function getStore(): Promise<Store> {
   return storeInstance;
 }
 
+// Conversations live in the backend's history database; the store only remembers which one is
+// open. Transcripts saved by older versions under CONVERSATIONS_KEY are moved over once.
 const CONVERSATIONS_KEY = "conversations";
 const CURRENT_CONVERSATION_KEY = "current_conversation_id";
 
-export async function saveConversation(conversation: Conversation): Promise<void> {
-  const store = await getStore();
-  const conversations = await getConversations();
-  const index = conversations.findIndex((c) => c.id === conversation.id);
-  
-  if (index >= 0) {
-    conversations[index] = conversation;
-  } else {
-    conversations.push(conversation);
+function toMessage(message: StoredMessage): Message {
+  return {
+    id: String(message.id),
+    role: message.role,
+    content: message.content,
+    timestamp: message.created_at * 1000,
+  };
+}
+
+function toConversation(summary: ConversationSummary, messages: StoredMessage[] = []): Conversation {
+  return {
+    id: String(summary.id),
+    title: summary.title,
+    messages: messages.map(toMessage),
+    createdAt: summary.created_at * 1000,
+    updatedAt: summary.updated_at * 1000,
+  };
+}
+
+async function storeConversation(conversation: Conversation): Promise<Conversation> {
+  const created = await invoke<StoredConversation>("create_conversation", {
+    title: conversation.title || null,
+  });
+  for (const message of conversation.messages) {
+    await invoke<StoredMessage>("append_message", {
+      conversationId: created.id,
+      message: {
+        role: message.role,
+        content: message.content,
+        created_at: Math.floor(message.timestamp / 1000),
+      },
+    });
   }
-  
-  await store.set(CONVERSATIONS_KEY, conversations);
-  await store.save();
+  return getConversationById(created.id);
+}
+
+let legacyMigration: Promise<void> | null = null;
+
+function migrateLegacyConversations(): Promise<void> {
+  if (!legacyMigration) {
+    legacyMigration = (async () => {
+      const store = await getStore();
+      const legacy = await store.get<Conversation[]>(CONVERSATIONS_KEY);
+      if (!legacy) return;
+      const currentId = await store.get<string>(CURRENT_CONVERSATION_KEY);
+      for (const conversation of legacy) {
+        const stored = await storeConversation(conversation);
+        if (conversation.id === currentId) {
+          await store.set(CURRENT_CONVERSATION_KEY, stored.id);
+        }
+      }
+      await store.delete(CONVERSATIONS_KEY);
+      await store.save();
+    })().catch((error) => {
+      legacyMigration = null;
+      throw error;
+    });
+  }
+  return legacyMigration;
+}
+
+async function getConversationById(id: number): Promise<Conversation> {
+  const stored = await invoke<StoredConversation>("get_conversation", { id });
+  return toConversation(stored, stored.messages);
 }
 
+/** Conversations without their messages, most recently active first */
 export async function getConversations(): Promise<Conversation[]> {
-  const store = await getStore();
-  const conversations = await store.get<Conversation[]>(CONVERSATIONS_KEY);
-  return conversations || [];
+  await migrateLegacyConversations();
+  const summaries = await invoke<ConversationSummary[]>("list_conversations", {});
+  return summaries.map((summary) => toConversation(summary));
+}
+
+/** Conversations whose messages match `query`, best match first */
+export async function searchConversations(query: string): Promise<Conversation[]> {
+  const summaries = await invoke<ConversationSummary[]>("search_conversations", { query });
+  return summaries.map((summary) => toConversation(summary));
 }
 
 export async function getConversation(id: string): Promise<Conversation | null> {
-  const conversations = await getConversations();
-  return conversations.find((c) => c.id === id) || null;
+  await migrateLegacyConversations();
+  try {
+    return await getConversationById(Number(id));
+  } catch {
+    return null;
+  }
 }
 
 export async function deleteConversation(id: string): Promise<void> {
-  const store = await getStore();
-  const conversations = await getConversations();
-  const filtered = conversations.filter((c) => c.id !== id);
-  await store.set(CONVERSATIONS_KEY, filtered);
-  await store.save();
+  await invoke("delete_conversation", { id: Number(id) });
 }
 
 export async function createConversation(title: string): Promise<Conversation> {
-  const conversation: Conversation = {
-    id: crypto.randomUUID(),
-    title,
-    messages: [],
-    createdAt: Date.now(),
-    updatedAt: Date.now(),
-  };
-  
-  await saveConversation(conversation);
-  return conversation;
+  const created = await invoke<StoredConversation>("create_conversation", { title });
+  return toConversation(created, created.messages);
 }
 
+/** Store a message and return it with its stored id */
 export async function addMessageToConversation(
   conversationId: string,
-  message: Message
-): Promise<void> {
-  const conversation = await getConversation(conversationId);
-  if (!conversation) {
-    throw new Error("Conversation not found");
-  }
-  
-  conversation.messages.push(message);
-  conversation.updatedAt = Date.now();
-  await saveConversation(conversation);
+  message: Message,
+  captureHash?: string
+): Promise<Message> {
+  const stored = await invoke<StoredMessage>("append_message", {
+    conversationId: Number(conversationId),
+    message: {
+      role: message.role,
+      content: message.content,
+      capture_hash: captureHash ?? null,
+    },
+  });
+  return { ...message, id: String(stored.id) };
+}
+
+export async function updateConversationMessage(messageId: string, content: string): Promise<void> {
+  await invoke("update_message", { id: Number(messageId), content });
+}
+
+/** A new conversation holding the messages up to and including `messageId` */
+export async function branchConversation(conversationId: string, messageId: string): Promise<Conversation> {
+  const branched = await invoke<StoredConversation>("branch_conversation", {
+    id: Number(conversationId),
+    messageId: Number(messageId),
+  });
+  return toConversation(branched, branched.messages);
 }
 
 export async function getCurrentConversationId(): Promise<string | null> {
+  await migrateLegacyConversations();
   const store = await getStore();
   return await store.get<string>(CURRENT_CONVERSATION_KEY) || null;
 }
@@ -100,10 +176,6 @@ export async function exportConversation(id: string): Promise<string> {
 
 export async function importConversation(json: string): Promise<Conversation> {
   const conversation: Conversation = JSON.parse(json);
-  conversation.id = crypto.randomUUID(); // Generate new ID
-  conversation.createdAt = Date.now();
-  conversation.updatedAt = Date.now();
-  await saveConversation(conversation);
-  return conversation;
+  return storeConversation(conversation);
 }
 
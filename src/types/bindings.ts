@@ -0,0 +1,2540 @@
+// This file is generated by src-tauri/src/bindings.rs when the app starts in debug mode.
+// Do not edit it by hand; change the Rust types instead.
+
+import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+
+export interface CaptureResult {
+  image_base64: string;
+  hash: string;
+  timestamp: number;
+  /** Text changes in the active window since the previous capture (when capture diff is enabled) */
+  diff?: CaptureDiff | null;
+  /** Set in delta mode; `image_base64` is then empty unless this is a keyframe */
+  delta?: FrameDelta | null;
+  /** Text of the frame, from monitors started with `ocr: true` and webcam frames taken with `ocr` */
+  ocr_text?: string | null;
+  /** What kind of content the frame shows (set by monitors) */
+  classification?: ContentClassification | null;
+}
+
+/** Lines that appeared or disappeared in a window's OCR text since the previous capture */
+export interface CaptureDiff {
+  window: string;
+  added_lines: string[];
+  removed_lines: string[];
+  /** Whether either list was cut off at the line limit */
+  truncated: boolean;
+}
+
+export interface FrameDelta {
+  /** Increases by one per emitted frame; a gap means the frontend should call `get_full_frame` */
+  sequence: number;
+  /** True when `image_base64` holds the whole frame and `tiles` is empty */
+  keyframe: boolean;
+  width: number;
+  height: number;
+  tiles: DeltaTile[];
+}
+
+/** A changed rectangle of the frame */
+export interface DeltaTile {
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+  /** PNG of just this rectangle */
+  image_base64: string;
+}
+
+export interface ContentClassification {
+  kind: ContentKind;
+  confidence: number;
+  /** Why this kind was chosen */
+  signals: string[];
+}
+
+export type ContentKind = "code" | "terminal" | "browser_article" | "video" | "chat" | "document" | "spreadsheet" | "other";
+
+export interface ProcessTree {
+  /** The requested process with its descendants */
+  root: ProcessNode;
+  /** Parent, grandparent and so on, nearest first */
+  ancestors: ProcessInfo[];
+}
+
+export interface ProcessNode {
+  children: ProcessNode[];
+  pid: number;
+  parent_pid?: number | null;
+  name: string;
+  /** Full command line; None when the OS doesn't reveal it (e.g. another user's process) */
+  command_line?: string | null;
+  /** Unix timestamp; only known on Windows */
+  started_at?: number | null;
+}
+
+export interface ProcessInfo {
+  pid: number;
+  parent_pid?: number | null;
+  name: string;
+  /** Full command line; None when the OS doesn't reveal it (e.g. another user's process) */
+  command_line?: string | null;
+  /** Unix timestamp; only known on Windows */
+  started_at?: number | null;
+}
+
+export interface ContainerSummary {
+  /** Short (12 character) id */
+  id: string;
+  names: string[];
+  image: string;
+  /** "running", "exited", "restarting", ... */
+  state: string;
+  /** Human-readable status, e.g. "Up 5 minutes" */
+  status: string;
+  created: number;
+  ports: ContainerPort[];
+}
+
+export interface ContainerPort {
+  private_port: number;
+  /** Host port it's published on, if any */
+  public_port?: number | null;
+  /** "tcp", "udp" or "sctp" */
+  protocol: string;
+  /** Host address it's published on */
+  ip?: string | null;
+}
+
+export interface ContainerLogLine {
+  /** "stdout" or "stderr" */
+  stream: string;
+  text: string;
+}
+
+export interface ContainerStats {
+  /** Share of one CPU, so it can exceed 100 on multi-core hosts; None when the engine doesn't report the system totals needed (e.g. Windows containers) */
+  cpu_percent?: number | null;
+  memory_bytes?: number | null;
+  memory_limit_bytes?: number | null;
+  network_rx_bytes: number;
+  network_tx_bytes: number;
+  pids?: number | null;
+}
+
+export interface DevEnvironment {
+  os: string;
+  arch: string;
+  tools: ToolInfo[];
+}
+
+export interface ToolInfo {
+  name: string;
+  found: boolean;
+  /** The executable PATH resolves to */
+  path?: string | null;
+  /** e.g. "20.11.1"; None when found but the version couldn't be read */
+  version?: string | null;
+  /** First line of the version output */
+  version_output?: string | null;
+  /** Other copies further down PATH, shadowed by `path` */
+  shadowed: string[];
+}
+
+export interface ListeningPort {
+  /** "tcp" or "udp" */
+  protocol: string;
+  /** Local address, e.g. `127.0.0.1`, `0.0.0.0` or `::` */
+  address: string;
+  port: number;
+  /** Owning process; None when the OS doesn't reveal it (e.g. another user's process) */
+  pid?: number | null;
+  process_name?: string | null;
+}
+
+export interface SystemContext {
+  active_window: string;
+  active_window_title: string;
+  open_windows: WindowInfo[];
+  running_applications: string[];
+  timestamp: number;
+}
+
+export interface WindowInfo {
+  title: string;
+  process_name: string;
+  is_active: boolean;
+}
+
+export interface CaptureWindowParams {
+  process_name?: string | null;
+  window_title?: string | null;
+  /** Key for `capture-progress` events; no events are emitted without one */
+  request_id?: string | null;
+  /** Also match compatibility forms, e.g. full-width "Ｗｏｒｄ" for "Word" */
+  normalize?: boolean;
+  /** Ignore accents and other diacritics, e.g. "resume" for "Résumé" */
+  ignore_diacritics?: boolean;
+  /** How both `process_name` and `window_title` match, e.g. `regex` for "python.*manage.py runserver" */
+  match_mode?: MatchMode | null;
+  case_sensitive?: boolean;
+  /** Capture only this element of the window, e.g. one browser tab's page or one terminal pane */
+  child?: ChildTarget | null;
+  /** Translate the OCR text into this language, e.g. "en" (`capture_window_with_ocr` only) */
+  translate_to?: string | null;
+}
+
+/** How `process_name` and `window_title` are compared; without one, the capture scripts' original rules apply */
+export type MatchMode = "exact" | "contains" | "regex" | "fuzzy";
+
+/** Which descendant of the matched window to capture; the first element (in tree order) matching every given field wins */
+export interface ChildTarget {
+  /** Element name, matched like `window_title` with the filter's match options. A browser tab's `Document` element is named after the page title. */
+  name?: string | null;
+  /** UI Automation control type, e.g. `Document`, `Pane`, `TabItem`, `Text` */
+  control_type?: string | null;
+  /** Class name of the element or child window, e.g. `Chrome_RenderWidgetHostHWND` */
+  class_name?: string | null;
+}
+
+export interface WindowCaptureResult {
+  image_base64: string;
+  hash: string;
+  timestamp: number;
+  ocr_text?: string | null;
+  window_title: string;
+  process_name: string;
+  diff?: CaptureDiff | null;
+  /** What kind of content the window shows (set when OCR runs) */
+  classification?: ContentClassification | null;
+  /** How the image was produced (PrintWindow, or a fallback when that came back blank) */
+  capture_method?: CaptureMethod | null;
+  /** Every capture method returned a blank, single-color image */
+  blank_frame?: boolean;
+  /** The child element (tab, pane) the image was cropped to */
+  element?: UiElement | null;
+  /** The OCR text translated, when `translate_to` was given */
+  translation?: Translation | null;
+}
+
+/** How a window capture was produced */
+export type CaptureMethod = "print_window" | "minimized_restore" | "graphics_capture" | "screen_crop";
+
+/** A UI Automation element with its screen rectangle */
+export interface UiElement {
+  name: string;
+  control_type: string;
+  class_name: string;
+  left: number;
+  top: number;
+  width: number;
+  height: number;
+}
+
+export interface Translation {
+  text: string;
+  /** Language the text was detected as, when the backend reports it */
+  source_lang?: string | null;
+  target_lang: string;
+  backend: TranslationBackend;
+}
+
+export type TranslationBackend = "local_model" | "libre_translate" | "cloud";
+
+/** One entry of a `capture_windows` result, in the same order as the filters */
+export interface BatchWindowCapture {
+  index: number;
+  capture?: WindowCaptureResult | null;
+  /** Why this filter produced no capture (window not found, capture failed) */
+  error?: string | null;
+}
+
+/** What to record */
+export type RecordingTarget = { kind: "screen" } | { kind: "region"; x: number; y: number; width: number; height: number } | { kind: "window"; title: string };
+
+export interface RecordingInfo {
+  id: string;
+  /** Where the MP4 is being written */
+  path: string;
+  target: RecordingTarget;
+  fps: number;
+  max_seconds: number;
+  started_at: number;
+}
+
+/** Payload of the `screen-recording-finished` event, also returned by `stop_screen_recording` */
+export interface RecordingFinished {
+  id: string;
+  path: string;
+  duration_ms: number;
+  /** False when the recording ended on its own at `max_seconds` (or ffmpeg failed) */
+  stopped: boolean;
+  /** ffmpeg's last error line if it exited unsuccessfully */
+  error?: string | null;
+}
+
+export interface FrameDeltaSettings {
+  enabled: boolean;
+  /** Send a full frame every this many frames */
+  keyframe_interval: number;
+}
+
+export interface RecentActivityGif {
+  gif_base64: string;
+  frame_count: number;
+  /** Time span covered by the animation */
+  duration_ms: number;
+  width: number;
+  height: number;
+}
+
+export type CaptureBackend = "print_window" | "auto" | "graphics_capture";
+
+export interface CaptureMemoryUsage {
+  memory_cap_bytes: number;
+  memory_bytes: number;
+  frames_in_memory: number;
+  /** Frames moved to temp files to stay under the cap */
+  disk_bytes: number;
+  frames_on_disk: number;
+  /** Frames spilled since the app started */
+  spilled_total: number;
+}
+
+export type ResizeBackend = "image" | "simd";
+
+export interface ParsedDiagnostic {
+  /** "rustc", "tsc", "python" or "gcc" */
+  tool: string;
+  /** "error" or "warning" */
+  severity: string;
+  file?: string | null;
+  line?: number | null;
+  column?: number | null;
+  /** e.g. "E0308", "TS2322" or "NameError" */
+  code?: string | null;
+  message: string;
+  /** Lines around `line` when the file is inside an approved project folder */
+  source?: SourceContext | null;
+}
+
+/** Source lines around an error */
+export interface SourceContext {
+  /** The file inside the approved project folder */
+  path: string;
+  /** 1-based line number of `lines[0]` */
+  first_line: number;
+  lines: string[];
+}
+
+export interface ClipboardImage {
+  /** PNG, base64-encoded */
+  image_base64: string;
+  width: number;
+  height: number;
+  /** Text read by OCR, when requested */
+  ocr_text?: string | null;
+}
+
+export type ColorVision = "protanopia" | "deuteranopia" | "tritanopia" | "achromatopsia";
+
+export interface AccessibilityReport {
+  width: number;
+  height: number;
+  min_contrast: number;
+  /** Tiles that looked like text or icons on a plain background */
+  checked_tiles: number;
+  low_contrast_tiles: number;
+  /** Low-contrast areas, worst first */
+  low_contrast_regions: ContrastRegion[];
+  simulations: ColorVisionSimulation[];
+}
+
+export interface ContrastRegion {
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+  /** Lowest contrast ratio in the region (1 to 21) */
+  contrast_ratio: number;
+  /** Text color as #rrggbb */
+  foreground: string;
+  /** Background color as #rrggbb */
+  background: string;
+}
+
+export interface ColorVisionSimulation {
+  deficiency: ColorVision;
+  image_base64: string;
+}
+
+export interface OcrLanguageInstall {
+  tag: string;
+  /** Whether OCR for the language works now */
+  installed: boolean;
+  already_installed: boolean;
+  opened_settings: boolean;
+}
+
+export interface DocumentLayout {
+  /** Blocks from top to bottom */
+  blocks: LayoutBlock[];
+  /** The same blocks rendered as Markdown, for prompts */
+  markdown: string;
+  word_count: number;
+}
+
+export type LayoutBlock = { type: "heading"; level: number; text: string; bounds: BoundingBox } | { type: "paragraph"; text: string; bounds: BoundingBox } | { type: "table"; rows: string[][]; bounds: BoundingBox } | { type: "columns"; columns: string[]; bounds: BoundingBox };
+
+export interface BoundingBox {
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+}
+
+/** Payload of the `observation-changed` event */
+export interface ObservationStatus {
+  /** Monitors are running and capturing, or the screen is being recorded */
+  observed: boolean;
+  /** Monitoring is paused by the student or a focus-timer break */
+  paused: boolean;
+  /** When the student's pause ends; None for an open-ended pause or when not paused */
+  paused_until?: number | null;
+  monitors: number;
+  /** A screen recording is running */
+  recording: boolean;
+}
+
+/** What monitoring may do */
+export type ConsentScope = "screen" | "screen_and_text";
+
+/** Payload of the `consent-changed` event */
+export interface ConsentStatus {
+  /** Granted scope; None while monitoring isn't allowed */
+  scope?: ConsentScope | null;
+  granted_at?: number | null;
+  /** None when consent lasts until the app exits */
+  expires_at?: number | null;
+  /** What changed: "granted", "revoked" or "expired" */
+  reason?: string | null;
+}
+
+export interface ConsentLogEntry {
+  /** "granted", "revoked" or "expired" */
+  action: string;
+  scope?: ConsentScope | null;
+  expires_at?: number | null;
+  at: number;
+}
+
+export interface MonitorOptions {
+  target?: MonitorTarget;
+  /** Seconds between captures; follows `set_capture_interval` when omitted */
+  interval_seconds?: number | null;
+  /** OCR every changed frame and include the text, and its diff from the previous frame, in the event */
+  ocr?: boolean;
+  /** Regex; OCR every changed frame and emit `text-detected` for new lines matching it */
+  text_pattern?: string | null;
+}
+
+/** What a monitor captures */
+export type MonitorTarget = { kind: "full_screen" } | { kind: "active_window" } | { kind: "window"; process_name?: string | null; window_title?: string | null };
+
+export interface MonitorInfo {
+  id: string;
+  /** Name of the event this monitor emits */
+  event: string;
+  target: MonitorTarget;
+  interval_seconds?: number | null;
+  ocr: boolean;
+  text_pattern?: string | null;
+  started_at: number;
+}
+
+export interface MathRecognition {
+  latex: string;
+  duration_ms: number;
+}
+
+export interface MathOcrSettings {
+  /** URL the image is POSTed to */
+  endpoint: string;
+}
+
+export interface InkStroke {
+  points: InkPoint[];
+}
+
+export interface InkPoint {
+  x: number;
+  y: number;
+}
+
+export interface HandwritingRecognition {
+  /** Best guess, one line per recognized group of strokes (or per OCR line) */
+  text: string;
+  /** Alternative readings per line, best first (ink only) */
+  alternatives: string[][];
+  method: HandwritingMethod;
+}
+
+export type HandwritingMethod = "ink" | "ocr";
+
+export interface WebcamDevice {
+  /** Pass as `device_id` to `capture_webcam_frame` */
+  id: string;
+  name: string;
+  is_default: boolean;
+}
+
+export interface WorksheetGrade {
+  answer_key_id: string;
+  title: string;
+  questions: QuestionGrade[];
+  score: number;
+  max_score: number;
+  /** Everything OCR read, to check a surprising grade against */
+  ocr_text: string;
+  /** The worksheet image that was graded, e.g. the webcam frame */
+  image_base64: string;
+}
+
+export interface QuestionGrade {
+  number: string;
+  /** What was read from the worksheet; None when the question wasn't found */
+  given?: string | null;
+  /** The formula read by the LaTeX-OCR server, for math questions */
+  given_latex?: string | null;
+  correct: boolean;
+  points: number;
+  max_points: number;
+}
+
+/** An answer key without its answers, for listing to students */
+export interface AnswerKeySummary {
+  id: string;
+  title: string;
+  questions: number;
+  max_score: number;
+  updated_at: number;
+}
+
+export interface AnswerKey {
+  id: string;
+  title: string;
+  questions: AnswerKeyQuestion[];
+  updated_at?: number;
+}
+
+export interface AnswerKeyQuestion {
+  /** The label written on the worksheet, e.g. "3" or "4b" */
+  number: string;
+  /** Accepted answers; any of them counts as correct */
+  answers: string[];
+  kind?: AnswerKind;
+  /** Largest difference still counted as correct for number answers */
+  tolerance?: number | null;
+  points?: number;
+}
+
+export type AnswerKind = "text" | "number" | "math";
+
+/** One stored item; `parts` holds its text, e.g. one entry per document page */
+export interface HistoryItem {
+  id: number;
+  /** What the item is, e.g. `document` */
+  kind: string;
+  title: string;
+  /** Where it came from, e.g. the imported file's path */
+  source?: string | null;
+  created_at: number;
+  parts: HistoryPart[];
+}
+
+export interface HistoryPart {
+  /** 1-based position, e.g. the page number */
+  part: number;
+  text: string;
+  /** Rendered image of the part (PDF pages on Windows) */
+  image_path?: string | null;
+}
+
+export interface TranscribeOptions {
+  /** Downloaded whisper.cpp model to use, "whisper" by default */
+  model_id?: string | null;
+  /** Spoken language, e.g. "en" or "de"; detected when omitted */
+  language?: string | null;
+  /** Makes the transcription cancellable with `cancel_operation` */
+  request_id?: string | null;
+}
+
+export interface TranslationSettings {
+  backend?: TranslationBackend;
+  /** e.g. "https://libretranslate.example.org"; its API key, if it needs one, is stored with `set_provider_api_key` under the id "libretranslate" */
+  libretranslate_url?: string | null;
+}
+
+export interface TextAnalysis {
+  words: number;
+  sentences: number;
+  syllables: number;
+  /** Flesch reading ease: 100 is very easy, below 30 is very hard */
+  reading_ease: number;
+  /** Flesch-Kincaid grade level: the US school grade the text suits */
+  grade_level: number;
+  /** Most frequent words that aren't stop words, most frequent first */
+  key_terms: KeyTerm[];
+  /** The sentences carrying the most key terms, in the order they appear in the text */
+  summary: string[];
+}
+
+export interface KeyTerm {
+  term: string;
+  count: number;
+}
+
+export interface Definition {
+  /** The word the senses belong to, which may be the base form of the one looked up */
+  word: string;
+  lang: string;
+  senses: Sense[];
+  source: DefinitionSource;
+}
+
+export interface Sense {
+  /** e.g. "noun" or "verb" */
+  part_of_speech: string;
+  definition: string;
+  example?: string | null;
+}
+
+export type DefinitionSource = "offline" | "online";
+
+export interface DictionaryImport {
+  lang: string;
+  words: number;
+  senses: number;
+}
+
+/** A search hit: the item and the part whose text matched */
+export interface HistoryMatch {
+  item_id: number;
+  kind: string;
+  title: string;
+  source?: string | null;
+  created_at: number;
+  part: number;
+  /** The matching text around the hit, with the terms in [brackets] */
+  snippet: string;
+}
+
+export interface NewNote {
+  title: string;
+  body?: string;
+  tags?: string[];
+  links?: NoteLink[];
+}
+
+export interface NoteLink {
+  kind: NoteLinkKind;
+  target: string;
+}
+
+/** What a note link points at */
+export type NoteLinkKind = "capture" | "session" | "history_item";
+
+export interface Note {
+  id: number;
+  title: string;
+  /** Markdown */
+  body: string;
+  tags: string[];
+  links: NoteLink[];
+  created_at: number;
+  updated_at: number;
+}
+
+/** Changes to a note; omitted fields are kept, given lists replace the old ones */
+export interface NoteUpdate {
+  title?: string | null;
+  body?: string | null;
+  tags?: string[] | null;
+  links?: NoteLink[] | null;
+}
+
+export interface ProviderInfo {
+  /** An API key is stored in the keychain */
+  has_api_key: boolean;
+  /** Unique name, e.g. "openai" or "school-proxy" */
+  id: string;
+  kind: ProviderKind;
+  model: string;
+  /** Overrides the provider's public API, e.g. for a proxy or a remote Ollama */
+  base_url?: string | null;
+  enabled?: boolean;
+}
+
+export type ProviderKind = "open_ai" | "anthropic" | "gemini" | "ollama" | "llama_cpp";
+
+export interface ProviderConfig {
+  /** Unique name, e.g. "openai" or "school-proxy" */
+  id: string;
+  kind: ProviderKind;
+  model: string;
+  /** Overrides the provider's public API, e.g. for a proxy or a remote Ollama */
+  base_url?: string | null;
+  enabled?: boolean;
+}
+
+export interface ProviderTest {
+  provider_id: string;
+  ok: boolean;
+  model: string;
+  latency_ms: number;
+  error?: string | null;
+}
+
+export interface ChatRequest {
+  system?: string | null;
+  messages: ChatMessage[];
+  max_tokens?: number | null;
+}
+
+export interface ChatMessage {
+  role: ChatRole;
+  content: string;
+}
+
+export type ChatRole = "user" | "assistant";
+
+export interface ChatResponse {
+  text: string;
+  /** The provider that answered */
+  provider_id: string;
+  model: string;
+  /** Providers tried before it, with why they failed */
+  failed_over: ProviderFailure[];
+  /** Secrets masked in the request before it was sent */
+  redactions: Redaction[];
+  duration_ms: number;
+}
+
+export interface ProviderFailure {
+  provider_id: string;
+  error: string;
+}
+
+export interface Redaction {
+  /** What was found, e.g. "aws_access_key", "password" or "high_entropy" */
+  kind: string;
+  /** 1-based line of the text it was on */
+  line: number;
+  /** Characters masked */
+  length: number;
+}
+
+/** A tool the model may call */
+export interface AiTool {
+  name: string;
+  description: string;
+  /** JSON schema of the tool's arguments */
+  parameters: unknown;
+  /** The backend command whose role and rate-limit policy applies to it */
+  command: string;
+}
+
+export interface ToolChatResponse {
+  /** The model's final answer */
+  response: ChatResponse;
+  /** Every tool call made on the way, in order */
+  tool_calls: ToolCallRecord[];
+  /** Model replies it took, including the final one */
+  rounds: number;
+}
+
+/** A tool call and what came of it */
+export interface ToolCallRecord {
+  name: string;
+  arguments: unknown;
+  /** False when the call was refused by policy or failed */
+  ok: boolean;
+  /** The result handed back to the model: the tool's output, or the refusal */
+  output: unknown;
+  duration_ms: number;
+}
+
+export interface UsageRange {
+  /** Unix timestamp (seconds), defaults to the start of the current month */
+  from?: number | null;
+  /** Unix timestamp (seconds), defaults to now */
+  to?: number | null;
+}
+
+export interface AiUsageReport {
+  from: number;
+  to: number;
+  requests: number;
+  input_tokens: number;
+  output_tokens: number;
+  cost_usd: number;
+  /** Requests whose token counts were estimated rather than reported */
+  estimated_requests: number;
+  /** Most expensive first */
+  by_model: ModelUsage[];
+  month_to_date_usd: number;
+  monthly_limit_usd?: number | null;
+}
+
+export interface ModelUsage {
+  provider_id: string;
+  model: string;
+  requests: number;
+  input_tokens: number;
+  output_tokens: number;
+  cost_usd: number;
+}
+
+export interface AiBudgetSettings {
+  /** Spending allowed per calendar month; None means no limit */
+  monthly_limit_usd?: number | null;
+  prices?: ModelPrice[];
+}
+
+/** Price of a model family in US dollars per million tokens */
+export interface ModelPrice {
+  /** Matches models starting with this; the longest matching prefix wins */
+  model_prefix: string;
+  input_per_million: number;
+  output_per_million: number;
+}
+
+export interface LocalModel {
+  id: string;
+  url: string;
+  /** Expected SHA-256 of the file, lowercase hex */
+  sha256: string;
+  size_bytes: number;
+  downloaded_at: number;
+}
+
+export interface ModelDownloadRequest {
+  /** Name for the model: letters, digits, '.', '-' and '_' */
+  id: string;
+  /** HTTPS address of the .gguf (or whisper.cpp .bin) file */
+  url: string;
+  /** The file's published SHA-256, lowercase or uppercase hex */
+  sha256: string;
+}
+
+export interface ModelVerification {
+  id: string;
+  ok: boolean;
+  sha256: string;
+}
+
+export interface LoadedModelStatus {
+  model_id: string;
+  loaded_at: number;
+}
+
+export interface FilteredText {
+  text: string;
+  redactions: Redaction[];
+}
+
+export interface TokenCount {
+  tokens: number;
+  /** Tokenizer family the estimate follows: "cl100k", "o200k" or "gemini" */
+  encoding: string;
+  /** Always false: counts are estimates */
+  exact: boolean;
+}
+
+/** One piece of context offered to the assembler */
+export interface ContextSection {
+  /** Heading for the section, e.g. "Active window: Visual Studio Code" */
+  label: string;
+  text: string;
+  /** The window the student is working in; always placed first */
+  active_window?: boolean;
+  /** When the content was captured or changed; newer sections win the remaining budget */
+  timestamp?: number | null;
+}
+
+export interface AssembledContext {
+  text: string;
+  tokens: number;
+  max_tokens: number;
+  /** Included sections in the order they appear in `text` */
+  sections: IncludedSection[];
+  /** Labels of sections left out for lack of budget */
+  dropped: string[];
+}
+
+export interface IncludedSection {
+  label: string;
+  tokens: number;
+  truncated: boolean;
+}
+
+export interface ContextBudgetSettings {
+  /** Tokens the assembled context may use */
+  max_tokens?: number;
+  /** Model whose tokenizer the counts follow */
+  model?: string;
+}
+
+/** A conversation without its messages, as listed and searched */
+export interface Conversation {
+  messages: ConversationMessage[];
+  id: number;
+  title: string;
+  /** The conversation this one was branched from */
+  parent_id?: number | null;
+  /** The last message copied from the parent */
+  branched_from_message?: number | null;
+  /** The focus session it belongs to */
+  session_id?: string | null;
+  message_count: number;
+  created_at: number;
+  updated_at: number;
+}
+
+export interface ConversationMessage {
+  id: number;
+  conversation_id: number;
+  role: MessageRole;
+  content: string;
+  /** The capture the message was about, by its `hash` */
+  capture_hash?: string | null;
+  created_at: number;
+}
+
+export type MessageRole = "user" | "assistant" | "system";
+
+export interface NewMessage {
+  role: MessageRole;
+  content: string;
+  capture_hash?: string | null;
+  /** When it was said, for imported transcripts; defaults to now */
+  created_at?: number | null;
+}
+
+/** A conversation without its messages, as listed and searched */
+export interface ConversationSummary {
+  id: number;
+  title: string;
+  /** The conversation this one was branched from */
+  parent_id?: number | null;
+  /** The last message copied from the parent */
+  branched_from_message?: number | null;
+  /** The focus session it belongs to */
+  session_id?: string | null;
+  message_count: number;
+  created_at: number;
+  updated_at: number;
+}
+
+export interface NewPromptTemplate {
+  name: string;
+  description?: string;
+  body: string;
+  /** Default values by variable name */
+  defaults?: Record<string, string>;
+}
+
+export interface PromptTemplate {
+  name: string;
+  version: number;
+  description: string;
+  body: string;
+  /** The placeholders in `body`, in order of first use */
+  variables: PromptVariable[];
+  created_at: number;
+}
+
+export interface PromptVariable {
+  name: string;
+  /** Used when rendering without a value for it */
+  default?: string | null;
+}
+
+export interface Flashcard {
+  id: number;
+  deck: string;
+  front: string;
+  back: string;
+  /** The note the card was made from */
+  note_id?: number | null;
+  /** SM-2 ease factor; higher means intervals grow faster */
+  ease: number;
+  interval_days: number;
+  /** Successful reviews in a row */
+  repetitions: number;
+  /** Times the card was forgotten after being learned */
+  lapses: number;
+  due_at: number;
+  last_reviewed_at?: number | null;
+  created_at: number;
+}
+
+export interface NewExercise {
+  /** The frontend's own id for the exercise; registering the same key again updates it instead of adding a duplicate */
+  key?: string | null;
+  topic: string;
+  prompt: string;
+  /** Expected answer, if there is one */
+  answer?: string | null;
+  /** 1 (easiest) to 5 */
+  difficulty?: number | null;
+}
+
+export interface Exercise {
+  id: number;
+  key?: string | null;
+  topic: string;
+  prompt: string;
+  answer?: string | null;
+  difficulty?: number | null;
+  created_at: number;
+}
+
+export interface ExerciseAttempt {
+  id: number;
+  exercise_id: number;
+  /** What the student answered */
+  answer?: string | null;
+  correct: boolean;
+  duration_secs?: number | null;
+  attempted_at: number;
+}
+
+export interface TopicMastery {
+  topic: string;
+  exercises: number;
+  /** Exercises with at least one attempt */
+  attempted: number;
+  /** Exercises whose latest attempt was correct */
+  mastered: number;
+  attempts: number;
+  correct_attempts: number;
+  /** Share of the topic's last attempts that were correct, 0 to 1 */
+  recent_accuracy?: number | null;
+  /** `mastered / exercises`, 0 to 1 */
+  mastery: number;
+  last_attempt_at?: number | null;
+}
+
+export interface CurriculumProgress {
+  curriculum: Curriculum;
+  /** Every lesson in plan order */
+  lessons: LessonStatus[];
+  completed: number;
+  total: number;
+  /** The first available lesson that isn't done; `None` once the plan is finished */
+  next?: LessonStatus | null;
+  imported_at: number;
+}
+
+/** A curriculum file's contents */
+export interface Curriculum {
+  /** Stable id; importing a file with the same id replaces the plan and keeps progress on lessons that still exist */
+  id: string;
+  title: string;
+  description?: string | null;
+  units: Unit[];
+}
+
+export interface Unit {
+  id: string;
+  title: string;
+  lessons: Lesson[];
+}
+
+export interface Lesson {
+  /** Unique within the curriculum */
+  id: string;
+  title: string;
+  description?: string | null;
+  /** Ids of lessons to finish first, from any unit */
+  prerequisites?: string[];
+  estimated_minutes?: number | null;
+  /** Exercise topics the lesson covers */
+  topics?: string[];
+}
+
+export interface LessonStatus {
+  unit_id: string;
+  lesson: Lesson;
+  completed_at?: number | null;
+  /** Every prerequisite is done */
+  available: boolean;
+}
+
+export interface CurriculumSummary {
+  id: string;
+  title: string;
+  completed: number;
+  total: number;
+  imported_at: number;
+}
+
+export interface Achievements {
+  /** Days in a row with study up to today (or yesterday, while today has none yet) */
+  current_streak_days: number;
+  longest_streak_days: number;
+  studied_today: boolean;
+  /** Minutes on task: per day, the larger of active input minutes and focus-session minutes */
+  total_study_minutes: number;
+  badges: Badge[];
+}
+
+export interface Badge {
+  id: string;
+  title: string;
+  description: string;
+  unlocked_at?: number | null;
+  /** Toward the goal, 0 to 1 */
+  progress: number;
+}
+
+export interface DailySummarySettings {
+  enabled: boolean;
+  /** Local time the summary is made, as `HH:MM` */
+  time: string;
+  /** Ask the frontend for an AI-written narrative with each summary */
+  request_narrative: boolean;
+}
+
+/** Payload of the `daily-summary-ready` event */
+export interface DailySummary {
+  /** Local date, `YYYY-MM-DD` */
+  day: string;
+  /** Time on task, as counted for achievements */
+  study_minutes: number;
+  /** Time per kind of content seen in captures (while the app was running) */
+  content_time: ContentTime[];
+  /** Exercise topics practiced, most attempts first */
+  topics: TopicActivity[];
+  exercises_attempted: number;
+  exercises_correct: number;
+  flashcards_reviewed: number;
+  /** Titles of lessons finished */
+  lessons_completed: string[];
+  /** Titles of notes written */
+  notes: string[];
+  /** Titles of documents imported */
+  documents: string[];
+  /** Captures linked from the day's notes, by hash */
+  notable_captures: string[];
+  /** Titles of badges unlocked */
+  badges: string[];
+  /** AI-written recap, once the frontend has stored one */
+  narrative?: string | null;
+  /** Whether the frontend should write the narrative */
+  narrative_requested: boolean;
+  created_at: number;
+}
+
+/** Time spent on one kind of content */
+export interface ContentTime {
+  kind: ContentKind;
+  seconds: number;
+}
+
+export interface TopicActivity {
+  topic: string;
+  attempts: number;
+  correct: number;
+}
+
+export interface DataExport {
+  path: string;
+  size_bytes: number;
+  /** Files inside the zip, besides the manifest */
+  file_count: number;
+  /** Schema version of the exported database */
+  schema_version: number;
+}
+
+export interface RetentionSettings {
+  /** Delete saved captures (and recordings) older than this many days; None keeps them */
+  capture_days?: number | null;
+  /** Blank the stored text of history items older than this many days; None keeps it */
+  ocr_text_days?: number | null;
+}
+
+export interface RetentionReport {
+  captures_deleted: number;
+  bytes_freed: number;
+  /** Text parts blanked in history */
+  texts_anonymized: number;
+  ran_at: number;
+}
+
+export interface PurgeConfirmation {
+  /** Pass to `purge_all_captures` to confirm */
+  token: string;
+  expires_at: number;
+  /** What would be deleted */
+  captures: number;
+  bytes: number;
+}
+
+export interface NarratorOptions {
+  mode?: NarrationMode;
+  /** Seconds between captures */
+  interval_seconds?: number;
+  /** New characters needed before anything is narrated, so a blinking cursor or clock stays quiet */
+  min_new_chars?: number;
+  /** Shortest time between two narrations */
+  min_gap_seconds?: number;
+  /** Only narrate, don't speak: for frontends with their own voice */
+  muted?: boolean;
+}
+
+export type NarrationMode = "read" | "summarize";
+
+export interface NarratorInfo {
+  target: MonitorTarget;
+  options: NarratorOptions;
+  started_at: number;
+}
+
+export interface CommandResult {
+  success: boolean;
+  stdout: string;
+  stderr: string;
+  exit_code?: number | null;
+  error?: string | null;
+}
+
+export interface SandboxSettings {
+  enabled?: boolean;
+  /** Memory for the command and everything it starts */
+  max_memory_mb?: number;
+  /** Share of total CPU time, 1-100 */
+  max_cpu_percent?: number;
+  max_runtime_seconds?: number;
+  /** Throttle outgoing network traffic to a byte per second. Off by default: anything that downloads (git fetch, npm install, docker pull) would hang until the time limit. */
+  block_network?: boolean;
+}
+
+/** Dry-run report of `execute_script` */
+export interface ScriptPlan {
+  steps: ScriptStep[];
+  /** Every step is permitted, so the script can be confirmed */
+  runnable: boolean;
+  /** Pass to `confirm_execution` to run the script; None when it isn't runnable */
+  token?: string | null;
+  expires_at?: number | null;
+}
+
+export interface ScriptStep {
+  /** 1-based line of the script the command is on */
+  line: number;
+  /** The command as written */
+  text: string;
+  program?: string | null;
+  args: string[];
+  condition: StepCondition;
+  allowed: boolean;
+  /** Why the policy blocks it */
+  reason?: string | null;
+}
+
+/** How a command is joined to the one before it */
+export type StepCondition = "always" | "on_success" | "on_failure";
+
+export interface ScriptRun {
+  steps: StepOutcome[];
+  /** The last command that ran succeeded */
+  success: boolean;
+}
+
+export interface StepOutcome {
+  text: string;
+  /** False when its `&&`/`||` condition wasn't met */
+  ran: boolean;
+  result?: CommandResult | null;
+}
+
+export interface HomeworkDetectionSettings {
+  enabled: boolean;
+  /** Start a focus session (25/5 minutes) when homework is detected and none is running */
+  auto_start_session: boolean;
+}
+
+export interface HomeworkDetectionParams {
+  window_title?: string | null;
+  process_name?: string | null;
+  ocr_text?: string | null;
+}
+
+/** Payload of the `homework-context-detected` event */
+export interface HomeworkContext {
+  kind: HomeworkKind;
+  confidence: number;
+  signals: string[];
+  window_title: string;
+  process_name: string;
+  /** Auto-start is on: a focus session was started for this context unless one was running */
+  auto_start_session: boolean;
+  timestamp: number;
+}
+
+export type HomeworkKind = "essay" | "coding_assignment" | "worksheet";
+
+/** Payload of the `connectivity-changed` event */
+export interface ConnectivityStatus {
+  online: boolean;
+  latency_ms?: number | null;
+  endpoint: string;
+  error?: string | null;
+  checked_at: number;
+}
+
+/** An AI request that couldn't be sent while offline */
+export interface PendingRequest {
+  id: string;
+  /** What the payload is for, e.g. "chat" or "screen-analysis" */
+  kind: string;
+  payload: unknown;
+  created_at: number;
+  /** Number of times the request was handed to the frontend for sending */
+  attempts: number;
+  last_dispatched_at?: number | null;
+  /** Handed to the frontend and not yet completed or failed; skipped by `flush` */
+  in_flight?: boolean;
+}
+
+export interface JobInfo {
+  id: string;
+  command: string;
+  args: string[];
+  pid: number;
+  started_at: number;
+}
+
+export interface CreateTerminalParams {
+  cols?: number | null;
+  rows?: number | null;
+  cwd?: string | null;
+}
+
+export interface TerminalInfo {
+  id: string;
+  shell: string;
+  cols: number;
+  rows: number;
+  created_at: number;
+}
+
+export interface ProjectFile {
+  path: string;
+  content: string;
+  size: number;
+  /** Whether the content was cut off at the size limit */
+  truncated: boolean;
+}
+
+export interface DirectoryEntry {
+  name: string;
+  path: string;
+  is_dir: boolean;
+  size: number;
+  modified?: number | null;
+}
+
+export interface SearchMatch {
+  path: string;
+  line_number: number;
+  line: string;
+}
+
+export interface ProjectAnalysis {
+  root: string;
+  projects: DetectedProject[];
+}
+
+export interface DetectedProject {
+  /** Manifest the project was detected from, relative to the analyzed root */
+  manifest: string;
+  /** "javascript", "typescript", "rust" or "python" */
+  language: string;
+  name?: string | null;
+  /** npm, yarn, pnpm, bun, cargo, pip, poetry, uv or pdm */
+  package_manager: string;
+  frameworks: string[];
+  commands: SuggestedCommand[];
+}
+
+export interface SuggestedCommand {
+  /** "install", "dev", "run", "build", "test" or "lint" */
+  purpose: string;
+  command: string;
+  /** Folder to run it in, relative to the analyzed root ("" for the root itself) */
+  cwd: string;
+}
+
+export interface FileWatcherStatus {
+  running: boolean;
+  watched_roots: string[];
+}
+
+export interface GitStatus {
+  branch?: string | null;
+  head_commit?: string | null;
+  upstream?: string | null;
+  ahead: number;
+  behind: number;
+  files: GitFileStatus[];
+  clean: boolean;
+}
+
+export interface GitFileStatus {
+  path: string;
+  /** One of "new", "modified", "deleted", "renamed", "typechange" or "conflicted" */
+  status: string;
+  staged: boolean;
+}
+
+export interface GitDiff {
+  staged: boolean;
+  files: GitDiffFile[];
+  additions: number;
+  deletions: number;
+  /** Whether some patches were left out because of the size limit */
+  truncated: boolean;
+}
+
+export interface GitDiffFile {
+  path: string;
+  old_path?: string | null;
+  status: string;
+  additions: number;
+  deletions: number;
+  /** Unified diff text; omitted for binary files or once the size limit is reached */
+  patch?: string | null;
+}
+
+export interface GitCommit {
+  id: string;
+  short_id: string;
+  summary: string;
+  message: string;
+  author_name: string;
+  author_email: string;
+  timestamp: number;
+}
+
+export interface ActivityRange {
+  /** Unix timestamp (seconds), defaults to one hour ago */
+  from?: number | null;
+  /** Unix timestamp (seconds), defaults to now */
+  to?: number | null;
+}
+
+export interface ActivityMetrics {
+  from: number;
+  to: number;
+  tracking_enabled: boolean;
+  minutes: ActivityMinute[];
+  total_keystrokes: number;
+  total_clicks: number;
+  /** Minutes with at least one keystroke or click */
+  active_minutes: number;
+  /** Average keystrokes per active minute */
+  keystrokes_per_minute: number;
+  clicks_per_minute: number;
+}
+
+/** Input counts for one minute. Never contains what was typed or clicked. */
+export interface ActivityMinute {
+  minute_start: number;
+  keystrokes: number;
+  clicks: number;
+}
+
+export interface TypingSessionStats {
+  window_title: string;
+  process_name: string;
+  /** Characters in the passage read from the practice window */
+  target_chars: number;
+  started_at: number;
+  /** Keys pressed in the practice window, backspaces included */
+  keystrokes: number;
+  backspaces: number;
+  /** Seconds from the first keystroke to the last */
+  typing_seconds: number;
+  /** Keys other than backspace per minute, in five-character words */
+  gross_wpm: number;
+  /** As `gross_wpm`, less the characters that were typed and then deleted */
+  net_wpm: number;
+  /** Share of typed characters that didn't have to be deleted, from 0 to 1 */
+  accuracy: number;
+  /** Share of the passage typed so far, from 0 to 1 */
+  progress: number;
+  running: boolean;
+}
+
+export interface FocusSession {
+  id: string;
+  focus_minutes: number;
+  break_minutes: number;
+  phase: FocusPhase;
+  phase_started_at: number;
+  /** When the current phase ends; `None` while paused */
+  phase_ends_at?: number | null;
+  /** Seconds left in the current phase when it was paused */
+  paused_remaining_secs?: number | null;
+  completed_cycles: number;
+  /** Stop screen monitoring captures while on a break */
+  pause_monitoring_during_breaks: boolean;
+}
+
+export type FocusPhase = "focus" | "break";
+
+/** User-defined classification lists. Keywords match case-insensitively against the window title, process name and URL; regexes are case-insensitive too. */
+export interface DistractionRules {
+  enabled: boolean;
+  study_keywords: string[];
+  distraction_keywords: string[];
+  study_patterns?: string[];
+  distraction_patterns?: string[];
+  /** How long a distraction has to last before `distraction-detected` is emitted */
+  nudge_after_secs: number;
+}
+
+export interface Classification {
+  class: ActivityClass;
+  /** The keyword or pattern that decided the class */
+  matched_rule?: string | null;
+}
+
+export type ActivityClass = "study" | "neutral" | "distraction";
+
+export interface StreakStatus {
+  class: ActivityClass;
+  since: number;
+  duration_secs: number;
+  window_title: string;
+  /** Longest uninterrupted study streak since the app started */
+  longest_study_secs: number;
+}
+
+export interface SyncSettings {
+  enabled: boolean;
+  /** Base URL of the dashboard server; reports are POSTed to `{endpoint}/reports` */
+  endpoint?: string | null;
+  interval_minutes: number;
+  /** Random identifier sent instead of any user or machine name */
+  device_id: string;
+}
+
+export interface SyncStatus {
+  last_attempt_at?: number | null;
+  last_success_at?: number | null;
+  last_error?: string | null;
+  consecutive_failures: number;
+  pending_reports: number;
+  has_token: boolean;
+}
+
+export interface IntegrationServerStatus {
+  running: boolean;
+  url?: string | null;
+  settings: IntegrationServerSettings;
+  methods: string[];
+}
+
+export interface IntegrationServerSettings {
+  enabled: boolean;
+  port: number;
+  token: string;
+  /** Also serve metrics for Prometheus at `http://127.0.0.1:<port>/metrics?token=<token>` */
+  prometheus?: boolean;
+}
+
+/** Payload of `remote-view-changed` and result of the remote view commands */
+export interface RemoteViewStatus {
+  active: boolean;
+  /** One-time code for the tutor; gone once a viewer has connected */
+  code?: string | null;
+  /** Addresses the tutor's browser can open */
+  urls: string[];
+  viewer_connected: boolean;
+}
+
+export interface DiagnosticsReport {
+  generated_at: number;
+  platform: string;
+  /** The worst status among the checks that ran */
+  overall: DiagnosticStatus;
+  checks: DiagnosticCheck[];
+}
+
+export type DiagnosticStatus = "pass" | "skipped" | "warn" | "fail";
+
+export interface DiagnosticCheck {
+  /** Stable identifier, e.g. "screen_capture" */
+  id: string;
+  label: string;
+  status: DiagnosticStatus;
+  message: string;
+  duration_ms: number;
+}
+
+export interface Metrics {
+  /** Unix milliseconds the counts start from */
+  since: number;
+  counters: Counter[];
+  histograms: LatencyHistogram[];
+  /** Share of OCR requests answered from the cache; None before the first one */
+  ocr_cache_hit_rate?: number | null;
+  /** Events waiting in the replay buffer for a reloading webview */
+  event_queue_depth: number;
+  event_queue_bytes: number;
+  /** Events emitted since the app started */
+  events_emitted: number;
+}
+
+export interface Counter {
+  name: string;
+  value: number;
+}
+
+export interface LatencyHistogram {
+  name: string;
+  count: number;
+  errors: number;
+  /** Time spent in all the calls together */
+  sum_ms: number;
+  avg_ms: number;
+  /** Estimated from the buckets: the bound of the bucket the percentile falls in */
+  p50_ms: number;
+  p95_ms: number;
+  max_ms: number;
+  buckets: LatencyBucket[];
+}
+
+export interface LatencyBucket {
+  /** Upper bound; None for the overflow bucket */
+  le_ms?: number | null;
+  /** Calls at or under `le_ms`, the faster buckets included */
+  count: number;
+}
+
+export interface BenchmarkReport {
+  iterations: number;
+  platform: string;
+  capture_backend: CaptureBackend;
+  /** Whether OCR and window capture went through the native helper rather than PowerShell */
+  native_helper: boolean;
+  /** Size of the full-screen capture the encode and OCR stages work on */
+  image_width?: number | null;
+  image_height?: number | null;
+  stages: StageTimings[];
+  started_at: number;
+  duration_ms: number;
+}
+
+export interface StageTimings {
+  /** "capture", "encode", "ocr" or "capture_window_with_ocr" */
+  stage: string;
+  /** Runs that succeeded */
+  samples: number;
+  min_ms: number;
+  p50_ms: number;
+  p90_ms: number;
+  p99_ms: number;
+  max_ms: number;
+  mean_ms: number;
+  /** Why the stage stopped early, if it did */
+  error?: string | null;
+}
+
+/** What the user agreed to share of their screen */
+export type ScreenshotConsent = "none" | "full" | "redacted";
+
+export interface DebugBundle {
+  path: string;
+  size_bytes: number;
+  /** Files inside the zip */
+  files: string[];
+  /** Screenshot actually included; `none` when nothing had been captured yet */
+  screenshot: ScreenshotConsent;
+}
+
+export interface CrashReport {
+  id: string;
+  timestamp: number;
+  app_version: string;
+  thread?: string | null;
+  message: string;
+  /** `file:line:column` of the panic */
+  location?: string | null;
+  backtrace: string;
+}
+
+export interface SubsystemHealth {
+  name: string;
+  status: HealthStatus;
+  /** Unix milliseconds of the latest heartbeat or successful run */
+  last_heartbeat?: number | null;
+  restarts: number;
+  last_error?: string | null;
+  /** Unix milliseconds of the pending restart */
+  next_restart_at?: number | null;
+}
+
+export type HealthStatus = "healthy" | "stalled" | "restarting" | "failing";
+
+export interface ProfileList {
+  profiles: Profile[];
+  /** Id of the profile the app is running as */
+  active: string;
+}
+
+export interface Profile {
+  id: string;
+  name: string;
+  created_at: number;
+}
+
+/** Payload of `role-changed` and result of the role commands */
+export interface RoleStatus {
+  role: Role;
+  pin_set: boolean;
+  /** Seconds until teacher mode locks again */
+  teacher_seconds_left?: number | null;
+}
+
+export type Role = "student" | "teacher";
+
+export interface RolePolicy {
+  /** Programs a student may run through `execute_command`, jobs and the terminal; each must also be on the global allowlist */
+  student_allowed_commands: string[];
+  /** Backend commands refused in student mode */
+  teacher_only_commands: string[];
+}
+
+/** Payload of `update-available` and result of `check_for_updates` when there is an update */
+export interface UpdateInfo {
+  current_version: string;
+  version: string;
+  channel: UpdateChannel;
+  /** Release notes */
+  notes?: string | null;
+  /** RFC 3339 publish date */
+  date?: string | null;
+}
+
+export type UpdateChannel = "stable" | "beta";
+
+export interface UpdateSettings {
+  channel: UpdateChannel;
+  /** Look for an update at startup and emit `update-available` when there is one */
+  check_on_startup: boolean;
+}
+
+export interface LogEntry {
+  /** Unix timestamp in milliseconds */
+  timestamp: number;
+  level: string;
+  target: string;
+  message: string;
+}
+
+export interface MissedEvents {
+  /** Events after the requested `seq`, oldest first */
+  events: BufferedEvent[];
+  /** Number of the latest event emitted, to continue from */
+  latest_seq: number;
+  /** Whether some missed events had already left the buffer */
+  truncated: boolean;
+}
+
+export interface BufferedEvent {
+  seq: number;
+  event: string;
+  /** None when it was too large to keep */
+  payload?: unknown;
+  timestamp: number;
+}
+
+/** All command metadata plus the JSON schemas of every named type they reference */
+export interface CommandCatalog {
+  commands: CommandMetadata[];
+  definitions: Record<string, unknown>;
+}
+
+/** Machine-readable description of an invokable command, served to the frontend command palette and the API layers. */
+export interface CommandMetadata {
+  name: string;
+  module: string;
+  description: string;
+  params: ParamMetadata[];
+  /** TypeScript type of the command's result */
+  returns: string;
+  capabilities: string[];
+  rate_limit?: RateLimit | null;
+}
+
+export interface ParamMetadata {
+  /** Argument key expected by `invoke` (camelCase, as Tauri converts it) */
+  name: string;
+  /** TypeScript type of the argument */
+  type_name: string;
+  required: boolean;
+  /** JSON schema of the argument; `$ref`s point into `CommandCatalog::definitions` */
+  schema: unknown;
+}
+
+export interface RateLimit {
+  max_calls: number;
+  per_seconds: number;
+}
+
+/** Payload of the `job-output` event, one per line of output */
+export interface JobOutput {
+  job_id: string;
+  stream: string;
+  line: string;
+  timestamp: number;
+}
+
+/** Payload of the `job-exited` event */
+export interface JobExit {
+  job_id: string;
+  exit_code?: number | null;
+  killed: boolean;
+}
+
+/** Payload of the `terminal-output` event */
+export interface TerminalOutput {
+  terminal_id: string;
+  data: string;
+}
+
+/** Payload of the `terminal-exited` event */
+export interface TerminalExit {
+  terminal_id: string;
+  exit_code?: number | null;
+}
+
+/** Payload of the `terminal-command-blocked` event */
+export interface TerminalCommandBlocked {
+  terminal_id: string;
+  command_line: string;
+  reason: string;
+}
+
+/** Payload of the `file-changed` event */
+export interface FileChange {
+  path: string;
+  kind: FileChangeKind;
+  timestamp: number;
+  /** Line counts compared to the previous version, when it was known */
+  diff_summary?: DiffSummary | null;
+}
+
+export type FileChangeKind = "created" | "modified" | "removed" | "renamed";
+
+export interface DiffSummary {
+  lines_added: number;
+  lines_removed: number;
+}
+
+/** Payload of the `focus-phase-changed` event */
+export interface FocusPhaseChange {
+  /** The session after the change; `None` when it was stopped */
+  session?: FocusSession | null;
+  /** "started", "phase_completed", "paused", "resumed" or "stopped" */
+  reason: string;
+}
+
+/** Payload of the `distraction-detected` event */
+export interface DistractionDetected {
+  window_title: string;
+  process_name: string;
+  url?: string | null;
+  matched_rule?: string | null;
+  streak_secs: number;
+  timestamp: number;
+}
+
+/** Payload of the `capture-progress` event */
+export interface CaptureProgress {
+  request_id: string;
+  stage: CaptureStage;
+  /** Error message for `failed`, otherwise unset */
+  detail?: string | null;
+  timestamp: number;
+}
+
+export type CaptureStage = "enumerating" | "capturing" | "encoding" | "ocr" | "done" | "failed";
+
+/** Payload of the `app-error` event */
+export interface AppError {
+  /** What went wrong, currently always "panic" */
+  kind: string;
+  message: string;
+  /** Id of the crash report with the details */
+  crash_id?: string | null;
+  timestamp: number;
+}
+
+/** Payload of the `ocr-language-progress` event */
+export interface OcrLanguageProgress {
+  tag: string;
+  stage: OcrLanguageStage;
+  elapsed_seconds: number;
+  detail?: string | null;
+}
+
+export type OcrLanguageStage = "checking" | "verifying" | "done" | "failed" | "installing" | "opened_settings";
+
+/** Payload of the `update-progress` event */
+export interface UpdateProgress {
+  stage: UpdateStage;
+  downloaded_bytes: number;
+  total_bytes?: number | null;
+}
+
+export type UpdateStage = "downloading" | "installing" | "done";
+
+/** Payload of the `flashcards-due` event */
+export interface FlashcardsDue {
+  due_count: number;
+  /** Decks with due cards */
+  decks: string[];
+}
+
+/** Payload of the `content-ingested` event */
+export interface ContentIngested {
+  path: string;
+  /** "image", "document", "audio" or "unsupported" */
+  kind: string;
+  /** The history item holding the extracted text; None when ingestion failed */
+  item?: HistoryItem | null;
+  error?: string | null;
+}
+
+/** Payload of the `text-detected` event */
+export interface TextDetected {
+  monitor_id: string;
+  pattern: string;
+  window_title: string;
+  process_name: string;
+  /** Lines of OCR text that match and weren't on the previous frame */
+  lines: string[];
+  /** Errors parsed from the frame, with source lines when the file is in an approved folder */
+  diagnostics: ParsedDiagnostic[];
+  timestamp: number;
+}
+
+/** Payload of the `budget-exceeded` event */
+export interface BudgetExceeded {
+  month_to_date_usd: number;
+  monthly_limit_usd: number;
+  /** The provider whose request crossed the limit */
+  provider_id: string;
+}
+
+/** Payload of `ai-token`: text received since the previous event for the request */
+export interface AiToken {
+  request_id: string;
+  text: string;
+}
+
+/** Payload of `ai-complete`, the last event of a successful streamed request */
+export interface AiComplete {
+  request_id: string;
+  response: ChatResponse;
+}
+
+/** Payload of `ai-error`, the last event of a failed or cancelled streamed request */
+export interface AiError {
+  request_id: string;
+  error: string;
+}
+
+/** Payload of the `model-download-progress` event */
+export interface ModelDownloadProgress {
+  id: string;
+  stage: ModelDownloadStage;
+  /** Including bytes kept from an earlier, interrupted download */
+  downloaded_bytes: number;
+  total_bytes?: number | null;
+  detail?: string | null;
+}
+
+export type ModelDownloadStage = "downloading" | "verifying" | "done" | "failed";
+
+/** Payload of the `narration` event */
+export interface Narration {
+  /** What was (or, when muted, would have been) spoken */
+  text: string;
+  window_title: string;
+  /** The new lines the narration is about */
+  lines: string[];
+  summarized: boolean;
+  timestamp: number;
+}
+
+/** Payload of the `subsystem-unhealthy` event */
+export interface SubsystemUnhealthy {
+  name: string;
+  status: HealthStatus;
+  reason: string;
+  restarts: number;
+  timestamp: number;
+}
+
+/** Payload of the `deep-link-opened` event */
+export interface DeepLinkOpened {
+  url: string;
+  /** None when the link wasn't valid */
+  action?: DeepLinkAction | null;
+  capture?: WindowCaptureResult | null;
+  lesson?: LessonStatus | null;
+  /** Curriculum of `lesson` */
+  curriculum_id?: string | null;
+  error?: string | null;
+  timestamp: number;
+}
+
+export type DeepLinkAction = { kind: "capture"; window_title?: string | null; process_name?: string | null; ocr: boolean } | { kind: "lesson"; lesson_id: string; curriculum_id?: string | null };
+
+export const commands = {
+  /** Capture the full virtual screen */
+  captureScreen: () => invoke<CaptureResult>("capture_screen"),
+  /** Get the process name of the foreground window */
+  getActiveWindow: () => invoke<string>("get_active_window"),
+  /** A process with its descendants, their command lines, and its ancestors */
+  getProcessTree: (pid: number) => invoke<ProcessTree>("get_process_tree", { pid }),
+  /** Docker containers with image, state and published ports (stopped ones too with all) */
+  listContainers: (all: boolean | null) => invoke<ContainerSummary[]>("list_containers", { all }),
+  /** The last lines (default 100) of a Docker container's stdout and stderr */
+  containerLogs: (id: string, tail: number | null) => invoke<ContainerLogLine[]>("container_logs", { id, tail }),
+  /** CPU, memory, network and process counts of a Docker container */
+  containerStats: (id: string) => invoke<ContainerStats>("container_stats", { id }),
+  /** Installed toolchains (node, npm, python, rustc, cargo, java, docker, git) with versions and PATH locations */
+  detectDevEnvironment: () => invoke<DevEnvironment>("detect_dev_environment"),
+  /** Listening TCP and bound UDP ports with the process behind each, optionally only one port */
+  getListeningPorts: (port: number | null) => invoke<ListeningPort[]>("get_listening_ports", { port }),
+  /** Get the active window, open windows and running applications */
+  getSystemContext: () => invoke<SystemContext>("get_system_context"),
+  /** List all windows matching a process name or window title */
+  listWindowsByProcess: (options: CaptureWindowParams) => invoke<WindowInfo[]>("list_windows_by_process", { options }),
+  /** Capture a specific window by process name or window title */
+  captureWindow: (options: CaptureWindowParams) => invoke<WindowCaptureResult>("capture_window", { options }),
+  /** Capture several windows in one call, sharing a single window enumeration */
+  captureWindows: (filters: CaptureWindowParams[]) => invoke<BatchWindowCapture[]>("capture_windows", { filters }),
+  /** Record the screen, a region or a window to an MP4 clip with ffmpeg */
+  startScreenRecording: (target: RecordingTarget, fps: number | null, maxSeconds: number | null) => invoke<RecordingInfo>("start_screen_recording", { target, fps, maxSeconds }),
+  /** Stop the running screen recording and finalize the clip */
+  stopScreenRecording: () => invoke<RecordingFinished>("stop_screen_recording"),
+  /** Get whether screen-changed events carry changed tiles instead of full frames */
+  getFrameDeltaSettings: () => invoke<FrameDeltaSettings>("get_frame_delta_settings"),
+  /** Send only changed tiles in screen-changed events, with a keyframe every N frames */
+  setFrameDeltaSettings: (settings: FrameDeltaSettings) => invoke<null>("set_frame_delta_settings", { settings }),
+  /** Get the latest full frame of a monitor to resync after a missed delta */
+  getFullFrame: (monitorId: string | null) => invoke<CaptureResult>("get_full_frame", { monitorId }),
+  /** Replay the last seconds (up to 60) of monitoring captures as an animated GIF */
+  exportRecentActivityGif: (seconds: number) => invoke<RecentActivityGif>("export_recent_activity_gif", { seconds }),
+  /** Get which backend window captures use */
+  getCaptureBackend: () => invoke<CaptureBackend>("get_capture_backend"),
+  /** Choose the window capture backend (auto, print_window, graphics_capture) */
+  setCaptureBackend: (backend: CaptureBackend) => invoke<null>("set_capture_backend", { backend }),
+  /** Get the memory and disk used by buffered capture frames */
+  getCaptureMemoryUsage: () => invoke<CaptureMemoryUsage>("get_capture_memory_usage"),
+  /** Cap the memory buffered capture frames may use; older frames past it move to disk */
+  setCaptureMemoryCap: (memoryCapMb: number) => invoke<CaptureMemoryUsage>("set_capture_memory_cap", { memoryCapMb }),
+  /** Get how captures and monitoring frames are downscaled */
+  getResizeBackend: () => invoke<ResizeBackend>("get_resize_backend"),
+  /** Choose how captures and monitoring frames are downscaled (image, simd) */
+  setResizeBackend: (backend: ResizeBackend) => invoke<null>("set_resize_backend", { backend }),
+  /** Capture a window and extract text using OCR */
+  captureWindowWithOcr: (options: CaptureWindowParams) => invoke<WindowCaptureResult>("capture_window_with_ocr", { options }),
+  /** Extract text from a base64-encoded image using Windows OCR */
+  extractTextFromImage: (imageBase64: string, requestId: string | null) => invoke<string>("extract_text_from_image", { imageBase64, requestId }),
+  /** Structured rustc, tsc, Python and gcc errors (file, line, code, message) found in OCR or terminal text */
+  parseDiagnostics: (text: string) => invoke<ParsedDiagnostic[]>("parse_diagnostics", { text }),
+  /** The image on the clipboard (e.g. a fresh Snipping Tool screenshot) as base64 PNG, optionally with OCR text */
+  getClipboardImage: (ocr: boolean | null, requestId: string | null) => invoke<ClipboardImage | null>("get_clipboard_image", { ocr, requestId }),
+  /** Find low-contrast text regions (WCAG ratio, default 4.5) and simulate color-vision deficiencies on an image */
+  analyzeAccessibility: (imageBase64: string, minContrast: number | null, simulate: ColorVision[] | null) => invoke<AccessibilityReport>("analyze_accessibility", { imageBase64, minContrast, simulate }),
+  /** List the language tags Windows OCR can currently recognize */
+  listOcrLanguages: () => invoke<string[]>("list_ocr_languages"),
+  /** Install the Windows OCR language pack for a tag such as de-DE (asks for elevation, falls back to Language settings), emitting ocr-language-progress */
+  installOcrLanguage: (tag: string) => invoke<OcrLanguageInstall>("install_ocr_language", { tag }),
+  /** OCR an image and rebuild its layout (headings, paragraphs, tables, text columns) as structured blocks and Markdown */
+  extractLayout: (imageBase64: string, requestId: string | null) => invoke<DocumentLayout>("extract_layout", { imageBase64, requestId }),
+  /** Abort an in-flight capture or OCR operation, or drop a queued AI request, by request id */
+  cancelOperation: (requestId: string) => invoke<boolean>("cancel_operation", { requestId }),
+  /** Pause every monitor for some minutes, or until resume_monitoring */
+  pauseMonitoring: (durationMinutes: number | null) => invoke<ObservationStatus>("pause_monitoring", { durationMinutes }),
+  /** Resume monitors paused with pause_monitoring */
+  resumeMonitoring: () => invoke<ObservationStatus>("resume_monitoring"),
+  /** Whether the screen is being observed, and whether monitoring is paused */
+  getObservationStatus: () => invoke<ObservationStatus>("get_observation_status"),
+  /** Show or hide the always-on-top dot shown while the screen is being captured */
+  setRecordingIndicator: (enabled: boolean) => invoke<null>("set_recording_indicator", { enabled }),
+  /** Record the student's consent to monitoring (screen, or screen and text) for some minutes, or until the app exits */
+  grantMonitoringConsent: (scope: ConsentScope, durationMinutes: number | null) => invoke<ConsentStatus>("grant_monitoring_consent", { scope, durationMinutes }),
+  /** Withdraw monitoring consent and stop every monitor and input counting */
+  revokeMonitoringConsent: () => invoke<ConsentStatus>("revoke_monitoring_consent"),
+  /** The monitoring consent in effect and when it expires */
+  getConsentState: () => invoke<ConsentStatus>("get_consent_state"),
+  /** Audit log of monitoring consent grants, revocations and expiries */
+  getConsentLog: () => invoke<ConsentLogEntry[]>("get_consent_log"),
+  /** Start a monitor (full screen, active window or a matching window) emitting screen-changed:{id} events */
+  startMonitoring: (options: MonitorOptions | null) => invoke<MonitorInfo>("start_monitoring", { options }),
+  /** Watch a window with OCR and emit text-detected when new text matches a regex (e.g. a build error) */
+  watchForText: (targetWindow: MonitorTarget, pattern: string, interval: number | null) => invoke<MonitorInfo>("watch_for_text", { targetWindow, pattern, interval }),
+  /** Stop a monitor by id, or every monitor when no id is given */
+  stopMonitoring: (id: string | null) => invoke<null>("stop_monitoring", { id }),
+  /** Read a math formula from an image and return it as LaTeX */
+  recognizeMath: (imageBase64: string) => invoke<MathRecognition>("recognize_math", { imageBase64 }),
+  /** Get the LaTeX-OCR server endpoint */
+  getMathOcrSettings: () => invoke<MathOcrSettings>("get_math_ocr_settings"),
+  /** Set the LaTeX-OCR server endpoint */
+  setMathOcrSettings: (settings: MathOcrSettings) => invoke<null>("set_math_ocr_settings", { settings }),
+  /** Read handwriting from pen strokes (Windows Ink) or from an image of handwritten work (preprocessed OCR) */
+  recognizeHandwriting: (imageBase64: string | null, strokes: InkStroke[] | null) => invoke<HandwritingRecognition>("recognize_handwriting", { imageBase64, strokes }),
+  /** List the cameras a webcam frame can be taken from */
+  listWebcams: () => invoke<WebcamDevice[]>("list_webcams"),
+  /** Take a photo with a webcam, e.g. of paper homework, optionally with handwriting OCR */
+  captureWebcamFrame: (deviceId: string | null, ocr: boolean | null, requestId: string | null) => invoke<CaptureResult>("capture_webcam_frame", { deviceId, ocr, requestId }),
+  /** Grade a photo or scan of a paper worksheet (or a webcam frame) against a saved answer key, question by question */
+  gradeWorksheet: (imageBase64: string | null, answerKeyId: string, deviceId: string | null, requestId: string | null) => invoke<WorksheetGrade>("grade_worksheet", { imageBase64, answerKeyId, deviceId, requestId }),
+  /** List the saved worksheet answer keys, without their answers */
+  listAnswerKeys: () => invoke<AnswerKeySummary[]>("list_answer_keys"),
+  /** Get a worksheet answer key with its answers */
+  getAnswerKey: (id: string) => invoke<AnswerKey>("get_answer_key", { id }),
+  /** Save a worksheet answer key, replacing the one with the same id */
+  saveAnswerKey: (key: AnswerKey) => invoke<AnswerKey>("save_answer_key", { key }),
+  /** Delete a worksheet answer key */
+  deleteAnswerKey: (id: string) => invoke<boolean>("delete_answer_key", { id }),
+  /** Import a PDF or DOCX file's text (and PDF page images) into the searchable history */
+  importDocument: (path: string, requestId: string | null) => invoke<HistoryItem>("import_document", { path, requestId }),
+  /** Transcribe a voice memo or lecture recording offline with whisper.cpp into the history, one part per minute */
+  transcribeAudioFile: (path: string, options: TranscribeOptions | null) => invoke<HistoryItem>("transcribe_audio_file", { path, options }),
+  /** Translate text, e.g. OCR of a worksheet, into a language with the configured backend (local model, LibreTranslate or cloud AI) */
+  translateText: (text: string, targetLang: string) => invoke<Translation>("translate_text", { text, targetLang }),
+  /** Get the translation backend and LibreTranslate URL */
+  getTranslationSettings: () => invoke<TranslationSettings>("get_translation_settings"),
+  /** Choose the translation backend: a local model, a LibreTranslate server or the cloud AI providers */
+  setTranslationSettings: (settings: TranslationSettings) => invoke<null>("set_translation_settings", { settings }),
+  /** Reading level (Flesch-Kincaid), key terms and an extractive summary of a text, computed locally */
+  analyzeText: (text: string, summarySentences: number | null) => invoke<TextAnalysis>("analyze_text", { text, summarySentences }),
+  /** Define a word from the offline dictionary, falling back to Wiktionary when online */
+  defineTerm: (word: string, lang: string, onlineFallback: boolean | null) => invoke<Definition>("define_term", { word, lang, onlineFallback }),
+  /** Fill the offline dictionary for a language from a Wiktionary JSON-lines extract */
+  importDictionary: (path: string, lang: string) => invoke<DictionaryImport>("import_dictionary", { path, lang }),
+  /** Full-text search of the history, optionally limited to one kind of item */
+  searchHistory: (query: string, kind: string | null, limit: number | null) => invoke<HistoryMatch[]>("search_history", { query, kind, limit }),
+  /** Get a history item with all of its text */
+  getHistoryItem: (id: number) => invoke<HistoryItem>("get_history_item", { id }),
+  /** Delete a history item and any page images stored with it */
+  deleteHistoryItem: (id: number) => invoke<null>("delete_history_item", { id }),
+  /** Save a knowledge-base note, e.g. a lesson summary, optionally linked to captures, sessions or history items */
+  createNote: (note: NewNote) => invoke<Note>("create_note", { note }),
+  /** Get a note by id */
+  getNote: (id: number) => invoke<Note>("get_note", { id }),
+  /** Change a note's title, body, tags or links */
+  updateNote: (id: number, update: NoteUpdate) => invoke<Note>("update_note", { id, update }),
+  /** Delete a note */
+  deleteNote: (id: number) => invoke<null>("delete_note", { id }),
+  /** Full-text search of notes, or the most recent ones without a query, optionally only those with a given link */
+  searchNotes: (query: string | null, link: NoteLink | null, limit: number | null) => invoke<Note[]>("search_notes", { query, link, limit }),
+  /** List the configured AI providers in failover order and whether each has an API key */
+  getAiProviders: () => invoke<ProviderInfo[]>("get_ai_providers"),
+  /** Replace the AI providers; the list order is the failover order */
+  setAiProviders: (providers: ProviderConfig[]) => invoke<null>("set_ai_providers", { providers }),
+  /** Store (or remove) an AI provider's API key in the OS keychain */
+  setProviderApiKey: (id: string, apiKey: string | null) => invoke<null>("set_provider_api_key", { id, apiKey }),
+  /** Send a tiny prompt to one AI provider to check its key, model and reachability */
+  testProvider: (id: string) => invoke<ProviderTest>("test_provider", { id }),
+  /** Answer a chat with the first enabled AI provider that responds, failing over down the list */
+  aiChat: (request: ChatRequest) => invoke<ChatResponse>("ai_chat", { request }),
+  /** Stream a chat answer as ai-token events ending with ai-complete or ai-error; returns the request id */
+  aiChatStream: (request: ChatRequest, requestId: string | null) => invoke<string>("ai_chat_stream", { request, requestId }),
+  /** List the tools the AI can call in ai_chat_with_tools, with their argument schemas */
+  listAiTools: () => invoke<AiTool[]>("list_ai_tools"),
+  /** Chat letting the AI capture windows, read project files and run allowlisted commands, each checked against policy in the backend */
+  aiChatWithTools: (request: ChatRequest, tools: string[] | null, maxRounds: number | null) => invoke<ToolChatResponse>("ai_chat_with_tools", { request, tools, maxRounds }),
+  /** Requests, tokens and estimated cost of AI calls in a time range (default this month), per model */
+  getAiUsage: (range: UsageRange | null) => invoke<AiUsageReport>("get_ai_usage", { range }),
+  /** Get the monthly AI budget and the model prices used to estimate cost */
+  getAiBudget: () => invoke<AiBudgetSettings>("get_ai_budget"),
+  /** Change the monthly AI budget that blocks further paid requests, and the model prices */
+  setAiBudget: (settings: AiBudgetSettings) => invoke<null>("set_ai_budget", { settings }),
+  /** List the GGUF models downloaded for offline tutoring */
+  listLocalModels: () => invoke<LocalModel[]>("list_local_models"),
+  /** Download a GGUF model, resuming an interrupted download, and keep it once its SHA-256 matches; emits model-download-progress */
+  downloadModel: (request: ModelDownloadRequest, requestId: string | null) => invoke<LocalModel>("download_model", { request, requestId }),
+  /** Re-hash a downloaded model and compare it with its expected SHA-256 */
+  verifyModel: (id: string) => invoke<ModelVerification>("verify_model", { id }),
+  /** Delete a downloaded model or the partial file of an interrupted download */
+  deleteModel: (id: string) => invoke<null>("delete_model", { id }),
+  /** Load a downloaded model in-process with llama.cpp for the llamacpp AI provider */
+  startLocalModel: (id: string) => invoke<LoadedModelStatus>("start_local_model", { id }),
+  /** Unload the local model */
+  stopLocalModel: () => invoke<null>("stop_local_model"),
+  /** The local model loaded for the llamacpp AI provider, if any */
+  getLoadedLocalModel: () => invoke<LoadedModelStatus | null>("get_loaded_local_model"),
+  /** Mask API keys, passwords and other secrets in text before it is sent to an AI provider, reporting what was masked */
+  filterSecrets: (text: string) => invoke<FilteredText>("filter_secrets", { text }),
+  /** Estimate how many tokens a text uses for a model (the configured model when omitted) */
+  countTokens: (text: string, model: string | null) => invoke<TokenCount>("count_tokens", { text, model }),
+  /** Join OCR and context sections into one text within a token budget, active window and newest first */
+  assembleContext: (sections: ContextSection[], maxTokens: number | null, model: string | null) => invoke<AssembledContext>("assemble_context", { sections, maxTokens, model }),
+  /** Get the default token budget and model for assembled context */
+  getContextBudget: () => invoke<ContextBudgetSettings>("get_context_budget"),
+  /** Change the default token budget and model for assembled context */
+  setContextBudget: (settings: ContextBudgetSettings) => invoke<null>("set_context_budget", { settings }),
+  /** Start an AI chat conversation, optionally as part of a focus session */
+  createConversation: (title: string | null, sessionId: string | null) => invoke<Conversation>("create_conversation", { title, sessionId }),
+  /** Add a message to the end of a conversation, optionally linked to the capture it was about */
+  appendMessage: (conversationId: number, message: NewMessage) => invoke<ConversationMessage>("append_message", { conversationId, message }),
+  /** Replace the text of a stored chat message */
+  updateMessage: (id: number, content: string) => invoke<null>("update_message", { id, content }),
+  /** Get a conversation with all its messages */
+  getConversation: (id: number) => invoke<Conversation>("get_conversation", { id }),
+  /** List conversations, most recently active first, optionally only those of one focus session */
+  listConversations: (sessionId: string | null, limit: number | null) => invoke<ConversationSummary[]>("list_conversations", { sessionId, limit }),
+  /** Full-text search of chat messages, returning the conversations that contain them */
+  searchConversations: (query: string, limit: number | null) => invoke<ConversationSummary[]>("search_conversations", { query, limit }),
+  /** Copy a conversation up to a message into a new conversation that can continue differently */
+  branchConversation: (id: number, messageId: number, title: string | null) => invoke<Conversation>("branch_conversation", { id, messageId, title }),
+  /** Delete a conversation and its messages */
+  deleteConversation: (id: number) => invoke<null>("delete_conversation", { id }),
+  /** Save a prompt template with {{variable}} placeholders as a new version of its name */
+  savePrompt: (template: NewPromptTemplate) => invoke<PromptTemplate>("save_prompt", { template }),
+  /** Get the latest or a given version of a prompt template */
+  getPrompt: (name: string, version: number | null) => invoke<PromptTemplate>("get_prompt", { name, version }),
+  /** List the latest version of every prompt template */
+  listPrompts: () => invoke<PromptTemplate[]>("list_prompts"),
+  /** List every saved version of a prompt template, newest first */
+  listPromptVersions: (name: string) => invoke<PromptTemplate[]>("list_prompt_versions", { name }),
+  /** Delete a prompt template with all its versions */
+  deletePrompt: (name: string) => invoke<null>("delete_prompt", { name }),
+  /** Fill in a prompt template's variables, using their defaults where no value is given */
+  renderPrompt: (name: string, vars: Record<string, string> | null, version: number | null) => invoke<string>("render_prompt", { name, vars, version }),
+  /** Add a spaced-repetition flashcard, due right away */
+  addCard: (front: string, back: string, deck: string | null, noteId: number | null) => invoke<Flashcard>("add_card", { front, back, deck, noteId }),
+  /** Flashcards due for review, most overdue first */
+  getDueCards: (deck: string | null, limit: number | null) => invoke<Flashcard[]>("get_due_cards", { deck, limit }),
+  /** Grade a review from 0 (blackout) to 5 (perfect) and schedule the card's next review (SM-2) */
+  gradeCard: (id: number, grade: number) => invoke<Flashcard>("grade_card", { id, grade }),
+  /** Delete a flashcard and its review history */
+  deleteCard: (id: number) => invoke<null>("delete_card", { id }),
+  /** Register an exercise (or update the one with the same key) so attempts at it can be tracked */
+  registerExercise: (exercise: NewExercise) => invoke<Exercise>("register_exercise", { exercise }),
+  /** List registered exercises, optionally of one topic */
+  listExercises: (topic: string | null) => invoke<Exercise[]>("list_exercises", { topic }),
+  /** Record an attempt at an exercise and whether it was correct */
+  recordAttempt: (exerciseId: number, correct: boolean, answer: string | null, durationSecs: number | null) => invoke<ExerciseAttempt>("record_attempt", { exerciseId, correct, answer, durationSecs }),
+  /** An exercise's attempts, newest first */
+  getAttempts: (exerciseId: number) => invoke<ExerciseAttempt[]>("get_attempts", { exerciseId }),
+  /** Mastery statistics per topic: exercises mastered, attempts, recent accuracy */
+  getMastery: (topic: string | null) => invoke<TopicMastery[]>("get_mastery", { topic }),
+  /** Import a lesson plan (units, lessons, prerequisites) from a JSON or YAML file */
+  importCurriculum: (path: string) => invoke<CurriculumProgress>("import_curriculum", { path }),
+  /** List imported curricula with how many lessons are done */
+  listCurricula: () => invoke<CurriculumSummary[]>("list_curricula"),
+  /** Get a curriculum with each lesson's completion state and the next recommended lesson */
+  getCurriculum: (id: string) => invoke<CurriculumProgress>("get_curriculum", { id }),
+  /** Mark a lesson done or not done */
+  setLessonCompleted: (curriculumId: string, lessonId: string, completed: boolean) => invoke<CurriculumProgress>("set_lesson_completed", { curriculumId, lessonId, completed }),
+  /** The next recommended lesson: the first one not done whose prerequisites are */
+  getNextLesson: (curriculumId: string) => invoke<LessonStatus | null>("get_next_lesson", { curriculumId }),
+  /** Delete a curriculum and its progress */
+  deleteCurriculum: (id: string) => invoke<null>("delete_curriculum", { id }),
+  /** Daily study streaks, time on task and badges with their progress */
+  getAchievements: () => invoke<Achievements>("get_achievements"),
+  /** Whether and when the end-of-day summary is made */
+  getDailySummarySettings: () => invoke<DailySummarySettings>("get_daily_summary_settings"),
+  /** Enable or disable the end-of-day summary, set its time (HH:MM) and whether to ask for an AI narrative */
+  setDailySummarySettings: (settings: DailySummarySettings) => invoke<null>("set_daily_summary_settings", { settings }),
+  /** Make the summary of a day (YYYY-MM-DD, default today) now and emit daily-summary-ready */
+  generateDailySummary: (day: string | null) => invoke<DailySummary>("generate_daily_summary", { day }),
+  /** The stored summary of a day (YYYY-MM-DD, default today), if one was made */
+  getDailySummary: (day: string | null) => invoke<DailySummary | null>("get_daily_summary", { day }),
+  /** Store the AI-written narrative for a day's summary */
+  setDailySummaryNarrative: (day: string, narrative: string) => invoke<DailySummary>("set_daily_summary_narrative", { day, narrative }),
+  /** Zip all of the active profile's data (settings, history database, documents, captures) to a file */
+  exportAllData: (path: string) => invoke<DataExport>("export_all_data", { path }),
+  /** Replace the active profile's data with an export from export_all_data and restart the app */
+  importAllData: (path: string) => invoke<null>("import_all_data", { path }),
+  /** How long captures and stored history text are kept */
+  getRetentionSettings: () => invoke<RetentionSettings>("get_retention_settings"),
+  /** Set how many days captures and stored history text are kept (null keeps them) and apply it now */
+  setRetentionSettings: (settings: RetentionSettings) => invoke<RetentionReport>("set_retention_settings", { settings }),
+  /** What the last retention run deleted or anonymized */
+  getRetentionReport: () => invoke<RetentionReport | null>("get_retention_report"),
+  /** Count the saved captures and issue a one-minute token that confirms purge_all_captures */
+  prepareCapturePurge: () => invoke<PurgeConfirmation>("prepare_capture_purge"),
+  /** Delete every saved capture and recording of the active profile, given a token from prepare_capture_purge */
+  purgeAllCaptures: (token: string) => invoke<RetentionReport>("purge_all_captures", { token }),
+  /** Label content (code, terminal, article, video, chat, ...) from window metadata and optional OCR text */
+  classifyScreenContent: (windowTitle: string, processName: string, ocrText: string | null) => invoke<ContentClassification>("classify_screen_content", { windowTitle, processName, ocrText }),
+  /** Time spent on each kind of content over the last hours (default 24) */
+  getContentTime: (hours: number | null) => invoke<ContentTime[]>("get_content_time", { hours }),
+  /** List the running monitors */
+  listMonitors: () => invoke<MonitorInfo[]>("list_monitors"),
+  /** Read new text appearing in a window aloud (or an AI summary of it), spaced at least min_gap_seconds apart; emits narration */
+  startNarrator: (target: MonitorTarget, options: NarratorOptions | null) => invoke<NarratorInfo>("start_narrator", { target, options }),
+  /** Stop the narrator and anything it is saying; returns whether it was running */
+  stopNarrator: () => invoke<boolean>("stop_narrator"),
+  /** The running narrator's target and options, if any */
+  getNarrator: () => invoke<NarratorInfo | null>("get_narrator"),
+  /** Get the monitoring capture interval in seconds */
+  getCaptureInterval: () => invoke<number>("get_capture_interval"),
+  /** Set the monitoring capture interval (1-10 seconds) */
+  setCaptureInterval: (interval: number) => invoke<null>("set_capture_interval", { interval }),
+  /** Minimize the main window */
+  minimizeWindow: () => invoke<null>("minimize_window"),
+  /** Toggle maximize on the main window */
+  maximizeWindow: () => invoke<null>("maximize_window"),
+  /** Close the main window */
+  closeWindow: () => invoke<null>("close_window"),
+  /** Run an allowlisted command subject to the command policy */
+  executeCommand: (command: string, args: string[]) => invoke<CommandResult>("execute_command", { command, args }),
+  /** CPU, memory, time and network limits applied to executed commands */
+  getSandboxSettings: () => invoke<SandboxSettings>("get_sandbox_settings"),
+  /** Change the limits applied to executed commands (teacher mode only) */
+  setSandboxSettings: (settings: SandboxSettings) => invoke<null>("set_sandbox_settings", { settings }),
+  /** Dry-run a multi-line script: each command with its policy verdict, plus a confirmation token when all are permitted */
+  executeScript: (script: string) => invoke<ScriptPlan>("execute_script", { script }),
+  /** Run a script previously analyzed by execute_script, using its one-time token */
+  confirmExecution: (token: string) => invoke<ScriptRun>("confirm_execution", { token }),
+  /** Diff consecutive OCR texts of the same window and attach added/removed lines to captures */
+  setCaptureDiffEnabled: (enabled: boolean) => invoke<null>("set_capture_diff_enabled", { enabled }),
+  /** Whether capture diffs are enabled */
+  getCaptureDiffEnabled: () => invoke<boolean>("get_capture_diff_enabled"),
+  /** Get the homework-detection mode settings */
+  getHomeworkDetectionSettings: () => invoke<HomeworkDetectionSettings>("get_homework_detection_settings"),
+  /** Enable or disable homework detection and session auto-start */
+  setHomeworkDetection: (settings: HomeworkDetectionSettings) => invoke<null>("set_homework_detection", { settings }),
+  /** Check window metadata and OCR text for an assignment context */
+  detectHomeworkContext: (options: HomeworkDetectionParams) => invoke<HomeworkContext | null>("detect_homework_context", { options }),
+  /** Get the last known connectivity status of the AI endpoint */
+  getConnectivityStatus: () => invoke<ConnectivityStatus>("get_connectivity_status"),
+  /** Probe the AI endpoint now */
+  checkConnectivity: () => invoke<ConnectivityStatus>("check_connectivity"),
+  /** Change the AI endpoint used for connectivity checks */
+  setConnectivityEndpoint: (endpoint: string) => invoke<ConnectivityStatus>("set_connectivity_endpoint", { endpoint }),
+  /** Queue an AI request payload to be sent when the endpoint is reachable */
+  enqueueAiRequest: (kind: string, payload: unknown) => invoke<PendingRequest>("enqueue_ai_request", { kind, payload }),
+  /** List AI requests waiting for connectivity */
+  getPendingRequests: () => invoke<PendingRequest[]>("get_pending_requests"),
+  /** Remove a queued request after it was sent successfully */
+  completePendingRequest: (id: string) => invoke<null>("complete_pending_request", { id }),
+  /** Put back a queued request whose send failed so it is retried */
+  failPendingRequest: (id: string) => invoke<null>("fail_pending_request", { id }),
+  /** Drop a queued request without sending it */
+  cancelPendingRequest: (id: string) => invoke<boolean>("cancel_pending_request", { id }),
+  /** Start an allowlisted long-running command under the sandbox limits (no time limit) and stream its output */
+  startLongRunningCommand: (command: string, args: string[]) => invoke<JobInfo>("start_long_running_command", { command, args }),
+  /** List long-running commands that are still running */
+  listRunningJobs: () => invoke<JobInfo[]>("list_running_jobs"),
+  /** Stop a long-running command */
+  killJob: (id: string) => invoke<boolean>("kill_job", { id }),
+  /** Open an interactive shell in a pseudo-terminal */
+  createTerminal: (options: CreateTerminalParams) => invoke<TerminalInfo>("create_terminal", { options }),
+  /** Send keystrokes to a terminal; lines that violate the command policy are cancelled */
+  writeToTerminal: (id: string, data: string) => invoke<null>("write_to_terminal", { id, data }),
+  /** Resize a terminal to the given number of columns and rows */
+  resizeTerminal: (id: string, cols: number, rows: number) => invoke<null>("resize_terminal", { id, cols, rows }),
+  /** Close a terminal and kill its shell */
+  closeTerminal: (id: string) => invoke<boolean>("close_terminal", { id }),
+  /** List open terminals */
+  listTerminals: () => invoke<TerminalInfo[]>("list_terminals"),
+  /** List the project folders the user approved for reading */
+  getProjectRoots: () => invoke<string[]>("get_project_roots"),
+  /** Approve a project folder for reading */
+  addProjectRoot: (path: string) => invoke<string[]>("add_project_root", { path }),
+  /** Revoke access to an approved project folder */
+  removeProjectRoot: (path: string) => invoke<boolean>("remove_project_root", { path }),
+  /** Read a text file inside an approved project folder */
+  readProjectFile: (path: string) => invoke<ProjectFile>("read_project_file", { path }),
+  /** List a directory inside an approved project folder */
+  listDirectory: (path: string) => invoke<DirectoryEntry[]>("list_directory", { path }),
+  /** Search text files under an approved project folder */
+  searchInFiles: (root: string, query: string) => invoke<SearchMatch[]>("search_in_files", { root, query }),
+  /** Detect the project type and framework in an approved folder and suggest its install, run and test commands */
+  analyzeProject: (root: string) => invoke<ProjectAnalysis>("analyze_project", { root }),
+  /** Watch the approved project folders and emit file-changed events */
+  startFileWatcher: () => invoke<FileWatcherStatus>("start_file_watcher"),
+  /** Stop watching project folders */
+  stopFileWatcher: () => invoke<null>("stop_file_watcher"),
+  /** Whether the file watcher is running and which folders it watches */
+  getFileWatcherStatus: () => invoke<FileWatcherStatus>("get_file_watcher_status"),
+  /** Branch, upstream and changed files of a git repository in an approved project folder */
+  getGitStatus: (repo: string) => invoke<GitStatus>("get_git_status", { repo }),
+  /** Staged or unstaged changes of a git repository, per file with patches */
+  getGitDiff: (repo: string, staged: boolean) => invoke<GitDiff>("get_git_diff", { repo, staged }),
+  /** The last n commits of a git repository, newest first */
+  getRecentCommits: (repo: string, n: number) => invoke<GitCommit[]>("get_recent_commits", { repo, n }),
+  /** Opt in to counting keystrokes and clicks per minute (never their content); needs monitoring consent */
+  setActivityTracking: (enabled: boolean) => invoke<null>("set_activity_tracking", { enabled }),
+  /** Keystroke and click counts per minute for a time range */
+  getActivityMetrics: (range: ActivityRange) => invoke<ActivityMetrics>("get_activity_metrics", { range }),
+  /** Start a typing lesson: read the passage in a practice window with OCR, then count and time keystrokes typed into it (never which keys) */
+  startTypingSession: (windowTitle: string | null, processName: string | null) => invoke<TypingSessionStats>("start_typing_session", { windowTitle, processName }),
+  /** Typing speed, accuracy and progress of the current or last typing session */
+  getTypingSessionStats: () => invoke<TypingSessionStats | null>("get_typing_session_stats"),
+  /** End the typing session and return its final stats */
+  stopTypingSession: () => invoke<TypingSessionStats | null>("stop_typing_session"),
+  /** Start a pomodoro-style focus session alternating focus and break phases */
+  startFocusSession: (minutes: number, breakMinutes: number, pauseMonitoringDuringBreaks: boolean | null) => invoke<FocusSession>("start_focus_session", { minutes, breakMinutes, pauseMonitoringDuringBreaks }),
+  /** Pause the running focus session */
+  pauseFocusSession: () => invoke<FocusSession>("pause_focus_session"),
+  /** Resume a paused focus session */
+  resumeFocusSession: () => invoke<FocusSession>("resume_focus_session"),
+  /** End the focus session */
+  stopFocusSession: () => invoke<boolean>("stop_focus_session"),
+  /** The current focus session, if any */
+  getFocusSession: () => invoke<FocusSession | null>("get_focus_session"),
+  /** The study/distraction classification rules */
+  getDistractionRules: () => invoke<DistractionRules>("get_distraction_rules"),
+  /** Replace the study/distraction classification rules */
+  setDistractionRules: (rules: DistractionRules) => invoke<null>("set_distraction_rules", { rules }),
+  /** Classify a window or URL as study, neutral or distraction and update the streak */
+  classifyActivity: (windowTitle: string, processName: string, url: string | null) => invoke<Classification>("classify_activity", { windowTitle, processName, url }),
+  /** How long the current study/neutral/distraction streak has lasted */
+  getActivityStreak: () => invoke<StreakStatus | null>("get_activity_streak"),
+  /** Dashboard sync settings */
+  getSyncSettings: () => invoke<SyncSettings>("get_sync_settings"),
+  /** Configure pushing anonymized usage summaries to a teacher/parent dashboard */
+  setSyncSettings: (enabled: boolean, endpoint: string | null, intervalMinutes: number) => invoke<SyncSettings>("set_sync_settings", { enabled, endpoint, intervalMinutes }),
+  /** Store (or remove) the dashboard auth token in the OS keychain */
+  setSyncToken: (token: string | null) => invoke<null>("set_sync_token", { token }),
+  /** Queue a session report for the next dashboard sync */
+  queueSessionReport: (report: unknown) => invoke<string>("queue_session_report", { report }),
+  /** Push the usage summary and queued session reports immediately */
+  syncNow: () => invoke<SyncStatus>("sync_now"),
+  /** Result of the last dashboard sync and number of queued reports */
+  getSyncStatus: () => invoke<SyncStatus>("get_sync_status"),
+  /** Whether the local integration server is running, with its URL and token */
+  getIntegrationServerStatus: () => invoke<IntegrationServerStatus>("get_integration_server_status"),
+  /** Start or stop the token-protected local WebSocket server for external tools; prometheus also serves /metrics on it */
+  setIntegrationServer: (enabled: boolean, port: number | null, prometheus: boolean | null) => invoke<IntegrationServerStatus>("set_integration_server", { enabled, port, prometheus }),
+  /** Issue a new integration server token, disconnecting existing clients */
+  regenerateIntegrationToken: () => invoke<IntegrationServerStatus>("regenerate_integration_token"),
+  /** Whether a remote view session is running, with its one-time code and URLs */
+  getRemoteViewStatus: () => invoke<RemoteViewStatus>("get_remote_view_status"),
+  /** Let a tutor watch the monitored frames from a browser, using a one-time code; localhost only unless local_network is set */
+  startRemoteView: (port: number | null, localNetwork: boolean | null) => invoke<RemoteViewStatus>("start_remote_view", { port, localNetwork }),
+  /** End the remote view session and disconnect the viewer */
+  stopRemoteView: () => invoke<RemoteViewStatus>("stop_remote_view"),
+  /** Self-test screen capture, OCR, PowerShell, disk space and AI endpoint access */
+  runDiagnostics: () => invoke<DiagnosticsReport>("run_diagnostics"),
+  /** Capture, OCR and AI call counts and latency histograms since start, the OCR cache hit rate and the event queue depth */
+  getMetrics: () => invoke<Metrics>("get_metrics"),
+  /** Time full-screen capture, PNG encoding, OCR and capture_window_with_ocr on this machine, returning percentiles per stage; cancel with request_id */
+  runBenchmark: (iterations: number | null, requestId: string | null) => invoke<BenchmarkReport>("run_benchmark", { iterations, requestId }),
+  /** Zip recent logs, crash reports, scrubbed settings, the command policy and diagnostics for a bug report; the last capture is added only with consent (redacted or full) */
+  createDebugBundle: (screenshot: ScreenshotConsent | null) => invoke<DebugBundle>("create_debug_bundle", { screenshot }),
+  /** Get the most recent backend panic report, including ones from earlier runs */
+  getLastCrashReport: () => invoke<CrashReport | null>("get_last_crash_report"),
+  /** Health of the background subsystems (screen monitors, narrator, distraction watcher, OCR workers) with their restart counts */
+  getSubsystemHealth: () => invoke<SubsystemHealth[]>("get_subsystem_health"),
+  /** Change the backend log level (trace, debug, info, warn, error, off) */
+  setLogLevel: (level: string) => invoke<null>("set_log_level", { level }),
+  /** List student profiles and the active one */
+  listProfiles: () => invoke<ProfileList>("list_profiles"),
+  /** Create a student profile with its own settings, history, captures and usage stats */
+  createProfile: (name: string) => invoke<Profile>("create_profile", { name }),
+  /** Switch to another profile; the app restarts to load its data */
+  switchProfile: (id: string) => invoke<null>("switch_profile", { id }),
+  /** Get whether the app is in student or teacher mode and whether a teacher PIN is set */
+  getRoleStatus: () => invoke<RoleStatus>("get_role_status"),
+  /** Set or change the 4-8 digit teacher PIN; once set, the app starts in student mode */
+  setTeacherPin: (currentPin: string | null, newPin: string) => invoke<RoleStatus>("set_teacher_pin", { currentPin, newPin }),
+  /** Enter teacher mode for 30 minutes with the teacher PIN */
+  unlockTeacherMode: (pin: string) => invoke<RoleStatus>("unlock_teacher_mode", { pin }),
+  /** Leave teacher mode and return to student mode */
+  lockTeacherMode: () => invoke<RoleStatus>("lock_teacher_mode"),
+  /** Get the programs students may run and the commands reserved for teacher mode */
+  getRolePolicy: () => invoke<RolePolicy>("get_role_policy"),
+  /** Change the active profile's student permissions (teacher mode only) */
+  setRolePolicy: (policy: RolePolicy) => invoke<null>("set_role_policy", { policy }),
+  /** Check the configured update channel for a newer build; null when up to date */
+  checkForUpdates: () => invoke<UpdateInfo | null>("check_for_updates"),
+  /** Download and install the available update (emitting update-progress), then restart */
+  installUpdate: () => invoke<null>("install_update"),
+  /** Get the update channel (stable/beta) and whether updates are checked at startup */
+  getUpdateSettings: () => invoke<UpdateSettings>("get_update_settings"),
+  /** Set the update channel (stable/beta) and whether updates are checked at startup */
+  setUpdateSettings: (settings: UpdateSettings) => invoke<null>("set_update_settings", { settings }),
+  /** Get the most recent backend log entries, oldest first */
+  getRecentLogs: (n: number) => invoke<LogEntry[]>("get_recent_logs", { n }),
+  /** Events emitted after a sequence number, to catch up after the webview reloads */
+  getMissedEvents: (sinceSeq: number) => invoke<MissedEvents>("get_missed_events", { sinceSeq }),
+  /** List every backend command with its parameters and requirements */
+  listAvailableCommands: () => invoke<CommandCatalog>("list_available_commands"),
+};
+
+export interface EventPayloads {
+  "app-ready": null;
+  "screen-changed": CaptureResult;
+  [channel: `screen-changed:${string}`]: CaptureResult;
+  "homework-context-detected": HomeworkContext;
+  "connectivity-changed": ConnectivityStatus;
+  "pending-request-ready": PendingRequest;
+  "job-output": JobOutput;
+  "job-exited": JobExit;
+  "terminal-output": TerminalOutput;
+  "terminal-exited": TerminalExit;
+  "terminal-command-blocked": TerminalCommandBlocked;
+  "file-changed": FileChange;
+  "focus-phase-changed": FocusPhaseChange;
+  "distraction-detected": DistractionDetected;
+  "capture-progress": CaptureProgress;
+  "app-error": AppError;
+  "screen-recording-finished": RecordingFinished;
+  "ocr-language-progress": OcrLanguageProgress;
+  "update-available": UpdateInfo;
+  "update-progress": UpdateProgress;
+  "role-changed": RoleStatus;
+  "remote-view-changed": RemoteViewStatus;
+  "flashcards-due": FlashcardsDue;
+  "achievement-unlocked": Badge;
+  "daily-summary-ready": DailySummary;
+  "consent-changed": ConsentStatus;
+  "observation-changed": ObservationStatus;
+  "content-ingested": ContentIngested;
+  "text-detected": TextDetected;
+  "budget-exceeded": BudgetExceeded;
+  "ai-token": AiToken;
+  "ai-complete": AiComplete;
+  "ai-error": AiError;
+  "model-download-progress": ModelDownloadProgress;
+  "narration": Narration;
+  "subsystem-unhealthy": SubsystemUnhealthy;
+  "deep-link-opened": DeepLinkOpened;
+}
+
+/** Sequence number of the latest event seen, to pass to `getMissedEvents` after a reload */
+export let lastEventSeq = Number(sessionStorage.getItem("lastEventSeq") ?? 0);
+
+export function listenEvent<K extends keyof EventPayloads>(
+  event: K,
+  handler: (payload: EventPayloads[K]) => void,
+): Promise<UnlistenFn> {
+  return listen<EventPayloads[K]>(event, (e) => {
+    const seq = (e.payload as { seq?: number } | null)?.seq;
+    if (typeof seq === "number" && seq > lastEventSeq) {
+      lastEventSeq = seq;
+      sessionStorage.setItem("lastEventSeq", String(seq));
+    }
+    handler(e.payload);
+  });
+}
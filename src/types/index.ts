@@ -1,4 +1,81 @@
 import type { WindowAnalysis } from "../services/windowAnalysis";
+import type {
+  CommandResult,
+  Conversation as StoredConversation,
+  ConversationMessage as StoredMessage,
+  Counter as MetricCounter,
+  WindowCaptureResult as BackendWindowCaptureResult,
+} from "./bindings";
+
+// Backend command and event types are generated into bindings.ts from the Rust structs
+export type {
+  AiBudgetSettings,
+  AiComplete,
+  AiError,
+  AiToken,
+  AiTool,
+  AiUsageReport,
+  AnswerKey,
+  AnswerKeyQuestion,
+  AnswerKeySummary,
+  AnswerKind,
+  AssembledContext,
+  BenchmarkReport,
+  BudgetExceeded,
+  CaptureMemoryUsage,
+  CaptureResult,
+  ChatMessage,
+  ChatRequest,
+  ChatResponse,
+  ClipboardImage,
+  CommandResult,
+  ContextBudgetSettings,
+  ContextSection,
+  ConversationSummary,
+  Definition,
+  DevEnvironment,
+  DictionaryImport,
+  FilteredText,
+  KeyTerm,
+  LatencyBucket,
+  LatencyHistogram,
+  LoadedModelStatus,
+  LocalModel,
+  Metrics,
+  ModelDownloadProgress,
+  ModelDownloadRequest,
+  ModelDownloadStage,
+  ModelPrice,
+  ModelVerification,
+  MonitorTarget,
+  Narration,
+  NarratorInfo,
+  NarratorOptions,
+  ParsedDiagnostic,
+  PromptTemplate,
+  PromptVariable,
+  ProviderConfig,
+  ProviderInfo,
+  ProviderKind,
+  ProviderTest,
+  QuestionGrade,
+  Redaction,
+  Sense,
+  SourceContext,
+  StageTimings,
+  SystemContext,
+  TextAnalysis,
+  TokenCount,
+  ToolCallRecord,
+  ToolChatResponse,
+  ToolInfo,
+  Translation,
+  TypingSessionStats,
+  WindowInfo,
+  WorksheetGrade,
+} from "./bindings";
+// Backend types the frontend knows by another name; stored conversations have timestamps in seconds
+export type { MetricCounter, StoredConversation, StoredMessage };
 
 export interface Message {
   id: string;
@@ -16,12 +93,6 @@ export interface Conversation {
   updatedAt: number;
 }
 
-export interface CaptureResult {
-  image_base64: string;
-  hash: string;
-  timestamp: number;
-}
-
 export interface Settings {
   geminiApiKey: string;
   anthropicApiKey?: string;
@@ -38,27 +109,7 @@ export interface ProcessEvent {
   timestamp: number;
 }
 
-export interface WindowInfo {
-  title: string;
-  process_name: string;
-  is_active: boolean;
-}
-
-export interface SystemContext {
-  active_window: string;
-  active_window_title: string;
-  open_windows: WindowInfo[];
-  running_applications: string[];
-  timestamp: number;
-}
-
-export interface WindowCaptureResult {
-  image_base64: string;
-  hash: string;
-  timestamp: number;
-  ocr_text?: string | null;
-  window_title: string;
-  process_name: string;
+export interface WindowCaptureResult extends BackendWindowCaptureResult {
   analysis?: WindowAnalysis | null;
 }
 
@@ -72,14 +123,6 @@ export interface CommandPolicyDecision {
   notes?: string;
 }
 
-export interface CommandResult {
-  success: boolean;
-  stdout: string;
-  stderr: string;
-  exit_code: number | null;
-  error: string | null;
-}
-
 export interface PendingCommandRequest {
   id: string;
   command: string;
@@ -90,4 +133,3 @@ export interface PendingCommandRequest {
   result?: CommandResult;
   error?: string;
 }
-